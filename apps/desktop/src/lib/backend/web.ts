@@ -187,9 +187,9 @@ async function webInvoke<T>(command: string, params: Record<string, unknown> = {
 	try {
 		const response = await fetch(`${getApiBaseUrl()}/${command}`, {
 			method: "POST",
-			headers: {
+			headers: authHeaders({
 				"Content-Type": "application/json",
-			},
+			}),
 			body: JSON.stringify(params),
 		});
 		const out: ServerResonse<T> = await response.json();
@@ -317,11 +317,37 @@ export function getApiBaseUrl(): string {
 }
 
 /**
- * Returns the WebSocket URL for the event stream.
+ * Returns the token to authenticate with a but-server running in remote mode.
+ *
+ * Resolution order:
+ * 1. `VITE_BUTLER_AUTH_TOKEN` — build-time env var
+ * 2. Cookie (`butlerToken`) — runtime override, so the token isn't baked into the build
+ */
+function getAuthToken(): string | undefined {
+	return import.meta.env.VITE_BUTLER_AUTH_TOKEN || getCookie("butlerToken") || undefined;
+}
+
+function authHeaders(headers: Record<string, string>): Record<string, string> {
+	const token = getAuthToken();
+	return token ? { ...headers, Authorization: `Bearer ${token}` } : headers;
+}
+
+/**
+ * Returns the WebSocket URL for the event stream, with the auth token as query parameter if set,
+ * as browsers can't add headers to WebSocket connections.
+ */
+function getWsUrl(): string {
+	const url = getWsBaseUrl();
+	const token = getAuthToken();
+	return token ? `${url}?token=${encodeURIComponent(token)}` : url;
+}
+
+/**
+ * Returns the WebSocket URL for the event stream without authentication.
  * Derived from `getApiBaseUrl()` — replaces http(s) with ws(s) and
  * appends `/ws` after any base path (e.g. `/api`), keeping the same host.
  */
-function getWsUrl(): string {
+function getWsBaseUrl(): string {
 	const base = getApiBaseUrl();
 	// Empty string or relative URL (e.g. /api) — use window.location.host
 	if (!base || base.startsWith("/")) {
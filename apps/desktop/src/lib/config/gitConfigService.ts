@@ -51,6 +51,7 @@ export class GitConfigService {
 			config: {
 				signCommits: config.signCommits ?? null,
 				gitbutlerGerritMode: config.gitbutlerGerritMode ?? null,
+				gitbutlerVerifySignatures: config.gitbutlerVerifySignatures ?? null,
 				gitbutlerReviewStackingDescription: config.gitbutlerReviewStackingDescription ?? null,
 				gitbutlerGithubStackingMode: config.gitbutlerGithubStackingMode ?? null,
 				gitbutlerForgeReviewTemplatePath: config.gitbutlerForgeReviewTemplatePath ?? null,
@@ -31,6 +31,7 @@ export type Operation =
 	| "RestoreFromSnapshotViaUndo"
 	| "RestoreFromSnapshotViaRedo"
 	| "RestoreFromSnapshot"
+	| "RestorePathsFromSnapshot"
 	| "ReorderCommit"
 	| "InsertBlankCommit"
 	| "MoveCommitFile"
@@ -45,6 +46,7 @@ export type Operation =
 	| "AutoHandleChangesBefore"
 	| "AutoHandleChangesAfter"
 	| "SplitBranch"
+	| "SplitCommit"
 	| "OnDemandSnapshot";
 
 export interface Trailer {
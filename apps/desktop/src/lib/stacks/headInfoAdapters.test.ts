@@ -25,6 +25,7 @@ const localCommit: Commit = {
 	author,
 	changeId: "I111",
 	gerritReviewUrl: null,
+	signature: null,
 };
 
 const upstreamCommit: UpstreamCommit = {
@@ -20,6 +20,7 @@ const MOCK_COMMIT_A: Commit = {
 	author: MOCK_AUTHOR_A,
 	changeId: "Icommit-a-id",
 	gerritReviewUrl: null,
+	signature: null,
 };
 
 const MOCK_UPSTREAM_COMMIT_A: UpstreamCommit = {
@@ -206,6 +206,7 @@ describe("branchIntegrationView", () => {
 										},
 										changeId: "",
 										gerritReviewUrl: null,
+										signature: null,
 									},
 								],
 								commitsOnRemote: [],
@@ -234,6 +235,7 @@ describe("branchIntegrationView", () => {
 										},
 										changeId: "",
 										gerritReviewUrl: null,
+										signature: null,
 									},
 								],
 								commitsOnRemote: [],
@@ -30,6 +30,7 @@ function commit(id: string, hasConflicts = false): Commit {
 		author,
 		changeId: `I${id}`,
 		gerritReviewUrl: null,
+		signature: null,
 	};
 }
 
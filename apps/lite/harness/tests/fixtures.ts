@@ -32,6 +32,7 @@ export const fixtureCommit = ({ id, message }: { id: string; message: string }):
 	author: { name: "Fixture", email: "fixture@example.com", gravatarUrl: "" },
 	changeId: `change-${id}`,
 	gerritReviewUrl: null,
+	signature: null,
 });
 
 export const fixtureSegment = ({
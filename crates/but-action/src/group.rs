@@ -0,0 +1,114 @@
+//! Propose how uncommitted changes should be split into branches and commits.
+
+use anyhow::Context as _;
+use but_llm::ChatMessage;
+use schemars::JsonSchema;
+
+/// An uncommitted hunk that should be grouped into a commit.
+#[derive(Debug, Clone)]
+pub struct HunkToGroup {
+    /// The ID the model refers to the hunk by, typically its CLI ID.
+    pub id: String,
+    /// The worktree-relative path of the file the hunk belongs to.
+    pub path: String,
+    /// The unified diff of the hunk.
+    pub diff: String,
+    /// The names of the branches whose commits this hunk depends on.
+    ///
+    /// Such a hunk can only be committed to one of these branches, or to a branch stacked on top of
+    /// them.
+    pub locked_to: Vec<String>,
+}
+
+/// The grouping the model proposed.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct GroupingProposal {
+    /// The branches to commit to, each with the commits to create on it.
+    pub branches: Vec<ProposedBranch>,
+}
+
+/// A branch, existing or new, with the commits to create on it.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct ProposedBranch {
+    /// The name of an existing branch, or of a new branch to create.
+    pub name: String,
+    /// The commits to create on the branch, from oldest to newest.
+    pub commits: Vec<ProposedCommit>,
+}
+
+/// A commit with its message and the hunks it contains.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct ProposedCommit {
+    /// The commit message.
+    pub message: String,
+    /// The IDs of the hunks to commit.
+    pub hunk_ids: Vec<String>,
+}
+
+const SYSTEM_MESSAGE: &str = "You are a version control assistant that organizes uncommitted changes into focused branches and commits.";
+
+/// Ask `llm` to group `hunks` into branches and commits.
+///
+/// `branches` are the names of the branches that exist in the workspace, which the model may
+/// reuse. `external_prompt` is optional guidance from the user. The proposal is returned as
+/// produced by the model and must be validated by the caller, as it may refer to unknown hunks,
+/// assign hunks more than once or ignore their locks.
+pub fn hunks(
+    llm: &but_llm::LLMProvider,
+    hunks: &[HunkToGroup],
+    branches: &[String],
+    external_prompt: Option<&str>,
+) -> anyhow::Result<GroupingProposal> {
+    let user_message = user_message(hunks, branches, external_prompt);
+    let model = llm.model_or_default();
+    llm.structured_output::<GroupingProposal>(
+        SYSTEM_MESSAGE,
+        vec![ChatMessage::User(user_message)],
+        &model,
+    )?
+    .context("The AI model returned no grouping of the changes")
+}
+
+fn user_message(
+    hunks: &[HunkToGroup],
+    branches: &[String],
+    external_prompt: Option<&str>,
+) -> String {
+    let mut message = String::from(
+        r#"Group the following uncommitted hunks into branches and commits.
+
+- Each branch should contain one logical, independently reviewable change. Each commit on it should be a coherent step of that change.
+- Put every hunk into exactly one commit, and refer to hunks only by their ID.
+- Reuse an existing branch if the hunks continue its work, otherwise invent a short, kebab-case branch name.
+- A hunk that is locked to branches depends on their commits. Put it on one of these branches, or on a new branch, which will be stacked on top of them.
+- Commit messages have a summary line of no more than 50 characters in the imperative mood, optionally followed by an empty line and a short paragraph explaining why the change was made.
+"#,
+    );
+    if let Some(prompt) = external_prompt.filter(|prompt| !prompt.trim().is_empty()) {
+        message.push_str(&format!("\nAdditional instructions: {prompt}\n"));
+    }
+
+    message.push_str("\nExisting branches:\n");
+    if branches.is_empty() {
+        message.push_str("(none)\n");
+    }
+    for branch in branches {
+        message.push_str(&format!("- {branch}\n"));
+    }
+
+    message.push_str("\nHunks:\n");
+    for hunk in hunks {
+        message.push_str(&format!("\nID: {}\nFile: {}\n", hunk.id, hunk.path));
+        if !hunk.locked_to.is_empty() {
+            message.push_str(&format!("Locked to: {}\n", hunk.locked_to.join(", ")));
+        }
+        message.push_str(&format!("```patch\n{}\n```\n", hunk.diff.trim_end()));
+    }
+    message
+}
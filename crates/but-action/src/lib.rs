@@ -16,6 +16,7 @@ mod action;
 pub mod cli;
 pub mod commit_format;
 mod generate;
+pub mod group;
 pub mod reword;
 mod simple;
 pub use action::{ActionListing, Source, list_actions};
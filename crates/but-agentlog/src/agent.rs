@@ -2,6 +2,8 @@
 pub enum Agent {
     Codex,
     Claude,
+    Gemini,
+    Cursor,
 }
 
 impl Agent {
@@ -9,6 +11,8 @@ impl Agent {
         match self {
             Agent::Codex => "codex",
             Agent::Claude => "claude",
+            Agent::Gemini => "gemini",
+            Agent::Cursor => "cursor",
         }
     }
 }
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     fmt,
     io::Read as _,
     path::{Path, PathBuf},
@@ -15,8 +16,9 @@ use crate::{
     gitmeta::{
         PublicationStatus, RelatedSession, RelatedTarget, SessionRecords, SessionTimeline,
         find_related_sessions_limited_by_statuses, find_session_status, get_session_records,
-        get_session_timeline_outline, share_sessions, sync_metadata,
+        get_session_timeline_outline, redact_local_transcript, share_sessions, sync_metadata,
     },
+    redaction::RedactionPolicy,
     skim::{self, SkimReport},
 };
 
@@ -64,7 +66,7 @@ pub enum Command {
         /// Target value for explicit branch, review, or change publishing.
         #[clap(value_name = "VALUE", value_parser = non_empty_value)]
         value: Option<String>,
-        /// Report what would be shared without changing metadata or syncing.
+        /// Report what would be shared and redacted without changing metadata or syncing.
         #[clap(long)]
         dry_run: bool,
     },
@@ -207,6 +209,11 @@ struct ShareSession {
     turn_count: usize,
     related_turn_count: usize,
     latest_captured_at: Option<String>,
+    /// The amount of redacted values in the shared transcript records, by reason.
+    redactions: BTreeMap<String, usize>,
+    /// The part of `redactions` that is only redacted when sharing, by patterns that were
+    /// configured after the records were captured.
+    added_redactions: BTreeMap<String, usize>,
 }
 
 impl ShareReport {
@@ -237,6 +244,8 @@ impl ShareReport {
                     turn_count: session.turn_count,
                     related_turn_count: session.related_turn_keys.len(),
                     latest_captured_at: session.latest_captured_at,
+                    redactions: BTreeMap::new(),
+                    added_redactions: BTreeMap::new(),
                 }
             })
             .collect::<Vec<_>>();
@@ -289,6 +298,21 @@ impl fmt::Display for ShareReport {
                 session.related_turn_count,
                 session.latest_captured_at.as_deref().unwrap_or("unknown")
             )?;
+            if !session.redactions.is_empty() {
+                writeln!(f, "  Redacted: {}", redaction_counts(&session.redactions))?;
+            }
+            if !session.added_redactions.is_empty() {
+                writeln!(
+                    f,
+                    "  {} while sharing: {}",
+                    if self.dry_run {
+                        "Would newly redact"
+                    } else {
+                        "Newly redacted"
+                    },
+                    redaction_counts(&session.added_redactions)
+                )?;
+            }
         }
         if self.dry_run {
             writeln!(
@@ -303,6 +327,14 @@ impl fmt::Display for ShareReport {
     }
 }
 
+fn redaction_counts(counts: &BTreeMap<String, usize>) -> String {
+    counts
+        .iter()
+        .map(|(reason, count)| format!("{reason} x{count}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 pub fn run_from_dir(dir: &Path, command: Command) -> anyhow::Result<impl Serialize + fmt::Display> {
     match command {
         Command::Hook { agent } => {
@@ -401,6 +433,21 @@ pub fn run_from_dir(dir: &Path, command: Command) -> anyhow::Result<impl Seriali
                 .context("failed to find local-only agent sessions")?;
                 let mut report =
                     ShareReport::new(target, value.clone(), target_key.clone(), dry_run, sessions);
+                // Patterns configured since capture also apply to what is shared.
+                let redaction = RedactionPolicy::from_repo(&repo_path)?;
+                for session in &mut report.sessions {
+                    let redactions = redact_local_transcript(
+                        &repo_path,
+                        &session.session_key,
+                        &redaction,
+                        !dry_run,
+                    )
+                    .with_context(|| {
+                        format!("failed to redact agent session '{}'", session.session_key)
+                    })?;
+                    session.redactions = redactions.redacted;
+                    session.added_redactions = redactions.added;
+                }
                 if !dry_run {
                     let session_keys = report
                         .sessions
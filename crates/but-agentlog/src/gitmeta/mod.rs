@@ -197,7 +197,7 @@ pub(crate) use read::{
 };
 pub(crate) use records_outline::{SessionRecords, get_session_records};
 pub(crate) use session_outline::RelatedSession;
-pub(crate) use share::share_sessions;
+pub(crate) use share::{SessionRedactions, redact_local_transcript, share_sessions};
 pub(crate) use timeline_outline::{SessionTimeline, get_session_timeline_outline};
 pub(crate) use write::{CaptureWriteOutcome, sync_metadata, write_transcript_batch};
 
@@ -4,12 +4,24 @@ use std::{
 };
 
 use anyhow::{Context as _, Result, bail};
-use git_meta_lib::{LocalPublish, MetaValue, Session, Target};
+use git_meta_lib::{LocalPublish, MetaEdit, MetaValue, Session, Target};
+use serde_json::Value;
 
 use super::{
     INDEX_NAMESPACE, IndexHit, PublicationStatus, SESSION_SET_KEY, local_storage_key,
-    session_storage_prefix, strip_local_storage_prefix,
+    read_support::read_transcript_entries, session_storage_prefix, strip_local_storage_prefix,
 };
+use crate::redaction::{RedactionPolicy, count_redactions};
+
+/// The redactions in the transcript records of a local-only session, by reason.
+#[derive(Debug, Default)]
+pub(crate) struct SessionRedactions {
+    /// All redactions in the records as they are shared.
+    pub(crate) redacted: BTreeMap<String, usize>,
+    /// The part of `redacted` that is only applied when sharing, as the patterns of the
+    /// current policy didn't apply when the records were captured.
+    pub(crate) added: BTreeMap<String, usize>,
+}
 
 struct SetMemberShare {
     local_key: String,
@@ -61,6 +73,49 @@ pub(crate) fn share_sessions(repo_path: &Path, session_keys: &[String]) -> Resul
     Ok(())
 }
 
+/// Apply the patterns of `policy` to the transcript records of the local-only session
+/// `session_key`, and report all of its redactions. Only if `store` is `true`, the redacted
+/// records replace the stored ones so they can be shared.
+pub(crate) fn redact_local_transcript(
+    repo_path: &Path,
+    session_key: &str,
+    policy: &RedactionPolicy,
+    store: bool,
+) -> Result<SessionRedactions> {
+    let gitmeta = Session::open(repo_path).context("failed to open GitMeta session")?;
+    let target = Target::project();
+    let handle = gitmeta.target(&target);
+    let transcript_key = format!(
+        "{}:transcript",
+        session_storage_prefix(PublicationStatus::LocalOnly, session_key)
+    );
+
+    let mut redactions = SessionRedactions::default();
+    let mut changed = false;
+    let mut entries = read_transcript_entries(&handle, &transcript_key)?;
+    for entry in &mut entries {
+        let mut record: Value = serde_json::from_str(&entry.value).with_context(|| {
+            format!("existing GitMeta key '{transcript_key}' has invalid transcript record")
+        })?;
+        if policy.redact_patterns_in_value(&mut record, &mut redactions.added) {
+            entry.value =
+                serde_json::to_string(&record).context("failed to serialize transcript record")?;
+            changed = true;
+        }
+        count_redactions(&entry.value, &mut redactions.redacted);
+    }
+
+    if store && changed {
+        handle
+            .apply_edits(vec![MetaEdit::set_value(
+                &transcript_key,
+                &MetaValue::List(entries),
+            )])
+            .with_context(|| format!("failed to write GitMeta key '{transcript_key}'"))?;
+    }
+    Ok(redactions)
+}
+
 fn index_set_member_shares(
     handle: &git_meta_lib::SessionTargetHandle<'_>,
     session_keys: &[String],
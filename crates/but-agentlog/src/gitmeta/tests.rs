@@ -9,6 +9,7 @@ use crate::projection::{
     ProjectionRequest, ProjectionSnapshotInput, ProjectionStatus, ProjectionWarningKind,
     project_pr,
 };
+use crate::redaction::RedactionPolicy;
 use crate::transcript::TranscriptBatch;
 use but_core::worktree::safe_checkout_from_head;
 use git_meta_lib::{MetaEdit, MetaValue, Session, Target};
@@ -62,6 +63,16 @@ fn set_agentlog_share_default(repo: &Path, value: bool) {
     assert!(output.status.success(), "git config should succeed");
 }
 
+fn add_git_config(repo: &Path, key: &str, value: &str) {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["config", "--add", key, value])
+        .output()
+        .expect("add git config");
+    assert!(output.status.success(), "git config should succeed");
+}
+
 fn commit_file(repo: &Path, path: &str, body: &str) {
     let repo = but_testsupport::open_repo(repo).expect("open gitoxide test repo");
     let parent = repo.head_commit().ok();
@@ -2388,6 +2399,100 @@ fn transcript_records_redact_secrets_and_copied_scalar_fields() {
     );
 }
 
+#[test]
+fn transcript_records_redact_configured_patterns_and_keep_allowed_values() {
+    let repo = setup_repo();
+    add_git_config(
+        repo.path(),
+        "gitbutler.agentlog-redact-pattern",
+        r"internal-host=\b[a-z0-9-]+\.corp\.example\.com\b",
+    );
+    add_git_config(
+        repo.path(),
+        "gitbutler.agentlog-redact-allow",
+        r"build\.corp\.example\.com",
+    );
+    let transcript = write_transcript(
+        repo.path(),
+        &jsonl([serde_json::json!({
+            "timestamp": "2026-05-07T09:00:00Z",
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": "user",
+                "content": "deploy db1.corp.example.com after build.corp.example.com",
+            },
+        })]),
+    );
+
+    assert_eq!(capture_project(repo.path(), Agent::Codex, &transcript), 1);
+
+    let session_key = only_session_key(repo.path());
+    let entries = transcript_entries(repo.path(), &session_key);
+    let record: serde_json::Value =
+        serde_json::from_str(&entries[0]).expect("transcript record json");
+    assert_eq!(
+        record["text"],
+        "deploy [REDACTED:internal-host] after build.corp.example.com"
+    );
+}
+
+#[test]
+fn sharing_redacts_patterns_configured_after_capture() {
+    let repo = setup_local_repo();
+    let transcript = write_transcript(
+        repo.path(),
+        &jsonl([serde_json::json!({
+            "timestamp": "2026-05-07T09:00:00Z",
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": "user",
+                "content": "deploy db1.corp.example.com",
+            },
+        })]),
+    );
+    assert_eq!(capture_project(repo.path(), Agent::Codex, &transcript), 1);
+    add_git_config(
+        repo.path(),
+        "gitbutler.agentlog-redact-pattern",
+        r"internal-host=\b[a-z0-9-]+\.corp\.example\.com\b",
+    );
+    let Some(MetaValue::Set(session_keys)) =
+        project_value(repo.path(), "local:gitbutler:agent-sessions")
+    else {
+        panic!("expected local session index set");
+    };
+    let session_key = session_keys.into_iter().next().expect("session key");
+    let local_transcript = || {
+        let Some(MetaValue::List(entries)) = project_value(
+            repo.path(),
+            &format!("local:gitbutler:agent-session:{session_key}:transcript"),
+        ) else {
+            panic!("expected local transcript list");
+        };
+        entries[0].value.clone()
+    };
+    let policy = RedactionPolicy::from_repo(repo.path()).expect("redaction policy");
+
+    let preview =
+        redact_local_transcript(repo.path(), &session_key, &policy, false).expect("preview");
+    assert_eq!(preview.added.get("internal-host"), Some(&1));
+    assert_eq!(preview.redacted.get("internal-host"), Some(&1));
+    assert!(
+        local_transcript().contains("db1.corp.example.com"),
+        "previews leave stored records alone"
+    );
+
+    redact_local_transcript(repo.path(), &session_key, &policy, true).expect("redact");
+    share_sessions(repo.path(), std::slice::from_ref(&session_key)).expect("share");
+
+    let entries = transcript_entries(repo.path(), &session_key);
+    let record: serde_json::Value =
+        serde_json::from_str(&entries[0]).expect("transcript record json");
+    assert_eq!(record["text"], "deploy [REDACTED:internal-host]");
+}
+
 #[test]
 fn transcript_records_store_prompt_source_tool_kind_and_outcome() {
     let repo = setup_repo();
@@ -15,7 +15,7 @@ use crate::{
         EnvironmentObservation, ObservedTargets, SnapshotStatus, is_public_repo_path,
         path_fingerprint,
     },
-    redaction::{redact_text, redact_value},
+    redaction::RedactionPolicy,
     transcript::{PromptSource, RecordKind, ToolKind, ToolOutcome, TranscriptBatch},
 };
 
@@ -58,6 +58,7 @@ pub(crate) fn write_transcript_batch(
         });
     }
 
+    let redaction = RedactionPolicy::from_repo(repo_path)?;
     let gitmeta = Session::open(repo_path).context("failed to open GitMeta session")?;
     let target = Target::project();
     let handle = gitmeta.target(&target);
@@ -144,23 +145,32 @@ pub(crate) fn write_transcript_batch(
             source_key,
             record_index: record.index,
             record_hash: &record_hash,
-            timestamp: record.source_timestamp.as_deref().map(redact_text),
+            timestamp: record
+                .source_timestamp
+                .as_deref()
+                .map(|timestamp| redaction.redact_text(timestamp)),
             kind: record.kind,
-            source_event_kind: redact_text(&record.source_event_kind),
-            role: record.role.as_deref().map(redact_text),
-            text: stored_text(record.kind, record.text.as_deref()),
+            source_event_kind: redaction.redact_text(&record.source_event_kind),
+            role: record
+                .role
+                .as_deref()
+                .map(|role| redaction.redact_text(role)),
+            text: stored_text(&redaction, record.kind, record.text.as_deref()),
             prompt_source: record.prompt_source,
-            tool_name: record.tool_name.as_deref().map(redact_text),
+            tool_name: record
+                .tool_name
+                .as_deref()
+                .map(|tool_name| redaction.redact_text(tool_name)),
             tool_kind: record.tool_kind,
             file_path_hashes: file_path_hashes_for_record(
                 &repo_root,
                 record.tool_kind,
                 record.tool_input.as_ref(),
             ),
-            tool_input: record.tool_input.map(redact_value),
+            tool_input: record.tool_input.map(|input| redaction.redact_value(input)),
             exit_code: record.exit_code,
             outcome: record.tool_outcome,
-            source_record: redact_value(record.source_record),
+            source_record: redaction.redact_value(record.source_record),
         };
         let transcript_record = serde_json::to_string(&transcript_record)
             .context("failed to serialize transcript record")?;
@@ -210,8 +220,14 @@ pub(crate) fn write_transcript_batch(
     let updated_at_key = format!("{session_prefix}:updated-at");
     let session_schema_value = MetaValue::String("gitbutler.agent-session.v1".to_owned());
     let updated_at_value = MetaValue::String(updated_at.clone());
-    let source_fields =
-        source_metadata_fields(&source_prefix, agent, provider, model, tool_version);
+    let source_fields = source_metadata_fields(
+        &redaction,
+        &source_prefix,
+        agent,
+        provider,
+        model,
+        tool_version,
+    );
     let previous_turn_keys = previous_turns
         .into_iter()
         .map(|turn| turn.summary.turn_key)
@@ -306,11 +322,15 @@ pub(crate) fn sync_metadata(repo_path: &Path) -> Result<()> {
     }
 }
 
-fn stored_text(kind: RecordKind, text: Option<&str>) -> Option<String> {
+fn stored_text(
+    redaction: &RedactionPolicy,
+    kind: RecordKind,
+    text: Option<&str>,
+) -> Option<String> {
     let text = text?;
     Some(match kind {
-        RecordKind::ToolResult => redact_text(cap_tool_result_text(text).as_ref()),
-        _ => redact_text(text),
+        RecordKind::ToolResult => redaction.redact_text(cap_tool_result_text(text).as_ref()),
+        _ => redaction.redact_text(text),
     })
 }
 
@@ -410,6 +430,7 @@ fn path_to_repo_string(path: &Path) -> Option<String> {
 }
 
 fn source_metadata_fields(
+    redaction: &RedactionPolicy,
     prefix: &str,
     agent: Agent,
     provider: Option<String>,
@@ -423,19 +444,19 @@ fn source_metadata_fields(
     if let Some(provider) = provider {
         fields.push((
             format!("{prefix}:provider"),
-            MetaValue::String(redact_text(&provider)),
+            MetaValue::String(redaction.redact_text(&provider)),
         ));
     }
     if let Some(model) = model {
         fields.push((
             format!("{prefix}:model"),
-            MetaValue::String(redact_text(&model)),
+            MetaValue::String(redaction.redact_text(&model)),
         ));
     }
     if let Some(tool_version) = tool_version {
         fields.push((
             format!("{prefix}:tool-version"),
-            MetaValue::String(redact_text(&tool_version)),
+            MetaValue::String(redaction.redact_text(&tool_version)),
         ));
     }
     fields
@@ -22,7 +22,7 @@ use crate::gitmeta::{
     PublicationStatus, RelatedSession, RelatedTarget, SessionRecords,
     find_related_sessions_limited, get_session_records, get_session_timeline_outline,
 };
-use crate::redaction::redact_text;
+use crate::redaction::RedactionPolicy;
 
 const DEFAULT_MAX_TURNS: usize = 32;
 const DEFAULT_MAX_RECORDS_PER_TURN: usize = 512;
@@ -33,6 +33,7 @@ const DEFAULT_MAX_TEXT_CHARS: usize = 1_200;
 /// GitMeta storage keys, raw provider session ids, raw `source_record` JSON, and
 /// raw tool payloads are intentionally not present.
 pub fn project_pr(repo_path: &Path, request: &ProjectionRequest) -> Result<Projection> {
+    let redaction = RedactionPolicy::from_repo(repo_path)?;
     let review_target = request.review_target_key();
     let branch_target = request.branch_target_key();
     let review_sessions = find_related_sessions_limited(
@@ -153,6 +154,7 @@ pub fn project_pr(repo_path: &Path, request: &ProjectionRequest) -> Result<Proje
         let turn_handle = turn_handle(request, &selected.session_key, &selected.turn_key);
         let public_records = project_records(
             request,
+            &redaction,
             &selected.session_key,
             &selected.turn_key,
             records,
@@ -555,6 +557,7 @@ fn evidence_rank(tier: ProjectionEvidenceTier) -> usize {
 
 fn project_records(
     request: &ProjectionRequest,
+    redaction: &RedactionPolicy,
     session_key: &str,
     turn_key: &str,
     records: SessionRecords,
@@ -568,7 +571,8 @@ fn project_records(
                 && !matches!(record.role.as_deref(), Some("system" | "developer"))
         })
         .map(|record| {
-            let (text, text_truncated) = public_text(&record.kind, record.text.as_deref(), request);
+            let (text, text_truncated) =
+                public_text(&record.kind, record.text.as_deref(), request, redaction);
             *saw_truncated_text |= text_truncated;
             ProjectionRecord {
                 handle: record_handle(request, session_key, turn_key, &record.record_hash),
@@ -608,6 +612,7 @@ fn public_text(
     kind: &Option<String>,
     text: Option<&str>,
     request: &ProjectionRequest,
+    redaction: &RedactionPolicy,
 ) -> (Option<String>, bool) {
     let Some(text) = text else {
         return (None, false);
@@ -626,7 +631,7 @@ fn public_text(
     if trimmed.is_empty() {
         return (None, false);
     }
-    let redacted = redact_text(trimmed);
+    let redacted = redaction.redact_text(trimmed);
     bounded_text(redacted.trim(), request.limits.max_text_chars)
 }
 
@@ -862,6 +867,7 @@ mod tests {
                 "Chunk ID: abc\nUpdated /Users/alice/src/project/src/lib.rs and /home/alice/.ssh/id_ed25519\nProcess exited with code 0",
             ),
             &request,
+            &RedactionPolicy::default(),
         );
 
         assert!(!truncated, "redacted text should fit within the test limit");
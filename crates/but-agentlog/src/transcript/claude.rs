@@ -0,0 +1,196 @@
+//! Reading Claude Code session logs, one JSON record per line.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use serde_json::Value;
+
+use super::{
+    ParsedRecord, RecordKind, TranscriptBatch, TranscriptReader, classify_outcome, classify_tool,
+    for_each_json_line, joined_block_text, parse_exit_code, prompt_source, remove_field_at,
+    sha256_prefixed, str_at,
+};
+
+pub(super) struct ClaudeReader;
+
+impl TranscriptReader for ClaudeReader {
+    fn default_provider(&self) -> Option<&'static str> {
+        Some("anthropic")
+    }
+
+    fn read(&self, snapshot: &[u8], transcript: &mut TranscriptBatch) -> Result<()> {
+        let mut tool_names = HashMap::new();
+        for_each_json_line(snapshot, |index, trimmed, parsed| {
+            transcript.apply_claude_metadata(&parsed);
+            if let Some(record) =
+                ParsedRecord::from_claude_source(index, trimmed, parsed, &mut tool_names)
+            {
+                transcript.records.push(record);
+            }
+        })
+    }
+}
+
+impl TranscriptBatch {
+    fn apply_claude_metadata(&mut self, source_record: &Value) {
+        if self.session_id.is_none() {
+            self.session_id = str_at(source_record, &["sessionId"]).map(ToOwned::to_owned);
+        }
+        if self.tool_version.is_none() {
+            self.tool_version = str_at(source_record, &["version"]).map(ToOwned::to_owned);
+        }
+        if self.model.is_none() {
+            self.model = str_at(source_record, &["message", "model"])
+                .or_else(|| str_at(source_record, &["model"]))
+                .map(ToOwned::to_owned);
+        }
+    }
+}
+
+impl ParsedRecord {
+    fn from_claude_source(
+        index: usize,
+        trimmed: &[u8],
+        mut source_record: Value,
+        tool_names: &mut HashMap<String, String>,
+    ) -> Option<Self> {
+        let content_block = claude_content_block(&source_record);
+        let content_type = content_block.and_then(|block| str_at(block, &["type"]));
+        let source_event_kind = claude_event_kind(&source_record, content_type);
+        let mut role = str_at(&source_record, &["message", "role"])
+            .or_else(|| {
+                let record_type = str_at(&source_record, &["type"])?;
+                matches!(record_type, "user" | "assistant").then_some(record_type)
+            })
+            .map(ToOwned::to_owned);
+        let mut text = claude_content_text(&source_record);
+        let kind = claude_kind(content_type, text.is_some())?;
+        let mut tool_name = None;
+        let mut tool_input = None;
+
+        match (kind, content_block) {
+            (RecordKind::ToolCall, Some(block)) => {
+                tool_name = str_at(block, &["name"]).map(ToOwned::to_owned);
+                tool_input = block.get("input").cloned();
+                if let (Some(id), Some(name)) = (str_at(block, &["id"]), tool_name.as_deref()) {
+                    tool_names.insert(id.to_owned(), name.to_owned());
+                }
+                text = None;
+            }
+            (RecordKind::ToolResult, Some(block)) => {
+                role = None;
+                text = claude_block_text(block);
+                tool_name = str_at(block, &["tool_use_id"])
+                    .and_then(|id| tool_names.get(id))
+                    .cloned();
+            }
+            _ => {}
+        }
+        prune_claude(&mut source_record, kind, tool_input.is_some());
+        let mut spawn_prompts = HashSet::new();
+        let prompt_source =
+            prompt_source(role.as_deref(), text.as_deref(), false, &mut spawn_prompts);
+        let tool_kind = tool_name.as_deref().map(classify_tool);
+        let exit_code = (kind == RecordKind::ToolResult)
+            .then(|| text.as_deref().and_then(parse_exit_code))
+            .flatten();
+        let tool_outcome = exit_code.map(|code| classify_outcome(code, text.as_deref()));
+
+        Some(ParsedRecord {
+            index,
+            source_record_hash: sha256_prefixed(trimmed),
+            source_timestamp: str_at(&source_record, &["timestamp"]).map(ToOwned::to_owned),
+            source_event_kind,
+            kind,
+            role,
+            text,
+            prompt_source,
+            tool_name,
+            tool_kind,
+            tool_input,
+            exit_code,
+            tool_outcome,
+            source_record,
+        })
+    }
+}
+
+fn claude_event_kind(source_record: &Value, content_type: Option<&str>) -> String {
+    let top_level_type = str_at(source_record, &["type"]).unwrap_or("unknown");
+    match content_type.or_else(|| str_at(source_record, &["message", "type"])) {
+        Some(nested_type) => format!("claude:{top_level_type}:{nested_type}"),
+        None if source_record.get("message").is_some() => {
+            format!("claude:{top_level_type}:message")
+        }
+        None => format!("claude:{top_level_type}"),
+    }
+}
+
+fn claude_kind(content_type: Option<&str>, has_text: bool) -> Option<RecordKind> {
+    match content_type {
+        Some("text") => Some(RecordKind::Message),
+        Some("tool_use") => Some(RecordKind::ToolCall),
+        Some("tool_result") => Some(RecordKind::ToolResult),
+        _ if has_text => Some(RecordKind::Message),
+        _ => None,
+    }
+}
+
+fn claude_content_block(source_record: &Value) -> Option<&Value> {
+    source_record
+        .get("message")?
+        .get("content")?
+        .as_array()?
+        .iter()
+        .find(|block| str_at(block, &["type"]).is_some())
+}
+
+fn claude_content_text(source_record: &Value) -> Option<String> {
+    if let Some(content) = str_at(source_record, &["message", "content"]) {
+        return Some(content.to_owned());
+    }
+
+    joined_block_text(source_record.get("message")?.get("content")?.as_array()?)
+}
+
+fn claude_block_text(block: &Value) -> Option<String> {
+    match block.get("content") {
+        Some(Value::String(text)) => Some(text.clone()),
+        Some(Value::Array(blocks)) => joined_block_text(blocks),
+        _ => str_at(block, &["text"]).map(ToOwned::to_owned),
+    }
+}
+
+fn prune_claude(source_record: &mut Value, kind: RecordKind, has_tool_input: bool) {
+    if kind == RecordKind::Message {
+        remove_field_at(source_record, &["message", "content"]);
+        return;
+    }
+
+    let Some(blocks) = source_record
+        .get_mut("message")
+        .and_then(|message| message.get_mut("content"))
+        .and_then(Value::as_array_mut)
+    else {
+        return;
+    };
+    let Some(block) = blocks
+        .iter_mut()
+        .find(|block| str_at(block, &["type"]).is_some())
+    else {
+        return;
+    };
+    match kind {
+        RecordKind::ToolCall if has_tool_input => {
+            if let Some(object) = block.as_object_mut() {
+                object.remove("input");
+            }
+        }
+        RecordKind::ToolResult => {
+            if let Some(object) = block.as_object_mut() {
+                object.remove("content");
+            }
+        }
+        RecordKind::Message | RecordKind::ToolCall => {}
+    }
+}
@@ -0,0 +1,259 @@
+//! Reading Codex session logs, one JSON record per line.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use serde_json::Value;
+
+use super::{
+    ParsedRecord, RecordKind, TranscriptBatch, TranscriptReader, classify_outcome, classify_tool,
+    first_text_at, for_each_json_line, json_value, normalized_prompt, parse_exit_code,
+    prompt_source, remove_field_at, sha256_prefixed, str_at, value_at,
+};
+
+pub(super) struct CodexReader;
+
+impl TranscriptReader for CodexReader {
+    fn read(&self, snapshot: &[u8], transcript: &mut TranscriptBatch) -> Result<()> {
+        let mut tool_names = HashMap::new();
+        let mut spawn_prompts = HashSet::new();
+        for_each_json_line(snapshot, |index, trimmed, parsed| {
+            transcript.apply_codex_metadata(&parsed);
+            if let Some(record) = ParsedRecord::from_codex_source(
+                index,
+                trimmed,
+                parsed,
+                &mut tool_names,
+                &mut spawn_prompts,
+                transcript.thread_source.as_deref(),
+            ) {
+                transcript.records.push(record);
+            }
+        })
+    }
+}
+
+impl TranscriptBatch {
+    fn apply_codex_metadata(&mut self, source_record: &Value) {
+        if str_at(source_record, &["type"]) == Some("session_meta") {
+            if self.session_id.is_none() {
+                self.session_id = str_at(source_record, &["payload", "id"])
+                    .or_else(|| str_at(source_record, &["payload", "session_id"]))
+                    .map(ToOwned::to_owned);
+            }
+            if self.provider.is_none() {
+                self.provider =
+                    str_at(source_record, &["payload", "model_provider"]).map(ToOwned::to_owned);
+            }
+            if self.tool_version.is_none() {
+                self.tool_version =
+                    str_at(source_record, &["payload", "cli_version"]).map(ToOwned::to_owned);
+            }
+            if self.thread_source.is_none() {
+                self.thread_source = str_at(source_record, &["payload", "thread_source"])
+                    .map(ToOwned::to_owned)
+                    .or_else(|| {
+                        value_at(source_record, &["payload", "source", "subagent"])
+                            .is_some()
+                            .then(|| "subagent".to_owned())
+                    });
+            }
+        }
+
+        if self.model.is_none() {
+            self.model = str_at(source_record, &["payload", "model"])
+                .or_else(|| str_at(source_record, &["payload", "model_slug"]))
+                .map(ToOwned::to_owned);
+        }
+    }
+}
+
+impl ParsedRecord {
+    fn from_codex_source(
+        index: usize,
+        trimmed: &[u8],
+        mut source_record: Value,
+        tool_names: &mut HashMap<String, String>,
+        spawn_prompts: &mut HashSet<String>,
+        thread_source: Option<&str>,
+    ) -> Option<Self> {
+        let raw_event_kind = codex_event_kind(&source_record);
+        let kind = codex_kind(&raw_event_kind)?;
+        let role = str_at(&source_record, &["payload", "role"])
+            .or_else(|| str_at(&source_record, &["payload", "item", "role"]))
+            .map(ToOwned::to_owned);
+        if kind == RecordKind::Message && matches!(role.as_deref(), Some("developer" | "system")) {
+            return None;
+        }
+        let text = codex_text(&source_record, kind);
+        let mut tool_name = [
+            &["payload", "tool_name"][..],
+            &["payload", "tool"],
+            &["payload", "name"],
+            &["payload", "item", "name"],
+        ]
+        .iter()
+        .find_map(|path| str_at(&source_record, path).map(ToOwned::to_owned));
+        let tool_input = codex_tool_input(&source_record, kind);
+        if kind == RecordKind::ToolResult && tool_name.is_none() {
+            tool_name = codex_call_id(&source_record)
+                .and_then(|call_id| tool_names.get(call_id))
+                .cloned();
+        }
+        if kind == RecordKind::ToolCall
+            && let (Some(call_id), Some(name)) = (codex_call_id(&source_record), tool_name.as_ref())
+        {
+            tool_names.insert(call_id.to_owned(), name.clone());
+        }
+        if matches!(tool_name.as_deref(), Some("spawn_agent"))
+            && let Some(message) = tool_input.as_ref().and_then(spawn_prompt)
+        {
+            spawn_prompts.insert(normalized_prompt(message).to_owned());
+        }
+        let prompt_source = prompt_source(
+            role.as_deref(),
+            text.as_deref(),
+            thread_source == Some("subagent"),
+            spawn_prompts,
+        );
+        let tool_kind = tool_name.as_deref().map(classify_tool);
+        let exit_code = (kind == RecordKind::ToolResult)
+            .then(|| text.as_deref().and_then(parse_exit_code))
+            .flatten();
+        let tool_outcome = exit_code.map(|code| classify_outcome(code, text.as_deref()));
+        prune_codex(&mut source_record, kind, tool_input.is_some());
+
+        Some(ParsedRecord {
+            index,
+            source_record_hash: sha256_prefixed(trimmed),
+            source_timestamp: str_at(&source_record, &["timestamp"]).map(ToOwned::to_owned),
+            source_event_kind: format!("codex:{raw_event_kind}"),
+            kind,
+            role,
+            text,
+            prompt_source,
+            tool_name,
+            tool_kind,
+            tool_input,
+            exit_code,
+            tool_outcome,
+            source_record,
+        })
+    }
+}
+
+fn codex_kind(source_event_kind: &str) -> Option<RecordKind> {
+    match source_event_kind {
+        "response_item:message" => Some(RecordKind::Message),
+        "response_item:function_call"
+        | "response_item:custom_tool_call"
+        | "response_item:web_search_call" => Some(RecordKind::ToolCall),
+        "response_item:function_call_output" | "response_item:custom_tool_call_output" => {
+            Some(RecordKind::ToolResult)
+        }
+        _ => None,
+    }
+}
+
+fn codex_text(source_record: &Value, kind: RecordKind) -> Option<String> {
+    match kind {
+        RecordKind::Message => first_text_at(
+            source_record,
+            &[
+                &["payload", "content"],
+                &["payload", "text"],
+                &["payload", "message"],
+                &["payload", "item", "content"],
+                &["payload", "item", "text"],
+            ],
+        ),
+        RecordKind::ToolResult => first_text_at(
+            source_record,
+            &[
+                &["payload", "output"],
+                &["payload", "content"],
+                &["payload", "item", "output"],
+                &["payload", "item", "content"],
+            ],
+        ),
+        RecordKind::ToolCall => None,
+    }
+}
+
+fn codex_tool_input(source_record: &Value, kind: RecordKind) -> Option<Value> {
+    if kind != RecordKind::ToolCall {
+        return None;
+    }
+
+    [
+        &["payload", "arguments"][..],
+        &["payload", "input"],
+        &["payload", "item", "arguments"],
+        &["payload", "item", "input"],
+    ]
+    .iter()
+    .find_map(|path| value_at(source_record, path))
+    .map(json_value)
+}
+
+fn codex_call_id(source_record: &Value) -> Option<&str> {
+    [
+        &["payload", "call_id"][..],
+        &["payload", "item", "call_id"],
+        &["payload", "id"],
+        &["payload", "item", "id"],
+    ]
+    .iter()
+    .find_map(|path| str_at(source_record, path))
+}
+
+fn spawn_prompt(input: &Value) -> Option<&str> {
+    ["message", "prompt", "task"]
+        .into_iter()
+        .find_map(|key| input.get(key).and_then(Value::as_str))
+}
+
+fn prune_codex(source_record: &mut Value, kind: RecordKind, has_tool_input: bool) {
+    match kind {
+        RecordKind::Message => {
+            for path in [
+                &["payload", "content"][..],
+                &["payload", "text"],
+                &["payload", "message"],
+                &["payload", "item", "content"],
+                &["payload", "item", "text"],
+            ] {
+                remove_field_at(source_record, path);
+            }
+        }
+        RecordKind::ToolCall if has_tool_input => {
+            for path in [
+                &["payload", "arguments"][..],
+                &["payload", "input"],
+                &["payload", "item", "arguments"],
+                &["payload", "item", "input"],
+            ] {
+                remove_field_at(source_record, path);
+            }
+        }
+        RecordKind::ToolResult => {
+            for path in [
+                &["payload", "output"][..],
+                &["payload", "content"],
+                &["payload", "item", "output"],
+                &["payload", "item", "content"],
+            ] {
+                remove_field_at(source_record, path);
+            }
+        }
+        RecordKind::ToolCall => {}
+    }
+}
+
+fn codex_event_kind(source_record: &Value) -> String {
+    let top_level_type = str_at(source_record, &["type"]).unwrap_or("unknown");
+    match str_at(source_record, &["payload", "type"]) {
+        Some(nested_type) => format!("{top_level_type}:{nested_type}"),
+        None => top_level_type.to_owned(),
+    }
+}
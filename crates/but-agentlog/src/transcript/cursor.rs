@@ -0,0 +1,149 @@
+//! Reading Cursor agent sessions in its `stream-json` format, one JSON event per line.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use super::{
+    ParsedRecord, RecordKind, ToolOutcome, TranscriptBatch, TranscriptReader, classify_outcome,
+    classify_tool, for_each_json_line, prompt_source, remove_field_at, sha256_prefixed, str_at,
+    text_at, value_at,
+};
+
+pub(super) struct CursorReader;
+
+impl TranscriptReader for CursorReader {
+    fn read(&self, snapshot: &[u8], transcript: &mut TranscriptBatch) -> Result<()> {
+        for_each_json_line(snapshot, |index, trimmed, parsed| {
+            transcript.apply_cursor_metadata(&parsed);
+            if let Some(record) = ParsedRecord::from_cursor_source(index, trimmed, parsed) {
+                transcript.records.push(record);
+            }
+        })
+    }
+}
+
+impl TranscriptBatch {
+    fn apply_cursor_metadata(&mut self, source_record: &Value) {
+        if self.session_id.is_none() {
+            self.session_id = str_at(source_record, &["session_id"]).map(ToOwned::to_owned);
+        }
+        if self.model.is_none() && str_at(source_record, &["type"]) == Some("system") {
+            self.model = str_at(source_record, &["model"]).map(ToOwned::to_owned);
+        }
+    }
+}
+
+impl ParsedRecord {
+    fn from_cursor_source(index: usize, trimmed: &[u8], mut source_record: Value) -> Option<Self> {
+        let record_type = str_at(&source_record, &["type"])?.to_owned();
+        let subtype = str_at(&source_record, &["subtype"]).map(ToOwned::to_owned);
+        let source_event_kind = match &subtype {
+            Some(subtype) => format!("cursor:{record_type}:{subtype}"),
+            None => format!("cursor:{record_type}"),
+        };
+        let mut role = None;
+        let mut text = None;
+        let mut tool_name = None;
+        let mut tool_input = None;
+        let mut exit_code = None;
+        let mut tool_outcome = None;
+
+        let kind = match (record_type.as_str(), subtype.as_deref()) {
+            ("user" | "assistant", _) => {
+                text = Some(text_at(&source_record, &["message", "content"])?);
+                role = Some(record_type.clone());
+                remove_field_at(&mut source_record, &["message", "content"]);
+                RecordKind::Message
+            }
+            ("tool_call", Some("started")) => {
+                let (name, call) = cursor_tool_call(&source_record)?;
+                tool_name = Some(name);
+                tool_input = call.get("args").cloned();
+                let key = call_key(&source_record)?;
+                remove_field_at(&mut source_record, &["tool_call", key.as_str(), "args"]);
+                RecordKind::ToolCall
+            }
+            ("tool_call", Some("completed")) => {
+                let (name, call) = cursor_tool_call(&source_record)?;
+                tool_name = Some(name);
+                let (outcome, result) = match call.get("result") {
+                    Some(result) => match (result.get("success"), result.get("failure")) {
+                        (Some(success), _) => (ToolOutcome::Succeeded, Some(success)),
+                        (None, Some(failure)) => (ToolOutcome::Failed, Some(failure)),
+                        (None, None) => (ToolOutcome::Failed, result.get("error")),
+                    },
+                    None => (ToolOutcome::Succeeded, None),
+                };
+                text = result.and_then(cursor_result_text);
+                exit_code = result
+                    .and_then(|result| result.get("exitCode"))
+                    .and_then(Value::as_i64)
+                    .and_then(|code| i32::try_from(code).ok());
+                tool_outcome =
+                    Some(exit_code.map_or(outcome, |code| classify_outcome(code, text.as_deref())));
+                let key = call_key(&source_record)?;
+                for field in ["args", "result"] {
+                    remove_field_at(&mut source_record, &["tool_call", key.as_str(), field]);
+                }
+                RecordKind::ToolResult
+            }
+            // `system` events carry session metadata, `result` events repeat the final message.
+            _ => return None,
+        };
+        let prompt_source =
+            prompt_source(role.as_deref(), text.as_deref(), false, &mut HashSet::new());
+        let tool_kind = tool_name.as_deref().map(classify_tool);
+
+        Some(ParsedRecord {
+            index,
+            source_record_hash: sha256_prefixed(trimmed),
+            source_timestamp: str_at(&source_record, &["timestamp"]).map(ToOwned::to_owned),
+            kind,
+            source_event_kind,
+            role,
+            text,
+            prompt_source,
+            tool_name,
+            tool_kind,
+            tool_input,
+            exit_code,
+            tool_outcome,
+            source_record,
+        })
+    }
+}
+
+/// The key of the single entry of `tool_call`, like `shellToolCall`.
+fn call_key(source_record: &Value) -> Option<String> {
+    source_record
+        .get("tool_call")?
+        .as_object()?
+        .keys()
+        .next()
+        .cloned()
+}
+
+/// The tool name and the call of a `tool_call` event. Cursor names the single entry of
+/// `tool_call` after the tool, like `shellToolCall` for the `shell` tool.
+fn cursor_tool_call(source_record: &Value) -> Option<(String, &Value)> {
+    let key = call_key(source_record)?;
+    let call = value_at(source_record, &["tool_call", key.as_str()])?;
+    let name = match key.strip_suffix("ToolCall") {
+        Some(name) if !name.is_empty() => name.to_owned(),
+        _ => str_at(call, &["name"]).unwrap_or(&key).to_owned(),
+    };
+    Some((name, call))
+}
+
+/// The text of a tool `result`: the output of commands, or the content of other tools.
+fn cursor_result_text(result: &Value) -> Option<String> {
+    let text = ["stdout", "stderr", "content", "output", "message"]
+        .into_iter()
+        .filter_map(|field| str_at(result, &[field]))
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    (!text.is_empty()).then_some(text)
+}
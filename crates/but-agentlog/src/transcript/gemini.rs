@@ -0,0 +1,207 @@
+//! Reading Gemini CLI sessions, which are stored as a single JSON object that is rewritten
+//! whenever the conversation changes.
+
+use std::collections::HashSet;
+
+use anyhow::{Context as _, Result};
+use serde_json::Value;
+
+use super::{
+    ParsedRecord, RecordKind, ToolOutcome, TranscriptBatch, TranscriptReader, classify_outcome,
+    classify_tool, parse_exit_code, prompt_source, sha256_prefixed, str_at, text_at, value_at,
+};
+
+pub(super) struct GeminiReader;
+
+impl TranscriptReader for GeminiReader {
+    fn default_provider(&self) -> Option<&'static str> {
+        Some("google")
+    }
+
+    fn read(&self, snapshot: &[u8], transcript: &mut TranscriptBatch) -> Result<()> {
+        let session = serde_json::from_slice::<Value>(snapshot)
+            .context("transcript is not a Gemini CLI session")?;
+        transcript.session_id = str_at(&session, &["sessionId"]).map(ToOwned::to_owned);
+        let messages = value_at(&session, &["messages"])
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        // The session has no stable line structure, so records are numbered in the order they
+        // are derived, which only ever grows at the end as the session file is rewritten.
+        let mut index = 0;
+        for message in messages {
+            if transcript.model.is_none() {
+                transcript.model = str_at(message, &["model"]).map(ToOwned::to_owned);
+            }
+            let timestamp = str_at(message, &["timestamp"]);
+            if let Some(record) = ParsedRecord::from_gemini_message(index, message) {
+                transcript.records.push(record);
+            }
+            index += 1;
+            for call in value_at(message, &["toolCalls"])
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                transcript
+                    .records
+                    .push(ParsedRecord::from_gemini_tool_call(index, call, timestamp));
+                index += 1;
+                if let Some(record) = ParsedRecord::from_gemini_tool_result(index, call, timestamp)
+                {
+                    transcript.records.push(record);
+                }
+                index += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ParsedRecord {
+    fn from_gemini_message(index: usize, message: &Value) -> Option<Self> {
+        let message_type = str_at(message, &["type"])?;
+        let role = match message_type {
+            "user" => "user",
+            "gemini" => "assistant",
+            // `info`, `error` and `warning` messages are notices of the CLI itself.
+            _ => return None,
+        };
+        let text = text_at(message, &["content"]).filter(|text| !text.trim().is_empty())?;
+        let mut source_record = message.clone();
+        if let Some(object) = source_record.as_object_mut() {
+            for field in ["content", "toolCalls", "thoughts"] {
+                object.remove(field);
+            }
+        }
+        let prompt_source = prompt_source(Some(role), Some(&text), false, &mut HashSet::new());
+
+        Some(ParsedRecord {
+            index,
+            source_record_hash: gemini_hash("message", message)
+                .unwrap_or_else(|| sha256_prefixed(text.as_bytes())),
+            source_timestamp: str_at(message, &["timestamp"]).map(ToOwned::to_owned),
+            kind: RecordKind::Message,
+            source_event_kind: format!("gemini:{message_type}"),
+            role: Some(role.to_owned()),
+            text: Some(text),
+            prompt_source,
+            tool_name: None,
+            tool_kind: None,
+            tool_input: None,
+            exit_code: None,
+            tool_outcome: None,
+            source_record,
+        })
+    }
+
+    fn from_gemini_tool_call(index: usize, call: &Value, timestamp: Option<&str>) -> Self {
+        let tool_name = str_at(call, &["name"]).map(ToOwned::to_owned);
+        let tool_input = call.get("args").cloned();
+        let source_record = gemini_call_record(call);
+
+        ParsedRecord {
+            index,
+            source_record_hash: gemini_hash("call", call)
+                .unwrap_or_else(|| sha256_prefixed(source_record.to_string().as_bytes())),
+            source_timestamp: str_at(call, &["timestamp"])
+                .or(timestamp)
+                .map(ToOwned::to_owned),
+            kind: RecordKind::ToolCall,
+            source_event_kind: "gemini:tool_call".to_owned(),
+            role: None,
+            text: None,
+            prompt_source: None,
+            tool_kind: tool_name.as_deref().map(classify_tool),
+            tool_name,
+            tool_input,
+            exit_code: None,
+            tool_outcome: None,
+            source_record,
+        }
+    }
+
+    /// The result of `call`, or `None` while it is still running.
+    fn from_gemini_tool_result(
+        index: usize,
+        call: &Value,
+        timestamp: Option<&str>,
+    ) -> Option<Self> {
+        let status = str_at(call, &["status"])?;
+        let status_outcome = match status {
+            "success" => ToolOutcome::Succeeded,
+            "error" => ToolOutcome::Failed,
+            "cancelled" => ToolOutcome::CouldNotExecute,
+            _ => return None,
+        };
+        let text = str_at(call, &["resultDisplay"])
+            .map(ToOwned::to_owned)
+            .or_else(|| gemini_response_output(call));
+        let tool_name = str_at(call, &["name"]).map(ToOwned::to_owned);
+        let exit_code = text.as_deref().and_then(parse_exit_code);
+        let tool_outcome = Some(exit_code.map_or(status_outcome, |code| {
+            classify_outcome(code, text.as_deref())
+        }));
+        let mut source_record = gemini_call_record(call);
+        if let Some(object) = source_record.as_object_mut() {
+            object.insert("status".to_owned(), Value::String(status.to_owned()));
+        }
+
+        Some(ParsedRecord {
+            index,
+            source_record_hash: gemini_hash("result", call)
+                .unwrap_or_else(|| sha256_prefixed(source_record.to_string().as_bytes())),
+            source_timestamp: str_at(call, &["timestamp"])
+                .or(timestamp)
+                .map(ToOwned::to_owned),
+            kind: RecordKind::ToolResult,
+            source_event_kind: format!("gemini:tool_result:{status}"),
+            role: None,
+            text,
+            prompt_source: None,
+            tool_kind: tool_name.as_deref().map(classify_tool),
+            tool_name,
+            tool_input: None,
+            exit_code,
+            tool_outcome,
+            source_record,
+        })
+    }
+}
+
+/// The parts of a tool `call` that aren't captured as input or output.
+fn gemini_call_record(call: &Value) -> Value {
+    let mut record = call.clone();
+    if let Some(object) = record.as_object_mut() {
+        for field in ["args", "result", "resultDisplay", "status"] {
+            object.remove(field);
+        }
+    }
+    record
+}
+
+/// The text the tool returned to the model, from its function responses.
+fn gemini_response_output(call: &Value) -> Option<String> {
+    let output = call
+        .get("result")?
+        .as_array()?
+        .iter()
+        .filter_map(|part| {
+            let response = value_at(part, &["functionResponse", "response"])?;
+            str_at(response, &["output"])
+                .or_else(|| str_at(response, &["error"]))
+                .map(ToOwned::to_owned)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    (!output.is_empty()).then_some(output)
+}
+
+/// Hash the `kind` and id of the message or tool call `item`, or return `None` if it has no id.
+/// As the session file is rewritten rather than appended to, records are identified by their
+/// ids instead of their bytes, which change while a message or tool call completes.
+fn gemini_hash(kind: &str, item: &Value) -> Option<String> {
+    let id = item.get("id").filter(|id| !id.is_null())?;
+    Some(sha256_prefixed(format!("gemini:{kind}:{id}").as_bytes()))
+}
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
 use anyhow::{Result, bail};
 use serde::Serialize;
@@ -7,6 +7,11 @@ use sha2::{Digest, Sha256};
 
 use crate::agent::Agent;
 
+mod claude;
+mod codex;
+mod cursor;
+mod gemini;
+
 #[derive(Debug)]
 pub(crate) struct TranscriptBatch {
     pub(crate) session_id: Option<String>,
@@ -19,105 +24,58 @@ pub(crate) struct TranscriptBatch {
 
 impl TranscriptBatch {
     pub(crate) fn parse(agent: Agent, snapshot: &[u8]) -> Result<Self> {
-        let mut raw_records = snapshot
-            .split(|byte| *byte == b'\n')
-            .map(|line| line.trim_ascii_end())
-            .filter(|line| !line.iter().all(|byte| byte.is_ascii_whitespace()))
-            .enumerate()
-            .peekable();
+        let reader: &dyn TranscriptReader = match agent {
+            Agent::Codex => &codex::CodexReader,
+            Agent::Claude => &claude::ClaudeReader,
+            Agent::Gemini => &gemini::GeminiReader,
+            Agent::Cursor => &cursor::CursorReader,
+        };
         let mut transcript = TranscriptBatch {
             session_id: None,
-            provider: match agent {
-                Agent::Codex => None,
-                Agent::Claude => Some("anthropic".to_string()),
-            },
+            provider: reader.default_provider().map(ToOwned::to_owned),
             model: None,
             tool_version: None,
             thread_source: None,
             records: Vec::new(),
         };
-        let mut codex_tool_names = HashMap::new();
-        let mut codex_spawn_prompts = HashSet::new();
-        let mut claude_tool_names = HashMap::new();
-
-        while let Some((index, trimmed)) = raw_records.next() {
-            let parsed = match serde_json::from_slice::<Value>(trimmed) {
-                Ok(parsed) => parsed,
-                Err(_) if raw_records.peek().is_none() => continue,
-                Err(_) => bail!("transcript contains malformed JSON before the final record"),
-            };
-
-            let record = match agent {
-                Agent::Codex => {
-                    transcript.apply_codex_metadata(&parsed);
-                    ParsedRecord::from_codex_source(
-                        index,
-                        trimmed,
-                        parsed,
-                        &mut codex_tool_names,
-                        &mut codex_spawn_prompts,
-                        transcript.thread_source.as_deref(),
-                    )
-                }
-                Agent::Claude => {
-                    transcript.apply_claude_metadata(&parsed);
-                    ParsedRecord::from_claude_source(index, trimmed, parsed, &mut claude_tool_names)
-                }
-            };
-            if let Some(record) = record {
-                transcript.records.push(record);
-            }
-        }
-
+        reader.read(snapshot, &mut transcript)?;
         Ok(transcript)
     }
+}
 
-    fn apply_codex_metadata(&mut self, source_record: &Value) {
-        if str_at(source_record, &["type"]) == Some("session_meta") {
-            if self.session_id.is_none() {
-                self.session_id = str_at(source_record, &["payload", "id"])
-                    .or_else(|| str_at(source_record, &["payload", "session_id"]))
-                    .map(ToOwned::to_owned);
-            }
-            if self.provider.is_none() {
-                self.provider =
-                    str_at(source_record, &["payload", "model_provider"]).map(ToOwned::to_owned);
-            }
-            if self.tool_version.is_none() {
-                self.tool_version =
-                    str_at(source_record, &["payload", "cli_version"]).map(ToOwned::to_owned);
-            }
-            if self.thread_source.is_none() {
-                self.thread_source = str_at(source_record, &["payload", "thread_source"])
-                    .map(ToOwned::to_owned)
-                    .or_else(|| {
-                        value_at(source_record, &["payload", "source", "subagent"])
-                            .is_some()
-                            .then(|| "subagent".to_owned())
-                    });
-            }
-        }
-
-        if self.model.is_none() {
-            self.model = str_at(source_record, &["payload", "model"])
-                .or_else(|| str_at(source_record, &["payload", "model_slug"]))
-                .map(ToOwned::to_owned);
-        }
-    }
-
-    fn apply_claude_metadata(&mut self, source_record: &Value) {
-        if self.session_id.is_none() {
-            self.session_id = str_at(source_record, &["sessionId"]).map(ToOwned::to_owned);
-        }
-        if self.tool_version.is_none() {
-            self.tool_version = str_at(source_record, &["version"]).map(ToOwned::to_owned);
-        }
-        if self.model.is_none() {
-            self.model = str_at(source_record, &["message", "model"])
-                .or_else(|| str_at(source_record, &["model"]))
-                .map(ToOwned::to_owned);
-        }
+/// Reads the transcript format of one agent into a [`TranscriptBatch`].
+///
+/// Readers fill in whatever session metadata the transcript has and push one [`ParsedRecord`]
+/// per message, tool call and tool result. Transcripts are read again as they grow and records
+/// are deduplicated by `source_record_hash`, so a record must hash the same on every read.
+trait TranscriptReader {
+    /// The model provider to assume if the transcript doesn't name it.
+    fn default_provider(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Parse `snapshot`, the current contents of the transcript file, into `transcript`.
+    fn read(&self, snapshot: &[u8], transcript: &mut TranscriptBatch) -> Result<()>;
+}
+
+/// Call `record` with the index, the bytes and the parsed value of each non-empty line of the
+/// JSON-lines `snapshot`. A malformed final line is skipped as the agent may still be writing it.
+fn for_each_json_line(snapshot: &[u8], mut record: impl FnMut(usize, &[u8], Value)) -> Result<()> {
+    let mut raw_records = snapshot
+        .split(|byte| *byte == b'\n')
+        .map(|line| line.trim_ascii_end())
+        .filter(|line| !line.iter().all(|byte| byte.is_ascii_whitespace()))
+        .enumerate()
+        .peekable();
+    while let Some((index, trimmed)) = raw_records.next() {
+        let parsed = match serde_json::from_slice::<Value>(trimmed) {
+            Ok(parsed) => parsed,
+            Err(_) if raw_records.peek().is_none() => continue,
+            Err(_) => bail!("transcript contains malformed JSON before the final record"),
+        };
+        record(index, trimmed, parsed);
     }
+    Ok(())
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -173,217 +131,6 @@ pub(crate) struct ParsedRecord {
     pub(crate) source_record: Value,
 }
 
-impl ParsedRecord {
-    fn from_codex_source(
-        index: usize,
-        trimmed: &[u8],
-        mut source_record: Value,
-        tool_names: &mut HashMap<String, String>,
-        spawn_prompts: &mut HashSet<String>,
-        thread_source: Option<&str>,
-    ) -> Option<Self> {
-        let raw_event_kind = codex_event_kind(&source_record);
-        let kind = codex_kind(&raw_event_kind)?;
-        let role = str_at(&source_record, &["payload", "role"])
-            .or_else(|| str_at(&source_record, &["payload", "item", "role"]))
-            .map(ToOwned::to_owned);
-        if kind == RecordKind::Message && matches!(role.as_deref(), Some("developer" | "system")) {
-            return None;
-        }
-        let text = codex_text(&source_record, kind);
-        let mut tool_name = [
-            &["payload", "tool_name"][..],
-            &["payload", "tool"],
-            &["payload", "name"],
-            &["payload", "item", "name"],
-        ]
-        .iter()
-        .find_map(|path| str_at(&source_record, path).map(ToOwned::to_owned));
-        let tool_input = codex_tool_input(&source_record, kind);
-        if kind == RecordKind::ToolResult && tool_name.is_none() {
-            tool_name = codex_call_id(&source_record)
-                .and_then(|call_id| tool_names.get(call_id))
-                .cloned();
-        }
-        if kind == RecordKind::ToolCall
-            && let (Some(call_id), Some(name)) = (codex_call_id(&source_record), tool_name.as_ref())
-        {
-            tool_names.insert(call_id.to_owned(), name.clone());
-        }
-        if matches!(tool_name.as_deref(), Some("spawn_agent"))
-            && let Some(message) = tool_input.as_ref().and_then(spawn_prompt)
-        {
-            spawn_prompts.insert(normalized_prompt(message).to_owned());
-        }
-        let prompt_source = prompt_source(
-            role.as_deref(),
-            text.as_deref(),
-            thread_source == Some("subagent"),
-            spawn_prompts,
-        );
-        let tool_kind = tool_name.as_deref().map(classify_tool);
-        let exit_code = (kind == RecordKind::ToolResult)
-            .then(|| text.as_deref().and_then(parse_exit_code))
-            .flatten();
-        let tool_outcome = exit_code.map(|code| classify_outcome(code, text.as_deref()));
-        prune_codex(&mut source_record, kind, tool_input.is_some());
-
-        Some(ParsedRecord {
-            index,
-            source_record_hash: sha256_prefixed(trimmed),
-            source_timestamp: str_at(&source_record, &["timestamp"]).map(ToOwned::to_owned),
-            source_event_kind: format!("codex:{raw_event_kind}"),
-            kind,
-            role,
-            text,
-            prompt_source,
-            tool_name,
-            tool_kind,
-            tool_input,
-            exit_code,
-            tool_outcome,
-            source_record,
-        })
-    }
-
-    fn from_claude_source(
-        index: usize,
-        trimmed: &[u8],
-        mut source_record: Value,
-        tool_names: &mut HashMap<String, String>,
-    ) -> Option<Self> {
-        let content_block = claude_content_block(&source_record);
-        let content_type = content_block.and_then(|block| str_at(block, &["type"]));
-        let source_event_kind = claude_event_kind(&source_record, content_type);
-        let mut role = str_at(&source_record, &["message", "role"])
-            .or_else(|| {
-                let record_type = str_at(&source_record, &["type"])?;
-                matches!(record_type, "user" | "assistant").then_some(record_type)
-            })
-            .map(ToOwned::to_owned);
-        let mut text = claude_content_text(&source_record);
-        let kind = claude_kind(content_type, text.is_some())?;
-        let mut tool_name = None;
-        let mut tool_input = None;
-
-        match (kind, content_block) {
-            (RecordKind::ToolCall, Some(block)) => {
-                tool_name = str_at(block, &["name"]).map(ToOwned::to_owned);
-                tool_input = block.get("input").cloned();
-                if let (Some(id), Some(name)) = (str_at(block, &["id"]), tool_name.as_deref()) {
-                    tool_names.insert(id.to_owned(), name.to_owned());
-                }
-                text = None;
-            }
-            (RecordKind::ToolResult, Some(block)) => {
-                role = None;
-                text = claude_block_text(block);
-                tool_name = str_at(block, &["tool_use_id"])
-                    .and_then(|id| tool_names.get(id))
-                    .cloned();
-            }
-            _ => {}
-        }
-        prune_claude(&mut source_record, kind, tool_input.is_some());
-        let mut spawn_prompts = HashSet::new();
-        let prompt_source =
-            prompt_source(role.as_deref(), text.as_deref(), false, &mut spawn_prompts);
-        let tool_kind = tool_name.as_deref().map(classify_tool);
-        let exit_code = (kind == RecordKind::ToolResult)
-            .then(|| text.as_deref().and_then(parse_exit_code))
-            .flatten();
-        let tool_outcome = exit_code.map(|code| classify_outcome(code, text.as_deref()));
-
-        Some(ParsedRecord {
-            index,
-            source_record_hash: sha256_prefixed(trimmed),
-            source_timestamp: str_at(&source_record, &["timestamp"]).map(ToOwned::to_owned),
-            source_event_kind,
-            kind,
-            role,
-            text,
-            prompt_source,
-            tool_name,
-            tool_kind,
-            tool_input,
-            exit_code,
-            tool_outcome,
-            source_record,
-        })
-    }
-}
-
-fn codex_kind(source_event_kind: &str) -> Option<RecordKind> {
-    match source_event_kind {
-        "response_item:message" => Some(RecordKind::Message),
-        "response_item:function_call"
-        | "response_item:custom_tool_call"
-        | "response_item:web_search_call" => Some(RecordKind::ToolCall),
-        "response_item:function_call_output" | "response_item:custom_tool_call_output" => {
-            Some(RecordKind::ToolResult)
-        }
-        _ => None,
-    }
-}
-
-fn codex_text(source_record: &Value, kind: RecordKind) -> Option<String> {
-    match kind {
-        RecordKind::Message => first_text_at(
-            source_record,
-            &[
-                &["payload", "content"],
-                &["payload", "text"],
-                &["payload", "message"],
-                &["payload", "item", "content"],
-                &["payload", "item", "text"],
-            ],
-        ),
-        RecordKind::ToolResult => first_text_at(
-            source_record,
-            &[
-                &["payload", "output"],
-                &["payload", "content"],
-                &["payload", "item", "output"],
-                &["payload", "item", "content"],
-            ],
-        ),
-        RecordKind::ToolCall => None,
-    }
-}
-
-fn codex_tool_input(source_record: &Value, kind: RecordKind) -> Option<Value> {
-    if kind != RecordKind::ToolCall {
-        return None;
-    }
-
-    [
-        &["payload", "arguments"][..],
-        &["payload", "input"],
-        &["payload", "item", "arguments"],
-        &["payload", "item", "input"],
-    ]
-    .iter()
-    .find_map(|path| value_at(source_record, path))
-    .map(json_value)
-}
-
-fn codex_call_id(source_record: &Value) -> Option<&str> {
-    [
-        &["payload", "call_id"][..],
-        &["payload", "item", "call_id"],
-        &["payload", "id"],
-        &["payload", "item", "id"],
-    ]
-    .iter()
-    .find_map(|path| str_at(source_record, path))
-}
-
-fn spawn_prompt(input: &Value) -> Option<&str> {
-    ["message", "prompt", "task"]
-        .into_iter()
-        .find_map(|key| input.get(key).and_then(Value::as_str))
-}
-
 fn prompt_source(
     role: Option<&str>,
     text: Option<&str>,
@@ -422,15 +169,32 @@ fn is_system_injected_prompt(text: &str) -> bool {
 
 fn classify_tool(tool_name: &str) -> ToolKind {
     match tool_name {
-        "exec_command" | "Bash" | "bash" | "shell" | "local_shell" | "run_command" => {
-            ToolKind::Exec
-        }
+        "exec_command" | "Bash" | "bash" | "shell" | "local_shell" | "run_command"
+        | "run_shell_command" => ToolKind::Exec,
         "apply_patch" | "edit_file" | "write_file" | "str_replace" | "Edit" | "MultiEdit"
-        | "Write" => ToolKind::FileEdit,
+        | "Write" | "replace" | "edit" | "write" | "delete" => ToolKind::FileEdit,
         "spawn_agent" | "Task" => ToolKind::SubAgent,
-        "write_stdin" | "wait_agent" | "close_agent" | "kill_agent" | "update_plan" | "Read"
-        | "Glob" | "Grep" | "LS" | "TodoWrite" => ToolKind::Housekeeping,
-        "web_search" | "web_search_call" => ToolKind::WebSearch,
+        "write_stdin"
+        | "wait_agent"
+        | "close_agent"
+        | "kill_agent"
+        | "update_plan"
+        | "Read"
+        | "Glob"
+        | "Grep"
+        | "LS"
+        | "TodoWrite"
+        | "read_file"
+        | "read_many_files"
+        | "list_directory"
+        | "search_file_content"
+        | "glob"
+        | "save_memory"
+        | "read"
+        | "grep"
+        | "ls"
+        | "updateTodos" => ToolKind::Housekeeping,
+        "web_search" | "web_search_call" | "google_web_search" | "web_fetch" => ToolKind::WebSearch,
         _ => ToolKind::Other,
     }
 }
@@ -478,43 +242,6 @@ fn looks_like_cargo_invocation_error(text: &str) -> bool {
     .any(|needle| lower.contains(needle))
 }
 
-fn prune_codex(source_record: &mut Value, kind: RecordKind, has_tool_input: bool) {
-    match kind {
-        RecordKind::Message => {
-            for path in [
-                &["payload", "content"][..],
-                &["payload", "text"],
-                &["payload", "message"],
-                &["payload", "item", "content"],
-                &["payload", "item", "text"],
-            ] {
-                remove_field_at(source_record, path);
-            }
-        }
-        RecordKind::ToolCall if has_tool_input => {
-            for path in [
-                &["payload", "arguments"][..],
-                &["payload", "input"],
-                &["payload", "item", "arguments"],
-                &["payload", "item", "input"],
-            ] {
-                remove_field_at(source_record, path);
-            }
-        }
-        RecordKind::ToolResult => {
-            for path in [
-                &["payload", "output"][..],
-                &["payload", "content"],
-                &["payload", "item", "output"],
-                &["payload", "item", "content"],
-            ] {
-                remove_field_at(source_record, path);
-            }
-        }
-        RecordKind::ToolCall => {}
-    }
-}
-
 fn first_text_at(value: &Value, paths: &[&[&str]]) -> Option<String> {
     paths.iter().find_map(|path| text_at(value, path))
 }
@@ -571,98 +298,10 @@ fn str_at<'a>(value: &'a Value, path: &[&str]) -> Option<&'a str> {
     value_at(value, path)?.as_str()
 }
 
-fn codex_event_kind(source_record: &Value) -> String {
-    let top_level_type = str_at(source_record, &["type"]).unwrap_or("unknown");
-    match str_at(source_record, &["payload", "type"]) {
-        Some(nested_type) => format!("{top_level_type}:{nested_type}"),
-        None => top_level_type.to_owned(),
-    }
-}
-
 fn sha256_prefixed(bytes: &[u8]) -> String {
     format!("sha256:{}", hex::encode(Sha256::digest(bytes)))
 }
 
-fn claude_event_kind(source_record: &Value, content_type: Option<&str>) -> String {
-    let top_level_type = str_at(source_record, &["type"]).unwrap_or("unknown");
-    match content_type.or_else(|| str_at(source_record, &["message", "type"])) {
-        Some(nested_type) => format!("claude:{top_level_type}:{nested_type}"),
-        None if source_record.get("message").is_some() => {
-            format!("claude:{top_level_type}:message")
-        }
-        None => format!("claude:{top_level_type}"),
-    }
-}
-
-fn claude_kind(content_type: Option<&str>, has_text: bool) -> Option<RecordKind> {
-    match content_type {
-        Some("text") => Some(RecordKind::Message),
-        Some("tool_use") => Some(RecordKind::ToolCall),
-        Some("tool_result") => Some(RecordKind::ToolResult),
-        _ if has_text => Some(RecordKind::Message),
-        _ => None,
-    }
-}
-
-fn claude_content_block(source_record: &Value) -> Option<&Value> {
-    source_record
-        .get("message")?
-        .get("content")?
-        .as_array()?
-        .iter()
-        .find(|block| str_at(block, &["type"]).is_some())
-}
-
-fn claude_content_text(source_record: &Value) -> Option<String> {
-    if let Some(content) = str_at(source_record, &["message", "content"]) {
-        return Some(content.to_owned());
-    }
-
-    joined_block_text(source_record.get("message")?.get("content")?.as_array()?)
-}
-
-fn claude_block_text(block: &Value) -> Option<String> {
-    match block.get("content") {
-        Some(Value::String(text)) => Some(text.clone()),
-        Some(Value::Array(blocks)) => joined_block_text(blocks),
-        _ => str_at(block, &["text"]).map(ToOwned::to_owned),
-    }
-}
-
-fn prune_claude(source_record: &mut Value, kind: RecordKind, has_tool_input: bool) {
-    if kind == RecordKind::Message {
-        remove_field_at(source_record, &["message", "content"]);
-        return;
-    }
-
-    let Some(blocks) = source_record
-        .get_mut("message")
-        .and_then(|message| message.get_mut("content"))
-        .and_then(Value::as_array_mut)
-    else {
-        return;
-    };
-    let Some(block) = blocks
-        .iter_mut()
-        .find(|block| str_at(block, &["type"]).is_some())
-    else {
-        return;
-    };
-    match kind {
-        RecordKind::ToolCall if has_tool_input => {
-            if let Some(object) = block.as_object_mut() {
-                object.remove("input");
-            }
-        }
-        RecordKind::ToolResult => {
-            if let Some(object) = block.as_object_mut() {
-                object.remove("content");
-            }
-        }
-        RecordKind::Message | RecordKind::ToolCall => {}
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -760,6 +399,122 @@ mod tests {
         assert_eq!(transcript.records[3].text.as_deref(), Some("ok"));
     }
 
+    #[test]
+    fn parses_gemini_session_and_extracts_metadata() {
+        let data = br#"{
+  "sessionId": "gemini-session-1",
+  "projectHash": "abc",
+  "messages": [
+    {"id": "m1", "timestamp": "2026-05-07T09:00:00Z", "type": "user", "content": "List the files"},
+    {"id": "m2", "timestamp": "2026-05-07T09:00:01Z", "type": "info", "content": "Switched model"},
+    {"id": "m3", "timestamp": "2026-05-07T09:00:02Z", "type": "gemini", "content": "Running ls", "model": "gemini-2.5-pro",
+     "toolCalls": [
+       {"id": "call-1", "name": "run_shell_command", "args": {"command": "ls"}, "status": "success", "resultDisplay": "README.md"},
+       {"id": "call-2", "name": "read_file", "args": {"absolute_path": "/tmp/a"}, "status": "executing"}
+     ]}
+  ]
+}"#;
+
+        let transcript = TranscriptBatch::parse(Agent::Gemini, data).expect("parse transcript");
+
+        assert_eq!(transcript.session_id.as_deref(), Some("gemini-session-1"));
+        assert_eq!(transcript.provider.as_deref(), Some("google"));
+        assert_eq!(transcript.model.as_deref(), Some("gemini-2.5-pro"));
+        assert_eq!(transcript.records.len(), 5);
+        assert_eq!(transcript.records[0].role.as_deref(), Some("user"));
+        assert_eq!(
+            transcript.records[0].prompt_source,
+            Some(PromptSource::Human)
+        );
+        assert_eq!(
+            transcript.records[0].text.as_deref(),
+            Some("List the files")
+        );
+        assert_eq!(transcript.records[1].role.as_deref(), Some("assistant"));
+        assert_eq!(transcript.records[1].source_event_kind, "gemini:gemini");
+        assert_eq!(transcript.records[2].kind, RecordKind::ToolCall);
+        assert_eq!(transcript.records[2].tool_kind, Some(ToolKind::Exec));
+        assert_eq!(
+            transcript.records[2]
+                .tool_input
+                .as_ref()
+                .expect("tool input")["command"],
+            "ls"
+        );
+        assert_eq!(transcript.records[3].kind, RecordKind::ToolResult);
+        assert_eq!(transcript.records[3].text.as_deref(), Some("README.md"));
+        assert_eq!(
+            transcript.records[3].tool_outcome,
+            Some(ToolOutcome::Succeeded)
+        );
+        assert_eq!(
+            transcript.records[4].kind,
+            RecordKind::ToolCall,
+            "running tool calls have no result yet"
+        );
+
+        let completed = String::from_utf8_lossy(data).replace(
+            r#""status": "executing"}"#,
+            r#""status": "error", "resultDisplay": "No such file"}"#,
+        );
+        let grown = TranscriptBatch::parse(Agent::Gemini, completed.as_bytes())
+            .expect("parse grown transcript");
+        assert_eq!(grown.records.len(), 6);
+        assert_eq!(
+            grown.records[4].source_record_hash, transcript.records[4].source_record_hash,
+            "records keep their identity when the session file is rewritten"
+        );
+        assert_eq!(grown.records[5].tool_outcome, Some(ToolOutcome::Failed));
+    }
+
+    #[test]
+    fn parses_cursor_records_and_extracts_metadata() {
+        let data = br#"
+{"type":"system","subtype":"init","session_id":"cursor-session-1","model":"Claude 4 Sonnet","cwd":"/tmp/project"}
+{"type":"user","message":{"role":"user","content":[{"type":"text","text":"Run the tests"}]},"session_id":"cursor-session-1"}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Running them"}]},"session_id":"cursor-session-1"}
+{"type":"tool_call","subtype":"started","call_id":"call-1","tool_call":{"shellToolCall":{"args":{"command":"cargo test"}}},"session_id":"cursor-session-1"}
+{"type":"tool_call","subtype":"completed","call_id":"call-1","tool_call":{"shellToolCall":{"args":{"command":"cargo test"},"result":{"failure":{"command":"cargo test","exitCode":101,"stdout":"","stderr":"test failed"}}}},"session_id":"cursor-session-1"}
+{"type":"result","subtype":"success","result":"Running them","session_id":"cursor-session-1"}
+"#;
+
+        let transcript = TranscriptBatch::parse(Agent::Cursor, data).expect("parse transcript");
+
+        assert_eq!(transcript.session_id.as_deref(), Some("cursor-session-1"));
+        assert_eq!(transcript.provider.as_deref(), None);
+        assert_eq!(transcript.model.as_deref(), Some("Claude 4 Sonnet"));
+        assert_eq!(transcript.records.len(), 4);
+        assert_eq!(transcript.records[0].index, 1);
+        assert_eq!(
+            transcript.records[0].prompt_source,
+            Some(PromptSource::Human)
+        );
+        assert_eq!(transcript.records[0].text.as_deref(), Some("Run the tests"));
+        assert_eq!(transcript.records[1].text.as_deref(), Some("Running them"));
+        assert_eq!(transcript.records[2].kind, RecordKind::ToolCall);
+        assert_eq!(transcript.records[2].tool_name.as_deref(), Some("shell"));
+        assert_eq!(transcript.records[2].tool_kind, Some(ToolKind::Exec));
+        assert_eq!(
+            transcript.records[2]
+                .tool_input
+                .as_ref()
+                .expect("tool input")["command"],
+            "cargo test"
+        );
+        assert_eq!(
+            transcript.records[2].source_record["tool_call"]["shellToolCall"].get("args"),
+            None,
+            "the input is captured separately"
+        );
+        assert_eq!(transcript.records[3].kind, RecordKind::ToolResult);
+        assert_eq!(transcript.records[3].text.as_deref(), Some("test failed"));
+        assert_eq!(transcript.records[3].exit_code, Some(101));
+        assert_eq!(
+            transcript.records[3].tool_outcome,
+            Some(ToolOutcome::Failed)
+        );
+    }
+
     #[test]
     fn tags_codex_prompt_sources() {
         let data = br##"
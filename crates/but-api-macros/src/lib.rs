@@ -87,6 +87,8 @@ use syn::{FnArg, ItemFn, Pat, parse_macro_input};
 ///           parameter by acquiring the matching lock in the generated wrapper
 ///         - Other serde-compatible types → `serde_json::Value`
 ///     - Automatically converts `anyhow::Error` → `napi::Error`.
+/// * a `but_schemars::ApiCommandEntry` describing the parameters and return type of `func_cmd`,
+///   for the machine-readable description of the `but-server` API.
 #[proc_macro_attribute]
 pub fn but_api(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input_fn = parse_macro_input!(item as ItemFn);
@@ -381,6 +383,25 @@ pub fn but_api(attr: TokenStream, item: TokenStream) -> TokenStream {
         quote! {}
     };
 
+    // Registered for every function, as `but-server` describes its API from this registry.
+    let command_docs = doc_string(&input_fn.attrs);
+    let command_params = wrapper_params.json_fields.iter().map(|(ident, ty)| {
+        let name = ident.to_string().to_case(Case::Camel);
+        let ty = type_to_api_type(ty);
+        quote! { ::but_schemars::ApiCommandParam { name: #name, ty: #ty } }
+    });
+    let command_returns = type_to_api_type(&json_ty);
+    let command_registry_entry = quote! {
+        ::but_schemars::internal_submit! {
+            ::but_schemars::ApiCommandEntry {
+                name: #fn_name_str,
+                docs: #command_docs,
+                params: &[#(#command_params),*],
+                returns: #command_returns,
+            }
+        }
+    };
+
     let napi_fn_block = if opts.napi {
         quote! {
             #(#napi_doc_attrs)*
@@ -405,6 +426,7 @@ pub fn but_api(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let expanded = quote! {
         #napi_registry_entry
+        #command_registry_entry
 
         // Generated struct
         #[cfg(feature = "legacy")]
@@ -467,6 +489,8 @@ struct WrapperParamsInfo {
     json_fn_input_params: Vec<FnArg>,
     param_conversions: Vec<proc_macro2::TokenStream>,
     call_arg_idents: Vec<proc_macro2::TokenStream>,
+    /// The fields of the JSON parameter object along with their transport types.
+    json_fields: Vec<(syn::Ident, syn::Type)>,
     requires_legacy: bool,
 }
 
@@ -530,6 +554,7 @@ fn build_wrapper_params<'a>(
     let mut json_fn_input_params = Vec::new();
     let mut param_conversions = Vec::new();
     let mut call_arg_idents = Vec::new();
+    let mut json_fields = Vec::new();
     let mut requires_legacy = false;
     let mut context_bindings = Vec::new();
     let mut permission_bindings = Vec::new();
@@ -566,6 +591,7 @@ fn build_wrapper_params<'a>(
                 let mut #ident: #binding_ty = <#binding_ty>::from(#ident);
             });
             call_arg_idents.push(quote! { #ident });
+            json_fields.push((ident.clone(), transport_ty.clone()));
             continue;
         }
 
@@ -613,6 +639,7 @@ fn build_wrapper_params<'a>(
                 WrapperCallArgKind::Ref { mutable: false } => quote! { &#ident },
                 WrapperCallArgKind::AsRef => quote! { #ident.as_ref() },
             });
+            json_fields.push((json_ident, mapping.transport_ty));
             requires_legacy |= mapping.requires_legacy;
         } else {
             param_field_names.push(ident.clone());
@@ -620,6 +647,7 @@ fn build_wrapper_params<'a>(
             struct_fields_with_json_types.push(quote! { pub #ident: #ty });
             json_fn_input_params.push(arg.clone());
             call_arg_idents.push(quote! { #ident });
+            json_fields.push((ident.clone(), (**ty).clone()));
         }
     }
 
@@ -672,6 +700,7 @@ fn build_wrapper_params<'a>(
         json_fn_input_params,
         param_conversions,
         call_arg_idents,
+        json_fields,
         requires_legacy,
     })
 }
@@ -983,6 +1012,31 @@ fn doc_attributes(attrs: &[syn::Attribute]) -> Vec<syn::Attribute> {
         .collect()
 }
 
+/// Join the text of all `#[doc = "..."]` attributes, as written with `///`.
+fn doc_string(attrs: &[syn::Attribute]) -> String {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(nv) if nv.path.is_ident("doc") => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(text),
+                    ..
+                }) => Some(text.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .map(ToOwned::to_owned)
+                .unwrap_or(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_owned()
+}
+
 struct JsonParameterMapping {
     /// The mapped type to which the actual type can be converted.
     json_ty: syn::Path,
@@ -1782,6 +1836,78 @@ fn type_to_ts_name(ty: &syn::Type) -> String {
     }
 }
 
+/// Convert a Rust `syn::Type` to an expression of `but_schemars::ApiType` that describes its
+/// JSON shape, along the lines of [`type_to_ts_name()`].
+fn type_to_api_type(ty: &syn::Type) -> proc_macro2::TokenStream {
+    match ty {
+        syn::Type::Path(tp) => {
+            let Some(last) = tp.path.segments.last() else {
+                return quote! { ::but_schemars::ApiType::Any };
+            };
+            // The type of the `nth` generic argument, or `Any` if there is none.
+            let type_argument = |nth: usize| {
+                let arg = match &last.arguments {
+                    syn::PathArguments::AngleBracketed(args) => args
+                        .args
+                        .iter()
+                        .filter_map(|arg| match arg {
+                            syn::GenericArgument::Type(ty) => Some(ty),
+                            _ => None,
+                        })
+                        .nth(nth),
+                    _ => None,
+                };
+                arg.map(type_to_api_type)
+                    .unwrap_or_else(|| quote! { ::but_schemars::ApiType::Any })
+            };
+            match last.ident.to_string().as_str() {
+                "String"
+                | "str"
+                | "PathBuf"
+                | "Path"
+                | "HexHash"
+                | "HexHashString"
+                | "ObjectId"
+                | "BString"
+                | "StackId"
+                | "FullName"
+                | "FullNameRef"
+                | "ProjectHandleOrLegacyProjectId" => quote! { ::but_schemars::ApiType::String },
+                "bool" | "DryRun" => quote! { ::but_schemars::ApiType::Boolean },
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+                | "i128" | "isize" => quote! { ::but_schemars::ApiType::Integer },
+                "f32" | "f64" => quote! { ::but_schemars::ApiType::Number },
+                "Value" => quote! { ::but_schemars::ApiType::Any },
+                "Vec" => {
+                    let items = type_argument(0);
+                    quote! { ::but_schemars::ApiType::Array(&#items) }
+                }
+                "Option" => {
+                    let inner = type_argument(0);
+                    quote! { ::but_schemars::ApiType::Nullable(&#inner) }
+                }
+                // `Sensitive` deserializes straight through to the value.
+                "Sensitive" => type_argument(0),
+                // Keys are always strings in JSON, so only the values matter.
+                "HashMap" | "BTreeMap" => {
+                    let values = type_argument(1);
+                    quote! { ::but_schemars::ApiType::Map(&#values) }
+                }
+                other => quote! { ::but_schemars::ApiType::Named(#other) },
+            }
+        }
+        syn::Type::Tuple(tuple) if tuple.elems.is_empty() => {
+            quote! { ::but_schemars::ApiType::Null }
+        }
+        syn::Type::Tuple(tuple) => {
+            let elems = tuple.elems.iter().map(type_to_api_type);
+            quote! { ::but_schemars::ApiType::Tuple(&[#(#elems),*]) }
+        }
+        syn::Type::Reference(r) => type_to_api_type(&r.elem),
+        _ => quote! { ::but_schemars::ApiType::Any },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use quote::quote;
@@ -1790,7 +1916,8 @@ mod tests {
     use super::{
         ContextParamKind, PermissionParamKind, WrapperCallArgKind, WrapperConversionKind,
         build_json_type_mapping, build_napi_params, build_wrapper_parameter_mapping,
-        build_wrapper_params, context_param_kind, doc_attributes, permission_param_kind,
+        build_wrapper_params, context_param_kind, doc_attributes, doc_string,
+        permission_param_kind, type_to_api_type,
     };
 
     #[test]
@@ -2011,4 +2138,84 @@ mod tests {
             "full-name references should not force legacy wrapper generation"
         );
     }
+
+    #[test]
+    fn joins_doc_lines_for_the_command_registry() {
+        let item_fn: ItemFn = parse_quote! {
+            /// Summary.
+            ///
+            /// Details with `code`.
+            #[cfg(feature = "napi")]
+            pub fn my_api() -> anyhow::Result<()> {
+                Ok(())
+            }
+        };
+
+        assert_eq!(
+            doc_string(&item_fn.attrs),
+            "Summary.\n\nDetails with `code`."
+        );
+    }
+
+    #[test]
+    fn maps_transport_types_to_api_types() {
+        let cases: [(syn::Type, proc_macro2::TokenStream); 6] = [
+            (
+                parse_quote!(but_ctx::ProjectHandleOrLegacyProjectId),
+                quote!(::but_schemars::ApiType::String),
+            ),
+            (
+                parse_quote!(Option<Vec<crate::json::HexHash>>),
+                quote!(::but_schemars::ApiType::Nullable(
+                    &::but_schemars::ApiType::Array(&::but_schemars::ApiType::String)
+                )),
+            ),
+            (
+                parse_quote!(std::collections::HashMap<String, u32>),
+                quote!(::but_schemars::ApiType::Map(
+                    &::but_schemars::ApiType::Integer
+                )),
+            ),
+            (
+                parse_quote!((crate::json::HexHash, bool)),
+                quote!(::but_schemars::ApiType::Tuple(&[
+                    ::but_schemars::ApiType::String,
+                    ::but_schemars::ApiType::Boolean
+                ])),
+            ),
+            (parse_quote!(()), quote!(::but_schemars::ApiType::Null)),
+            (
+                parse_quote!(crate::json::WorkspaceState),
+                quote!(::but_schemars::ApiType::Named("WorkspaceState")),
+            ),
+        ];
+        for (ty, expected) in cases {
+            assert_eq!(
+                type_to_api_type(&ty).to_string(),
+                expected.to_string(),
+                "{}",
+                quote!(#ty)
+            );
+        }
+    }
+
+    #[test]
+    fn collects_json_fields_with_their_transport_types() {
+        let context: FnArg = parse_quote!(ctx: &mut but_ctx::Context);
+        let commit: FnArg = parse_quote!(commit_id: gix::ObjectId);
+        let params = build_wrapper_params([&context, &commit]).unwrap();
+        let fields: Vec<_> = params
+            .json_fields
+            .iter()
+            .map(|(ident, ty)| quote!(#ident: #ty).to_string())
+            .collect();
+
+        assert_eq!(
+            fields,
+            [
+                quote!(project_id: but_ctx::ProjectHandleOrLegacyProjectId).to_string(),
+                quote!(commit_id: crate::json::HexHash).to_string(),
+            ]
+        );
+    }
 }
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use but_api_macros::but_api;
+use but_azure::{AuthStatusResponse, AuthenticatedUser, json};
+use but_secret::Sensitive;
+use tracing::instrument;
+
+/// Stores a personal access token for an Azure DevOps organization.
+///
+/// Azure DevOps PATs are issued per organization and authenticate over HTTP Basic
+/// with an empty username. Validates and stores the provided token, then returns
+/// the authenticated user.
+///
+/// # Arguments
+///
+/// * `organization` - The organization name, as in `https://dev.azure.com/{organization}`
+/// * `access_token` - The personal access token to store (wrapped in Sensitive)
+///
+/// # Returns
+///
+/// * `Ok(_)` - The token is valid and stored
+/// * `Err(_)` - If the token is invalid or storage fails
+#[but_api(napi, json::AzureAuthStatusResponse, invalidates = [ForgeAccounts, ForgeLogin])]
+#[instrument(err(Debug))]
+pub async fn store_azure_pat(
+    organization: String,
+    access_token: Sensitive<String>,
+) -> Result<AuthStatusResponse> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_azure::store_pat(&organization, &access_token, &storage).await
+}
+
+/// Removes stored credentials for a specific Azure DevOps account.
+///
+/// # Arguments
+///
+/// * `account` - Identifier for the Azure DevOps account
+///
+/// # Returns
+///
+/// * `Ok(())` - Always succeeds, even if no token was found
+#[but_api(napi, invalidates = [ForgeAccounts, ForgeLogin])]
+#[instrument(err(Debug))]
+pub fn forget_azure_account(account: but_azure::AzureAccountIdentifier) -> Result<()> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_azure::forget_az_access_token(&account, &storage).ok();
+    Ok(())
+}
+
+/// Removes all stored Azure DevOps credentials.
+///
+/// # Returns
+///
+/// * `Ok(())` - All tokens successfully cleared
+/// * `Err(_)` - If storage cleanup fails
+#[but_api]
+#[instrument(err(Debug))]
+pub fn clear_all_azure_tokens() -> Result<()> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_azure::clear_all_azure_tokens(&storage)
+}
+
+/// Retrieves the authenticated user information for an Azure DevOps account.
+///
+/// # Arguments
+///
+/// * `account` - Identifier for the Azure DevOps account to query
+///
+/// # Returns
+///
+/// * `Ok(Some(AuthenticatedUser))` - User information
+/// * `Ok(None)` - No credentials stored for this account
+/// * `Err(_)` - If the API request fails or credentials are invalid
+#[but_api(napi, json::AzureAuthenticatedUserSensitive)]
+#[instrument(err(Debug))]
+pub async fn get_az_user(
+    account: but_azure::AzureAccountIdentifier,
+) -> Result<Option<AuthenticatedUser>> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_azure::get_az_user(&account, &storage).await
+}
+
+/// Lists all Azure DevOps accounts with stored credentials.
+///
+/// # Returns
+///
+/// * `Ok(Vec<AzureAccountIdentifier>)` - List of all known accounts
+/// * `Err(_)` - If storage access fails
+#[but_api(napi)]
+#[instrument(err(Debug))]
+pub fn list_known_azure_accounts() -> Result<Vec<but_azure::AzureAccountIdentifier>> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_azure::list_known_azure_accounts(&storage)
+}
+
+/// Validates stored Azure DevOps credentials.
+///
+/// # Arguments
+///
+/// * `account` - Identifier for the Azure DevOps account to validate
+///
+/// # Returns
+///
+/// * `Ok(CredentialCheckResult)` - Result indicating if credentials are valid
+/// * `Err(_)` - If the validation request fails
+#[but_api]
+#[instrument(err(Debug))]
+pub async fn check_azure_credentials(
+    account: but_azure::AzureAccountIdentifier,
+) -> Result<but_azure::CredentialCheckResult> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_azure::check_credentials(&account, &storage).await
+}
@@ -1,10 +1,13 @@
-//! API surface for ephemeral comments anchored to lines in diffs, created in the GUI and acted
+//! API surface for comments anchored to lines in diffs, created in the GUI and acted
 //! on by agents via the CLI. All logic lives in [`but_comments`]; this module only binds it to
 //! [`Context`](but_ctx::Context) (store location, workspace resolution, locking, and wall-clock
 //! time).
 
 use but_api_macros::but_api;
-use but_comments::{CommentStore, DiffComment, NewComment};
+use but_comments::{
+    CommentReply, CommentStore, DiffComment, ImportOutcome, NewComment, NewReply, ReviewComment,
+    notes::NotesSync,
+};
 use but_core::sync::RepoShared;
 use but_ctx::Context;
 use tracing::instrument;
@@ -18,10 +21,43 @@ fn now_ms() -> i64 {
     chrono::Utc::now().timestamp_millis()
 }
 
-/// The comments file is invisible to the desktop file monitor, so out-of-process mutations
+/// The name of the Git author configured for the repository of `ctx`, used for comments that
+/// don't name their author.
+fn author_name(ctx: &Context) -> Option<String> {
+    let repo = ctx.repo.get().ok()?;
+    let author = repo.author()?.ok()?;
+    Some(author.name.to_string())
+}
+
+/// The project database is invisible to the desktop file monitor, so out-of-process mutations
 /// (notably agents running the CLI) must touch the refresh sentinel for the GUI to pick them up.
 fn notify_desktop_watcher(ctx: &Context) {
-    but_project_handle::write_refresh_sentinel(&ctx.project_data_dir().join("comments.json"));
+    but_project_handle::write_refresh_sentinel(&but_db::DbHandle::db_file_path(
+        ctx.project_data_dir(),
+    ));
+}
+
+/// Exchange comments on commits with the Git notes ref if that is enabled with
+/// [`but_comments::notes::SYNC_CONFIG_KEY`]. Failing to do so is only logged, as the comments
+/// themselves are safe in the store. Returns `true` if comments were imported into the store.
+fn sync_notes_if_enabled(ctx: &Context) -> bool {
+    let synced = ctx
+        .repo
+        .get()
+        .map_err(anyhow::Error::from)
+        .and_then(|repo| {
+            if !but_comments::notes::is_sync_enabled(&repo) {
+                return Ok(NotesSync::default());
+            }
+            but_comments::notes::sync_with_notes(&repo, &store(ctx))
+        });
+    match synced {
+        Ok(synced) => synced.imported > 0,
+        Err(err) => {
+            tracing::warn!("could not sync comments with Git notes: {err:#}");
+            false
+        }
+    }
 }
 
 /// Create a new comment anchored to a line in a diff.
@@ -37,9 +73,12 @@ pub fn comment_create(ctx: &Context, comment: NewComment) -> anyhow::Result<Diff
 /// See [`comment_create`]; this variant is for callers that already hold shared worktree access.
 pub fn comment_create_with_perm(
     ctx: &Context,
-    comment: NewComment,
+    mut comment: NewComment,
     perm: &RepoShared,
 ) -> anyhow::Result<DiffComment> {
+    if comment.author.is_none() {
+        comment.author = author_name(ctx);
+    }
     let created = {
         let (repo, workspace, _db) = ctx.workspace_and_db_with_perm(perm)?;
         but_comments::create_comment(
@@ -51,10 +90,38 @@ pub fn comment_create_with_perm(
             now_ms(),
         )?
     };
+    sync_notes_if_enabled(ctx);
     notify_desktop_watcher(ctx);
     Ok(created)
 }
 
+/// Import `comments` from the forge review of `branch` as comments on its commits.
+///
+/// See [`but_comments::import_review_comments`] for how they are anchored.
+pub fn import_review_comments(
+    ctx: &Context,
+    branch: &gix::refs::FullNameRef,
+    comments: Vec<ReviewComment>,
+) -> anyhow::Result<ImportOutcome> {
+    let outcome = {
+        let guard = ctx.shared_worktree_access();
+        let (repo, workspace, _db) = ctx.workspace_and_db_with_perm(guard.read_permission())?;
+        but_comments::import_review_comments(
+            &repo,
+            &workspace,
+            &store(ctx),
+            branch,
+            comments,
+            ctx.settings.context_lines,
+        )?
+    };
+    if outcome.imported > 0 || outcome.updated > 0 {
+        sync_notes_if_enabled(ctx);
+        notify_desktop_watcher(ctx);
+    }
+    Ok(outcome)
+}
+
 /// List all unarchived comments, re-anchored against the current diffs.
 ///
 /// See [`but_comments::list_comments`] for the re-anchoring and auto-archiving semantics.
@@ -70,6 +137,8 @@ pub fn comments_list_with_perm(
     ctx: &Context,
     perm: &RepoShared,
 ) -> anyhow::Result<Vec<DiffComment>> {
+    // Import what teammates shared first, so it is re-anchored and listed right away.
+    let imported = sync_notes_if_enabled(ctx);
     let listing = {
         let (repo, workspace, _db) = ctx.workspace_and_db_with_perm(perm)?;
         but_comments::list_comments(
@@ -80,9 +149,9 @@ pub fn comments_list_with_perm(
             now_ms(),
         )?
     };
-    // Listing can mutate the store (drift, auto-archiving, purging); when it did, other
-    // processes' views are stale — notably the GUI after a CLI listing auto-archived something.
-    if listing.persisted_changes {
+    // Listing can mutate the store (drift, auto-archiving); when it did, other processes' views
+    // are stale — notably the GUI after a CLI listing auto-archived something.
+    if imported || listing.persisted_changes {
         notify_desktop_watcher(ctx);
     }
     Ok(listing.comments)
@@ -93,6 +162,7 @@ pub fn comments_list_with_perm(
 #[instrument(skip(ctx, payload), err(Debug))]
 pub fn comment_update(ctx: &Context, id: String, payload: String) -> anyhow::Result<()> {
     but_comments::update_payload(&store(ctx), &id, payload, now_ms())?;
+    sync_notes_if_enabled(ctx);
     notify_desktop_watcher(ctx);
     Ok(())
 }
@@ -104,7 +174,62 @@ pub fn comment_update(ctx: &Context, id: String, payload: String) -> anyhow::Res
 pub fn comment_archive(ctx: &Context, id: String) -> anyhow::Result<bool> {
     let archived = but_comments::archive_comment(&store(ctx), &id, now_ms())?;
     if archived {
+        sync_notes_if_enabled(ctx);
         notify_desktop_watcher(ctx);
     }
     Ok(archived)
 }
+
+/// Reply to a comment, adding the reply to the thread the comment belongs to.
+/// The reply is attributed to the configured Git author unless it names its author.
+#[but_api(napi)]
+#[instrument(skip(ctx, reply), err(Debug))]
+pub fn comment_reply(ctx: &Context, mut reply: NewReply) -> anyhow::Result<CommentReply> {
+    if reply.author.is_none() {
+        reply.author = author_name(ctx);
+    }
+    let created = but_comments::reply_to_comment(&store(ctx), reply, now_ms())?;
+    sync_notes_if_enabled(ctx);
+    notify_desktop_watcher(ctx);
+    Ok(created)
+}
+
+/// Resolve the thread the comment with the given `id` belongs to, on behalf of the configured
+/// Git author. Returns `false` if the thread was already resolved.
+#[but_api(napi)]
+#[instrument(skip(ctx), err(Debug))]
+pub fn comment_resolve(ctx: &Context, id: String) -> anyhow::Result<bool> {
+    let resolved = but_comments::resolve_thread(&store(ctx), &id, author_name(ctx), now_ms())?;
+    if resolved {
+        sync_notes_if_enabled(ctx);
+        notify_desktop_watcher(ctx);
+    }
+    Ok(resolved)
+}
+
+/// Mark the thread the comment with the given `id` belongs to as unresolved again.
+/// Returns `false` if the thread wasn't resolved.
+#[but_api(napi)]
+#[instrument(skip(ctx), err(Debug))]
+pub fn comment_reopen(ctx: &Context, id: String) -> anyhow::Result<bool> {
+    let reopened = but_comments::reopen_thread(&store(ctx), &id, now_ms())?;
+    if reopened {
+        sync_notes_if_enabled(ctx);
+        notify_desktop_watcher(ctx);
+    }
+    Ok(reopened)
+}
+
+/// Merge the comments shared in the Git notes ref into the store and write all comments on
+/// commits back to it, whether or not syncing is enabled in the configuration.
+///
+/// See [`but_comments::notes`] for how to share the ref with teammates.
+#[but_api(napi)]
+#[instrument(skip(ctx), err(Debug))]
+pub fn comments_sync_notes(ctx: &Context) -> anyhow::Result<NotesSync> {
+    let synced = but_comments::notes::sync_with_notes(&*ctx.repo.get()?, &store(ctx))?;
+    if synced.imported > 0 {
+        notify_desktop_watcher(ctx);
+    }
+    Ok(synced)
+}
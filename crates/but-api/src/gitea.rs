@@ -0,0 +1,112 @@
+use anyhow::Result;
+use but_api_macros::but_api;
+use but_gitea::{AuthStatusResponse, AuthenticatedUser, json};
+use but_secret::Sensitive;
+use tracing::instrument;
+
+/// Stores an access token for a Gitea or Forgejo instance.
+///
+/// There is no canonical public instance, so the token is always stored together with
+/// the instance it was issued by. Validates and stores the provided token, then returns
+/// the authenticated user.
+///
+/// # Arguments
+///
+/// * `host` - The URL of the instance, e.g. `https://codeberg.org`
+/// * `access_token` - The access token to store (wrapped in Sensitive)
+///
+/// # Returns
+///
+/// * `Ok(_)` - The token is valid and stored
+/// * `Err(_)` - If the token is invalid or storage fails
+#[but_api(napi, json::GiteaAuthStatusResponse, invalidates = [ForgeAccounts, ForgeLogin])]
+#[instrument(err(Debug))]
+pub async fn store_gitea_pat(
+    host: String,
+    access_token: Sensitive<String>,
+) -> Result<AuthStatusResponse> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_gitea::store_pat(&host, &access_token, &storage).await
+}
+
+/// Removes stored credentials for a specific Gitea account.
+///
+/// # Arguments
+///
+/// * `account` - Identifier for the Gitea account
+///
+/// # Returns
+///
+/// * `Ok(())` - Always succeeds, even if no token was found
+#[but_api(napi, invalidates = [ForgeAccounts, ForgeLogin])]
+#[instrument(err(Debug))]
+pub fn forget_gitea_account(account: but_gitea::GiteaAccountIdentifier) -> Result<()> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_gitea::forget_gitea_access_token(&account, &storage).ok();
+    Ok(())
+}
+
+/// Removes all stored Gitea credentials.
+///
+/// # Returns
+///
+/// * `Ok(())` - All tokens successfully cleared
+/// * `Err(_)` - If storage cleanup fails
+#[but_api]
+#[instrument(err(Debug))]
+pub fn clear_all_gitea_tokens() -> Result<()> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_gitea::clear_all_gitea_tokens(&storage)
+}
+
+/// Retrieves the authenticated user information for a Gitea account.
+///
+/// # Arguments
+///
+/// * `account` - Identifier for the Gitea account to query
+///
+/// # Returns
+///
+/// * `Ok(Some(AuthenticatedUser))` - User information
+/// * `Ok(None)` - No credentials stored for this account
+/// * `Err(_)` - If the API request fails or credentials are invalid
+#[but_api(napi, json::GiteaAuthenticatedUserSensitive)]
+#[instrument(err(Debug))]
+pub async fn get_gitea_user(
+    account: but_gitea::GiteaAccountIdentifier,
+) -> Result<Option<AuthenticatedUser>> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_gitea::get_gitea_user(&account, &storage).await
+}
+
+/// Lists all Gitea accounts with stored credentials.
+///
+/// # Returns
+///
+/// * `Ok(Vec<GiteaAccountIdentifier>)` - List of all known accounts
+/// * `Err(_)` - If storage access fails
+#[but_api(napi)]
+#[instrument(err(Debug))]
+pub fn list_known_gitea_accounts() -> Result<Vec<but_gitea::GiteaAccountIdentifier>> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_gitea::list_known_gitea_accounts(&storage)
+}
+
+/// Validates stored Gitea credentials.
+///
+/// # Arguments
+///
+/// * `account` - Identifier for the Gitea account to validate
+///
+/// # Returns
+///
+/// * `Ok(CredentialCheckResult)` - Result indicating if credentials are valid
+/// * `Err(_)` - If the validation request fails
+#[but_api]
+#[instrument(err(Debug))]
+pub async fn check_gitea_credentials(
+    account: but_gitea::GiteaAccountIdentifier,
+) -> Result<but_gitea::CredentialCheckResult> {
+    let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
+    but_gitea::check_credentials(&account, &storage).await
+}
@@ -713,6 +713,74 @@ pub async fn list_review_comments(
         .await
 }
 
+/// Import the inline comments on the lines of a review as local comments on the commits of the
+/// workspace branch the review belongs to, so they can be seen and addressed without the forge.
+///
+/// See [`but_comments::import_review_comments`] for how they are anchored.
+#[but_api(napi)]
+#[instrument(err(Debug))]
+pub async fn import_review_comments(
+    ctx: ThreadSafeContext,
+    review_id: usize,
+) -> Result<but_comments::ImportOutcome> {
+    let branch = {
+        let ctx = ctx.clone().into_thread_local();
+        let info = crate::legacy::workspace::head_info(&ctx)?;
+        info.stacks
+            .iter()
+            .flat_map(|stack| &stack.segments)
+            .find(|segment| review_number(segment) == Some(review_id as i64))
+            .and_then(|segment| segment.ref_info.as_ref())
+            .map(|ref_info| ref_info.ref_name.clone())
+            .with_context(|| format!("No branch in the workspace belongs to review #{review_id}"))?
+    };
+    let (storage, forge_repo_info, preferred_forge_user) = forge_endpoint_context(ctx.clone())?;
+    let comments = but_forge::list_inline_review_comments(
+        &preferred_forge_user,
+        &forge_repo_info,
+        review_id,
+        &storage,
+    )
+    .await?;
+    let ctx = ctx.into_thread_local();
+    crate::comments::import_review_comments(
+        &ctx,
+        branch.as_ref(),
+        comments.into_iter().map(review_comment).collect(),
+    )
+}
+
+/// The local form of the inline review `comment`, with an id derived from the forge's so that
+/// importing it again updates it.
+fn review_comment(comment: but_forge::ForgeInlineReviewComment) -> but_comments::ReviewComment {
+    let local_id = |id: i64| format!("review-comment-{id}");
+    let timestamp_ms = |timestamp: Option<&str>| {
+        timestamp
+            .and_then(|timestamp| chrono::DateTime::parse_from_rfc3339(timestamp).ok())
+            .map(|timestamp| timestamp.timestamp_millis())
+    };
+    let created_at_ms = timestamp_ms(comment.created_at.as_deref())
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+    but_comments::ReviewComment {
+        id: local_id(comment.id),
+        in_reply_to: comment.in_reply_to.map(local_id),
+        path: comment.path,
+        side: match comment.side {
+            but_forge::ForgeDiffSide::Old => but_comments::DiffSide::Old,
+            but_forge::ForgeDiffSide::New => but_comments::DiffSide::New,
+        },
+        line_number: comment.line,
+        line_content: comment.line_content,
+        line_before: comment.line_before,
+        author: comment
+            .author
+            .map(|author| author.name.unwrap_or(author.login)),
+        payload: comment.body,
+        created_at_ms,
+        updated_at_ms: timestamp_ms(comment.modified_at.as_deref()).unwrap_or(created_at_ms),
+    }
+}
+
 /// List the individual reactions (with who reacted) on a review itself.
 #[but_api(napi, provides = [ReviewReactions])]
 #[instrument(err(Debug))]
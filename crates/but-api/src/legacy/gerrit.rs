@@ -0,0 +1,253 @@
+use anyhow::{Result, bail};
+use bstr::ByteSlice;
+use but_api_macros::but_api;
+use but_core::{
+    DryRun, RepositoryExt, commit::Headers, ref_metadata::StackId, sync::RepoExclusive,
+};
+use but_ctx::Context;
+use but_gerrit::{ChainLink, ChainLinkState};
+use but_oplog::legacy::{OperationKind, SnapshotDetails};
+use but_rebase::{
+    commit::DateMode,
+    graph_rebase::{Editor, Step},
+};
+use tracing::instrument;
+
+use crate::WorkspaceState;
+
+/// List the review status of all workspace commits that were pushed to Gerrit.
+///
+/// This is the Gerrit counterpart to listing forge reviews, with one entry per pushed change
+/// carrying its state, the `Code-Review` and `Verified` votes, the unresolved comment count
+/// and whether it is submittable. It's empty unless Gerrit mode is enabled for the repository.
+///
+/// With `cache_config` set to `NoCache` (the default), the Gerrit server is queried and
+/// the cache is updated, otherwise the last known status is returned.
+#[but_api(napi, provides = [Reviews])]
+#[instrument(skip(ctx), err(Debug))]
+pub fn list_gerrit_changes(
+    ctx: &Context,
+    cache_config: Option<but_forge::CacheConfig>,
+) -> Result<Vec<but_gerrit::GerritChangeStatus>> {
+    let repo = ctx.repo.get()?;
+    if !repo.git_settings()?.gitbutler_gerrit_mode.unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+    let change_ids = workspace_change_ids(ctx)?;
+    let db = &mut *ctx.db.get_cache_mut()?;
+    match cache_config.unwrap_or_default() {
+        but_forge::CacheConfig::CacheOnly => but_gerrit::change_statuses_from_cache(db, change_ids),
+        but_forge::CacheConfig::CacheWithFallback { max_age_seconds } => {
+            let cached = but_gerrit::change_statuses_from_cache(db, change_ids.clone())?;
+            let now = chrono::Local::now().naive_local();
+            let is_fresh = !cached.is_empty()
+                && cached.iter().all(|status| {
+                    (now - status.last_sync_at).num_seconds() < max_age_seconds as i64
+                });
+            if is_fresh {
+                Ok(cached)
+            } else {
+                but_gerrit::refresh_change_statuses(&repo, db, change_ids)
+            }
+        }
+        but_forge::CacheConfig::NoCache => {
+            but_gerrit::refresh_change_statuses(&repo, db, change_ids)
+        }
+    }
+}
+
+/// The commits of a stack in the workspace along with the Gerrit changes they were pushed as.
+#[derive(Debug, Clone, serde::Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct GerritRelationChain {
+    /// The id of the stack, if it has one.
+    #[cfg_attr(feature = "export-schema", schemars(with = "Option<String>"))]
+    pub stack_id: Option<StackId>,
+    /// The short name of the top-most branch of the stack, if it has one.
+    pub name: Option<String>,
+    /// The commits of the stack from bottom to top, which is the order of the relation chain on Gerrit.
+    pub links: Vec<ChainLink>,
+}
+
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(GerritRelationChain);
+
+/// List the relation chain of each stack in the workspace, mapping every commit to the Gerrit change
+/// it was pushed as and comparing it with the current patchset on the server.
+///
+/// This is how patchsets that others uploaded are detected, which can then be pulled into the local
+/// stack with [`gerrit_pull_patchsets()`]. It's empty unless Gerrit mode is enabled for the repository.
+#[but_api(napi, provides = [Reviews])]
+#[instrument(skip(ctx), err(Debug))]
+pub fn gerrit_relation_chains(ctx: &Context) -> Result<Vec<GerritRelationChain>> {
+    let repo = ctx.repo.get()?;
+    if !repo.git_settings()?.gitbutler_gerrit_mode.unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+    let info = crate::legacy::workspace::head_info(ctx)?;
+    let db = &mut *ctx.db.get_cache_mut()?;
+    info.stacks
+        .iter()
+        .map(|stack| {
+            let commits = stack
+                .segments
+                .iter()
+                .rev()
+                .flat_map(|segment| segment.commits.iter().rev())
+                .map(|commit| but_gerrit::ChainCommit {
+                    commit_id: commit.id,
+                    change_id: commit.change_id().into_owned(),
+                    title: commit
+                        .message
+                        .lines()
+                        .next()
+                        .map(|line| line.to_str_lossy().into_owned())
+                        .unwrap_or_default(),
+                })
+                .collect();
+            Ok(GerritRelationChain {
+                stack_id: stack.id,
+                name: stack
+                    .name()
+                    .map(|name| name.shorten().to_str_lossy().into_owned()),
+                links: but_gerrit::relation_chain(&repo, db, commits)?,
+            })
+        })
+        .collect()
+}
+
+/// Replace the local commits of `change_ids` with the current patchsets that others uploaded for them,
+/// rebasing everything on top. If `change_ids` is empty, all such patchsets in the workspace are pulled.
+///
+/// This acquires exclusive worktree access from `ctx` and records an oplog entry.
+/// See [`gerrit_pull_patchsets_with_perm()`] for details.
+#[but_api(napi, try_from = crate::json::WorkspaceState)]
+#[instrument(skip(ctx), err(Debug))]
+pub fn gerrit_pull_patchsets(
+    ctx: &mut Context,
+    change_ids: Vec<String>,
+    dry_run: DryRun,
+) -> Result<WorkspaceState> {
+    let mut guard = ctx.exclusive_worktree_access();
+    let perm = guard.write_permission();
+    let maybe_oplog_entry = but_oplog::UnmaterializedOplogSnapshot::from_details_with_perm(
+        ctx,
+        SnapshotDetails::new(OperationKind::MergeUpstream),
+        perm.read_permission(),
+        dry_run,
+    );
+    let res = gerrit_pull_patchsets_with_perm(ctx, change_ids, dry_run, perm);
+    if let Some(snapshot) = maybe_oplog_entry
+        && res.is_ok()
+    {
+        snapshot.commit(ctx, perm).ok();
+    }
+    res
+}
+
+/// Pull patchsets like [`gerrit_pull_patchsets()`] under caller-held exclusive repository access,
+/// without creating an oplog entry.
+///
+/// The patchsets are fetched from the push remote, and each replaces its local commit as the change-id
+/// of the local commit is kept, so the commit remains associated with its change.
+/// Unless `dry_run` is enabled, the pulled patchsets are remembered as what the server has,
+/// so the changes count as up-to-date or as needing a push if they had to be rebased.
+pub fn gerrit_pull_patchsets_with_perm(
+    ctx: &mut Context,
+    change_ids: Vec<String>,
+    dry_run: DryRun,
+    perm: &mut RepoExclusive,
+) -> Result<WorkspaceState> {
+    let pullable: Vec<_> = gerrit_relation_chains(ctx)?
+        .into_iter()
+        .flat_map(|chain| chain.links)
+        .filter(|link| link.state == ChainLinkState::UploadedByOthers)
+        .collect();
+    if let Some(unknown) = change_ids
+        .iter()
+        .find(|change_id| !pullable.iter().any(|link| &link.change_id == *change_id))
+    {
+        bail!("Change {unknown} has no patchset uploaded by others that could be pulled");
+    }
+    let to_pull: Vec<_> = pullable
+        .into_iter()
+        .filter(|link| change_ids.is_empty() || change_ids.contains(&link.change_id))
+        .filter_map(|link| {
+            let patchset = link.patchset.clone()?;
+            Some((link, patchset))
+        })
+        .collect();
+    if to_pull.is_empty() {
+        bail!("There are no patchsets uploaded by others to pull");
+    }
+
+    let remote = but_gerrit::push_remote_name(&*ctx.repo.get()?);
+    gitbutler_git::fetch_refspecs_with_askpass(
+        ctx.workdir_or_gitdir()?,
+        &remote.to_str_lossy(),
+        Some(
+            to_pull
+                .iter()
+                .map(|(_, patchset)| patchset.ref_name.clone())
+                .collect(),
+        ),
+        Some("pull gerrit patchsets".into()),
+    )?;
+
+    let mut meta = ctx.meta()?;
+    let (repo, mut ws, mut db) = ctx.workspace_mut_and_db_mut_with_perm(perm)?;
+    let workspace = {
+        let mut editor = Editor::create(&mut ws, &mut meta, &repo, &mut db)?;
+        for (link, patchset) in &to_pull {
+            let (selector, local) = editor.find_selectable_commit(link.commit_id)?;
+            let mut pulled = editor.find_commit(patchset.commit_id)?;
+            let change_id = local.change_id();
+            let pulled_id = if pulled.change_id() == change_id {
+                patchset.commit_id
+            } else {
+                Headers::from_change_id(change_id).set_in_commit(&mut pulled);
+                editor.new_commit(pulled, DateMode::CommitterKeepAuthorKeep)?
+            };
+            editor.replace(selector, Step::new_pick(pulled_id))?;
+        }
+        WorkspaceState::from_successful_rebase(editor.rebase()?, &repo, dry_run)?
+    };
+    if dry_run == DryRun::No {
+        for (link, patchset) in &to_pull {
+            but_gerrit::record_pulled_patchset(&mut db, &link.change_id, patchset.commit_id)?;
+        }
+    }
+    Ok(workspace)
+}
+
+/// Submit the relation chain of the stack with `stack_id`, which submits all of its open changes at once.
+///
+/// This fails unless every open change in the chain is up-to-date with the local commit and submittable,
+/// and returns the state of the top-most change after the submission.
+#[but_api(napi, invalidates = [Reviews])]
+#[instrument(skip(ctx), err(Debug))]
+pub fn gerrit_submit_chain(
+    ctx: &Context,
+    stack_id: StackId,
+) -> Result<but_gerrit::GerritChangeState> {
+    let Some(chain) = gerrit_relation_chains(ctx)?
+        .into_iter()
+        .find(|chain| chain.stack_id == Some(stack_id))
+    else {
+        bail!("Stack {stack_id} is not in the workspace, or Gerrit mode is disabled");
+    };
+    but_gerrit::submit_chain(&*ctx.repo.get()?, &chain.links)
+}
+
+/// The change-ids of all local commits in the workspace, in stack order.
+fn workspace_change_ids(ctx: &Context) -> Result<Vec<but_core::ChangeId>> {
+    let info = crate::legacy::workspace::head_info(ctx)?;
+    Ok(info
+        .stacks
+        .iter()
+        .flat_map(|stack| stack.segments.iter())
+        .flat_map(|segment| segment.commits.iter())
+        .map(|commit| commit.change_id().into_owned())
+        .collect())
+}
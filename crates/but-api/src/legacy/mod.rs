@@ -7,6 +7,7 @@ pub mod askpass;
 pub mod cli;
 pub mod config;
 pub mod forge;
+pub mod gerrit;
 pub mod git;
 pub mod modes;
 pub mod oplog;
@@ -16,13 +16,16 @@
 //! Depending on the snapshot operation kind, there may be a payload (body) with additional details about the operation (e.g. commit message).
 //! Refer to `gitbutler_oplog::entry::Snapshot` and `gitbutler_oplog::entry::SnapshotDetails` for the metadata stored.
 //!
+use std::path::Path;
+
 use anyhow::Result;
 use but_api_macros::but_api;
-use but_core::sync::RepoExclusive;
+use but_core::{DryRun, sync::RepoExclusive};
 use gitbutler_oplog::{
-    OplogExt,
+    ExportOutcome, GcOutcome, ImportOutcome, OplogExt, RetentionPolicy, SnapshotRange,
     entry::{OperationKind, Snapshot, SnapshotDetails},
 };
+use gix::bstr::BString;
 use tracing::instrument;
 
 mod json {
@@ -299,6 +302,54 @@ pub fn create_snapshot(
     Ok(oid)
 }
 
+/// Removes the snapshots that `policy` doesn't keep from the oplog, and rewrites the remaining ones.
+///
+/// - `policy`: Decides which snapshots to keep. The most recent snapshot is always kept.
+/// - `dry_run`: If set, nothing is changed and only the snapshots that would be removed are reported.
+///
+/// Returns what was (or would be) removed, along with the objects that `git gc` may reclaim as a result.
+///
+/// # Errors
+/// Returns an error if the oplog cannot be read or rewritten.
+#[instrument(err(Debug))]
+pub fn gc_snapshots(
+    ctx: &mut but_ctx::Context,
+    policy: RetentionPolicy,
+    dry_run: DryRun,
+) -> Result<GcOutcome> {
+    let mut guard = ctx.exclusive_worktree_access();
+    gitbutler_oplog::gc_snapshots(ctx, &policy, dry_run, guard.write_permission())
+}
+
+/// Writes the snapshots in `range` into a Git bundle at `path`, to be imported into another clone with
+/// [`import_snapshots`].
+///
+/// - `range`: The oldest and newest snapshot to export, defaulting to the whole oplog.
+/// - `path`: Where to write the bundle file.
+///
+/// # Errors
+/// Returns an error if the range doesn't describe a part of the oplog, or if the bundle cannot be written.
+#[instrument(err(Debug))]
+pub fn export_snapshots(
+    ctx: &but_ctx::Context,
+    range: SnapshotRange,
+    path: &Path,
+) -> Result<ExportOutcome> {
+    let guard = ctx.shared_worktree_access();
+    gitbutler_oplog::export_snapshots(ctx, range, path, guard.read_permission())
+}
+
+/// Adds the snapshots from the Git bundle at `path`, as written by [`export_snapshots`], on top of the oplog.
+/// Afterwards they can be restored like any other snapshot.
+///
+/// # Errors
+/// Returns an error if the bundle cannot be read or doesn't contain snapshots.
+#[instrument(err(Debug))]
+pub fn import_snapshots(ctx: &mut but_ctx::Context, path: &Path) -> Result<ImportOutcome> {
+    let mut guard = ctx.exclusive_worktree_access();
+    gitbutler_oplog::import_snapshots(ctx, path, guard.write_permission())
+}
+
 pub use gitbutler_oplog::RestoreKind;
 
 /// Restores the project to a specific snapshot. This operation also creates a new snapshot in the oplog.
@@ -344,6 +395,49 @@ pub fn restore_snapshot_with_kind_with_perm(
     Ok(())
 }
 
+/// Computes the changes that [`restore_snapshot_paths`] would make to the working directory.
+///
+/// - `sha`: The SHA of the snapshot to restore from.
+/// - `paths`: The paths to restore, relative to the root of the worktree. Directories include everything below them.
+///
+/// Returns the difference between the current working directory and the one of the snapshot, limited to `paths`.
+///
+/// # Errors
+/// Returns an error if the snapshot SHA is invalid or if the diff cannot be computed.
+#[instrument(err(Debug))]
+pub fn restore_snapshot_paths_diff(
+    ctx: &but_ctx::Context,
+    sha: gix::ObjectId,
+    paths: &[BString],
+) -> Result<Vec<but_core::TreeChange>> {
+    let _guard = ctx.shared_worktree_access();
+    ctx.restore_paths_diff(sha, paths)
+}
+
+/// Restores only `paths` in the working directory to their state in a specific snapshot.
+/// This operation also creates a new snapshot in the oplog.
+///
+/// - `sha`: The SHA of the snapshot to restore from.
+/// - `paths`: The paths to restore, relative to the root of the worktree. Directories include everything below them.
+///
+/// Returns the SHA of the snapshot that records the state before the restore.
+///
+/// # Errors
+/// Returns an error if the snapshot SHA is invalid, if none of `paths` differs from the snapshot,
+/// or if the files cannot be written.
+///
+/// # Side Effects
+/// Only files within `paths` are changed. The index, commit history, references and branches are left as they are.
+#[instrument(err(Debug))]
+pub fn restore_snapshot_paths(
+    ctx: &mut but_ctx::Context,
+    sha: gix::ObjectId,
+    paths: &[BString],
+) -> Result<gix::ObjectId> {
+    let mut guard = ctx.exclusive_worktree_access();
+    ctx.restore_paths(sha, paths, guard.write_permission())
+}
+
 /// Computes the file tree difference between the the state of the project at a specific snapshot and the current state.
 /// Not all snapshots may have a meaningful file tree difference, in which case the result may be empty.
 /// An example of a snapshot that does have file tree diffs is a `CreateCommit` snapshot where the commit introduced changes to files.
@@ -43,7 +43,8 @@ pub fn head_info(ctx: &but_ctx::Context) -> Result<but_workspace::RefInfo> {
         )?
         .into_workspace()?
     };
-    let gerrit_mode_enabled = repo.git_settings()?.gitbutler_gerrit_mode.unwrap_or(false);
+    let git_settings = repo.git_settings()?;
+    let gerrit_mode_enabled = git_settings.gitbutler_gerrit_mode.unwrap_or(false);
     let db = gerrit_mode_enabled
         .then(|| ctx.db.get_cache())
         .transpose()?;
@@ -59,6 +60,7 @@ pub fn head_info(ctx: &but_ctx::Context) -> Result<but_workspace::RefInfo> {
             traversal: but_graph::init::Options::limited(),
             expensive_commit_info: true,
             gerrit_mode,
+            verify_signatures: git_settings.gitbutler_verify_signatures.unwrap_or(false),
         },
     )?
     .pruned_to_entrypoint();
@@ -486,6 +488,7 @@ pub fn workspace_branch_and_ancestors_push_only(
             traversal: but_graph::init::Options::limited(),
             expensive_commit_info: true,
             gerrit_mode,
+            verify_signatures: false,
         },
     )?;
     let head_info = head_info.pruned_to_entrypoint();
@@ -26,6 +26,12 @@ pub mod gitlab;
 /// Functions for Bitbucket authentication.
 pub mod bitbucket;
 
+/// Functions for Azure DevOps authentication.
+pub mod azure;
+
+/// Functions for Gitea and Forgejo authentication.
+pub mod gitea;
+
 /// Functions that take a branch as input.
 pub mod branch;
 
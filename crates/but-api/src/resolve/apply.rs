@@ -362,6 +362,29 @@ pub(crate) fn apply(
         );
     }
 
+    // Remember how each fully resolved file was resolved before rebasing the
+    // descendants, so any rebase running into the same conflict resolves it
+    // automatically, theirs included.
+    if dry_run == DryRun::No {
+        let resolved_paths: Vec<_> = request
+            .files
+            .iter()
+            .zip(picks_per_file)
+            .filter(|(file, picks)| !picks.is_empty() && picks.len() == file.hunks.len())
+            .map(|(file, _)| file.rela_path.clone())
+            .collect();
+        if !resolved_paths.is_empty()
+            && let Err(err) = but_rebase::rerere::record(
+                &repo,
+                request.commit_id,
+                merged_tree_id,
+                Some(resolved_paths.as_slice()),
+            )
+        {
+            tracing::warn!("Could not record conflict resolutions: {err:#}");
+        }
+    }
+
     let mut editor = Editor::create(&mut ws, &mut meta, &repo, &mut db)?;
     let (target_selector, mut commit) = editor.find_selectable_commit(request.commit_id)?;
     // Fully resolving in favor of the base can leave the commit with no
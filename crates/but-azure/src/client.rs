@@ -0,0 +1,1227 @@
+use anyhow::{Result, bail};
+use base64::Engine as _;
+use but_secret::Sensitive;
+use reqwest::header::{ACCEPT, AUTHORIZATION, HeaderMap, HeaderValue, USER_AGENT};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::time::Duration;
+
+use crate::AzureRepoId;
+
+const AZURE_DEVOPS_BASE_URL: &str = "https://dev.azure.com";
+const AZURE_API_VERSION: &str = "7.1";
+const AZURE_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// The page size used for `$top`/`$skip` pagination.
+const PAGE_SIZE: usize = 100;
+/// Safety cap on pagination so a misbehaving server can't make us loop forever.
+const MAX_PAGES: usize = 25;
+
+/// An HTTP error with a status code, returned when the API responds with a non-success status.
+///
+/// This can be downcasted from `anyhow::Error` to distinguish auth failures (401/403) from other errors.
+#[derive(Debug, thiserror::Error)]
+#[error("HTTP {status}")]
+pub struct HttpStatusError {
+    pub status: reqwest::StatusCode,
+}
+
+pub struct AzureClient {
+    pub(crate) client: reqwest::Client,
+    /// The organization URL, e.g. `https://dev.azure.com/contoso`.
+    pub(crate) base_url: String,
+}
+
+impl AzureClient {
+    /// Build a client for `organization`, authenticating with a personal access token.
+    ///
+    /// Azure DevOps accepts PATs as the password of HTTP Basic auth with an empty username.
+    pub fn new(organization: &str, access_token: &Sensitive<String>) -> Result<Self> {
+        let base_url = format!(
+            "{AZURE_DEVOPS_BASE_URL}/{}",
+            urlencoding::encode(organization)
+        );
+        Self::new_with_base_url(access_token, &base_url)
+    }
+
+    /// Build a client against an explicit organization URL, e.g. for Azure DevOps Server
+    /// collections or a local mock server.
+    pub fn new_with_base_url(access_token: &Sensitive<String>, base_url: &str) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("gb-azure-integration"));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        let basic =
+            base64::engine::general_purpose::STANDARD.encode(format!(":{}", access_token.0));
+        let mut auth_value = HeaderValue::from_str(&format!("Basic {basic}"))?;
+        auth_value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, auth_value);
+
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(AZURE_REQUEST_TIMEOUT)
+            .build()?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    pub fn from_storage(
+        storage: &but_forge_storage::Controller,
+        preferred_account: Option<&crate::AzureAccountIdentifier>,
+        organization: &str,
+    ) -> Result<Self> {
+        let account_id = resolve_account(preferred_account, organization, storage)?;
+        if let Some(access_token) = crate::token::get_az_access_token(&account_id, storage)? {
+            account_id.client(&access_token)
+        } else {
+            Err(anyhow::anyhow!(
+                "No Azure DevOps access token found for account '{account_id}'.\nRun 'but config forge auth' to re-authenticate."
+            ))
+        }
+    }
+
+    pub async fn get_authenticated(&self) -> Result<AuthenticatedUser> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ConnectionData {
+            authenticated_user: ConnectionUser,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ConnectionUser {
+            id: String,
+            #[serde(default)]
+            provider_display_name: Option<String>,
+            #[serde(default)]
+            custom_display_name: Option<String>,
+            #[serde(default)]
+            properties: ConnectionUserProperties,
+        }
+        #[derive(Deserialize, Default)]
+        struct ConnectionUserProperties {
+            #[serde(default, rename = "Account")]
+            account: Option<PropertyValue>,
+        }
+        #[derive(Deserialize)]
+        struct PropertyValue {
+            #[serde(rename = "$value")]
+            value: String,
+        }
+
+        let url = format!(
+            "{}/_apis/connectionData?api-version={AZURE_API_VERSION}-preview",
+            self.base_url
+        );
+        let data: ConnectionData = self.get_json(&url).await?;
+        let user = data.authenticated_user;
+        // Anonymous access yields an all-zero identity rather than an auth error.
+        if user.id.chars().all(|c| c == '0' || c == '-') {
+            return Err(HttpStatusError {
+                status: reqwest::StatusCode::UNAUTHORIZED,
+            }
+            .into());
+        }
+        let name = user.custom_display_name.or(user.provider_display_name);
+        let username = user
+            .properties
+            .account
+            .map(|account| account.value)
+            .or_else(|| name.clone())
+            .unwrap_or_else(|| user.id.clone());
+        Ok(AuthenticatedUser {
+            username,
+            name,
+            id: user.id,
+        })
+    }
+
+    /// GET `url` and deserialize the JSON body, turning non-success statuses into [`HttpStatusError`].
+    ///
+    /// Azure DevOps answers requests with an invalid PAT with `203 Non-Authoritative Information`
+    /// and an HTML sign-in page instead of a `401`, so that is reported as unauthorized too.
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self.client.get(url).send().await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NON_AUTHORITATIVE_INFORMATION {
+            return Err(HttpStatusError {
+                status: reqwest::StatusCode::UNAUTHORIZED,
+            }
+            .into());
+        }
+        if !status.is_success() {
+            return Err(HttpStatusError { status }.into());
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Fetch every entry of a `$top`/`$skip` paginated collection.
+    ///
+    /// `initial_url` must already carry a query string. Errors out if the `MAX_PAGES`
+    /// safety cap is hit rather than silently truncating the result.
+    async fn get_paginated<T: DeserializeOwned>(&self, initial_url: &str) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        for page in 0..MAX_PAGES {
+            let url = format!("{initial_url}&$top={PAGE_SIZE}&$skip={}", page * PAGE_SIZE);
+            let page: Collection<T> = self.get_json(&url).await?;
+            let is_last = page.value.len() < PAGE_SIZE;
+            items.extend(page.value);
+            if is_last {
+                return Ok(items);
+            }
+        }
+        bail!("Azure DevOps pagination exceeded the {MAX_PAGES}-page safety cap")
+    }
+
+    fn repo_url(&self, repo: &AzureRepoId) -> String {
+        format!(
+            "{}/{}/_apis/git/repositories/{}",
+            self.base_url,
+            urlencoding::encode(&repo.project),
+            urlencoding::encode(&repo.repository),
+        )
+    }
+
+    /// The browsable URL of `repo`, used when a response lacks its own `webUrl`.
+    fn repo_web_url(&self, repo: &AzureRepoId) -> String {
+        format!(
+            "{}/{}/_git/{}",
+            self.base_url,
+            urlencoding::encode(&repo.project),
+            urlencoding::encode(&repo.repository),
+        )
+    }
+
+    fn pr_from_api(&self, repo: &AzureRepoId, pr: AzureApiPullRequest) -> AzurePullRequest {
+        AzurePullRequest::from_api(pr, &self.repo_web_url(repo))
+    }
+
+    pub async fn list_open_prs(&self, repo: &AzureRepoId) -> Result<Vec<AzurePullRequest>> {
+        let url = format!(
+            "{}/pullrequests?searchCriteria.status=active&api-version={AZURE_API_VERSION}",
+            self.repo_url(repo)
+        );
+        let prs: Vec<AzureApiPullRequest> = self.get_paginated(&url).await?;
+        Ok(prs
+            .into_iter()
+            .map(|pr| self.pr_from_api(repo, pr))
+            .collect())
+    }
+
+    pub async fn list_prs_for_target(
+        &self,
+        repo: &AzureRepoId,
+        target_branch: &str,
+    ) -> Result<Vec<AzurePullRequest>> {
+        let url = format!(
+            "{}/pullrequests?searchCriteria.status=all&searchCriteria.targetRefName={}&api-version={AZURE_API_VERSION}",
+            self.repo_url(repo),
+            urlencoding::encode(&branch_ref(target_branch)),
+        );
+        let prs: Vec<AzureApiPullRequest> = self.get_paginated(&url).await?;
+        Ok(prs
+            .into_iter()
+            .map(|pr| self.pr_from_api(repo, pr))
+            .collect())
+    }
+
+    pub async fn get_pull_request(&self, repo: &AzureRepoId, id: i64) -> Result<AzurePullRequest> {
+        let url = format!(
+            "{}/pullrequests/{id}?api-version={AZURE_API_VERSION}",
+            self.repo_url(repo)
+        );
+        let pr: AzureApiPullRequest = self.get_json(&url).await?;
+        Ok(self.pr_from_api(repo, pr))
+    }
+
+    pub async fn create_pull_request(
+        &self,
+        params: &CreatePullRequestParams<'_>,
+    ) -> Result<AzurePullRequest> {
+        let url = format!(
+            "{}/pullrequests?api-version={AZURE_API_VERSION}",
+            self.repo_url(params.repo)
+        );
+        let body = CreatePullRequestBody {
+            source_ref_name: branch_ref(params.source_branch),
+            target_ref_name: branch_ref(params.target_branch),
+            title: params.title,
+            description: truncate_description(params.body),
+            is_draft: params.draft,
+        };
+        let response = self.client.post(&url).json(&body).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(
+                anyhow::Error::new(HttpStatusError { status }).context(format!(
+                    "Failed to create pull request: {status} - {error_text}"
+                )),
+            );
+        }
+        let pr: AzureApiPullRequest = response.json().await?;
+        Ok(self.pr_from_api(params.repo, pr))
+    }
+
+    /// Send a partial update of a pull request. Unlike some forges, Azure DevOps' PATCH
+    /// only touches the fields present in the body.
+    async fn patch_pull_request(
+        &self,
+        repo: &AzureRepoId,
+        id: i64,
+        body: &UpdatePullRequestBody<'_>,
+    ) -> Result<AzurePullRequest> {
+        let url = format!(
+            "{}/pullrequests/{id}?api-version={AZURE_API_VERSION}",
+            self.repo_url(repo)
+        );
+        let response = self.client.patch(&url).json(body).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(
+                anyhow::Error::new(HttpStatusError { status }).context(format!(
+                    "Failed to update pull request: {status} - {error_text}"
+                )),
+            );
+        }
+        let pr: AzureApiPullRequest = response.json().await?;
+        Ok(self.pr_from_api(repo, pr))
+    }
+
+    pub async fn update_pull_request(
+        &self,
+        params: &UpdatePullRequestParams<'_>,
+    ) -> Result<AzurePullRequest> {
+        let body = UpdatePullRequestBody {
+            title: params.title,
+            description: params.description.map(truncate_description),
+            target_ref_name: params.target_branch.map(branch_ref),
+            status: params.status.map(PullRequestStatus::as_str),
+            ..Default::default()
+        };
+        self.patch_pull_request(params.repo, params.id, &body).await
+    }
+
+    pub async fn set_pull_request_draft_state(
+        &self,
+        repo: &AzureRepoId,
+        id: i64,
+        is_draft: bool,
+    ) -> Result<()> {
+        let body = UpdatePullRequestBody {
+            is_draft: Some(is_draft),
+            ..Default::default()
+        };
+        self.patch_pull_request(repo, id, &body).await?;
+        Ok(())
+    }
+
+    /// Complete (merge) a pull request.
+    ///
+    /// Azure DevOps requires the source commit the caller believes is current, so the
+    /// pull request is fetched first; a concurrent push makes the completion fail.
+    pub async fn merge_pull_request(&self, params: &MergePullRequestParams<'_>) -> Result<()> {
+        let pr = self.get_pull_request(params.repo, params.id).await?;
+        if pr.source_commit_id.is_empty() {
+            bail!(
+                "Pull request {} has no source commit to complete",
+                params.id
+            );
+        }
+        let body = UpdatePullRequestBody {
+            status: Some(PullRequestStatus::Completed.as_str()),
+            last_merge_source_commit: Some(CommitRefBody {
+                commit_id: &pr.source_commit_id,
+            }),
+            completion_options: Some(CompletionOptionsBody {
+                merge_strategy: params.strategy.as_str(),
+                delete_source_branch: false,
+            }),
+            ..Default::default()
+        };
+        self.patch_pull_request(params.repo, params.id, &body)
+            .await?;
+        Ok(())
+    }
+
+    /// List all comment threads of a pull request, including system-generated ones.
+    pub async fn list_threads(&self, repo: &AzureRepoId, id: i64) -> Result<Vec<AzureThread>> {
+        let url = format!(
+            "{}/pullrequests/{id}/threads?api-version={AZURE_API_VERSION}",
+            self.repo_url(repo)
+        );
+        let threads: Collection<AzureApiThread> = self.get_json(&url).await?;
+        Ok(threads
+            .value
+            .into_iter()
+            .filter(|thread| !thread.is_deleted)
+            .map(AzureThread::from)
+            .collect())
+    }
+
+    /// Start a new comment thread on a pull request.
+    ///
+    /// Without `file_position` the thread is a general comment on the pull request;
+    /// otherwise it is anchored to a line on the right (new) side of the diff.
+    pub async fn create_thread(
+        &self,
+        repo: &AzureRepoId,
+        id: i64,
+        content: &str,
+        file_position: Option<(&str, u32)>,
+    ) -> Result<AzureThread> {
+        let url = format!(
+            "{}/pullrequests/{id}/threads?api-version={AZURE_API_VERSION}",
+            self.repo_url(repo)
+        );
+        let body = CreateThreadBody {
+            comments: vec![CommentBody {
+                parent_comment_id: 0,
+                content,
+                comment_type: COMMENT_TYPE_TEXT,
+            }],
+            status: ThreadStatus::Active.as_str(),
+            thread_context: file_position.map(|(file_path, line)| ThreadContextBody {
+                file_path,
+                right_file_start: FilePositionBody { line, offset: 1 },
+                right_file_end: FilePositionBody { line, offset: 1 },
+            }),
+        };
+        let response = self.client.post(&url).json(&body).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            bail!("Failed to create comment thread: {status} - {error_text}");
+        }
+        let thread: AzureApiThread = response.json().await?;
+        Ok(thread.into())
+    }
+
+    /// Reply to an existing comment thread.
+    pub async fn reply_to_thread(
+        &self,
+        repo: &AzureRepoId,
+        id: i64,
+        thread_id: i64,
+        parent_comment_id: i64,
+        content: &str,
+    ) -> Result<AzureComment> {
+        let url = format!(
+            "{}/pullrequests/{id}/threads/{thread_id}/comments?api-version={AZURE_API_VERSION}",
+            self.repo_url(repo)
+        );
+        let body = CommentBody {
+            parent_comment_id,
+            content,
+            comment_type: COMMENT_TYPE_TEXT,
+        };
+        let response = self.client.post(&url).json(&body).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            bail!("Failed to reply to comment thread: {status} - {error_text}");
+        }
+        let comment: AzureApiComment = response.json().await?;
+        Ok(comment.into())
+    }
+
+    /// Change the status of a comment thread, e.g. to resolve it.
+    pub async fn set_thread_status(
+        &self,
+        repo: &AzureRepoId,
+        id: i64,
+        thread_id: i64,
+        status: ThreadStatus,
+    ) -> Result<()> {
+        #[derive(Serialize)]
+        struct Body<'a> {
+            status: &'a str,
+        }
+        let url = format!(
+            "{}/pullrequests/{id}/threads/{thread_id}?api-version={AZURE_API_VERSION}",
+            self.repo_url(repo)
+        );
+        let response = self
+            .client
+            .patch(&url)
+            .json(&Body {
+                status: status.as_str(),
+            })
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            bail!("Failed to update comment thread: {status} - {error_text}");
+        }
+        Ok(())
+    }
+
+    pub async fn fetch_repo(&self, repo: &AzureRepoId) -> Result<AzureRepo> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Raw {
+            #[serde(default)]
+            is_fork: bool,
+            #[serde(default)]
+            default_branch: Option<String>,
+        }
+        let url = format!("{}?api-version={AZURE_API_VERSION}", self.repo_url(repo));
+        let raw: Raw = self.get_json(&url).await?;
+        Ok(AzureRepo {
+            is_fork: raw.is_fork,
+            default_branch: raw
+                .default_branch
+                .map(|name| short_branch_name(&name).to_owned()),
+        })
+    }
+}
+
+pub(crate) fn resolve_account(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    organization: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::AzureAccountIdentifier, anyhow::Error> {
+    let known_accounts = crate::token::list_known_azure_accounts(storage)?;
+    if let Some(account) = preferred_account {
+        if known_accounts.contains(account) {
+            return Ok(account.to_owned());
+        }
+        bail!(
+            "Preferred Azure DevOps account '{account}' has not authenticated yet.\nRun 'but config forge auth' to authenticate, or choose another account."
+        );
+    }
+    // PATs are issued per organization, so prefer an account that can actually see the repository.
+    known_accounts
+        .iter()
+        .find(|account| account.organization().eq_ignore_ascii_case(organization))
+        .or_else(|| known_accounts.first())
+        .cloned()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No authenticated Azure DevOps users found.\nRun 'but config forge auth' to authenticate with Azure DevOps."
+            )
+        })
+}
+
+/// Azure DevOps rejects pull request descriptions longer than this many characters.
+const MAX_DESCRIPTION_CHARS: usize = 4000;
+
+fn truncate_description(description: &str) -> &str {
+    match description.char_indices().nth(MAX_DESCRIPTION_CHARS) {
+        Some((end, _)) => &description[..end],
+        None => description,
+    }
+}
+
+/// Azure DevOps addresses branches by their full ref name.
+fn branch_ref(branch: &str) -> String {
+    if branch.starts_with("refs/") {
+        branch.to_owned()
+    } else {
+        format!("refs/heads/{branch}")
+    }
+}
+
+fn short_branch_name(ref_name: &str) -> &str {
+    ref_name.strip_prefix("refs/heads/").unwrap_or(ref_name)
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    /// The unique name of the user, usually their email address.
+    pub username: String,
+    pub name: Option<String>,
+    /// The identity GUID.
+    pub id: String,
+}
+
+/// Wrapper of Azure DevOps list responses.
+#[derive(Debug, Deserialize)]
+struct Collection<T> {
+    #[serde(default = "Vec::new")]
+    value: Vec<T>,
+}
+
+/// An identity reference as embedded in pull requests, reviewers and comments.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AzureApiIdentity {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    unique_name: Option<String>,
+    #[serde(default)]
+    image_url: Option<String>,
+    /// Groups (e.g. a team requested as reviewer) are containers.
+    #[serde(default)]
+    is_container: bool,
+    /// Only present on reviewers: 10 approved, 5 approved with suggestions,
+    /// 0 no vote, -5 waiting for author, -10 rejected.
+    #[serde(default)]
+    vote: i64,
+}
+
+/// An Azure DevOps identity mapped to the shape `but_forge` expects for review participants.
+#[derive(Debug)]
+pub struct AzureUser {
+    /// Azure identities are GUIDs; this is a stable hash of it so consumers that
+    /// key by numeric id don't collapse distinct users.
+    pub id: i64,
+    pub username: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub avatar_url: Option<String>,
+    pub is_bot: bool,
+    /// The reviewer vote, `0` for non-reviewers.
+    pub vote: i64,
+}
+
+impl From<AzureApiIdentity> for AzureUser {
+    fn from(identity: AzureApiIdentity) -> Self {
+        let username = identity
+            .unique_name
+            .clone()
+            .or_else(|| identity.display_name.clone())
+            .or_else(|| identity.id.clone())
+            .unwrap_or_default();
+        let id_seed = identity.id.clone().unwrap_or_else(|| username.clone());
+        let email = identity
+            .unique_name
+            .filter(|unique_name| unique_name.contains('@'));
+        AzureUser {
+            id: crate::stable_id_hash(&id_seed),
+            username,
+            name: identity.display_name,
+            email,
+            avatar_url: identity.image_url,
+            // Build service accounts carry this marker in their unique name.
+            is_bot: identity.is_container || id_seed.starts_with("Build\\"),
+            vote: identity.vote,
+        }
+    }
+}
+
+/// The status of a pull request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullRequestStatus {
+    Active,
+    Abandoned,
+    Completed,
+}
+
+impl PullRequestStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            PullRequestStatus::Active => "active",
+            PullRequestStatus::Abandoned => "abandoned",
+            PullRequestStatus::Completed => "completed",
+        }
+    }
+
+    fn from_api(status: &str) -> Self {
+        match status {
+            "abandoned" => PullRequestStatus::Abandoned,
+            "completed" => PullRequestStatus::Completed,
+            _ => PullRequestStatus::Active,
+        }
+    }
+}
+
+/// An Azure DevOps pull request, normalised to the fields `but_forge` needs.
+#[derive(Debug)]
+pub struct AzurePullRequest {
+    pub html_url: String,
+    pub id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: PullRequestStatus,
+    pub draft: bool,
+    pub source_branch: String,
+    pub target_branch: String,
+    /// The head commit of the source branch the server last merged.
+    pub source_commit_id: String,
+    /// The merge commit on the target branch, only for completed pull requests.
+    pub merge_commit_id: Option<String>,
+    pub created_at: Option<String>,
+    pub closed_at: Option<String>,
+    /// The server-side merge simulation result, e.g. `succeeded` or `conflicts`.
+    pub merge_status: Option<String>,
+    pub labels: Vec<String>,
+    pub author: Option<AzureUser>,
+    pub reviewers: Vec<AzureUser>,
+    pub head_repo_is_fork: bool,
+}
+
+impl AzurePullRequest {
+    pub fn is_open(&self) -> bool {
+        self.status == PullRequestStatus::Active
+    }
+
+    pub fn merged_at(&self) -> Option<String> {
+        (self.status == PullRequestStatus::Completed)
+            .then(|| self.closed_at.clone())
+            .flatten()
+    }
+
+    pub fn abandoned_at(&self) -> Option<String> {
+        (self.status == PullRequestStatus::Abandoned)
+            .then(|| self.closed_at.clone())
+            .flatten()
+    }
+
+    fn from_api(pr: AzureApiPullRequest, fallback_repo_web_url: &str) -> Self {
+        let status = PullRequestStatus::from_api(&pr.status);
+        let repo_web_url = pr
+            .repository
+            .and_then(|repo| repo.web_url)
+            .unwrap_or_else(|| fallback_repo_web_url.to_owned());
+        AzurePullRequest {
+            html_url: format!("{repo_web_url}/pullrequest/{}", pr.pull_request_id),
+            id: pr.pull_request_id,
+            title: pr.title,
+            description: pr.description,
+            status,
+            draft: pr.is_draft,
+            source_branch: short_branch_name(&pr.source_ref_name).to_owned(),
+            target_branch: short_branch_name(&pr.target_ref_name).to_owned(),
+            source_commit_id: pr
+                .last_merge_source_commit
+                .map(|commit| commit.commit_id)
+                .unwrap_or_default(),
+            merge_commit_id: (status == PullRequestStatus::Completed)
+                .then_some(pr.last_merge_commit.map(|commit| commit.commit_id))
+                .flatten(),
+            created_at: pr.creation_date,
+            closed_at: pr.closed_date,
+            merge_status: pr.merge_status,
+            labels: pr
+                .labels
+                .into_iter()
+                .filter(|label| label.active)
+                .map(|label| label.name)
+                .collect(),
+            author: pr.created_by.map(Into::into),
+            reviewers: pr.reviewers.into_iter().map(Into::into).collect(),
+            head_repo_is_fork: pr.fork_source.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureApiPullRequest {
+    pull_request_id: i64,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    status: String,
+    #[serde(default)]
+    is_draft: bool,
+    source_ref_name: String,
+    target_ref_name: String,
+    #[serde(default)]
+    creation_date: Option<String>,
+    #[serde(default)]
+    closed_date: Option<String>,
+    #[serde(default)]
+    merge_status: Option<String>,
+    #[serde(default)]
+    created_by: Option<AzureApiIdentity>,
+    #[serde(default)]
+    reviewers: Vec<AzureApiIdentity>,
+    #[serde(default)]
+    labels: Vec<AzureApiLabel>,
+    #[serde(default)]
+    last_merge_source_commit: Option<AzureApiCommitRef>,
+    #[serde(default)]
+    last_merge_commit: Option<AzureApiCommitRef>,
+    #[serde(default)]
+    repository: Option<AzureApiRepositoryRef>,
+    /// Present only when the source branch lives in a fork.
+    #[serde(default)]
+    fork_source: Option<serde::de::IgnoredAny>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureApiCommitRef {
+    commit_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureApiRepositoryRef {
+    #[serde(default)]
+    web_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AzureApiLabel {
+    name: String,
+    #[serde(default = "default_true")]
+    active: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Repository metadata used to populate `but_forge`'s `RepoInfo`.
+#[derive(Debug)]
+pub struct AzureRepo {
+    pub is_fork: bool,
+    pub default_branch: Option<String>,
+}
+
+/// The status of a pull request comment thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadStatus {
+    Active,
+    Pending,
+    Fixed,
+    WontFix,
+    ByDesign,
+    Closed,
+    Unknown,
+}
+
+impl ThreadStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            ThreadStatus::Active => "active",
+            ThreadStatus::Pending => "pending",
+            ThreadStatus::Fixed => "fixed",
+            ThreadStatus::WontFix => "wontFix",
+            ThreadStatus::ByDesign => "byDesign",
+            ThreadStatus::Closed => "closed",
+            ThreadStatus::Unknown => "unknown",
+        }
+    }
+
+    fn from_api(status: Option<&str>) -> Self {
+        match status {
+            Some("active") => ThreadStatus::Active,
+            Some("pending") => ThreadStatus::Pending,
+            Some("fixed") => ThreadStatus::Fixed,
+            Some("wontFix") => ThreadStatus::WontFix,
+            Some("byDesign") => ThreadStatus::ByDesign,
+            Some("closed") => ThreadStatus::Closed,
+            _ => ThreadStatus::Unknown,
+        }
+    }
+
+    /// Whether the thread still needs attention.
+    pub fn is_unresolved(self) -> bool {
+        matches!(self, ThreadStatus::Active | ThreadStatus::Pending)
+    }
+}
+
+/// A comment thread on a pull request.
+#[derive(Debug)]
+pub struct AzureThread {
+    pub id: i64,
+    pub status: ThreadStatus,
+    /// The file the thread is anchored to, `None` for general comments.
+    pub file_path: Option<String>,
+    /// The first line on the right (new) side of the diff the thread is anchored to.
+    pub line: Option<u32>,
+    pub comments: Vec<AzureComment>,
+}
+
+impl AzureThread {
+    /// System threads record events like pushes and votes rather than conversation.
+    pub fn is_system(&self) -> bool {
+        !self.comments.is_empty() && self.comments.iter().all(|comment| comment.is_system)
+    }
+}
+
+/// A single comment within a thread.
+#[derive(Debug)]
+pub struct AzureComment {
+    pub id: i64,
+    pub parent_comment_id: i64,
+    pub content: String,
+    pub author: Option<AzureUser>,
+    pub published_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub is_system: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureApiThread {
+    id: i64,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    thread_context: Option<AzureApiThreadContext>,
+    #[serde(default)]
+    comments: Vec<AzureApiComment>,
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureApiThreadContext {
+    #[serde(default)]
+    file_path: Option<String>,
+    #[serde(default)]
+    right_file_start: Option<AzureApiFilePosition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AzureApiFilePosition {
+    line: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureApiComment {
+    id: i64,
+    #[serde(default)]
+    parent_comment_id: i64,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    author: Option<AzureApiIdentity>,
+    #[serde(default)]
+    published_date: Option<String>,
+    #[serde(default)]
+    last_updated_date: Option<String>,
+    #[serde(default)]
+    comment_type: Option<String>,
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+impl From<AzureApiComment> for AzureComment {
+    fn from(comment: AzureApiComment) -> Self {
+        AzureComment {
+            id: comment.id,
+            parent_comment_id: comment.parent_comment_id,
+            content: comment.content.unwrap_or_default(),
+            author: comment.author.map(Into::into),
+            published_at: comment.published_date,
+            updated_at: comment.last_updated_date,
+            is_system: comment.comment_type.as_deref() == Some("system"),
+        }
+    }
+}
+
+impl From<AzureApiThread> for AzureThread {
+    fn from(thread: AzureApiThread) -> Self {
+        let (file_path, line) = match thread.thread_context {
+            Some(context) => (
+                context.file_path,
+                context.right_file_start.map(|position| position.line),
+            ),
+            None => (None, None),
+        };
+        AzureThread {
+            id: thread.id,
+            status: ThreadStatus::from_api(thread.status.as_deref()),
+            file_path,
+            line,
+            comments: thread
+                .comments
+                .into_iter()
+                .filter(|comment| !comment.is_deleted)
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+pub struct CreatePullRequestParams<'a> {
+    pub repo: &'a AzureRepoId,
+    pub title: &'a str,
+    pub body: &'a str,
+    pub source_branch: &'a str,
+    pub target_branch: &'a str,
+    pub draft: bool,
+}
+
+pub struct UpdatePullRequestParams<'a> {
+    pub repo: &'a AzureRepoId,
+    pub id: i64,
+    pub title: Option<&'a str>,
+    pub description: Option<&'a str>,
+    pub target_branch: Option<&'a str>,
+    pub status: Option<PullRequestStatus>,
+}
+
+pub struct MergePullRequestParams<'a> {
+    pub repo: &'a AzureRepoId,
+    pub id: i64,
+    pub strategy: MergeStrategy,
+}
+
+/// Azure DevOps pull request completion strategies.
+#[derive(Debug, Clone, Copy)]
+pub enum MergeStrategy {
+    NoFastForward,
+    Squash,
+    Rebase,
+    RebaseMerge,
+}
+
+impl MergeStrategy {
+    fn as_str(self) -> &'static str {
+        match self {
+            MergeStrategy::NoFastForward => "noFastForward",
+            MergeStrategy::Squash => "squash",
+            MergeStrategy::Rebase => "rebase",
+            MergeStrategy::RebaseMerge => "rebaseMerge",
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreatePullRequestBody<'a> {
+    source_ref_name: String,
+    target_ref_name: String,
+    title: &'a str,
+    description: &'a str,
+    is_draft: bool,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct UpdatePullRequestBody<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_ref_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_draft: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_merge_source_commit: Option<CommitRefBody<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completion_options: Option<CompletionOptionsBody>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommitRefBody<'a> {
+    commit_id: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletionOptionsBody {
+    merge_strategy: &'static str,
+    delete_source_branch: bool,
+}
+
+/// `CommentType.text` in the REST API.
+const COMMENT_TYPE_TEXT: u8 = 1;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentBody<'a> {
+    parent_comment_id: i64,
+    content: &'a str,
+    comment_type: u8,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateThreadBody<'a> {
+    comments: Vec<CommentBody<'a>>,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread_context: Option<ThreadContextBody<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadContextBody<'a> {
+    file_path: &'a str,
+    right_file_start: FilePositionBody,
+    right_file_end: FilePositionBody,
+}
+
+#[derive(Serialize)]
+struct FilePositionBody {
+    line: u32,
+    offset: u32,
+}
+
+#[cfg(test)]
+mod mock_tests;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_pr(json: &str) -> AzurePullRequest {
+        let api: AzureApiPullRequest = serde_json::from_str(json).unwrap();
+        AzurePullRequest::from_api(api, "https://dev.azure.com/org/proj/_git/repo")
+    }
+
+    #[test]
+    fn parses_active_pull_request_json() {
+        let pr = api_pr(
+            r#"{
+                "pullRequestId": 42,
+                "title": "Add feature",
+                "description": "Body text",
+                "status": "active",
+                "isDraft": true,
+                "sourceRefName": "refs/heads/feature/login",
+                "targetRefName": "refs/heads/main",
+                "creationDate": "2026-01-01T00:00:00Z",
+                "mergeStatus": "succeeded",
+                "createdBy": { "id": "a1", "displayName": "Alice", "uniqueName": "alice@example.com" },
+                "reviewers": [ { "id": "b1", "displayName": "Bob", "uniqueName": "bob@example.com", "vote": 10 } ],
+                "labels": [ { "name": "bug", "active": true }, { "name": "old", "active": false } ],
+                "lastMergeSourceCommit": { "commitId": "deadbeef" },
+                "repository": { "webUrl": "https://dev.azure.com/org/proj/_git/repo" }
+            }"#,
+        );
+
+        assert_eq!(pr.id, 42);
+        assert!(pr.draft);
+        assert!(pr.is_open());
+        assert_eq!(pr.source_branch, "feature/login");
+        assert_eq!(pr.target_branch, "main");
+        assert_eq!(pr.source_commit_id, "deadbeef");
+        assert_eq!(
+            pr.html_url,
+            "https://dev.azure.com/org/proj/_git/repo/pullrequest/42"
+        );
+        assert_eq!(
+            pr.labels,
+            vec!["bug".to_owned()],
+            "inactive labels are dropped"
+        );
+        let author = pr.author.unwrap();
+        assert_eq!(author.username, "alice@example.com");
+        assert_eq!(author.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(pr.reviewers[0].vote, 10);
+        assert_eq!(pr.merged_at(), None);
+        assert!(!pr.head_repo_is_fork);
+    }
+
+    #[test]
+    fn completed_pull_request_reports_merge_commit_and_time() {
+        let pr = api_pr(
+            r#"{
+                "pullRequestId": 7,
+                "title": "t",
+                "status": "completed",
+                "sourceRefName": "refs/heads/feature",
+                "targetRefName": "refs/heads/main",
+                "closedDate": "2026-01-03T00:00:00Z",
+                "lastMergeSourceCommit": { "commitId": "aaaa" },
+                "lastMergeCommit": { "commitId": "bbbb" },
+                "forkSource": { "repository": { "id": "x" } }
+            }"#,
+        );
+        assert!(!pr.is_open());
+        assert_eq!(pr.merged_at().as_deref(), Some("2026-01-03T00:00:00Z"));
+        assert_eq!(pr.abandoned_at(), None);
+        assert_eq!(pr.merge_commit_id.as_deref(), Some("bbbb"));
+        assert!(pr.head_repo_is_fork);
+        assert_eq!(
+            pr.html_url, "https://dev.azure.com/org/proj/_git/repo/pullrequest/7",
+            "the repository URL falls back to the one derived from the remote"
+        );
+    }
+
+    #[test]
+    fn active_pull_request_ignores_speculative_merge_commit() {
+        let pr = api_pr(
+            r#"{
+                "pullRequestId": 8,
+                "title": "t",
+                "status": "abandoned",
+                "sourceRefName": "refs/heads/feature",
+                "targetRefName": "refs/heads/main",
+                "closedDate": "2026-01-03T00:00:00Z",
+                "lastMergeCommit": { "commitId": "bbbb" }
+            }"#,
+        );
+        assert_eq!(pr.merge_commit_id, None);
+        assert_eq!(pr.abandoned_at().as_deref(), Some("2026-01-03T00:00:00Z"));
+        assert_eq!(pr.merged_at(), None);
+    }
+
+    #[test]
+    fn threads_distinguish_system_and_file_comments() {
+        let thread: AzureThread = serde_json::from_str::<AzureApiThread>(
+            r#"{
+                "id": 3,
+                "status": "active",
+                "threadContext": { "filePath": "/src/lib.rs", "rightFileStart": { "line": 12, "offset": 1 } },
+                "comments": [
+                    { "id": 1, "parentCommentId": 0, "content": "Why?", "commentType": "text" },
+                    { "id": 2, "parentCommentId": 1, "content": "gone", "commentType": "text", "isDeleted": true }
+                ]
+            }"#,
+        )
+        .unwrap()
+        .into();
+        assert_eq!(thread.file_path.as_deref(), Some("/src/lib.rs"));
+        assert_eq!(thread.line, Some(12));
+        assert_eq!(thread.comments.len(), 1, "deleted comments are dropped");
+        assert!(!thread.is_system());
+        assert!(thread.status.is_unresolved());
+
+        let system: AzureThread = serde_json::from_str::<AzureApiThread>(
+            r#"{ "id": 4, "comments": [ { "id": 1, "content": "Bob voted 10", "commentType": "system" } ] }"#,
+        )
+        .unwrap()
+        .into();
+        assert!(system.is_system());
+        assert_eq!(system.status, ThreadStatus::Unknown);
+    }
+
+    #[test]
+    fn update_body_only_serializes_provided_fields() {
+        let body = UpdatePullRequestBody {
+            title: Some("New"),
+            target_ref_name: Some(branch_ref("develop")),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "title": "New", "targetRefName": "refs/heads/develop" })
+        );
+    }
+
+    #[test]
+    fn branch_refs_are_qualified_once() {
+        assert_eq!(branch_ref("main"), "refs/heads/main");
+        assert_eq!(branch_ref("refs/heads/main"), "refs/heads/main");
+        assert_eq!(short_branch_name("refs/heads/a/b"), "a/b");
+    }
+
+    #[test]
+    fn descriptions_are_truncated_on_char_boundaries() {
+        let long = "é".repeat(MAX_DESCRIPTION_CHARS + 10);
+        assert_eq!(
+            truncate_description(&long).chars().count(),
+            MAX_DESCRIPTION_CHARS
+        );
+        assert_eq!(truncate_description("short"), "short");
+    }
+
+    #[test]
+    fn merge_strategy_serializes_to_azure_names() {
+        assert_eq!(MergeStrategy::NoFastForward.as_str(), "noFastForward");
+        assert_eq!(MergeStrategy::Squash.as_str(), "squash");
+        assert_eq!(MergeStrategy::Rebase.as_str(), "rebase");
+        assert_eq!(MergeStrategy::RebaseMerge.as_str(), "rebaseMerge");
+    }
+}
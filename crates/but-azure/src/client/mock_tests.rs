@@ -0,0 +1,215 @@
+use super::*;
+use but_testsupport::{MockHttpRequest, MockHttpResponse, mock_http_server};
+use std::sync::mpsc;
+
+/// Serve `responses` in order, asserting method and path of each request, and
+/// forward every request to the returned receiver.
+fn mock_client(
+    responses: Vec<MockHttpResponse>,
+) -> (
+    AzureClient,
+    mpsc::Receiver<MockHttpRequest>,
+    std::thread::JoinHandle<()>,
+) {
+    let (origin, requests, server) = mock_http_server(responses);
+    let client = AzureClient::new_with_base_url(
+        &Sensitive("test-token".to_string()),
+        &format!("{origin}/contoso"),
+    )
+    .unwrap();
+    (client, requests, server)
+}
+
+fn repo() -> AzureRepoId {
+    AzureRepoId {
+        organization: "contoso".into(),
+        project: "My Project".into(),
+        repository: "web".into(),
+    }
+}
+
+fn pr_json(id: usize, status: &str) -> String {
+    format!(
+        r#"{{"pullRequestId":{id},"title":"PR {id}","status":"{status}","sourceRefName":"refs/heads/feature-{id}","targetRefName":"refs/heads/main","lastMergeSourceCommit":{{"commitId":"c0ffee{id}"}}}}"#
+    )
+}
+
+fn page(ids: std::ops::Range<usize>) -> String {
+    let prs: Vec<_> = ids.map(|id| pr_json(id, "active")).collect();
+    format!(r#"{{"value":[{}],"count":{}}}"#, prs.join(","), prs.len())
+}
+
+const PRS_PATH: &str = "/contoso/My%20Project/_apis/git/repositories/web/pullrequests";
+
+#[tokio::test(flavor = "current_thread")]
+async fn list_open_prs_follows_skip_pagination() {
+    let (client, _bodies, server) = mock_client(vec![
+        MockHttpResponse::ok(
+            "GET",
+            format!("{PRS_PATH}?searchCriteria.status=active&api-version=7.1&$top=100&$skip=0"),
+            page(0..100),
+        ),
+        MockHttpResponse::ok(
+            "GET",
+            format!("{PRS_PATH}?searchCriteria.status=active&api-version=7.1&$top=100&$skip=100"),
+            page(100..101),
+        ),
+    ]);
+
+    let prs = client.list_open_prs(&repo()).await.unwrap();
+    server.join().unwrap();
+
+    assert_eq!(prs.len(), 101, "a short page ends pagination");
+    assert_eq!(prs[100].source_branch, "feature-100");
+    assert_eq!(
+        prs[0].html_url,
+        format!("{}/My%20Project/_git/web/pullrequest/0", client.base_url),
+        "html URL is derived from the repository when the response has none"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn list_prs_for_target_qualifies_the_ref() {
+    let (client, _bodies, server) = mock_client(vec![MockHttpResponse::ok(
+        "GET",
+        format!(
+            "{PRS_PATH}?searchCriteria.status=all&searchCriteria.targetRefName=refs%2Fheads%2Fmain&api-version=7.1&$top=100&$skip=0"
+        ),
+        format!(r#"{{"value":[{}]}}"#, pr_json(3, "completed")),
+    )]);
+
+    let prs = client.list_prs_for_target(&repo(), "main").await.unwrap();
+    server.join().unwrap();
+
+    assert_eq!(prs.len(), 1);
+    assert!(!prs[0].is_open());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_pull_request_sends_full_ref_names() {
+    let (client, bodies, server) = mock_client(vec![MockHttpResponse::ok(
+        "POST",
+        format!("{PRS_PATH}?api-version=7.1"),
+        pr_json(12, "active"),
+    )]);
+
+    let pr = client
+        .create_pull_request(&CreatePullRequestParams {
+            repo: &repo(),
+            title: "Add login",
+            body: "Description",
+            source_branch: "feature/login",
+            target_branch: "main",
+            draft: true,
+        })
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    assert_eq!(pr.id, 12);
+    let body: serde_json::Value = serde_json::from_str(&bodies.recv().unwrap().body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "sourceRefName": "refs/heads/feature/login",
+            "targetRefName": "refs/heads/main",
+            "title": "Add login",
+            "description": "Description",
+            "isDraft": true,
+        })
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn merge_pull_request_completes_with_the_current_source_commit() {
+    let (client, bodies, server) = mock_client(vec![
+        MockHttpResponse::ok(
+            "GET",
+            format!("{PRS_PATH}/5?api-version=7.1"),
+            pr_json(5, "active"),
+        ),
+        MockHttpResponse::ok(
+            "PATCH",
+            format!("{PRS_PATH}/5?api-version=7.1"),
+            pr_json(5, "completed"),
+        ),
+    ]);
+
+    client
+        .merge_pull_request(&MergePullRequestParams {
+            repo: &repo(),
+            id: 5,
+            strategy: MergeStrategy::Squash,
+        })
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    let _get = bodies.recv().unwrap();
+    let patch: serde_json::Value = serde_json::from_str(&bodies.recv().unwrap().body).unwrap();
+    assert_eq!(
+        patch,
+        serde_json::json!({
+            "status": "completed",
+            "lastMergeSourceCommit": { "commitId": "c0ffee5" },
+            "completionOptions": { "mergeStrategy": "squash", "deleteSourceBranch": false },
+        })
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn anchored_threads_target_the_right_side_of_the_diff() {
+    let (client, bodies, server) = mock_client(vec![MockHttpResponse::ok(
+        "POST",
+        format!("{PRS_PATH}/5/threads?api-version=7.1"),
+        r#"{"id":9,"status":"active","threadContext":{"filePath":"/src/main.rs","rightFileStart":{"line":4,"offset":1}},"comments":[{"id":1,"content":"nit","commentType":"text"}]}"#,
+    )]);
+
+    let thread = client
+        .create_thread(&repo(), 5, "nit", Some(("/src/main.rs", 4)))
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    assert_eq!(thread.id, 9);
+    assert_eq!(thread.line, Some(4));
+    let body: serde_json::Value = serde_json::from_str(&bodies.recv().unwrap().body).unwrap();
+    assert_eq!(body["threadContext"]["filePath"], "/src/main.rs");
+    assert_eq!(body["threadContext"]["rightFileStart"]["line"], 4);
+    assert_eq!(body["comments"][0]["parentCommentId"], 0);
+    assert_eq!(body["comments"][0]["commentType"], 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn sign_in_page_for_invalid_token_is_unauthorized() {
+    let (client, _bodies, server) = mock_client(vec![MockHttpResponse {
+        method: "GET",
+        path: "/contoso/_apis/connectionData?api-version=7.1-preview".into(),
+        status: reqwest::StatusCode::NON_AUTHORITATIVE_INFORMATION.as_u16(),
+        body: "<html>Sign in</html>".into(),
+    }]);
+
+    let err = client.get_authenticated().await.unwrap_err();
+    server.join().unwrap();
+
+    let status = err
+        .downcast_ref::<HttpStatusError>()
+        .expect("auth failures are reported as HTTP status errors")
+        .status;
+    assert_eq!(status, reqwest::StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn authenticated_user_is_read_from_connection_data() {
+    let (client, _bodies, server) = mock_client(vec![MockHttpResponse::ok(
+        "GET",
+        "/contoso/_apis/connectionData?api-version=7.1-preview",
+        r#"{"authenticatedUser":{"id":"6f1a-42","providerDisplayName":"Alice","properties":{"Account":{"$type":"System.String","$value":"alice@example.com"}}}}"#,
+    )]);
+
+    let user = client.get_authenticated().await.unwrap();
+    server.join().unwrap();
+
+    assert_eq!(user.username, "alice@example.com");
+    assert_eq!(user.name.as_deref(), Some("Alice"));
+}
@@ -0,0 +1,398 @@
+use anyhow::{Context as _, Result, bail};
+use but_secret::Sensitive;
+
+mod client;
+pub mod pr;
+mod repo;
+pub use client::{
+    AzureClient, AzureComment, AzurePullRequest, AzureRepo, AzureThread, AzureUser,
+    CreatePullRequestParams, HttpStatusError, MergePullRequestParams, MergeStrategy,
+    PullRequestStatus, ThreadStatus, UpdatePullRequestParams,
+};
+pub use repo::fetch_repo;
+mod token;
+use serde::Serialize;
+pub use token::AzureAccountIdentifier;
+
+/// Identifies a Git repository in Azure DevOps.
+///
+/// Unlike other forges, Azure DevOps nests repositories in a project within an organization,
+/// so `but_forge` stores `organization/project` as the repository owner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AzureRepoId {
+    pub organization: String,
+    pub project: String,
+    pub repository: String,
+}
+
+impl AzureRepoId {
+    /// Build a repository id from an `organization/project` owner and the repository name.
+    pub fn from_owner_and_repo(owner: &str, repository: &str) -> Result<Self> {
+        let Some((organization, project)) = owner.split_once('/') else {
+            bail!("Expected the Azure DevOps owner to be 'organization/project', got '{owner}'");
+        };
+        if organization.is_empty() || project.is_empty() || repository.is_empty() {
+            bail!("Incomplete Azure DevOps repository '{owner}/{repository}'");
+        }
+        Ok(AzureRepoId {
+            organization: organization.to_owned(),
+            project: project.to_owned(),
+            repository: repository.to_owned(),
+        })
+    }
+}
+
+impl AzureRepoId {
+    /// The browsable URL of pull request `id` in this repository on Azure DevOps Services.
+    pub fn pull_request_web_url(&self, id: i64) -> String {
+        format!(
+            "https://dev.azure.com/{}/{}/_git/{}/pullrequest/{id}",
+            urlencoding::encode(&self.organization),
+            urlencoding::encode(&self.project),
+            urlencoding::encode(&self.repository),
+        )
+    }
+}
+
+impl std::fmt::Display for AzureRepoId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}",
+            self.organization, self.project, self.repository
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthStatusResponse {
+    /// The access token.
+    /// This is only shared with the FrontEnd temporarily as we undergo the migration to having all API calls
+    /// made to the forges from the Rustend.
+    pub access_token: Sensitive<String>,
+    pub username: String,
+    pub name: Option<String>,
+    pub organization: String,
+}
+
+/// Store an Azure DevOps personal access token and fetch the associated user data.
+///
+/// `organization` is the organization the token was issued for, as in `https://dev.azure.com/{organization}`.
+pub async fn store_pat(
+    organization: &str,
+    access_token: &Sensitive<String>,
+    storage: &but_forge_storage::Controller,
+) -> Result<AuthStatusResponse> {
+    let user = fetch_and_persist_user_data(organization, access_token, storage).await?;
+    Ok(AuthStatusResponse {
+        access_token: access_token.clone(),
+        username: user.username,
+        name: user.name,
+        organization: organization.to_owned(),
+    })
+}
+
+/// Cache the user profile so it's available offline.
+fn cache_user_profile(
+    account: &AzureAccountIdentifier,
+    user: &client::AuthenticatedUser,
+    storage: &but_forge_storage::Controller,
+) {
+    let profile = but_forge_storage::settings::CachedProfile {
+        avatar_url: None,
+        name: user.name.clone(),
+        email: user.username.contains('@').then(|| user.username.clone()),
+    };
+    let key = account.cache_key();
+    let existing = storage.cached_profile(&key).ok().flatten();
+    if existing.as_ref() == Some(&profile) {
+        return;
+    }
+    if let Err(err) = storage.set_cached_profile(&key, Some(profile)) {
+        tracing::warn!(
+            ?account,
+            "Failed to update cached Azure DevOps profile: {err}"
+        );
+    }
+}
+
+/// Fetch the authenticated user data from Azure DevOps and persist the access token.
+async fn fetch_and_persist_user_data(
+    organization: &str,
+    access_token: &Sensitive<String>,
+    storage: &but_forge_storage::Controller,
+) -> Result<client::AuthenticatedUser, anyhow::Error> {
+    let az = client::AzureClient::new(organization, access_token)
+        .context("Failed to create Azure DevOps client")?;
+    let user = az
+        .get_authenticated()
+        .await
+        .context("Failed to get authenticated user")?;
+    let account_id = token::AzureAccountIdentifier::pat(organization, &user.username);
+    token::persist_az_access_token(&account_id, access_token, storage)
+        .context("Failed to persist access token")?;
+    cache_user_profile(&account_id, &user, storage);
+    Ok(user)
+}
+
+pub fn forget_az_access_token(
+    account: &AzureAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    token::delete_az_access_token(account, storage).context("Failed to delete access token")
+}
+
+pub async fn get_az_user(
+    account: &AzureAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<Option<AuthenticatedUser>> {
+    if let Some(access_token) = token::get_az_access_token(account, storage)? {
+        let az = account
+            .client(&access_token)
+            .context("Failed to create Azure DevOps client")?;
+        match az.get_authenticated().await {
+            Ok(user) => {
+                cache_user_profile(account, &user, storage);
+                Ok(Some(AuthenticatedUser {
+                    access_token,
+                    email: user.username.contains('@').then(|| user.username.clone()),
+                    username: user.username,
+                    name: user.name,
+                    organization: account.organization().to_owned(),
+                    avatar_url: None,
+                }))
+            }
+            Err(client_err) => {
+                let cache_key = account.cache_key();
+                // Check if this is a network error — return cached data if available.
+                if let Some(reqwest_err) = client_err.downcast_ref::<reqwest::Error>()
+                    && is_network_error(reqwest_err)
+                {
+                    match storage.cached_profile(&cache_key) {
+                        Ok(Some(cached)) => {
+                            return Ok(Some(AuthenticatedUser {
+                                access_token,
+                                username: account.username().to_owned(),
+                                organization: account.organization().to_owned(),
+                                avatar_url: cached.avatar_url,
+                                name: cached.name,
+                                email: cached.email,
+                            }));
+                        }
+                        Ok(None) => {}
+                        Err(err) => {
+                            tracing::warn!("Failed to read cached Azure DevOps profile: {err}");
+                        }
+                    }
+                    return Err(client_err.context(but_error::Context::new_static(
+                        but_error::Code::NetworkError,
+                        "Unable to connect to Azure DevOps.",
+                    )));
+                }
+                // Check if this is an auth error (401/403) — clear cached profile.
+                if let Some(http_err) = client_err.downcast_ref::<client::HttpStatusError>()
+                    && matches!(
+                        http_err.status,
+                        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+                    )
+                    && let Err(err) = storage.set_cached_profile(&cache_key, None)
+                {
+                    tracing::warn!("Failed to clear cached Azure DevOps profile: {err}");
+                }
+                Err(client_err.context("Failed to get authenticated user"))
+            }
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+/// Check if an error is a network connectivity error.
+///
+/// This includes DNS resolution failures, connection timeouts, connection refused, etc.
+fn is_network_error(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect() || err.is_request()
+}
+
+/// Stable 64-bit hash (FNV-1a) for synthesizing numeric ids from the GUIDs
+/// Azure DevOps uses to identify users.
+pub fn stable_id_hash(input: &str) -> i64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x00000100000001B3);
+    }
+    hash as i64
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub enum CredentialCheckResult {
+    Valid,
+    Invalid,
+    NoCredentials,
+}
+
+/// Check the validity of the stored credentials for the given Azure DevOps account.
+pub async fn check_credentials(
+    account: &AzureAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<CredentialCheckResult> {
+    if let Some(access_token) = token::get_az_access_token(account, storage)? {
+        let az = account
+            .client(&access_token)
+            .context("Failed to create Azure DevOps client")?;
+        match az.get_authenticated().await {
+            Ok(_) => Ok(CredentialCheckResult::Valid),
+            Err(_) => Ok(CredentialCheckResult::Invalid),
+        }
+    } else {
+        Ok(CredentialCheckResult::NoCredentials)
+    }
+}
+
+pub fn list_known_azure_accounts(
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<token::AzureAccountIdentifier>> {
+    token::list_known_azure_accounts(storage).context("Failed to list known Azure DevOps accounts")
+}
+
+pub fn clear_all_azure_tokens(storage: &but_forge_storage::Controller) -> Result<()> {
+    token::clear_all_azure_accounts(storage).context("Failed to clear all Azure DevOps tokens")
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub access_token: Sensitive<String>,
+    pub username: String,
+    pub organization: String,
+    pub avatar_url: Option<String>,
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// JSON serialization types for Azure DevOps API responses.
+///
+/// This module contains serializable versions of Azure DevOps authentication types
+/// that expose sensitive data (like access tokens) as plain strings for API responses.
+pub mod json {
+    use serde::Serialize;
+
+    use crate::{AuthStatusResponse, AuthenticatedUser};
+
+    /// Serializable version of [`AuthStatusResponse`], without the access token.
+    ///
+    /// The credential is stored by the backend as part of the call, so the caller is told
+    /// who authenticated and nothing more. Field names are camelCase for JSON.
+    #[derive(Debug, Serialize)]
+    #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+    #[serde(rename_all = "camelCase")]
+    pub struct AzureAuthStatusResponse {
+        pub username: String,
+        pub name: Option<String>,
+        pub organization: String,
+    }
+
+    impl From<AuthStatusResponse> for AzureAuthStatusResponse {
+        fn from(
+            AuthStatusResponse {
+                username,
+                name,
+                organization,
+                ..
+            }: AuthStatusResponse,
+        ) -> Self {
+            AzureAuthStatusResponse {
+                username,
+                name,
+                organization,
+            }
+        }
+    }
+
+    #[cfg(feature = "export-schema")]
+    but_schemars::register_sdk_type!(AzureAuthStatusResponse);
+
+    /// Serializable version of [`AuthenticatedUser`] with exposed access token.
+    #[derive(Debug, Serialize)]
+    #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+    #[serde(rename_all = "camelCase")]
+    pub struct AzureAuthenticatedUserSensitive {
+        /// The Azure DevOps personal access token as a plain string (sensitive data).
+        pub access_token: String,
+        /// The unique name of the user, usually their email address.
+        pub username: String,
+        /// The organization the token was issued for.
+        pub organization: String,
+        /// The URL to the user's avatar image, if available.
+        pub avatar_url: Option<String>,
+        /// The user's display name, if available.
+        pub name: Option<String>,
+        /// The user's email, if available.
+        pub email: Option<String>,
+    }
+
+    impl From<AuthenticatedUser> for AzureAuthenticatedUserSensitive {
+        fn from(
+            AuthenticatedUser {
+                access_token,
+                username,
+                organization,
+                avatar_url,
+                name,
+                email,
+            }: AuthenticatedUser,
+        ) -> Self {
+            AzureAuthenticatedUserSensitive {
+                access_token: access_token.0,
+                username,
+                organization,
+                avatar_url,
+                name,
+                email,
+            }
+        }
+    }
+
+    #[cfg(feature = "export-schema")]
+    but_schemars::register_sdk_type!(AzureAuthenticatedUserSensitive);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repo_id_is_parsed_from_owner_and_repo() {
+        let id = AzureRepoId::from_owner_and_repo("contoso/Web Platform", "frontend").unwrap();
+        assert_eq!(id.organization, "contoso");
+        assert_eq!(id.project, "Web Platform");
+        assert_eq!(id.repository, "frontend");
+        assert_eq!(id.to_string(), "contoso/Web Platform/frontend");
+        assert_eq!(
+            id.pull_request_web_url(3),
+            "https://dev.azure.com/contoso/Web%20Platform/_git/frontend/pullrequest/3"
+        );
+    }
+
+    #[test]
+    fn repo_id_requires_organization_and_project() {
+        assert!(AzureRepoId::from_owner_and_repo("contoso", "frontend").is_err());
+        assert!(AzureRepoId::from_owner_and_repo("/project", "frontend").is_err());
+        assert!(AzureRepoId::from_owner_and_repo("contoso/project", "").is_err());
+    }
+
+    #[test]
+    fn test_is_network_error_with_connection_error() {
+        // Loopback port 1 is closed, so the connection is refused immediately
+        // without touching the external network — deterministic and fast.
+        let client = reqwest::blocking::Client::new();
+        let err = client
+            .get("http://127.0.0.1:1")
+            .send()
+            .expect_err("connection to a closed port should fail");
+        assert!(
+            is_network_error(&err),
+            "connection refused should be classified as a network error"
+        );
+    }
+}
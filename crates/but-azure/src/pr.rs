@@ -0,0 +1,158 @@
+use anyhow::{Context as _, Result};
+
+use crate::{AzureRepoId, client::AzureClient};
+
+pub async fn list(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    repo: &AzureRepoId,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::AzurePullRequest>> {
+    if let Ok(az) = AzureClient::from_storage(storage, preferred_account, &repo.organization) {
+        az.list_open_prs(repo)
+            .await
+            .context("Failed to list open pull requests")
+    } else {
+        Ok(vec![])
+    }
+}
+
+pub async fn list_all_for_target(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    repo: &AzureRepoId,
+    target_branch: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::AzurePullRequest>> {
+    if let Ok(az) = AzureClient::from_storage(storage, preferred_account, &repo.organization) {
+        az.list_prs_for_target(repo, target_branch)
+            .await
+            .context("Failed to list pull requests for target branch")
+    } else {
+        Ok(vec![])
+    }
+}
+
+pub async fn get(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    repo: &AzureRepoId,
+    id: usize,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::AzurePullRequest> {
+    let id = id.try_into().context("PR number is too large")?;
+    AzureClient::from_storage(storage, preferred_account, &repo.organization)?
+        .get_pull_request(repo, id)
+        .await
+        .context("Failed to get pull request")
+}
+
+pub async fn create(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    params: crate::client::CreatePullRequestParams<'_>,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::AzurePullRequest> {
+    AzureClient::from_storage(storage, preferred_account, &params.repo.organization)?
+        .create_pull_request(&params)
+        .await
+        .context("Failed to create pull request")
+}
+
+pub async fn update(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    params: crate::client::UpdatePullRequestParams<'_>,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::AzurePullRequest> {
+    AzureClient::from_storage(storage, preferred_account, &params.repo.organization)?
+        .update_pull_request(&params)
+        .await
+        .context("Failed to update pull request")
+}
+
+pub async fn merge(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    params: crate::client::MergePullRequestParams<'_>,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    AzureClient::from_storage(storage, preferred_account, &params.repo.organization)?
+        .merge_pull_request(&params)
+        .await
+        .context("Failed to complete pull request")
+}
+
+pub async fn set_draft_state(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    repo: &AzureRepoId,
+    id: usize,
+    is_draft: bool,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let id = id.try_into().context("PR number is too large")?;
+    AzureClient::from_storage(storage, preferred_account, &repo.organization)?
+        .set_pull_request_draft_state(repo, id, is_draft)
+        .await
+        .context("Failed to set pull request draft state")
+}
+
+/// List the human comment threads of a pull request, skipping system threads
+/// that only record pushes, votes and similar events.
+pub async fn list_threads(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    repo: &AzureRepoId,
+    id: usize,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::AzureThread>> {
+    let id = id.try_into().context("PR number is too large")?;
+    let threads = AzureClient::from_storage(storage, preferred_account, &repo.organization)?
+        .list_threads(repo, id)
+        .await
+        .context("Failed to list pull request threads")?;
+    Ok(threads
+        .into_iter()
+        .filter(|thread| !thread.is_system())
+        .collect())
+}
+
+/// Start a comment thread, optionally anchored to `(file_path, line)`.
+pub async fn create_thread(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    repo: &AzureRepoId,
+    id: usize,
+    content: &str,
+    file_position: Option<(&str, u32)>,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::AzureThread> {
+    let id = id.try_into().context("PR number is too large")?;
+    AzureClient::from_storage(storage, preferred_account, &repo.organization)?
+        .create_thread(repo, id, content, file_position)
+        .await
+        .context("Failed to create pull request thread")
+}
+
+pub async fn reply_to_thread(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    repo: &AzureRepoId,
+    id: usize,
+    thread_id: i64,
+    parent_comment_id: i64,
+    content: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::AzureComment> {
+    let id = id.try_into().context("PR number is too large")?;
+    AzureClient::from_storage(storage, preferred_account, &repo.organization)?
+        .reply_to_thread(repo, id, thread_id, parent_comment_id, content)
+        .await
+        .context("Failed to reply to pull request thread")
+}
+
+pub async fn set_thread_status(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    repo: &AzureRepoId,
+    id: usize,
+    thread_id: i64,
+    status: crate::client::ThreadStatus,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let id = id.try_into().context("PR number is too large")?;
+    AzureClient::from_storage(storage, preferred_account, &repo.organization)?
+        .set_thread_status(repo, id, thread_id, status)
+        .await
+        .context("Failed to update pull request thread status")
+}
@@ -0,0 +1,13 @@
+use anyhow::{Context as _, Result};
+
+/// Fetch repository metadata (fork status, default branch) for `repo`.
+pub async fn fetch_repo(
+    preferred_account: Option<&crate::AzureAccountIdentifier>,
+    repo: &crate::AzureRepoId,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::AzureRepo> {
+    crate::client::AzureClient::from_storage(storage, preferred_account, &repo.organization)?
+        .fetch_repo(repo)
+        .await
+        .context("Failed to fetch Azure DevOps repository")
+}
@@ -0,0 +1,273 @@
+use std::sync::Mutex;
+
+use anyhow::Result;
+use but_secret::{Sensitive, secret};
+use serde::{Deserialize, Serialize};
+
+use crate::client::AzureClient;
+
+/// Persist Azure DevOps account access tokens securely.
+pub fn persist_az_access_token(
+    account_id: &AzureAccountIdentifier,
+    access_token: &Sensitive<String>,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let account = AzureAccount::new(account_id, access_token.clone());
+    persist_azure_account(&account, storage)
+}
+
+/// Delete an Azure DevOps account access token for a given account.
+pub fn delete_az_access_token(
+    account_id: &AzureAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let account = find_azure_account(account_id, storage)?;
+    if let Some(account) = account {
+        delete_azure_account(&account, storage)
+    } else {
+        Ok(())
+    }
+}
+
+/// Retrieve an Azure DevOps account access token for a given account.
+pub fn get_az_access_token(
+    account_id: &AzureAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<Option<Sensitive<String>>> {
+    let account = find_azure_account(account_id, storage)?;
+    Ok(account.map(|acct| acct.access_token()))
+}
+
+pub fn list_known_azure_accounts(
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<AzureAccountIdentifier>> {
+    Ok(storage
+        .azure_accounts()?
+        .iter()
+        .map(|account| account.into())
+        .collect::<Vec<_>>())
+}
+
+pub fn clear_all_azure_accounts(storage: &but_forge_storage::Controller) -> Result<()> {
+    delete_all_azure_accounts(storage)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase", tag = "type", content = "info")]
+pub enum AzureAccountIdentifier {
+    /// A personal access token. PATs are issued per organization, so the account
+    /// is identified by the organization together with the user owning the token.
+    Pat {
+        organization: String,
+        username: String,
+    },
+}
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(AzureAccountIdentifier);
+
+impl AzureAccountIdentifier {
+    pub fn pat(organization: &str, username: &str) -> Self {
+        AzureAccountIdentifier::Pat {
+            organization: organization.to_string(),
+            username: username.to_string(),
+        }
+    }
+
+    pub fn organization(&self) -> &str {
+        match self {
+            AzureAccountIdentifier::Pat { organization, .. } => organization,
+        }
+    }
+
+    pub fn username(&self) -> &str {
+        match self {
+            AzureAccountIdentifier::Pat { username, .. } => username,
+        }
+    }
+
+    /// The key used to store and look up the cached profile for this account.
+    pub fn cache_key(&self) -> String {
+        match self {
+            AzureAccountIdentifier::Pat {
+                organization,
+                username,
+            } => format!("azure_pat_{organization}_{username}"),
+        }
+    }
+
+    pub fn client(&self, access_token: &Sensitive<String>) -> Result<AzureClient> {
+        match self {
+            AzureAccountIdentifier::Pat { organization, .. } => {
+                AzureClient::new(organization, access_token)
+            }
+        }
+    }
+
+    /// Retrieve the custom forge host. Only Azure DevOps Services (`dev.azure.com`)
+    /// is supported, so this is always `None`; kept for symmetry with the other forge integrations.
+    pub fn custom_host(&self) -> Option<String> {
+        None
+    }
+}
+
+impl std::fmt::Display for AzureAccountIdentifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AzureAccountIdentifier::Pat {
+                organization,
+                username,
+            } => {
+                write!(f, "PAT: {username} ({organization})")
+            }
+        }
+    }
+}
+
+pub enum AzureAccount {
+    Pat {
+        organization: String,
+        username: String,
+        access_token: Sensitive<String>,
+    },
+}
+
+impl From<&AzureAccount> for but_forge_storage::settings::AzureAccount {
+    fn from(account: &AzureAccount) -> Self {
+        let access_token_key = account.secret_key();
+        match account {
+            AzureAccount::Pat {
+                organization,
+                username,
+                ..
+            } => but_forge_storage::settings::AzureAccount::Pat {
+                organization: organization.to_owned(),
+                username: username.to_owned(),
+                access_token_key,
+            },
+        }
+    }
+}
+
+impl From<&but_forge_storage::settings::AzureAccount> for AzureAccountIdentifier {
+    fn from(account: &but_forge_storage::settings::AzureAccount) -> Self {
+        match account {
+            but_forge_storage::settings::AzureAccount::Pat {
+                organization,
+                username,
+                ..
+            } => AzureAccountIdentifier::pat(organization, username),
+        }
+    }
+}
+
+impl AzureAccount {
+    pub fn new(account_id: &AzureAccountIdentifier, access_token: Sensitive<String>) -> Self {
+        match account_id {
+            AzureAccountIdentifier::Pat {
+                organization,
+                username,
+            } => AzureAccount::Pat {
+                organization: organization.to_owned(),
+                username: username.to_owned(),
+                access_token,
+            },
+        }
+    }
+
+    fn secret_key(&self) -> String {
+        match self {
+            AzureAccount::Pat {
+                organization,
+                username,
+                ..
+            } => AzureAccountIdentifier::pat(organization, username).cache_key(),
+        }
+    }
+
+    fn secret_value(&self) -> Result<Sensitive<String>> {
+        Ok(self.access_token())
+    }
+
+    fn access_token(&self) -> Sensitive<String> {
+        match self {
+            AzureAccount::Pat { access_token, .. } => access_token.clone(),
+        }
+    }
+}
+
+fn retrieve_azure_secret(account_secret_key: &str) -> Result<Option<Sensitive<String>>> {
+    static FAIR_QUEUE: Mutex<()> = Mutex::new(());
+    let _one_at_a_time_to_prevent_races = FAIR_QUEUE.lock().unwrap();
+    secret::retrieve(account_secret_key, secret::Namespace::BuildKind)
+}
+
+fn persist_azure_account(
+    account: &AzureAccount,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let secret_key = account.secret_key();
+    storage.add_azure_account(&account.into())?;
+
+    static FAIR_QUEUE: Mutex<()> = Mutex::new(());
+    let _one_at_a_time_to_prevent_races = FAIR_QUEUE.lock().unwrap();
+    secret::persist(
+        &secret_key,
+        &account.secret_value()?,
+        secret::Namespace::BuildKind,
+    )
+}
+
+fn delete_azure_account(
+    account: &AzureAccount,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let secret_key = account.secret_key();
+    storage.remove_azure_account(&account.into())?;
+
+    static FAIR_QUEUE: Mutex<()> = Mutex::new(());
+    let _one_at_a_time_to_prevent_races = FAIR_QUEUE.lock().unwrap();
+    secret::delete(&secret_key, secret::Namespace::BuildKind)
+}
+
+fn delete_all_azure_accounts(storage: &but_forge_storage::Controller) -> Result<()> {
+    let keys_to_delete = storage.clear_all_azure_accounts()?;
+    static FAIR_QUEUE: Mutex<()> = Mutex::new(());
+    let _one_at_a_time_to_prevent_races = FAIR_QUEUE.lock().unwrap();
+    for key in keys_to_delete {
+        secret::delete(&key, secret::Namespace::BuildKind)?;
+    }
+    Ok(())
+}
+
+fn find_azure_account(
+    account_id: &AzureAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<Option<AzureAccount>> {
+    let accounts = storage.azure_accounts()?;
+    let result = match account_id {
+        AzureAccountIdentifier::Pat {
+            organization,
+            username,
+        } => accounts.iter().find_map(|account| {
+            let but_forge_storage::settings::AzureAccount::Pat {
+                organization: acct_organization,
+                username: acct_username,
+                access_token_key,
+            } = account;
+            if acct_organization == organization
+                && acct_username == username
+                && let Some(access_token) = retrieve_azure_secret(access_token_key).ok().flatten()
+            {
+                return Some(AzureAccount::Pat {
+                    organization: acct_organization.clone(),
+                    username: acct_username.clone(),
+                    access_token,
+                });
+            }
+            None
+        }),
+    };
+    Ok(result)
+}
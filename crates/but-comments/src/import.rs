@@ -0,0 +1,365 @@
+//! Turning review comments from a forge into local comments on the commits of the reviewed branch.
+//!
+//! A forge anchors inline comments to lines of the review's diff, which spans all commits of the
+//! branch. Locally, each comment is anchored to the first commit of the branch whose own diff has the
+//! commented line, so it follows that commit through amends and rebases like any other comment.
+
+use bstr::ByteSlice;
+use gix::prelude::ObjectIdExt;
+use serde::Serialize;
+
+use crate::{CommentStore, DiffSide, FileAnchor, ScopeDiffs, StoredComment};
+
+/// A comment on a line of the diff of a review, to import with [`import_review_comments`].
+#[derive(Debug, Clone)]
+pub struct ReviewComment {
+    /// The id of the local comment, which must be the same whenever the same forge comment is
+    /// imported, so importing again updates it instead of adding a duplicate.
+    pub id: String,
+    /// The id of the local comment this comment replies to, if any.
+    pub in_reply_to: Option<String>,
+    /// The worktree-relative path of the commented file.
+    pub path: String,
+    /// The side of the review's diff the commented line is on.
+    pub side: DiffSide,
+    /// The 1-based number of the commented line in the reviewed version, or `None` if the forge
+    /// considers the comment outdated.
+    pub line_number: Option<u32>,
+    /// The content of the commented line, if the forge reports it. Otherwise it is read from the
+    /// tip of the reviewed branch, which only works for lines on the [new](DiffSide::New) side.
+    pub line_content: Option<String>,
+    /// The content of the line before the commented one on the same side, if known.
+    pub line_before: Option<String>,
+    /// The name of whoever wrote the comment, if known.
+    pub author: Option<String>,
+    /// The comment text.
+    pub payload: String,
+    /// When the comment was created, in milliseconds since the Unix epoch (UTC).
+    pub created_at_ms: i64,
+    /// When the comment was last edited, in milliseconds since the Unix epoch (UTC).
+    pub updated_at_ms: i64,
+}
+
+/// The result of [`import_review_comments`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOutcome {
+    /// The amount of comments and replies that were added.
+    pub imported: usize,
+    /// The amount of previously imported comments and replies whose text changed on the forge.
+    pub updated: usize,
+    /// The amount of comments and replies that couldn't be anchored to a commit of the branch,
+    /// for instance because their line isn't part of any commit's diff anymore.
+    pub unanchored: usize,
+}
+
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(ImportOutcome);
+
+/// Import `comments` made on the review of `branch` into `store`, anchoring each to the
+/// parent-most commit of `branch` in `workspace` whose diff contains the commented line.
+///
+/// Replies join the thread of the comment they answer. Comments that were imported before only
+/// have their text updated, while their anchor is left to the usual re-anchoring when listing.
+pub fn import_review_comments(
+    repo: &gix::Repository,
+    workspace: &but_graph::Workspace,
+    store: &CommentStore,
+    branch: &gix::refs::FullNameRef,
+    comments: Vec<ReviewComment>,
+    context_lines: u32,
+) -> anyhow::Result<ImportOutcome> {
+    let Some(segment) = workspace
+        .stacks
+        .iter()
+        .flat_map(|stack| &stack.segments)
+        .find(|segment| segment.ref_name() == Some(branch))
+    else {
+        anyhow::bail!(
+            "Branch `{}` is not part of the current workspace",
+            branch.shorten()
+        );
+    };
+    let tip = segment.commits.first().map(|commit| commit.id);
+    let mut change_ids = Vec::new();
+    for commit in segment.commits.iter().rev() {
+        let commit = but_core::Commit::from_id(commit.id.attach(repo))?;
+        change_ids.push(commit.change_id().to_string());
+    }
+
+    let existing = store.read();
+    let mut diffs = ScopeDiffs::new(repo, workspace, context_lines);
+    let mut outcome = ImportOutcome::default();
+    let mut anchored = Vec::new();
+    for comment in comments {
+        let known = existing
+            .iter()
+            .chain(&anchored)
+            .any(|stored| stored.id == comment.id);
+        if known || comment.in_reply_to.is_some() {
+            // Updates and replies only need an existing comment, never a new anchor.
+            anchored.push(StoredComment::from_review(comment, None));
+            continue;
+        }
+        let Some(line_content) = comment
+            .line_content
+            .clone()
+            .or_else(|| tip_line(repo, tip, &comment).ok().flatten())
+        else {
+            outcome.unanchored += 1;
+            continue;
+        };
+        let mut anchor = None;
+        for change_id in &change_ids {
+            let Some(FileAnchor::Lines(lines)) = diffs.file(Some(change_id), &comment.path)? else {
+                continue;
+            };
+            let Some(line) = lines.locate(
+                comment.side,
+                comment.line_number.unwrap_or_default(),
+                &line_content,
+                comment.line_before.as_deref(),
+                None,
+            ) else {
+                continue;
+            };
+            let neighbour = |line_number: Option<u32>| {
+                line_number
+                    .and_then(|line_number| lines.line_at(comment.side, line_number))
+                    .map(|line| line.content.clone())
+            };
+            anchor = Some(Anchor {
+                change_id: change_id.clone(),
+                line_number: line.line_number,
+                line_content: line_content.clone(),
+                line_before: neighbour(line.line_number.checked_sub(1)),
+                line_after: neighbour(line.line_number.checked_add(1)),
+            });
+            break;
+        }
+        match anchor {
+            Some(anchor) => anchored.push(StoredComment::from_review(comment, Some(anchor))),
+            None => outcome.unanchored += 1,
+        }
+    }
+
+    store.update(|stored| {
+        merge_imported(stored, anchored, &mut outcome);
+        Ok(())
+    })?;
+    Ok(outcome)
+}
+
+/// Add the `imported` comments to the `stored` ones, or update the text of those that are known,
+/// and count what happened in `outcome`.
+fn merge_imported(
+    stored: &mut Vec<StoredComment>,
+    imported: Vec<StoredComment>,
+    outcome: &mut ImportOutcome,
+) {
+    for comment in imported {
+        if let Some(known) = stored.iter_mut().find(|stored| stored.id == comment.id) {
+            if known.payload != comment.payload && known.updated_at_ms < comment.updated_at_ms {
+                known.payload = comment.payload;
+                known.updated_at_ms = comment.updated_at_ms;
+                outcome.updated += 1;
+            }
+            continue;
+        }
+        if let Some(parent_id) = &comment.parent_id {
+            // Forges may reply to replies, but locally all replies belong to the thread.
+            let thread = stored
+                .iter()
+                .find(|stored| &stored.id == parent_id)
+                .map(|parent| parent.parent_id.as_ref().unwrap_or(&parent.id))
+                .and_then(|thread_id| stored.iter().find(|stored| &stored.id == thread_id));
+            let Some(thread) = thread else {
+                outcome.unanchored += 1;
+                continue;
+            };
+            let reply = StoredComment {
+                id: comment.id,
+                parent_id: Some(thread.id.clone()),
+                author: comment.author,
+                payload: comment.payload,
+                created_at_ms: comment.created_at_ms,
+                updated_at_ms: comment.updated_at_ms,
+                archived_at_ms: None,
+                resolved_at_ms: None,
+                resolved_by: None,
+                ..thread.clone()
+            };
+            stored.push(reply);
+        } else {
+            stored.push(comment);
+        }
+        outcome.imported += 1;
+    }
+}
+
+/// Where an imported comment is anchored locally.
+struct Anchor {
+    change_id: String,
+    line_number: u32,
+    line_content: String,
+    line_before: Option<String>,
+    line_after: Option<String>,
+}
+
+impl StoredComment {
+    /// The stored form of `comment`, with its `anchor` if it starts a new thread. Without an
+    /// anchor, the result is only good for updating a known comment or adding a reply, and
+    /// `parent_id` holds the id of the comment it replies to.
+    fn from_review(comment: ReviewComment, anchor: Option<Anchor>) -> Self {
+        let (commit_change_id, line_number, line_content, line_before, line_after) = match anchor {
+            Some(anchor) => (
+                Some(anchor.change_id),
+                anchor.line_number,
+                anchor.line_content,
+                anchor.line_before,
+                anchor.line_after,
+            ),
+            None => (None, 0, String::new(), None, None),
+        };
+        StoredComment {
+            id: comment.id,
+            path: comment.path,
+            commit_change_id,
+            side: comment.side,
+            line_number,
+            line_content,
+            line_before,
+            line_after,
+            payload: comment.payload,
+            created_at_ms: comment.created_at_ms,
+            updated_at_ms: comment.updated_at_ms,
+            archived_at_ms: None,
+            parent_id: comment.in_reply_to,
+            author: comment.author,
+            resolved_at_ms: None,
+            resolved_by: None,
+        }
+    }
+}
+
+/// Read the commented line of `comment` from the `tip` of the reviewed branch.
+fn tip_line(
+    repo: &gix::Repository,
+    tip: Option<gix::ObjectId>,
+    comment: &ReviewComment,
+) -> anyhow::Result<Option<String>> {
+    let (Some(tip), DiffSide::New, Some(line_number)) = (tip, comment.side, comment.line_number)
+    else {
+        return Ok(None);
+    };
+    let Some(entry) = repo
+        .find_commit(tip)?
+        .tree()?
+        .lookup_entry_by_path(&comment.path)?
+    else {
+        return Ok(None);
+    };
+    let blob = entry.object()?;
+    Ok(blob
+        .data
+        .lines()
+        .nth(line_number.saturating_sub(1) as usize)
+        .map(|line| line.to_str_lossy().into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review_comment(id: &str, in_reply_to: Option<&str>, payload: &str) -> ReviewComment {
+        ReviewComment {
+            id: id.to_string(),
+            in_reply_to: in_reply_to.map(ToOwned::to_owned),
+            path: "src/a.rs".to_string(),
+            side: DiffSide::New,
+            line_number: Some(15),
+            line_content: Some("let x = 1;".to_string()),
+            line_before: None,
+            author: Some("reviewer".to_string()),
+            payload: payload.to_string(),
+            created_at_ms: 1000,
+            updated_at_ms: 1000,
+        }
+    }
+
+    fn anchor() -> Anchor {
+        Anchor {
+            change_id: "change-a".to_string(),
+            line_number: 12,
+            line_content: "let x = 1;".to_string(),
+            line_before: Some("fn a() {".to_string()),
+            line_after: None,
+        }
+    }
+
+    #[test]
+    fn replies_join_the_thread_of_their_root() {
+        let mut stored = Vec::new();
+        let mut outcome = ImportOutcome::default();
+        merge_imported(
+            &mut stored,
+            vec![
+                StoredComment::from_review(review_comment("1", None, "root"), Some(anchor())),
+                StoredComment::from_review(review_comment("2", Some("1"), "reply"), None),
+                StoredComment::from_review(review_comment("3", Some("2"), "nested"), None),
+                StoredComment::from_review(review_comment("4", Some("unknown"), "lost"), None),
+            ],
+            &mut outcome,
+        );
+        assert_eq!(
+            outcome,
+            ImportOutcome {
+                imported: 3,
+                updated: 0,
+                unanchored: 1
+            }
+        );
+        for reply in &stored[1..] {
+            assert_eq!(reply.parent_id.as_deref(), Some("1"));
+            assert_eq!(
+                (reply.commit_change_id.as_deref(), reply.line_number),
+                (Some("change-a"), 12),
+                "replies share the anchor of their thread"
+            );
+        }
+    }
+
+    #[test]
+    fn importing_again_only_updates_edited_text() {
+        let root = StoredComment::from_review(review_comment("1", None, "root"), Some(anchor()));
+        let mut stored = vec![StoredComment {
+            line_number: 20,
+            ..root
+        }];
+        let mut outcome = ImportOutcome::default();
+        let edited = ReviewComment {
+            updated_at_ms: 2000,
+            ..review_comment("1", None, "edited")
+        };
+        merge_imported(
+            &mut stored,
+            vec![StoredComment::from_review(edited, None)],
+            &mut outcome,
+        );
+        assert_eq!(
+            outcome,
+            ImportOutcome {
+                imported: 0,
+                updated: 1,
+                unanchored: 0
+            }
+        );
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].payload, "edited");
+        assert_eq!(
+            stored[0].line_number, 20,
+            "the local anchor follows local changes"
+        );
+    }
+}
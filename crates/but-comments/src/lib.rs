@@ -1,5 +1,5 @@
-//! Ephemeral comments anchored to lines in diffs, shared between the GUI (which creates them)
-//! and the CLI (where agents read and archive them).
+//! Comments anchored to lines in diffs, shared between the GUI (which creates them)
+//! and the CLI (where agents read, answer and archive them).
 //!
 //! A comment is anchored to a line in a diff: either the uncommitted worktree diff of a file, or
 //! the first-parent diff of a commit identified by its change-id (which survives amends and
@@ -8,15 +8,21 @@
 //! identical lines). [`list_comments`] re-locates each comment in the current diff by content
 //! before returning it, persists the refreshed position, and archives comments whose anchor no
 //! longer exists (file committed, line gone). Consumers therefore only ever see comments that
-//! point at real lines in the current diffs; everything else is best-effort auto-archived,
-//! befitting their ephemeral nature.
+//! point at real lines in the current diffs; everything else is best-effort auto-archived.
 //!
-//! Comments are stored in a plain JSON file in the project data directory (see
-//! [`CommentStore`]), not the project database — deliberately the lightest possible storage
-//! while the feature proves itself.
+//! Each comment starts a thread that others can [reply to](reply_to_comment), and that is
+//! [resolved](resolve_thread) once the conversation is over. Resolving is independent of
+//! archiving: resolved threads stay listed until their anchor disappears or they are archived.
+//!
+//! Comments are stored in the project database (see [`CommentStore`]), archived ones included,
+//! so they can be queried later. Comments on commits can additionally be shared with teammates
+//! through a Git notes ref, see [`notes`], and inline comments from a forge review can be
+//! [imported](import_review_comments) onto the commits of the reviewed branch.
 #![deny(missing_docs)]
 
 mod anchor;
+mod import;
+pub mod notes;
 mod store;
 
 use anchor::FileDiffLines;
@@ -26,11 +32,9 @@ use gix::prelude::ObjectIdExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub use import::{ImportOutcome, ReviewComment, import_review_comments};
 pub use store::{CommentStore, StoredComment};
 
-/// How long archived comments are kept around before being purged on the next list call.
-const PURGE_ARCHIVED_AFTER_MS: i64 = 14 * 24 * 60 * 60 * 1000;
-
 /// The side of a diff a comment line lives on: `old` line numbers count in the pre-image,
 /// `new` line numbers in the post-image. Context lines exist on both sides.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -75,10 +79,23 @@ pub struct DiffComment {
     pub line_content: String,
     /// The comment text itself.
     pub payload: String,
+    /// The name of whoever wrote the comment, if known.
+    pub author: Option<String>,
     /// When the comment was created, in milliseconds since the Unix epoch (UTC).
     pub created_at_ms: i64,
-    /// When the comment payload was last updated, in milliseconds since the Unix epoch (UTC).
+    /// When the comment payload or the resolution of its thread was last updated, in milliseconds
+    /// since the Unix epoch (UTC).
     pub updated_at_ms: i64,
+    /// When the thread was resolved, in milliseconds since the Unix epoch (UTC), or `None` if it
+    /// is unresolved.
+    pub resolved_at_ms: Option<i64>,
+    /// The name of whoever resolved the thread, if known.
+    pub resolved_by: Option<String>,
+    /// The short name of the branch containing the anchored commit, or `None` for comments on
+    /// the uncommitted changes and on commits of anonymous segments.
+    pub branch: Option<String>,
+    /// The replies to this comment, oldest first.
+    pub replies: Vec<CommentReply>,
     /// A unified-diff-formatted excerpt of the current diff around the anchored line, so consumers
     /// can understand what the comment is about without recomputing the diff.
     /// Only present on comments returned from [`list_comments`].
@@ -88,6 +105,65 @@ pub struct DiffComment {
 #[cfg(feature = "export-schema")]
 but_schemars::register_sdk_type!(DiffComment);
 
+impl DiffComment {
+    /// Return `true` if the thread started by this comment was resolved.
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_at_ms.is_some()
+    }
+}
+
+/// A reply in the thread started by a [`DiffComment`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CommentReply {
+    /// The unique identifier of the reply.
+    pub id: String,
+    /// The name of whoever wrote the reply, if known.
+    pub author: Option<String>,
+    /// The reply text.
+    pub payload: String,
+    /// When the reply was created, in milliseconds since the Unix epoch (UTC).
+    pub created_at_ms: i64,
+    /// When the reply payload was last updated, in milliseconds since the Unix epoch (UTC).
+    pub updated_at_ms: i64,
+}
+
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(CommentReply);
+
+/// Which threads to keep after [listing](list_comments) them.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadFilter {
+    /// Only keep threads that aren't resolved.
+    #[serde(default)]
+    pub unresolved: bool,
+    /// Only keep threads on commits of the branch with this short or full name.
+    #[serde(default)]
+    pub branch: Option<String>,
+}
+
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(ThreadFilter);
+
+impl ThreadFilter {
+    /// Return `true` if `comment` is a thread that should be kept.
+    pub fn matches(&self, comment: &DiffComment) -> bool {
+        if self.unresolved && comment.is_resolved() {
+            return false;
+        }
+        match &self.branch {
+            None => true,
+            Some(branch) => {
+                let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+                comment.branch.as_deref() == Some(branch)
+            }
+        }
+    }
+}
+
 /// Everything needed to create a new comment. See [`DiffComment`] for the field semantics.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
@@ -106,11 +182,33 @@ pub struct NewComment {
     pub line_number: u32,
     /// The comment text.
     pub payload: String,
+    /// The name of whoever writes the comment, if known.
+    #[serde(default)]
+    pub author: Option<String>,
 }
 
 #[cfg(feature = "export-schema")]
 but_schemars::register_sdk_type!(NewComment);
 
+/// Everything needed to reply to a comment. See [`CommentReply`] for the field semantics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NewReply {
+    /// An optional client-supplied ID. An ID will be generated if this is absent.
+    pub id: Option<String>,
+    /// The id of the comment to reply to. Replies to replies are added to the same thread.
+    pub comment_id: String,
+    /// The reply text.
+    pub payload: String,
+    /// The name of whoever writes the reply, if known.
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(NewReply);
+
 /// Create a new comment anchored to a line in a diff.
 ///
 /// The anchor must point at a line that exists in the current diff — the uncommitted worktree
@@ -175,8 +273,14 @@ pub fn create_comment(
         created_at_ms: now_ms,
         updated_at_ms: now_ms,
         archived_at_ms: None,
+        parent_id: None,
+        author: comment.author,
+        resolved_at_ms: None,
+        resolved_by: None,
     };
-    let result = stored.to_comment(stored.line_number, file.context_excerpt(line));
+    let context = file.context_excerpt(line);
+    let branch = diffs.branch(stored.commit_change_id.as_deref())?;
+    let result = stored.to_comment(stored.line_number, context, branch, Vec::new());
     store.update(|comments| {
         comments.push(stored);
         Ok(())
@@ -185,9 +289,15 @@ pub fn create_comment(
 }
 
 impl StoredComment {
-    /// The consumer-facing view of this comment, re-anchored at `line_number` and carrying the
-    /// diff excerpt around it.
-    fn to_comment(&self, line_number: u32, context: String) -> DiffComment {
+    /// The consumer-facing view of the thread started by this comment, re-anchored at
+    /// `line_number` and carrying the diff excerpt around it.
+    fn to_comment(
+        &self,
+        line_number: u32,
+        context: String,
+        branch: Option<String>,
+        replies: Vec<CommentReply>,
+    ) -> DiffComment {
         DiffComment {
             id: self.id.clone(),
             path: self.path.clone(),
@@ -196,14 +306,31 @@ impl StoredComment {
             line_number,
             line_content: self.line_content.clone(),
             payload: self.payload.clone(),
+            author: self.author.clone(),
             created_at_ms: self.created_at_ms,
             updated_at_ms: self.updated_at_ms,
+            resolved_at_ms: self.resolved_at_ms,
+            resolved_by: self.resolved_by.clone(),
+            branch,
+            replies,
             context: Some(context),
         }
     }
+
+    /// The consumer-facing view of this comment as a reply.
+    fn to_reply(&self) -> CommentReply {
+        CommentReply {
+            id: self.id.clone(),
+            author: self.author.clone(),
+            payload: self.payload.clone(),
+            created_at_ms: self.created_at_ms,
+            updated_at_ms: self.updated_at_ms,
+        }
+    }
 }
 
-/// List all unarchived comments, re-anchored against the current diffs.
+/// List all unarchived threads, re-anchored against the current diffs, with their unarchived
+/// replies. Use a [`ThreadFilter`] to narrow them down, for instance to unresolved threads.
 ///
 /// Each returned comment is guaranteed to point at a line that exists in the current diff of its
 /// file, with `line_number` refreshed (and persisted) if the line drifted, and `context` filled
@@ -220,12 +347,15 @@ pub fn list_comments(
     now_ms: i64,
 ) -> anyhow::Result<Listing> {
     let all = store.read();
-    let purge_cutoff_ms = now_ms - PURGE_ARCHIVED_AFTER_MS;
-    let purge_needed = all.iter().any(|comment| {
-        comment
-            .archived_at_ms
-            .is_some_and(|at| at < purge_cutoff_ms)
-    });
+    let mut replies = HashMap::<String, Vec<CommentReply>>::new();
+    for reply in all.iter().filter(|c| c.archived_at_ms.is_none()) {
+        if let Some(parent_id) = &reply.parent_id {
+            replies
+                .entry(parent_id.clone())
+                .or_default()
+                .push(reply.to_reply());
+        }
+    }
 
     enum Outcome {
         Keep { comment: DiffComment, drifted: bool },
@@ -233,7 +363,11 @@ pub fn list_comments(
     }
     let mut diffs = ScopeDiffs::new(repo, workspace, context_lines);
     let mut outcomes = Vec::new();
-    for row in all.into_iter().filter(|c| c.archived_at_ms.is_none()) {
+    for row in all
+        .into_iter()
+        .filter(|c| c.archived_at_ms.is_none() && c.parent_id.is_none())
+    {
+        let branch = diffs.branch(row.commit_change_id.as_deref())?;
         // An unresolvable scope (e.g. the commit's branch is unapplied) leaves the comment
         // completely untouched: it comes back when the scope does.
         let Some(anchor) = diffs.file(row.commit_change_id.as_deref(), &row.path)? else {
@@ -260,25 +394,29 @@ pub fn list_comments(
         match located {
             Some(line) => {
                 let drifted = line.line_number != row.line_number;
-                let comment = row.to_comment(line.line_number, lines.context_excerpt(line));
+                let comment = row.to_comment(
+                    line.line_number,
+                    lines.context_excerpt(line),
+                    branch,
+                    replies.remove(&row.id).unwrap_or_default(),
+                );
                 outcomes.push(Outcome::Keep { comment, drifted });
             }
             None => outcomes.push(Outcome::Archive { id: row.id }),
         }
     }
 
-    let needs_write = purge_needed
-        || outcomes.iter().any(|outcome| match outcome {
-            Outcome::Keep { drifted, .. } => *drifted,
-            Outcome::Archive { .. } => true,
-        });
+    let needs_write = outcomes.iter().any(|outcome| match outcome {
+        Outcome::Keep { drifted, .. } => *drifted,
+        Outcome::Archive { .. } => true,
+    });
     let mut persisted_changes = false;
     let mut result = Vec::new();
     if needs_write {
-        // Mutations are applied to the freshly re-read state inside the lock, field by field,
-        // so payload edits or archiving that happened while we were re-anchoring survive.
-        // Best-effort: listing is conceptually a read, so a failing persist step (e.g. a held
-        // lock) must not fail it — the same drift/archiving is recomputed on the next call.
+        // Mutations are applied to the freshly re-read state inside the transaction, field by
+        // field, so payload edits or archiving that happened while we were re-anchoring survive.
+        // Best-effort: listing is conceptually a read, so a failing persist step (e.g. a busy
+        // database) must not fail it — the same drift/archiving is recomputed on the next call.
         let persisted = store.update(|comments| {
             for outcome in &outcomes {
                 match outcome {
@@ -295,16 +433,10 @@ pub fn list_comments(
                     }
                     Outcome::Keep { .. } => {}
                     Outcome::Archive { id } => {
-                        if let Some(stored) = comments
-                            .iter_mut()
-                            .find(|c| &c.id == id && c.archived_at_ms.is_none())
-                        {
-                            stored.archived_at_ms = Some(now_ms);
-                        }
+                        archive_thread(comments, id, now_ms);
                     }
                 }
             }
-            comments.retain(|c| c.archived_at_ms.is_none_or(|at| at >= purge_cutoff_ms));
             Ok(())
         });
         match persisted {
@@ -329,8 +461,8 @@ pub fn list_comments(
 pub struct Listing {
     /// The re-anchored, unarchived comments.
     pub comments: Vec<DiffComment>,
-    /// Whether the listing wrote to the store (persisted drift, auto-archived comments, or
-    /// purged old archived rows). Callers that bridge processes can use this to notify other
+    /// Whether the listing wrote to the store (persisted drift or auto-archived comments).
+    /// Callers that bridge processes can use this to notify other
     /// consumers of the store.
     pub persisted_changes: bool,
 }
@@ -355,25 +487,123 @@ pub fn update_payload(
     })
 }
 
-/// Archive the comment with the given `id`, hiding it from all future listings.
+/// Archive the comment with the given `id`, hiding it from all future listings. Archiving the
+/// comment that starts a thread archives its replies as well.
 /// Returns `false` if the comment does not exist or was already archived.
 pub fn archive_comment(store: &CommentStore, id: &str, now_ms: i64) -> anyhow::Result<bool> {
+    store.update(|comments| Ok(archive_thread(comments, id, now_ms)))
+}
+
+/// Archive the unarchived comment with `id` in `comments` along with its replies, and return
+/// `true` if there was such a comment.
+fn archive_thread(comments: &mut [StoredComment], id: &str, now_ms: i64) -> bool {
+    let Some(comment) = comments
+        .iter_mut()
+        .find(|c| c.id == id && c.archived_at_ms.is_none())
+    else {
+        return false;
+    };
+    comment.archived_at_ms = Some(now_ms);
+    for reply in comments
+        .iter_mut()
+        .filter(|c| c.parent_id.as_deref() == Some(id) && c.archived_at_ms.is_none())
+    {
+        reply.archived_at_ms = Some(now_ms);
+    }
+    true
+}
+
+/// Reply to the unarchived comment named in `reply`, or to the thread it is a reply in.
+pub fn reply_to_comment(
+    store: &CommentStore,
+    reply: NewReply,
+    now_ms: i64,
+) -> anyhow::Result<CommentReply> {
     store.update(|comments| {
-        Ok(
-            match comments
-                .iter_mut()
-                .find(|c| c.id == id && c.archived_at_ms.is_none())
-            {
-                Some(comment) => {
-                    comment.archived_at_ms = Some(now_ms);
-                    true
-                }
-                None => false,
-            },
-        )
+        let Some(comment) = comments
+            .iter()
+            .find(|c| c.id == reply.comment_id && c.archived_at_ms.is_none())
+        else {
+            bail!("No unarchived comment with id {}", reply.comment_id);
+        };
+        let thread_id = comment.parent_id.as_deref().unwrap_or(&comment.id);
+        let Some(thread) = comments
+            .iter()
+            .find(|c| c.id == thread_id && c.archived_at_ms.is_none())
+        else {
+            bail!("The thread of comment {} is archived", reply.comment_id);
+        };
+        let stored = StoredComment {
+            id: reply.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            parent_id: Some(thread.id.clone()),
+            author: reply.author,
+            payload: reply.payload,
+            created_at_ms: now_ms,
+            updated_at_ms: now_ms,
+            archived_at_ms: None,
+            resolved_at_ms: None,
+            resolved_by: None,
+            ..thread.clone()
+        };
+        let result = stored.to_reply();
+        comments.push(stored);
+        Ok(result)
+    })
+}
+
+/// Mark the thread that the unarchived comment with `id` belongs to as resolved by `resolved_by`.
+/// Returns `false` if the thread was already resolved.
+pub fn resolve_thread(
+    store: &CommentStore,
+    id: &str,
+    resolved_by: Option<String>,
+    now_ms: i64,
+) -> anyhow::Result<bool> {
+    store.update(|comments| {
+        let thread = find_thread(comments, id)?;
+        if thread.resolved_at_ms.is_some() {
+            return Ok(false);
+        }
+        thread.resolved_at_ms = Some(now_ms);
+        thread.resolved_by = resolved_by;
+        thread.updated_at_ms = now_ms;
+        Ok(true)
+    })
+}
+
+/// Mark the thread that the unarchived comment with `id` belongs to as unresolved again.
+/// Returns `false` if the thread wasn't resolved.
+pub fn reopen_thread(store: &CommentStore, id: &str, now_ms: i64) -> anyhow::Result<bool> {
+    store.update(|comments| {
+        let thread = find_thread(comments, id)?;
+        if thread.resolved_at_ms.is_none() {
+            return Ok(false);
+        }
+        thread.resolved_at_ms = None;
+        thread.resolved_by = None;
+        thread.updated_at_ms = now_ms;
+        Ok(true)
     })
 }
 
+/// Find the unarchived comment that starts the thread the comment with `id` belongs to.
+fn find_thread<'a>(
+    comments: &'a mut [StoredComment],
+    id: &str,
+) -> anyhow::Result<&'a mut StoredComment> {
+    let Some(comment) = comments
+        .iter()
+        .find(|c| c.id == id && c.archived_at_ms.is_none())
+    else {
+        bail!("No unarchived comment with id {id}");
+    };
+    let thread_id = comment.parent_id.clone().unwrap_or_else(|| id.to_owned());
+    comments
+        .iter_mut()
+        .find(|c| c.id == thread_id && c.archived_at_ms.is_none())
+        .with_context(|| format!("The thread of comment {id} is archived"))
+}
+
 fn anchor_scope_display(commit_change_id: &Option<String>, path: &str) -> String {
     match commit_change_id {
         None => format!("the uncommitted changes of {path}"),
@@ -398,7 +628,7 @@ struct ScopeDiffs<'a> {
     repo: &'a gix::Repository,
     workspace: &'a but_graph::Workspace,
     context_lines: u32,
-    change_ids: Option<HashMap<String, gix::ObjectId>>,
+    change_ids: Option<HashMap<String, IndexedCommit>>,
     /// Tree changes per anchor scope; `None` when the scope could not be resolved.
     changes: HashMap<Option<String>, Option<Vec<but_core::TreeChange>>>,
     files: HashMap<(Option<String>, String), FileAnchor>,
@@ -443,6 +673,25 @@ impl<'a> ScopeDiffs<'a> {
         Ok(Some(&self.files[&file_key]))
     }
 
+    /// The short name of the branch containing the commit of the scope, if there is one.
+    fn branch(&mut self, scope: Option<&str>) -> anyhow::Result<Option<String>> {
+        let Some(change_id) = scope else {
+            return Ok(None);
+        };
+        Ok(self
+            .commit(change_id)?
+            .and_then(|commit| commit.branch.clone()))
+    }
+
+    /// The workspace commit with `change_id`, if there is one.
+    fn commit(&mut self, change_id: &str) -> anyhow::Result<Option<&IndexedCommit>> {
+        let change_ids = match &mut self.change_ids {
+            Some(index) => index,
+            slot => slot.insert(change_id_index(self.repo, self.workspace)?),
+        };
+        Ok(change_ids.get(change_id))
+    }
+
     fn changes_for_scope(
         &mut self,
         scope: Option<&str>,
@@ -450,11 +699,7 @@ impl<'a> ScopeDiffs<'a> {
         match scope {
             None => Ok(Some(but_core::diff::worktree_changes(self.repo)?.changes)),
             Some(change_id) => {
-                let change_ids = match &mut self.change_ids {
-                    Some(index) => index,
-                    slot => slot.insert(change_id_index(self.repo, self.workspace)?),
-                };
-                let Some(&commit_id) = change_ids.get(change_id) else {
+                let Some(commit_id) = self.commit(change_id)?.map(|commit| commit.id) else {
                     return Ok(None);
                 };
                 Ok(Some(
@@ -493,20 +738,33 @@ fn file_diff_lines(
     }
 }
 
+/// A workspace commit as found by its change-id.
+struct IndexedCommit {
+    id: gix::ObjectId,
+    /// The short name of the branch whose segment contains the commit.
+    branch: Option<String>,
+}
+
 /// Index every commit of every applied stack by its change-id, in one scan.
 fn change_id_index(
     repo: &gix::Repository,
     workspace: &but_graph::Workspace,
-) -> anyhow::Result<HashMap<String, gix::ObjectId>> {
+) -> anyhow::Result<HashMap<String, IndexedCommit>> {
     let mut index = HashMap::new();
     for stack in &workspace.stacks {
         for segment in &stack.segments {
+            let branch = segment
+                .ref_name()
+                .map(|ref_name| ref_name.shorten().to_string());
             for commit in &segment.commits {
                 let commit = but_core::Commit::from_id(commit.id.attach(repo))?;
                 // The child-most commit wins when a change-id occurs twice.
                 index
                     .entry(commit.change_id().to_string())
-                    .or_insert_with(|| commit.id.detach());
+                    .or_insert_with(|| IndexedCommit {
+                        id: commit.id.detach(),
+                        branch: branch.clone(),
+                    });
             }
         }
     }
@@ -0,0 +1,271 @@
+//! Sharing comments on committed changes with a Git notes ref, so teammates who fetch it see the
+//! same anchored comments.
+//!
+//! Comments are anchored to commits by change-id, which survives amends and rebases, while
+//! `git notes` are keyed by object id. So instead of the fan-out of `git notes`, the tree of
+//! [`NOTES_REF`] has one blob per change-id with the JSON array of all comments on that commit.
+//!
+//! Syncing goes both ways: comments from the notes that are unknown or outdated in the
+//! [`CommentStore`] are stored, and then all comments on commits are written back to the notes.
+//! Transferring the ref is left to Git, for example with `git push origin refs/notes/gitbutler/comments`
+//! and `git fetch origin +refs/notes/gitbutler/comments:refs/notes/gitbutler/comments`.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+use bstr::ByteSlice;
+use gix::object::tree::EntryKind;
+use serde::Serialize;
+
+use crate::{CommentStore, StoredComment};
+
+/// The reference whose tree holds the comments on commits, keyed by change-id.
+pub const NOTES_REF: &str = "refs/notes/gitbutler/comments";
+/// The Git configuration key that enables syncing comments with [`NOTES_REF`] whenever they change.
+pub const SYNC_CONFIG_KEY: &str = "gitbutler.commentNotes";
+
+/// Return `true` if comments of `repo` should be synced with [`NOTES_REF`], as configured with [`SYNC_CONFIG_KEY`].
+pub fn is_sync_enabled(repo: &gix::Repository) -> bool {
+    repo.config_snapshot()
+        .boolean(SYNC_CONFIG_KEY)
+        .unwrap_or(false)
+}
+
+/// The result of [`sync_with_notes`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NotesSync {
+    /// The amount of comments that were added or updated from the notes.
+    pub imported: usize,
+    /// The amount of comments written to the notes, or `0` if the notes didn't change.
+    pub exported: usize,
+}
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(NotesSync);
+
+/// Merge the comments in [`NOTES_REF`] of `repo` into `store`, and write all comments on commits
+/// in `store` back to a new notes commit if that changes them.
+///
+/// When a comment exists on both sides, the most recently updated version wins, and it stays
+/// archived if it was archived on either side.
+pub fn sync_with_notes(repo: &gix::Repository, store: &CommentStore) -> anyhow::Result<NotesSync> {
+    let notes_commit = repo
+        .try_find_reference(NOTES_REF)?
+        .map(|mut reference| reference.peel_to_commit())
+        .transpose()?;
+    let (notes_commit_id, notes_tree_id, noted) = match notes_commit {
+        Some(commit) => {
+            let tree = commit.tree()?;
+            (Some(commit.id), Some(tree.id), read_notes(&tree)?)
+        }
+        None => (None, None, Vec::new()),
+    };
+
+    let (imported, comments) = store.update(|comments| {
+        let mut imported = 0;
+        for noted in noted {
+            match comments.iter_mut().find(|comment| comment.id == noted.id) {
+                Some(local) => {
+                    let merged = merge(local, noted);
+                    if merged != *local {
+                        *local = merged;
+                        imported += 1;
+                    }
+                }
+                None => {
+                    comments.push(noted);
+                    imported += 1;
+                }
+            }
+        }
+        Ok((imported, comments.clone()))
+    })?;
+
+    let mut by_change_id = BTreeMap::<&str, Vec<&StoredComment>>::new();
+    for comment in &comments {
+        if let Some(change_id) = comment
+            .commit_change_id
+            .as_deref()
+            .filter(|change_id| is_valid_entry_name(change_id))
+        {
+            by_change_id.entry(change_id).or_default().push(comment);
+        }
+    }
+    if by_change_id.is_empty() && notes_commit_id.is_none() {
+        return Ok(NotesSync {
+            imported,
+            exported: 0,
+        });
+    }
+
+    let mut tree = repo.empty_tree().edit()?;
+    for (change_id, comments) in &by_change_id {
+        let blob = repo.write_blob(serde_json::to_vec_pretty(comments)?)?;
+        tree.upsert(*change_id, EntryKind::Blob, blob)?;
+    }
+    let tree_id = tree.write()?.detach();
+    let exported = if notes_tree_id == Some(tree_id) {
+        0
+    } else {
+        repo.commit(
+            NOTES_REF,
+            "Update comments from GitButler",
+            tree_id,
+            notes_commit_id,
+        )
+        .context("Failed to write the comments notes")?;
+        by_change_id.values().map(Vec::len).sum()
+    };
+    Ok(NotesSync { imported, exported })
+}
+
+/// Read all comments from the notes `tree`, skipping entries that can't be parsed.
+fn read_notes(tree: &gix::Tree<'_>) -> anyhow::Result<Vec<StoredComment>> {
+    let mut comments = Vec::new();
+    for entry in tree.iter() {
+        let entry = entry?;
+        if !entry.mode().is_blob() {
+            continue;
+        }
+        let change_id = entry.filename().to_str_lossy().into_owned();
+        let blob = entry.object()?;
+        match serde_json::from_slice::<Vec<StoredComment>>(&blob.data) {
+            Ok(noted) => comments.extend(noted.into_iter().map(|comment| StoredComment {
+                commit_change_id: Some(change_id.clone()),
+                ..comment
+            })),
+            Err(err) => {
+                tracing::warn!("ignoring unparsable comments notes of change {change_id}: {err}")
+            }
+        }
+    }
+    Ok(comments)
+}
+
+/// Merge the `local` and `noted` versions of the same comment.
+fn merge(local: &StoredComment, noted: StoredComment) -> StoredComment {
+    let archived_at_ms = match (local.archived_at_ms, noted.archived_at_ms) {
+        (Some(local), Some(noted)) => Some(local.min(noted)),
+        (local, noted) => local.or(noted),
+    };
+    let mut merged = if noted.updated_at_ms > local.updated_at_ms {
+        noted
+    } else {
+        local.clone()
+    };
+    merged.archived_at_ms = archived_at_ms;
+    merged
+}
+
+/// Return `true` if `change_id` can be the name of a tree entry.
+fn is_valid_entry_name(change_id: &str) -> bool {
+    !change_id.is_empty()
+        && change_id != "."
+        && change_id != ".."
+        && !change_id.contains(['/', '\0'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiffSide;
+
+    fn repo(dir: &std::path::Path) -> anyhow::Result<gix::Repository> {
+        let mut repo = gix::init(dir)?;
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value("user.name", "Author")?;
+        config.set_raw_value("user.email", "author@example.com")?;
+        config.commit()?;
+        Ok(repo)
+    }
+
+    fn stored(id: &str, commit_change_id: Option<&str>) -> StoredComment {
+        StoredComment {
+            id: id.to_string(),
+            path: "src/a.rs".to_string(),
+            commit_change_id: commit_change_id.map(ToOwned::to_owned),
+            side: DiffSide::New,
+            line_number: 15,
+            line_content: "let x = 1;".to_string(),
+            line_before: None,
+            line_after: None,
+            payload: "hello".to_string(),
+            created_at_ms: 1000,
+            updated_at_ms: 1000,
+            archived_at_ms: None,
+            parent_id: None,
+            author: None,
+            resolved_at_ms: None,
+            resolved_by: None,
+        }
+    }
+
+    #[test]
+    fn comments_on_commits_round_trip_between_stores() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let repo = repo(&tmp.path().join("repo"))?;
+        let ours = CommentStore::from_project_data_dir(tmp.path().join("ours"));
+        let theirs = CommentStore::from_project_data_dir(tmp.path().join("theirs"));
+
+        ours.update(|comments| {
+            comments.extend([stored("1", Some("change-a")), stored("2", None)]);
+            Ok(())
+        })?;
+        assert_eq!(
+            sync_with_notes(&repo, &ours)?,
+            NotesSync {
+                imported: 0,
+                exported: 1
+            },
+            "only comments on commits are shared"
+        );
+        assert_eq!(
+            sync_with_notes(&repo, &ours)?,
+            NotesSync::default(),
+            "unchanged notes aren't written again"
+        );
+
+        assert_eq!(
+            sync_with_notes(&repo, &theirs)?,
+            NotesSync {
+                imported: 1,
+                exported: 0
+            }
+        );
+        assert_eq!(theirs.read(), [stored("1", Some("change-a"))]);
+
+        theirs.update(|comments| {
+            comments[0].payload = "edited".into();
+            comments[0].updated_at_ms = 2000;
+            comments[0].archived_at_ms = Some(2000);
+            Ok(())
+        })?;
+        sync_with_notes(&repo, &theirs)?;
+        sync_with_notes(&repo, &ours)?;
+
+        let synced = ours.read();
+        assert_eq!(synced[0].payload, "edited", "the newer version wins");
+        assert_eq!(synced[0].archived_at_ms, Some(2000));
+        assert_eq!(
+            synced[1],
+            stored("2", None),
+            "uncommitted comments are untouched"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn archiving_on_either_side_is_kept() {
+        let local = StoredComment {
+            updated_at_ms: 2000,
+            archived_at_ms: Some(1500),
+            ..stored("1", Some("change-a"))
+        };
+        let noted = StoredComment {
+            payload: "older".into(),
+            ..stored("1", Some("change-a"))
+        };
+        assert_eq!(merge(&local, noted), local);
+    }
+}
@@ -1,34 +1,30 @@
-//! File-backed storage for comments: a single JSON file in the project data directory
-//! (`.git/gitbutler/comments.json` for CLI-registered projects).
+//! Storage for comments in the `comments` table of the project database
+//! (`.git/gitbutler/but.sqlite` for CLI-registered projects), where they survive and can be queried.
 //!
-//! Comments are ephemeral, so the storage is deliberately the simplest thing that is safe under
-//! concurrent GUI and CLI access: reads parse the whole file (writes go through atomic renames,
-//! so a partial file is never observed), and every mutation re-reads the file under an exclusive
-//! lock before rewriting it, so concurrent writers cannot clobber each other's changes.
+//! Every mutation re-reads the stored comments in an *immediate* transaction and writes back only what changed,
+//! so concurrent GUI and CLI writers cannot clobber each other's changes.
 //!
-//! There is no downgrade protection: an older binary reads a newer file version as empty and
-//! overwrites it on its next write — acceptable for data that is ephemeral by contract.
+//! Comments used to live in a JSON file in the project data directory. When such a file is found,
+//! its comments are imported into the database once, and the file is removed afterwards.
 
-use std::io::Write as _;
 use std::path::PathBuf;
 
-use anyhow::Context as _;
+use anyhow::{Context as _, bail};
 use serde::{Deserialize, Serialize};
 
 use crate::DiffSide;
 
-const FILE_NAME: &str = "comments.json";
-const FILE_VERSION: u32 = 1;
-/// Writers hold the lock for milliseconds; a lock file older than this was left behind by a
-/// crashed process (`gix-lock` only cleans up on drop, not after SIGKILL) and would otherwise
-/// brick all writes forever.
-const STALE_LOCK_AGE: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+const LEGACY_FILE_NAME: &str = "comments.json";
+const LEGACY_FILE_VERSION: u32 = 1;
 
-/// A comment as persisted in the comments file. See [`crate::DiffComment`] for the field
+/// A comment as persisted in the project database. See [`crate::DiffComment`] for the field
 /// semantics; additionally `line_before`/`line_after` snapshot the same-side neighbouring diff
 /// lines (when they existed) to disambiguate identical lines during re-anchoring, and
-/// `archived_at_ms` marks archived comments, which are kept for a while so that archiving twice
-/// stays a no-op, and purged eventually.
+/// `archived_at_ms` marks archived comments, which are kept so that archiving twice
+/// stays a no-op.
+///
+/// Replies are stored like the comment that starts their thread, with `parent_id` pointing to it
+/// and a copy of its anchor, so replies travel with their thread when syncing.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(missing_docs)]
@@ -45,18 +41,80 @@ pub struct StoredComment {
     pub created_at_ms: i64,
     pub updated_at_ms: i64,
     pub archived_at_ms: Option<i64>,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub resolved_at_ms: Option<i64>,
+    #[serde(default)]
+    pub resolved_by: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct CommentsFile {
+impl TryFrom<but_db::Comment> for StoredComment {
+    type Error = anyhow::Error;
+
+    fn try_from(row: but_db::Comment) -> anyhow::Result<Self> {
+        let side = match row.side.as_str() {
+            "old" => DiffSide::Old,
+            "new" => DiffSide::New,
+            other => bail!("comment {} has an invalid diff side: {other:?}", row.id),
+        };
+        Ok(StoredComment {
+            id: row.id,
+            path: row.path,
+            commit_change_id: row.commit_change_id,
+            side,
+            line_number: row.line_number,
+            line_content: row.line_content,
+            line_before: row.line_before,
+            line_after: row.line_after,
+            payload: row.payload,
+            created_at_ms: row.created_at_ms,
+            updated_at_ms: row.updated_at_ms,
+            archived_at_ms: row.archived_at_ms,
+            parent_id: row.parent_id,
+            author: row.author,
+            resolved_at_ms: row.resolved_at_ms,
+            resolved_by: row.resolved_by,
+        })
+    }
+}
+
+impl From<StoredComment> for but_db::Comment {
+    fn from(comment: StoredComment) -> Self {
+        but_db::Comment {
+            id: comment.id,
+            path: comment.path,
+            commit_change_id: comment.commit_change_id,
+            side: comment.side.as_str().to_owned(),
+            line_number: comment.line_number,
+            line_content: comment.line_content,
+            line_before: comment.line_before,
+            line_after: comment.line_after,
+            payload: comment.payload,
+            created_at_ms: comment.created_at_ms,
+            updated_at_ms: comment.updated_at_ms,
+            archived_at_ms: comment.archived_at_ms,
+            parent_id: comment.parent_id,
+            author: comment.author,
+            resolved_at_ms: comment.resolved_at_ms,
+            resolved_by: comment.resolved_by,
+        }
+    }
+}
+
+/// The format of the JSON file comments were stored in before they moved into the database.
+#[derive(Debug, Deserialize)]
+struct LegacyCommentsFile {
     version: u32,
     comments: Vec<StoredComment>,
 }
 
-/// Handle on a project's comments file.
+/// Handle on a project's comments in its database.
 #[derive(Debug, Clone)]
 pub struct CommentStore {
-    file_path: PathBuf,
+    project_data_dir: PathBuf,
 }
 
 impl CommentStore {
@@ -64,34 +122,37 @@ impl CommentStore {
     /// (`.git/gitbutler` for CLI-registered projects).
     pub fn from_project_data_dir(project_data_dir: impl Into<PathBuf>) -> Self {
         CommentStore {
-            file_path: project_data_dir.into().join(FILE_NAME),
+            project_data_dir: project_data_dir.into(),
         }
     }
 
-    /// All stored comments, archived or not, in insertion order. A missing, unreadable, or
-    /// incompatible file reads as an empty store — comments are ephemeral and must never take
-    /// anything else down with them.
+    /// All stored comments, archived or not, in insertion order. A database that can't be read
+    /// reads as an empty store — comments must never take anything else down with them.
     pub fn read(&self) -> Vec<StoredComment> {
-        let Ok(bytes) = std::fs::read(&self.file_path) else {
-            return Vec::new();
-        };
-        match serde_json::from_slice::<CommentsFile>(&bytes) {
-            Ok(file) if file.version == FILE_VERSION => file.comments,
-            Ok(_) => Vec::new(),
+        let comments = self.open().and_then(|db| {
+            db.comments()
+                .list()?
+                .into_iter()
+                .map(StoredComment::try_from)
+                .collect()
+        });
+        match comments {
+            Ok(comments) => comments,
             Err(err) => {
                 tracing::warn!(
-                    "ignoring unparsable comments file at {:?}: {err}",
-                    self.file_path
+                    "ignoring unreadable comments in {:?}: {err:#}",
+                    self.project_data_dir
                 );
                 Vec::new()
             }
         }
     }
 
-    /// Mutate the stored comments under an exclusive lock and write them back atomically.
+    /// Mutate the stored comments in a single transaction and write back what changed:
+    /// comments that were added or modified are stored, and those that were removed are deleted.
     /// When `mutate` fails, nothing is written.
     ///
-    /// The file is re-read *inside* the lock, so mutations must be expressed against the
+    /// The comments are re-read *inside* the transaction, so mutations must be expressed against the
     /// freshest state (find a comment by id and update a field) — never as a write-back of
     /// previously read data. That discipline is what keeps concurrent GUI and CLI writers from
     /// clobbering each other.
@@ -99,46 +160,72 @@ impl CommentStore {
         &self,
         mutate: impl FnOnce(&mut Vec<StoredComment>) -> anyhow::Result<R>,
     ) -> anyhow::Result<R> {
-        let parent = self
-            .file_path
-            .parent()
-            .context("comments file has a parent directory")?;
-        std::fs::create_dir_all(parent)?;
-        self.remove_stale_lock();
-        let mut lock = gix::lock::File::acquire_to_update_resource(
-            &self.file_path,
-            gix::lock::acquire::Fail::AfterDurationWithBackoff(std::time::Duration::from_millis(
-                2500,
-            )),
-            None,
-        )?;
-        let mut comments = self.read();
+        let mut db = self.open()?;
+        let mut trans = db.immediate_transaction()?;
+        let before = trans
+            .comments()
+            .list()?
+            .into_iter()
+            .map(StoredComment::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut comments = before.clone();
         let result = mutate(&mut comments)?;
-        let file = CommentsFile {
-            version: FILE_VERSION,
-            comments,
-        };
-        lock.write_all(serde_json::to_string_pretty(&file)?.as_bytes())?;
-        lock.commit()
-            .map_err(|err| err.error)
-            .with_context(|| format!("failed to commit comments file at {:?}", self.file_path))?;
+
+        let mut table = trans.comments_mut();
+        for removed in before
+            .iter()
+            .filter(|old| !comments.iter().any(|comment| comment.id == old.id))
+        {
+            table.delete(&removed.id)?;
+        }
+        for changed in comments
+            .into_iter()
+            .filter(|comment| !before.contains(comment))
+        {
+            table.upsert(changed.into())?;
+        }
+        trans.commit()?;
         Ok(result)
     }
 
-    /// Remove a leftover lock file from a crashed process so writes don't stay bricked forever.
-    fn remove_stale_lock(&self) {
-        let mut lock_path = self.file_path.clone().into_os_string();
-        lock_path.push(".lock");
-        let lock_path = PathBuf::from(lock_path);
-        let is_stale = std::fs::metadata(&lock_path)
-            .and_then(|meta| meta.modified())
-            .ok()
-            .and_then(|modified| modified.elapsed().ok())
-            .is_some_and(|age| age > STALE_LOCK_AGE);
-        if is_stale {
-            tracing::warn!(?lock_path, "removing stale comments lock file");
-            let _ = std::fs::remove_file(&lock_path);
+    fn open(&self) -> anyhow::Result<but_db::DbHandle> {
+        let mut db = but_db::DbHandle::new_in_directory(&self.project_data_dir)?;
+        if let Err(err) = self.import_legacy_file(&mut db) {
+            tracing::warn!("could not import the legacy comments file: {err:#}");
+        }
+        Ok(db)
+    }
+
+    /// Move the comments of a legacy comments file, if there is one, into `db` and remove the file.
+    /// Comments that already exist in `db` are left as they are.
+    fn import_legacy_file(&self, db: &mut but_db::DbHandle) -> anyhow::Result<()> {
+        let file_path = self.project_data_dir.join(LEGACY_FILE_NAME);
+        let Ok(bytes) = std::fs::read(&file_path) else {
+            return Ok(());
+        };
+        let comments = match serde_json::from_slice::<LegacyCommentsFile>(&bytes) {
+            Ok(file) if file.version == LEGACY_FILE_VERSION => file.comments,
+            // Keep what can't be imported, but don't try again.
+            _ => {
+                let mut backup_path = file_path.clone().into_os_string();
+                backup_path.push(".bak");
+                tracing::warn!(
+                    "moving unparsable comments file at {file_path:?} to {backup_path:?}"
+                );
+                return std::fs::rename(&file_path, backup_path)
+                    .context("failed to move the unparsable comments file aside");
+            }
+        };
+
+        let mut trans = db.immediate_transaction()?;
+        for comment in comments {
+            if trans.comments().get(&comment.id)?.is_none() {
+                trans.comments_mut().upsert(comment.into())?;
+            }
         }
+        trans.commit()?;
+        std::fs::remove_file(&file_path)?;
+        Ok(())
     }
 }
 
@@ -160,11 +247,15 @@ mod tests {
             created_at_ms: 1000,
             updated_at_ms: 1000,
             archived_at_ms: None,
+            parent_id: None,
+            author: None,
+            resolved_at_ms: None,
+            resolved_by: None,
         }
     }
 
     #[test]
-    fn missing_file_reads_as_empty() {
+    fn empty_store_reads_as_empty() {
         let dir = tempfile::tempdir().unwrap();
         let store = CommentStore::from_project_data_dir(dir.path());
         assert_eq!(store.read(), Vec::new());
@@ -192,6 +283,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn update_writes_modifications_and_removals() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CommentStore::from_project_data_dir(dir.path());
+        store.update(|comments| {
+            comments.extend([stored("1"), stored("2"), stored("3")]);
+            Ok(())
+        })?;
+
+        store.update(|comments| {
+            comments.retain(|c| c.id != "2");
+            comments[0].payload = "changed".into();
+            Ok(())
+        })?;
+
+        let read = store.read();
+        let ids: Vec<&str> = read.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["1", "3"], "the removed comment is deleted");
+        assert_eq!(read[0].payload, "changed");
+        Ok(())
+    }
+
     #[test]
     fn failing_mutation_writes_nothing() -> anyhow::Result<()> {
         let dir = tempfile::tempdir().unwrap();
@@ -215,40 +328,35 @@ mod tests {
     }
 
     #[test]
-    fn stale_locks_are_broken() -> anyhow::Result<()> {
+    fn legacy_file_is_imported_once() -> anyhow::Result<()> {
         let dir = tempfile::tempdir().unwrap();
-        let store = CommentStore::from_project_data_dir(dir.path());
-
-        let lock_path = dir.path().join(format!("{FILE_NAME}.lock"));
-        std::fs::write(&lock_path, b"")?;
-        let stale = std::time::SystemTime::now() - (STALE_LOCK_AGE + STALE_LOCK_AGE);
-        std::fs::File::options()
-            .write(true)
-            .open(&lock_path)?
-            .set_modified(stale)?;
+        let legacy = serde_json::json!({
+            "version": LEGACY_FILE_VERSION,
+            "comments": [stored("1"), stored("2")],
+        });
+        std::fs::write(
+            dir.path().join(LEGACY_FILE_NAME),
+            serde_json::to_vec(&legacy)?,
+        )?;
 
-        // The stale lock is removed and the write succeeds instead of timing out.
-        store.update(|comments| {
-            comments.push(stored("1"));
-            Ok(())
-        })?;
-        assert_eq!(store.read().len(), 1);
+        let store = CommentStore::from_project_data_dir(dir.path());
+        assert_eq!(store.read(), [stored("1"), stored("2")]);
+        assert!(
+            !dir.path().join(LEGACY_FILE_NAME).exists(),
+            "the imported file is removed"
+        );
         Ok(())
     }
 
     #[test]
-    fn incompatible_or_corrupt_files_read_as_empty() -> anyhow::Result<()> {
+    fn unparsable_legacy_file_is_moved_aside() -> anyhow::Result<()> {
         let dir = tempfile::tempdir().unwrap();
         let store = CommentStore::from_project_data_dir(dir.path());
 
-        std::fs::write(dir.path().join(FILE_NAME), b"not json at all")?;
-        assert_eq!(store.read(), Vec::new());
-
-        std::fs::write(
-            dir.path().join(FILE_NAME),
-            br#"{"version":999,"comments":[]}"#,
-        )?;
+        std::fs::write(dir.path().join(LEGACY_FILE_NAME), b"not json at all")?;
         assert_eq!(store.read(), Vec::new());
+        assert!(!dir.path().join(LEGACY_FILE_NAME).exists());
+        assert!(dir.path().join(format!("{LEGACY_FILE_NAME}.bak")).exists());
         Ok(())
     }
 }
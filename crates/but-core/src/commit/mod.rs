@@ -338,7 +338,8 @@ pub fn sign_buffer(repo: &gix::Repository, buffer: &[u8]) -> anyhow::Result<BStr
     let config = repo.config_snapshot();
     let signing_key = signing_key(repo)?;
     let sign_format = config.string("gpg.format");
-    let is_ssh = sign_format.is_some_and(|value| value == "ssh");
+    let is_ssh = sign_format.as_deref().is_some_and(|value| value == "ssh");
+    let is_x509 = sign_format.as_deref().is_some_and(|value| value == "x509");
 
     if is_ssh {
         let mut signature_storage = tempfile::NamedTempFile::new()?;
@@ -397,9 +398,15 @@ pub fn sign_buffer(repo: &gix::Repository, buffer: &[u8]) -> anyhow::Result<BStr
             bail!("Failed to sign SSH: {stdout} {stderr}");
         }
     } else {
-        let gpg_program = match config.trusted_path("gpg.program")? {
+        // X.509 signatures are made by `gpgsm`, which takes the same arguments as `gpg`.
+        let (program_key, default_program) = if is_x509 {
+            ("gpg.x509.program", "gpgsm")
+        } else {
+            ("gpg.program", "gpg")
+        };
+        let gpg_program = match config.trusted_path(program_key)? {
             Some(program) if !program.as_os_str().is_empty() => program,
-            _ => Path::new("gpg").into(),
+            _ => Path::new(default_program).into(),
         };
 
         let mut cmd = into_command(
@@ -416,7 +423,7 @@ pub fn sign_buffer(repo: &gix::Repository, buffer: &[u8]) -> anyhow::Result<BStr
             Ok(child) => child,
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
                 bail!(
-                    "Could not find '{}'. Please make sure it is in your `PATH` or configure the full path using `gpg.program` in the Git configuration",
+                    "Could not find '{}'. Please make sure it is in your `PATH` or configure the full path using `{program_key}` in the Git configuration",
                     gpg_program.display()
                 )
             }
@@ -432,7 +439,8 @@ pub fn sign_buffer(repo: &gix::Repository, buffer: &[u8]) -> anyhow::Result<BStr
         } else {
             let stderr = BString::new(output.stderr);
             let stdout = BString::new(output.stdout);
-            bail!("Failed to sign GPG: {stdout} {stderr}");
+            let kind = if is_x509 { "X.509" } else { "GPG" };
+            bail!("Failed to sign {kind}: {stdout} {stderr}");
         }
     }
 }
@@ -845,6 +853,7 @@ use conflict::message_is_conflicted;
 pub use conflict::{
     add_conflict_markers, rewrite_conflict_markers_on_message_change, strip_conflict_markers,
 };
+pub mod signature;
 pub mod tree_expression;
 
 /// Write a GitButler conflicted tree that wraps `resolved_tree_id` together
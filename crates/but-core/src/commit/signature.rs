@@ -0,0 +1,569 @@
+//! Verification of commit signatures, the counterpart of [`sign_buffer()`](super::sign_buffer()).
+//!
+//! Verification follows what `git verify-commit` does. SSH signatures are checked against
+//! `gpg.ssh.allowedSignersFile`, and OpenPGP and X.509 signatures against the keyring of
+//! `gpg.program` and `gpg.x509.program` respectively.
+use std::{
+    borrow::Cow,
+    io::Write,
+    path::Path,
+    process::{Output, Stdio},
+};
+
+use bstr::{BStr, ByteSlice};
+use serde::Serialize;
+
+use crate::cmd::prepare_with_shell_on_windows;
+
+/// The kind of a commit signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "export-schema", schemars(rename = "SignatureFormat"))]
+#[serde(rename_all = "camelCase")]
+pub enum Format {
+    /// An OpenPGP signature, made with `gpg`.
+    OpenPgp,
+    /// An SSH signature, made with `ssh-keygen`.
+    Ssh,
+    /// An X.509 signature, made with `gpgsm`.
+    X509,
+}
+
+impl Format {
+    /// Determine the format from the armor of `signature`, or return `None` if it isn't known.
+    pub fn detect(signature: &BStr) -> Option<Self> {
+        [
+            ("-----BEGIN PGP SIGNATURE-----", Format::OpenPgp),
+            ("-----BEGIN PGP MESSAGE-----", Format::OpenPgp),
+            ("-----BEGIN SSH SIGNATURE-----", Format::Ssh),
+            ("-----BEGIN SIGNED MESSAGE-----", Format::X509),
+        ]
+        .into_iter()
+        .find_map(|(armor, format)| {
+            signature
+                .trim_start()
+                .starts_with_str(armor)
+                .then_some(format)
+        })
+    }
+}
+
+/// The outcome of verifying a commit signature, like `%G?` in `git log --format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "export-schema", schemars(rename = "SignatureStatus"))]
+#[serde(rename_all = "camelCase")]
+pub enum Status {
+    /// The commit isn't signed.
+    Unsigned,
+    /// The signature is valid and was made by a trusted key.
+    Good,
+    /// The signature is valid, but its key isn't trusted, either because it's not listed in
+    /// `gpg.ssh.allowedSignersFile`, or because its trust level is below `gpg.minTrustLevel`,
+    /// which requires at least marginal trust by default.
+    Untrusted,
+    /// The signature doesn't match the commit.
+    Bad,
+    /// The signature is valid, but has expired.
+    Expired,
+    /// The signature is valid, but was made by a key that has expired.
+    ExpiredKey,
+    /// The signature was made by a key that has been revoked.
+    RevokedKey,
+    /// The signature couldn't be checked, for instance because its key isn't known or
+    /// the program to verify it isn't available.
+    Unverifiable,
+}
+
+impl Status {
+    /// Return `true` if the signature is valid and made by a trusted key.
+    pub fn is_good(&self) -> bool {
+        matches!(self, Status::Good)
+    }
+
+    /// Return the letter `git log --format=%G?` would show for this status.
+    pub fn as_char(&self) -> char {
+        match self {
+            Status::Unsigned => 'N',
+            Status::Good => 'G',
+            Status::Untrusted => 'U',
+            Status::Bad => 'B',
+            Status::Expired => 'X',
+            Status::ExpiredKey => 'Y',
+            Status::RevokedKey => 'R',
+            Status::Unverifiable => 'E',
+        }
+    }
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Status::Unsigned => "unsigned",
+            Status::Good => "good",
+            Status::Untrusted => "untrusted",
+            Status::Bad => "bad",
+            Status::Expired => "expired",
+            Status::ExpiredKey => "expired key",
+            Status::RevokedKey => "revoked key",
+            Status::Unverifiable => "unverifiable",
+        })
+    }
+}
+
+/// The signature of a commit along with the outcome of its verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct Signature {
+    /// The outcome of the verification.
+    pub status: Status,
+    /// The kind of signature, or `None` if the commit is unsigned or the format isn't known.
+    pub format: Option<Format>,
+    /// Who made the signature, as far as the verifying program could tell.
+    ///
+    /// This is the user ID for OpenPGP and X.509 signatures, and the principal from
+    /// the allowed signers file for SSH signatures.
+    pub signer: Option<String>,
+    /// The fingerprint or ID of the key that made the signature.
+    pub key: Option<String>,
+}
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(Signature);
+
+impl Signature {
+    /// The signature of an unsigned commit.
+    pub fn unsigned() -> Self {
+        Self::with_status(Status::Unsigned, None)
+    }
+
+    fn with_status(status: Status, format: Option<Format>) -> Self {
+        Signature {
+            status,
+            format,
+            signer: None,
+            key: None,
+        }
+    }
+}
+
+/// Verify the signature of the commit with `commit_id` in `repo`, using the programs and
+/// trust settings in its Git configuration.
+///
+/// Failing to run the verification program isn't an error, but yields
+/// [`Status::Unverifiable`].
+pub fn verify(repo: &gix::Repository, commit_id: gix::ObjectId) -> anyhow::Result<Signature> {
+    let commit = repo.find_commit(commit_id)?;
+    let Some((signature, signed_data)) = commit.signature()? else {
+        return Ok(Signature::unsigned());
+    };
+    let payload = signed_data.to_bstring();
+    let format = Format::detect(signature.as_ref());
+    let res = match format {
+        Some(Format::Ssh) => verify_ssh(repo, signature.as_ref(), &payload),
+        Some(format) => verify_gpg(repo, format, signature.as_ref(), &payload),
+        None => return Ok(Signature::with_status(Status::Unverifiable, None)),
+    };
+    match res {
+        Ok(signature) => Ok(signature),
+        Err(err) => {
+            tracing::warn!(?commit_id, ?err, "Failed to verify commit signature");
+            Ok(Signature::with_status(Status::Unverifiable, format))
+        }
+    }
+}
+
+fn verify_gpg(
+    repo: &gix::Repository,
+    format: Format,
+    signature: &BStr,
+    payload: &[u8],
+) -> anyhow::Result<Signature> {
+    let config = repo.config_snapshot();
+    let (program_key, default_program, args): (_, _, &[&str]) = match format {
+        Format::X509 => ("gpg.x509.program", "gpgsm", &[]),
+        _ => ("gpg.program", "gpg", &["--keyid-format=long"]),
+    };
+    let program = program_or_default(config.trusted_path(program_key)?, default_program);
+    let min_trust = config
+        .string("gpg.minTrustLevel")
+        .map(|level| TrustLevel::parse(level.as_ref()))
+        .transpose()?
+        .unwrap_or_default();
+
+    let signature_file = write_to_tempfile(signature)?;
+    let output = run(
+        prepare_with_shell_on_windows(&*program)
+            .args(args.iter().copied())
+            .args(["--status-fd=1", "--verify"])
+            .arg(signature_file.to_path_buf())
+            .arg("-"),
+        payload,
+    )?;
+    let mut signature = parse_gpg_status(&output.stdout, min_trust);
+    signature.format = Some(format);
+    Ok(signature)
+}
+
+fn verify_ssh(
+    repo: &gix::Repository,
+    signature: &BStr,
+    payload: &[u8],
+) -> anyhow::Result<Signature> {
+    let config = repo.config_snapshot();
+    let program = program_or_default(config.trusted_path("gpg.ssh.program")?, "ssh-keygen");
+    let allowed_signers = config.trusted_path("gpg.ssh.allowedSignersFile")?;
+    let revocation_file = config.trusted_path("gpg.ssh.revocationFile")?;
+
+    let signature_file = write_to_tempfile(signature)?;
+    let principal = match allowed_signers.as_deref() {
+        Some(allowed_signers) => {
+            let output = run(
+                prepare_with_shell_on_windows(&*program)
+                    .args(["-Y", "find-principals", "-f"])
+                    .arg(allowed_signers)
+                    .arg("-s")
+                    .arg(signature_file.to_path_buf()),
+                &[],
+            )?;
+            output
+                .status
+                .success()
+                .then(|| {
+                    output
+                        .stdout
+                        .lines()
+                        .map(|line| line.trim())
+                        .find(|line| !line.is_empty())
+                        .map(|line| line.to_str_lossy().into_owned())
+                })
+                .flatten()
+        }
+        None => None,
+    };
+
+    let cmd = prepare_with_shell_on_windows(&*program);
+    let cmd = match (allowed_signers.as_deref(), principal.as_deref()) {
+        (Some(allowed_signers), Some(principal)) => {
+            let cmd = cmd
+                .args(["-Y", "verify", "-n", "git", "-f"])
+                .arg(allowed_signers)
+                .arg("-I")
+                .arg(principal)
+                .arg("-s")
+                .arg(signature_file.to_path_buf());
+            match revocation_file.as_deref() {
+                Some(revocation_file) => cmd.arg("-r").arg(revocation_file),
+                None => cmd,
+            }
+        }
+        // Without a known signer, the best we can do is to see if the signature matches.
+        _ => cmd
+            .args(["-Y", "check-novalidate", "-n", "git", "-s"])
+            .arg(signature_file.to_path_buf()),
+    };
+    let output = run(cmd, payload)?;
+    let mut messages = output.stdout;
+    messages.extend_from_slice(&output.stderr);
+    let mut signature = parse_ssh_output(output.status.success(), &messages, principal.is_some());
+    signature.signer = principal;
+    Ok(signature)
+}
+
+fn program_or_default<'a>(program: Option<Cow<'a, Path>>, default: &'static str) -> Cow<'a, Path> {
+    match program {
+        Some(program) if !program.as_os_str().is_empty() => program,
+        _ => Path::new(default).into(),
+    }
+}
+
+fn write_to_tempfile(data: &[u8]) -> std::io::Result<tempfile::TempPath> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(data)?;
+    Ok(file.into_temp_path())
+}
+
+fn run(cmd: gix::command::Prepare, stdin: &[u8]) -> std::io::Result<Output> {
+    let mut cmd: std::process::Command = cmd.into();
+    tracing::debug!(?cmd, "command to verify commit signature");
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().expect("configured").write_all(stdin)?;
+    child.wait_with_output()
+}
+
+/// The trust levels of `gpg.minTrustLevel`, in increasing order.
+///
+/// Like with Git, keys of undefined or no trust only make good signatures if `gpg.minTrustLevel`
+/// allows it, and are of unknown validity otherwise.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum TrustLevel {
+    Undefined,
+    Never,
+    #[default]
+    Marginal,
+    Fully,
+    Ultimate,
+}
+
+impl TrustLevel {
+    fn parse(level: &BStr) -> anyhow::Result<Self> {
+        Ok(match level.to_ascii_lowercase().as_slice() {
+            b"undefined" => TrustLevel::Undefined,
+            b"never" => TrustLevel::Never,
+            b"marginal" => TrustLevel::Marginal,
+            b"fully" => TrustLevel::Fully,
+            b"ultimate" => TrustLevel::Ultimate,
+            _ => anyhow::bail!("Invalid value for 'gpg.minTrustLevel': {level}"),
+        })
+    }
+}
+
+/// Parse the `--status-fd` output of `gpg` or `gpgsm` into a signature, with signatures
+/// below `min_trust` considered [untrusted](Status::Untrusted).
+fn parse_gpg_status(status: &[u8], min_trust: TrustLevel) -> Signature {
+    let mut signature = Signature::with_status(Status::Unverifiable, None);
+    let mut seen_result = false;
+    let mut trust = TrustLevel::Undefined;
+    let mut fingerprint = None;
+    for line in status.lines() {
+        let Some(line) = line.strip_prefix(b"[GNUPG:] ") else {
+            continue;
+        };
+        let mut fields = line.splitn_str(3, " ");
+        let keyword = fields.next().unwrap_or_default();
+        let (status, has_signer) = match keyword {
+            b"GOODSIG" => (Status::Good, true),
+            b"BADSIG" => (Status::Bad, true),
+            b"EXPSIG" => (Status::Expired, true),
+            b"EXPKEYSIG" => (Status::ExpiredKey, true),
+            b"REVKEYSIG" => (Status::RevokedKey, true),
+            b"ERRSIG" => (Status::Unverifiable, false),
+            b"VALIDSIG" => {
+                fingerprint = fields.next().map(|fpr| fpr.to_str_lossy().into_owned());
+                continue;
+            }
+            b"TRUST_UNDEFINED" => {
+                trust = TrustLevel::Undefined;
+                continue;
+            }
+            b"TRUST_NEVER" => {
+                trust = TrustLevel::Never;
+                continue;
+            }
+            b"TRUST_MARGINAL" => {
+                trust = TrustLevel::Marginal;
+                continue;
+            }
+            b"TRUST_FULLY" => {
+                trust = TrustLevel::Fully;
+                continue;
+            }
+            b"TRUST_ULTIMATE" => {
+                trust = TrustLevel::Ultimate;
+                continue;
+            }
+            _ => continue,
+        };
+        if seen_result {
+            // Like Git, refuse to make sense of more than one signature.
+            return Signature::with_status(Status::Bad, None);
+        }
+        seen_result = true;
+        signature.status = status;
+        signature.key = fields.next().map(|key| key.to_str_lossy().into_owned());
+        if has_signer {
+            signature.signer = fields.next().map(|uid| uid.to_str_lossy().into_owned());
+        }
+    }
+    if signature.status == Status::Good && trust < min_trust {
+        signature.status = Status::Untrusted;
+    }
+    if fingerprint.is_some() {
+        signature.key = fingerprint;
+    }
+    signature
+}
+
+/// Parse the `messages` of `ssh-keygen -Y verify` or `ssh-keygen -Y check-novalidate`, where
+/// the latter is only run if the signer isn't `trusted`.
+fn parse_ssh_output(success: bool, messages: &[u8], trusted: bool) -> Signature {
+    let good_line = messages
+        .lines()
+        .find(|line| line.starts_with(br#"Good "git" signature"#));
+    let status = match good_line {
+        Some(_) if success && trusted => Status::Good,
+        Some(_) if success => Status::Untrusted,
+        _ if messages.contains_str("revoked") => Status::RevokedKey,
+        _ => Status::Bad,
+    };
+    Signature {
+        status,
+        format: Some(Format::Ssh),
+        signer: None,
+        key: good_line
+            .and_then(|line| line.rsplit_once_str(" key "))
+            .map(|(_, key)| key.trim().to_str_lossy().into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_detection() {
+        for (signature, expected) in [
+            (
+                "-----BEGIN PGP SIGNATURE-----\n\n...",
+                Some(Format::OpenPgp),
+            ),
+            ("-----BEGIN SSH SIGNATURE-----\n...", Some(Format::Ssh)),
+            ("-----BEGIN SIGNED MESSAGE-----\n...", Some(Format::X509)),
+            ("something else", None),
+        ] {
+            assert_eq!(Format::detect(signature.into()), expected, "{signature}");
+        }
+    }
+
+    #[test]
+    fn gpg_good_signature_with_trust() {
+        let status = b"[GNUPG:] NEWSIG
+[GNUPG:] KEY_CONSIDERED 1234567890ABCDEF1234567890ABCDEF12345678 0
+[GNUPG:] GOODSIG 1234567890ABCDEF Jane Doe <jane@example.com>
+[GNUPG:] VALIDSIG 1234567890ABCDEF1234567890ABCDEF12345678 2024-01-01 1704067200 0 4 0 1 10 00 1234567890ABCDEF1234567890ABCDEF12345678
+[GNUPG:] TRUST_MARGINAL 0 pgp
+";
+        let signature = parse_gpg_status(status, TrustLevel::Undefined);
+        assert_eq!(
+            signature,
+            Signature {
+                status: Status::Good,
+                format: None,
+                signer: Some("Jane Doe <jane@example.com>".into()),
+                key: Some("1234567890ABCDEF1234567890ABCDEF12345678".into()),
+            }
+        );
+        assert_eq!(
+            parse_gpg_status(status, TrustLevel::Marginal).status,
+            Status::Good
+        );
+        assert_eq!(
+            parse_gpg_status(status, TrustLevel::Fully).status,
+            Status::Untrusted,
+            "the key isn't trusted enough"
+        );
+    }
+
+    #[test]
+    fn gpg_undefined_trust_is_only_good_if_allowed() {
+        let status = b"[GNUPG:] GOODSIG 1234567890ABCDEF Jane Doe <jane@example.com>
+[GNUPG:] TRUST_UNDEFINED 0 pgp
+";
+        assert_eq!(
+            parse_gpg_status(status, TrustLevel::default()).status,
+            Status::Untrusted,
+            "without `gpg.minTrustLevel`, the validity of the signature is unknown"
+        );
+        assert_eq!(
+            parse_gpg_status(status, TrustLevel::Undefined).status,
+            Status::Good,
+            "unless it allows keys of undefined trust"
+        );
+        assert_eq!(
+            parse_gpg_status(
+                b"[GNUPG:] GOODSIG 1234567890ABCDEF Jane Doe\n",
+                TrustLevel::default()
+            )
+            .status,
+            Status::Untrusted,
+            "no trust level is undefined trust"
+        );
+    }
+
+    #[test]
+    fn gpg_failures() {
+        let signature = parse_gpg_status(
+            b"[GNUPG:] BADSIG 1234567890ABCDEF Jane Doe <jane@example.com>\n",
+            TrustLevel::Undefined,
+        );
+        assert_eq!(signature.status, Status::Bad);
+        assert_eq!(signature.key.as_deref(), Some("1234567890ABCDEF"));
+        assert_eq!(
+            signature.signer.as_deref(),
+            Some("Jane Doe <jane@example.com>")
+        );
+
+        let signature = parse_gpg_status(
+            b"[GNUPG:] ERRSIG 1234567890ABCDEF 1 10 00 1704067200 9 -\n[GNUPG:] NO_PUBKEY 1234567890ABCDEF\n",
+            TrustLevel::Undefined,
+        );
+        assert_eq!(signature.status, Status::Unverifiable);
+        assert_eq!(signature.key.as_deref(), Some("1234567890ABCDEF"));
+        assert_eq!(signature.signer, None);
+
+        for (line, expected) in [
+            ("EXPSIG", Status::Expired),
+            ("EXPKEYSIG", Status::ExpiredKey),
+            ("REVKEYSIG", Status::RevokedKey),
+        ] {
+            let status = format!("[GNUPG:] {line} 1234567890ABCDEF Jane Doe\n");
+            assert_eq!(
+                parse_gpg_status(status.as_bytes(), TrustLevel::Undefined).status,
+                expected
+            );
+        }
+
+        assert_eq!(
+            parse_gpg_status(b"", TrustLevel::Undefined).status,
+            Status::Unverifiable,
+            "no status output means nothing could be verified"
+        );
+        assert_eq!(
+            parse_gpg_status(
+                b"[GNUPG:] GOODSIG 1 A\n[GNUPG:] GOODSIG 2 B\n",
+                TrustLevel::Undefined
+            )
+            .status,
+            Status::Bad,
+            "multiple signatures are rejected"
+        );
+    }
+
+    #[test]
+    fn trust_level_parsing() -> anyhow::Result<()> {
+        assert_eq!(TrustLevel::parse("Fully".into())?, TrustLevel::Fully);
+        assert!(TrustLevel::parse("very".into()).is_err());
+        assert!(TrustLevel::Marginal < TrustLevel::Ultimate);
+        Ok(())
+    }
+
+    #[test]
+    fn ssh_output() {
+        let good = b"Good \"git\" signature for test@example.com with ED25519 key SHA256:abc\n";
+        let signature = parse_ssh_output(true, good, true);
+        assert_eq!(signature.status, Status::Good);
+        assert_eq!(signature.key.as_deref(), Some("SHA256:abc"));
+
+        let signature = parse_ssh_output(
+            true,
+            b"Good \"git\" signature with ED25519 key SHA256:abc\n",
+            false,
+        );
+        assert_eq!(signature.status, Status::Untrusted);
+        assert_eq!(signature.key.as_deref(), Some("SHA256:abc"));
+
+        assert_eq!(
+            parse_ssh_output(
+                false,
+                b"Signature verification failed: incorrect signature\n",
+                true
+            )
+            .status,
+            Status::Bad
+        );
+    }
+}
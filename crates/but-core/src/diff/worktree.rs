@@ -18,7 +18,7 @@ use tracing::instrument;
 
 use crate::{
     ChangeState, IgnoredWorktreeChange, IgnoredWorktreeTreeChangeStatus, ModeFlags, TreeChange,
-    TreeStatus, UnifiedPatch, WorktreeChanges,
+    TreeStatus, TreeStatusKind, UnifiedPatch, WorktreeChanges,
 };
 
 /// Identify where a [`TreeChange`] is from.
@@ -36,13 +36,75 @@ enum Origin {
 /// to get a commit with a tree equal to the current worktree.
 #[instrument(skip(repo), err(Debug))]
 pub fn worktree_changes(repo: &gix::Repository) -> anyhow::Result<WorktreeChanges> {
-    worktree_changes_inner(repo, RenameTracking::Always)
+    worktree_changes_inner(repo, RenameTracking::Always, None)
 }
 
 /// Just like [`worktree_changes()`], but don't do any rename tracking for performance.
 #[instrument(skip(repo), err(Debug))]
 pub fn worktree_changes_no_renames(repo: &gix::Repository) -> anyhow::Result<WorktreeChanges> {
-    worktree_changes_inner(repo, RenameTracking::Disabled)
+    worktree_changes_inner(repo, RenameTracking::Disabled, None)
+}
+
+/// Like [`worktree_changes()`], but only look at the worktree-relative `changed_paths` and take everything
+/// else from `previous`, the result of an earlier call made with the same `HEAD^{tree}` and index.
+///
+/// Directories in `changed_paths` stand for everything below them. This is useful if a filesystem monitor
+/// knows what changed since `previous` was obtained, as unchanged directories don't have to be traversed.
+/// Additions, deletions and renames in `previous` are looked at as well so renames can still be found.
+#[instrument(skip(repo, previous), err(Debug))]
+pub fn refresh_worktree_changes(
+    repo: &gix::Repository,
+    previous: WorktreeChanges,
+    changed_paths: &[BString],
+) -> anyhow::Result<WorktreeChanges> {
+    if changed_paths.is_empty() {
+        return Ok(previous);
+    }
+    let mut paths = changed_paths.to_vec();
+    for change in &previous.changes {
+        if change.status.kind() != TreeStatusKind::Modification {
+            paths.push(change.path.clone());
+            paths.extend(change.previous_path().map(ToOwned::to_owned));
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    let is_refreshed = |path: &BStr| {
+        paths.iter().any(|refreshed| {
+            path.strip_prefix(refreshed.as_slice())
+                .is_some_and(|rest| rest.is_empty() || rest[0] == b'/')
+        })
+    };
+
+    let patterns = paths.iter().map(|path| {
+        let mut pattern = BString::from(":(top,literal)");
+        pattern.push_str(path);
+        pattern
+    });
+    let refreshed = worktree_changes_inner(repo, RenameTracking::Always, patterns)?;
+
+    let mut changes: Vec<_> = previous
+        .changes
+        .into_iter()
+        .filter(|change| !is_refreshed(change.path.as_bstr()))
+        .chain(refreshed.changes)
+        .collect();
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut ignored_changes: Vec<_> = previous
+        .ignored_changes
+        .into_iter()
+        .filter(|change| !is_refreshed(change.path.as_bstr()))
+        .chain(refreshed.ignored_changes)
+        .collect();
+    ignored_changes.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(WorktreeChanges {
+        changes,
+        ignored_changes,
+        // These only depend on `HEAD^{tree}` and the index, which didn't change.
+        index_changes: previous.index_changes,
+        index_conflicts: previous.index_conflicts,
+    })
 }
 
 enum RenameTracking {
@@ -50,9 +112,11 @@ enum RenameTracking {
     Disabled,
 }
 
+/// Obtain the worktree changes of `repo`, limited to the given pathspec `patterns`, or everything if there are none.
 fn worktree_changes_inner(
     repo: &gix::Repository,
     renames: RenameTracking,
+    patterns: impl IntoIterator<Item = BString>,
 ) -> anyhow::Result<WorktreeChanges> {
     let (tree_index_rewrites, worktree_rewrites) = match renames {
         RenameTracking::Always => {
@@ -95,7 +159,7 @@ fn worktree_changes_inner(
                     .set_emit_collapsed(None);
             }
         })
-        .into_iter(None)?;
+        .into_iter(patterns)?;
 
     let work_dir = repo.workdir().context("need non-bare repository")?;
     let mut tmp = Vec::new();
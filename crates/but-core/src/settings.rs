@@ -10,6 +10,7 @@ pub mod git {
     const GIT_SIGN_COMMITS: &str = "commit.gpgsign";
     const GITBUTLER_SIGN_COMMITS: &str = "gitbutler.signCommits";
     const GITBUTLER_GERRIT_MODE: &str = "gitbutler.gerritMode";
+    const GITBUTLER_VERIFY_SIGNATURES: &str = "gitbutler.verifySignatures";
     const GITBUTLER_REVIEW_STACKING_DESCRIPTION: &str = "gitbutler.reviewStackingDescription";
     const GITBUTLER_GITHUB_STACKING_MODE: &str = "gitbutler.githubStackingMode";
     const GITBUTLER_FORGE_TEMPLATE_PATH: &str = "gitbutler.forgeReviewTemplatePath";
@@ -63,6 +64,7 @@ pub mod git {
             #[serde(rename = "signCommits")]
             pub gitbutler_sign_commits: Option<bool>,
             pub gitbutler_gerrit_mode: Option<bool>,
+            pub gitbutler_verify_signatures: Option<bool>,
             pub gitbutler_review_stacking_description: Option<ReviewStackingDescription>,
             pub gitbutler_github_stacking_mode: Option<GitHubStackingMode>,
             #[cfg_attr(feature = "export-schema", schemars(with = "Option<String>"))]
@@ -86,6 +88,7 @@ pub mod git {
                 crate::GitConfigSettings {
                     gitbutler_sign_commits,
                     gitbutler_gerrit_mode,
+                    gitbutler_verify_signatures,
                     gitbutler_review_stacking_description,
                     gitbutler_github_stacking_mode,
                     gitbutler_forge_review_template_path,
@@ -100,6 +103,7 @@ pub mod git {
                 GitConfigSettings {
                     gitbutler_sign_commits,
                     gitbutler_gerrit_mode,
+                    gitbutler_verify_signatures,
                     gitbutler_review_stacking_description,
                     gitbutler_github_stacking_mode,
                     gitbutler_forge_review_template_path: gitbutler_forge_review_template_path
@@ -121,6 +125,7 @@ pub mod git {
                 GitConfigSettings {
                     gitbutler_sign_commits,
                     gitbutler_gerrit_mode,
+                    gitbutler_verify_signatures,
                     gitbutler_review_stacking_description,
                     gitbutler_github_stacking_mode,
                     gitbutler_forge_review_template_path,
@@ -135,6 +140,7 @@ pub mod git {
                 crate::GitConfigSettings {
                     gitbutler_sign_commits,
                     gitbutler_gerrit_mode,
+                    gitbutler_verify_signatures,
                     gitbutler_review_stacking_description,
                     gitbutler_github_stacking_mode,
                     gitbutler_forge_review_template_path: gitbutler_forge_review_template_path
@@ -169,6 +175,8 @@ pub mod git {
             pub gitbutler_sign_commits: Option<bool>,
             /// If `true`, GitButler will create ChangeId trailers and will push references in the Gerrit way
             pub gitbutler_gerrit_mode: Option<bool>,
+            /// If `true`, the signatures of commits in the workspace are verified and shown.
+            pub gitbutler_verify_signatures: Option<bool>,
             /// Controls where GitButler puts stack information in review descriptions.
             pub gitbutler_review_stacking_description: Option<ReviewStackingDescription>,
             /// Controls whether GitHub pull requests are registered with the native stacks API.
@@ -204,6 +212,7 @@ pub mod git {
                 .or_else(|| config.boolean(GIT_SIGN_COMMITS))
                 .or(Some(false));
             let gitbutler_gerrit_mode = config.boolean(GITBUTLER_GERRIT_MODE).or(Some(false));
+            let gitbutler_verify_signatures = config.boolean(GITBUTLER_VERIFY_SIGNATURES);
             let gitbutler_review_stacking_description = config
                 .string(GITBUTLER_REVIEW_STACKING_DESCRIPTION)
                 .map(|value| match value.as_slice() {
@@ -240,6 +249,7 @@ pub mod git {
             Ok(GitConfigSettings {
                 gitbutler_sign_commits,
                 gitbutler_gerrit_mode,
+                gitbutler_verify_signatures,
                 gitbutler_review_stacking_description,
                 gitbutler_github_stacking_mode,
                 gitbutler_forge_review_template_path,
@@ -269,6 +279,12 @@ pub mod git {
                         if gerrit_mode { "true" } else { "false" },
                     )?;
                 };
+                if let Some(verify_signatures) = self.gitbutler_verify_signatures {
+                    config.set_raw_value(
+                        GITBUTLER_VERIFY_SIGNATURES,
+                        if verify_signatures { "true" } else { "false" },
+                    )?;
+                };
                 if let Some(description) = self.gitbutler_review_stacking_description {
                     config.set_raw_value(
                         GITBUTLER_REVIEW_STACKING_DESCRIPTION,
@@ -515,3 +515,72 @@ mod create {
         Ok(commit)
     }
 }
+
+mod verify_signature {
+    use but_core::commit::signature::{self, Format, Status};
+    use but_testsupport::writable_scenario_with_ssh_key;
+
+    #[test]
+    fn ssh_signature_by_allowed_signer_is_good() -> anyhow::Result<()> {
+        let (mut repo, _tmp) = writable_scenario_with_ssh_key("single-signed");
+        let workdir = repo.workdir().expect("non-bare").to_owned();
+        let public_key = std::fs::read_to_string(workdir.join("signature.key.pub"))?;
+        let allowed_signers = workdir.join("allowed_signers");
+        std::fs::write(&allowed_signers, format!("test@example.com {public_key}"))?;
+        repo.config_snapshot_mut().set_raw_value(
+            "gpg.ssh.allowedSignersFile",
+            allowed_signers.to_str().expect("valid UTF-8"),
+        )?;
+
+        let signature = signature::verify(&repo, repo.head_id()?.detach())?;
+        assert_eq!(signature.status, Status::Good);
+        assert_eq!(signature.format, Some(Format::Ssh));
+        assert_eq!(signature.signer.as_deref(), Some("test@example.com"));
+        assert!(
+            signature.key.is_some_and(|key| key.starts_with("SHA256:")),
+            "the key fingerprint is extracted"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ssh_signature_without_allowed_signers_is_untrusted() -> anyhow::Result<()> {
+        let (repo, _tmp) = writable_scenario_with_ssh_key("single-signed");
+
+        let signature = signature::verify(&repo, repo.head_id()?.detach())?;
+        assert_eq!(
+            signature.status,
+            Status::Untrusted,
+            "the signature matches, but nobody said the key can be trusted"
+        );
+        assert_eq!(signature.signer, None);
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_commit_has_bad_signature() -> anyhow::Result<()> {
+        let (repo, _tmp) = writable_scenario_with_ssh_key("single-signed");
+        let mut commit = repo.head_commit()?.decode()?.to_owned()?;
+        commit.message = "not what was signed".into();
+        let tampered = repo.write_object(&commit)?.detach();
+
+        let signature = signature::verify(&repo, tampered)?;
+        assert_eq!(signature.status, Status::Bad);
+        assert_eq!(signature.format, Some(Format::Ssh));
+        Ok(())
+    }
+
+    #[test]
+    fn unsigned_commit() -> anyhow::Result<()> {
+        let (repo, _tmp) = writable_scenario_with_ssh_key("single-signed");
+        let mut commit = repo.head_commit()?.decode()?.to_owned()?;
+        commit.extra_headers.clear();
+        let unsigned = repo.write_object(&commit)?.detach();
+
+        assert_eq!(
+            signature::verify(&repo, unsigned)?,
+            signature::Signature::unsigned()
+        );
+        Ok(())
+    }
+}
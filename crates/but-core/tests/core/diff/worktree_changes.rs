@@ -2157,6 +2157,31 @@ WorktreeChanges {
     Ok(())
 }
 
+#[test]
+fn refresh_only_looks_at_changed_paths() -> Result<()> {
+    let repo = repo("added-modified-in-worktree")?;
+    let expected = diff::worktree_changes(&repo)?;
+
+    let mut stale = expected.clone();
+    stale.changes.retain(|change| change.path != "modified");
+    let actual = diff::refresh_worktree_changes(&repo, stale, &["modified".into()])?;
+    assert_eq!(
+        format!("{actual:?}"),
+        format!("{expected:?}"),
+        "changed paths are looked at again"
+    );
+
+    let mut outdated = expected.clone();
+    outdated.changes.retain(|change| change.path == "modified");
+    let actual = diff::refresh_worktree_changes(&repo, outdated.clone(), &["unrelated".into()])?;
+    assert_eq!(
+        format!("{actual:?}"),
+        format!("{outdated:?}"),
+        "everything else is taken as it was"
+    );
+    Ok(())
+}
+
 fn unified_patches(
     worktree: WorktreeChanges,
     repo: &gix::Repository,
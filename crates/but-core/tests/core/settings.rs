@@ -26,6 +26,7 @@ mod git {
         let expected = GitConfigSettings {
             gitbutler_sign_commits: Some(true),
             gitbutler_gerrit_mode: Some(false),
+            gitbutler_verify_signatures: Some(true),
             gitbutler_review_stacking_description: None,
             gitbutler_github_stacking_mode: None,
             gitbutler_forge_review_template_path: None,
@@ -88,6 +89,7 @@ mod git {
         repo.set_git_settings(&GitConfigSettings {
             gitbutler_sign_commits: Some(true),
             gitbutler_gerrit_mode: Some(false),
+            gitbutler_verify_signatures: Some(true),
             gitbutler_review_stacking_description: None,
             gitbutler_github_stacking_mode: None,
             gitbutler_forge_review_template_path: Some("template.md".into()),
@@ -102,6 +104,7 @@ mod git {
         repo.set_git_settings(&GitConfigSettings {
             gitbutler_sign_commits: Some(true),
             gitbutler_gerrit_mode: Some(false),
+            gitbutler_verify_signatures: Some(true),
             gitbutler_review_stacking_description: None,
             gitbutler_github_stacking_mode: None,
             gitbutler_forge_review_template_path: Some("".into()),
@@ -75,9 +75,11 @@ pub use table::{
     branch_order::{BranchOrderHandle, BranchOrderHandleMut},
     butler_actions::ButlerAction,
     claude::{ClaudeMessage, ClaudePermissionRequest, ClaudeSession},
+    comments::{Comment, CommentsHandle, CommentsHandleMut},
     file_write_locks::FileWriteLock,
     fetch_status::FetchStatus,
     gerrit_metadata::{GerritMeta, GerritMetadataHandle},
+    gerrit_changes::{GerritChange, GerritChangesHandle, GerritChangesHandleMut},
     forge_reviews::ForgeReview,
     ci_checks::CiCheck,
     virtual_branches::{VbStack, VbStackHead, VbState, VirtualBranchesSnapshot, VirtualBranchesHandle, VirtualBranchesHandleMut},
@@ -134,9 +136,11 @@ pub const MIGRATIONS: &[&[M<'static>]] = &[
     table::branch_order::M,
     table::butler_actions::M,
     table::claude::M,
+    table::comments::M,
     table::file_write_locks::M,
     table::fetch_status::M,
     table::gerrit_metadata::M,
+    table::gerrit_changes::M,
     table::forge_reviews::M,
     table::ci_checks::M,
     table::virtual_branches::M,
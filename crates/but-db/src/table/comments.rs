@@ -0,0 +1,196 @@
+#![allow(missing_docs)]
+
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+use crate::{DbHandle, M, SchemaVersion, Transaction};
+
+pub(crate) const M: &[M<'static>] = &[
+    M::up(
+        20261018140000,
+        SchemaVersion::Zero,
+        "CREATE TABLE `comments`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`path` TEXT NOT NULL,
+	`commit_change_id` TEXT,
+	`side` TEXT NOT NULL,
+	`line_number` INTEGER NOT NULL,
+	`line_content` TEXT NOT NULL,
+	`line_before` TEXT,
+	`line_after` TEXT,
+	`payload` TEXT NOT NULL,
+	`created_at_ms` BIGINT NOT NULL,
+	`updated_at_ms` BIGINT NOT NULL,
+	`archived_at_ms` BIGINT
+);
+
+CREATE INDEX `idx_comments_commit_change_id` ON `comments`(`commit_change_id`);",
+    ),
+    M::up(
+        20261018150000,
+        SchemaVersion::Zero,
+        "ALTER TABLE `comments` ADD COLUMN `parent_id` TEXT;
+ALTER TABLE `comments` ADD COLUMN `author` TEXT;
+ALTER TABLE `comments` ADD COLUMN `resolved_at_ms` BIGINT;
+ALTER TABLE `comments` ADD COLUMN `resolved_by` TEXT;",
+    ),
+];
+
+/// A comment anchored to a line in a diff, as stored by `but-comments`, which also documents the fields.
+///
+/// Tests are in `but-db/tests/db/table/comments.rs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comment {
+    /// The unique identifier of the comment (primary key).
+    pub id: String,
+    pub path: String,
+    pub commit_change_id: Option<String>,
+    /// Either `old` or `new`.
+    pub side: String,
+    pub line_number: u32,
+    pub line_content: String,
+    pub line_before: Option<String>,
+    pub line_after: Option<String>,
+    pub payload: String,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+    pub archived_at_ms: Option<i64>,
+    /// The id of the comment that starts the thread this comment replies to.
+    pub parent_id: Option<String>,
+    pub author: Option<String>,
+    pub resolved_at_ms: Option<i64>,
+    pub resolved_by: Option<String>,
+}
+
+impl DbHandle {
+    pub fn comments(&self) -> CommentsHandle<'_> {
+        CommentsHandle { conn: &self.conn }
+    }
+
+    pub fn comments_mut(&mut self) -> CommentsHandleMut<'_> {
+        CommentsHandleMut { conn: &self.conn }
+    }
+}
+
+impl<'conn> Transaction<'conn> {
+    pub fn comments(&self) -> CommentsHandle<'_> {
+        CommentsHandle { conn: self.inner() }
+    }
+
+    pub fn comments_mut(&mut self) -> CommentsHandleMut<'_> {
+        CommentsHandleMut { conn: self.inner() }
+    }
+}
+
+pub struct CommentsHandle<'conn> {
+    conn: &'conn rusqlite::Connection,
+}
+
+pub struct CommentsHandleMut<'conn> {
+    conn: &'conn rusqlite::Connection,
+}
+
+const COLUMNS: &str = "id, path, commit_change_id, side, line_number, line_content, line_before, \
+                       line_after, payload, created_at_ms, updated_at_ms, archived_at_ms, parent_id, \
+                       author, resolved_at_ms, resolved_by";
+
+fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Comment> {
+    Ok(Comment {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        commit_change_id: row.get(2)?,
+        side: row.get(3)?,
+        line_number: row.get(4)?,
+        line_content: row.get(5)?,
+        line_before: row.get(6)?,
+        line_after: row.get(7)?,
+        payload: row.get(8)?,
+        created_at_ms: row.get(9)?,
+        updated_at_ms: row.get(10)?,
+        archived_at_ms: row.get(11)?,
+        parent_id: row.get(12)?,
+        author: row.get(13)?,
+        resolved_at_ms: row.get(14)?,
+        resolved_by: row.get(15)?,
+    })
+}
+
+impl CommentsHandle<'_> {
+    /// Get a Comment entry by id (primary key)
+    pub fn get(&self, id: &str) -> rusqlite::Result<Option<Comment>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT {COLUMNS} FROM comments WHERE id = ?1"))?;
+        stmt.query_row([id], from_row).optional()
+    }
+
+    /// List all comments, archived or not, in insertion order.
+    pub fn list(&self) -> rusqlite::Result<Vec<Comment>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT {COLUMNS} FROM comments ORDER BY rowid"))?;
+        let results = stmt.query_map([], from_row)?;
+        results.collect::<Result<Vec<_>, _>>()
+    }
+
+    /// List all comments anchored to the commit with `change_id`, in insertion order.
+    pub fn list_for_change_id(&self, change_id: &str) -> rusqlite::Result<Vec<Comment>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM comments WHERE commit_change_id = ?1 ORDER BY rowid"
+        ))?;
+        let results = stmt.query_map([change_id], from_row)?;
+        results.collect::<Result<Vec<_>, _>>()
+    }
+}
+
+impl CommentsHandleMut<'_> {
+    /// Enable read-only access functions.
+    pub fn to_ref(&self) -> CommentsHandle<'_> {
+        CommentsHandle { conn: self.conn }
+    }
+
+    /// Insert `comment`, or update the entry with the same id while keeping its position in the insertion order.
+    pub fn upsert(&mut self, comment: Comment) -> rusqlite::Result<()> {
+        self.conn.execute(
+            &format!(
+                "INSERT INTO comments ({COLUMNS}) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16) \
+                 ON CONFLICT(id) DO UPDATE SET \
+                 path = excluded.path, commit_change_id = excluded.commit_change_id, \
+                 side = excluded.side, line_number = excluded.line_number, \
+                 line_content = excluded.line_content, line_before = excluded.line_before, \
+                 line_after = excluded.line_after, payload = excluded.payload, \
+                 created_at_ms = excluded.created_at_ms, updated_at_ms = excluded.updated_at_ms, \
+                 archived_at_ms = excluded.archived_at_ms, parent_id = excluded.parent_id, \
+                 author = excluded.author, resolved_at_ms = excluded.resolved_at_ms, \
+                 resolved_by = excluded.resolved_by"
+            ),
+            rusqlite::params![
+                comment.id,
+                comment.path,
+                comment.commit_change_id,
+                comment.side,
+                comment.line_number,
+                comment.line_content,
+                comment.line_before,
+                comment.line_after,
+                comment.payload,
+                comment.created_at_ms,
+                comment.updated_at_ms,
+                comment.archived_at_ms,
+                comment.parent_id,
+                comment.author,
+                comment.resolved_at_ms,
+                comment.resolved_by,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Delete the entry for `id`, if it exists.
+    pub fn delete(&mut self, id: &str) -> rusqlite::Result<()> {
+        self.conn
+            .execute("DELETE FROM comments WHERE id = ?1", [id])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+#![allow(missing_docs)]
+
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+use crate::{DbHandle, M, SchemaVersion, Transaction};
+
+pub(crate) const M: &[M<'static>] = &[M::up(
+    20261018093000,
+    SchemaVersion::Zero,
+    "CREATE TABLE `gerrit_changes`(
+	`change_id` TEXT NOT NULL PRIMARY KEY,
+	`number` BIGINT NOT NULL,
+	`status` TEXT NOT NULL,
+	`code_review` INTEGER,
+	`verified` INTEGER,
+	`unresolved_comment_count` INTEGER NOT NULL,
+	`submittable` BOOLEAN NOT NULL,
+	`work_in_progress` BOOLEAN NOT NULL,
+	`current_revision` TEXT,
+	`last_sync_at` TIMESTAMP NOT NULL
+);",
+)];
+
+/// The last known state of a change on the Gerrit server, as queried via its REST API.
+///
+/// Tests are in `but-db/tests/db/table/gerrit_changes.rs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GerritChange {
+    /// The GitButler change ID of the commit (primary key), like in [`crate::GerritMeta`].
+    pub change_id: String,
+    /// The numeric ID of the change on the Gerrit server.
+    pub number: i64,
+    /// The change status as reported by Gerrit, like `NEW`, `MERGED` or `ABANDONED`.
+    pub status: String,
+    /// The summarized `Code-Review` vote, if there is any.
+    pub code_review: Option<i32>,
+    /// The summarized `Verified` vote, if there is any.
+    pub verified: Option<i32>,
+    /// The amount of unresolved comment threads.
+    pub unresolved_comment_count: i64,
+    /// Whether the change could be submitted right now.
+    pub submittable: bool,
+    /// Whether the change is marked as work in progress.
+    pub work_in_progress: bool,
+    /// The commit id of the current patchset, if it was provided.
+    pub current_revision: Option<String>,
+    /// The time when the entry was fetched from the server.
+    pub last_sync_at: chrono::NaiveDateTime,
+}
+
+impl DbHandle {
+    pub fn gerrit_changes(&self) -> GerritChangesHandle<'_> {
+        GerritChangesHandle { conn: &self.conn }
+    }
+
+    pub fn gerrit_changes_mut(&mut self) -> GerritChangesHandleMut<'_> {
+        GerritChangesHandleMut { conn: &self.conn }
+    }
+}
+
+impl<'conn> Transaction<'conn> {
+    pub fn gerrit_changes(&self) -> GerritChangesHandle<'_> {
+        GerritChangesHandle { conn: self.inner() }
+    }
+
+    pub fn gerrit_changes_mut(&mut self) -> GerritChangesHandleMut<'_> {
+        GerritChangesHandleMut { conn: self.inner() }
+    }
+}
+
+pub struct GerritChangesHandle<'conn> {
+    conn: &'conn rusqlite::Connection,
+}
+
+pub struct GerritChangesHandleMut<'conn> {
+    conn: &'conn rusqlite::Connection,
+}
+
+const COLUMNS: &str = "change_id, number, status, code_review, verified, unresolved_comment_count, \
+                       submittable, work_in_progress, current_revision, last_sync_at";
+
+fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<GerritChange> {
+    Ok(GerritChange {
+        change_id: row.get(0)?,
+        number: row.get(1)?,
+        status: row.get(2)?,
+        code_review: row.get(3)?,
+        verified: row.get(4)?,
+        unresolved_comment_count: row.get(5)?,
+        submittable: row.get(6)?,
+        work_in_progress: row.get(7)?,
+        current_revision: row.get(8)?,
+        last_sync_at: row.get(9)?,
+    })
+}
+
+impl GerritChangesHandle<'_> {
+    /// Get a GerritChange entry by change_id (primary key)
+    pub fn get(&self, change_id: &str) -> rusqlite::Result<Option<GerritChange>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM gerrit_changes WHERE change_id = ?1"
+        ))?;
+        stmt.query_row([change_id], from_row).optional()
+    }
+
+    /// List all known GerritChange entries, ordered by change_id.
+    pub fn list(&self) -> rusqlite::Result<Vec<GerritChange>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM gerrit_changes ORDER BY change_id"
+        ))?;
+        let results = stmt.query_map([], from_row)?;
+        results.collect::<Result<Vec<_>, _>>()
+    }
+}
+
+impl GerritChangesHandleMut<'_> {
+    /// Enable read-only access functions.
+    pub fn to_ref(&self) -> GerritChangesHandle<'_> {
+        GerritChangesHandle { conn: self.conn }
+    }
+
+    /// Insert `change`, or replace the entry with the same change_id.
+    pub fn upsert(&mut self, change: GerritChange) -> rusqlite::Result<()> {
+        self.conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO gerrit_changes ({COLUMNS}) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
+            ),
+            rusqlite::params![
+                change.change_id,
+                change.number,
+                change.status,
+                change.code_review,
+                change.verified,
+                change.unresolved_comment_count,
+                change.submittable,
+                change.work_in_progress,
+                change.current_revision,
+                change.last_sync_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Delete the entry for `change_id`, if it exists.
+    pub fn delete(&mut self, change_id: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "DELETE FROM gerrit_changes WHERE change_id = ?1",
+            [change_id],
+        )?;
+        Ok(())
+    }
+}
@@ -4,9 +4,11 @@ pub(crate) mod branch_order;
 pub(crate) mod butler_actions;
 pub(crate) mod ci_checks;
 pub(crate) mod claude;
+pub(crate) mod comments;
 pub(crate) mod fetch_status;
 pub(crate) mod file_write_locks;
 pub(crate) mod forge_reviews;
+pub(crate) mod gerrit_changes;
 pub(crate) mod gerrit_metadata;
 pub(crate) mod hunk_assignments;
 pub(crate) mod virtual_branches;
@@ -468,6 +468,22 @@ CREATE TABLE `claude_sessions`(
 	`updated_at` TIMESTAMP NOT NULL
 , in_gui BOOLEAN NOT NULL DEFAULT FALSE, session_ids TEXT NOT NULL DEFAULT '[]', approved_permissions TEXT NOT NULL DEFAULT '[]', denied_permissions TEXT NOT NULL DEFAULT '[]');
 
+-- table comments
+CREATE TABLE `comments`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`path` TEXT NOT NULL,
+	`commit_change_id` TEXT,
+	`side` TEXT NOT NULL,
+	`line_number` INTEGER NOT NULL,
+	`line_content` TEXT NOT NULL,
+	`line_before` TEXT,
+	`line_after` TEXT,
+	`payload` TEXT NOT NULL,
+	`created_at_ms` BIGINT NOT NULL,
+	`updated_at_ms` BIGINT NOT NULL,
+	`archived_at_ms` BIGINT
+, `parent_id` TEXT, `author` TEXT, `resolved_at_ms` BIGINT, `resolved_by` TEXT);
+
 -- table fetch_status
 CREATE TABLE `fetch_status`(
 	`singleton` INTEGER NOT NULL PRIMARY KEY CHECK (`singleton` = 1),
@@ -508,6 +524,20 @@ CREATE TABLE `forge_reviews`(
 	`struct_version` INTEGER NOT NULL
 , `head_repo_is_fork` BOOL NOT NULL DEFAULT FALSE, `integration_commit_shas` TEXT NOT NULL DEFAULT '[]', `auto_merge_enabled` BOOL NOT NULL DEFAULT FALSE);
 
+-- table gerrit_changes
+CREATE TABLE `gerrit_changes`(
+	`change_id` TEXT NOT NULL PRIMARY KEY,
+	`number` BIGINT NOT NULL,
+	`status` TEXT NOT NULL,
+	`code_review` INTEGER,
+	`verified` INTEGER,
+	`unresolved_comment_count` INTEGER NOT NULL,
+	`submittable` BOOLEAN NOT NULL,
+	`work_in_progress` BOOLEAN NOT NULL,
+	`current_revision` TEXT,
+	`last_sync_at` TIMESTAMP NOT NULL
+);
+
 -- table gerrit_metadata
 CREATE TABLE `gerrit_metadata`(
 	`change_id` TEXT NOT NULL PRIMARY KEY,
@@ -617,6 +647,9 @@ CREATE INDEX `idx_butler_actions_created_at` ON `butler_actions`(`created_at`);
 -- index idx_ci_checks_reference
 CREATE INDEX `idx_ci_checks_reference` ON `ci_checks`(`reference`);
 
+-- index idx_comments_commit_change_id
+CREATE INDEX `idx_comments_commit_change_id` ON `comments`(`commit_change_id`);
+
 -- index idx_vb_stack_heads_stack_id
 CREATE INDEX `idx_vb_stack_heads_stack_id` ON `vb_stack_heads`(`stack_id`);
 
@@ -680,6 +713,9 @@ Text("20260715120000")
 Text("20260715161258")
 Text("20260716175500")
 Text("20260805120000")
+Text("20261018093000")
+Text("20261018140000")
+Text("20261018150000")
 
 Table: hunk_assignments
 hunk_header | path | path_bytes | stack_id | id | branch_ref
@@ -735,6 +771,12 @@ name | archived
 Table: worktree_adoption
 id | adopted_at
 
+Table: gerrit_changes
+change_id | number | status | code_review | verified | unresolved_comment_count | submittable | work_in_progress | current_revision | last_sync_at
+
+Table: comments
+id | path | commit_change_id | side | line_number | line_content | line_before | line_after | payload | created_at_ms | updated_at_ms | archived_at_ms | parent_id | author | resolved_at_ms | resolved_by
+
 
 "#]]
         );
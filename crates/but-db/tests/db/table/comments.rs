@@ -0,0 +1,96 @@
+use but_db::Comment;
+
+use crate::table::in_memory_db;
+
+#[test]
+fn get_nonexistent() -> anyhow::Result<()> {
+    let db = in_memory_db();
+
+    assert!(db.comments().get("1")?.is_none());
+    assert!(db.comments().list()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn upsert_inserts_and_updates_in_place() -> anyhow::Result<()> {
+    let mut db = in_memory_db();
+
+    let first = comment("1", None);
+    let second = comment("2", Some("change-a"));
+    db.comments_mut().upsert(first.clone())?;
+    db.comments_mut().upsert(second.clone())?;
+    assert_eq!(db.comments().get("1")?, Some(first.clone()));
+
+    let archived = Comment {
+        payload: "updated".into(),
+        updated_at_ms: 2000,
+        archived_at_ms: Some(3000),
+        resolved_at_ms: Some(2000),
+        resolved_by: Some("Author".into()),
+        ..first
+    };
+    db.comments_mut().upsert(archived.clone())?;
+
+    assert_eq!(
+        db.comments().list()?,
+        vec![archived, second],
+        "updating keeps the insertion order"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn list_for_change_id_and_delete() -> anyhow::Result<()> {
+    let mut db = in_memory_db();
+
+    let uncommitted = comment("1", None);
+    let a = comment("2", Some("change-a"));
+    let b = comment("3", Some("change-b"));
+    for comment in [uncommitted.clone(), a.clone(), b.clone()] {
+        db.comments_mut().upsert(comment)?;
+    }
+    assert_eq!(db.comments().list_for_change_id("change-a")?, vec![a]);
+
+    db.comments_mut().delete("2")?;
+    db.comments_mut().delete("does-not-exist")?;
+    assert_eq!(db.comments().list()?, vec![uncommitted, b]);
+
+    Ok(())
+}
+
+#[test]
+fn transaction_rollback() -> anyhow::Result<()> {
+    let mut db = in_memory_db();
+
+    let mut trans = db.transaction()?;
+    trans.comments_mut().upsert(comment("1", None))?;
+    assert!(trans.comments().get("1")?.is_some());
+    trans.rollback()?;
+
+    assert!(db.comments().get("1")?.is_none());
+
+    Ok(())
+}
+
+fn comment(id: &str, commit_change_id: Option<&str>) -> Comment {
+    Comment {
+        id: id.to_string(),
+        path: "src/a.rs".into(),
+        commit_change_id: commit_change_id.map(ToOwned::to_owned),
+        side: "new".into(),
+        line_number: 15,
+        line_content: "let x = 1;".into(),
+        line_before: None,
+        line_after: Some("let y = 2;".into()),
+        payload: "hello".into(),
+        created_at_ms: 1000,
+        updated_at_ms: 1000,
+        archived_at_ms: None,
+        parent_id: None,
+        author: Some("Reviewer".into()),
+        resolved_at_ms: None,
+        resolved_by: None,
+    }
+}
@@ -0,0 +1,94 @@
+use but_db::GerritChange;
+
+use crate::table::in_memory_db;
+
+#[test]
+fn get_nonexistent() -> anyhow::Result<()> {
+    let db = in_memory_db();
+
+    assert!(db.gerrit_changes().get("change-1")?.is_none());
+    assert!(db.gerrit_changes().list()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn upsert_inserts_and_replaces() -> anyhow::Result<()> {
+    let mut db = in_memory_db();
+
+    let change = gerrit_change("change-1", 42);
+    db.gerrit_changes_mut().upsert(change.clone())?;
+    assert_eq!(db.gerrit_changes().get("change-1")?, Some(change.clone()));
+
+    let updated = GerritChange {
+        status: "MERGED".into(),
+        code_review: Some(2),
+        verified: Some(1),
+        unresolved_comment_count: 0,
+        submittable: false,
+        current_revision: Some("def456".into()),
+        last_sync_at: chrono::DateTime::from_timestamp(2000000, 0)
+            .unwrap()
+            .naive_utc(),
+        ..change
+    };
+    db.gerrit_changes_mut().upsert(updated.clone())?;
+
+    assert_eq!(db.gerrit_changes().list()?, vec![updated]);
+
+    Ok(())
+}
+
+#[test]
+fn list_and_delete() -> anyhow::Result<()> {
+    let mut db = in_memory_db();
+
+    let a = gerrit_change("change-a", 1);
+    let b = gerrit_change("change-b", 2);
+    db.gerrit_changes_mut().upsert(b.clone())?;
+    db.gerrit_changes_mut().upsert(a.clone())?;
+    assert_eq!(
+        db.gerrit_changes().list()?,
+        vec![a.clone(), b.clone()],
+        "entries are ordered by change-id"
+    );
+
+    db.gerrit_changes_mut().delete("change-a")?;
+    db.gerrit_changes_mut().delete("does-not-exist")?;
+    assert_eq!(db.gerrit_changes().list()?, vec![b]);
+
+    Ok(())
+}
+
+#[test]
+fn transaction_rollback() -> anyhow::Result<()> {
+    let mut db = in_memory_db();
+
+    let mut trans = db.transaction()?;
+    trans
+        .gerrit_changes_mut()
+        .upsert(gerrit_change("change-1", 1))?;
+    assert!(trans.gerrit_changes().get("change-1")?.is_some());
+    trans.rollback()?;
+
+    assert!(db.gerrit_changes().get("change-1")?.is_none());
+
+    Ok(())
+}
+
+fn gerrit_change(change_id: &str, number: i64) -> GerritChange {
+    GerritChange {
+        change_id: change_id.to_string(),
+        number,
+        status: "NEW".into(),
+        code_review: Some(-1),
+        verified: None,
+        unresolved_comment_count: 3,
+        submittable: false,
+        work_in_progress: true,
+        current_revision: Some("abc123".into()),
+        last_sync_at: chrono::DateTime::from_timestamp(1000000, 0)
+            .unwrap()
+            .naive_utc(),
+    }
+}
@@ -4,9 +4,11 @@ mod branch_order;
 mod butler_actions;
 mod ci_check;
 mod claude;
+mod comments;
 mod fetch_status;
 mod file_write_lock;
 mod forge_review;
+mod gerrit_changes;
 mod gerrit_metadata;
 mod hunk_assignments;
 mod virtual_branches;
@@ -196,6 +196,102 @@ impl Controller {
         self.save_settings(&settings)
     }
 
+    /// Get all known Azure DevOps accounts.
+    pub fn azure_accounts(&self) -> anyhow::Result<Vec<crate::settings::AzureAccount>> {
+        let settings = self.read_settings()?;
+        Ok(settings.azure.known_accounts)
+    }
+
+    /// Add an Azure DevOps account if it does not already exist.
+    pub fn add_azure_account(&self, account: &crate::settings::AzureAccount) -> anyhow::Result<()> {
+        let mut settings = self.read_settings()?;
+
+        if settings.azure.known_accounts.iter().any(|a| a == account) {
+            return Ok(());
+        }
+
+        settings.azure.known_accounts.push(account.to_owned());
+        self.save_settings(&settings)
+    }
+
+    /// Clear all Azure DevOps accounts.
+    /// Returns the list of access token keys that should be deleted.
+    pub fn clear_all_azure_accounts(&self) -> anyhow::Result<Vec<String>> {
+        let mut settings = self.read_settings()?;
+        let access_tokens_to_delete = settings
+            .azure
+            .known_accounts
+            .iter()
+            .map(|account| account.access_token_key().to_string())
+            .collect::<Vec<String>>();
+        for key in &access_tokens_to_delete {
+            settings.cached_profiles.remove(key);
+        }
+        settings.azure.known_accounts.clear();
+        self.save_settings(&settings)?;
+
+        Ok(access_tokens_to_delete)
+    }
+
+    /// Remove an Azure DevOps account and its cached profile.
+    pub fn remove_azure_account(
+        &self,
+        account: &crate::settings::AzureAccount,
+    ) -> anyhow::Result<()> {
+        let mut settings = self.read_settings()?;
+        settings.cached_profiles.remove(account.access_token_key());
+        settings.azure.known_accounts.retain(|a| a != account);
+        self.save_settings(&settings)
+    }
+
+    /// Get all known Gitea/Forgejo accounts.
+    pub fn gitea_accounts(&self) -> anyhow::Result<Vec<crate::settings::GiteaAccount>> {
+        let settings = self.read_settings()?;
+        Ok(settings.gitea.known_accounts)
+    }
+
+    /// Add a Gitea/Forgejo account if it does not already exist.
+    pub fn add_gitea_account(&self, account: &crate::settings::GiteaAccount) -> anyhow::Result<()> {
+        let mut settings = self.read_settings()?;
+
+        if settings.gitea.known_accounts.iter().any(|a| a == account) {
+            return Ok(());
+        }
+
+        settings.gitea.known_accounts.push(account.to_owned());
+        self.save_settings(&settings)
+    }
+
+    /// Clear all Gitea/Forgejo accounts.
+    /// Returns the list of access token keys that should be deleted.
+    pub fn clear_all_gitea_accounts(&self) -> anyhow::Result<Vec<String>> {
+        let mut settings = self.read_settings()?;
+        let access_tokens_to_delete = settings
+            .gitea
+            .known_accounts
+            .iter()
+            .map(|account| account.access_token_key().to_string())
+            .collect::<Vec<String>>();
+        for key in &access_tokens_to_delete {
+            settings.cached_profiles.remove(key);
+        }
+        settings.gitea.known_accounts.clear();
+        self.save_settings(&settings)?;
+
+        Ok(access_tokens_to_delete)
+    }
+
+    /// Remove a Gitea/Forgejo account and its cached profile.
+    pub fn remove_gitea_account(
+        &self,
+        account: &crate::settings::GiteaAccount,
+    ) -> anyhow::Result<()> {
+        let mut settings = self.read_settings()?;
+        settings.cached_profiles.remove(account.access_token_key());
+        settings.gitea.known_accounts.retain(|a| a != account);
+        self.save_settings(&settings)
+    }
+
     fn read_settings(&self) -> anyhow::Result<crate::settings::ForgeSettings> {
         self.settings_storage.read()
     }
@@ -208,7 +304,9 @@ impl Controller {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::settings::{BitbucketAccount, CachedProfile, GitHubAccount, GitLabAccount};
+    use crate::settings::{
+        AzureAccount, BitbucketAccount, CachedProfile, GitHubAccount, GitLabAccount, GiteaAccount,
+    };
 
     fn test_controller() -> (Controller, tempfile::TempDir) {
         let dir = tempfile::tempdir().unwrap();
@@ -342,4 +440,64 @@ mod tests {
                 .is_none()
         );
     }
+
+    #[test]
+    fn clear_all_azure_accounts_returns_keys_and_clears_cached_profiles() {
+        let (controller, _dir) = test_controller();
+        let account = AzureAccount::Pat {
+            organization: "contoso".into(),
+            username: "az@test.com".into(),
+            access_token_key: "azure_pat_contoso".into(),
+        };
+        controller.add_azure_account(&account).unwrap();
+        controller.add_azure_account(&account).unwrap();
+        controller
+            .set_cached_profile(
+                "azure_pat_contoso",
+                Some(CachedProfile {
+                    name: Some("Azure User".into()),
+                    ..Default::default()
+                }),
+            )
+            .unwrap();
+
+        assert_eq!(
+            controller.azure_accounts().unwrap(),
+            vec![account],
+            "adding the same account twice keeps a single entry"
+        );
+        let keys = controller.clear_all_azure_accounts().unwrap();
+        assert_eq!(keys, vec!["azure_pat_contoso"]);
+        assert!(controller.azure_accounts().unwrap().is_empty());
+        assert!(
+            controller
+                .cached_profile("azure_pat_contoso")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn remove_gitea_account_only_removes_matching_host() {
+        let (controller, _dir) = test_controller();
+        let codeberg = GiteaAccount::Pat {
+            username: "alice".into(),
+            host: "https://codeberg.org".into(),
+            access_token_key: "gitea_pat_https://codeberg.org_alice".into(),
+        };
+        let self_hosted = GiteaAccount::Pat {
+            username: "alice".into(),
+            host: "https://git.example.com".into(),
+            access_token_key: "gitea_pat_https://git.example.com_alice".into(),
+        };
+        controller.add_gitea_account(&codeberg).unwrap();
+        controller.add_gitea_account(&self_hosted).unwrap();
+
+        controller.remove_gitea_account(&codeberg).unwrap();
+        assert_eq!(controller.gitea_accounts().unwrap(), vec![self_hosted]);
+
+        let keys = controller.clear_all_gitea_accounts().unwrap();
+        assert_eq!(keys, vec!["gitea_pat_https://git.example.com_alice"]);
+        assert!(controller.gitea_accounts().unwrap().is_empty());
+    }
 }
@@ -25,6 +25,12 @@ pub struct ForgeSettings {
     /// Bitbucket-specific settings.
     #[serde(default)]
     pub bitbucket: BitbucketSettings,
+    /// Azure DevOps-specific settings.
+    #[serde(default)]
+    pub azure: AzureSettings,
+    /// Gitea/Forgejo-specific settings.
+    #[serde(default)]
+    pub gitea: GiteaSettings,
     /// Cached user profiles, keyed by account `access_token_key`.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub cached_profiles: HashMap<String, CachedProfile>,
@@ -170,6 +176,94 @@ impl BitbucketAccount {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureSettings {
+    /// Azure DevOps-specific settings.
+    #[serde(default, deserialize_with = "deserialize_lenient_vec")]
+    pub known_accounts: Vec<AzureAccount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum AzureAccount {
+    /// A personal access token scoped to one Azure DevOps organization.
+    Pat {
+        // Azure DevOps organization the token was issued for.
+        organization: String,
+        // Unique name (usually the email) of the user owning the token.
+        username: String,
+        // Key to retrieve the access token from secure storage.
+        access_token_key: String,
+    },
+}
+
+impl AzureAccount {
+    pub fn access_token_key(&self) -> &str {
+        match self {
+            AzureAccount::Pat {
+                access_token_key, ..
+            } => access_token_key,
+        }
+    }
+
+    pub fn organization(&self) -> &str {
+        match self {
+            AzureAccount::Pat { organization, .. } => organization,
+        }
+    }
+
+    pub fn username(&self) -> &str {
+        match self {
+            AzureAccount::Pat { username, .. } => username,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GiteaSettings {
+    /// Gitea/Forgejo-specific settings.
+    #[serde(default, deserialize_with = "deserialize_lenient_vec")]
+    pub known_accounts: Vec<GiteaAccount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum GiteaAccount {
+    /// An access token for a user on a Gitea or Forgejo instance.
+    Pat {
+        // Username associated with the access token.
+        username: String,
+        // Base URL of the Gitea or Forgejo instance.
+        host: String,
+        // Key to retrieve the access token from secure storage.
+        access_token_key: String,
+    },
+}
+
+impl GiteaAccount {
+    pub fn access_token_key(&self) -> &str {
+        match self {
+            GiteaAccount::Pat {
+                access_token_key, ..
+            } => access_token_key,
+        }
+    }
+
+    pub fn username(&self) -> &str {
+        match self {
+            GiteaAccount::Pat { username, .. } => username,
+        }
+    }
+
+    pub fn host(&self) -> &str {
+        match self {
+            GiteaAccount::Pat { host, .. } => host,
+        }
+    }
+}
+
 /// Deserialize a list of values, silently discarding entries that cannot be
 /// deserialized (e.g. legacy bare-string usernames from an older storage format).
 fn deserialize_lenient_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
@@ -252,6 +346,20 @@ mod tests {
                     access_token_key: "bitbucket_apitoken_bb@test.com".into(),
                 }],
             },
+            azure: AzureSettings {
+                known_accounts: vec![AzureAccount::Pat {
+                    organization: "contoso".into(),
+                    username: "az@test.com".into(),
+                    access_token_key: "azure_pat_contoso_az@test.com".into(),
+                }],
+            },
+            gitea: GiteaSettings {
+                known_accounts: vec![GiteaAccount::Pat {
+                    username: "gtest".into(),
+                    host: "https://codeberg.org".into(),
+                    access_token_key: "gitea_pat_https://codeberg.org_gtest".into(),
+                }],
+            },
             cached_profiles: HashMap::new(),
         };
         let json = serde_json::to_string(&settings).unwrap();
@@ -259,6 +367,8 @@ mod tests {
         assert_eq!(roundtripped.github.known_accounts.len(), 1);
         assert_eq!(roundtripped.gitlab.known_accounts.len(), 1);
         assert_eq!(roundtripped.bitbucket.known_accounts.len(), 1);
+        assert_eq!(roundtripped.azure.known_accounts.len(), 1);
+        assert_eq!(roundtripped.gitea.known_accounts.len(), 1);
     }
 
     #[test]
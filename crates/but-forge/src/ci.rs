@@ -190,6 +190,37 @@ fn ci_checks_for_ref(
                     .collect()
             }))
         }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user
+                .as_ref()
+                .and_then(|user| user.gitea().cloned());
+            let gitea = but_gitea::GiteaClient::from_storage(storage, preferred_account.as_ref())?;
+
+            // Clone owned data for thread
+            let owner = owner.clone();
+            let repo = repo.clone();
+            let reference = reference.to_string();
+            let reference_for_checks = reference.clone();
+
+            let statuses = std::thread::spawn(move || -> anyhow::Result<_> {
+                let runtime = tokio::runtime::Runtime::new()
+                    .map_err(|err| anyhow::anyhow!("Failed to create tokio runtime: {err}"))?;
+                runtime.block_on(gitea.list_statuses_for_ref(&owner, &repo, &reference))
+            })
+            .join()
+            .map_err(|e| anyhow::anyhow!("Failed to join thread: {e:?}"))??;
+
+            Ok(statuses.map(|statuses| {
+                statuses
+                    .into_iter()
+                    .map(|status| {
+                        let mut ci_check = CiCheck::from(status);
+                        ci_check.reference = reference_for_checks.to_string();
+                        ci_check
+                    })
+                    .collect()
+            }))
+        }
         _ => Err(anyhow::anyhow!(
             "Listing ci checks for forge {forge:?} is not implemented yet."
         )),
@@ -476,6 +507,57 @@ impl From<but_bitbucket::BitbucketBuildStatus> for CiCheck {
     }
 }
 
+impl From<but_gitea::GiteaCommitStatus> for CiCheck {
+    fn from(status: but_gitea::GiteaCommitStatus) -> Self {
+        let started_at = status
+            .created_at
+            .as_deref()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+        let completed_at = status
+            .updated_at
+            .as_deref()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+
+        let ci_status = match status.status.as_str() {
+            "success" => CiStatus::Complete {
+                conclusion: CiConclusion::Success,
+                completed_at,
+            },
+            "failure" | "error" => CiStatus::Complete {
+                conclusion: CiConclusion::Failure,
+                completed_at,
+            },
+            "warning" => CiStatus::Complete {
+                conclusion: CiConclusion::Neutral,
+                completed_at,
+            },
+            "pending" => CiStatus::InProgress,
+            _ => CiStatus::Unknown,
+        };
+
+        let url = status.target_url.unwrap_or_default();
+        CiCheck {
+            id: status.id,
+            name: status.context,
+            output: CiOutput {
+                summary: status.description.unwrap_or_default(),
+                ..Default::default()
+            },
+            started_at,
+            status: ci_status,
+            head_sha: status.sha,
+            url: url.clone(),
+            html_url: url.clone(),
+            details_url: url,
+            pull_requests: Vec::new(),
+            reference: String::new(), // Will be set by the caller
+            last_sync_at: chrono::Local::now().naive_local(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{CiCheck, CiConclusion, CiOutput, CiStatus, refresh_cache_with_fetched};
@@ -685,6 +767,53 @@ mod tests {
         ));
     }
 
+    fn gitea_status(state: &str) -> but_gitea::GiteaCommitStatus {
+        but_gitea::GiteaCommitStatus {
+            id: 7,
+            status: state.into(),
+            context: "ci/build".into(),
+            description: Some("desc".into()),
+            target_url: Some("https://ci.example.com/builds/7".into()),
+            sha: "deadbeef".into(),
+            created_at: Some("2026-05-01T12:00:00Z".into()),
+            updated_at: Some("2026-05-01T12:05:00Z".into()),
+        }
+    }
+
+    #[test]
+    fn maps_gitea_commit_statuses() {
+        assert!(matches!(
+            CiCheck::from(gitea_status("success")).status,
+            CiStatus::Complete {
+                conclusion: CiConclusion::Success,
+                ..
+            }
+        ));
+        assert!(matches!(
+            CiCheck::from(gitea_status("error")).status,
+            CiStatus::Complete {
+                conclusion: CiConclusion::Failure,
+                ..
+            }
+        ));
+        assert!(matches!(
+            CiCheck::from(gitea_status("warning")).status,
+            CiStatus::Complete {
+                conclusion: CiConclusion::Neutral,
+                ..
+            }
+        ));
+        assert!(matches!(
+            CiCheck::from(gitea_status("pending")).status,
+            CiStatus::InProgress
+        ));
+
+        let check = CiCheck::from(gitea_status("success"));
+        assert_eq!(check.name, "ci/build");
+        assert_eq!(check.head_sha, "deadbeef");
+        assert_eq!(check.details_url, "https://ci.example.com/builds/7");
+    }
+
     #[test]
     fn bitbucket_build_status_id_is_stable_and_nonzero() {
         let a = CiCheck::from(bb_status("SUCCESSFUL"));
@@ -9,6 +9,7 @@ pub enum ForgeName {
     GitLab,
     Bitbucket,
     Azure,
+    Gitea,
 }
 
 #[cfg(feature = "export-schema")]
@@ -36,6 +37,8 @@ pub enum ForgeUser {
     GitHub(but_github::GithubAccountIdentifier),
     GitLab(but_gitlab::GitlabAccountIdentifier),
     Bitbucket(but_bitbucket::BitbucketAccountIdentifier),
+    Azure(but_azure::AzureAccountIdentifier),
+    Gitea(but_gitea::GiteaAccountIdentifier),
 }
 #[cfg(feature = "export-schema")]
 but_schemars::register_sdk_type!(ForgeUser);
@@ -59,11 +62,25 @@ impl ForgeUser {
             _ => None,
         }
     }
+    pub fn azure(&self) -> Option<&but_azure::AzureAccountIdentifier> {
+        match self {
+            ForgeUser::Azure(id) => Some(id),
+            _ => None,
+        }
+    }
+    pub fn gitea(&self) -> Option<&but_gitea::GiteaAccountIdentifier> {
+        match self {
+            ForgeUser::Gitea(id) => Some(id),
+            _ => None,
+        }
+    }
     pub fn forge_name(&self) -> ForgeName {
         match self {
             ForgeUser::GitHub(_) => ForgeName::GitHub,
             ForgeUser::GitLab(_) => ForgeName::GitLab,
             ForgeUser::Bitbucket(_) => ForgeName::Bitbucket,
+            ForgeUser::Azure(_) => ForgeName::Azure,
+            ForgeUser::Gitea(_) => ForgeName::Gitea,
         }
     }
     /// The enterprise/self-hosted instance host, when the account has one.
@@ -72,6 +89,8 @@ impl ForgeUser {
             ForgeUser::GitHub(id) => id.custom_host(),
             ForgeUser::GitLab(id) => id.custom_host(),
             ForgeUser::Bitbucket(id) => id.custom_host(),
+            ForgeUser::Azure(id) => id.custom_host(),
+            ForgeUser::Gitea(id) => id.custom_host(),
         }
     }
 }
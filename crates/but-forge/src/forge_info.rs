@@ -106,6 +106,7 @@ pub fn compare_branch_url(
         ForgeName::Azure => {
             format!("{base_url}/branchCompare?baseVersion=GB{base}&targetVersion=GB{head}")
         }
+        ForgeName::Gitea => format!("{base_url}/compare/{base}...{head}"),
     })
 }
 
@@ -126,6 +127,7 @@ fn build_base_url(remote_url: &str, repo_info: &ForgeRepoInfo, accounts: &[Forge
             ForgeName::GitLab => "gitlab.com".into(),
             ForgeName::Bitbucket => "bitbucket.org".into(),
             ForgeName::Azure => "dev.azure.com".into(),
+            ForgeName::Gitea => "gitea.com".into(),
         });
     let host = match parsed.as_ref().and_then(|u| u.port()) {
         Some(port) if !rewrote_scheme => format!("{host}:{port}"),
@@ -133,10 +135,10 @@ fn build_base_url(remote_url: &str, repo_info: &ForgeRepoInfo, accounts: &[Forge
     };
     match repo_info.forge {
         ForgeName::Azure => {
-            // `derive_forge_repo_info` uses git-url-parse's GenericProvider,
-            // which mangles Azure's org/project/repo triple into a single
-            // owner/repo pair — dropping the repo name (and for SSH remotes
-            // the org too). Re-parse with the Azure-specific provider.
+            // `derive_forge_repo_info` falls back to git-url-parse's
+            // GenericProvider, which mangles Azure's org/project/repo triple
+            // into a single owner/repo pair — dropping the repo name (and for
+            // SSH remotes the org too). Re-parse with the Azure-specific provider.
             // Web URLs are {host}/{org}/{project}/_git/{repo}; the browser
             // host is always dev.azure.com (the ssh.* host can't open in a
             // browser).
@@ -151,7 +153,7 @@ fn build_base_url(remote_url: &str, repo_info: &ForgeRepoInfo, accounts: &[Forge
                     az.project(),
                     az.repo()
                 ),
-                // Fallback: best-effort with the generic owner/repo.
+                // Fallback: `owner` is `org/project` for Azure remotes.
                 None => format!(
                     "{scheme}://{host}/{}/_git/{}",
                     repo_info.owner, repo_info.repo
@@ -207,12 +209,13 @@ fn url_paths(forge: &ForgeName) -> (&'static str, &'static str) {
         ForgeName::GitLab => ("/-/commit/", "/-/merge_requests/"),
         ForgeName::Bitbucket => ("/commits/", "/pull-requests/"),
         ForgeName::Azure => ("/commit/", "/pullrequest/"),
+        ForgeName::Gitea => ("/commit/", "/pulls/"),
     }
 }
 
 fn label_for(forge: &ForgeName) -> (ForgeUnitInfo, &'static str) {
     match forge {
-        ForgeName::GitHub | ForgeName::Bitbucket | ForgeName::Azure => (
+        ForgeName::GitHub | ForgeName::Bitbucket | ForgeName::Azure | ForgeName::Gitea => (
             ForgeUnitInfo {
                 name: "Pull request".into(),
                 abbr: "PR".into(),
@@ -259,12 +262,20 @@ fn capabilities_for(forge: &ForgeName) -> ForgeCapabilities {
         },
         ForgeName::Azure => ForgeCapabilities {
             checks: false,
-            repo_info: false,
-            pr_service: false,
-            list_service: false,
+            repo_info: true,
+            pr_service: true,
+            list_service: true,
             review_comments: false,
             review_management: false,
         },
+        ForgeName::Gitea => ForgeCapabilities {
+            checks: true,
+            repo_info: true,
+            pr_service: true,
+            list_service: true,
+            review_comments: true,
+            review_management: true,
+        },
     }
 }
 
@@ -510,6 +521,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn gitea_commit_and_pr_urls() {
+        let info = forge_info("https://codeberg.org/owner/repo.git", &[]).unwrap();
+        assert_eq!(info.name, ForgeName::Gitea);
+        assert!(info.capabilities.checks);
+        assert!(info.capabilities.review_management);
+        assert_eq!(
+            composed_commit_url("https://codeberg.org/owner/repo.git", "abc123"),
+            "https://codeberg.org/owner/repo/commit/abc123"
+        );
+        assert_eq!(
+            composed_pr_url("https://codeberg.org/owner/repo.git", 42),
+            "https://codeberg.org/owner/repo/pulls/42"
+        );
+    }
+
     #[test]
     fn bitbucket_commit_and_pr_urls() {
         assert_eq!(
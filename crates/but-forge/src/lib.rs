@@ -1,4 +1,7 @@
-use git_url_parse::{GitUrl, types::provider::GenericProvider};
+use git_url_parse::{
+    GitUrl,
+    types::provider::{AzureDevOpsProvider, GenericProvider},
+};
 
 mod forge;
 pub use crate::forge::{ForgeName, ForgeRepoInfo, ForgeUser, deserialize_preferred_forge_user_opt};
@@ -17,19 +20,20 @@ pub use ci::{CiCheck, CiConclusion, CiOutput, CiStatus, ci_checks_for_ref_with_c
 pub use forge_info::{ForgeCapabilities, ForgeInfo, ForgeUnitInfo, compare_branch_url, forge_info};
 pub use repo::{RepoInfo, RepoPermissions, get_repo_info};
 pub use review::{
-    CacheConfig, CreateForgeReviewParams, ForgeAccountValidity, ForgeReview, ForgeReviewComment,
-    ForgeReviewFilter, ForgeReviewLabel, ForgeReviewReaction, ForgeReviewReactionCount,
-    ForgeReviewSubmission, ForgeReviewSubmissionState, ForgeReviewTargetUpdate,
-    ForgeReviewTimelineEvent, ForgeReviewTimelineEventKind, ForgeReviewUpdate, ForgeReviewUser,
-    GitHubStackingMode, PublishReviewOutcome, ReviewMergeMethod, ReviewMergeStatus,
-    ReviewStackingDescription, ReviewState, ReviewSyncOutcome, ReviewTemplateFunctions,
-    ReviewUpdatePayload, add_comment_reaction, add_review_labels, add_review_reaction,
-    available_review_templates, cache_review, check_forge_account_is_valid,
-    compute_review_target_updates, create_forge_review, create_review_comment,
-    delete_review_comment, get_forge_review, get_review_base_repo_url, get_review_merge_status,
-    get_review_template_functions, list_comment_reactions, list_forge_reviews_for_branch,
-    list_forge_reviews_with_cache, list_repo_labels, list_review_comments, list_review_reactions,
-    list_review_submissions, list_review_timeline_events, list_reviewer_candidates, merge_review,
+    CacheConfig, CreateForgeReviewParams, ForgeAccountValidity, ForgeDiffSide,
+    ForgeInlineReviewComment, ForgeReview, ForgeReviewComment, ForgeReviewFilter, ForgeReviewLabel,
+    ForgeReviewReaction, ForgeReviewReactionCount, ForgeReviewSubmission,
+    ForgeReviewSubmissionState, ForgeReviewTargetUpdate, ForgeReviewTimelineEvent,
+    ForgeReviewTimelineEventKind, ForgeReviewUpdate, ForgeReviewUser, GitHubStackingMode,
+    PublishReviewOutcome, ReviewMergeMethod, ReviewMergeStatus, ReviewStackingDescription,
+    ReviewState, ReviewSyncOutcome, ReviewTemplateFunctions, ReviewUpdatePayload,
+    add_comment_reaction, add_review_labels, add_review_reaction, available_review_templates,
+    cache_review, check_forge_account_is_valid, compute_review_target_updates, create_forge_review,
+    create_review_comment, delete_review_comment, get_forge_review, get_review_base_repo_url,
+    get_review_merge_status, get_review_template_functions, list_comment_reactions,
+    list_forge_reviews_for_branch, list_forge_reviews_with_cache, list_inline_review_comments,
+    list_repo_labels, list_review_comments, list_review_reactions, list_review_submissions,
+    list_review_timeline_events, list_reviewer_candidates, merge_review,
     prepare_review_target_updates, remove_comment_reaction, remove_review_label,
     remove_review_reaction, request_review, restore_native_stacks, set_review_auto_merge_state,
     set_review_draftiness, sync_reviews, update_review, update_review_comment,
@@ -45,6 +49,12 @@ fn determine_forge_from_host(host: &str) -> Option<ForgeName> {
         Some(ForgeName::Bitbucket)
     } else if host.contains("azure.com") {
         Some(ForgeName::Azure)
+    } else if host.contains("codeberg.org")
+        || host.contains("gitea.com")
+        || host.starts_with("gitea.")
+        || host.starts_with("forgejo.")
+    {
+        Some(ForgeName::Gitea)
     } else {
         None
     }
@@ -70,10 +80,29 @@ pub fn derive_forge_repo_info(url: &str) -> Option<ForgeRepoInfo> {
         match_host_to_accounts_custom_host(host, &accounts)
     })?;
 
+    // Azure DevOps nests repositories as `org/project/repo`, which the generic provider
+    // can't represent. Keep the organization and project together as the owner.
+    let (owner, repo) = match forge {
+        ForgeName::Azure => match git_url.provider_info::<AzureDevOpsProvider>() {
+            Ok(az) => (
+                format!("{}/{}", az.org(), az.project()),
+                az.repo().to_string(),
+            ),
+            Err(_) => (
+                provider_info.owner().to_string(),
+                provider_info.repo().to_string(),
+            ),
+        },
+        _ => (
+            provider_info.owner().to_string(),
+            provider_info.repo().to_string(),
+        ),
+    };
+
     Some(ForgeRepoInfo {
         forge,
-        owner: provider_info.owner().to_string(),
-        repo: provider_info.repo().to_string(),
+        owner,
+        repo,
         protocol: protocol.to_string(),
     })
 }
@@ -165,6 +194,16 @@ pub fn current_forge_login(
                 .or(accounts.first())
                 .map(|account| account.username().to_string()))
         }
+        ForgeName::Gitea => {
+            let accounts = but_gitea::list_known_gitea_accounts(storage)?;
+            let preferred = preferred_forge_user
+                .as_ref()
+                .and_then(|user| user.gitea())
+                .filter(|preferred| accounts.contains(preferred));
+            Ok(preferred
+                .or(accounts.first())
+                .map(|account| account.username().to_string()))
+        }
         _ => Ok(None),
     }
 }
@@ -174,6 +213,7 @@ pub fn get_all_forge_accounts() -> anyhow::Result<Vec<ForgeUser>> {
     let storage = but_forge_storage::Controller::from_path(but_path::app_data_dir()?);
     let gh_accounts = but_github::list_known_github_accounts(&storage)?;
     let gl_accounts = but_gitlab::list_known_gitlab_accounts(&storage)?;
+    let gitea_accounts = but_gitea::list_known_gitea_accounts(&storage)?;
 
     let mut forge_users = vec![];
     for gh_account in gh_accounts {
@@ -184,13 +224,19 @@ pub fn get_all_forge_accounts() -> anyhow::Result<Vec<ForgeUser>> {
         forge_users.push(ForgeUser::GitLab(gl_account));
     }
 
+    // Self-hosted Gitea and Forgejo instances can only be recognized by their account's host.
+    for gitea_account in gitea_accounts {
+        forge_users.push(ForgeUser::Gitea(gitea_account));
+    }
+
     Ok(forge_users)
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        ForgeName, ForgeUser, match_host_to_accounts_custom_host, normalize_host_for_comparison,
+        ForgeName, ForgeUser, derive_forge_repo_info, match_host_to_accounts_custom_host,
+        normalize_host_for_comparison,
     };
 
     #[test]
@@ -217,6 +263,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn matches_self_hosted_gitea_custom_host() {
+        let accounts = vec![ForgeUser::Gitea(but_gitea::GiteaAccountIdentifier::pat(
+            "carol",
+            "https://git.example.com",
+        ))];
+
+        assert_eq!(
+            match_host_to_accounts_custom_host("git.example.com", &accounts),
+            Some(ForgeName::Gitea)
+        );
+    }
+
+    #[test]
+    fn codeberg_remotes_are_gitea() {
+        let info = derive_forge_repo_info("git@codeberg.org:alice/web.git").unwrap();
+        assert_eq!(info.forge, ForgeName::Gitea);
+        assert_eq!(info.owner, "alice");
+        assert_eq!(info.repo, "web");
+    }
+
     #[test]
     fn does_not_match_accounts_without_custom_host() {
         let accounts = vec![
@@ -307,6 +374,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn azure_remotes_keep_organization_and_project_in_owner() {
+        for url in [
+            "https://dev.azure.com/myorg/myproject/_git/myrepo",
+            "git@ssh.dev.azure.com:v3/myorg/myproject/myrepo",
+        ] {
+            let info = derive_forge_repo_info(url).unwrap();
+            assert_eq!(info.forge, ForgeName::Azure);
+            assert_eq!(info.owner, "myorg/myproject", "{url}");
+            assert_eq!(info.repo, "myrepo", "{url}");
+        }
+    }
+
     #[test]
     fn normalize_host_for_comparison_strips_url_parts_and_normalizes_case() {
         assert_eq!(
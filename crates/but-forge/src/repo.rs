@@ -34,10 +34,19 @@ pub async fn get_repo_info(
                 .await
                 .map(RepoInfo::from)
         }
-        ForgeName::Azure => Err(anyhow::anyhow!(
-            "Fetching repo info for forge {:?} is not implemented yet.",
-            forge_repo_info.forge
-        )),
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            but_azure::fetch_repo(preferred_account, &repo_id, storage)
+                .await
+                .map(RepoInfo::from)
+        }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            but_gitea::fetch_repo(preferred_account, owner, repo, storage)
+                .await
+                .map(RepoInfo::from)
+        }
     }
 }
 
@@ -135,6 +144,46 @@ impl From<but_bitbucket::BitbucketRepo> for RepoInfo {
     }
 }
 
+impl From<but_azure::AzureRepo> for RepoInfo {
+    fn from(value: but_azure::AzureRepo) -> Self {
+        // Azure DevOps exposes no per-caller permission summary on the repository,
+        // so default to optimistic permissions like Bitbucket's unknown case;
+        // branch policies are enforced server-side.
+        RepoInfo {
+            permissions: Some(RepoPermissions {
+                admin: false,
+                maintain: false,
+                push: true,
+                triage: true,
+                pull: true,
+            }),
+            fork: value.is_fork,
+            // Source branch deletion is a per-PR completion option, not a repository setting.
+            delete_branch_on_merge: None,
+        }
+    }
+}
+
+impl From<but_gitea::GiteaRepo> for RepoInfo {
+    fn from(value: but_gitea::GiteaRepo) -> Self {
+        // Gitea only distinguishes admin, push and pull; map the finer GitHub
+        // levels onto the closest of them.
+        let permissions = value.permissions.map(|permissions| RepoPermissions {
+            admin: permissions.admin,
+            maintain: permissions.admin,
+            push: permissions.push,
+            triage: permissions.push,
+            pull: permissions.pull,
+        });
+        RepoInfo {
+            permissions,
+            fork: value.fork,
+            // Branch deletion is chosen when merging rather than configured on the repository.
+            delete_branch_on_merge: None,
+        }
+    }
+}
+
 impl From<but_gitlab::GitLabProject> for RepoInfo {
     fn from(value: but_gitlab::GitLabProject) -> Self {
         // GitLab access levels: 10=Guest, 20=Reporter, 30=Developer,
@@ -108,6 +108,15 @@ pub fn get_review_template_functions(forge_name: &ForgeName) -> ReviewTemplateFu
             is_valid_review_template_path: is_valid_review_template_path_azure,
             supported_template_directories: &[SupportedTemplateDirectory::ForgeRoot],
         },
+        ForgeName::Gitea => ReviewTemplateFunctions {
+            is_review_template: is_review_template_gitea,
+            get_root: get_gitea_directory_path,
+            is_valid_review_template_path: is_valid_review_template_path_gitea,
+            supported_template_directories: &[
+                SupportedTemplateDirectory::ForgeRoot,
+                SupportedTemplateDirectory::ProjectRoot,
+            ],
+        },
     }
 }
 
@@ -180,6 +189,26 @@ fn is_valid_review_template_path_azure(_path: &path::Path) -> bool {
     false
 }
 
+fn get_gitea_directory_path(root_path: &path::Path) -> path::PathBuf {
+    let mut path = root_path.to_path_buf();
+    path.push(".gitea");
+    path
+}
+
+fn is_review_template_gitea(path_str: &str) -> bool {
+    let normalized_path = path_str.replace('\\', "/");
+    normalized_path == "PULL_REQUEST_TEMPLATE.md"
+        || normalized_path == "pull_request_template.md"
+        || normalized_path.contains(".gitea/PULL_REQUEST_TEMPLATE")
+            && normalized_path.ends_with(".md")
+        || normalized_path.contains(".gitea/pull_request_template")
+            && normalized_path.ends_with(".md")
+}
+
+fn is_valid_review_template_path_gitea(path: &path::Path) -> bool {
+    is_review_template_gitea(path.to_str().unwrap_or_default())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
@@ -202,6 +231,16 @@ impl From<but_github::GitHubPrLabel> for ForgeReviewLabel {
     }
 }
 
+impl From<but_gitea::GiteaLabel> for ForgeReviewLabel {
+    fn from(label: but_gitea::GiteaLabel) -> Self {
+        ForgeReviewLabel {
+            name: label.name,
+            description: label.description,
+            color: label.color,
+        }
+    }
+}
+
 impl From<but_gitlab::GitLabLabel> for ForgeReviewLabel {
     fn from(label: but_gitlab::GitLabLabel) -> Self {
         ForgeReviewLabel {
@@ -287,6 +326,32 @@ impl From<but_bitbucket::BitbucketUser> for ForgeReviewUser {
     }
 }
 
+impl From<but_azure::AzureUser> for ForgeReviewUser {
+    fn from(user: but_azure::AzureUser) -> Self {
+        ForgeReviewUser {
+            id: user.id,
+            login: user.username,
+            name: user.name,
+            email: user.email,
+            avatar_url: user.avatar_url,
+            is_bot: user.is_bot,
+        }
+    }
+}
+
+impl From<but_gitea::GiteaUser> for ForgeReviewUser {
+    fn from(user: but_gitea::GiteaUser) -> Self {
+        ForgeReviewUser {
+            id: user.id,
+            login: user.username,
+            name: user.name,
+            email: user.email,
+            avatar_url: user.avatar_url,
+            is_bot: user.is_bot,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
@@ -497,6 +562,90 @@ impl From<but_bitbucket::BitbucketPullRequest> for ForgeReview {
     }
 }
 
+impl From<but_azure::AzurePullRequest> for ForgeReview {
+    fn from(pr: but_azure::AzurePullRequest) -> Self {
+        let merged_at = pr.merged_at();
+        let closed_at = pr.abandoned_at();
+        let integration_commit_shas = pr.merge_commit_id.clone().into_iter().collect();
+        ForgeReview {
+            html_url: pr.html_url,
+            number: pr.id,
+            title: pr.title,
+            body: pr.description,
+            author: pr.author.map(ForgeReviewUser::from),
+            labels: pr
+                .labels
+                .into_iter()
+                .map(|name| ForgeReviewLabel {
+                    name,
+                    description: None,
+                    color: None,
+                })
+                .collect(),
+            draft: pr.draft,
+            source_branch: pr.source_branch,
+            target_branch: pr.target_branch,
+            sha: pr.source_commit_id,
+            integration_commit_shas,
+            created_at: pr.created_at,
+            // Azure DevOps doesn't report a last-modified time for pull requests.
+            modified_at: None,
+            merged_at,
+            closed_at,
+            repository_ssh_url: None,
+            repository_https_url: None,
+            repo_owner: None,
+            head_repo_is_fork: pr.head_repo_is_fork,
+            reviewers: pr
+                .reviewers
+                .into_iter()
+                .map(ForgeReviewUser::from)
+                .collect(),
+            // Auto-complete is a per-PR policy that isn't exposed here yet.
+            auto_merge_enabled: false,
+            unit_symbol: "#".to_string(),
+            last_sync_at: chrono::Local::now().naive_local(),
+        }
+    }
+}
+
+impl From<but_gitea::GiteaPullRequest> for ForgeReview {
+    fn from(pr: but_gitea::GiteaPullRequest) -> Self {
+        let closed_at = pr.closed_unmerged_at();
+        let integration_commit_shas = pr.merge_commit_sha.clone().into_iter().collect();
+        ForgeReview {
+            html_url: pr.html_url,
+            number: pr.number,
+            title: pr.title,
+            body: pr.body,
+            author: pr.author.map(ForgeReviewUser::from),
+            labels: pr.labels.into_iter().map(ForgeReviewLabel::from).collect(),
+            draft: pr.draft,
+            source_branch: pr.source_branch,
+            target_branch: pr.target_branch,
+            sha: pr.sha,
+            integration_commit_shas,
+            created_at: pr.created_at,
+            modified_at: pr.updated_at,
+            merged_at: pr.merged_at,
+            closed_at,
+            repository_ssh_url: pr.repository_ssh_url,
+            repository_https_url: pr.repository_https_url,
+            repo_owner: pr.repo_owner,
+            head_repo_is_fork: pr.head_repo_is_fork,
+            reviewers: pr
+                .requested_reviewers
+                .into_iter()
+                .map(ForgeReviewUser::from)
+                .collect(),
+            // Gitea's "merge when checks succeed" isn't reported on the pull request.
+            auto_merge_enabled: false,
+            unit_symbol: "#".to_string(),
+            last_sync_at: chrono::Local::now().naive_local(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
@@ -595,6 +744,26 @@ impl From<but_bitbucket::CredentialCheckResult> for ForgeAccountValidity {
     }
 }
 
+impl From<but_azure::CredentialCheckResult> for ForgeAccountValidity {
+    fn from(value: but_azure::CredentialCheckResult) -> Self {
+        match value {
+            but_azure::CredentialCheckResult::Invalid => ForgeAccountValidity::Invalid,
+            but_azure::CredentialCheckResult::NoCredentials => ForgeAccountValidity::NoCredentials,
+            but_azure::CredentialCheckResult::Valid => ForgeAccountValidity::Valid,
+        }
+    }
+}
+
+impl From<but_gitea::CredentialCheckResult> for ForgeAccountValidity {
+    fn from(value: but_gitea::CredentialCheckResult) -> Self {
+        match value {
+            but_gitea::CredentialCheckResult::Invalid => ForgeAccountValidity::Invalid,
+            but_gitea::CredentialCheckResult::NoCredentials => ForgeAccountValidity::NoCredentials,
+            but_gitea::CredentialCheckResult::Valid => ForgeAccountValidity::Valid,
+        }
+    }
+}
+
 /// Check whether there's an account that would be used for this repository is authenticated.
 pub async fn check_forge_account_is_valid(
     preferred_forge_user: Option<crate::ForgeUser>,
@@ -665,6 +834,54 @@ pub async fn check_forge_account_is_valid(
                 .await
                 .map(Into::into)
         }
+        ForgeName::Azure => {
+            let preferred_account = match preferred_forge_user
+                .as_ref()
+                .and_then(|user| user.azure().cloned())
+            {
+                Some(account) => account,
+                None => {
+                    // PATs are scoped to an organization, so prefer an account that can see this repo.
+                    let organization = owner_organization(&forge_repo_info.owner);
+                    let known_accounts = but_azure::list_known_azure_accounts(storage)?;
+                    match known_accounts
+                        .iter()
+                        .find(|account| account.organization().eq_ignore_ascii_case(organization))
+                        .or(known_accounts.first())
+                    {
+                        Some(account) => account.clone(),
+                        None => {
+                            return Ok(ForgeAccountValidity::NoCredentials);
+                        }
+                    }
+                }
+            };
+
+            but_azure::check_credentials(&preferred_account, storage)
+                .await
+                .map(Into::into)
+        }
+        ForgeName::Gitea => {
+            let preferred_account = match preferred_forge_user
+                .as_ref()
+                .and_then(|user| user.gitea().cloned())
+            {
+                Some(account) => account,
+                None => {
+                    let known_accounts = but_gitea::list_known_gitea_accounts(storage)?;
+                    match known_accounts.first() {
+                        Some(account) => account.clone(),
+                        None => {
+                            return Ok(ForgeAccountValidity::NoCredentials);
+                        }
+                    }
+                }
+            };
+
+            but_gitea::check_credentials(&preferred_account, storage)
+                .await
+                .map(Into::into)
+        }
         _ => Err(Error::msg(format!(
             "Checking reviews for forge {:?} is not implemented yet",
             forge_repo_info.forge
@@ -761,6 +978,58 @@ fn list_forge_reviews(
                 .map(ForgeReview::from)
                 .collect::<Vec<ForgeReview>>()
         }
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user
+                .as_ref()
+                .and_then(|user| user.azure().cloned());
+
+            // Clone owned data for thread
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let storage = storage.clone();
+
+            let prs = std::thread::spawn(move || {
+                tokio::runtime::Runtime::new()
+                    .unwrap()
+                    .block_on(but_azure::pr::list(
+                        preferred_account.as_ref(),
+                        &repo_id,
+                        &storage,
+                    ))
+            })
+            .join()
+            .map_err(|e| anyhow::anyhow!("Failed to join thread: {e:?}"))??;
+
+            prs.into_iter()
+                .map(ForgeReview::from)
+                .collect::<Vec<ForgeReview>>()
+        }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user
+                .as_ref()
+                .and_then(|user| user.gitea().cloned());
+
+            // Clone owned data for thread
+            let owner = owner.clone();
+            let repo = repo.clone();
+            let storage = storage.clone();
+
+            let prs = std::thread::spawn(move || {
+                tokio::runtime::Runtime::new()
+                    .unwrap()
+                    .block_on(but_gitea::pr::list(
+                        preferred_account.as_ref(),
+                        &owner,
+                        &repo,
+                        &storage,
+                    ))
+            })
+            .join()
+            .map_err(|e| anyhow::anyhow!("Failed to join thread: {e:?}"))??;
+
+            prs.into_iter()
+                .map(ForgeReview::from)
+                .collect::<Vec<ForgeReview>>()
+        }
         _ => {
             return Err(Error::msg(format!(
                 "Listing reviews for forge {forge:?} is not implemented yet.",
@@ -843,6 +1112,36 @@ pub async fn list_forge_reviews_for_branch(
             let prs = filter_bb_prs(prs, &filter);
             Ok(prs.into_iter().map(ForgeReview::from).collect())
         }
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user
+                .as_ref()
+                .and_then(|user| user.azure().cloned());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let prs = but_azure::pr::list_all_for_target(
+                preferred_account.as_ref(),
+                &repo_id,
+                branch,
+                storage,
+            )
+            .await?;
+            let prs = filter_az_prs(prs, &filter);
+            Ok(prs.into_iter().map(ForgeReview::from).collect())
+        }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user
+                .as_ref()
+                .and_then(|user| user.gitea().cloned());
+            let prs = but_gitea::pr::list_all_for_target(
+                preferred_account.as_ref(),
+                owner,
+                repo,
+                branch,
+                storage,
+            )
+            .await?;
+            let prs = filter_gitea_prs(prs, &filter);
+            Ok(prs.into_iter().map(ForgeReview::from).collect())
+        }
         _ => Err(Error::msg(format!(
             "Listing reviews for forge {forge:?} is not implemented yet.",
         ))),
@@ -964,6 +1263,71 @@ fn filter_bb_prs(
         .collect()
 }
 
+fn filter_az_prs(
+    prs: Vec<but_azure::AzurePullRequest>,
+    filter: &ForgeReviewFilter,
+) -> Vec<but_azure::AzurePullRequest> {
+    let now = chrono::Utc::now();
+    prs.into_iter()
+        .filter(|pr| {
+            let Some(merged_at_str) = pr.merged_at() else {
+                return false;
+            };
+            let Ok(merged_at) = chrono::DateTime::parse_from_rfc3339(&merged_at_str) else {
+                return false;
+            };
+            match filter {
+                ForgeReviewFilter::Today => merged_at.date_naive() == now.date_naive(),
+                ForgeReviewFilter::ThisWeek => {
+                    let week_start =
+                        now - chrono::Duration::days(now.weekday().num_days_from_monday() as i64);
+                    merged_at.date_naive() >= week_start.date_naive()
+                }
+                ForgeReviewFilter::ThisMonth => {
+                    merged_at.year() == now.year() && merged_at.month() == now.month()
+                }
+                ForgeReviewFilter::All => true,
+            }
+        })
+        .collect()
+}
+
+fn filter_gitea_prs(
+    prs: Vec<but_gitea::GiteaPullRequest>,
+    filter: &ForgeReviewFilter,
+) -> Vec<but_gitea::GiteaPullRequest> {
+    let now = chrono::Utc::now();
+    prs.into_iter()
+        .filter(|pr| {
+            let Some(merged_at_str) = &pr.merged_at else {
+                return false;
+            };
+            let Ok(merged_at) = chrono::DateTime::parse_from_rfc3339(merged_at_str) else {
+                return false;
+            };
+            match filter {
+                ForgeReviewFilter::Today => merged_at.date_naive() == now.date_naive(),
+                ForgeReviewFilter::ThisWeek => {
+                    let week_start =
+                        now - chrono::Duration::days(now.weekday().num_days_from_monday() as i64);
+                    merged_at.date_naive() >= week_start.date_naive()
+                }
+                ForgeReviewFilter::ThisMonth => {
+                    merged_at.year() == now.year() && merged_at.month() == now.month()
+                }
+                ForgeReviewFilter::All => true,
+            }
+        })
+        .collect()
+}
+
+/// The organization part of an Azure DevOps `organization/project` owner.
+fn owner_organization(owner: &str) -> &str {
+    owner
+        .split_once('/')
+        .map_or(owner, |(organization, _)| organization)
+}
+
 async fn get_forge_review_inner(
     preferred_forge_user: &Option<crate::ForgeUser>,
     forge_repo_info: &crate::forge::ForgeRepoInfo,
@@ -995,6 +1359,19 @@ async fn get_forge_review_inner(
                 .await?;
             Ok(ForgeReview::from(pr))
         }
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let pr =
+                but_azure::pr::get(preferred_account, &repo_id, review_number, storage).await?;
+            Ok(ForgeReview::from(pr))
+        }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let pr =
+                but_gitea::pr::get(preferred_account, owner, repo, review_number, storage).await?;
+            Ok(ForgeReview::from(pr))
+        }
         _ => Err(Error::msg(format!(
             "Getting reviews for forge {forge:?} is not implemented yet.",
         ))),
@@ -1097,6 +1474,116 @@ impl From<but_github::PullRequestComment> for ForgeReviewComment {
     }
 }
 
+impl From<but_gitea::GiteaComment> for ForgeReviewComment {
+    fn from(comment: but_gitea::GiteaComment) -> Self {
+        ForgeReviewComment {
+            id: comment.id,
+            body: comment.body,
+            author: comment.author.map(ForgeReviewUser::from),
+            created_at: comment.created_at,
+            modified_at: comment.updated_at,
+            html_url: comment.html_url,
+            // Comment reactions need a request per comment; they aren't fetched here.
+            reactions: Vec::new(),
+        }
+    }
+}
+
+/// The side of a review's diff that an inline comment is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeDiffSide {
+    /// The base side, i.e. removed lines and context lines.
+    Old,
+    /// The head side, i.e. added lines and context lines.
+    New,
+}
+
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(ForgeDiffSide);
+
+/// A comment on a line of the diff of a review, as opposed to a [`ForgeReviewComment`] in its
+/// conversation.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeInlineReviewComment {
+    /// Forge-assigned identifier of the comment.
+    pub id: i64,
+    /// The comment this comment replies to, if any.
+    pub in_reply_to: Option<i64>,
+    /// The repository-relative path of the commented file.
+    pub path: String,
+    /// The 1-based number of the commented line on `side`, or `None` if the comment is outdated.
+    pub line: Option<u32>,
+    /// The side of the diff the commented line is on.
+    pub side: ForgeDiffSide,
+    /// The full id of the commit the comment was made on, if the forge reports it.
+    pub commit_id: Option<String>,
+    /// The content of the commented line, if the forge reports it.
+    pub line_content: Option<String>,
+    /// The content of the line before the commented one on the same side, if the forge reports it.
+    pub line_before: Option<String>,
+    /// The comment text, as forge-flavored markdown.
+    pub body: String,
+    /// The comment's author.
+    pub author: Option<ForgeReviewUser>,
+    /// ISO 8601 timestamp of when the comment was created.
+    pub created_at: Option<String>,
+    /// ISO 8601 timestamp of the comment's last edit.
+    pub modified_at: Option<String>,
+    /// The URL to view this comment in a web browser.
+    pub html_url: String,
+}
+
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(ForgeInlineReviewComment);
+
+impl From<but_github::PullRequestReviewComment> for ForgeInlineReviewComment {
+    fn from(comment: but_github::PullRequestReviewComment) -> Self {
+        let side = match comment.side.as_deref() {
+            Some("LEFT") => ForgeDiffSide::Old,
+            _ => ForgeDiffSide::New,
+        };
+        let (line_content, line_before) = commented_lines(&comment.diff_hunk, side);
+        ForgeInlineReviewComment {
+            id: comment.id,
+            in_reply_to: comment.in_reply_to_id,
+            path: comment.path,
+            line: comment.line,
+            side,
+            commit_id: Some(comment.commit_id),
+            line_content,
+            line_before,
+            body: comment.body,
+            author: comment.author.map(ForgeReviewUser::from),
+            created_at: comment.created_at,
+            modified_at: comment.modified_at,
+            html_url: comment.html_url,
+        }
+    }
+}
+
+/// Extract the commented line and the line before it on `side` from a `diff_hunk` that ends with
+/// the commented line, as GitHub reports it.
+fn commented_lines(diff_hunk: &str, side: ForgeDiffSide) -> (Option<String>, Option<String>) {
+    let other_side_marker = match side {
+        ForgeDiffSide::Old => '+',
+        ForgeDiffSide::New => '-',
+    };
+    let mut rows = diff_hunk
+        .lines()
+        .rev()
+        .filter(|row| !row.starts_with("@@") && !row.starts_with('\\'));
+    let content = |row: &str| row.get(1..).unwrap_or_default().to_owned();
+    let line_content = rows.next().map(content);
+    let line_before = rows
+        .find(|row| !row.starts_with(other_side_marker))
+        .map(content);
+    (line_content, line_before)
+}
+
 /// List the labels defined on the repository backing a review.
 pub async fn list_repo_labels(
     preferred_forge_user: &Option<crate::ForgeUser>,
@@ -1113,6 +1600,12 @@ pub async fn list_repo_labels(
                 but_github::pr::list_repo_labels(preferred_account, owner, repo, storage).await?;
             Ok(labels.into_iter().map(Into::into).collect())
         }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let labels =
+                but_gitea::pr::list_repo_labels(preferred_account, owner, repo, storage).await?;
+            Ok(labels.into_iter().map(Into::into).collect())
+        }
         _ => Err(anyhow::anyhow!(
             "Repository labels for forge {forge:?} are not implemented yet."
         )),
@@ -1144,6 +1637,19 @@ pub async fn add_review_labels(
             .await?;
             Ok(labels.into_iter().map(Into::into).collect())
         }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let labels = but_gitea::pr::add_labels(
+                preferred_account,
+                owner,
+                repo,
+                review_number,
+                labels,
+                storage,
+            )
+            .await?;
+            Ok(labels.into_iter().map(Into::into).collect())
+        }
         _ => Err(anyhow::anyhow!(
             "Review labels for forge {forge:?} are not implemented yet."
         )),
@@ -1174,6 +1680,18 @@ pub async fn remove_review_label(
             )
             .await
         }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            but_gitea::pr::remove_label(
+                preferred_account,
+                owner,
+                repo,
+                review_number,
+                label,
+                storage,
+            )
+            .await
+        }
         _ => Err(anyhow::anyhow!(
             "Review labels for forge {forge:?} are not implemented yet."
         )),
@@ -1197,6 +1715,13 @@ pub async fn list_reviewer_candidates(
                     .await?;
             Ok(users.into_iter().map(Into::into).collect())
         }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let users =
+                but_gitea::pr::list_reviewer_candidates(preferred_account, owner, repo, storage)
+                    .await?;
+            Ok(users.into_iter().map(Into::into).collect())
+        }
         _ => Err(anyhow::anyhow!(
             "Reviewer candidates for forge {forge:?} are not implemented yet."
         )),
@@ -1227,6 +1752,18 @@ pub async fn request_review(
             )
             .await
         }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            but_gitea::pr::request_reviewers(
+                preferred_account,
+                owner,
+                repo,
+                review_number,
+                logins,
+                storage,
+            )
+            .await
+        }
         _ => Err(anyhow::anyhow!(
             "Review requests for forge {forge:?} are not implemented yet."
         )),
@@ -1257,6 +1794,18 @@ pub async fn withdraw_review_request(
             )
             .await
         }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            but_gitea::pr::remove_requested_reviewers(
+                preferred_account,
+                owner,
+                repo,
+                review_number,
+                logins,
+                storage,
+            )
+            .await
+        }
         _ => Err(anyhow::anyhow!(
             "Review requests for forge {forge:?} are not implemented yet."
         )),
@@ -1327,6 +1876,19 @@ pub async fn update_review_comment(
             .await?;
             Ok(comment.into())
         }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let comment = but_gitea::pr::update_comment(
+                preferred_account,
+                owner,
+                repo,
+                comment_id,
+                body,
+                storage,
+            )
+            .await?;
+            Ok(comment.into())
+        }
         _ => Err(anyhow::anyhow!(
             "Review comments for forge {forge:?} are not implemented yet."
         )),
@@ -1349,6 +1911,10 @@ pub async fn delete_review_comment(
             but_github::pr::delete_comment(preferred_account, owner, repo, comment_id, storage)
                 .await
         }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            but_gitea::pr::delete_comment(preferred_account, owner, repo, comment_id, storage).await
+        }
         _ => Err(anyhow::anyhow!(
             "Review comments for forge {forge:?} are not implemented yet."
         )),
@@ -1368,6 +1934,20 @@ fn github_submission_state(raw: &str) -> Option<ForgeReviewSubmissionState> {
     }
 }
 
+/// Map a Gitea review state to the forge-agnostic verdict.
+/// Pending reviews are the caller's own unsubmitted drafts and are omitted.
+fn gitea_submission_state(state: but_gitea::ReviewState) -> Option<ForgeReviewSubmissionState> {
+    match state {
+        but_gitea::ReviewState::Approved => Some(ForgeReviewSubmissionState::Approved),
+        but_gitea::ReviewState::RequestChanges => {
+            Some(ForgeReviewSubmissionState::ChangesRequested)
+        }
+        but_gitea::ReviewState::Comment => Some(ForgeReviewSubmissionState::Commented),
+        but_gitea::ReviewState::Dismissed => Some(ForgeReviewSubmissionState::Dismissed),
+        but_gitea::ReviewState::Pending => None,
+    }
+}
+
 /// List the submitted reviews on a review, oldest first. Each call hits
 /// the forge fresh (no DB cache).
 pub async fn list_review_submissions(
@@ -1405,26 +1985,111 @@ pub async fn list_review_submissions(
                 })
                 .collect())
         }
-        // Read as empty rather than erroring; see list_review_comments.
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let reviews =
+                but_gitea::pr::list_reviews(preferred_account, owner, repo, review_number, storage)
+                    .await?;
+            Ok(reviews
+                .into_iter()
+                .filter_map(|review| {
+                    let state = gitea_submission_state(review.state)?;
+                    Some(ForgeReviewSubmission {
+                        id: review.id,
+                        author: review.author.map(ForgeReviewUser::from),
+                        state,
+                        body: review.body.filter(|body| !body.trim().is_empty()),
+                        submitted_at: review.submitted_at,
+                        html_url: review.html_url,
+                    })
+                })
+                .collect())
+        }
+        // Read as empty rather than erroring; see list_review_comments.
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// List the top-level conversation comments on a review, oldest first.
+/// Each call hits the forge fresh (no DB cache).
+pub async fn list_review_comments(
+    preferred_forge_user: &Option<crate::ForgeUser>,
+    forge_repo_info: &crate::forge::ForgeRepoInfo,
+    review_number: usize,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<ForgeReviewComment>> {
+    let crate::forge::ForgeRepoInfo {
+        forge, owner, repo, ..
+    } = forge_repo_info;
+    match forge {
+        ForgeName::GitHub => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.github());
+            let comments = but_github::pr::list_comments(
+                preferred_account,
+                owner,
+                repo,
+                review_number,
+                storage,
+            )
+            .await?;
+            Ok(comments.into_iter().map(Into::into).collect())
+        }
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let threads =
+                but_azure::pr::list_threads(preferred_account, &repo_id, review_number, storage)
+                    .await?;
+            let review_url = repo_id.pull_request_web_url(review_number.try_into()?);
+            // Only general threads form the conversation; file threads are diff-anchored.
+            let mut comments: Vec<_> = threads
+                .into_iter()
+                .filter(|thread| thread.file_path.is_none())
+                .flat_map(|thread| {
+                    let review_url = review_url.clone();
+                    thread.comments.into_iter().filter_map(move |comment| {
+                        azure_review_comment(comment, thread.id, &review_url)
+                    })
+                })
+                .collect();
+            comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+            Ok(comments)
+        }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let comments = but_gitea::pr::list_comments(
+                preferred_account,
+                owner,
+                repo,
+                review_number,
+                storage,
+            )
+            .await?;
+            Ok(comments.into_iter().map(Into::into).collect())
+        }
+        // Read as empty rather than erroring: the UI polls this for every
+        // open review, and a forge without comment support shouldn't turn
+        // that into a permanent failure loop.
         _ => Ok(Vec::new()),
     }
 }
 
-/// List the top-level conversation comments on a review, oldest first.
-/// Each call hits the forge fresh (no DB cache).
-pub async fn list_review_comments(
+/// List the comments on lines of the diff of a review, oldest first.
+///
+/// Only GitHub and Azure DevOps are supported; other forges read as empty.
+pub async fn list_inline_review_comments(
     preferred_forge_user: &Option<crate::ForgeUser>,
     forge_repo_info: &crate::forge::ForgeRepoInfo,
     review_number: usize,
     storage: &but_forge_storage::Controller,
-) -> Result<Vec<ForgeReviewComment>> {
+) -> Result<Vec<ForgeInlineReviewComment>> {
     let crate::forge::ForgeRepoInfo {
         forge, owner, repo, ..
     } = forge_repo_info;
     match forge {
         ForgeName::GitHub => {
             let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.github());
-            let comments = but_github::pr::list_comments(
+            let comments = but_github::pr::list_review_comments(
                 preferred_account,
                 owner,
                 repo,
@@ -1434,9 +2099,48 @@ pub async fn list_review_comments(
             .await?;
             Ok(comments.into_iter().map(Into::into).collect())
         }
-        // Read as empty rather than erroring: the UI polls this for every
-        // open review, and a forge without comment support shouldn't turn
-        // that into a permanent failure loop.
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let threads =
+                but_azure::pr::list_threads(preferred_account, &repo_id, review_number, storage)
+                    .await?;
+            let review_url = repo_id.pull_request_web_url(review_number.try_into()?);
+            let mut comments = Vec::new();
+            for thread in threads {
+                let Some(path) = thread.file_path else {
+                    continue;
+                };
+                let path = path.trim_start_matches('/').to_owned();
+                let mut root_id = None;
+                for comment in thread.comments.into_iter().filter(|c| !c.is_system) {
+                    let Some(comment) = azure_review_comment(comment, thread.id, &review_url)
+                    else {
+                        continue;
+                    };
+                    comments.push(ForgeInlineReviewComment {
+                        id: comment.id,
+                        // Azure threads are flat, so every later comment answers the first.
+                        in_reply_to: root_id,
+                        path: path.clone(),
+                        line: thread.line,
+                        side: ForgeDiffSide::New,
+                        commit_id: None,
+                        line_content: None,
+                        line_before: None,
+                        body: comment.body,
+                        author: comment.author,
+                        created_at: comment.created_at,
+                        modified_at: comment.modified_at,
+                        html_url: comment.html_url,
+                    });
+                    root_id.get_or_insert(comment.id);
+                }
+            }
+            comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+            Ok(comments)
+        }
+        // Read as empty rather than erroring; see list_review_comments.
         _ => Ok(Vec::new()),
     }
 }
@@ -1753,12 +2457,81 @@ pub async fn create_review_comment(
             .await?;
             Ok(comment.into())
         }
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let thread = but_azure::pr::create_thread(
+                preferred_account,
+                &repo_id,
+                review_number,
+                body,
+                None,
+                storage,
+            )
+            .await?;
+            let review_url = repo_id.pull_request_web_url(review_number.try_into()?);
+            let comment = thread
+                .comments
+                .into_iter()
+                .next()
+                .context("Azure DevOps returned a comment thread without comments")?;
+            azure_review_comment(comment, thread.id, &review_url).with_context(|| {
+                format!(
+                    "The comment was posted to thread {}, but its id is out of the supported range",
+                    thread.id
+                )
+            })
+        }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let comment = but_gitea::pr::create_comment(
+                preferred_account,
+                owner,
+                repo,
+                review_number,
+                body,
+                storage,
+            )
+            .await?;
+            Ok(comment.into())
+        }
         _ => Err(anyhow::anyhow!(
             "Review comments for forge {forge:?} are not implemented yet."
         )),
     }
 }
 
+/// Azure DevOps comment ids are only unique within their thread, so the thread id
+/// is folded into the upper bits to keep them unique within the review.
+///
+/// Azure DevOps documents thread ids as 32-bit and comment ids as 16-bit integers, so the
+/// combined id is unique as long as they stay within these bounds. Comments beyond them are
+/// logged and skipped with `None`, so one of them doesn't hide all others.
+fn azure_review_comment(
+    comment: but_azure::AzureComment,
+    thread_id: i64,
+    review_url: &str,
+) -> Option<ForgeReviewComment> {
+    let (Ok(thread_bits), Ok(comment_bits)) = (i32::try_from(thread_id), u16::try_from(comment.id))
+    else {
+        tracing::warn!(
+            "Skipping Azure DevOps comment {} in thread {thread_id}, as only 16-bit comment ids and 32-bit thread ids are supported",
+            comment.id
+        );
+        return None;
+    };
+    Some(ForgeReviewComment {
+        id: (i64::from(thread_bits) << 16) | i64::from(comment_bits),
+        body: comment.content,
+        author: comment.author.map(ForgeReviewUser::from),
+        created_at: comment.published_at,
+        modified_at: comment.updated_at,
+        html_url: format!("{review_url}?discussionId={thread_id}"),
+        // Azure DevOps only has "likes", which aren't exposed as reactions.
+        reactions: Vec::new(),
+    })
+}
+
 #[cfg(feature = "export-schema")]
 but_schemars::register_sdk_type!(ReviewMergeStatus);
 
@@ -1785,7 +2558,7 @@ pub async fn get_review_base_repo_url(
                 .context("Failed to fetch PR base repo URL")
         }
         // None tells the UI to fall back to a branch-name-only check.
-        ForgeName::GitLab | ForgeName::Bitbucket | ForgeName::Azure => Ok(None),
+        ForgeName::GitLab | ForgeName::Bitbucket | ForgeName::Azure | ForgeName::Gitea => Ok(None),
     }
 }
 
@@ -1850,6 +2623,43 @@ pub async fn get_review_merge_status(
                 comments_count: pr.comment_count,
             })
         }
+        ForgeName::Azure => {
+            // Azure DevOps reports the result of its speculative merge on the PR itself;
+            // comments live in threads, of which only the human ones are counted.
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let pr =
+                but_azure::pr::get(preferred_account, &repo_id, review_number, storage).await?;
+            let threads =
+                but_azure::pr::list_threads(preferred_account, &repo_id, review_number, storage)
+                    .await?;
+            let has_blocking_merge_status = matches!(
+                pr.merge_status.as_deref(),
+                Some("conflicts" | "failure" | "rejectedByPolicy")
+            );
+            Ok(ReviewMergeStatus {
+                is_mergeable: pr.is_open() && !has_blocking_merge_status,
+                // Like Bitbucket, Azure's merge status vocabulary isn't one the UI understands.
+                mergeable_state: None,
+                comments_count: threads
+                    .iter()
+                    .flat_map(|thread| &thread.comments)
+                    .filter(|comment| !comment.is_system)
+                    .count() as i64,
+            })
+        }
+        ForgeName::Gitea => {
+            // Gitea computes mergeability when the pull request is fetched.
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let pr =
+                but_gitea::pr::get(preferred_account, owner, repo, review_number, storage).await?;
+            Ok(ReviewMergeStatus {
+                is_mergeable: pr.is_open() && pr.mergeable,
+                // Gitea only reports a boolean, not a state the UI understands.
+                mergeable_state: None,
+                comments_count: pr.comments_count,
+            })
+        }
         _ => Err(anyhow::anyhow!(
             "Merge status for forge {forge:?} is not implemented yet."
         )),
@@ -2034,6 +2844,49 @@ pub async fn update_review(
             }
             Ok(())
         }
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let id = review_number
+                .try_into()
+                .context("PR: Failed to cast usize to i64, somehow")?;
+            // Closing a pull request abandons it; abandoned pull requests can be reactivated.
+            let status = state.as_ref().map(|state| match state {
+                ReviewState::Open => but_azure::PullRequestStatus::Active,
+                ReviewState::Closed => but_azure::PullRequestStatus::Abandoned,
+            });
+            let params = but_azure::UpdatePullRequestParams {
+                repo: &repo_id,
+                id,
+                title: title.as_deref(),
+                description: body.as_deref(),
+                target_branch: target_base.as_deref(),
+                status,
+            };
+            but_azure::pr::update(preferred_account, params, storage).await?;
+            Ok(())
+        }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let index = review_number
+                .try_into()
+                .context("PR: Failed to cast usize to i64, somehow")?;
+            let state = state.as_ref().map(|state| match state {
+                ReviewState::Open => but_gitea::PullRequestState::Open,
+                ReviewState::Closed => but_gitea::PullRequestState::Closed,
+            });
+            let params = but_gitea::UpdatePullRequestParams {
+                owner,
+                repo,
+                index,
+                title: title.as_deref(),
+                body: body.as_deref(),
+                base: target_base.as_deref(),
+                state,
+            };
+            but_gitea::pr::update(preferred_account, params, storage).await?;
+            Ok(())
+        }
         _ => Err(anyhow::anyhow!(
             "Updating pull requests for forge {forge:?} is not implemented yet."
         )),
@@ -2128,6 +2981,42 @@ pub async fn merge_review(
             };
             but_bitbucket::pr::merge(preferred_account, params, storage).await
         }
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let id = review_number
+                .try_into()
+                .context("PR: Failed to cast usize to i64, somehow")?;
+            let strategy = match merge_method {
+                Some(ReviewMergeMethod::Squash) => but_azure::MergeStrategy::Squash,
+                Some(ReviewMergeMethod::Rebase) => but_azure::MergeStrategy::Rebase,
+                Some(ReviewMergeMethod::Merge) | None => but_azure::MergeStrategy::NoFastForward,
+            };
+            let params = but_azure::MergePullRequestParams {
+                repo: &repo_id,
+                id,
+                strategy,
+            };
+            but_azure::pr::merge(preferred_account, params, storage).await
+        }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let index = review_number
+                .try_into()
+                .context("PR: Failed to cast usize to i64, somehow")?;
+            let style = match merge_method {
+                Some(ReviewMergeMethod::Squash) => but_gitea::MergeStyle::Squash,
+                Some(ReviewMergeMethod::Rebase) => but_gitea::MergeStyle::Rebase,
+                Some(ReviewMergeMethod::Merge) | None => but_gitea::MergeStyle::Merge,
+            };
+            let params = but_gitea::MergePullRequestParams {
+                owner,
+                repo,
+                index,
+                style,
+            };
+            but_gitea::pr::merge(preferred_account, params, storage).await
+        }
         _ => Err(Error::msg(format!(
             "Merging reviews for forge {forge:?} is not implemented yet.",
         ))),
@@ -2176,6 +3065,9 @@ pub async fn set_review_auto_merge_state(
         ForgeName::Bitbucket => Err(Error::msg(
             "Bitbucket Cloud does not support auto-merge for pull requests.",
         )),
+        ForgeName::Gitea => Err(Error::msg(
+            "Auto-merge for Gitea and Forgejo pull requests is not supported.",
+        )),
         _ => Err(Error::msg(format!(
             "Setting the auto-merge state of reviews for forge {forge:?} is not implemented yet.",
         ))),
@@ -2236,6 +3128,30 @@ pub async fn set_review_draftiness(
             };
             but_bitbucket::pr::set_draft_state(preferred_account, params, storage).await
         }
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            but_azure::pr::set_draft_state(
+                preferred_account,
+                &repo_id,
+                review_number,
+                draft,
+                storage,
+            )
+            .await
+        }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            but_gitea::pr::set_draft_state(
+                preferred_account,
+                owner,
+                repo,
+                review_number,
+                draft,
+                storage,
+            )
+            .await
+        }
         _ => Err(Error::msg(format!(
             "Setting the draftiness of reviews for forge {forge:?} is not implemented yet.",
         ))),
@@ -2352,6 +3268,50 @@ pub async fn create_forge_review(
             let pr = but_bitbucket::pr::create(preferred_account, pr_params, storage).await?;
             Ok(ForgeReview::from(pr))
         }
+        ForgeName::Azure => {
+            if forge_push_repo_info
+                .as_ref()
+                .is_some_and(|push| push != forge_repo_info)
+            {
+                return Err(Error::msg(
+                    "Creating Azure DevOps pull requests from a fork is not supported yet.",
+                ));
+            }
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let pr_params = but_azure::CreatePullRequestParams {
+                repo: &repo_id,
+                title: &params.title,
+                body: &params.body,
+                source_branch: &params.source_branch,
+                target_branch: &params.target_branch,
+                draft: params.draft,
+            };
+            let pr = but_azure::pr::create(preferred_account, pr_params, storage).await?;
+            Ok(ForgeReview::from(pr))
+        }
+        ForgeName::Gitea => {
+            // Like GitHub, a pull request from a fork names its head as `owner:branch`.
+            let head = match forge_push_repo_info
+                .as_ref()
+                .filter(|push| *push != forge_repo_info)
+            {
+                Some(push) => format!("{}:{}", push.owner, params.source_branch),
+                None => params.source_branch.clone(),
+            };
+            let pr_params = but_gitea::CreatePullRequestParams {
+                owner,
+                repo,
+                title: &params.title,
+                body: &params.body,
+                head: &head,
+                base: &params.target_branch,
+                draft: params.draft,
+            };
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let pr = but_gitea::pr::create(preferred_account, pr_params, storage).await?;
+            Ok(ForgeReview::from(pr))
+        }
         _ => Err(Error::msg(format!(
             "Creating reviews for forge {forge:?} is not implemented yet.",
         ))),
@@ -2945,6 +3905,103 @@ pub async fn sync_reviews(
                 }
             }
         }
+        ForgeName::Azure => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.azure());
+            let repo_id = but_azure::AzureRepoId::from_owner_and_repo(owner, repo)?;
+            let pr_ids: Vec<i64> = reviews.iter().map(|r| r.number).collect();
+
+            for review in reviews {
+                let current_body = if !review.update_description {
+                    match but_azure::pr::get(
+                        preferred_account,
+                        &repo_id,
+                        review.number.try_into()?,
+                        storage,
+                    )
+                    .await
+                    {
+                        Ok(review) => Some(review.description),
+                        Err(err) => {
+                            errors.push(format!("PR #{} description: {err}", review.number));
+                            None
+                        }
+                    }
+                } else {
+                    Some(review.body.clone())
+                };
+                let updated_body = current_body.map(|body| {
+                    update_body_with_mode(
+                        body.as_deref(),
+                        review.number,
+                        &pr_ids,
+                        "#",
+                        description_mode,
+                    )
+                });
+
+                let params = but_azure::UpdatePullRequestParams {
+                    repo: &repo_id,
+                    id: review.number,
+                    title: None,
+                    description: updated_body.as_deref(),
+                    target_branch: review.target_branch.as_deref(),
+                    status: None,
+                };
+
+                if let Err(err) = but_azure::pr::update(preferred_account, params, storage).await {
+                    errors.push(format!("PR #{}: {err}", review.number));
+                }
+            }
+        }
+        ForgeName::Gitea => {
+            let preferred_account = preferred_forge_user.as_ref().and_then(|user| user.gitea());
+            let pr_numbers: Vec<i64> = reviews.iter().map(|r| r.number).collect();
+
+            for review in reviews {
+                let current_body = if !review.update_description {
+                    match but_gitea::pr::get(
+                        preferred_account,
+                        owner,
+                        repo,
+                        review.number.try_into()?,
+                        storage,
+                    )
+                    .await
+                    {
+                        Ok(review) => Some(review.body),
+                        Err(err) => {
+                            errors.push(format!("PR #{} description: {err}", review.number));
+                            None
+                        }
+                    }
+                } else {
+                    Some(review.body.clone())
+                };
+                let updated_body = current_body.map(|body| {
+                    update_body_with_mode(
+                        body.as_deref(),
+                        review.number,
+                        &pr_numbers,
+                        "#",
+                        description_mode,
+                    )
+                });
+
+                let params = but_gitea::UpdatePullRequestParams {
+                    owner,
+                    repo,
+                    index: review.number,
+                    title: None,
+                    body: updated_body.as_deref(),
+                    base: review.target_branch.as_deref(),
+                    state: None,
+                };
+
+                if let Err(err) = but_gitea::pr::update(preferred_account, params, storage).await {
+                    errors.push(format!("PR #{}: {err}", review.number));
+                }
+            }
+        }
         _ => {
             return Err(Error::msg(format!(
                 "Updating reviews for forge {forge:?} is not implemented yet.",
@@ -3199,6 +4256,34 @@ mod tests {
         Path::new(path)
     }
 
+    #[test]
+    fn azure_comment_ids_are_unique_within_a_review() {
+        let comment = |id| but_azure::AzureComment {
+            id,
+            parent_comment_id: 0,
+            content: String::new(),
+            author: None,
+            published_at: None,
+            updated_at: None,
+            is_system: false,
+        };
+        let id = |thread_id, comment_id| {
+            azure_review_comment(comment(comment_id), thread_id, "").map(|comment| comment.id)
+        };
+        assert_eq!(id(1, 1), Some(65537));
+        assert_ne!(id(1, 0xffff), id(2, 0xffff));
+        assert_eq!(
+            id(0, 0x10000),
+            None,
+            "ids beyond 16 bits would collide with the next thread"
+        );
+        assert_eq!(
+            id(1 << 32, 1),
+            None,
+            "thread ids beyond 32 bits are rejected"
+        );
+    }
+
     #[test]
     fn github_submission_states_map_and_unknowns_drop() {
         use ForgeReviewSubmissionState as S;
@@ -4085,4 +5170,76 @@ mod tests {
         assert_eq!(review.reviewers.len(), 1);
         assert_eq!(review.reviewers[0].login, "bob");
     }
+
+    #[test]
+    fn forge_review_from_azure_pull_request_maps_fields() {
+        let user = |username: &str| but_azure::AzureUser {
+            id: 0,
+            username: username.into(),
+            name: None,
+            email: None,
+            avatar_url: None,
+            is_bot: false,
+            vote: 0,
+        };
+        let pr = but_azure::AzurePullRequest {
+            html_url: "https://dev.azure.com/org/proj/_git/repo/pullrequest/7".into(),
+            id: 7,
+            title: "Add feature".into(),
+            description: Some("body".into()),
+            status: but_azure::PullRequestStatus::Abandoned,
+            draft: true,
+            source_branch: "feature".into(),
+            target_branch: "main".into(),
+            source_commit_id: "deadbeef".into(),
+            merge_commit_id: None,
+            created_at: Some("2026-06-01T00:00:00Z".into()),
+            closed_at: Some("2026-06-02T00:00:00Z".into()),
+            merge_status: None,
+            labels: vec!["bug".into()],
+            author: Some(user("alice@example.com")),
+            reviewers: vec![user("bob@example.com")],
+            head_repo_is_fork: false,
+        };
+
+        let review = ForgeReview::from(pr);
+
+        assert_eq!(review.number, 7);
+        assert_eq!(review.sha, "deadbeef");
+        assert!(review.integration_commit_shas.is_empty());
+        assert_eq!(
+            review.closed_at.as_deref(),
+            Some("2026-06-02T00:00:00Z"),
+            "abandoned pull requests are closed, not merged"
+        );
+        assert_eq!(review.merged_at, None);
+        assert_eq!(review.labels[0].name, "bug");
+        assert!(review.draft);
+        assert_eq!(review.unit_symbol, "#");
+        assert_eq!(
+            review.author.as_ref().map(|a| a.login.as_str()),
+            Some("alice@example.com")
+        );
+        assert_eq!(review.reviewers[0].login, "bob@example.com");
+    }
+
+    #[test]
+    fn commented_lines_come_from_the_end_of_the_diff_hunk() {
+        let hunk = "@@ -1,4 +1,4 @@\n fn main() {\n-    old();\n+    new();\n+    more();";
+        assert_eq!(
+            commented_lines(hunk, ForgeDiffSide::New),
+            (Some("    more();".into()), Some("    new();".into()))
+        );
+
+        let hunk = "@@ -1,3 +1,3 @@\n fn main() {\n-    old();";
+        assert_eq!(
+            commented_lines(hunk, ForgeDiffSide::Old),
+            (Some("    old();".into()), Some("fn main() {".into()))
+        );
+        assert_eq!(
+            commented_lines("", ForgeDiffSide::New),
+            (None, None),
+            "comments without a hunk have no line content"
+        );
+    }
 }
@@ -0,0 +1,338 @@
+//! The relation chain of a stack, i.e. which Gerrit change each of its commits became and how the
+//! local commits relate to the patchsets on the server.
+//!
+//! Commits are linked to changes through the metadata recorded from the
+//! [`PushOutput`](crate::parse::PushOutput) of previous pushes, and to the server through their
+//! [`GerritChangeId`].
+use std::collections::HashMap;
+
+use anyhow::{Context as _, Result, bail};
+use but_core::ChangeId;
+use but_db::DbHandle;
+use serde::Serialize;
+
+use crate::{
+    GerritChangeId, GerritChangeState, GerritChangeStatus,
+    rest::{self, AccountInfo},
+    status::{client_for_repo, refresh_changes},
+};
+
+/// A commit of a stack that should be mapped to its Gerrit change.
+#[derive(Clone, Debug)]
+pub struct ChainCommit {
+    /// The id of the commit in the local stack.
+    pub commit_id: gix::ObjectId,
+    /// The GitButler change-id of the commit, from which the Gerrit `Change-Id` is derived.
+    pub change_id: ChangeId,
+    /// The first line of the commit message.
+    pub title: String,
+}
+
+/// How a local commit relates to the change it was pushed as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum ChainLinkState {
+    /// The commit was never pushed to Gerrit.
+    NotPushed,
+    /// The commit was pushed, but the server doesn't know the change or it isn't visible to us.
+    Missing,
+    /// The local commit is the current patchset of the change.
+    UpToDate,
+    /// The local commit was changed since it was pushed, and the server still has what we pushed last.
+    NeedsPush,
+    /// The current patchset on the server wasn't pushed from here, so somebody else uploaded it.
+    UploadedByOthers,
+    /// The change was submitted.
+    Merged,
+    /// The change was abandoned.
+    Abandoned,
+}
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(ChainLinkState);
+
+impl ChainLinkState {
+    /// Classify `local_commit_id` by comparing it to the commit we pushed last, `pushed_commit_id`,
+    /// and the `state` and `current_commit_id` of the change on the server.
+    pub fn classify(
+        local_commit_id: gix::ObjectId,
+        pushed_commit_id: Option<gix::ObjectId>,
+        server: Option<(GerritChangeState, Option<gix::ObjectId>)>,
+    ) -> Self {
+        let Some(pushed_commit_id) = pushed_commit_id else {
+            return ChainLinkState::NotPushed;
+        };
+        let Some((state, current_commit_id)) = server else {
+            return ChainLinkState::Missing;
+        };
+        match state {
+            GerritChangeState::Merged => return ChainLinkState::Merged,
+            GerritChangeState::Abandoned => return ChainLinkState::Abandoned,
+            GerritChangeState::New => {}
+        }
+        match current_commit_id {
+            Some(current) if current == local_commit_id => ChainLinkState::UpToDate,
+            Some(current) if current != pushed_commit_id => ChainLinkState::UploadedByOthers,
+            _ => ChainLinkState::NeedsPush,
+        }
+    }
+}
+
+/// The current patchset of a change on the server.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct Patchset {
+    /// The patchset number, starting at 1.
+    pub number: u32,
+    /// The commit of the patchset.
+    #[cfg_attr(
+        feature = "export-schema",
+        schemars(schema_with = "but_schemars::object_id")
+    )]
+    #[serde(with = "but_serde::object_id")]
+    pub commit_id: gix::ObjectId,
+    /// The ref to fetch the patchset from, like `refs/changes/11/4711/3`.
+    pub ref_name: String,
+    /// The name of whoever uploaded the patchset, if known.
+    pub uploader: Option<String>,
+}
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(Patchset);
+
+impl Patchset {
+    fn from_change_info(info: &rest::ChangeInfo) -> Result<Option<Self>> {
+        let Some((commit_id, revision)) = info.current_patchset() else {
+            return Ok(None);
+        };
+        Ok(Some(Patchset {
+            number: revision.number,
+            commit_id: gix::ObjectId::from_hex(commit_id.as_bytes())
+                .with_context(|| format!("Gerrit returned an invalid revision '{commit_id}'"))?,
+            ref_name: revision.ref_name.clone(),
+            uploader: revision
+                .uploader
+                .as_ref()
+                .and_then(AccountInfo::display_name)
+                .map(ToOwned::to_owned),
+        }))
+    }
+}
+
+/// A commit of a stack along with the Gerrit change it belongs to.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ChainLink {
+    /// The id of the commit in the local stack.
+    #[cfg_attr(
+        feature = "export-schema",
+        schemars(schema_with = "but_schemars::object_id")
+    )]
+    #[serde(with = "but_serde::object_id")]
+    pub commit_id: gix::ObjectId,
+    /// The GitButler change-id of the commit.
+    pub change_id: String,
+    /// The `Change-Id` the commit has on the Gerrit server.
+    pub gerrit_change_id: String,
+    /// The first line of the commit message.
+    pub title: String,
+    /// How the local commit relates to the change on the server.
+    pub state: ChainLinkState,
+    /// The URL to review the change at, if it was pushed.
+    pub review_url: Option<String>,
+    /// The review status of the change, if the server knows it.
+    pub status: Option<GerritChangeStatus>,
+    /// The current patchset of the change, if the server knows it.
+    pub patchset: Option<Patchset>,
+}
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(ChainLink);
+
+/// Map each of `commits`, ordered from the bottom of the stack to its top, to the Gerrit change it
+/// was pushed as, and compare it with the current patchset on the server.
+///
+/// This queries the server for each change that was pushed before, and updates the status cache
+/// in `db` just like [`refresh_change_statuses()`](crate::refresh_change_statuses) does.
+pub fn relation_chain(
+    repo: &gix::Repository,
+    db: &mut DbHandle,
+    commits: Vec<ChainCommit>,
+) -> Result<Vec<ChainLink>> {
+    let mut changes: HashMap<_, _> = refresh_changes(
+        repo,
+        db,
+        commits.iter().map(|commit| commit.change_id.clone()),
+    )?
+    .into_iter()
+    .map(|(change_id, info, status)| (change_id, (info, status)))
+    .collect();
+    let metadata = db.gerrit_metadata();
+    commits
+        .into_iter()
+        .map(|commit| {
+            let meta = metadata.get(&commit.change_id.to_string())?;
+            let pushed_commit_id = meta
+                .as_ref()
+                .map(|meta| gix::ObjectId::from_hex(meta.commit_id.as_bytes()))
+                .transpose()?;
+            let (patchset, status) = match changes.remove(&commit.change_id) {
+                Some((info, status)) => (Patchset::from_change_info(&info)?, Some(status)),
+                None => (None, None),
+            };
+            let state = ChainLinkState::classify(
+                commit.commit_id,
+                pushed_commit_id,
+                status.as_ref().map(|status| {
+                    (
+                        status.state,
+                        patchset.as_ref().map(|patchset| patchset.commit_id),
+                    )
+                }),
+            );
+            Ok(ChainLink {
+                commit_id: commit.commit_id,
+                change_id: commit.change_id.to_string(),
+                gerrit_change_id: GerritChangeId::from(&commit.change_id).to_string(),
+                title: commit.title,
+                state,
+                review_url: meta.map(|meta| meta.review_url),
+                status,
+                patchset,
+            })
+        })
+        .collect()
+}
+
+/// Remember that `commit_id` is the patchset of `change_id` that is now known to the server, after
+/// it was pulled into the local stack, so it isn't considered to be uploaded by others anymore.
+pub fn record_pulled_patchset(
+    db: &mut DbHandle,
+    change_id: &str,
+    commit_id: gix::ObjectId,
+) -> Result<()> {
+    let mut trans = db.transaction()?;
+    let Some(existing) = trans.gerrit_metadata().get(change_id)? else {
+        bail!("Change {change_id} was never pushed to Gerrit");
+    };
+    trans.gerrit_metadata_mut().update(but_db::GerritMeta {
+        commit_id: commit_id.to_string(),
+        updated_at: chrono::Utc::now().naive_utc(),
+        ..existing
+    })?;
+    trans.commit()?;
+    Ok(())
+}
+
+/// Submit the relation `chain`, ordered from bottom to top, by submitting its top-most open change,
+/// which makes Gerrit submit all of its ancestors as well.
+///
+/// Every open change must be [up-to-date](ChainLinkState::UpToDate) and submittable, so that exactly
+/// what is in the local stack is submitted. Return the state of the top-most change after the submission.
+pub fn submit_chain(repo: &gix::Repository, chain: &[ChainLink]) -> Result<GerritChangeState> {
+    let open: Vec<_> = chain
+        .iter()
+        .filter(|link| link.state != ChainLinkState::Merged)
+        .collect();
+    let Some(top) = open.last() else {
+        bail!("There is nothing to submit as all changes are merged already");
+    };
+    for link in &open {
+        match link.state {
+            ChainLinkState::UpToDate => {}
+            ChainLinkState::NotPushed | ChainLinkState::NeedsPush => {
+                bail!("'{}' must be pushed before it can be submitted", link.title)
+            }
+            ChainLinkState::UploadedByOthers => bail!(
+                "'{}' has a newer patchset on the server that must be pulled first",
+                link.title
+            ),
+            ChainLinkState::Missing => {
+                bail!("'{}' could not be found on the Gerrit server", link.title)
+            }
+            ChainLinkState::Abandoned => {
+                bail!("'{}' was abandoned and can't be submitted", link.title)
+            }
+            ChainLinkState::Merged => unreachable!("filtered above"),
+        }
+        if !link
+            .status
+            .as_ref()
+            .is_some_and(|status| status.submittable)
+        {
+            bail!("'{}' is not submittable yet", link.title);
+        }
+    }
+    let number = top
+        .status
+        .as_ref()
+        .map(|status| status.number)
+        .expect("up-to-date changes have a status");
+    let client = client_for_repo(repo)?;
+    let submitted = std::thread::spawn(move || -> Result<_> {
+        let runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(client.submit(number))
+    })
+    .join()
+    .map_err(|_| anyhow::anyhow!("Thread panicked while submitting to Gerrit"))??;
+    GerritChangeState::from_gerrit(&submitted.status)
+        .ok_or_else(|| anyhow::anyhow!("Unknown Gerrit change status '{}'", submitted.status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(hex: char) -> gix::ObjectId {
+        gix::ObjectId::from_hex(hex.to_string().repeat(40).as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn classify() {
+        let (local, pushed, foreign) = (id('1'), id('2'), id('3'));
+        let open = |current| Some((GerritChangeState::New, Some(current)));
+
+        assert_eq!(
+            ChainLinkState::classify(local, None, open(local)),
+            ChainLinkState::NotPushed,
+            "without metadata the commit wasn't pushed from here"
+        );
+        assert_eq!(
+            ChainLinkState::classify(local, Some(pushed), None),
+            ChainLinkState::Missing
+        );
+        assert_eq!(
+            ChainLinkState::classify(local, Some(local), open(local)),
+            ChainLinkState::UpToDate
+        );
+        assert_eq!(
+            ChainLinkState::classify(local, Some(pushed), open(local)),
+            ChainLinkState::UpToDate,
+            "it doesn't matter how the server got our commit"
+        );
+        assert_eq!(
+            ChainLinkState::classify(local, Some(pushed), open(pushed)),
+            ChainLinkState::NeedsPush
+        );
+        assert_eq!(
+            ChainLinkState::classify(local, Some(pushed), open(foreign)),
+            ChainLinkState::UploadedByOthers
+        );
+        assert_eq!(
+            ChainLinkState::classify(
+                local,
+                Some(pushed),
+                Some((GerritChangeState::Merged, Some(foreign)))
+            ),
+            ChainLinkState::Merged
+        );
+        assert_eq!(
+            ChainLinkState::classify(
+                local,
+                Some(pushed),
+                Some((GerritChangeState::Abandoned, None))
+            ),
+            ChainLinkState::Abandoned
+        );
+    }
+}
@@ -9,7 +9,18 @@ use serde::{Deserialize, Serialize};
 
 use crate::parse::PushOutput;
 
+mod chain;
+pub use chain::{
+    ChainCommit, ChainLink, ChainLinkState, Patchset, record_pulled_patchset, relation_chain,
+    submit_chain,
+};
 pub mod parse;
+pub mod rest;
+mod status;
+pub use status::{
+    GerritChangeState, GerritChangeStatus, change_statuses_from_cache, refresh_change_statuses,
+};
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase", tag = "type", content = "subject")]
@@ -295,13 +306,15 @@ fn mappings(
     Ok(mappings)
 }
 
+/// The name of the remote that changes are pushed to, which is also where patchsets are fetched from.
+pub fn push_remote_name(repo: &gix::Repository) -> BString {
+    repo.remote_default_name(gix::remote::Direction::Push)
+        .map(|name| name.as_ref().to_owned())
+        .unwrap_or_else(|| "origin".into())
+}
+
 fn gerrit_host(repo: &gix::Repository) -> Option<String> {
-    let name = repo.remote_default_name(gix::remote::Direction::Push);
-    let name = name
-        .as_ref()
-        .map(|n| n.as_ref())
-        .unwrap_or(b"origin".as_bstr());
-    let remote = repo.find_remote(name).ok()?;
+    let remote = repo.find_remote(push_remote_name(repo).as_bstr()).ok()?;
     let url = remote
         .url(gix::remote::Direction::Push)
         .or_else(|| remote.url(gix::remote::Direction::Fetch))?;
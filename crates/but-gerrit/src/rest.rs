@@ -0,0 +1,237 @@
+//! A minimal client for the [Gerrit REST API](https://gerrit-review.googlesource.com/Documentation/rest-api.html),
+//! just enough to learn about the review state of changes we pushed, and to submit them.
+use std::{collections::BTreeMap, time::Duration};
+
+use anyhow::{Context as _, Result};
+use reqwest::header::{ACCEPT, HeaderMap, HeaderValue, USER_AGENT};
+use serde::Deserialize;
+
+use crate::GerritChangeId;
+
+const GERRIT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Gerrit prefixes all JSON responses with this line to prevent XSSI, and it must be stripped before parsing.
+const XSSI_PREFIX: &str = ")]}'";
+
+/// An HTTP error with a status code, returned when the API responds with a non-success status.
+///
+/// This can be downcasted from `anyhow::Error` to distinguish auth failures (401/403) from other errors.
+#[derive(Debug, thiserror::Error)]
+#[error("HTTP {status}")]
+pub struct HttpStatusError {
+    pub status: reqwest::StatusCode,
+}
+
+/// The username and HTTP password to authenticate with, as configured in the Gerrit user settings.
+#[derive(Clone)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+pub struct GerritClient {
+    client: reqwest::Client,
+    credentials: Option<Credentials>,
+    /// The REST root of the server, e.g. `https://review.example.com/a`.
+    base_url: String,
+}
+
+impl GerritClient {
+    /// Build a client for the server at `host`, which may be a bare hostname or an origin like `https://host`.
+    ///
+    /// With `credentials`, requests go to the authenticated `/a/` endpoints, otherwise they are anonymous
+    /// and will only see public changes.
+    pub fn new(host: &str, credentials: Option<Credentials>) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_static("gb-gerrit-integration"),
+        );
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(GERRIT_REQUEST_TIMEOUT)
+            .build()?;
+
+        let host = host.trim_end_matches('/');
+        let origin = if host.starts_with("http://") || host.starts_with("https://") {
+            host.to_owned()
+        } else {
+            format!("https://{host}")
+        };
+        let base_url = if credentials.is_some() {
+            format!("{origin}/a")
+        } else {
+            origin
+        };
+        Ok(GerritClient {
+            client,
+            credentials,
+            base_url,
+        })
+    }
+
+    /// Query the change with `change_id`, returning `None` if the server doesn't know it or it isn't visible to us.
+    ///
+    /// If the same Change-Id was pushed to multiple projects or branches, the most recently updated one is returned.
+    pub async fn change(&self, change_id: &GerritChangeId) -> Result<Option<ChangeInfo>> {
+        let url = format!(
+            "{}/changes/?q=change:{change_id}&o=DETAILED_LABELS&o=DETAILED_ACCOUNTS&o=SUBMITTABLE&o=CURRENT_REVISION&n=1",
+            self.base_url
+        );
+        let mut request = self.client.get(&url);
+        if let Some(credentials) = &self.credentials {
+            request = request.basic_auth(&credentials.username, Some(&credentials.password));
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(HttpStatusError { status }.into());
+        }
+        let body = response.text().await?;
+        Ok(parse_changes(&body)?.into_iter().next())
+    }
+
+    /// Submit the change with the numeric ID `number`, which also submits all of its open ancestors
+    /// in the relation chain, and return the change as it is after the submission.
+    ///
+    /// This is only possible with credentials.
+    pub async fn submit(&self, number: i64) -> Result<ChangeInfo> {
+        let Some(credentials) = &self.credentials else {
+            anyhow::bail!(
+                "Submitting Gerrit changes requires credentials, but no git credential helper provided them"
+            );
+        };
+        let url = format!("{}/changes/{number}/submit", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .basic_auth(&credentials.username, Some(&credentials.password))
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(HttpStatusError { status }.into());
+        }
+        let body = response.text().await?;
+        let json = strip_xssi_prefix(&body);
+        serde_json::from_str(json).context("Could not parse Gerrit submit response")
+    }
+}
+
+/// A change as returned by the `/changes/` endpoint, reduced to what we need.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChangeInfo {
+    /// The numeric ID of the change, which is also used in review URLs.
+    #[serde(rename = "_number")]
+    pub number: i64,
+    /// One of `NEW`, `MERGED` or `ABANDONED`.
+    pub status: String,
+    /// Only present if `true`.
+    #[serde(default)]
+    pub work_in_progress: bool,
+    /// Only present if the `SUBMITTABLE` option was requested.
+    #[serde(default)]
+    pub submittable: bool,
+    #[serde(default)]
+    pub unresolved_comment_count: u32,
+    /// The commit id of the current patchset, if the `CURRENT_REVISION` option was requested.
+    pub current_revision: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, LabelInfo>,
+    /// Patchsets by commit id, which only contains the current one if the `CURRENT_REVISION` option was requested.
+    #[serde(default)]
+    pub revisions: BTreeMap<String, RevisionInfo>,
+}
+
+/// A single patchset of a change.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RevisionInfo {
+    /// The patchset number, starting at 1.
+    #[serde(rename = "_number")]
+    pub number: u32,
+    /// The ref under which the patchset can be fetched, like `refs/changes/11/4711/3`.
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// Who uploaded the patchset, with details only if the `DETAILED_ACCOUNTS` option was requested.
+    pub uploader: Option<AccountInfo>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct AccountInfo {
+    pub name: Option<String>,
+    pub username: Option<String>,
+    pub email: Option<String>,
+}
+
+impl AccountInfo {
+    /// The most human-readable identification of the account that is available.
+    pub fn display_name(&self) -> Option<&str> {
+        self.name
+            .as_deref()
+            .or(self.username.as_deref())
+            .or(self.email.as_deref())
+    }
+}
+
+/// The votes on a single label, like `Code-Review`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct LabelInfo {
+    /// All votes, only present if the `DETAILED_LABELS` option was requested.
+    #[serde(default)]
+    pub all: Vec<ApprovalInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApprovalInfo {
+    /// The vote, which is absent for reviewers that didn't vote yet.
+    pub value: Option<i32>,
+}
+
+impl ChangeInfo {
+    /// Summarize the votes on `label` into a single value, or `None` if nobody voted.
+    ///
+    /// Like Gerrit itself does, a negative vote wins over any positive vote as it blocks submission.
+    pub fn label_vote(&self, label: &str) -> Option<i32> {
+        let votes = self
+            .labels
+            .get(label)?
+            .all
+            .iter()
+            .filter_map(|approval| approval.value)
+            .filter(|value| *value != 0);
+        votes.fold(None, |summary, vote| match summary {
+            None => Some(vote),
+            Some(current) if current < 0 || vote < 0 => Some(current.min(vote)),
+            Some(current) => Some(current.max(vote)),
+        })
+    }
+
+    /// Return the commit id and the details of the current patchset, if the `CURRENT_REVISION` option was requested.
+    pub fn current_patchset(&self) -> Option<(&str, &RevisionInfo)> {
+        let commit_id = self.current_revision.as_deref()?;
+        self.revisions
+            .get(commit_id)
+            .map(|revision| (commit_id, revision))
+    }
+}
+
+/// Parse the body of a `/changes/` query response, which is a list of changes behind the XSSI prefix.
+pub fn parse_changes(body: &str) -> Result<Vec<ChangeInfo>> {
+    serde_json::from_str(strip_xssi_prefix(body))
+        .context("Could not parse Gerrit change query response")
+}
+
+fn strip_xssi_prefix(body: &str) -> &str {
+    body.trim_start().strip_prefix(XSSI_PREFIX).unwrap_or(body)
+}
+
+#[cfg(test)]
+mod mock_tests;
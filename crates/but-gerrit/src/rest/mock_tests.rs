@@ -0,0 +1,207 @@
+use super::*;
+use but_testsupport::{MockHttpResponse, mock_http_server};
+
+const CHANGE_ID: &str = "I10c56efd90c998f406d4e0b99d9c58feeaf896c5";
+
+fn query_path(prefix: &str) -> String {
+    format!(
+        "{prefix}/changes/?q=change:{CHANGE_ID}&o=DETAILED_LABELS&o=DETAILED_ACCOUNTS&o=SUBMITTABLE&o=CURRENT_REVISION&n=1"
+    )
+}
+
+const CHANGE_RESPONSE: &str = r#")]}'
+[
+  {
+    "id": "project~main~I10c56efd90c998f406d4e0b99d9c58feeaf896c5",
+    "project": "project",
+    "branch": "main",
+    "change_id": "I10c56efd90c998f406d4e0b99d9c58feeaf896c5",
+    "subject": "Add login",
+    "status": "NEW",
+    "_number": 4711,
+    "submittable": true,
+    "unresolved_comment_count": 2,
+    "current_revision": "7923faec4760ee74d7ad794892766d1b9b00ca96",
+    "labels": {
+      "Code-Review": {
+        "all": [
+          {"value": 2, "_account_id": 1},
+          {"value": 0, "_account_id": 2},
+          {"_account_id": 3}
+        ]
+      },
+      "Verified": {
+        "all": [{"value": 1, "_account_id": 4}]
+      }
+    },
+    "revisions": {
+      "7923faec4760ee74d7ad794892766d1b9b00ca96": {
+        "_number": 3,
+        "ref": "refs/changes/11/4711/3",
+        "uploader": {"_account_id": 5, "name": "Bob Reviewer", "username": "bob"}
+      }
+    }
+  }
+]
+"#;
+
+#[tokio::test(flavor = "current_thread")]
+async fn anonymous_change_query() {
+    let (origin, requests, server) = mock_http_server(vec![MockHttpResponse::ok(
+        "GET",
+        query_path(""),
+        CHANGE_RESPONSE,
+    )]);
+    let client = GerritClient::new(&origin, None).unwrap();
+
+    let change = client
+        .change(&GerritChangeId(CHANGE_ID.into()))
+        .await
+        .unwrap()
+        .expect("the change is known");
+    server.join().unwrap();
+
+    assert_eq!(change.number, 4711);
+    assert_eq!(change.status, "NEW");
+    assert!(change.submittable);
+    assert!(!change.work_in_progress, "absent means `false`");
+    assert_eq!(change.unresolved_comment_count, 2);
+    assert_eq!(change.label_vote("Code-Review"), Some(2));
+    assert_eq!(change.label_vote("Verified"), Some(1));
+    assert_eq!(change.label_vote("Unknown"), None);
+    let (commit_id, patchset) = change
+        .current_patchset()
+        .expect("current revision is present");
+    assert_eq!(commit_id, "7923faec4760ee74d7ad794892766d1b9b00ca96");
+    assert_eq!(patchset.number, 3);
+    assert_eq!(patchset.ref_name, "refs/changes/11/4711/3");
+    assert_eq!(
+        patchset
+            .uploader
+            .as_ref()
+            .and_then(AccountInfo::display_name),
+        Some("Bob Reviewer")
+    );
+    assert!(
+        !requests
+            .recv()
+            .unwrap()
+            .headers
+            .to_lowercase()
+            .contains("authorization:"),
+        "anonymous requests carry no credentials"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn authenticated_change_query_uses_a_prefix() {
+    let (origin, requests, server) = mock_http_server(vec![MockHttpResponse::ok(
+        "GET",
+        query_path("/a"),
+        ")]}'\n[]",
+    )]);
+    let client = GerritClient::new(
+        &origin,
+        Some(Credentials {
+            username: "alice".into(),
+            password: "secret".into(),
+        }),
+    )
+    .unwrap();
+
+    let change = client.change(&GerritChangeId(CHANGE_ID.into())).await;
+    server.join().unwrap();
+
+    assert_eq!(change.unwrap(), None, "unknown changes are not an error");
+    assert!(
+        requests
+            .recv()
+            .unwrap()
+            .headers
+            .to_lowercase()
+            .contains("authorization: basic"),
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn http_errors_are_downcastable() {
+    let (origin, _requests, server) = mock_http_server(vec![
+        MockHttpResponse::ok("GET", query_path(""), "Unauthorized")
+            .with_status(reqwest::StatusCode::UNAUTHORIZED.as_u16()),
+    ]);
+    let client = GerritClient::new(&origin, None).unwrap();
+
+    let err = client
+        .change(&GerritChangeId(CHANGE_ID.into()))
+        .await
+        .unwrap_err();
+    server.join().unwrap();
+
+    assert_eq!(
+        err.downcast_ref::<HttpStatusError>().map(|err| err.status),
+        Some(reqwest::StatusCode::UNAUTHORIZED)
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn submit_posts_to_authenticated_endpoint() {
+    let (origin, requests, server) = mock_http_server(vec![MockHttpResponse::ok(
+        "POST",
+        "/a/changes/4711/submit",
+        r#")]}'
+{"_number": 4711, "status": "MERGED"}"#,
+    )]);
+    let client = GerritClient::new(
+        &origin,
+        Some(Credentials {
+            username: "alice".into(),
+            password: "secret".into(),
+        }),
+    )
+    .unwrap();
+
+    let change = client.submit(4711).await.unwrap();
+    server.join().unwrap();
+
+    assert_eq!(change.status, "MERGED");
+    assert!(
+        requests
+            .recv()
+            .unwrap()
+            .headers
+            .to_lowercase()
+            .contains("authorization: basic"),
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn submit_requires_credentials() {
+    let client = GerritClient::new("http://127.0.0.1:9", None).unwrap();
+    let err = client.submit(4711).await.unwrap_err();
+    assert!(
+        err.to_string().contains("requires credentials"),
+        "no request is made without credentials: {err}"
+    );
+}
+
+#[test]
+fn negative_votes_win() {
+    let change = parse_changes(
+        r#")]}'
+[{"_number": 1, "status": "NEW", "labels": {"Code-Review": {"all": [{"value": 2}, {"value": -1}, {"value": 1}]}}}]"#,
+    )
+    .unwrap()
+    .remove(0);
+    assert_eq!(change.label_vote("Code-Review"), Some(-1));
+
+    let change = parse_changes(
+        r#"[{"_number": 1, "status": "MERGED", "labels": {"Code-Review": {"all": [{"value": -1}, {"value": -2}]}}}]"#,
+    )
+    .unwrap()
+    .remove(0);
+    assert_eq!(
+        change.label_vote("Code-Review"),
+        Some(-2),
+        "the strongest veto is shown, and the XSSI prefix is optional"
+    );
+}
@@ -0,0 +1,241 @@
+//! The review state of pushed changes, as queried from the Gerrit server and cached in the database.
+use anyhow::Result;
+use but_core::ChangeId;
+use but_db::DbHandle;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GerritChangeId,
+    rest::{self, GerritClient},
+};
+
+/// The state of a change on the Gerrit server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum GerritChangeState {
+    /// The change is open for review.
+    New,
+    /// The change was submitted.
+    Merged,
+    /// The change was abandoned.
+    Abandoned,
+}
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(GerritChangeState);
+
+impl GerritChangeState {
+    pub(crate) fn from_gerrit(status: &str) -> Option<Self> {
+        Some(match status {
+            "NEW" => GerritChangeState::New,
+            "MERGED" => GerritChangeState::Merged,
+            "ABANDONED" => GerritChangeState::Abandoned,
+            _ => return None,
+        })
+    }
+
+    fn as_gerrit(&self) -> &'static str {
+        match self {
+            GerritChangeState::New => "NEW",
+            GerritChangeState::Merged => "MERGED",
+            GerritChangeState::Abandoned => "ABANDONED",
+        }
+    }
+}
+
+/// The review status of a single pushed change, similar to what a forge review provides for pull requests.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct GerritChangeStatus {
+    /// The GitButler change-id of the commit that was pushed as this change.
+    pub change_id: String,
+    /// The numeric ID of the change on the Gerrit server.
+    pub number: i64,
+    /// Whether the change is open, merged or abandoned.
+    pub state: GerritChangeState,
+    /// The summarized `Code-Review` vote, where negative votes take precedence.
+    pub code_review: Option<i32>,
+    /// The summarized `Verified` vote, where negative votes take precedence.
+    pub verified: Option<i32>,
+    /// The amount of unresolved comment threads.
+    pub unresolved_comment_count: u32,
+    /// Whether the change could be submitted right now.
+    pub submittable: bool,
+    /// Whether the change is marked as work in progress.
+    pub work_in_progress: bool,
+    /// The commit id of the current patchset on the server.
+    pub current_revision: Option<String>,
+    /// The time at which this information was obtained from the server.
+    pub last_sync_at: chrono::NaiveDateTime,
+}
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(GerritChangeStatus);
+
+impl GerritChangeStatus {
+    fn from_change_info(change_id: &ChangeId, info: &rest::ChangeInfo) -> Option<Self> {
+        Some(GerritChangeStatus {
+            change_id: change_id.to_string(),
+            number: info.number,
+            state: GerritChangeState::from_gerrit(&info.status)?,
+            code_review: info.label_vote("Code-Review"),
+            verified: info.label_vote("Verified"),
+            unresolved_comment_count: info.unresolved_comment_count,
+            submittable: info.submittable,
+            work_in_progress: info.work_in_progress,
+            current_revision: info.current_revision.clone(),
+            last_sync_at: chrono::Local::now().naive_local(),
+        })
+    }
+}
+
+impl TryFrom<but_db::GerritChange> for GerritChangeStatus {
+    type Error = anyhow::Error;
+
+    fn try_from(change: but_db::GerritChange) -> Result<Self> {
+        Ok(GerritChangeStatus {
+            state: GerritChangeState::from_gerrit(&change.status).ok_or_else(|| {
+                anyhow::anyhow!("Unknown Gerrit change status '{}'", change.status)
+            })?,
+            change_id: change.change_id,
+            number: change.number,
+            code_review: change.code_review,
+            verified: change.verified,
+            unresolved_comment_count: change.unresolved_comment_count.try_into()?,
+            submittable: change.submittable,
+            work_in_progress: change.work_in_progress,
+            current_revision: change.current_revision,
+            last_sync_at: change.last_sync_at,
+        })
+    }
+}
+
+impl From<GerritChangeStatus> for but_db::GerritChange {
+    fn from(status: GerritChangeStatus) -> Self {
+        but_db::GerritChange {
+            change_id: status.change_id,
+            number: status.number,
+            status: status.state.as_gerrit().to_owned(),
+            code_review: status.code_review,
+            verified: status.verified,
+            unresolved_comment_count: status.unresolved_comment_count.into(),
+            submittable: status.submittable,
+            work_in_progress: status.work_in_progress,
+            current_revision: status.current_revision,
+            last_sync_at: status.last_sync_at,
+        }
+    }
+}
+
+/// Return the cached status of each of `change_ids` that has one, without talking to the server.
+pub fn change_statuses_from_cache(
+    db: &DbHandle,
+    change_ids: impl IntoIterator<Item = ChangeId>,
+) -> Result<Vec<GerritChangeStatus>> {
+    let handle = db.gerrit_changes();
+    let mut out = Vec::new();
+    for change_id in change_ids {
+        if let Some(change) = handle.get(&change_id.to_string())? {
+            out.push(change.try_into()?);
+        }
+    }
+    Ok(out)
+}
+
+/// Query the Gerrit server behind the push remote of `repo` for the status of each of `change_ids`,
+/// update the cache in `db` accordingly and return all statuses that are known to the server.
+///
+/// Only changes that were pushed before, i.e. that have Gerrit metadata, are queried.
+/// Credentials are obtained from the git credential helpers configured for the server, and
+/// the query is anonymous if there are none.
+pub fn refresh_change_statuses(
+    repo: &gix::Repository,
+    db: &mut DbHandle,
+    change_ids: impl IntoIterator<Item = ChangeId>,
+) -> Result<Vec<GerritChangeStatus>> {
+    Ok(refresh_changes(repo, db, change_ids)?
+        .into_iter()
+        .map(|(_, _, status)| status)
+        .collect())
+}
+
+/// Like [`refresh_change_statuses()`], but also return the change-id and the full server response
+/// along with each status.
+pub(crate) fn refresh_changes(
+    repo: &gix::Repository,
+    db: &mut DbHandle,
+    change_ids: impl IntoIterator<Item = ChangeId>,
+) -> Result<Vec<(ChangeId, rest::ChangeInfo, GerritChangeStatus)>> {
+    let pushed: Vec<_> = {
+        let metadata = db.gerrit_metadata();
+        change_ids
+            .into_iter()
+            .filter_map(|change_id| {
+                metadata
+                    .get(&change_id.to_string())
+                    .map(|meta| meta.map(|_| change_id))
+                    .transpose()
+            })
+            .collect::<Result<_, _>>()?
+    };
+    if pushed.is_empty() {
+        return Ok(Vec::new());
+    }
+    let client = client_for_repo(repo)?;
+
+    let fetched = std::thread::spawn(move || -> Result<Vec<_>> {
+        let runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(async {
+            let mut out = Vec::with_capacity(pushed.len());
+            for change_id in pushed {
+                let info = client.change(&GerritChangeId::from(&change_id)).await?;
+                out.push((change_id, info));
+            }
+            Ok(out)
+        })
+    })
+    .join()
+    .map_err(|_| anyhow::anyhow!("Thread panicked while querying Gerrit"))??;
+
+    let mut changes = Vec::new();
+    let mut trans = db.transaction()?;
+    for (change_id, info) in fetched {
+        let status = info
+            .as_ref()
+            .and_then(|info| GerritChangeStatus::from_change_info(&change_id, info));
+        match info.zip(status) {
+            Some((info, status)) => {
+                trans.gerrit_changes_mut().upsert(status.clone().into())?;
+                changes.push((change_id, info, status));
+            }
+            None => trans.gerrit_changes_mut().delete(&change_id.to_string())?,
+        }
+    }
+    trans.commit()?;
+    Ok(changes)
+}
+
+/// Create a client for the Gerrit server behind the push remote of `repo`, authenticated if
+/// credentials are available.
+pub(crate) fn client_for_repo(repo: &gix::Repository) -> Result<GerritClient> {
+    let host = crate::gerrit_host(repo).ok_or_else(|| {
+        anyhow::anyhow!("Could not determine the Gerrit host from the push remote")
+    })?;
+    let credentials = credentials_for_host(repo, &host);
+    GerritClient::new(&host, credentials)
+}
+
+/// Ask the git credential helpers for the HTTP credentials of `host`, without ever prompting.
+fn credentials_for_host(repo: &gix::Repository, host: &str) -> Option<rest::Credentials> {
+    let url = gix::url::parse(format!("https://{host}").as_str().into()).ok()?;
+    let (mut cascade, action, mut prompt) = repo.config_snapshot().credential_helpers(url).ok()?;
+    prompt.mode = gix::prompt::Mode::Disable;
+    let outcome = cascade
+        .invoke(action, prompt)
+        .inspect_err(|err| tracing::debug!("Gerrit credentials for '{host}' unavailable: {err}"))
+        .ok()??;
+    Some(rest::Credentials {
+        username: outcome.identity.username,
+        password: outcome.identity.password,
+    })
+}
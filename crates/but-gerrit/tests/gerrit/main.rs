@@ -1,4 +1,4 @@
-use but_gerrit::{parse::PushOutput, record_push_metadata};
+use but_gerrit::{parse::PushOutput, record_pulled_patchset, record_push_metadata};
 use snapbox::str;
 
 #[test]
@@ -52,3 +52,48 @@ GerritMeta {
 
     Ok(())
 }
+
+#[test]
+fn record_pulled_patchset_updates_commit_id() -> anyhow::Result<()> {
+    let (repo, _tmp_writable_for_database) =
+        but_testsupport::writable_scenario("one-commit-with-gerrit-remote");
+    let repo = gix::open(repo.path())?;
+    let commit_id = but_testsupport::id_by_rev(&repo, "7923fae");
+    let change_id = but_core::Commit::from_id(commit_id)?
+        .headers()
+        .expect("gb header are set")
+        .change_id
+        .expect("commit has change id")
+        .to_string();
+    let ctx = but_ctx::Context::from_repo_for_testing(repo)?;
+    let repo = ctx.repo.get()?;
+    let mut db = ctx.db.get_cache_mut()?;
+
+    let pulled_id = gix::ObjectId::from_hex(b"1111111111111111111111111111111111111111")?;
+    let err = record_pulled_patchset(&mut db, &change_id, pulled_id).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("Change {change_id} was never pushed to Gerrit"),
+        "there is nothing to update for changes that were never pushed"
+    );
+
+    let push_output = PushOutput {
+        success: true,
+        warnings: vec![],
+        changes: vec![],
+        processing_info: None,
+    };
+    record_push_metadata(&repo, &mut db, vec![commit_id.detach()], push_output)?;
+    record_pulled_patchset(&mut db, &change_id, pulled_id)?;
+
+    let meta = db
+        .gerrit_metadata()
+        .get(&change_id)?
+        .expect("metadata is still present");
+    assert_eq!(meta.commit_id, pulled_id.to_string());
+    assert_eq!(
+        meta.review_url, "https://gerrithost/q/I10c56efd90c998f406d4e0b99d9c58feeaf896c5",
+        "everything else is retained"
+    );
+    Ok(())
+}
@@ -0,0 +1,1144 @@
+use anyhow::{Context as _, Result, bail};
+use but_secret::Sensitive;
+use reqwest::header::{ACCEPT, AUTHORIZATION, HeaderMap, HeaderValue, USER_AGENT};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::time::Duration;
+
+const GITEA_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// The page size used for `page`/`limit` pagination. Gitea caps `limit` at 50 by default.
+const PAGE_SIZE: usize = 50;
+/// Safety cap on pagination so a misbehaving server can't make us loop forever.
+const MAX_PAGES: usize = 25;
+/// How many pages of recently updated closed pull requests are searched for merged ones.
+const MAX_CLOSED_PAGES: usize = 4;
+/// The title prefix Gitea and Forgejo use to mark a pull request as work in progress.
+const WIP_PREFIX: &str = "WIP: ";
+
+/// An HTTP error with a status code, returned when the API responds with a non-success status.
+///
+/// This can be downcasted from `anyhow::Error` to distinguish auth failures (401/403) from other errors.
+#[derive(Debug, thiserror::Error)]
+#[error("HTTP {status}")]
+pub struct HttpStatusError {
+    pub status: reqwest::StatusCode,
+}
+
+pub struct GiteaClient {
+    pub(crate) client: reqwest::Client,
+    /// The API root of the instance, e.g. `https://codeberg.org/api/v1`.
+    pub(crate) base_url: String,
+}
+
+impl GiteaClient {
+    /// Build a client for the instance at `host`, authenticating with an access token.
+    ///
+    /// `host` may be a bare hostname, an origin, or the API root itself.
+    pub fn new(access_token: &Sensitive<String>, host: &str) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("gb-gitea-integration"));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        let mut auth_value = HeaderValue::from_str(&format!("token {}", access_token.0))?;
+        auth_value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, auth_value);
+
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(GITEA_REQUEST_TIMEOUT)
+            .build()?;
+
+        let host = normalize_host(host);
+        let base_url = if host.ends_with("/api/v1") {
+            host
+        } else {
+            format!("{host}/api/v1")
+        };
+
+        Ok(Self { client, base_url })
+    }
+
+    pub fn from_storage(
+        storage: &but_forge_storage::Controller,
+        preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    ) -> Result<Self> {
+        let account_id = resolve_account(preferred_account, storage)?;
+        if let Some(access_token) = crate::token::get_gitea_access_token(&account_id, storage)? {
+            account_id.client(&access_token)
+        } else {
+            Err(anyhow::anyhow!(
+                "No Gitea access token found for account '{account_id}'.\nRun 'but config forge auth' to re-authenticate."
+            ))
+        }
+    }
+
+    pub async fn get_authenticated(&self) -> Result<AuthenticatedUser> {
+        let url = format!("{}/user", self.base_url);
+        let user: GiteaApiUser = self.get_json(&url).await?;
+        Ok(AuthenticatedUser {
+            username: user.login,
+            name: user.full_name.filter(|name| !name.is_empty()),
+            email: user.email.filter(|email| !email.is_empty()),
+            avatar_url: user.avatar_url.filter(|url| !url.is_empty()),
+        })
+    }
+
+    /// GET `url` and deserialize the JSON body, turning non-success statuses into [`HttpStatusError`].
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self.client.get(url).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(HttpStatusError { status }.into());
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Fetch up to `max_pages` pages of a `page`/`limit` paginated collection,
+    /// returning the items and whether the last page was reached.
+    async fn get_pages<T: DeserializeOwned>(
+        &self,
+        initial_url: &str,
+        max_pages: usize,
+    ) -> Result<(Vec<T>, bool)> {
+        let separator = if initial_url.contains('?') { '&' } else { '?' };
+        let mut items = Vec::new();
+        for page in 1..=max_pages {
+            let url = format!("{initial_url}{separator}page={page}&limit={PAGE_SIZE}");
+            let page: Vec<T> = self.get_json(&url).await?;
+            let is_last = page.len() < PAGE_SIZE;
+            items.extend(page);
+            if is_last {
+                return Ok((items, true));
+            }
+        }
+        Ok((items, false))
+    }
+
+    /// Fetch every entry of a `page`/`limit` paginated collection.
+    ///
+    /// Errors out if the `MAX_PAGES` safety cap is hit rather than silently truncating the result.
+    async fn get_paginated<T: DeserializeOwned>(&self, initial_url: &str) -> Result<Vec<T>> {
+        let (items, complete) = self.get_pages(initial_url, MAX_PAGES).await?;
+        if !complete {
+            bail!("Gitea pagination exceeded the {MAX_PAGES}-page safety cap");
+        }
+        Ok(items)
+    }
+
+    fn repo_url(&self, owner: &str, repo: &str) -> String {
+        format!(
+            "{}/repos/{}/{}",
+            self.base_url,
+            urlencoding::encode(owner),
+            urlencoding::encode(repo),
+        )
+    }
+
+    pub async fn list_open_prs(&self, owner: &str, repo: &str) -> Result<Vec<GiteaPullRequest>> {
+        let url = format!("{}/pulls?state=open", self.repo_url(owner, repo));
+        let prs: Vec<GiteaApiPullRequest> = self.get_paginated(&url).await?;
+        Ok(prs.into_iter().map(GiteaPullRequest::from).collect())
+    }
+
+    /// List recently closed pull requests targeting `target_branch`.
+    ///
+    /// The pulls API can't filter by base branch, so only the most recently updated
+    /// closed pull requests are searched; older ones are unlikely to be of interest.
+    pub async fn list_prs_for_target(
+        &self,
+        owner: &str,
+        repo: &str,
+        target_branch: &str,
+    ) -> Result<Vec<GiteaPullRequest>> {
+        let url = format!(
+            "{}/pulls?state=closed&sort=recentupdate",
+            self.repo_url(owner, repo)
+        );
+        let (prs, _) = self
+            .get_pages::<GiteaApiPullRequest>(&url, MAX_CLOSED_PAGES)
+            .await?;
+        Ok(prs
+            .into_iter()
+            .map(GiteaPullRequest::from)
+            .filter(|pr| pr.target_branch == target_branch)
+            .collect())
+    }
+
+    pub async fn get_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        index: i64,
+    ) -> Result<GiteaPullRequest> {
+        let url = format!("{}/pulls/{index}", self.repo_url(owner, repo));
+        let pr: GiteaApiPullRequest = self.get_json(&url).await?;
+        Ok(pr.into())
+    }
+
+    pub async fn create_pull_request(
+        &self,
+        params: &CreatePullRequestParams<'_>,
+    ) -> Result<GiteaPullRequest> {
+        let url = format!("{}/pulls", self.repo_url(params.owner, params.repo));
+        let title = if params.draft {
+            with_wip_prefix(params.title)
+        } else {
+            params.title.to_owned()
+        };
+        let body = CreatePullRequestBody {
+            head: params.head,
+            base: params.base,
+            title: &title,
+            body: params.body,
+        };
+        let response = self.client.post(&url).json(&body).send().await?;
+        let response = error_for_status(response, "create pull request").await?;
+        let pr: GiteaApiPullRequest = response.json().await?;
+        Ok(pr.into())
+    }
+
+    /// Send a partial update of a pull request; only fields present in the body are changed.
+    async fn patch_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        index: i64,
+        body: &UpdatePullRequestBody<'_>,
+    ) -> Result<GiteaPullRequest> {
+        let url = format!("{}/pulls/{index}", self.repo_url(owner, repo));
+        let response = self.client.patch(&url).json(body).send().await?;
+        let response = error_for_status(response, "update pull request").await?;
+        let pr: GiteaApiPullRequest = response.json().await?;
+        Ok(pr.into())
+    }
+
+    pub async fn update_pull_request(
+        &self,
+        params: &UpdatePullRequestParams<'_>,
+    ) -> Result<GiteaPullRequest> {
+        let body = UpdatePullRequestBody {
+            title: params.title,
+            body: params.body,
+            base: params.base,
+            state: params.state.map(PullRequestState::as_str),
+        };
+        self.patch_pull_request(params.owner, params.repo, params.index, &body)
+            .await
+    }
+
+    /// Mark a pull request as draft or ready for review.
+    ///
+    /// Gitea and Forgejo have no dedicated draft flag; instead a pull request is
+    /// work in progress while its title carries a `WIP:` prefix.
+    pub async fn set_pull_request_draft_state(
+        &self,
+        owner: &str,
+        repo: &str,
+        index: i64,
+        draft: bool,
+    ) -> Result<()> {
+        let pr = self.get_pull_request(owner, repo, index).await?;
+        let title = if draft {
+            with_wip_prefix(&pr.title)
+        } else {
+            strip_wip_prefix(&pr.title).to_owned()
+        };
+        if title == pr.title {
+            return Ok(());
+        }
+        let body = UpdatePullRequestBody {
+            title: Some(&title),
+            ..Default::default()
+        };
+        self.patch_pull_request(owner, repo, index, &body).await?;
+        Ok(())
+    }
+
+    pub async fn merge_pull_request(&self, params: &MergePullRequestParams<'_>) -> Result<()> {
+        #[derive(Serialize)]
+        struct Body {
+            #[serde(rename = "Do")]
+            style: &'static str,
+        }
+        let url = format!(
+            "{}/pulls/{}/merge",
+            self.repo_url(params.owner, params.repo),
+            params.index
+        );
+        let body = Body {
+            style: params.style.as_str(),
+        };
+        let response = self.client.post(&url).json(&body).send().await?;
+        error_for_status(response, "merge pull request").await?;
+        Ok(())
+    }
+
+    pub async fn list_repo_labels(&self, owner: &str, repo: &str) -> Result<Vec<GiteaLabel>> {
+        let url = format!("{}/labels", self.repo_url(owner, repo));
+        self.get_paginated(&url).await
+    }
+
+    /// Resolve label names to the ids the issue label endpoints expect.
+    async fn label_ids(&self, owner: &str, repo: &str, names: &[String]) -> Result<Vec<i64>> {
+        let labels = self.list_repo_labels(owner, repo).await?;
+        names
+            .iter()
+            .map(|name| {
+                labels
+                    .iter()
+                    .find(|label| label.name == *name)
+                    .map(|label| label.id)
+                    .with_context(|| format!("Label '{name}' does not exist in {owner}/{repo}"))
+            })
+            .collect()
+    }
+
+    /// Add labels to a pull request, returning its resulting labels.
+    pub async fn add_labels(
+        &self,
+        owner: &str,
+        repo: &str,
+        index: i64,
+        labels: &[String],
+    ) -> Result<Vec<GiteaLabel>> {
+        #[derive(Serialize)]
+        struct Body {
+            labels: Vec<i64>,
+        }
+        let body = Body {
+            labels: self.label_ids(owner, repo, labels).await?,
+        };
+        let url = format!("{}/issues/{index}/labels", self.repo_url(owner, repo));
+        let response = self.client.post(&url).json(&body).send().await?;
+        let response = error_for_status(response, "add labels").await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn remove_label(
+        &self,
+        owner: &str,
+        repo: &str,
+        index: i64,
+        label: &str,
+    ) -> Result<()> {
+        let id = self
+            .label_ids(owner, repo, &[label.to_owned()])
+            .await?
+            .remove(0);
+        let url = format!("{}/issues/{index}/labels/{id}", self.repo_url(owner, repo));
+        let response = self.client.delete(&url).send().await?;
+        error_for_status(response, "remove label").await?;
+        Ok(())
+    }
+
+    /// List the users that may be requested to review pull requests in the repository.
+    pub async fn list_reviewer_candidates(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<GiteaUser>> {
+        let url = format!("{}/reviewers", self.repo_url(owner, repo));
+        let users: Vec<GiteaApiUser> = self.get_json(&url).await?;
+        Ok(users.into_iter().map(GiteaUser::from).collect())
+    }
+
+    pub async fn request_reviewers(
+        &self,
+        owner: &str,
+        repo: &str,
+        index: i64,
+        logins: &[String],
+    ) -> Result<()> {
+        let url = format!(
+            "{}/pulls/{index}/requested_reviewers",
+            self.repo_url(owner, repo)
+        );
+        let body = ReviewersBody { reviewers: logins };
+        let response = self.client.post(&url).json(&body).send().await?;
+        error_for_status(response, "request reviewers").await?;
+        Ok(())
+    }
+
+    pub async fn remove_requested_reviewers(
+        &self,
+        owner: &str,
+        repo: &str,
+        index: i64,
+        logins: &[String],
+    ) -> Result<()> {
+        let url = format!(
+            "{}/pulls/{index}/requested_reviewers",
+            self.repo_url(owner, repo)
+        );
+        let body = ReviewersBody { reviewers: logins };
+        let response = self.client.delete(&url).json(&body).send().await?;
+        error_for_status(response, "remove requested reviewers").await?;
+        Ok(())
+    }
+
+    /// List the conversation comments of a pull request, oldest first.
+    pub async fn list_comments(
+        &self,
+        owner: &str,
+        repo: &str,
+        index: i64,
+    ) -> Result<Vec<GiteaComment>> {
+        let url = format!("{}/issues/{index}/comments", self.repo_url(owner, repo));
+        let comments: Vec<GiteaApiComment> = self.get_json(&url).await?;
+        Ok(comments.into_iter().map(GiteaComment::from).collect())
+    }
+
+    pub async fn create_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        index: i64,
+        body: &str,
+    ) -> Result<GiteaComment> {
+        let url = format!("{}/issues/{index}/comments", self.repo_url(owner, repo));
+        let response = self
+            .client
+            .post(&url)
+            .json(&CommentBody { body })
+            .send()
+            .await?;
+        let response = error_for_status(response, "create comment").await?;
+        let comment: GiteaApiComment = response.json().await?;
+        Ok(comment.into())
+    }
+
+    pub async fn update_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<GiteaComment> {
+        let url = format!(
+            "{}/issues/comments/{comment_id}",
+            self.repo_url(owner, repo)
+        );
+        let response = self
+            .client
+            .patch(&url)
+            .json(&CommentBody { body })
+            .send()
+            .await?;
+        let response = error_for_status(response, "update comment").await?;
+        // An edit that doesn't change the body is answered with `204 No Content`.
+        let comment: GiteaApiComment = if response.status() == reqwest::StatusCode::NO_CONTENT {
+            self.get_json(&url).await?
+        } else {
+            response.json().await?
+        };
+        Ok(comment.into())
+    }
+
+    pub async fn delete_comment(&self, owner: &str, repo: &str, comment_id: i64) -> Result<()> {
+        let url = format!(
+            "{}/issues/comments/{comment_id}",
+            self.repo_url(owner, repo)
+        );
+        let response = self.client.delete(&url).send().await?;
+        error_for_status(response, "delete comment").await?;
+        Ok(())
+    }
+
+    /// List the reviews submitted on a pull request.
+    pub async fn list_reviews(
+        &self,
+        owner: &str,
+        repo: &str,
+        index: i64,
+    ) -> Result<Vec<GiteaReview>> {
+        let url = format!("{}/pulls/{index}/reviews", self.repo_url(owner, repo));
+        let reviews: Vec<GiteaApiReview> = self.get_paginated(&url).await?;
+        Ok(reviews.into_iter().map(GiteaReview::from).collect())
+    }
+
+    /// List the latest commit status of each context for `reference`.
+    ///
+    /// Returns `None` when the reference doesn't resolve, e.g. a deleted branch.
+    pub async fn list_statuses_for_ref(
+        &self,
+        owner: &str,
+        repo: &str,
+        reference: &str,
+    ) -> Result<Option<Vec<GiteaCommitStatus>>> {
+        #[derive(Deserialize)]
+        struct CombinedStatus {
+            #[serde(default)]
+            sha: String,
+            #[serde(default)]
+            statuses: Option<Vec<GiteaApiCommitStatus>>,
+        }
+
+        let reference = reference.strip_prefix("refs/heads/").unwrap_or(reference);
+        let url = format!(
+            "{}/commits/{}/status",
+            self.repo_url(owner, repo),
+            urlencoding::encode(reference)
+        );
+        let response = self.client.get(&url).send().await?;
+        let status = response.status();
+        if matches!(
+            status,
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::UNPROCESSABLE_ENTITY
+        ) {
+            return Ok(None);
+        }
+        let response = error_for_status(response, "list commit statuses").await?;
+        let combined: CombinedStatus = response.json().await?;
+        Ok(Some(
+            combined
+                .statuses
+                .unwrap_or_default()
+                .into_iter()
+                .map(|status| GiteaCommitStatus::from_api(status, &combined.sha))
+                .collect(),
+        ))
+    }
+
+    pub async fn fetch_repo(&self, owner: &str, repo: &str) -> Result<GiteaRepo> {
+        self.get_json(&self.repo_url(owner, repo)).await
+    }
+}
+
+pub(crate) fn resolve_account(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::GiteaAccountIdentifier, anyhow::Error> {
+    let known_accounts = crate::token::list_known_gitea_accounts(storage)?;
+    let Some(default_account) = known_accounts.first() else {
+        bail!(
+            "No authenticated Gitea users found.\nRun 'but config forge auth' to authenticate with Gitea or Forgejo."
+        );
+    };
+
+    if let Some(account) = preferred_account {
+        if known_accounts.contains(account) {
+            Ok(account.to_owned())
+        } else {
+            bail!(
+                "Preferred Gitea account '{account}' has not authenticated yet.\nRun 'but config forge auth' to authenticate, or choose another account."
+            );
+        }
+    } else {
+        Ok(default_account.to_owned())
+    }
+}
+
+/// Normalize a user-provided instance host into an origin without trailing slash,
+/// defaulting to `https` when no scheme was given.
+pub(crate) fn normalize_host(host: &str) -> String {
+    let host = host.trim().trim_end_matches('/');
+    if host.contains("://") {
+        host.to_owned()
+    } else {
+        format!("https://{host}")
+    }
+}
+
+/// Turn a non-success response into an [`HttpStatusError`] carrying the response text as context.
+async fn error_for_status(response: reqwest::Response, action: &str) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let error_text = response.text().await.unwrap_or_default();
+    Err(anyhow::Error::new(HttpStatusError { status })
+        .context(format!("Failed to {action}: {status} - {error_text}")))
+}
+
+/// Whether `title` marks a pull request as work in progress, using Gitea's default prefixes.
+fn has_wip_prefix(title: &str) -> bool {
+    let title = title.trim_start().to_ascii_uppercase();
+    title.starts_with("WIP:") || title.starts_with("[WIP]")
+}
+
+fn with_wip_prefix(title: &str) -> String {
+    if has_wip_prefix(title) {
+        title.to_owned()
+    } else {
+        format!("{WIP_PREFIX}{title}")
+    }
+}
+
+fn strip_wip_prefix(title: &str) -> &str {
+    let trimmed = title.trim_start();
+    if !has_wip_prefix(trimmed) {
+        return title;
+    }
+    let prefix_len = if trimmed.starts_with('[') { 5 } else { 4 };
+    trimmed[prefix_len..].trim_start()
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub username: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaApiUser {
+    id: i64,
+    login: String,
+    #[serde(default)]
+    full_name: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    avatar_url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GiteaUser {
+    pub id: i64,
+    pub username: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub avatar_url: Option<String>,
+    pub is_bot: bool,
+}
+
+impl From<GiteaApiUser> for GiteaUser {
+    fn from(user: GiteaApiUser) -> Self {
+        GiteaUser {
+            id: user.id,
+            // Negative ids are reserved for system users like the Actions runner,
+            // and bot accounts follow GitHub's `[bot]` suffix convention.
+            is_bot: user.login.ends_with("[bot]") || user.id < 0,
+            username: user.login,
+            name: user.full_name.filter(|name| !name.is_empty()),
+            email: user.email.filter(|email| !email.is_empty()),
+            avatar_url: user.avatar_url.filter(|url| !url.is_empty()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiteaLabel {
+    pub id: i64,
+    pub name: String,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaApiBranch {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    #[serde(default)]
+    sha: String,
+    #[serde(default)]
+    repo: Option<GiteaApiBranchRepo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaApiBranchRepo {
+    #[serde(default)]
+    fork: bool,
+    #[serde(default)]
+    owner: Option<GiteaApiUser>,
+    #[serde(default)]
+    ssh_url: Option<String>,
+    #[serde(default)]
+    clone_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaApiPullRequest {
+    number: i64,
+    #[serde(default)]
+    html_url: String,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    /// Only reported by recent Gitea and Forgejo releases.
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    user: Option<GiteaApiUser>,
+    #[serde(default)]
+    labels: Vec<GiteaLabel>,
+    head: GiteaApiBranch,
+    base: GiteaApiBranch,
+    #[serde(default)]
+    merged: bool,
+    #[serde(default)]
+    merged_at: Option<String>,
+    #[serde(default)]
+    merge_commit_sha: Option<String>,
+    #[serde(default)]
+    closed_at: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    updated_at: Option<String>,
+    #[serde(default)]
+    requested_reviewers: Option<Vec<GiteaApiUser>>,
+    #[serde(default)]
+    mergeable: bool,
+    #[serde(default)]
+    comments: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullRequestState {
+    Open,
+    Closed,
+}
+
+impl PullRequestState {
+    fn as_str(self) -> &'static str {
+        match self {
+            PullRequestState::Open => "open",
+            PullRequestState::Closed => "closed",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GiteaPullRequest {
+    pub html_url: String,
+    pub number: i64,
+    pub title: String,
+    pub body: Option<String>,
+    pub state: PullRequestState,
+    pub draft: bool,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub sha: String,
+    /// The commit the pull request landed as, once merged.
+    pub merge_commit_sha: Option<String>,
+    pub merged_at: Option<String>,
+    pub closed_at: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub labels: Vec<GiteaLabel>,
+    pub author: Option<GiteaUser>,
+    pub requested_reviewers: Vec<GiteaUser>,
+    pub head_repo_is_fork: bool,
+    pub repo_owner: Option<String>,
+    pub repository_ssh_url: Option<String>,
+    pub repository_https_url: Option<String>,
+    /// Whether the forge considers the pull request mergeable (no conflicts).
+    pub mergeable: bool,
+    pub comments_count: i64,
+}
+
+impl GiteaPullRequest {
+    pub fn is_open(&self) -> bool {
+        self.state == PullRequestState::Open
+    }
+
+    /// When the pull request was closed without being merged.
+    pub fn closed_unmerged_at(&self) -> Option<String> {
+        if self.merged_at.is_some() || self.is_open() {
+            None
+        } else {
+            self.closed_at.clone()
+        }
+    }
+}
+
+impl From<GiteaApiPullRequest> for GiteaPullRequest {
+    fn from(pr: GiteaApiPullRequest) -> Self {
+        let merged_at = pr.merged_at.filter(|_| pr.merged);
+        let merge_commit_sha = pr
+            .merge_commit_sha
+            .filter(|sha| pr.merged && !sha.is_empty());
+        let state = if pr.state == "open" {
+            PullRequestState::Open
+        } else {
+            PullRequestState::Closed
+        };
+        let head_repo = pr.head.repo;
+        GiteaPullRequest {
+            html_url: pr.html_url,
+            number: pr.number,
+            draft: pr.draft || has_wip_prefix(&pr.title),
+            title: pr.title,
+            body: pr.body.filter(|body| !body.is_empty()),
+            state,
+            source_branch: pr.head.ref_name,
+            target_branch: pr.base.ref_name,
+            sha: pr.head.sha,
+            merge_commit_sha,
+            merged_at,
+            closed_at: pr.closed_at,
+            created_at: pr.created_at,
+            updated_at: pr.updated_at,
+            labels: pr.labels,
+            author: pr.user.map(GiteaUser::from),
+            requested_reviewers: pr
+                .requested_reviewers
+                .unwrap_or_default()
+                .into_iter()
+                .map(GiteaUser::from)
+                .collect(),
+            head_repo_is_fork: head_repo.as_ref().is_some_and(|repo| repo.fork),
+            repo_owner: head_repo
+                .as_ref()
+                .and_then(|repo| repo.owner.as_ref())
+                .map(|owner| owner.login.clone()),
+            repository_ssh_url: head_repo.as_ref().and_then(|repo| repo.ssh_url.clone()),
+            repository_https_url: head_repo.and_then(|repo| repo.clone_url),
+            mergeable: pr.mergeable,
+            comments_count: pr.comments,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaApiComment {
+    id: i64,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    user: Option<GiteaApiUser>,
+    #[serde(default)]
+    html_url: String,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GiteaComment {
+    pub id: i64,
+    pub body: String,
+    pub author: Option<GiteaUser>,
+    pub html_url: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl From<GiteaApiComment> for GiteaComment {
+    fn from(comment: GiteaApiComment) -> Self {
+        GiteaComment {
+            id: comment.id,
+            body: comment.body,
+            author: comment.user.map(GiteaUser::from),
+            html_url: comment.html_url,
+            created_at: comment.created_at,
+            updated_at: comment.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaApiReview {
+    id: i64,
+    #[serde(default)]
+    user: Option<GiteaApiUser>,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    submitted_at: Option<String>,
+    #[serde(default)]
+    html_url: String,
+    #[serde(default)]
+    dismissed: bool,
+}
+
+/// The verdict of a submitted pull request review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewState {
+    Approved,
+    RequestChanges,
+    Comment,
+    Dismissed,
+    /// The caller's own unsubmitted review, or a pending review request.
+    Pending,
+}
+
+#[derive(Debug, Clone)]
+pub struct GiteaReview {
+    pub id: i64,
+    pub author: Option<GiteaUser>,
+    pub state: ReviewState,
+    pub body: Option<String>,
+    pub submitted_at: Option<String>,
+    pub html_url: String,
+}
+
+impl From<GiteaApiReview> for GiteaReview {
+    fn from(review: GiteaApiReview) -> Self {
+        let state = match review.state.as_str() {
+            _ if review.dismissed => ReviewState::Dismissed,
+            "APPROVED" => ReviewState::Approved,
+            "REQUEST_CHANGES" => ReviewState::RequestChanges,
+            "COMMENT" => ReviewState::Comment,
+            _ => ReviewState::Pending,
+        };
+        GiteaReview {
+            id: review.id,
+            author: review.user.map(GiteaUser::from),
+            state,
+            body: review.body,
+            submitted_at: review.submitted_at,
+            html_url: review.html_url,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaApiCommitStatus {
+    id: i64,
+    /// One of `pending`, `success`, `error`, `failure` or `warning`.
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    context: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    target_url: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GiteaCommitStatus {
+    pub id: i64,
+    pub status: String,
+    pub context: String,
+    pub description: Option<String>,
+    pub target_url: Option<String>,
+    pub sha: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl GiteaCommitStatus {
+    fn from_api(status: GiteaApiCommitStatus, sha: &str) -> Self {
+        GiteaCommitStatus {
+            id: status.id,
+            status: status.status,
+            context: status.context,
+            description: status.description.filter(|text| !text.is_empty()),
+            target_url: status.target_url.filter(|url| !url.is_empty()),
+            sha: sha.to_owned(),
+            created_at: status.created_at,
+            updated_at: status.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GiteaRepo {
+    #[serde(default)]
+    pub fork: bool,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    /// The caller's permissions on the repository.
+    #[serde(default)]
+    pub permissions: Option<GiteaRepoPermissions>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GiteaRepoPermissions {
+    #[serde(default)]
+    pub admin: bool,
+    #[serde(default)]
+    pub push: bool,
+    #[serde(default)]
+    pub pull: bool,
+}
+
+pub struct CreatePullRequestParams<'a> {
+    pub owner: &'a str,
+    pub repo: &'a str,
+    pub title: &'a str,
+    pub body: &'a str,
+    /// The source branch, as `branch` or `owner:branch` when opening from a fork.
+    pub head: &'a str,
+    pub base: &'a str,
+    pub draft: bool,
+}
+
+pub struct UpdatePullRequestParams<'a> {
+    pub owner: &'a str,
+    pub repo: &'a str,
+    pub index: i64,
+    pub title: Option<&'a str>,
+    pub body: Option<&'a str>,
+    pub base: Option<&'a str>,
+    pub state: Option<PullRequestState>,
+}
+
+pub struct MergePullRequestParams<'a> {
+    pub owner: &'a str,
+    pub repo: &'a str,
+    pub index: i64,
+    pub style: MergeStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStyle {
+    Merge,
+    Rebase,
+    RebaseMerge,
+    Squash,
+}
+
+impl MergeStyle {
+    fn as_str(self) -> &'static str {
+        match self {
+            MergeStyle::Merge => "merge",
+            MergeStyle::Rebase => "rebase",
+            MergeStyle::RebaseMerge => "rebase-merge",
+            MergeStyle::Squash => "squash",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CreatePullRequestBody<'a> {
+    head: &'a str,
+    base: &'a str,
+    title: &'a str,
+    body: &'a str,
+}
+
+#[derive(Serialize, Default)]
+struct UpdatePullRequestBody<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct ReviewersBody<'a> {
+    reviewers: &'a [String],
+}
+
+#[derive(Serialize)]
+struct CommentBody<'a> {
+    body: &'a str,
+}
+
+#[cfg(test)]
+mod mock_tests;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_pr(json: &str) -> GiteaPullRequest {
+        serde_json::from_str::<GiteaApiPullRequest>(json)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn parses_open_pull_request_json() {
+        let pr = api_pr(
+            r#"{
+                "number": 7,
+                "html_url": "https://codeberg.org/alice/web/pulls/7",
+                "title": "WIP: Add login",
+                "body": "",
+                "state": "open",
+                "user": {"id": 1, "login": "alice", "full_name": "Alice", "email": "", "avatar_url": "https://codeberg.org/avatars/1"},
+                "labels": [{"id": 3, "name": "feature", "color": "00aabb", "description": ""}],
+                "head": {"ref": "feature/login", "sha": "c0ffee", "repo": {"fork": true, "owner": {"id": 2, "login": "bob"}, "clone_url": "https://codeberg.org/bob/web.git"}},
+                "base": {"ref": "main", "sha": "beef"},
+                "merged": false,
+                "merged_at": null,
+                "merge_commit_sha": "0000",
+                "requested_reviewers": null,
+                "mergeable": true,
+                "comments": 2
+            }"#,
+        );
+        assert!(pr.is_open());
+        assert!(pr.draft, "a WIP title marks the pull request as draft");
+        assert_eq!(pr.body, None);
+        assert_eq!(pr.source_branch, "feature/login");
+        assert_eq!(pr.target_branch, "main");
+        assert_eq!(pr.sha, "c0ffee");
+        assert_eq!(
+            pr.merge_commit_sha, None,
+            "only merged PRs report a merge commit"
+        );
+        assert!(pr.head_repo_is_fork);
+        assert_eq!(pr.repo_owner.as_deref(), Some("bob"));
+        assert_eq!(
+            pr.repository_https_url.as_deref(),
+            Some("https://codeberg.org/bob/web.git")
+        );
+        let author = pr.author.unwrap();
+        assert_eq!(author.username, "alice");
+        assert_eq!(author.email, None);
+        assert_eq!(pr.labels[0].name, "feature");
+        assert_eq!(pr.comments_count, 2);
+    }
+
+    #[test]
+    fn merged_and_closed_pull_requests_are_distinguished() {
+        let merged = api_pr(
+            r#"{"number": 1, "title": "t", "state": "closed", "head": {"ref": "a"}, "base": {"ref": "main"},
+                "merged": true, "merged_at": "2024-05-01T10:00:00Z", "closed_at": "2024-05-01T10:00:00Z", "merge_commit_sha": "abc"}"#,
+        );
+        assert_eq!(merged.merged_at.as_deref(), Some("2024-05-01T10:00:00Z"));
+        assert_eq!(merged.merge_commit_sha.as_deref(), Some("abc"));
+        assert_eq!(merged.closed_unmerged_at(), None);
+
+        let closed = api_pr(
+            r#"{"number": 2, "title": "t", "state": "closed", "head": {"ref": "b"}, "base": {"ref": "main"},
+                "merged": false, "closed_at": "2024-05-02T10:00:00Z"}"#,
+        );
+        assert_eq!(closed.merged_at, None);
+        assert_eq!(
+            closed.closed_unmerged_at().as_deref(),
+            Some("2024-05-02T10:00:00Z")
+        );
+    }
+
+    #[test]
+    fn wip_prefix_is_added_and_stripped_once() {
+        assert_eq!(with_wip_prefix("Add login"), "WIP: Add login");
+        assert_eq!(with_wip_prefix("WIP: Add login"), "WIP: Add login");
+        assert_eq!(with_wip_prefix("[wip] Add login"), "[wip] Add login");
+        assert_eq!(strip_wip_prefix("WIP: Add login"), "Add login");
+        assert_eq!(strip_wip_prefix("[WIP] Add login"), "Add login");
+        assert_eq!(strip_wip_prefix("Wipe caches"), "Wipe caches");
+    }
+
+    #[test]
+    fn hosts_are_normalized_to_api_roots() {
+        let token = Sensitive("t".to_string());
+        for (host, expected) in [
+            ("codeberg.org", "https://codeberg.org/api/v1"),
+            ("https://git.example.com/", "https://git.example.com/api/v1"),
+            (
+                "http://localhost:3000/api/v1",
+                "http://localhost:3000/api/v1",
+            ),
+        ] {
+            assert_eq!(GiteaClient::new(&token, host).unwrap().base_url, expected);
+        }
+    }
+
+    #[test]
+    fn merge_styles_serialize_to_gitea_names() {
+        assert_eq!(MergeStyle::Merge.as_str(), "merge");
+        assert_eq!(MergeStyle::RebaseMerge.as_str(), "rebase-merge");
+        assert_eq!(MergeStyle::Squash.as_str(), "squash");
+    }
+}
@@ -0,0 +1,217 @@
+use super::*;
+use but_testsupport::{MockHttpRequest, MockHttpResponse, mock_http_server};
+use std::sync::mpsc;
+
+/// Serve `responses` in order, asserting method and path of each request, and
+/// forward every request to the returned receiver.
+fn mock_client(
+    responses: Vec<MockHttpResponse>,
+) -> (
+    GiteaClient,
+    mpsc::Receiver<MockHttpRequest>,
+    std::thread::JoinHandle<()>,
+) {
+    let (origin, requests, server) = mock_http_server(responses);
+    let client = GiteaClient::new(&Sensitive("test-token".to_string()), &origin).unwrap();
+    (client, requests, server)
+}
+
+fn pr_json(number: usize, state: &str) -> String {
+    format!(
+        r#"{{"number":{number},"title":"PR {number}","state":"{state}","head":{{"ref":"feature-{number}","sha":"c0ffee{number}"}},"base":{{"ref":"main"}}}}"#
+    )
+}
+
+fn page(numbers: std::ops::Range<usize>) -> String {
+    let prs: Vec<_> = numbers.map(|number| pr_json(number, "open")).collect();
+    format!("[{}]", prs.join(","))
+}
+
+const REPO_PATH: &str = "/api/v1/repos/alice/web";
+
+#[tokio::test(flavor = "current_thread")]
+async fn list_open_prs_follows_page_pagination() {
+    let (client, _bodies, server) = mock_client(vec![
+        MockHttpResponse::ok(
+            "GET",
+            format!("{REPO_PATH}/pulls?state=open&page=1&limit=50"),
+            page(0..50),
+        ),
+        MockHttpResponse::ok(
+            "GET",
+            format!("{REPO_PATH}/pulls?state=open&page=2&limit=50"),
+            page(50..51),
+        ),
+    ]);
+
+    let prs = client.list_open_prs("alice", "web").await.unwrap();
+    server.join().unwrap();
+
+    assert_eq!(prs.len(), 51, "a short page ends pagination");
+    assert_eq!(prs[50].source_branch, "feature-50");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_draft_pull_request_uses_wip_title() {
+    let (client, bodies, server) = mock_client(vec![MockHttpResponse::ok(
+        "POST",
+        format!("{REPO_PATH}/pulls"),
+        pr_json(12, "open"),
+    )]);
+
+    let pr = client
+        .create_pull_request(&CreatePullRequestParams {
+            owner: "alice",
+            repo: "web",
+            title: "Add login",
+            body: "Description",
+            head: "feature/login",
+            base: "main",
+            draft: true,
+        })
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    assert_eq!(pr.number, 12);
+    let body: serde_json::Value = serde_json::from_str(&bodies.recv().unwrap().body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "head": "feature/login",
+            "base": "main",
+            "title": "WIP: Add login",
+            "body": "Description",
+        })
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn marking_ready_for_review_strips_the_wip_prefix() {
+    let (client, bodies, server) = mock_client(vec![
+        MockHttpResponse::ok(
+            "GET",
+            format!("{REPO_PATH}/pulls/5"),
+            r#"{"number":5,"title":"WIP: Add login","state":"open","head":{"ref":"a"},"base":{"ref":"main"}}"#,
+        ),
+        MockHttpResponse::ok("PATCH", format!("{REPO_PATH}/pulls/5"), pr_json(5, "open")),
+    ]);
+
+    client
+        .set_pull_request_draft_state("alice", "web", 5, false)
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    let _get = bodies.recv().unwrap();
+    let patch: serde_json::Value = serde_json::from_str(&bodies.recv().unwrap().body).unwrap();
+    assert_eq!(patch, serde_json::json!({ "title": "Add login" }));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn merge_pull_request_sends_the_merge_style() {
+    let (client, bodies, server) = mock_client(vec![MockHttpResponse::ok(
+        "POST",
+        format!("{REPO_PATH}/pulls/5/merge"),
+        "",
+    )]);
+
+    client
+        .merge_pull_request(&MergePullRequestParams {
+            owner: "alice",
+            repo: "web",
+            index: 5,
+            style: MergeStyle::Squash,
+        })
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    let body: serde_json::Value = serde_json::from_str(&bodies.recv().unwrap().body).unwrap();
+    assert_eq!(body, serde_json::json!({ "Do": "squash" }));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn labels_are_added_by_id() {
+    let (client, bodies, server) = mock_client(vec![
+        MockHttpResponse::ok(
+            "GET",
+            format!("{REPO_PATH}/labels?page=1&limit=50"),
+            r#"[{"id":1,"name":"bug"},{"id":4,"name":"feature"}]"#,
+        ),
+        MockHttpResponse::ok(
+            "POST",
+            format!("{REPO_PATH}/issues/5/labels"),
+            r#"[{"id":4,"name":"feature","color":"00aabb"}]"#,
+        ),
+    ]);
+
+    let labels = client
+        .add_labels("alice", "web", 5, &["feature".to_string()])
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    assert_eq!(labels[0].name, "feature");
+    let _get = bodies.recv().unwrap();
+    let body: serde_json::Value = serde_json::from_str(&bodies.recv().unwrap().body).unwrap();
+    assert_eq!(body, serde_json::json!({ "labels": [4] }));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn statuses_for_a_branch_use_the_combined_status() {
+    let (client, _bodies, server) = mock_client(vec![MockHttpResponse::ok(
+        "GET",
+        format!("{REPO_PATH}/commits/feature%2Flogin/status"),
+        r#"{"state":"failure","sha":"c0ffee","statuses":[{"id":3,"status":"failure","context":"ci/test","target_url":"https://ci.example.com/3","description":""}]}"#,
+    )]);
+
+    let statuses = client
+        .list_statuses_for_ref("alice", "web", "refs/heads/feature/login")
+        .await
+        .unwrap()
+        .expect("the branch resolves");
+    server.join().unwrap();
+
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].context, "ci/test");
+    assert_eq!(statuses[0].sha, "c0ffee");
+    assert_eq!(statuses[0].description, None);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn statuses_for_an_unknown_ref_are_unresolved() {
+    let (client, _bodies, server) = mock_client(vec![MockHttpResponse {
+        method: "GET",
+        path: format!("{REPO_PATH}/commits/gone/status"),
+        status: reqwest::StatusCode::NOT_FOUND.as_u16(),
+        body: r#"{"message":"not found"}"#.into(),
+    }]);
+
+    let statuses = client
+        .list_statuses_for_ref("alice", "web", "gone")
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    assert!(statuses.is_none());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn invalid_token_is_unauthorized() {
+    let (client, _bodies, server) = mock_client(vec![MockHttpResponse {
+        method: "GET",
+        path: "/api/v1/user".into(),
+        status: reqwest::StatusCode::UNAUTHORIZED.as_u16(),
+        body: r#"{"message":"token is required"}"#.into(),
+    }]);
+
+    let err = client.get_authenticated().await.unwrap_err();
+    server.join().unwrap();
+
+    let status = err
+        .downcast_ref::<HttpStatusError>()
+        .expect("auth failures are reported as HTTP status errors")
+        .status;
+    assert_eq!(status, reqwest::StatusCode::UNAUTHORIZED);
+}
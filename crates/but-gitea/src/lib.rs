@@ -0,0 +1,332 @@
+use anyhow::{Context as _, Result};
+use but_secret::Sensitive;
+
+mod client;
+pub mod pr;
+mod repo;
+pub use client::{
+    CreatePullRequestParams, GiteaClient, GiteaComment, GiteaCommitStatus, GiteaLabel,
+    GiteaPullRequest, GiteaRepo, GiteaRepoPermissions, GiteaReview, GiteaUser, HttpStatusError,
+    MergePullRequestParams, MergeStyle, PullRequestState, ReviewState, UpdatePullRequestParams,
+};
+pub use repo::fetch_repo;
+mod token;
+use serde::Serialize;
+pub use token::GiteaAccountIdentifier;
+
+#[derive(Debug, Clone)]
+pub struct AuthStatusResponse {
+    /// The access token.
+    /// This is only shared with the FrontEnd temporarily as we undergo the migration to having all API calls
+    /// made to the forges from the Rustend.
+    pub access_token: Sensitive<String>,
+    pub username: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub host: String,
+}
+
+/// Store a Gitea or Forgejo access token and fetch the associated user data.
+///
+/// `host` is the instance the token was issued by, e.g. `https://codeberg.org`.
+pub async fn store_pat(
+    host: &str,
+    access_token: &Sensitive<String>,
+    storage: &but_forge_storage::Controller,
+) -> Result<AuthStatusResponse> {
+    let host = client::normalize_host(host);
+    let user = fetch_and_persist_user_data(&host, access_token, storage).await?;
+    Ok(AuthStatusResponse {
+        access_token: access_token.clone(),
+        username: user.username,
+        name: user.name,
+        email: user.email,
+        host,
+    })
+}
+
+/// Cache the user profile so it's available offline.
+fn cache_user_profile(
+    account: &GiteaAccountIdentifier,
+    user: &client::AuthenticatedUser,
+    storage: &but_forge_storage::Controller,
+) {
+    let profile = but_forge_storage::settings::CachedProfile {
+        avatar_url: user.avatar_url.clone(),
+        name: user.name.clone(),
+        email: user.email.clone(),
+    };
+    let key = account.cache_key();
+    let existing = storage.cached_profile(&key).ok().flatten();
+    if existing.as_ref() == Some(&profile) {
+        return;
+    }
+    if let Err(err) = storage.set_cached_profile(&key, Some(profile)) {
+        tracing::warn!(?account, "Failed to update cached Gitea profile: {err}");
+    }
+}
+
+/// Fetch the authenticated user data from the instance and persist the access token.
+async fn fetch_and_persist_user_data(
+    host: &str,
+    access_token: &Sensitive<String>,
+    storage: &but_forge_storage::Controller,
+) -> Result<client::AuthenticatedUser, anyhow::Error> {
+    let gitea =
+        client::GiteaClient::new(access_token, host).context("Failed to create Gitea client")?;
+    let user = gitea
+        .get_authenticated()
+        .await
+        .context("Failed to get authenticated user")?;
+    let account_id = token::GiteaAccountIdentifier::pat(&user.username, host);
+    token::persist_gitea_access_token(&account_id, access_token, storage)
+        .context("Failed to persist access token")?;
+    cache_user_profile(&account_id, &user, storage);
+    Ok(user)
+}
+
+pub fn forget_gitea_access_token(
+    account: &GiteaAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    token::delete_gitea_access_token(account, storage).context("Failed to delete access token")
+}
+
+pub async fn get_gitea_user(
+    account: &GiteaAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<Option<AuthenticatedUser>> {
+    if let Some(access_token) = token::get_gitea_access_token(account, storage)? {
+        let gitea = account
+            .client(&access_token)
+            .context("Failed to create Gitea client")?;
+        match gitea.get_authenticated().await {
+            Ok(user) => {
+                cache_user_profile(account, &user, storage);
+                Ok(Some(AuthenticatedUser {
+                    access_token,
+                    username: user.username,
+                    host: account.host().to_owned(),
+                    avatar_url: user.avatar_url,
+                    name: user.name,
+                    email: user.email,
+                }))
+            }
+            Err(client_err) => {
+                let cache_key = account.cache_key();
+                // Check if this is a network error — return cached data if available.
+                if let Some(reqwest_err) = client_err.downcast_ref::<reqwest::Error>()
+                    && is_network_error(reqwest_err)
+                {
+                    match storage.cached_profile(&cache_key) {
+                        Ok(Some(cached)) => {
+                            return Ok(Some(AuthenticatedUser {
+                                access_token,
+                                username: account.username().to_owned(),
+                                host: account.host().to_owned(),
+                                avatar_url: cached.avatar_url,
+                                name: cached.name,
+                                email: cached.email,
+                            }));
+                        }
+                        Ok(None) => {}
+                        Err(err) => {
+                            tracing::warn!("Failed to read cached Gitea profile: {err}");
+                        }
+                    }
+                    return Err(client_err.context(but_error::Context::new_static(
+                        but_error::Code::NetworkError,
+                        "Unable to connect to the Gitea instance.",
+                    )));
+                }
+                // Check if this is an auth error (401/403) — clear cached profile.
+                if let Some(http_err) = client_err.downcast_ref::<client::HttpStatusError>()
+                    && matches!(
+                        http_err.status,
+                        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+                    )
+                    && let Err(err) = storage.set_cached_profile(&cache_key, None)
+                {
+                    tracing::warn!("Failed to clear cached Gitea profile: {err}");
+                }
+                Err(client_err.context("Failed to get authenticated user"))
+            }
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+/// Check if an error is a network connectivity error.
+///
+/// This includes DNS resolution failures, connection timeouts, connection refused, etc.
+fn is_network_error(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect() || err.is_request()
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub enum CredentialCheckResult {
+    Valid,
+    Invalid,
+    NoCredentials,
+}
+
+/// Check the validity of the stored credentials for the given Gitea account.
+pub async fn check_credentials(
+    account: &GiteaAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<CredentialCheckResult> {
+    if let Some(access_token) = token::get_gitea_access_token(account, storage)? {
+        let gitea = account
+            .client(&access_token)
+            .context("Failed to create Gitea client")?;
+        match gitea.get_authenticated().await {
+            Ok(_) => Ok(CredentialCheckResult::Valid),
+            Err(_) => Ok(CredentialCheckResult::Invalid),
+        }
+    } else {
+        Ok(CredentialCheckResult::NoCredentials)
+    }
+}
+
+pub fn list_known_gitea_accounts(
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<token::GiteaAccountIdentifier>> {
+    token::list_known_gitea_accounts(storage).context("Failed to list known Gitea accounts")
+}
+
+pub fn clear_all_gitea_tokens(storage: &but_forge_storage::Controller) -> Result<()> {
+    token::clear_all_gitea_accounts(storage).context("Failed to clear all Gitea tokens")
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub access_token: Sensitive<String>,
+    pub username: String,
+    pub host: String,
+    pub avatar_url: Option<String>,
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// JSON serialization types for Gitea API responses.
+///
+/// This module contains serializable versions of Gitea authentication types
+/// that expose sensitive data (like access tokens) as plain strings for API responses.
+pub mod json {
+    use serde::Serialize;
+
+    use crate::{AuthStatusResponse, AuthenticatedUser};
+
+    /// Serializable version of [`AuthStatusResponse`], without the access token.
+    ///
+    /// The credential is stored by the backend as part of the call, so the caller is told
+    /// who authenticated and nothing more. Field names are camelCase for JSON.
+    #[derive(Debug, Serialize)]
+    #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+    #[serde(rename_all = "camelCase")]
+    pub struct GiteaAuthStatusResponse {
+        pub username: String,
+        pub name: Option<String>,
+        pub email: Option<String>,
+        pub host: String,
+    }
+
+    impl From<AuthStatusResponse> for GiteaAuthStatusResponse {
+        fn from(
+            AuthStatusResponse {
+                username,
+                name,
+                email,
+                host,
+                ..
+            }: AuthStatusResponse,
+        ) -> Self {
+            GiteaAuthStatusResponse {
+                username,
+                name,
+                email,
+                host,
+            }
+        }
+    }
+
+    #[cfg(feature = "export-schema")]
+    but_schemars::register_sdk_type!(GiteaAuthStatusResponse);
+
+    /// Serializable version of [`AuthenticatedUser`] with exposed access token.
+    #[derive(Debug, Serialize)]
+    #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+    #[serde(rename_all = "camelCase")]
+    pub struct GiteaAuthenticatedUserSensitive {
+        /// The Gitea access token as a plain string (sensitive data).
+        pub access_token: String,
+        /// The login of the user.
+        pub username: String,
+        /// The instance the account belongs to.
+        pub host: String,
+        /// The URL to the user's avatar image, if available.
+        pub avatar_url: Option<String>,
+        /// The user's display name, if available.
+        pub name: Option<String>,
+        /// The user's email, if available.
+        pub email: Option<String>,
+    }
+
+    impl From<AuthenticatedUser> for GiteaAuthenticatedUserSensitive {
+        fn from(
+            AuthenticatedUser {
+                access_token,
+                username,
+                host,
+                avatar_url,
+                name,
+                email,
+            }: AuthenticatedUser,
+        ) -> Self {
+            GiteaAuthenticatedUserSensitive {
+                access_token: access_token.0,
+                username,
+                host,
+                avatar_url,
+                name,
+                email,
+            }
+        }
+    }
+
+    #[cfg(feature = "export-schema")]
+    but_schemars::register_sdk_type!(GiteaAuthenticatedUserSensitive);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accounts_are_keyed_by_host_and_username() {
+        let codeberg = GiteaAccountIdentifier::pat("alice", "https://codeberg.org");
+        let self_hosted = GiteaAccountIdentifier::pat("alice", "https://git.example.com");
+        assert_ne!(codeberg.cache_key(), self_hosted.cache_key());
+        assert_eq!(
+            codeberg.custom_host().as_deref(),
+            Some("https://codeberg.org")
+        );
+        assert_eq!(codeberg.to_string(), "PAT: alice (https://codeberg.org)");
+    }
+
+    #[test]
+    fn test_is_network_error_with_connection_error() {
+        // Loopback port 1 is closed, so the connection is refused immediately
+        // without touching the external network — deterministic and fast.
+        let client = reqwest::blocking::Client::new();
+        let err = client
+            .get("http://127.0.0.1:1")
+            .send()
+            .expect_err("connection to a closed port should fail");
+        assert!(
+            is_network_error(&err),
+            "connection refused should be classified as a network error"
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::{Context as _, Result};
+
+use crate::client::GiteaClient;
+
+pub async fn list(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::GiteaPullRequest>> {
+    if let Ok(gitea) = GiteaClient::from_storage(storage, preferred_account) {
+        gitea
+            .list_open_prs(owner, repo)
+            .await
+            .context("Failed to list open pull requests")
+    } else {
+        Ok(vec![])
+    }
+}
+
+pub async fn list_all_for_target(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    target_branch: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::GiteaPullRequest>> {
+    if let Ok(gitea) = GiteaClient::from_storage(storage, preferred_account) {
+        gitea
+            .list_prs_for_target(owner, repo, target_branch)
+            .await
+            .context("Failed to list pull requests for target branch")
+    } else {
+        Ok(vec![])
+    }
+}
+
+pub async fn get(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    index: usize,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::GiteaPullRequest> {
+    let index = index.try_into().context("PR number is too large")?;
+    GiteaClient::from_storage(storage, preferred_account)?
+        .get_pull_request(owner, repo, index)
+        .await
+        .context("Failed to get pull request")
+}
+
+pub async fn create(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    params: crate::client::CreatePullRequestParams<'_>,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::GiteaPullRequest> {
+    GiteaClient::from_storage(storage, preferred_account)?
+        .create_pull_request(&params)
+        .await
+        .context("Failed to create pull request")
+}
+
+pub async fn update(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    params: crate::client::UpdatePullRequestParams<'_>,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::GiteaPullRequest> {
+    GiteaClient::from_storage(storage, preferred_account)?
+        .update_pull_request(&params)
+        .await
+        .context("Failed to update pull request")
+}
+
+pub async fn merge(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    params: crate::client::MergePullRequestParams<'_>,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    GiteaClient::from_storage(storage, preferred_account)?
+        .merge_pull_request(&params)
+        .await
+        .context("Failed to merge pull request")
+}
+
+pub async fn set_draft_state(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    index: usize,
+    draft: bool,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let index = index.try_into().context("PR number is too large")?;
+    GiteaClient::from_storage(storage, preferred_account)?
+        .set_pull_request_draft_state(owner, repo, index, draft)
+        .await
+        .context("Failed to set pull request draft state")
+}
+
+pub async fn list_repo_labels(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::GiteaLabel>> {
+    GiteaClient::from_storage(storage, preferred_account)?
+        .list_repo_labels(owner, repo)
+        .await
+        .context("Failed to list repository labels")
+}
+
+pub async fn add_labels(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    index: usize,
+    labels: &[String],
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::GiteaLabel>> {
+    let index = index.try_into().context("PR number is too large")?;
+    GiteaClient::from_storage(storage, preferred_account)?
+        .add_labels(owner, repo, index, labels)
+        .await
+        .context("Failed to add labels to pull request")
+}
+
+pub async fn remove_label(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    index: usize,
+    label: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let index = index.try_into().context("PR number is too large")?;
+    GiteaClient::from_storage(storage, preferred_account)?
+        .remove_label(owner, repo, index, label)
+        .await
+        .context("Failed to remove label from pull request")
+}
+
+pub async fn list_reviewer_candidates(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::GiteaUser>> {
+    GiteaClient::from_storage(storage, preferred_account)?
+        .list_reviewer_candidates(owner, repo)
+        .await
+        .context("Failed to list reviewer candidates")
+}
+
+pub async fn request_reviewers(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    index: usize,
+    logins: &[String],
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let index = index.try_into().context("PR number is too large")?;
+    GiteaClient::from_storage(storage, preferred_account)?
+        .request_reviewers(owner, repo, index, logins)
+        .await
+        .context("Failed to request reviewers")
+}
+
+pub async fn remove_requested_reviewers(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    index: usize,
+    logins: &[String],
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let index = index.try_into().context("PR number is too large")?;
+    GiteaClient::from_storage(storage, preferred_account)?
+        .remove_requested_reviewers(owner, repo, index, logins)
+        .await
+        .context("Failed to remove requested reviewers")
+}
+
+pub async fn list_comments(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    index: usize,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::GiteaComment>> {
+    let index = index.try_into().context("PR number is too large")?;
+    GiteaClient::from_storage(storage, preferred_account)?
+        .list_comments(owner, repo, index)
+        .await
+        .context("Failed to list pull request comments")
+}
+
+pub async fn create_comment(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    index: usize,
+    body: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::GiteaComment> {
+    let index = index.try_into().context("PR number is too large")?;
+    GiteaClient::from_storage(storage, preferred_account)?
+        .create_comment(owner, repo, index, body)
+        .await
+        .context("Failed to create pull request comment")
+}
+
+pub async fn update_comment(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    comment_id: i64,
+    body: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::GiteaComment> {
+    GiteaClient::from_storage(storage, preferred_account)?
+        .update_comment(owner, repo, comment_id, body)
+        .await
+        .context("Failed to update pull request comment")
+}
+
+pub async fn delete_comment(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    comment_id: i64,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    GiteaClient::from_storage(storage, preferred_account)?
+        .delete_comment(owner, repo, comment_id)
+        .await
+        .context("Failed to delete pull request comment")
+}
+
+pub async fn list_reviews(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    index: usize,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::GiteaReview>> {
+    let index = index.try_into().context("PR number is too large")?;
+    GiteaClient::from_storage(storage, preferred_account)?
+        .list_reviews(owner, repo, index)
+        .await
+        .context("Failed to list pull request reviews")
+}
@@ -0,0 +1,14 @@
+use anyhow::{Context as _, Result};
+
+/// Fetch repository metadata (fork status, default branch, permissions) for `owner/repo`.
+pub async fn fetch_repo(
+    preferred_account: Option<&crate::GiteaAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<crate::client::GiteaRepo> {
+    crate::client::GiteaClient::from_storage(storage, preferred_account)?
+        .fetch_repo(owner, repo)
+        .await
+        .context("Failed to fetch Gitea repository")
+}
@@ -0,0 +1,251 @@
+use std::sync::Mutex;
+
+use anyhow::Result;
+use but_secret::{Sensitive, secret};
+use serde::{Deserialize, Serialize};
+
+use crate::client::GiteaClient;
+
+/// Persist Gitea account access tokens securely.
+pub fn persist_gitea_access_token(
+    account_id: &GiteaAccountIdentifier,
+    access_token: &Sensitive<String>,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let account = GiteaAccount::new(account_id, access_token.clone());
+    persist_gitea_account(&account, storage)
+}
+
+/// Delete a Gitea account access token for a given account.
+pub fn delete_gitea_access_token(
+    account_id: &GiteaAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let account = find_gitea_account(account_id, storage)?;
+    if let Some(account) = account {
+        delete_gitea_account(&account, storage)
+    } else {
+        Ok(())
+    }
+}
+
+/// Retrieve a Gitea account access token for a given account.
+pub fn get_gitea_access_token(
+    account_id: &GiteaAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<Option<Sensitive<String>>> {
+    let account = find_gitea_account(account_id, storage)?;
+    Ok(account.map(|acct| acct.access_token()))
+}
+
+pub fn list_known_gitea_accounts(
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<GiteaAccountIdentifier>> {
+    Ok(storage
+        .gitea_accounts()?
+        .iter()
+        .map(|account| account.into())
+        .collect::<Vec<_>>())
+}
+
+pub fn clear_all_gitea_accounts(storage: &but_forge_storage::Controller) -> Result<()> {
+    delete_all_gitea_accounts(storage)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase", tag = "type", content = "info")]
+pub enum GiteaAccountIdentifier {
+    /// An access token for a user on a Gitea or Forgejo instance.
+    /// There is no canonical public instance, so the account always carries its host.
+    Pat { username: String, host: String },
+}
+#[cfg(feature = "export-schema")]
+but_schemars::register_sdk_type!(GiteaAccountIdentifier);
+
+impl GiteaAccountIdentifier {
+    pub fn pat(username: &str, host: &str) -> Self {
+        GiteaAccountIdentifier::Pat {
+            username: username.to_string(),
+            host: host.to_string(),
+        }
+    }
+
+    pub fn username(&self) -> &str {
+        match self {
+            GiteaAccountIdentifier::Pat { username, .. } => username,
+        }
+    }
+
+    pub fn host(&self) -> &str {
+        match self {
+            GiteaAccountIdentifier::Pat { host, .. } => host,
+        }
+    }
+
+    /// The key used to store and look up the cached profile for this account.
+    pub fn cache_key(&self) -> String {
+        match self {
+            GiteaAccountIdentifier::Pat { username, host } => {
+                format!("gitea_pat_{host}_{username}")
+            }
+        }
+    }
+
+    pub fn client(&self, access_token: &Sensitive<String>) -> Result<GiteaClient> {
+        match self {
+            GiteaAccountIdentifier::Pat { host, .. } => GiteaClient::new(access_token, host),
+        }
+    }
+
+    /// Retrieve the custom forge host.
+    pub fn custom_host(&self) -> Option<String> {
+        Some(self.host().to_owned())
+    }
+}
+
+impl std::fmt::Display for GiteaAccountIdentifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GiteaAccountIdentifier::Pat { username, host } => {
+                write!(f, "PAT: {username} ({host})")
+            }
+        }
+    }
+}
+
+pub enum GiteaAccount {
+    Pat {
+        username: String,
+        host: String,
+        access_token: Sensitive<String>,
+    },
+}
+
+impl From<&GiteaAccount> for but_forge_storage::settings::GiteaAccount {
+    fn from(account: &GiteaAccount) -> Self {
+        let access_token_key = account.secret_key();
+        match account {
+            GiteaAccount::Pat { username, host, .. } => {
+                but_forge_storage::settings::GiteaAccount::Pat {
+                    username: username.to_owned(),
+                    host: host.to_owned(),
+                    access_token_key,
+                }
+            }
+        }
+    }
+}
+
+impl From<&but_forge_storage::settings::GiteaAccount> for GiteaAccountIdentifier {
+    fn from(account: &but_forge_storage::settings::GiteaAccount) -> Self {
+        match account {
+            but_forge_storage::settings::GiteaAccount::Pat { username, host, .. } => {
+                GiteaAccountIdentifier::pat(username, host)
+            }
+        }
+    }
+}
+
+impl GiteaAccount {
+    pub fn new(account_id: &GiteaAccountIdentifier, access_token: Sensitive<String>) -> Self {
+        match account_id {
+            GiteaAccountIdentifier::Pat { username, host } => GiteaAccount::Pat {
+                username: username.to_owned(),
+                host: host.to_owned(),
+                access_token,
+            },
+        }
+    }
+
+    fn secret_key(&self) -> String {
+        match self {
+            GiteaAccount::Pat { username, host, .. } => {
+                GiteaAccountIdentifier::pat(username, host).cache_key()
+            }
+        }
+    }
+
+    fn secret_value(&self) -> Result<Sensitive<String>> {
+        Ok(self.access_token())
+    }
+
+    fn access_token(&self) -> Sensitive<String> {
+        match self {
+            GiteaAccount::Pat { access_token, .. } => access_token.clone(),
+        }
+    }
+}
+
+fn retrieve_gitea_secret(account_secret_key: &str) -> Result<Option<Sensitive<String>>> {
+    static FAIR_QUEUE: Mutex<()> = Mutex::new(());
+    let _one_at_a_time_to_prevent_races = FAIR_QUEUE.lock().unwrap();
+    secret::retrieve(account_secret_key, secret::Namespace::BuildKind)
+}
+
+fn persist_gitea_account(
+    account: &GiteaAccount,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let secret_key = account.secret_key();
+    storage.add_gitea_account(&account.into())?;
+
+    static FAIR_QUEUE: Mutex<()> = Mutex::new(());
+    let _one_at_a_time_to_prevent_races = FAIR_QUEUE.lock().unwrap();
+    secret::persist(
+        &secret_key,
+        &account.secret_value()?,
+        secret::Namespace::BuildKind,
+    )
+}
+
+fn delete_gitea_account(
+    account: &GiteaAccount,
+    storage: &but_forge_storage::Controller,
+) -> Result<()> {
+    let secret_key = account.secret_key();
+    storage.remove_gitea_account(&account.into())?;
+
+    static FAIR_QUEUE: Mutex<()> = Mutex::new(());
+    let _one_at_a_time_to_prevent_races = FAIR_QUEUE.lock().unwrap();
+    secret::delete(&secret_key, secret::Namespace::BuildKind)
+}
+
+fn delete_all_gitea_accounts(storage: &but_forge_storage::Controller) -> Result<()> {
+    let keys_to_delete = storage.clear_all_gitea_accounts()?;
+    static FAIR_QUEUE: Mutex<()> = Mutex::new(());
+    let _one_at_a_time_to_prevent_races = FAIR_QUEUE.lock().unwrap();
+    for key in keys_to_delete {
+        secret::delete(&key, secret::Namespace::BuildKind)?;
+    }
+    Ok(())
+}
+
+fn find_gitea_account(
+    account_id: &GiteaAccountIdentifier,
+    storage: &but_forge_storage::Controller,
+) -> Result<Option<GiteaAccount>> {
+    let accounts = storage.gitea_accounts()?;
+    let result = match account_id {
+        GiteaAccountIdentifier::Pat { username, host } => accounts.iter().find_map(|account| {
+            let but_forge_storage::settings::GiteaAccount::Pat {
+                username: acct_username,
+                host: acct_host,
+                access_token_key,
+            } = account;
+            if acct_username == username
+                && acct_host == host
+                && let Some(access_token) = retrieve_gitea_secret(access_token_key).ok().flatten()
+            {
+                return Some(GiteaAccount::Pat {
+                    username: acct_username.clone(),
+                    host: acct_host.clone(),
+                    access_token,
+                });
+            }
+            None
+        }),
+    };
+    Ok(result)
+}
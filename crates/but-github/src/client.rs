@@ -498,6 +498,27 @@ impl GitHubClient {
             .collect())
     }
 
+    /// List the review comments on lines of the diff of a pull request,
+    /// oldest first, with replies following the comment they answer.
+    pub async fn list_pull_request_review_comments(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<PullRequestReviewComment>> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/comments",
+            self.base_url, owner, repo, pr_number
+        );
+
+        Ok(self
+            .get_all_pages::<GitHubReviewComment>(&url, 50)
+            .await?
+            .into_iter()
+            .map(PullRequestReviewComment::from)
+            .collect())
+    }
+
     /// List the individual reactions on a pull request itself (GitHub
     /// models the PR as an issue for reaction purposes).
     pub async fn list_pull_request_reactions(
@@ -1664,6 +1685,28 @@ pub struct PullRequestComment {
     pub reactions: CommentReactions,
 }
 
+/// A review comment on a line of the diff of a pull request, from
+/// `GET /pulls/{n}/comments`. `line` is `None` when the comment is outdated,
+/// i.e. its line isn't part of the current diff anymore.
+#[derive(Debug, Serialize)]
+pub struct PullRequestReviewComment {
+    pub id: i64,
+    pub in_reply_to_id: Option<i64>,
+    pub path: String,
+    pub line: Option<u32>,
+    /// `LEFT` for the base side of the diff, `RIGHT` for the head side.
+    pub side: Option<String>,
+    /// The commit the comment was made on.
+    pub commit_id: String,
+    /// The diff hunk up to and including the commented line.
+    pub diff_hunk: String,
+    pub body: String,
+    pub author: Option<GitHubUser>,
+    pub created_at: Option<String>,
+    pub modified_at: Option<String>,
+    pub html_url: String,
+}
+
 /// One reaction from the reactions endpoints. `content` is GitHub's raw
 /// kind string (`+1`, `laugh`, `heart`, …); `id` addresses deletion.
 #[derive(Debug, Serialize)]
@@ -1808,6 +1851,45 @@ impl From<GitHubIssueComment> for PullRequestComment {
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct GitHubReviewComment {
+    id: i64,
+    #[serde(default)]
+    in_reply_to_id: Option<i64>,
+    path: String,
+    #[serde(default)]
+    line: Option<u32>,
+    #[serde(default)]
+    side: Option<String>,
+    commit_id: String,
+    #[serde(default)]
+    diff_hunk: String,
+    body: Option<String>,
+    user: Option<GitHubApiUser>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    html_url: String,
+}
+
+impl From<GitHubReviewComment> for PullRequestReviewComment {
+    fn from(comment: GitHubReviewComment) -> Self {
+        PullRequestReviewComment {
+            id: comment.id,
+            in_reply_to_id: comment.in_reply_to_id,
+            path: comment.path,
+            line: comment.line,
+            side: comment.side,
+            commit_id: comment.commit_id,
+            diff_hunk: comment.diff_hunk,
+            body: comment.body.unwrap_or_default(),
+            author: comment.user.map(Into::into),
+            created_at: comment.created_at,
+            modified_at: comment.updated_at,
+            html_url: comment.html_url,
+        }
+    }
+}
+
 /// The timeline rows lite renders between conversation comments.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum PullRequestTimelineEventKind {
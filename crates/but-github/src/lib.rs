@@ -14,8 +14,9 @@ pub use client::{
     AutoMergeEnableParams, AutoMergeState, CheckRun, CommentReactions, CreatePullRequestParams,
     GitHubClient, GitHubPrLabel, GitHubRepoPermissions, GitHubRepository, GitHubUser, MergeMethod,
     MergePullRequestParams, PullRequest, PullRequestComment, PullRequestMergeStatus,
-    PullRequestReview, PullRequestTimelineEvent, PullRequestTimelineEventKind, Reaction,
-    SetPullRequestAutoMergeParams, SetPullRequestDraftStateParams, UpdatePullRequestParams,
+    PullRequestReview, PullRequestReviewComment, PullRequestTimelineEvent,
+    PullRequestTimelineEventKind, Reaction, SetPullRequestAutoMergeParams,
+    SetPullRequestDraftStateParams, UpdatePullRequestParams,
 };
 mod token;
 pub use token::GithubAccountIdentifier;
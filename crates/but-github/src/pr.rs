@@ -117,6 +117,21 @@ pub async fn list_comments(
         .context("Failed to list pull request comments")
 }
 
+pub async fn list_review_comments(
+    preferred_account: Option<&crate::GithubAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    pr_number: usize,
+    storage: &but_forge_storage::Controller,
+) -> Result<Vec<crate::client::PullRequestReviewComment>> {
+    let pr_number = pr_number.try_into().context("PR number is too large")?;
+    GitHubClient::from_storage(storage, preferred_account)?
+        .list_pull_request_review_comments(owner, repo, pr_number)
+        .await
+        .map_err(classify_forge_error)
+        .context("Failed to list pull request review comments")
+}
+
 pub async fn list_timeline_events(
     preferred_account: Option<&crate::GithubAccountIdentifier>,
     owner: &str,
@@ -0,0 +1,727 @@
+//! Providers that record LLM interactions to a cassette file and replay them from it.
+//!
+//! A cassette is a JSON file holding request/response pairs. The [`RecordingProvider`] forwards
+//! every request to a live provider and appends what it sent and received, including streamed
+//! tokens and the tool calls made during tool-calling loops. The [`ReplayProvider`] serves these
+//! responses without any network access, which makes AI features deterministic in tests and usable
+//! offline.
+//!
+//! Requests are matched by their content, i.e. operation, model, system message, conversation and
+//! tools, and each recorded interaction is served at most once.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context as _, Result, bail};
+use but_tools::tool::{Tool, Toolset};
+use schemars::{JsonSchema, schema_for};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{ChatMessage, LLMProvider, client::LLMClient};
+
+/// The version of the cassette file format.
+const CASSETTE_VERSION: u32 = 1;
+
+/// The contents of a cassette file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cassette {
+    /// The version of the file format.
+    pub version: u32,
+    /// The model the recorded provider used by default, reported by the replaying provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// All recorded request/response pairs, in the order they happened.
+    #[serde(default)]
+    pub interactions: Vec<Interaction>,
+}
+
+impl Default for Cassette {
+    fn default() -> Self {
+        Cassette {
+            version: CASSETTE_VERSION,
+            model: None,
+            interactions: Vec::new(),
+        }
+    }
+}
+
+impl Cassette {
+    /// Read the cassette at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)
+            .with_context(|| format!("Could not read LLM cassette at {}", path.display()))?;
+        let cassette: Cassette = serde_json::from_slice(&data)
+            .with_context(|| format!("Could not parse LLM cassette at {}", path.display()))?;
+        if cassette.version != CASSETTE_VERSION {
+            bail!(
+                "LLM cassette at {} has unsupported version {}",
+                path.display(),
+                cassette.version
+            );
+        }
+        Ok(cassette)
+    }
+
+    /// Write the cassette to `path`, creating leading directories as needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut data = serde_json::to_vec_pretty(self)?;
+        data.push(b'\n');
+        std::fs::write(path, data)
+            .with_context(|| format!("Could not write LLM cassette to {}", path.display()))
+    }
+}
+
+/// A request and the response it received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Interaction {
+    pub request: Request,
+    pub response: Response,
+}
+
+/// The kind of request that was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Operation {
+    /// A plain text response, streamed or not.
+    Response,
+    /// A response that conforms to a JSON schema.
+    StructuredOutput,
+    /// A conversation in which the model may call tools, streamed or not.
+    ToolCallingLoop,
+}
+
+/// Everything sent to the model, used to find the matching response when replaying.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub operation: Operation,
+    pub model: String,
+    pub system_message: String,
+    pub messages: Vec<ChatMessage>,
+    /// The names of the tools offered to the model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    /// The schema the structured output has to conform to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<serde_json::Value>,
+}
+
+/// Everything the model produced in response to a [`Request`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    /// The final text of the response, if there was one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// The tokens of a streamed response, in the order they were received.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<String>,
+    /// The tools the model called, in the order they were called.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<RecordedToolCall>,
+    /// The conversation after a streamed tool-calling loop finished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<ChatMessage>>,
+    /// The value of a structured output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+}
+
+/// A tool call the model made, along with what the tool returned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedToolCall {
+    pub name: String,
+    pub arguments: String,
+    pub result: serde_json::Value,
+}
+
+/// A provider which forwards all requests to another provider and records them to a cassette.
+///
+/// Interactions are appended to an existing cassette, and the file is written after each of them.
+#[derive(Debug, Clone)]
+pub struct RecordingProvider {
+    inner: LLMProvider,
+    path: PathBuf,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+impl RecordingProvider {
+    /// Record all requests sent to `inner` to the cassette at `path`.
+    pub fn new(inner: LLMProvider, path: PathBuf) -> Result<Self> {
+        let mut cassette = if path.exists() {
+            Cassette::load(&path)?
+        } else {
+            Cassette::default()
+        };
+        cassette.model = Some(inner.model_or_default());
+        Ok(RecordingProvider {
+            inner,
+            path,
+            cassette: Arc::new(Mutex::new(cassette)),
+        })
+    }
+
+    /// The provider requests are forwarded to.
+    pub fn inner(&self) -> &LLMProvider {
+        &self.inner
+    }
+
+    fn record(&self, request: Request, response: Response) -> Result<()> {
+        let mut cassette = self
+            .cassette
+            .lock()
+            .map_err(|_| anyhow::anyhow!("LLM cassette lock was poisoned"))?;
+        cassette
+            .interactions
+            .push(Interaction { request, response });
+        cassette.save(&self.path)
+    }
+}
+
+impl LLMClient for RecordingProvider {
+    fn model(&self) -> Option<String> {
+        self.inner.model()
+    }
+
+    fn tool_calling_loop_stream(
+        &self,
+        system_message: &str,
+        chat_messages: Vec<ChatMessage>,
+        tool_set: &mut impl Toolset,
+        model: &str,
+        on_token: impl Fn(&str) + Send + Sync + 'static,
+    ) -> Result<(String, Vec<ChatMessage>)> {
+        let request = Request::tool_calling_loop(system_message, &chat_messages, &*tool_set, model);
+        let (tokens, on_token) = recording_tokens(on_token);
+        let mut recording_tool_set = RecordingToolset::new(tool_set);
+        let (text, messages) = self.inner.tool_calling_loop_stream(
+            system_message,
+            chat_messages,
+            &mut recording_tool_set,
+            model,
+            on_token,
+        )?;
+        let response = Response {
+            text: Some(text.clone()),
+            tokens: take_tokens(&tokens),
+            tool_calls: recording_tool_set.calls,
+            messages: Some(messages.clone()),
+            value: None,
+        };
+        self.record(request, response)?;
+        Ok((text, messages))
+    }
+
+    fn tool_calling_loop(
+        &self,
+        system_message: &str,
+        chat_messages: Vec<ChatMessage>,
+        tool_set: &mut impl Toolset,
+        model: &str,
+    ) -> Result<String> {
+        let request = Request::tool_calling_loop(system_message, &chat_messages, &*tool_set, model);
+        let mut recording_tool_set = RecordingToolset::new(tool_set);
+        let text = self.inner.tool_calling_loop(
+            system_message,
+            chat_messages,
+            &mut recording_tool_set,
+            model,
+        )?;
+        let response = Response {
+            text: Some(text.clone()),
+            tool_calls: recording_tool_set.calls,
+            ..Default::default()
+        };
+        self.record(request, response)?;
+        Ok(text)
+    }
+
+    fn stream_response(
+        &self,
+        system_message: &str,
+        chat_messages: Vec<ChatMessage>,
+        model: &str,
+        on_token: impl Fn(&str) + Send + Sync + 'static,
+    ) -> Result<Option<String>> {
+        let request = Request::new(Operation::Response, system_message, &chat_messages, model);
+        let (tokens, on_token) = recording_tokens(on_token);
+        let text = self
+            .inner
+            .stream_response(system_message, chat_messages, model, on_token)?;
+        let response = Response {
+            text: text.clone(),
+            tokens: take_tokens(&tokens),
+            ..Default::default()
+        };
+        self.record(request, response)?;
+        Ok(text)
+    }
+
+    fn response(
+        &self,
+        system_message: &str,
+        chat_messages: Vec<ChatMessage>,
+        model: &str,
+    ) -> Result<Option<String>> {
+        let request = Request::new(Operation::Response, system_message, &chat_messages, model);
+        let text = self.inner.response(system_message, chat_messages, model)?;
+        let response = Response {
+            text: text.clone(),
+            ..Default::default()
+        };
+        self.record(request, response)?;
+        Ok(text)
+    }
+
+    fn structured_output<
+        T: serde::Serialize + DeserializeOwned + JsonSchema + std::marker::Send + 'static,
+    >(
+        &self,
+        system_message: &str,
+        chat_messages: Vec<ChatMessage>,
+        model: &str,
+    ) -> Result<Option<T>> {
+        let request = Request::structured_output::<T>(system_message, &chat_messages, model)?;
+        let value = self
+            .inner
+            .structured_output::<T>(system_message, chat_messages, model)?;
+        let response = Response {
+            value: value.as_ref().map(serde_json::to_value).transpose()?,
+            ..Default::default()
+        };
+        self.record(request, response)?;
+        Ok(value)
+    }
+}
+
+/// A provider which answers requests with the responses recorded in a cassette.
+///
+/// Tool calls recorded during tool-calling loops are performed again on the given toolset so their
+/// effects are reproduced, but the recorded conversation is returned.
+#[derive(Debug, Clone)]
+pub struct ReplayProvider {
+    source: String,
+    cassette: Arc<Cassette>,
+    /// Whether the interaction at the same index was already replayed.
+    replayed: Arc<Mutex<Vec<bool>>>,
+}
+
+impl ReplayProvider {
+    /// Replay the cassette stored at `path`.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let cassette = Cassette::load(path)?;
+        Ok(Self::with_source(cassette, path.display().to_string()))
+    }
+
+    /// Replay `cassette`, which is already in memory.
+    pub fn new(cassette: Cassette) -> Self {
+        Self::with_source(cassette, "the LLM cassette".into())
+    }
+
+    fn with_source(cassette: Cassette, source: String) -> Self {
+        ReplayProvider {
+            source,
+            replayed: Arc::new(Mutex::new(vec![false; cassette.interactions.len()])),
+            cassette: Arc::new(cassette),
+        }
+    }
+
+    /// Return the first response recorded for `request` that wasn't replayed yet.
+    fn replay(&self, request: &Request) -> Result<&Response> {
+        let mut replayed = self
+            .replayed
+            .lock()
+            .map_err(|_| anyhow::anyhow!("LLM cassette lock was poisoned"))?;
+        let index = self
+            .cassette
+            .interactions
+            .iter()
+            .zip(replayed.iter())
+            .position(|(interaction, replayed)| !replayed && interaction.request == *request)
+            .with_context(|| {
+                format!(
+                    "No interaction recorded in {} matches the {:?} request for model '{}' - record it again",
+                    self.source, request.operation, request.model
+                )
+            })?;
+        replayed[index] = true;
+        Ok(&self.cassette.interactions[index].response)
+    }
+}
+
+impl LLMClient for ReplayProvider {
+    fn model(&self) -> Option<String> {
+        self.cassette.model.clone()
+    }
+
+    fn tool_calling_loop_stream(
+        &self,
+        system_message: &str,
+        chat_messages: Vec<ChatMessage>,
+        tool_set: &mut impl Toolset,
+        model: &str,
+        on_token: impl Fn(&str) + Send + Sync + 'static,
+    ) -> Result<(String, Vec<ChatMessage>)> {
+        let request = Request::tool_calling_loop(system_message, &chat_messages, &*tool_set, model);
+        let response = self.replay(&request)?;
+        call_tools(response, tool_set);
+        replay_tokens(response, &on_token);
+        let messages = response.messages.clone().unwrap_or(chat_messages);
+        Ok((response.text.clone().unwrap_or_default(), messages))
+    }
+
+    fn tool_calling_loop(
+        &self,
+        system_message: &str,
+        chat_messages: Vec<ChatMessage>,
+        tool_set: &mut impl Toolset,
+        model: &str,
+    ) -> Result<String> {
+        let request = Request::tool_calling_loop(system_message, &chat_messages, &*tool_set, model);
+        let response = self.replay(&request)?;
+        call_tools(response, tool_set);
+        Ok(response.text.clone().unwrap_or_default())
+    }
+
+    fn stream_response(
+        &self,
+        system_message: &str,
+        chat_messages: Vec<ChatMessage>,
+        model: &str,
+        on_token: impl Fn(&str) + Send + Sync + 'static,
+    ) -> Result<Option<String>> {
+        let request = Request::new(Operation::Response, system_message, &chat_messages, model);
+        let response = self.replay(&request)?;
+        replay_tokens(response, &on_token);
+        Ok(response.text.clone())
+    }
+
+    fn response(
+        &self,
+        system_message: &str,
+        chat_messages: Vec<ChatMessage>,
+        model: &str,
+    ) -> Result<Option<String>> {
+        let request = Request::new(Operation::Response, system_message, &chat_messages, model);
+        Ok(self.replay(&request)?.text.clone())
+    }
+
+    fn structured_output<
+        T: serde::Serialize + DeserializeOwned + JsonSchema + std::marker::Send + 'static,
+    >(
+        &self,
+        system_message: &str,
+        chat_messages: Vec<ChatMessage>,
+        model: &str,
+    ) -> Result<Option<T>> {
+        let request = Request::structured_output::<T>(system_message, &chat_messages, model)?;
+        self.replay(&request)?
+            .value
+            .clone()
+            .map(serde_json::from_value)
+            .transpose()
+            .context("The recorded structured output doesn't match the requested type")
+    }
+}
+
+impl Request {
+    fn new(
+        operation: Operation,
+        system_message: &str,
+        chat_messages: &[ChatMessage],
+        model: &str,
+    ) -> Self {
+        Request {
+            operation,
+            model: model.to_owned(),
+            system_message: system_message.to_owned(),
+            messages: chat_messages.to_vec(),
+            tools: Vec::new(),
+            schema: None,
+        }
+    }
+
+    fn tool_calling_loop(
+        system_message: &str,
+        chat_messages: &[ChatMessage],
+        tool_set: &impl Toolset,
+        model: &str,
+    ) -> Self {
+        let mut tools: Vec<_> = tool_set.list().iter().map(|tool| tool.name()).collect();
+        tools.sort();
+        Request {
+            tools,
+            ..Self::new(
+                Operation::ToolCallingLoop,
+                system_message,
+                chat_messages,
+                model,
+            )
+        }
+    }
+
+    fn structured_output<T: JsonSchema>(
+        system_message: &str,
+        chat_messages: &[ChatMessage],
+        model: &str,
+    ) -> Result<Self> {
+        Ok(Request {
+            schema: Some(serde_json::to_value(schema_for!(T))?),
+            ..Self::new(
+                Operation::StructuredOutput,
+                system_message,
+                chat_messages,
+                model,
+            )
+        })
+    }
+}
+
+/// Perform the tool calls recorded in `response` on `tool_set`.
+fn call_tools(response: &Response, tool_set: &mut impl Toolset) {
+    for call in &response.tool_calls {
+        let result = tool_set.call_tool(&call.name, &call.arguments);
+        if result != call.result {
+            tracing::warn!(
+                tool = %call.name,
+                "Replayed tool call returned a different result than when it was recorded"
+            );
+        }
+    }
+}
+
+fn replay_tokens(response: &Response, on_token: &impl Fn(&str)) {
+    if response.tokens.is_empty() {
+        if let Some(text) = response.text.as_deref() {
+            on_token(text);
+        }
+    } else {
+        for token in &response.tokens {
+            on_token(token);
+        }
+    }
+}
+
+type Tokens = Arc<Mutex<Vec<String>>>;
+
+/// Return a callback which records all tokens before passing them on to `on_token`.
+fn recording_tokens(
+    on_token: impl Fn(&str) + Send + Sync + 'static,
+) -> (Tokens, impl Fn(&str) + Send + Sync + 'static) {
+    let tokens = Tokens::default();
+    let recorded = Arc::clone(&tokens);
+    (tokens, move |token: &str| {
+        if let Ok(mut recorded) = recorded.lock() {
+            recorded.push(token.to_owned());
+        }
+        on_token(token)
+    })
+}
+
+fn take_tokens(tokens: &Tokens) -> Vec<String> {
+    tokens
+        .lock()
+        .map(|mut tokens| std::mem::take(&mut *tokens))
+        .unwrap_or_default()
+}
+
+/// A toolset which records all calls made to the toolset it wraps.
+struct RecordingToolset<'a, T> {
+    inner: &'a mut T,
+    calls: Vec<RecordedToolCall>,
+}
+
+impl<'a, T: Toolset> RecordingToolset<'a, T> {
+    fn new(inner: &'a mut T) -> Self {
+        RecordingToolset {
+            inner,
+            calls: Vec::new(),
+        }
+    }
+}
+
+impl<T: Toolset> Toolset for RecordingToolset<'_, T> {
+    fn register_tool<U: Tool>(&mut self, tool: U) {
+        self.inner.register_tool(tool)
+    }
+
+    fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
+        self.inner.get(name)
+    }
+
+    fn list(&self) -> Vec<Arc<dyn Tool>> {
+        self.inner.list()
+    }
+
+    fn call_tool(&mut self, name: &str, parameters: &str) -> serde_json::Value {
+        let result = self.inner.call_tool(name, parameters);
+        self.calls.push(RecordedToolCall {
+            name: name.to_owned(),
+            arguments: parameters.to_owned(),
+            result: result.clone(),
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A toolset without tools that remembers which calls were made.
+    #[derive(Default)]
+    struct CallLog(Vec<(String, String)>);
+
+    impl Toolset for CallLog {
+        fn register_tool<T: Tool>(&mut self, _tool: T) {}
+
+        fn get(&self, _name: &str) -> Option<Arc<dyn Tool>> {
+            None
+        }
+
+        fn list(&self) -> Vec<Arc<dyn Tool>> {
+            Vec::new()
+        }
+
+        fn call_tool(&mut self, name: &str, parameters: &str) -> serde_json::Value {
+            self.0.push((name.to_owned(), parameters.to_owned()));
+            serde_json::json!({ "ok": true })
+        }
+    }
+
+    fn cassette() -> Cassette {
+        Cassette {
+            model: Some("recorded-model".into()),
+            interactions: vec![
+                Interaction {
+                    request: Request::new(
+                        Operation::Response,
+                        "system",
+                        &[ChatMessage::from("Write a commit message")],
+                        "recorded-model",
+                    ),
+                    response: Response {
+                        text: Some("Add feature".into()),
+                        tokens: vec!["Add".into(), " feature".into()],
+                        ..Default::default()
+                    },
+                },
+                Interaction {
+                    request: Request::tool_calling_loop(
+                        "system",
+                        &[ChatMessage::from("Commit everything")],
+                        &CallLog::default(),
+                        "recorded-model",
+                    ),
+                    response: Response {
+                        text: Some("Done".into()),
+                        tool_calls: vec![RecordedToolCall {
+                            name: "commit".into(),
+                            arguments: r#"{"message":"Add feature"}"#.into(),
+                            result: serde_json::json!({ "ok": true }),
+                        }],
+                        ..Default::default()
+                    },
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn replay_streams_recorded_tokens() {
+        let provider = ReplayProvider::new(cassette());
+        assert_eq!(provider.model().as_deref(), Some("recorded-model"));
+
+        let tokens = Tokens::default();
+        let text = provider
+            .stream_response(
+                "system",
+                vec!["Write a commit message".into()],
+                "recorded-model",
+                {
+                    let tokens = Arc::clone(&tokens);
+                    move |token: &str| tokens.lock().unwrap().push(token.to_owned())
+                },
+            )
+            .unwrap();
+
+        assert_eq!(text.as_deref(), Some("Add feature"));
+        assert_eq!(*tokens.lock().unwrap(), ["Add", " feature"]);
+    }
+
+    #[test]
+    fn replay_performs_recorded_tool_calls() {
+        let provider = ReplayProvider::new(cassette());
+        let mut tool_set = CallLog::default();
+
+        let text = provider
+            .tool_calling_loop(
+                "system",
+                vec!["Commit everything".into()],
+                &mut tool_set,
+                "recorded-model",
+            )
+            .unwrap();
+
+        assert_eq!(text, "Done");
+        assert_eq!(
+            tool_set.0,
+            [(
+                "commit".to_owned(),
+                r#"{"message":"Add feature"}"#.to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn each_interaction_is_replayed_once() {
+        let provider = ReplayProvider::new(cassette());
+        let request = || {
+            provider.response(
+                "system",
+                vec!["Write a commit message".into()],
+                "recorded-model",
+            )
+        };
+
+        assert_eq!(request().unwrap().as_deref(), Some("Add feature"));
+        let err = request().unwrap_err();
+        assert!(
+            err.to_string().starts_with("No interaction recorded"),
+            "the only matching interaction was used up: {err}"
+        );
+    }
+
+    #[test]
+    fn unrecorded_requests_fail() {
+        let provider = ReplayProvider::new(cassette());
+        assert!(
+            provider
+                .response("system", vec!["Something else".into()], "recorded-model")
+                .is_err(),
+            "requests are matched by their content"
+        );
+    }
+
+    #[test]
+    fn cassettes_roundtrip_through_files() {
+        let dir = std::env::temp_dir().join(format!("but-llm-cassette-{}", std::process::id()));
+        let path = dir.join("nested").join("cassette.json");
+        let expected = cassette();
+
+        expected.save(&path).unwrap();
+        let actual = Cassette::load(&path);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(actual.unwrap(), expected);
+    }
+}
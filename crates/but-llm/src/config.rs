@@ -15,6 +15,8 @@ pub const AI_LMSTUDIO_ENDPOINT_KEY: &str = "gitbutler.aiLMStudioEndpoint";
 pub const AI_LMSTUDIO_MODEL_NAME_KEY: &str = "gitbutler.aiLMStudioModelName";
 pub const AI_OPENROUTER_MODEL_NAME_KEY: &str = "gitbutler.aiOpenRouterModelName";
 pub const AI_OPENROUTER_ENDPOINT_KEY: &str = "gitbutler.aiOpenRouterEndpoint";
+pub const AI_CASSETTE_PATH_KEY: &str = "gitbutler.aiCassettePath";
+pub const AI_RECORD_PROVIDER_KEY: &str = "gitbutler.aiRecordProvider";
 
 pub const AI_OPENAI_SECRET_HANDLE: &str = "aiOpenAIKey";
 pub const AI_ANTHROPIC_SECRET_HANDLE: &str = "aiAnthropicKey";
@@ -28,6 +30,7 @@ pub const DEFAULT_OLLAMA_MODEL: &str = "llama3";
 pub const DEFAULT_LMSTUDIO_ENDPOINT: &str = "http://127.0.0.1:1234/v1";
 pub const DEFAULT_LMSTUDIO_MODEL: &str = "default";
 pub const DEFAULT_OPENROUTER_MODEL: &str = "openai/gpt-4.1-mini";
+pub const DEFAULT_REPLAY_MODEL: &str = "replay";
 
 const AI_CONFIG_KEYS: &[&str] = &[
     AI_MODEL_PROVIDER_KEY,
@@ -42,6 +45,8 @@ const AI_CONFIG_KEYS: &[&str] = &[
     AI_LMSTUDIO_MODEL_NAME_KEY,
     AI_OPENROUTER_MODEL_NAME_KEY,
     AI_OPENROUTER_ENDPOINT_KEY,
+    AI_CASSETTE_PATH_KEY,
+    AI_RECORD_PROVIDER_KEY,
 ];
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -69,6 +74,15 @@ pub struct LmStudioConfiguration {
     pub model: String,
 }
 
+/// Settings of the `record` and `replay` providers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CassetteConfiguration {
+    /// The cassette file to record to or replay from, relative to the current directory.
+    pub path: Option<String>,
+    /// The provider whose interactions are recorded.
+    pub record_provider: Option<LLMProviderKind>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AiConfiguration {
     pub provider: LLMProviderKind,
@@ -76,6 +90,7 @@ pub struct AiConfiguration {
     pub anthropic: AnthropicConfiguration,
     pub ollama: OllamaConfiguration,
     pub lmstudio: LmStudioConfiguration,
+    pub cassette: CassetteConfiguration,
 }
 
 impl Default for AiConfiguration {
@@ -99,6 +114,7 @@ impl Default for AiConfiguration {
                 endpoint: DEFAULT_LMSTUDIO_ENDPOINT.into(),
                 model: DEFAULT_LMSTUDIO_MODEL.into(),
             },
+            cassette: CassetteConfiguration::default(),
         }
     }
 }
@@ -122,6 +138,14 @@ impl AiConfiguration {
                 .with_context(|| format!("Unsupported Anthropic credential source '{value}'"))?,
             None => defaults.anthropic.key_option,
         };
+        let record_provider = values
+            .record_provider
+            .as_deref()
+            .map(|value| {
+                LLMProviderKind::from_git_config_value(value)
+                    .with_context(|| format!("Unsupported AI provider to record '{value}'"))
+            })
+            .transpose()?;
 
         let configuration = Self {
             provider,
@@ -144,6 +168,10 @@ impl AiConfiguration {
                     .unwrap_or(defaults.lmstudio.endpoint),
                 model: values.lmstudio_model.unwrap_or(defaults.lmstudio.model),
             },
+            cassette: CassetteConfiguration {
+                path: values.cassette_path,
+                record_provider,
+            },
         };
         Ok(configuration)
     }
@@ -164,6 +192,9 @@ impl AiConfiguration {
         {
             validate_url(endpoint, "OpenAI custom endpoint")?;
         }
+        if let Some(provider) = self.cassette.record_provider {
+            validate_recordable(provider)?;
+        }
         Ok(())
     }
 
@@ -189,10 +220,38 @@ impl AiConfiguration {
                 validate_url(&self.lmstudio.endpoint, "LM Studio endpoint")?;
             }
             LLMProviderKind::OpenRouter => {}
+            LLMProviderKind::Record => {
+                required(
+                    self.cassette.path.as_deref().unwrap_or_default(),
+                    "LLM cassette path",
+                )?;
+                let provider = self
+                    .cassette
+                    .record_provider
+                    .context("The AI provider to record is required")?;
+                validate_recordable(provider)?;
+                self.recorded().validate_active()?;
+            }
+            LLMProviderKind::Replay => {
+                required(
+                    self.cassette.path.as_deref().unwrap_or_default(),
+                    "LLM cassette path",
+                )?;
+            }
         }
         Ok(())
     }
 
+    /// Return this configuration with the provider to record made the active one.
+    ///
+    /// The provider is left unchanged if none is configured.
+    pub fn recorded(&self) -> Self {
+        Self {
+            provider: self.cassette.record_provider.unwrap_or(self.provider),
+            ..self.clone()
+        }
+    }
+
     /// Return whether the active provider has valid settings and its required credentials.
     pub fn is_configured(
         &self,
@@ -214,6 +273,12 @@ impl AiConfiguration {
             },
             LLMProviderKind::Ollama | LLMProviderKind::LMStudio => true,
             LLMProviderKind::OpenRouter => false,
+            LLMProviderKind::Record => self.recorded().is_configured(
+                has_openai_key,
+                has_anthropic_key,
+                has_gitbutler_token,
+            ),
+            LLMProviderKind::Replay => true,
         }
     }
 
@@ -257,6 +322,14 @@ impl AiConfiguration {
             AI_LMSTUDIO_MODEL_NAME_KEY,
             self.lmstudio.model.trim(),
         )?;
+        set_optional(config, AI_CASSETTE_PATH_KEY, self.cassette.path.as_deref())?;
+        set_optional(
+            config,
+            AI_RECORD_PROVIDER_KEY,
+            self.cassette
+                .record_provider
+                .map(LLMProviderKind::as_git_config_value),
+        )?;
         Ok(())
     }
 }
@@ -273,6 +346,8 @@ pub struct AiConfigurationSnapshot {
     pub ollama_model: Option<String>,
     pub lmstudio_endpoint: Option<String>,
     pub lmstudio_model: Option<String>,
+    pub cassette_path: Option<String>,
+    pub record_provider: Option<String>,
 }
 
 impl AiConfigurationSnapshot {
@@ -288,6 +363,8 @@ impl AiConfigurationSnapshot {
             ollama_model: config_value(config, AI_OLLAMA_MODEL_NAME_KEY),
             lmstudio_endpoint: config_value(config, AI_LMSTUDIO_ENDPOINT_KEY),
             lmstudio_model: config_value(config, AI_LMSTUDIO_MODEL_NAME_KEY),
+            cassette_path: config_value(config, AI_CASSETTE_PATH_KEY),
+            record_provider: config_value(config, AI_RECORD_PROVIDER_KEY),
         }
     }
 }
@@ -371,6 +448,30 @@ pub fn clear_ai_configuration(config: &mut gix::config::File) -> Result<()> {
     Ok(())
 }
 
+/// Configure recording all interactions with `provider` to the cassette at `path`.
+pub fn apply_record_configuration(
+    config: &mut gix::config::File,
+    path: &str,
+    provider: LLMProviderKind,
+) -> Result<()> {
+    required(path, "LLM cassette path")?;
+    validate_recordable(provider)?;
+    set_provider(config, LLMProviderKind::Record)?;
+    set_config_value(config, AI_CASSETTE_PATH_KEY, path.trim())?;
+    set_config_value(
+        config,
+        AI_RECORD_PROVIDER_KEY,
+        provider.as_git_config_value(),
+    )
+}
+
+/// Configure answering all requests from the cassette at `path`.
+pub fn apply_replay_configuration(config: &mut gix::config::File, path: &str) -> Result<()> {
+    required(path, "LLM cassette path")?;
+    set_provider(config, LLMProviderKind::Replay)?;
+    set_config_value(config, AI_CASSETTE_PATH_KEY, path.trim())
+}
+
 fn set_provider(config: &mut gix::config::File, provider: LLMProviderKind) -> Result<()> {
     set_config_value(
         config,
@@ -411,6 +512,16 @@ fn validate_url(value: &str, field: &str) -> Result<()> {
     Ok(())
 }
 
+fn validate_recordable(provider: LLMProviderKind) -> Result<()> {
+    if matches!(provider, LLMProviderKind::Record | LLMProviderKind::Replay) {
+        bail!(
+            "Cannot record the '{}' AI provider",
+            provider.as_git_config_value()
+        )
+    }
+    Ok(())
+}
+
 fn validate_ollama_endpoint(value: &str) -> Result<()> {
     let value = required(value, "Ollama endpoint")?;
     if value.contains("://") {
@@ -527,4 +638,41 @@ mod tests {
             "an invalid local provider is not configured"
         );
     }
+
+    #[test]
+    fn cassette_providers_roundtrip_through_git_config() {
+        let mut config =
+            gix::config::File::new(gix::config::file::Metadata::from(gix::config::Source::User));
+
+        apply_record_configuration(&mut config, "cassette.json", LLMProviderKind::Anthropic)
+            .unwrap();
+        let configuration = AiConfiguration::from_git_config(&config).unwrap();
+        assert_eq!(configuration.provider, LLMProviderKind::Record);
+        assert_eq!(
+            configuration.cassette,
+            CassetteConfiguration {
+                path: Some("cassette.json".into()),
+                record_provider: Some(LLMProviderKind::Anthropic),
+            }
+        );
+        assert!(
+            !configuration.is_configured(false, false, false),
+            "recording needs the credentials of the recorded provider"
+        );
+        assert!(configuration.is_configured(false, true, true));
+
+        apply_replay_configuration(&mut config, "cassette.json").unwrap();
+        let configuration = AiConfiguration::from_git_config(&config).unwrap();
+        assert_eq!(configuration.provider, LLMProviderKind::Replay);
+        assert!(
+            configuration.is_configured(false, false, false),
+            "replaying needs no credentials"
+        );
+
+        assert!(
+            apply_record_configuration(&mut config, "cassette.json", LLMProviderKind::Replay)
+                .is_err(),
+            "cassette providers can't be recorded"
+        );
+    }
 }
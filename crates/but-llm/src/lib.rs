@@ -1,4 +1,5 @@
 mod anthropic;
+pub mod cassette;
 mod chat;
 mod client;
 mod config;
@@ -9,7 +10,7 @@ mod openai;
 mod openai_utils;
 mod openrouter;
 
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 pub use chat::{ChatMessage, StreamToolCallResult, ToolCall, ToolCallContent, ToolResponseContent};
 pub use config::*;
@@ -27,6 +28,10 @@ pub enum LLMProviderKind {
     Ollama,
     LMStudio,
     OpenRouter,
+    /// Forward requests to another provider and record them to a cassette file.
+    Record,
+    /// Answer requests from a cassette file, without network access.
+    Replay,
 }
 
 impl LLMProviderKind {
@@ -37,6 +42,8 @@ impl LLMProviderKind {
             "ollama" => Some(LLMProviderKind::Ollama),
             "lmstudio" => Some(LLMProviderKind::LMStudio),
             "openrouter" => Some(LLMProviderKind::OpenRouter),
+            "record" => Some(LLMProviderKind::Record),
+            "replay" => Some(LLMProviderKind::Replay),
             _ => None,
         }
     }
@@ -48,6 +55,8 @@ impl LLMProviderKind {
             LLMProviderKind::Ollama => "ollama",
             LLMProviderKind::LMStudio => "lmstudio",
             LLMProviderKind::OpenRouter => "openrouter",
+            LLMProviderKind::Record => "record",
+            LLMProviderKind::Replay => "replay",
         }
     }
 
@@ -58,6 +67,8 @@ impl LLMProviderKind {
             LLMProviderKind::Ollama => "Ollama",
             LLMProviderKind::LMStudio => "LM Studio",
             LLMProviderKind::OpenRouter => "OpenRouter",
+            LLMProviderKind::Record => "Recording",
+            LLMProviderKind::Replay => "Replay",
         }
     }
 }
@@ -75,6 +86,13 @@ pub enum LLMProviderConfig {
     Ollama(Option<ollama::OllamaConfig>),
     LMStudio(Option<lmstudio::LMStudioConfig>),
     OpenRouter(Option<openrouter::OpenRouterConfig>),
+    /// Record all interactions with `provider` to the cassette at `path`.
+    Record {
+        path: PathBuf,
+        provider: Box<LLMProviderConfig>,
+    },
+    /// Replay the interactions recorded in the cassette at the given path.
+    Replay(PathBuf),
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +102,8 @@ pub enum LLMClientType {
     Ollama(Arc<ollama::OllamaProvider>),
     LMStudio(Arc<lmstudio::LMStudioProvider>),
     OpenRouter(Arc<openrouter::OpenRouterProvider>),
+    Record(Arc<cassette::RecordingProvider>),
+    Replay(Arc<cassette::ReplayProvider>),
 }
 
 #[derive(Debug, Clone)]
@@ -131,6 +151,13 @@ impl LLMProvider {
                 openrouter::OpenRouterProvider::with(config, None)
                     .map(|p| LLMClientType::OpenRouter(Arc::new(p)))?
             }
+            LLMProviderConfig::Record { path, provider } => {
+                let inner = Self::new(*provider)?;
+                LLMClientType::Record(Arc::new(recording_provider(inner, path)?))
+            }
+            LLMProviderConfig::Replay(path) => {
+                LLMClientType::Replay(Arc::new(replay_provider(&path)?))
+            }
         };
         Some(Self { client })
     }
@@ -162,6 +189,15 @@ impl LLMProvider {
     pub fn from_git_config(config: &gix::config::File) -> Option<Self> {
         let configuration = AiConfiguration::from_git_config(config).ok()?;
         configuration.validate_active().ok()?;
+        Self::from_configuration(configuration, config)
+    }
+
+    /// Create the provider that is active in `configuration`, with `config` providing the settings
+    /// that aren't part of it.
+    fn from_configuration(
+        configuration: AiConfiguration,
+        config: &gix::config::File,
+    ) -> Option<Self> {
         match configuration.provider {
             LLMProviderKind::OpenAi => {
                 let credentials = match configuration.openai.key_option {
@@ -229,6 +265,23 @@ impl LLMProvider {
                     client: LLMClientType::OpenRouter(Arc::new(client)),
                 })
             }
+            LLMProviderKind::Record => {
+                let recorded = configuration.recorded();
+                if recorded.provider == LLMProviderKind::Record {
+                    return None;
+                }
+                let inner = Self::from_configuration(recorded, config)?;
+                let client = recording_provider(inner, configuration.cassette.path?.into())?;
+                Some(Self {
+                    client: LLMClientType::Record(Arc::new(client)),
+                })
+            }
+            LLMProviderKind::Replay => {
+                let client = replay_provider(configuration.cassette.path?.as_ref())?;
+                Some(Self {
+                    client: LLMClientType::Replay(Arc::new(client)),
+                })
+            }
         }
     }
 
@@ -253,6 +306,8 @@ impl LLMProvider {
             LLMClientType::Ollama(client) => client.model(),
             LLMClientType::LMStudio(client) => client.model(),
             LLMClientType::OpenRouter(client) => client.model(),
+            LLMClientType::Record(client) => client.model(),
+            LLMClientType::Replay(client) => client.model(),
         }
     }
 
@@ -272,6 +327,8 @@ impl LLMProvider {
             LLMClientType::Ollama(_) => DEFAULT_OLLAMA_MODEL,
             LLMClientType::LMStudio(_) => DEFAULT_LMSTUDIO_MODEL,
             LLMClientType::OpenRouter(_) => DEFAULT_OPENROUTER_MODEL,
+            LLMClientType::Record(client) => return client.inner().model_or_default(),
+            LLMClientType::Replay(_) => DEFAULT_REPLAY_MODEL,
         }
         .to_string()
     }
@@ -396,6 +453,20 @@ impl LLMProvider {
                 model,
                 on_token,
             ),
+            LLMClientType::Record(client) => client.tool_calling_loop_stream(
+                system_message,
+                chat_messages,
+                tool_set,
+                model,
+                on_token,
+            ),
+            LLMClientType::Replay(client) => client.tool_calling_loop_stream(
+                system_message,
+                chat_messages,
+                tool_set,
+                model,
+                on_token,
+            ),
         }
     }
 
@@ -446,6 +517,12 @@ impl LLMProvider {
             LLMClientType::OpenRouter(client) => {
                 client.tool_calling_loop(system_message, chat_messages, tool_set, model)
             }
+            LLMClientType::Record(client) => {
+                client.tool_calling_loop(system_message, chat_messages, tool_set, model)
+            }
+            LLMClientType::Replay(client) => {
+                client.tool_calling_loop(system_message, chat_messages, tool_set, model)
+            }
         }
     }
 
@@ -490,6 +567,12 @@ impl LLMProvider {
             LLMClientType::OpenRouter(client) => {
                 client.stream_response(system_message, chat_messages, model, on_token)
             }
+            LLMClientType::Record(client) => {
+                client.stream_response(system_message, chat_messages, model, on_token)
+            }
+            LLMClientType::Replay(client) => {
+                client.stream_response(system_message, chat_messages, model, on_token)
+            }
         }
     }
 
@@ -542,6 +625,12 @@ impl LLMProvider {
             LLMClientType::OpenRouter(client) => {
                 client.structured_output::<T>(system_message, chat_messages, model)
             }
+            LLMClientType::Record(client) => {
+                client.structured_output::<T>(system_message, chat_messages, model)
+            }
+            LLMClientType::Replay(client) => {
+                client.structured_output::<T>(system_message, chat_messages, model)
+            }
         }
     }
 
@@ -580,6 +669,22 @@ impl LLMProvider {
             LLMClientType::OpenRouter(client) => {
                 client.response(system_message, chat_messages, model)
             }
+            LLMClientType::Record(client) => client.response(system_message, chat_messages, model),
+            LLMClientType::Replay(client) => client.response(system_message, chat_messages, model),
         }
     }
 }
+
+fn recording_provider(inner: LLMProvider, path: PathBuf) -> Option<cassette::RecordingProvider> {
+    cassette::RecordingProvider::new(inner, path)
+        .inspect_err(|err| {
+            tracing::error!("Failed to set up recording of LLM interactions: {err:#}")
+        })
+        .ok()
+}
+
+fn replay_provider(path: &std::path::Path) -> Option<cassette::ReplayProvider> {
+    cassette::ReplayProvider::from_path(path)
+        .inspect_err(|err| tracing::error!("Failed to load LLM cassette: {err:#}"))
+        .ok()
+}
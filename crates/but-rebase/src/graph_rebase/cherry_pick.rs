@@ -79,6 +79,10 @@ pub enum PickMode {
 /// Except in the case where X is conflicted. In that case we then make use of
 /// X's "base" sub-tree as the base.
 ///
+/// Conflicts that were resolved before are resolved the same way again, using the
+/// resolutions recorded in [`crate::rerere`]. The commit is only conflicted if some
+/// conflicts remain.
+///
 /// Special case: when a synthetic merge template has no original parents, an
 /// empty tree, and exactly two new parents whose trees conflict with each
 /// other, we materialize that conflict as a GitButler conflicted merge commit
@@ -184,6 +188,7 @@ pub fn cherry_pick(
             let empty_tree = gix::ObjectId::empty_tree(gix::hash::Kind::Sha1);
             let base_t = base_t.object_id().unwrap_or(empty_tree);
             let onto_t = onto_t.object_id().unwrap_or(empty_tree);
+            let target_t = target_t.detach();
 
             let mut outcome = repo.merge_trees(
                 base_t,
@@ -192,9 +197,12 @@ pub fn cherry_pick(
                 repo.default_merge_labels(),
                 repo.merge_options_force_ours()?,
             )?;
+            let conflict_kind = gix::merge::tree::TreatAsUnresolved::forced_resolution();
+            if outcome.has_unresolved_conflicts(conflict_kind) {
+                replay_recorded_resolutions(repo, base_t, onto_t, target_t, &mut outcome);
+            }
             let tree_id = outcome.tree.write()?;
 
-            let conflict_kind = gix::merge::tree::TreatAsUnresolved::forced_resolution();
             if outcome.has_unresolved_conflicts(conflict_kind) {
                 let conflicted_commit = commit_from_conflicted_tree(
                     ontos,
@@ -204,7 +212,7 @@ pub fn cherry_pick(
                     conflict_kind,
                     base_t,
                     onto_t,
-                    target_t.detach(),
+                    target_t,
                     sign_commit,
                 )?;
                 Ok(CherryPickOutcome::ConflictedCommit(
@@ -219,6 +227,33 @@ pub fn cherry_pick(
     }
 }
 
+/// Resolve the conflicts in `outcome`, the merge of `onto_t` and `target_t` with `base_t`,
+/// like they were resolved before. Only the merged tree changes, so a commit that stays
+/// conflicted still has the original sides.
+///
+/// Failing to do so isn't fatal, the commit then is conflicted as if nothing was recorded.
+fn replay_recorded_resolutions(
+    repo: &gix::Repository,
+    base_t: gix::ObjectId,
+    onto_t: gix::ObjectId,
+    target_t: gix::ObjectId,
+    outcome: &mut gix::merge::tree::Outcome<'_>,
+) {
+    if !crate::rerere::is_enabled(repo) {
+        return;
+    }
+    let replayed = crate::rerere::Store::from_repo(repo).and_then(|store| {
+        // Avoid merging once more just to find the conflicted files if nothing can match them.
+        if store.is_empty()? {
+            return Ok(0);
+        }
+        crate::rerere::replay(repo, &store, base_t, onto_t, target_t, outcome)
+    });
+    if let Err(err) = replayed {
+        tracing::warn!("Could not replay recorded conflict resolutions: {err:#}");
+    }
+}
+
 /// Materialize the narrow synthetic-merge case where building the merged
 /// `onto` tree conflicts before the normal final cherry-pick merge can run.
 ///
@@ -25,6 +25,8 @@ pub mod commit;
 /// Utilities around merging
 pub mod merge;
 
+pub mod rerere;
+
 /// An instruction for [`Rebase::rebase()`].
 #[derive(Debug, Clone)]
 pub enum RebaseStep {
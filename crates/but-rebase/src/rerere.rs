@@ -0,0 +1,433 @@
+//! Record how conflicts were resolved, and resolve them the same way when they show up again,
+//! similar to `git rerere`.
+//!
+//! A conflict is identified by its *pre-image*: the conflicted file as merged with conflict markers,
+//! with the sides of each conflict sorted so it doesn't matter which side a change ends up on.
+//! Its *post-image* is the content the file was resolved to. Resolutions are stored under a hash
+//! over the conflicting sides only, so they are found again even if the lines around the conflicts
+//! or the path of the file changed, as they do when a stack is rebased onto a moving target.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::{Context as _, Result};
+use bstr::{BStr, BString, ByteSlice};
+use but_core::RepositoryExt as _;
+use gix::{objs::tree::EntryKind, prelude::ObjectIdExt as _};
+use serde::{Deserialize, Serialize};
+
+/// The boolean configuration key to turn off recording and replaying conflict resolutions,
+/// which is on by default.
+pub const CONFIG_KEY: &str = "gitbutler.rerere";
+
+/// The side labels used when merging, so that conflict markers are exactly known strings.
+const OURS_LABEL: &str = "gitbutler-rerere-ours";
+const THEIRS_LABEL: &str = "gitbutler-rerere-theirs";
+
+/// Return `true` if conflict resolutions should be recorded and replayed in `repo`.
+pub fn is_enabled(repo: &gix::Repository) -> bool {
+    repo.config_snapshot().boolean(CONFIG_KEY).unwrap_or(true)
+}
+
+/// A conflict resolution as recorded in the [`Store`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    /// The hash over the conflicting sides which identifies the conflict.
+    pub id: String,
+    /// The repository-relative path of the file the resolution was recorded for.
+    pub path: BString,
+    /// When the resolution was recorded, in seconds since the Unix epoch.
+    pub recorded_at: i64,
+    /// The conflicted file with normalized conflict markers.
+    pub preimage: BString,
+    /// The content the conflicted file was resolved to.
+    pub postimage: BString,
+}
+
+impl Resolution {
+    /// Resolve a conflicted file with the normalized `preimage` like this resolution did.
+    ///
+    /// If the lines around the conflicts changed since the resolution was recorded, the
+    /// resolution is merged into `preimage` like any other change. Return `None` if that
+    /// merge conflicts, or if its result still has conflict markers, like those of a conflict
+    /// in `preimage` the resolution doesn't know.
+    pub fn apply_to(&self, repo: &gix::Repository, preimage: &BStr) -> Result<Option<BString>> {
+        if self.preimage == preimage {
+            return Ok(Some(self.postimage.clone()));
+        }
+
+        let file_tree = |content: &[u8]| -> Result<gix::ObjectId> {
+            let blob_id = repo.write_blob(content)?;
+            let mut tree = repo.edit_tree(gix::ObjectId::empty_tree(repo.object_hash()))?;
+            tree.upsert("file", EntryKind::Blob, blob_id)?;
+            Ok(tree.write()?.detach())
+        };
+        let mut outcome = repo.merge_trees(
+            file_tree(&self.preimage)?,
+            file_tree(&self.postimage)?,
+            file_tree(preimage)?,
+            repo.default_merge_labels(),
+            repo.tree_merge_options()?,
+        )?;
+        if outcome.has_unresolved_conflicts(gix::merge::tree::TreatAsUnresolved::git()) {
+            return Ok(None);
+        }
+        let tree = repo.find_tree(outcome.tree.write()?)?;
+        let Some(entry) = tree.find_entry("file") else {
+            return Ok(None);
+        };
+        let resolved = entry.object()?.into_blob().data.as_bstr().to_owned();
+        Ok((!has_conflict_markers(resolved.as_ref())).then_some(resolved))
+    }
+}
+
+/// Metadata stored next to the pre- and post-image of a [`Resolution`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    path: String,
+    recorded_at: i64,
+}
+
+/// The on-disk store of recorded resolutions, with one directory per conflict.
+#[derive(Debug, Clone)]
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    /// Open the store in the GitButler storage directory of `repo`.
+    pub fn from_repo(repo: &gix::Repository) -> Result<Self> {
+        Ok(Store {
+            root: repo.gitbutler_storage_path()?.join("rerere"),
+        })
+    }
+
+    /// Return all recorded resolutions, the most recently recorded first.
+    pub fn list(&self) -> Result<Vec<Resolution>> {
+        let entries = match std::fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut out = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let Some(id) = entry.file_name().to_str().map(ToOwned::to_owned) else {
+                continue;
+            };
+            if let Some(resolution) = self.find(&id)? {
+                out.push(resolution);
+            }
+        }
+        out.sort_by(|a, b| {
+            b.recorded_at
+                .cmp(&a.recorded_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        Ok(out)
+    }
+
+    /// Return the resolution recorded for the conflict identified by `id`, if there is one.
+    pub fn find(&self, id: &str) -> Result<Option<Resolution>> {
+        let Some(dir) = self.dir(id) else {
+            return Ok(None);
+        };
+        let metadata = match std::fs::read_to_string(dir.join("meta.toml")) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let Metadata { path, recorded_at } = toml::from_str(&metadata)
+            .with_context(|| format!("Could not parse the metadata of resolution {id}"))?;
+        Ok(Some(Resolution {
+            id: id.to_owned(),
+            path: path.into(),
+            recorded_at,
+            preimage: std::fs::read(dir.join("preimage"))?.into(),
+            postimage: std::fs::read(dir.join("postimage"))?.into(),
+        }))
+    }
+
+    /// Record `postimage` as the resolution of the conflict with `preimage` identified by `id`,
+    /// replacing a previously recorded one.
+    pub fn insert(&self, id: &str, path: &BStr, preimage: &BStr, postimage: &BStr) -> Result<()> {
+        let dir = self
+            .dir(id)
+            .with_context(|| format!("Invalid resolution id: {id:?}"))?;
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("preimage"), preimage)?;
+        std::fs::write(dir.join("postimage"), postimage)?;
+        // The metadata goes last as a resolution without it isn't found.
+        let metadata = Metadata {
+            path: path.to_str_lossy().into_owned(),
+            recorded_at: gix::date::Time::now_utc().seconds,
+        };
+        std::fs::write(dir.join("meta.toml"), toml::to_string(&metadata)?)?;
+        Ok(())
+    }
+
+    /// Forget the resolution identified by `id`, and return `true` if there was one.
+    pub fn forget(&self, id: &str) -> Result<bool> {
+        let Some(dir) = self.dir(id) else {
+            return Ok(false);
+        };
+        match std::fs::remove_dir_all(dir) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Return `true` if no resolution was recorded yet.
+    pub(crate) fn is_empty(&self) -> Result<bool> {
+        match std::fs::read_dir(&self.root) {
+            Ok(mut entries) => Ok(entries.next().is_none()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(true),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Return the directory of the resolution identified by `id`, or `None` if `id` isn't a hash.
+    fn dir(&self, id: &str) -> Option<PathBuf> {
+        (!id.is_empty() && id.bytes().all(|b| b.is_ascii_hexdigit())).then(|| self.root.join(id))
+    }
+}
+
+/// A file which conflicts when merging two trees.
+#[derive(Debug, Clone)]
+pub struct ConflictedFile {
+    /// The repository-relative path of the file.
+    pub rela_path: BString,
+    /// The kind of the merged entry, to keep when writing its resolution.
+    pub entry_kind: EntryKind,
+    /// The hash over the conflicting sides which identifies the conflict.
+    pub id: String,
+    /// The merged file with normalized conflict markers.
+    pub preimage: BString,
+}
+
+/// Merge `ours` and `theirs` with `base` and return all files with conflicts that a
+/// resolution can be recorded for, i.e. text files that were changed on both sides.
+pub fn conflicted_files(
+    repo: &gix::Repository,
+    base: gix::ObjectId,
+    ours: gix::ObjectId,
+    theirs: gix::ObjectId,
+) -> Result<Vec<ConflictedFile>> {
+    use gix::merge::blob::builtin_driver::text;
+
+    let mut options: gix::merge::plumbing::tree::Options = repo.tree_merge_options()?.into();
+    options.blob_merge.text.conflict = text::Conflict::Keep {
+        style: text::ConflictStyle::Merge,
+        marker_size: 7.try_into().expect("non-zero constant"),
+    };
+    let labels = text::Labels {
+        ancestor: None,
+        current: Some(OURS_LABEL.into()),
+        other: Some(THEIRS_LABEL.into()),
+    };
+    let mut outcome = repo.merge_trees(base, ours, theirs, labels, options.into())?;
+    let merged_tree_id = outcome.tree.write()?.detach();
+
+    let mut index = repo.index_from_tree(&merged_tree_id)?;
+    if !outcome.index_changed_after_applying_conflicts(
+        &mut index,
+        gix::merge::tree::TreatAsUnresolved::git(),
+        gix::merge::tree::apply_index_entries::RemovalMode::Mark,
+    ) {
+        return Ok(Vec::new());
+    }
+    let mut sides_by_path = BTreeMap::<BString, (bool, bool)>::new();
+    for entry in index.entries() {
+        use gix::index::entry::Stage;
+        let sides = sides_by_path
+            .entry(entry.path(&index).to_owned())
+            .or_default();
+        match entry.stage() {
+            Stage::Ours => sides.0 = true,
+            Stage::Theirs => sides.1 = true,
+            Stage::Unconflicted | Stage::Base => {}
+        }
+    }
+
+    let merged_tree = repo.find_tree(merged_tree_id)?;
+    let mut files = Vec::new();
+    for (rela_path, (ours, theirs)) in sides_by_path {
+        if !(ours && theirs) {
+            continue;
+        }
+        let Some(entry) = merged_tree.lookup_entry(rela_path.split(|b| *b == b'/'))? else {
+            continue;
+        };
+        let entry_kind = entry.mode().kind();
+        if !matches!(entry_kind, EntryKind::Blob | EntryKind::BlobExecutable) {
+            continue;
+        }
+        let Some((id, preimage)) = normalize(entry.object()?.data.as_bstr())? else {
+            continue;
+        };
+        files.push(ConflictedFile {
+            rela_path,
+            entry_kind,
+            id,
+            preimage,
+        });
+    }
+    Ok(files)
+}
+
+/// Resolve the conflicts in `merged`, the result of merging `ours` and `theirs` with `base`,
+/// with the resolutions recorded in `store`.
+///
+/// Each file with a recorded resolution is written to the tree of `merged`, and its conflict is
+/// removed, so only conflicts without a recorded resolution remain. `ours` and `theirs` are left
+/// as they are. Return the number of replayed resolutions.
+pub(crate) fn replay(
+    repo: &gix::Repository,
+    store: &Store,
+    base: gix::ObjectId,
+    ours: gix::ObjectId,
+    theirs: gix::ObjectId,
+    merged: &mut gix::merge::tree::Outcome<'_>,
+) -> Result<usize> {
+    let mut num_replayed = 0;
+    for file in conflicted_files(repo, base, ours, theirs)? {
+        let Some(resolution) = store.find(&file.id)? else {
+            continue;
+        };
+        let Some(resolved) = resolution.apply_to(repo, file.preimage.as_ref())? else {
+            continue;
+        };
+        let blob_id = repo.write_blob(&resolved)?;
+        merged
+            .tree
+            .upsert(file.rela_path.as_bstr(), file.entry_kind, blob_id)?;
+        merged.conflicts.retain(|conflict| {
+            conflict.ours.location() != file.rela_path
+                && conflict.theirs.location() != file.rela_path
+        });
+        num_replayed += 1;
+    }
+    Ok(num_replayed)
+}
+
+/// Record how the conflicts of `conflicted_commit` were resolved in `resolved_tree`, for all of
+/// its conflicted files or only for those in `paths`.
+///
+/// Files that still contain conflict markers aren't recorded. Return the number of recorded
+/// resolutions.
+pub fn record(
+    repo: &gix::Repository,
+    conflicted_commit: gix::ObjectId,
+    resolved_tree: gix::ObjectId,
+    paths: Option<&[BString]>,
+) -> Result<usize> {
+    if !is_enabled(repo) {
+        return Ok(0);
+    }
+    let commit = but_core::Commit::from_id(conflicted_commit.attach(repo))?;
+    let Some((base, ours, theirs)) = commit.conflicted_tree_ids()? else {
+        return Ok(0);
+    };
+
+    let store = Store::from_repo(repo)?;
+    let resolved_tree = repo.find_tree(resolved_tree)?;
+    let mut num_recorded = 0;
+    for file in conflicted_files(repo, base.detach(), ours.detach(), theirs.detach())? {
+        if paths.is_some_and(|paths| !paths.contains(&file.rela_path)) {
+            continue;
+        }
+        let Some(entry) = resolved_tree.lookup_entry(file.rela_path.split(|b| *b == b'/'))? else {
+            continue;
+        };
+        if !matches!(
+            entry.mode().kind(),
+            EntryKind::Blob | EntryKind::BlobExecutable
+        ) {
+            continue;
+        }
+        let postimage = entry.object()?.into_blob();
+        if has_conflict_markers(postimage.data.as_bstr()) {
+            continue;
+        }
+        store.insert(
+            &file.id,
+            file.rela_path.as_ref(),
+            file.preimage.as_ref(),
+            postimage.data.as_bstr(),
+        )?;
+        num_recorded += 1;
+    }
+    Ok(num_recorded)
+}
+
+/// Rewrite the conflict markers in `merged` without labels and with the sides of each conflict
+/// sorted, and hash the sides of all conflicts.
+///
+/// Return the hash along with the normalized text, or `None` if there are no well-formed conflicts.
+fn normalize(merged: &BStr) -> Result<Option<(String, BString)>> {
+    let ours_marker = format!("<<<<<<< {OURS_LABEL}");
+    let theirs_marker = format!(">>>>>>> {THEIRS_LABEL}");
+    let mut hasher = gix::hash::hasher(gix::hash::Kind::Sha1);
+    let mut out = BString::default();
+    let mut num_conflicts = 0;
+
+    let mut lines = merged.lines_with_terminator();
+    while let Some(line) = lines.next() {
+        if trim_line_end(line) != ours_marker.as_bytes() {
+            out.extend_from_slice(line);
+            continue;
+        }
+        let mut sides = [BString::default(), BString::default()];
+        let mut in_theirs = false;
+        let mut closed = false;
+        for line in lines.by_ref() {
+            let content = trim_line_end(line);
+            if !in_theirs && content == b"=======" {
+                in_theirs = true;
+            } else if in_theirs && content == theirs_marker.as_bytes() {
+                closed = true;
+                break;
+            } else {
+                sides[usize::from(in_theirs)].extend_from_slice(line);
+            }
+        }
+        if !closed {
+            return Ok(None);
+        }
+
+        sides.sort();
+        out.extend_from_slice(b"<<<<<<<\n");
+        for (idx, side) in sides.iter().enumerate() {
+            hasher.update(side);
+            hasher.update(b"\0");
+            out.extend_from_slice(side);
+            if !side.is_empty() && !side.ends_with(b"\n") {
+                out.push(b'\n');
+            }
+            out.extend_from_slice(if idx == 0 { b"=======\n" } else { b">>>>>>>\n" });
+        }
+        num_conflicts += 1;
+    }
+
+    if num_conflicts == 0 {
+        return Ok(None);
+    }
+    Ok(Some((hasher.try_finalize()?.to_hex().to_string(), out)))
+}
+
+/// Return `true` if `content` has a line that looks like a `<<<<<<<` or `>>>>>>>` conflict marker.
+fn has_conflict_markers(content: &BStr) -> bool {
+    content.lines().any(|line| {
+        let line = trim_line_end(line);
+        [b'<', b'>'].into_iter().any(|marker| {
+            let run = line.iter().take_while(|b| **b == marker).count();
+            run >= 7 && (line.len() == run || line[run] == b' ')
+        })
+    })
+}
+
+fn trim_line_end(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
@@ -0,0 +1,24 @@
+#!/bin/bash
+
+set -eu -o pipefail
+
+git init
+
+printf 'one\ntwo\nthree\nfour\nfive\n' >file && git add . && git commit -m "base" && git branch base
+
+git checkout -b target
+printf 'one\ntwo on target\nthree\nfour\nfive\n' >file && git commit -am "target"
+
+git checkout -b moved-target
+printf 'one\ntwo on target\nthree\nfour\nfive moved\n' >file && git commit -am "moved-target"
+
+git checkout -b feature base
+printf 'one\ntwo on feature\nthree\nfour\nfive\n' >file && git commit -am "feature"
+
+git checkout -b other-target base
+printf 'one\ntwo on target\nthree\nfour\nfive\n' >file && printf 'other on target\n' >other && git add . && git commit -m "other-target"
+
+git checkout -b other-feature base
+printf 'one\ntwo on feature\nthree\nfour\nfive\n' >file && printf 'other on feature\n' >other && git add . && git commit -m "other-feature"
+
+git checkout feature
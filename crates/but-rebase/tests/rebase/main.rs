@@ -11,6 +11,7 @@ use crate::utils::{
 
 mod error_handling;
 mod graph_rebase;
+mod rerere;
 
 mod commit {
     mod store_author_globally_if_unset {
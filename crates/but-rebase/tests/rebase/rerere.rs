@@ -0,0 +1,214 @@
+use anyhow::{Result, bail};
+use bstr::ByteSlice;
+use but_core::commit::SignCommit;
+use but_rebase::{
+    graph_rebase::cherry_pick::{CherryPickOutcome, PickMode, TreeMergeMode, cherry_pick},
+    rerere,
+};
+use gix::prelude::ObjectIdExt as _;
+
+use crate::utils::fixture_writable;
+
+fn pick(repo: &gix::Repository, target: &str, onto: &str) -> Result<CherryPickOutcome> {
+    cherry_pick(
+        repo,
+        repo.rev_parse_single(target)?.detach(),
+        &[repo.rev_parse_single(onto)?.detach()],
+        PickMode::IfChanged,
+        TreeMergeMode::WithRenames,
+        SignCommit::No,
+    )
+}
+
+fn file_content(repo: &gix::Repository, commit_id: gix::ObjectId) -> Result<String> {
+    file_content_in_tree(repo, repo.find_commit(commit_id)?.tree_id()?.detach())
+}
+
+fn file_content_in_tree(repo: &gix::Repository, tree_id: gix::ObjectId) -> Result<String> {
+    let tree = repo.find_tree(tree_id)?;
+    let entry = tree
+        .find_entry("file")
+        .expect("all trees of the fixture have this file");
+    Ok(entry.object()?.data.to_str_lossy().into_owned())
+}
+
+/// Pick `feature` onto `target` and record `resolution` as the content of its only conflicted file.
+fn conflict_and_record(repo: &gix::Repository, resolution: &str) -> Result<usize> {
+    let CherryPickOutcome::ConflictedCommit(conflicted) = pick(repo, "feature", "target")? else {
+        bail!("the fixture is set up to conflict");
+    };
+    let blob_id = repo.write_blob(resolution.as_bytes())?;
+    let mut resolved_tree = repo.edit_tree(repo.rev_parse_single("target^{tree}")?)?;
+    resolved_tree.upsert("file", gix::objs::tree::EntryKind::Blob, blob_id)?;
+    rerere::record(repo, conflicted, resolved_tree.write()?.detach(), None)
+}
+
+#[test]
+fn recorded_resolution_is_replayed_when_the_conflict_recurs() -> Result<()> {
+    let (repo, _tmpdir, _meta, _db) = fixture_writable("rerere")?;
+    assert_eq!(
+        conflict_and_record(&repo, "one\ntwo resolved\nthree\nfour\nfive\n")?,
+        1
+    );
+
+    let CherryPickOutcome::Commit(id) = pick(&repo, "feature", "target")? else {
+        bail!("the same conflict is resolved like before");
+    };
+    assert_eq!(
+        file_content(&repo, id)?,
+        "one\ntwo resolved\nthree\nfour\nfive\n"
+    );
+
+    let CherryPickOutcome::Commit(id) = pick(&repo, "feature", "moved-target")? else {
+        bail!("the conflict is the same even though the lines around it changed");
+    };
+    assert_eq!(
+        file_content(&repo, id)?,
+        "one\ntwo resolved\nthree\nfour\nfive moved\n",
+        "the resolution is merged with the changes around the conflict"
+    );
+    Ok(())
+}
+
+#[test]
+fn replayed_resolutions_keep_the_sides_of_commits_that_stay_conflicted() -> Result<()> {
+    let (repo, _tmpdir, _meta, _db) = fixture_writable("rerere")?;
+    conflict_and_record(&repo, "one\ntwo resolved\nthree\nfour\nfive\n")?;
+
+    let CherryPickOutcome::ConflictedCommit(id) = pick(&repo, "other-feature", "other-target")?
+    else {
+        bail!("`other` conflicts as well, and nothing was recorded for it");
+    };
+    let commit = but_core::Commit::from_id(id.attach(&repo))?;
+    assert_eq!(
+        file_content_in_tree(&repo, commit.tree_id_or_auto_resolution()?.detach())?,
+        "one\ntwo resolved\nthree\nfour\nfive\n",
+        "the merged result uses the recorded resolution"
+    );
+    let (_base, ours, theirs) = commit
+        .conflicted_tree_ids()?
+        .expect("the commit is conflicted");
+    assert_eq!(
+        file_content_in_tree(&repo, ours.detach())?,
+        "one\ntwo on target\nthree\nfour\nfive\n",
+        "the sides are kept as they were, to resolve the remaining conflict from"
+    );
+    assert_eq!(
+        file_content_in_tree(&repo, theirs.detach())?,
+        "one\ntwo on feature\nthree\nfour\nfive\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn recorded_resolution_applies_no_matter_which_side_a_change_is_on() -> Result<()> {
+    let (repo, _tmpdir, _meta, _db) = fixture_writable("rerere")?;
+    conflict_and_record(&repo, "one\ntwo resolved\nthree\nfour\nfive\n")?;
+
+    let CherryPickOutcome::Commit(id) = pick(&repo, "target", "feature")? else {
+        bail!("the sides of the conflict are swapped, but it's the same conflict");
+    };
+    assert_eq!(
+        file_content(&repo, id)?,
+        "one\ntwo resolved\nthree\nfour\nfive\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn recorded_resolutions_can_be_listed_and_forgotten() -> Result<()> {
+    let (repo, _tmpdir, _meta, _db) = fixture_writable("rerere")?;
+    conflict_and_record(&repo, "one\ntwo resolved\nthree\nfour\nfive\n")?;
+
+    let store = rerere::Store::from_repo(&repo)?;
+    let resolutions = store.list()?;
+    assert_eq!(resolutions.len(), 1);
+    let resolution = &resolutions[0];
+    assert_eq!(resolution.path, "file");
+    assert_eq!(
+        resolution.preimage,
+        "one\n<<<<<<<\ntwo on feature\n=======\ntwo on target\n>>>>>>>\nthree\nfour\nfive\n",
+        "markers are normalized and the sides of the conflict are sorted"
+    );
+    assert_eq!(
+        resolution.postimage,
+        "one\ntwo resolved\nthree\nfour\nfive\n"
+    );
+    assert_eq!(
+        store.find(&resolution.id)?.as_ref(),
+        Some(resolution),
+        "resolutions can be looked up by their id"
+    );
+
+    assert!(store.forget(&resolution.id)?);
+    assert!(store.list()?.is_empty());
+    assert!(!store.forget(&resolution.id)?, "nothing is left to forget");
+    assert!(
+        matches!(
+            pick(&repo, "feature", "target")?,
+            CherryPickOutcome::ConflictedCommit(_)
+        ),
+        "forgotten resolutions aren't applied anymore"
+    );
+    Ok(())
+}
+
+#[test]
+fn recorded_resolutions_are_ignored_if_disabled() -> Result<()> {
+    let (mut repo, _tmpdir, _meta, _db) = fixture_writable("rerere")?;
+    conflict_and_record(&repo, "one\ntwo resolved\nthree\nfour\nfive\n")?;
+
+    repo.config_snapshot_mut()
+        .set_raw_value(rerere::CONFIG_KEY, "false")?;
+    assert!(matches!(
+        pick(&repo, "feature", "target")?,
+        CherryPickOutcome::ConflictedCommit(_)
+    ));
+    Ok(())
+}
+
+#[test]
+fn resolutions_that_leave_conflict_markers_do_not_apply() -> Result<()> {
+    let (repo, _tmpdir, _meta, _db) = fixture_writable("rerere")?;
+    let resolution = rerere::Resolution {
+        id: "0".into(),
+        path: "file".into(),
+        recorded_at: 0,
+        preimage:
+            "one\n<<<<<<<\ntwo on feature\n=======\ntwo on target\n>>>>>>>\nthree\nfour\nfive\n"
+                .into(),
+        postimage: "one\ntwo resolved\nthree\nfour\nfive\n".into(),
+    };
+
+    assert_eq!(
+        resolution.apply_to(
+            &repo,
+            "one\n<<<<<<<\ntwo on feature\n=======\ntwo on target\n>>>>>>>\nthree\nfour\nfive moved\n".into()
+        )?,
+        Some("one\ntwo resolved\nthree\nfour\nfive moved\n".into()),
+        "changes around the conflict are merged"
+    );
+    assert_eq!(
+        resolution.apply_to(
+            &repo,
+            "one\n<<<<<<<\ntwo on feature\n=======\ntwo on target\n>>>>>>>\nthree\nfour\n<<<<<<<\nfive on feature\n=======\nfive on target\n>>>>>>>\n".into()
+        )?,
+        None,
+        "the merge is clean, but the conflict the resolution doesn't know about would remain"
+    );
+    Ok(())
+}
+
+#[test]
+fn files_with_conflict_markers_are_not_recorded() -> Result<()> {
+    let (repo, _tmpdir, _meta, _db) = fixture_writable("rerere")?;
+    assert_eq!(
+        conflict_and_record(
+            &repo,
+            "one\n<<<<<<< ours\ntwo on target\n=======\ntwo on feature\n>>>>>>> theirs\nthree\nfour\nfive\n"
+        )?,
+        0
+    );
+    assert!(rerere::Store::from_repo(&repo)?.list()?.is_empty());
+    Ok(())
+}
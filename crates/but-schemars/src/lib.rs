@@ -408,6 +408,63 @@ pub struct ApiFnEntry {
 
 inventory::collect!(ApiFnEntry);
 
+/// One `#[but_api]` function as it is called with a JSON object of named
+/// parameters, which is how `but-server` exposes it as `POST /<name>`.
+///
+/// Unlike [`ApiFnEntry`], this is registered for every function, as it is the
+/// source of the machine-readable description of the server API.
+#[derive(Debug)]
+pub struct ApiCommandEntry {
+    /// The Rust name of the function, which is also the command name, e.g. `branch_diff`.
+    pub name: &'static str,
+    /// The documentation of the function, without the leading `///`.
+    pub docs: &'static str,
+    /// The fields of the parameter object, in declaration order.
+    pub params: &'static [ApiCommandParam],
+    /// What the function returns on success.
+    pub returns: ApiType,
+}
+
+inventory::collect!(ApiCommandEntry);
+
+/// A field of the parameter object of an [`ApiCommandEntry`].
+#[derive(Debug)]
+pub struct ApiCommandParam {
+    /// The camelCased name of the field.
+    pub name: &'static str,
+    /// The type of the field, which is optional if it is [`ApiType::Nullable`].
+    pub ty: ApiType,
+}
+
+/// The shape of a value crossing the JSON boundary of a `#[but_api]` function,
+/// as far as it can be told from its Rust type.
+#[derive(Debug)]
+pub enum ApiType {
+    /// A JSON string, also used for hashes, IDs and reference names.
+    String,
+    /// A JSON boolean.
+    Boolean,
+    /// A JSON number without fraction.
+    Integer,
+    /// Any JSON number.
+    Number,
+    /// `null`, which is what `()` serializes to.
+    Null,
+    /// Any JSON value.
+    Any,
+    /// An array of values of the given type.
+    Array(&'static ApiType),
+    /// The given type, or `null`.
+    Nullable(&'static ApiType),
+    /// An object with arbitrary keys and values of the given type.
+    Map(&'static ApiType),
+    /// A fixed-length array with the given types.
+    Tuple(&'static [ApiType]),
+    /// A type by the name of its Rust type, which is usually also the name it
+    /// is registered with via [`register_sdk_type!`].
+    Named(&'static str),
+}
+
 use std::borrow::Cow;
 
 #[doc(hidden)]
@@ -12,7 +12,7 @@ use axum::{
     http::StatusCode,
     middleware::{self, Next},
     response::IntoResponse,
-    routing::{MethodRouter, any, post},
+    routing::{MethodRouter, any, get, post},
 };
 use but_api::{commit, diff, github, gitlab, json, legacy, open, platform, workspace};
 use but_ctx::ProjectHandleOrLegacyProjectId;
@@ -30,6 +30,12 @@ use tower_http::cors::{self, CorsLayer};
 mod projects;
 use crate::projects::ActiveProjects;
 
+mod openapi;
+
+mod remote;
+use remote::{AllowedOrigins, Authentication};
+pub use remote::{RemoteConfig, TlsConfig};
+
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
 enum Response {
@@ -86,10 +92,13 @@ where
     })
 }
 
-fn server_capabilities(_params: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+fn server_capabilities(
+    _params: serde_json::Value,
+    is_remote: bool,
+) -> anyhow::Result<serde_json::Value> {
     Ok(serde_json::to_value(
         but_api::platform::ServerCapabilities {
-            is_remote: false,
+            is_remote,
             can_add_projects: true,
         },
     )?)
@@ -292,6 +301,11 @@ pub struct Config {
     pub base_path: Option<String>,
     /// If set, auto-activate this directory's project on startup.
     pub project_path: Option<std::path::PathBuf>,
+    /// If set, serve HTTPS with this certificate instead of plain HTTP.
+    pub tls: Option<TlsConfig>,
+    /// If set, accept authenticated connections from other machines instead of only
+    /// from localhost.
+    pub remote: Option<RemoteConfig>,
 }
 
 /// Middleware to ensure all connections are from localhost only.
@@ -339,6 +353,45 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
         .or_else(|| std::env::var("BUTLER_PORT").ok()?.parse().ok())
         .unwrap_or(6978);
 
+    let remote = config.remote.clone();
+    if let Some(remote) = &remote
+        && remote.client_ca.is_some()
+        && config.tls.is_none()
+    {
+        anyhow::bail!("Client certificates can only be verified when TLS is configured");
+    }
+    let allowed_origins = AllowedOrigins::new(
+        remote
+            .as_ref()
+            .map(|remote| remote.allowed_origins.as_slice())
+            .unwrap_or_default(),
+    )?;
+    let configured_token = remote.as_ref().and_then(|remote| {
+        remote
+            .auth_token
+            .clone()
+            .or_else(|| std::env::var("BUTLER_AUTH_TOKEN").ok())
+            .filter(|token| !token.is_empty())
+    });
+    // Without any means of authentication, generate a token to print on startup.
+    let generated_token = remote
+        .as_ref()
+        .filter(|remote| configured_token.is_none() && remote.client_ca.is_none())
+        .map(|_| uuid::Uuid::new_v4().simple().to_string());
+    let auth_token = configured_token.or_else(|| generated_token.clone());
+    let tls_config = config
+        .tls
+        .as_ref()
+        .map(|tls| {
+            remote::server_config(
+                tls,
+                remote
+                    .as_ref()
+                    .and_then(|remote| remote.client_ca.as_deref()),
+            )
+        })
+        .transpose()?;
+
     // CORS wildcards are forbidden when credentials are allowed, so always list explicitly.
     // `baggage` and `sentry-trace` are injected by Sentry's performance monitoring into
     // outgoing fetch requests; without them the browser blocks the preflight.
@@ -359,8 +412,9 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
     .into();
     let cors = CorsLayer::new()
         .allow_methods(allowed_methods)
-        .allow_origin(cors::AllowOrigin::predicate(|origin, _parts| {
-            is_localhost_origin(origin.as_bytes())
+        .allow_origin(cors::AllowOrigin::predicate({
+            let allowed_origins = allowed_origins.clone();
+            move |origin, _parts| allowed_origins.allows(origin.as_bytes())
         }))
         .allow_headers(allowed_headers)
         .allow_credentials(true);
@@ -419,8 +473,161 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
         app_settings,
     };
 
-    let app = Router::new()
-        .route("/server_capabilities", but_post(server_capabilities))
+    let is_remote = remote.is_some();
+    let routes = api_routes(is_remote);
+    let openapi = Arc::new(openapi::document(&api_base, &routes.paths));
+    let app = routes.router.route(
+        openapi::PATH,
+        get(move || async move { Json(openapi.as_ref().clone()) }),
+    );
+
+    // Catch-all for commands that need special handling (app, extra, app_settings_sync)
+    let app = app
+        .route("/{command}", post(post_handle_command_with_path))
+        .route(
+            "/ws",
+            any({
+                let allowed_origins = allowed_origins.clone();
+                move |headers, ws| handle_ws_request(headers, ws, broadcaster, allowed_origins)
+            }),
+        )
+        // Spawning in a separate thread to prevent abort if the client
+        // disconnects.
+        .route_layer(middleware::from_fn(
+            |req: axum::extract::Request<Body>, next: Next| async move {
+                tokio::task::spawn(next.run(req)).await.unwrap()
+            },
+        ))
+        .with_state(state);
+
+    // Optionally nest all API routes under a configurable base path.
+    // e.g. --base-path=/api makes all endpoints available at /api/...
+    let app: Router = if api_base.is_empty() {
+        app
+    } else {
+        Router::new().nest(&api_base, app)
+    };
+
+    let app = if is_remote {
+        app.layer(ServiceBuilder::new().layer(middleware::from_fn_with_state(
+            (allowed_origins, Authentication::new(auth_token.clone())),
+            remote::remote_middleware,
+        )))
+    } else {
+        app.layer(ServiceBuilder::new().layer(middleware::from_fn(localhost_only_middleware)))
+    }
+    .layer(cors);
+
+    let default_host = "127.0.0.1";
+    let host_env = std::env::var("BUTLER_HOST").ok();
+    let host = config
+        .bind_addr
+        .as_deref()
+        .or(host_env.as_deref())
+        .unwrap_or(default_host);
+    let url = format!("{host}:{port}");
+    let listener = match tokio::net::TcpListener::bind(&url).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            if e.kind() == std::io::ErrorKind::AddrInUse {
+                tracing::error!(
+                    "Failed to bind to {url}: {e}. Another instance of but-server may already be running on port {port}."
+                );
+            } else {
+                tracing::error!("Failed to bind to {url}: {e}");
+            }
+            anyhow::bail!("Failed to bind to {url}: {e}");
+        }
+    };
+    let scheme = if tls_config.is_some() {
+        "https"
+    } else {
+        "http"
+    };
+    println!(
+        "{} {}",
+        "Local:".bold(),
+        format!("{scheme}://localhost:{port}").cyan().underline()
+    );
+    if is_remote {
+        println!(
+            "{} {}",
+            "Network:".bold(),
+            format!("{scheme}://{host}:{port}").cyan().underline()
+        );
+        if let Some(token) = &generated_token {
+            println!("{} {token}", "Token:".bold());
+        }
+        if tls_config.is_none() {
+            tracing::warn!(
+                "Remote mode is enabled without TLS, so the token is sent in plain text. \
+                 Configure TLS unless a proxy terminates it."
+            );
+        }
+    }
+    let make_service = app.into_make_service_with_connect_info::<SocketAddr>();
+    let server = async move {
+        match tls_config {
+            Some(tls_config) => {
+                axum_server::from_tcp_rustls(
+                    listener.into_std()?,
+                    axum_server::tls_rustls::RustlsConfig::from_config(Arc::new(tls_config)),
+                )
+                .serve(make_service)
+                .await
+            }
+            None => axum::serve(listener, make_service).await,
+        }
+    };
+
+    tokio::select! {
+        result = server => { result.unwrap(); }
+        _ = tokio::signal::ctrl_c() => {
+            // The settings file watcher (spawn_blocking with infinite loop) and
+            // other background tasks prevent the tokio runtime from exiting
+            // cleanly. It's safe to terminate immediately.
+            std::process::exit(0);
+        }
+    }
+    Ok(())
+}
+
+/// A [`Router`] that remembers the paths of its routes, so only what is served gets described in
+/// the [OpenAPI document](openapi::document).
+struct ApiRoutes<S> {
+    router: Router<S>,
+    paths: Vec<&'static str>,
+}
+
+impl<S> ApiRoutes<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn new() -> Self {
+        ApiRoutes {
+            router: Router::new(),
+            paths: Vec::new(),
+        }
+    }
+
+    fn route(mut self, path: &'static str, method_router: MethodRouter<S, Infallible>) -> Self {
+        self.router = self.router.route(path, method_router);
+        self.paths.push(path);
+        self
+    }
+}
+
+/// All commands that are routed to a `#[but_api]` function, or to a function that is called just like
+/// one, which is everything that doesn't need the state of the server.
+fn api_routes<S>(is_remote: bool) -> ApiRoutes<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let routes = ApiRoutes::new()
+        .route(
+            "/server_capabilities",
+            but_post(move |params| server_capabilities(params, is_remote)),
+        )
         .route("/pick_directory", but_post_async(pick_directory))
         .route(
             "/git_remote_branches",
@@ -491,12 +698,12 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
         .route("/head_info", but_post(legacy::workspace::head_info_cmd));
 
     #[cfg(unix)]
-    let app = app.route(
+    let routes = routes.route(
         "/show_graph_svg",
         but_post(legacy::workspace::show_graph_svg_cmd),
     );
 
-    let app = app
+    let routes = routes
         .route(
             "/branch_details",
             but_post(legacy::workspace::branch_details_cmd),
@@ -711,6 +918,25 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
             "/clear_all_github_tokens",
             but_post(github::clear_all_github_tokens_cmd),
         )
+        .route(
+            "/init_github_device_oauth",
+            but_post_async(|_params| async {
+                github::init_github_device_oauth().await.map(|r| json!(r))
+            }),
+        )
+        .route(
+            "/check_github_auth_status",
+            but_post_async(github::check_github_auth_status_cmd),
+        )
+        .route(
+            "/store_github_pat",
+            but_post_async(github::store_github_pat_cmd),
+        )
+        .route(
+            "/store_github_enterprise_pat",
+            but_post_async(github::store_github_enterprise_pat_cmd),
+        )
+        .route("/get_gh_user", but_post_async(github::get_gh_user_cmd))
         .route(
             "/forget_gitlab_account",
             but_post(gitlab::forget_gitlab_account_cmd),
@@ -723,7 +949,47 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
             "/clear_all_gitlab_tokens",
             but_post(gitlab::clear_all_gitlab_tokens_cmd),
         )
+        .route(
+            "/store_gitlab_pat",
+            but_post_async(gitlab::store_gitlab_pat_cmd),
+        )
+        .route(
+            "/store_gitlab_selfhosted_pat",
+            but_post_async(gitlab::store_gitlab_selfhosted_pat_cmd),
+        )
+        .route("/get_gl_user", but_post_async(gitlab::get_gl_user_cmd))
         // Forge commands
+        .route("/list_reviews", but_post(legacy::forge::list_reviews_cmd))
+        .route(
+            "/publish_review",
+            but_post_async(legacy::forge::publish_review_cmd),
+        )
+        .route(
+            "/update_review_footers",
+            but_post_async(legacy::forge::update_review_footers_cmd),
+        )
+        .route("/get_review", but_post(legacy::forge::get_review_cmd))
+        .route(
+            "/get_review_merge_status",
+            but_post_async(legacy::forge::get_review_merge_status_cmd),
+        )
+        .route(
+            "/get_review_base_repo_url",
+            but_post_async(legacy::forge::get_review_base_repo_url_cmd),
+        )
+        .route(
+            "/get_repo_info",
+            but_post_async(legacy::forge::get_repo_info_cmd),
+        )
+        .route("/forge_info", but_post(legacy::forge::forge_info_cmd))
+        .route(
+            "/forge_compare_branch_url",
+            but_post(legacy::forge::forge_compare_branch_url_cmd),
+        )
+        .route(
+            "/list_ci_checks",
+            but_post(legacy::forge::list_ci_checks_cmd),
+        )
         .route("/pr_templates", but_post(legacy::forge::pr_templates_cmd))
         .route("/pr_template", but_post(legacy::forge::pr_template_cmd))
         .route(
@@ -798,76 +1064,7 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
             but_post(workspace::workspace_fetch_status_cmd),
         )
         .route("/build_type", but_post(platform::build_type_cmd));
-
-    // Catch-all for commands that need special handling (app, extra, app_settings_sync)
-    let app = app
-        .route("/{command}", post(post_handle_command_with_path))
-        .route(
-            "/ws",
-            any(move |headers, ws| handle_ws_request(headers, ws, broadcaster)),
-        )
-        // Spawning in a separate thread to prevent abort if the client
-        // disconnects.
-        .route_layer(middleware::from_fn(
-            |req: axum::extract::Request<Body>, next: Next| async move {
-                tokio::task::spawn(next.run(req)).await.unwrap()
-            },
-        ))
-        .with_state(state);
-
-    // Optionally nest all API routes under a configurable base path.
-    // e.g. --base-path=/api makes all endpoints available at /api/...
-    let app: Router = if api_base.is_empty() {
-        app
-    } else {
-        Router::new().nest(&api_base, app)
-    };
-
-    let app = app
-        .layer(ServiceBuilder::new().layer(middleware::from_fn(localhost_only_middleware)))
-        .layer(cors);
-
-    let default_host = "127.0.0.1";
-    let host_env = std::env::var("BUTLER_HOST").ok();
-    let host = config
-        .bind_addr
-        .as_deref()
-        .or(host_env.as_deref())
-        .unwrap_or(default_host);
-    let url = format!("{host}:{port}");
-    let listener = match tokio::net::TcpListener::bind(&url).await {
-        Ok(listener) => listener,
-        Err(e) => {
-            if e.kind() == std::io::ErrorKind::AddrInUse {
-                tracing::error!(
-                    "Failed to bind to {url}: {e}. Another instance of but-server may already be running on port {port}."
-                );
-            } else {
-                tracing::error!("Failed to bind to {url}: {e}");
-            }
-            anyhow::bail!("Failed to bind to {url}: {e}");
-        }
-    };
-    println!(
-        "{} {}",
-        "Local:".bold(),
-        format!("http://localhost:{port}").cyan().underline()
-    );
-    let server = axum::serve(
-        listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
-    );
-
-    tokio::select! {
-        result = server => { result.unwrap(); }
-        _ = tokio::signal::ctrl_c() => {
-            // The settings file watcher (spawn_blocking with infinite loop) and
-            // other background tasks prevent the tokio runtime from exiting
-            // cleanly. It's safe to terminate immediately.
-            std::process::exit(0);
-        }
-    }
-    Ok(())
+    routes
 }
 
 /// Handler that extracts the command from the URL path.
@@ -895,13 +1092,14 @@ async fn handle_ws_request(
     headers: axum::http::HeaderMap,
     ws: WebSocketUpgrade,
     broadcaster: Arc<Mutex<Broadcaster>>,
+    allowed_origins: AllowedOrigins,
 ) -> Result<impl IntoResponse, StatusCode> {
     // Validate the Origin header to prevent cross-site WebSocket hijacking.
     // CORS headers don't protect WebSocket upgrades, so we must check manually.
     let origin = headers
         .get(axum::http::header::ORIGIN)
         .ok_or(StatusCode::FORBIDDEN)?;
-    if !is_localhost_origin(origin.as_bytes()) {
+    if !allowed_origins.allows(origin.as_bytes()) {
         tracing::warn!("Rejected WebSocket connection from origin: {origin:?}");
         return Err(StatusCode::FORBIDDEN);
     }
@@ -974,112 +1172,7 @@ async fn handle_command(
             projects::set_project_active(&broadcaster, &extra, app_settings_sync, request.params)
                 .await
         }
-        "branch_create" => deserialize_json(request.params)
-            .and_then(|params| but_api::branch::branch_create_cmd(params).map(|r| json!(r))),
-        "branch_remove" => deserialize_json(request.params)
-            .and_then(|params| but_api::branch::branch_remove_cmd(params).map(|r| json!(r))),
-        "commit_uncommit_changes_from_commits" => {
-            commit::uncommit::commit_uncommit_changes_from_commits_cmd(request.params)
-        }
-        "branch_rename" => deserialize_json(request.params)
-            .and_then(|params| but_api::branch::branch_rename_cmd(params).map(|r| json!(r))),
-        // GitHub commands (async, not yet migrated)
-        "init_github_device_oauth" => {
-            let result = github::init_github_device_oauth().await;
-            result.map(|r| json!(r))
-        }
-        "check_github_auth_status" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = github::check_github_auth_status_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "store_github_pat" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = github::store_github_pat_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "store_github_enterprise_pat" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = github::store_github_enterprise_pat_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "get_gh_user" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = github::get_gh_user_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        // GitLab commands (async, not yet migrated)
-        "store_gitlab_pat" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = gitlab::store_gitlab_pat_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "store_gitlab_selfhosted_pat" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = gitlab::store_gitlab_selfhosted_pat_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "get_gl_user" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = but_api::gitlab::get_gl_user_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        // Forge commands (some async, not yet migrated)
-        "list_reviews" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = legacy::forge::list_reviews_cmd(params);
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "publish_review" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = legacy::forge::publish_review_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
+        // Forge commands whose result isn't the one of their `#[but_api]` function
         "merge_review" => {
             let params = deserialize_json(request.params);
             match params {
@@ -1110,46 +1203,6 @@ async fn handle_command(
                 Err(e) => Err(e),
             }
         }
-        "update_review_footers" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = legacy::forge::update_review_footers_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "get_review" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = legacy::forge::get_review_cmd(params);
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "get_review_merge_status" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = legacy::forge::get_review_merge_status_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "get_review_base_repo_url" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = legacy::forge::get_review_base_repo_url_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
         "update_review" => {
             let params = deserialize_json(request.params);
             match params {
@@ -1160,46 +1213,6 @@ async fn handle_command(
                 Err(e) => Err(e),
             }
         }
-        "get_repo_info" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = legacy::forge::get_repo_info_cmd(params).await;
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "forge_info" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = legacy::forge::forge_info_cmd(params);
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "forge_compare_branch_url" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = legacy::forge::forge_compare_branch_url_cmd(params);
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
-        "list_ci_checks" => {
-            let params = deserialize_json(request.params);
-            match params {
-                Ok(params) => {
-                    let result = legacy::forge::list_ci_checks_cmd(params);
-                    result.map(|r| json!(r))
-                }
-                Err(e) => Err(e),
-            }
-        }
         // Askpass commands (async)
         "submit_prompt_response" => {
             let params = deserialize_json(request.params);
@@ -1313,4 +1326,137 @@ mod tests {
         // Empty
         assert!(!is_localhost_host(b""));
     }
+
+    #[tokio::test]
+    async fn documented_operations_are_routed() {
+        use tower::ServiceExt as _;
+
+        let routes = api_routes::<()>(false);
+        let document = openapi::document("", &routes.paths);
+        let operations = document["paths"].as_object().unwrap();
+        assert!(
+            operations.contains_key("/branch_diff"),
+            "routed commands are documented"
+        );
+        assert!(
+            !operations.contains_key("/store_azure_pat"),
+            "registered commands that aren't routed are left out"
+        );
+
+        for path in operations.keys() {
+            // Only routes that exist can reject the method, and no handler runs.
+            let response = routes
+                .router
+                .clone()
+                .oneshot(
+                    axum::http::Request::get(path.as_str())
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(
+                response.status(),
+                StatusCode::METHOD_NOT_ALLOWED,
+                "{path} is dispatched"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn migrated_commands_answer_like_the_command_handler() {
+        use tower::ServiceExt as _;
+
+        // Invalid parameters fail before any command touches a project, the network or a credential.
+        let params = json!(42);
+        let expected = [
+            (
+                "/check_github_auth_status",
+                github::check_github_auth_status_cmd(params.clone()).await,
+            ),
+            (
+                "/store_github_pat",
+                github::store_github_pat_cmd(params.clone()).await,
+            ),
+            (
+                "/store_github_enterprise_pat",
+                github::store_github_enterprise_pat_cmd(params.clone()).await,
+            ),
+            (
+                "/get_gh_user",
+                github::get_gh_user_cmd(params.clone()).await,
+            ),
+            (
+                "/store_gitlab_pat",
+                gitlab::store_gitlab_pat_cmd(params.clone()).await,
+            ),
+            (
+                "/store_gitlab_selfhosted_pat",
+                gitlab::store_gitlab_selfhosted_pat_cmd(params.clone()).await,
+            ),
+            (
+                "/get_gl_user",
+                gitlab::get_gl_user_cmd(params.clone()).await,
+            ),
+            (
+                "/list_reviews",
+                legacy::forge::list_reviews_cmd(params.clone()),
+            ),
+            (
+                "/publish_review",
+                legacy::forge::publish_review_cmd(params.clone()).await,
+            ),
+            (
+                "/update_review_footers",
+                legacy::forge::update_review_footers_cmd(params.clone()).await,
+            ),
+            ("/get_review", legacy::forge::get_review_cmd(params.clone())),
+            (
+                "/get_review_merge_status",
+                legacy::forge::get_review_merge_status_cmd(params.clone()).await,
+            ),
+            (
+                "/get_review_base_repo_url",
+                legacy::forge::get_review_base_repo_url_cmd(params.clone()).await,
+            ),
+            (
+                "/get_repo_info",
+                legacy::forge::get_repo_info_cmd(params.clone()).await,
+            ),
+            ("/forge_info", legacy::forge::forge_info_cmd(params.clone())),
+            (
+                "/forge_compare_branch_url",
+                legacy::forge::forge_compare_branch_url_cmd(params.clone()),
+            ),
+            (
+                "/list_ci_checks",
+                legacy::forge::list_ci_checks_cmd(params.clone()),
+            ),
+        ];
+
+        let routes = api_routes::<()>(false);
+        for (path, result) in expected {
+            // This is what `handle_command` answered with before the command got its own route.
+            let Json(expected) = cmd_result_to_json(result);
+            assert_eq!(expected["type"], "error", "{path} rejects the parameters");
+
+            let response = routes
+                .router
+                .clone()
+                .oneshot(
+                    axum::http::Request::post(path)
+                        .header(axum::http::header::CONTENT_TYPE, "application/json")
+                        .body(Body::from(params.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{path} is routed");
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let actual: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(actual, expected, "{path} answers like before");
+        }
+    }
 }
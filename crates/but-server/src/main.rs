@@ -1,4 +1,7 @@
-use but_server::Config;
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use but_server::{Config, RemoteConfig, TlsConfig};
 use clap::Parser;
 
 #[derive(Parser, Debug)]
@@ -15,6 +18,35 @@ struct Args {
     /// Prefix all API routes with this path (e.g. /api).
     #[arg(long)]
     base_path: Option<String>,
+
+    /// Accept connections from other machines, which must authenticate with a bearer token or
+    /// a client certificate. Typically combined with --bind-addr 0.0.0.0.
+    ///
+    /// The token is read from --auth-token-file or BUTLER_AUTH_TOKEN. If neither is set and
+    /// --client-ca isn't used, a random token is generated and printed on startup.
+    #[arg(long)]
+    remote: bool,
+
+    /// Read the bearer token that clients must send from this file.
+    #[arg(long, value_name = "PATH", requires = "remote")]
+    auth_token_file: Option<PathBuf>,
+
+    /// Require client certificates signed by one of the CAs in this PEM file.
+    #[arg(long, value_name = "PATH", requires_all = ["remote", "tls_cert"])]
+    client_ca: Option<PathBuf>,
+
+    /// Allow browsers to use the server from this origin, e.g. https://gitbutler.example.com,
+    /// in addition to localhost. Can be given multiple times.
+    #[arg(long = "allowed-origin", value_name = "ORIGIN", requires = "remote")]
+    allowed_origins: Vec<String>,
+
+    /// Serve HTTPS with the certificate chain in this PEM file.
+    #[arg(long, value_name = "PATH", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// The PEM file with the private key of --tls-cert.
+    #[arg(long, value_name = "PATH", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -35,11 +67,33 @@ async fn main() -> anyhow::Result<()> {
     but_askpass::disable();
 
     let args = Args::parse();
+    let remote = if args.remote {
+        let auth_token = args
+            .auth_token_file
+            .map(|path| {
+                std::fs::read_to_string(&path)
+                    .map(|token| token.trim().to_owned())
+                    .with_context(|| format!("Could not read the token from {}", path.display()))
+            })
+            .transpose()?;
+        Some(RemoteConfig {
+            auth_token,
+            client_ca: args.client_ca,
+            allowed_origins: args.allowed_origins,
+        })
+    } else {
+        None
+    };
     let config = Config {
         port: Some(args.port),
         bind_addr: args.bind_addr,
         base_path: args.base_path,
         project_path: None,
+        tls: args
+            .tls_cert
+            .zip(args.tls_key)
+            .map(|(cert, key)| TlsConfig { cert, key }),
+        remote,
     };
     but_server::run(config).await
 }
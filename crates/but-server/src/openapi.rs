@@ -0,0 +1,314 @@
+//! A machine-readable description of the API, generated from the metadata `#[but_api]` registers.
+//!
+//! Each registered function the server routes becomes a `POST /<name>` operation which takes its
+//! parameters as JSON object, and whose result is wrapped into the `{ type, subject }` envelope of
+//! [`Response`](crate::Response). Types registered with `but_schemars::register_sdk_type!` are
+//! included as components, so clients can be generated from the document alone.
+
+use std::collections::BTreeMap;
+
+use but_schemars::{ApiCommandEntry, ApiType, SchemarEntry};
+use serde_json::{Map, Value, json};
+
+/// The path the document is served at, relative to the base path.
+pub(crate) const PATH: &str = "/openapi.json";
+
+/// Build the OpenAPI 3.1 document for all registered commands that are routed to one of `paths`,
+/// like `/branch_diff`, served below `base_path`.
+///
+/// Registered commands the server doesn't route are left out, as calling them would fail.
+pub(crate) fn document(base_path: &str, paths: &[&str]) -> Value {
+    let schemas = component_schemas();
+
+    let mut commands: Vec<_> = inventory::iter::<ApiCommandEntry>()
+        .filter(|command| {
+            paths
+                .iter()
+                .any(|path| path.strip_prefix('/') == Some(command.name))
+        })
+        .collect();
+    commands.sort_by_key(|command| command.name);
+    let paths: Map<_, _> = commands
+        .into_iter()
+        .map(|command| {
+            (
+                format!("/{}", command.name),
+                json!({ "post": operation(command, &schemas) }),
+            )
+        })
+        .collect();
+    let server_url = if base_path.is_empty() { "/" } else { base_path };
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "GitButler",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "servers": [{ "url": server_url }],
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": {
+                "bearer": { "type": "http", "scheme": "bearer" },
+            },
+        },
+        // Only required in remote mode, which an empty requirement leaves optional.
+        "security": [{ "bearer": [] }, {}],
+    })
+}
+
+fn operation(command: &ApiCommandEntry, schemas: &BTreeMap<String, Value>) -> Value {
+    let properties: Map<_, _> = command
+        .params
+        .iter()
+        .map(|param| (param.name.to_owned(), schema_for(&param.ty, schemas)))
+        .collect();
+    let required: Vec<_> = command
+        .params
+        .iter()
+        .filter(|param| !matches!(param.ty, ApiType::Nullable(_)))
+        .map(|param| param.name)
+        .collect();
+
+    let mut operation = json!({
+        "operationId": command.name,
+        "requestBody": {
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": {
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                    },
+                },
+            },
+        },
+        "responses": {
+            "200": {
+                "description": "The result of the command, or the error it failed with.",
+                "content": {
+                    "application/json": {
+                        "schema": {
+                            "oneOf": [
+                                envelope("success", schema_for(&command.returns, schemas)),
+                                envelope("error", error_schema()),
+                            ],
+                        },
+                    },
+                },
+            },
+        },
+    });
+    if let Some(summary) = command.docs.lines().next().filter(|line| !line.is_empty()) {
+        operation["summary"] = summary.into();
+        operation["description"] = command.docs.into();
+    }
+    operation
+}
+
+fn envelope(kind: &str, subject: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "type": { "const": kind },
+            "subject": subject,
+        },
+        "required": ["type", "subject"],
+    })
+}
+
+/// The serialized form of `but_api::json::Error`.
+fn error_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "code": { "type": "string" },
+            "message": { "type": "string" },
+        },
+        "required": ["code", "message"],
+    })
+}
+
+/// Return the JSON schema for `ty`, referring to `schemas` for named types.
+///
+/// Named types without a registered schema are described by name only, as their shape is unknown.
+fn schema_for(ty: &ApiType, schemas: &BTreeMap<String, Value>) -> Value {
+    match ty {
+        ApiType::String => json!({ "type": "string" }),
+        ApiType::Boolean => json!({ "type": "boolean" }),
+        ApiType::Integer => json!({ "type": "integer" }),
+        ApiType::Number => json!({ "type": "number" }),
+        ApiType::Null => json!({ "type": "null" }),
+        ApiType::Any => json!({}),
+        ApiType::Array(items) => json!({ "type": "array", "items": schema_for(items, schemas) }),
+        ApiType::Nullable(inner) => {
+            json!({ "oneOf": [schema_for(inner, schemas), { "type": "null" }] })
+        }
+        ApiType::Map(values) => {
+            json!({ "type": "object", "additionalProperties": schema_for(values, schemas) })
+        }
+        ApiType::Tuple(items) => json!({
+            "type": "array",
+            "prefixItems": items.iter().map(|item| schema_for(item, schemas)).collect::<Vec<_>>(),
+            "minItems": items.len(),
+            "maxItems": items.len(),
+        }),
+        ApiType::Named(name) if schemas.contains_key(*name) => {
+            json!({ "$ref": format!("#/components/schemas/{name}") })
+        }
+        ApiType::Named(name) => json!({ "description": name }),
+    }
+}
+
+/// Collect the schemas of all registered types by name, with their definitions hoisted into
+/// components as well.
+fn component_schemas() -> BTreeMap<String, Value> {
+    let mut entries: Vec<_> = inventory::iter::<SchemarEntry>().collect();
+    // The first registration wins on name collisions, which `but-ts` rejects anyway.
+    entries.sort_by_key(|entry| entry.registration_location);
+
+    let mut schemas = BTreeMap::new();
+    let mut definitions = Vec::new();
+    for entry in entries {
+        let name = (entry.name)().into_owned();
+        let mut schema = (entry.schema)().to_value();
+        if let Some(obj) = schema.as_object_mut() {
+            obj.remove("$schema");
+            for key in ["$defs", "definitions"] {
+                if let Some(Value::Object(defs)) = obj.remove(key) {
+                    definitions.extend(defs);
+                }
+            }
+        }
+        rewrite_refs(&mut schema, &name);
+        schemas.entry(name).or_insert(schema);
+    }
+    for (name, mut schema) in definitions {
+        rewrite_refs(&mut schema, &name);
+        schemas.entry(name).or_insert(schema);
+    }
+    schemas
+}
+
+/// Point the `$ref`s in `schema` of the type `name` to components instead of local definitions.
+fn rewrite_refs(schema: &mut Value, name: &str) {
+    match schema {
+        Value::Object(obj) => {
+            for (key, value) in obj.iter_mut() {
+                match value {
+                    Value::String(target) if key == "$ref" => {
+                        let referenced = if target.as_str() == "#" {
+                            Some(name)
+                        } else {
+                            target
+                                .strip_prefix("#/$defs/")
+                                .or_else(|| target.strip_prefix("#/definitions/"))
+                        };
+                        if let Some(referenced) = referenced {
+                            *target = format!("#/components/schemas/{referenced}");
+                        }
+                    }
+                    _ => rewrite_refs(value, name),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                rewrite_refs(item, name);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_types_refer_to_components_if_registered() {
+        let schemas = BTreeMap::from([("Commit".to_owned(), json!({ "type": "object" }))]);
+        assert_eq!(
+            schema_for(
+                &ApiType::Nullable(&ApiType::Array(&ApiType::Named("Commit"))),
+                &schemas
+            ),
+            json!({
+                "oneOf": [
+                    { "type": "array", "items": { "$ref": "#/components/schemas/Commit" } },
+                    { "type": "null" },
+                ]
+            })
+        );
+        assert_eq!(
+            schema_for(&ApiType::Named("Unregistered"), &schemas),
+            json!({ "description": "Unregistered" })
+        );
+    }
+
+    #[test]
+    fn refs_are_rewritten_to_components() {
+        let mut schema = json!({
+            "properties": {
+                "parent": { "$ref": "#" },
+                "author": { "anyOf": [{ "$ref": "#/$defs/Author" }, { "type": "null" }] },
+                "external": { "$ref": "https://example.com/schema.json" },
+            }
+        });
+        rewrite_refs(&mut schema, "Commit");
+        assert_eq!(
+            schema,
+            json!({
+                "properties": {
+                    "parent": { "$ref": "#/components/schemas/Commit" },
+                    "author": {
+                        "anyOf": [{ "$ref": "#/components/schemas/Author" }, { "type": "null" }]
+                    },
+                    "external": { "$ref": "https://example.com/schema.json" },
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn only_routed_commands_are_documented() {
+        let document = document("/api", &["/branch_diff"]);
+        let operations = document["paths"].as_object().unwrap();
+        assert!(
+            operations.contains_key("/branch_diff"),
+            "routed commands are documented"
+        );
+        assert_eq!(
+            operations.len(),
+            1,
+            "registered commands that aren't routed are left out"
+        );
+    }
+
+    #[test]
+    fn optional_parameters_are_not_required() {
+        let command = ApiCommandEntry {
+            name: "commit_reword",
+            docs: "Reword a commit.\n\nMore details.",
+            params: &[
+                but_schemars::ApiCommandParam {
+                    name: "projectId",
+                    ty: ApiType::String,
+                },
+                but_schemars::ApiCommandParam {
+                    name: "message",
+                    ty: ApiType::Nullable(&ApiType::String),
+                },
+            ],
+            returns: ApiType::Null,
+        };
+        let operation = operation(&command, &BTreeMap::new());
+        assert_eq!(operation["summary"], "Reword a commit.");
+        assert_eq!(
+            operation["requestBody"]["content"]["application/json"]["schema"]["required"],
+            json!(["projectId"])
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Opt-in remote mode, which admits clients from other machines once they authenticated.
+//!
+//! By default, `but-server` only talks to loopback clients. In remote mode, clients may connect
+//! from anywhere, but have to present a bearer token or a client certificate signed by a
+//! configured CA, and browsers may only use it from localhost or the explicitly allowed origins.
+
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{Context as _, bail};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use rustls::{
+    RootCertStore, ServerConfig,
+    crypto::CryptoProvider,
+    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject as _},
+    server::WebPkiClientVerifier,
+};
+
+use crate::is_localhost_origin;
+
+/// The query parameter that carries the bearer token for WebSocket upgrades, as browsers can't set
+/// headers on them.
+const TOKEN_QUERY_PARAMETER: &str = "token";
+
+/// Configuration for serving clients on other machines.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteConfig {
+    /// The token clients must send as `Authorization: Bearer <token>`.
+    ///
+    /// If neither this nor [`Self::client_ca`] is set, a random token is generated on startup.
+    pub auth_token: Option<String>,
+    /// A PEM file with the CAs that client certificates must be signed by.
+    ///
+    /// Requires [`Config::tls`](crate::Config::tls), as client certificates are verified during the
+    /// TLS handshake.
+    pub client_ca: Option<PathBuf>,
+    /// Origins like `https://gitbutler.example.com` that browsers may use the server from, in
+    /// addition to localhost.
+    pub allowed_origins: Vec<String>,
+}
+
+/// The certificate and private key to terminate TLS with.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// A PEM file with the server certificate, followed by its intermediate certificates.
+    pub cert: PathBuf,
+    /// A PEM file with the private key of the server certificate.
+    pub key: PathBuf,
+}
+
+/// The origins that browsers may send requests from.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AllowedOrigins(Arc<Vec<String>>);
+
+impl AllowedOrigins {
+    pub(crate) fn new(origins: &[String]) -> anyhow::Result<Self> {
+        origins
+            .iter()
+            .map(|origin| normalize_origin(origin))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(|origins| Self(Arc::new(origins)))
+    }
+
+    /// Return `true` if `origin` is a localhost origin or one of the allowed ones.
+    pub(crate) fn allows(&self, origin: &[u8]) -> bool {
+        if is_localhost_origin(origin) {
+            return true;
+        }
+        let Ok(origin) = std::str::from_utf8(origin) else {
+            return false;
+        };
+        self.0
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(origin))
+    }
+}
+
+/// Validate `origin` as `scheme://host[:port]`, and strip a trailing slash that is easily added by
+/// accident but never sent by browsers.
+fn normalize_origin(origin: &str) -> anyhow::Result<String> {
+    let origin = origin.trim().trim_end_matches('/');
+    let Some((scheme, authority)) = origin.split_once("://") else {
+        bail!("Allowed origin '{origin}' must start with http:// or https://");
+    };
+    if !matches!(scheme, "http" | "https") {
+        bail!("Allowed origin '{origin}' must start with http:// or https://");
+    }
+    if authority.is_empty() || authority.contains(['/', '?', '#', '*']) {
+        bail!("Allowed origin '{origin}' must be a scheme and host without path or wildcards");
+    }
+    Ok(origin.to_owned())
+}
+
+/// What a client needs to present to be let in.
+#[derive(Clone)]
+pub(crate) struct Authentication {
+    /// The expected bearer token, if token authentication is enabled.
+    token: Option<Arc<str>>,
+}
+
+impl Authentication {
+    pub(crate) fn new(token: Option<String>) -> Self {
+        Authentication {
+            token: token.map(Into::into),
+        }
+    }
+
+    /// Return `true` if `req` carries the expected token, or if no token is required.
+    fn admits(&self, req: &axum::extract::Request<Body>) -> bool {
+        let Some(expected) = self.token.as_deref() else {
+            return true;
+        };
+        let from_header = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if let Some(token) = from_header {
+            return constant_time_eq(token.trim().as_bytes(), expected.as_bytes());
+        }
+        is_websocket_upgrade(req)
+            && req
+                .uri()
+                .query()
+                .into_iter()
+                .flat_map(|query| query.split('&'))
+                .filter_map(|pair| pair.split_once('='))
+                .any(|(name, token)| {
+                    // Clients percent-encode the token, like `encodeURIComponent()` does.
+                    name == TOKEN_QUERY_PARAMETER
+                        && urlencoding::decode(token).is_ok_and(|token| {
+                            constant_time_eq(token.as_bytes(), expected.as_bytes())
+                        })
+                })
+    }
+}
+
+fn is_websocket_upgrade(req: &axum::extract::Request<Body>) -> bool {
+    req.headers()
+        .get(header::UPGRADE)
+        .is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"websocket"))
+}
+
+/// Compare `a` and `b` in time that only depends on their length, so tokens can't be guessed byte
+/// by byte from response times.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Middleware for remote mode, which replaces the localhost-only checks.
+///
+/// Requests from a browser must come from an allowed origin, and all requests must carry the
+/// bearer token if one is configured. Client certificates are checked during the TLS handshake
+/// already. As every request has to be authenticated, there is no need to check the `Host` header
+/// against DNS rebinding: the attacker's page doesn't know the token.
+pub(crate) async fn remote_middleware(
+    State((origins, auth)): State<(AllowedOrigins, Authentication)>,
+    req: axum::extract::Request<Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if let Some(origin) = req.headers().get(header::ORIGIN)
+        && !origins.allows(origin.as_bytes())
+    {
+        tracing::warn!(
+            "Rejected request with disallowed Origin: {}",
+            String::from_utf8_lossy(origin.as_bytes())
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+    if !auth.admits(&req) {
+        tracing::warn!("Rejected unauthenticated request to {}", req.uri().path());
+        return Ok((
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response());
+    }
+    Ok(next.run(req).await)
+}
+
+/// Build the TLS configuration from the PEM files in `tls`, requiring client certificates signed
+/// by a CA in `client_ca` if it is set.
+pub(crate) fn server_config(
+    tls: &TlsConfig,
+    client_ca: Option<&std::path::Path>,
+) -> anyhow::Result<ServerConfig> {
+    let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let certs = CertificateDer::pem_file_iter(&tls.cert)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| {
+            format!(
+                "Could not read the TLS certificate from {}",
+                tls.cert.display()
+            )
+        })?;
+    let key = PrivateKeyDer::from_pem_file(&tls.key).with_context(|| {
+        format!(
+            "Could not read the TLS private key from {}",
+            tls.key.display()
+        )
+    })?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?;
+    let builder = match client_ca {
+        Some(client_ca) => builder.with_client_cert_verifier(client_verifier(client_ca, provider)?),
+        None => builder.with_no_client_auth(),
+    };
+    let mut config = builder
+        .with_single_cert(certs, key)
+        .context("The TLS certificate doesn't match its private key")?;
+    // WebSockets are only served over HTTP/1.1.
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+fn client_verifier(
+    client_ca: &std::path::Path,
+    provider: Arc<CryptoProvider>,
+) -> anyhow::Result<Arc<dyn rustls::server::danger::ClientCertVerifier>> {
+    let mut roots = RootCertStore::empty();
+    for cert in CertificateDer::pem_file_iter(client_ca).with_context(|| {
+        format!(
+            "Could not read the client CA certificates from {}",
+            client_ca.display()
+        )
+    })? {
+        roots.add(cert?)?;
+    }
+    if roots.is_empty() {
+        bail!("{} contains no CA certificates", client_ca.display());
+    }
+    Ok(WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider).build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(uri: &str, headers: &[(header::HeaderName, &str)]) -> axum::extract::Request<Body> {
+        let mut req = axum::http::Request::builder().uri(uri);
+        for (name, value) in headers {
+            req = req.header(name, *value);
+        }
+        req.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn allowed_origins_extend_localhost() {
+        let origins = AllowedOrigins::new(&["https://gitbutler.example.com/".into()]).unwrap();
+        assert!(origins.allows(b"http://localhost:1420"));
+        assert!(origins.allows(b"https://gitbutler.example.com"));
+        assert!(origins.allows(b"https://GitButler.example.com"));
+
+        assert!(!origins.allows(b"http://gitbutler.example.com"));
+        assert!(!origins.allows(b"https://gitbutler.example.com.evil.com"));
+        assert!(!origins.allows(b"https://evil.com"));
+    }
+
+    #[test]
+    fn allowed_origins_must_be_plain_origins() {
+        for invalid in [
+            "gitbutler.example.com",
+            "ftp://gitbutler.example.com",
+            "https://",
+            "https://gitbutler.example.com/app",
+            "https://*.example.com",
+        ] {
+            assert!(AllowedOrigins::new(&[invalid.into()]).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn token_is_accepted_from_the_authorization_header() {
+        let auth = Authentication::new(Some("secret".into()));
+        assert!(auth.admits(&request(
+            "/get_user",
+            &[(header::AUTHORIZATION, "Bearer secret")]
+        )));
+
+        assert!(!auth.admits(&request("/get_user", &[])));
+        assert!(!auth.admits(&request(
+            "/get_user",
+            &[(header::AUTHORIZATION, "Bearer secre")]
+        )));
+        assert!(!auth.admits(&request(
+            "/get_user",
+            &[(header::AUTHORIZATION, "Basic secret")]
+        )));
+    }
+
+    #[test]
+    fn token_is_accepted_from_the_query_only_for_websockets() {
+        let auth = Authentication::new(Some("secret".into()));
+        assert!(auth.admits(&request(
+            "/ws?token=secret",
+            &[(header::UPGRADE, "websocket")]
+        )));
+
+        assert!(!auth.admits(&request("/get_user?token=secret", &[])));
+        assert!(!auth.admits(&request(
+            "/ws?token=wrong",
+            &[(header::UPGRADE, "websocket")]
+        )));
+    }
+
+    #[test]
+    fn token_in_the_query_is_percent_decoded() {
+        let auth = Authentication::new(Some("a+b/c=".into()));
+        assert!(auth.admits(&request(
+            "/ws?token=a%2Bb%2Fc%3D",
+            &[(header::UPGRADE, "websocket")]
+        )));
+
+        assert!(
+            !auth.admits(&request(
+                "/ws?token=a%20b%2Fc%3D",
+                &[(header::UPGRADE, "websocket")]
+            )),
+            "`+` is only a space in forms, and `encodeURIComponent()` encodes it"
+        );
+    }
+
+    #[test]
+    fn everything_is_admitted_without_token() {
+        let auth = Authentication::new(None);
+        assert!(auth.admits(&request("/get_user", &[])));
+    }
+}
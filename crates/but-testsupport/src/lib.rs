@@ -13,6 +13,9 @@ use gix_testtools::{Creation, FixtureState, PostResult, tempfile};
 mod in_memory_meta;
 pub use in_memory_meta::{InMemoryRefMetadata, InMemoryRefMetadataHandle, StackState};
 
+mod mock_http;
+pub use mock_http::{MockHttpRequest, MockHttpResponse, mock_http_server};
+
 #[cfg(feature = "sandbox")]
 mod sandbox;
 #[cfg(feature = "sandbox")]
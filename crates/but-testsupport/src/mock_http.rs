@@ -0,0 +1,122 @@
+use std::{
+    io::{ErrorKind, Read as _, Write as _},
+    net::TcpListener,
+    sync::mpsc,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+/// A canned response of a [`mock_http_server()`], sent to the request it expects.
+pub struct MockHttpResponse {
+    /// The method the request is expected to use, like `GET`.
+    pub method: &'static str,
+    /// The expected path of the request, including the query.
+    pub path: String,
+    /// The HTTP status code to respond with.
+    pub status: u16,
+    /// The body to respond with, served as JSON.
+    pub body: String,
+}
+
+impl MockHttpResponse {
+    /// Respond with `200 OK` and `body` to a `method` request to `path`.
+    pub fn ok(method: &'static str, path: impl Into<String>, body: impl Into<String>) -> Self {
+        MockHttpResponse {
+            method,
+            path: path.into(),
+            status: 200,
+            body: body.into(),
+        }
+    }
+
+    /// Respond with `status` instead.
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+}
+
+/// A request as received by a [`mock_http_server()`].
+pub struct MockHttpRequest {
+    /// The request line and all headers, up to and including the empty line.
+    pub headers: String,
+    /// The request body, as long as announced by `Content-Length`.
+    pub body: String,
+}
+
+/// Serve `responses` in order on a local port, one connection each, asserting the method and path
+/// of each request and forwarding it to the returned receiver.
+///
+/// Returns the origin to send requests to, like `http://127.0.0.1:1234`, the receiver of all requests,
+/// and the server thread which panics if a request doesn't arrive or doesn't match. Join it to fail the test.
+pub fn mock_http_server(
+    responses: Vec<MockHttpResponse>,
+) -> (String, mpsc::Receiver<MockHttpRequest>, JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (requests_tx, requests_rx) = mpsc::channel();
+    let server = std::thread::spawn(move || {
+        for expected in responses {
+            let deadline = Instant::now() + Duration::from_secs(2);
+            let mut stream = loop {
+                match listener.accept() {
+                    Ok((stream, _)) => break stream,
+                    Err(err)
+                        if err.kind() == ErrorKind::WouldBlock && Instant::now() < deadline =>
+                    {
+                        std::thread::sleep(Duration::from_millis(5));
+                    }
+                    Err(err) => panic!("expected request to {}: {err}", expected.path),
+                }
+            };
+            stream.set_nonblocking(false).unwrap();
+
+            let mut request = Vec::new();
+            let mut chunk = [0; 1024];
+            let header_end = loop {
+                if let Some(pos) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                    break pos + 4;
+                }
+                let read = stream.read(&mut chunk).unwrap();
+                assert_ne!(read, 0, "request should include complete HTTP headers");
+                request.extend_from_slice(&chunk[..read]);
+            };
+            let headers = String::from_utf8(request[..header_end].to_vec()).unwrap();
+            let content_length = headers
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            while request.len() < header_end + content_length {
+                let read = stream.read(&mut chunk).unwrap();
+                assert_ne!(read, 0, "request body should be complete");
+                request.extend_from_slice(&chunk[..read]);
+            }
+
+            let mut request_line = headers.lines().next().unwrap().split_whitespace();
+            assert_eq!(request_line.next(), Some(expected.method));
+            assert_eq!(
+                request_line.next(),
+                Some(expected.path.as_str()),
+                "client requests the expected endpoint"
+            );
+            let body = String::from_utf8(request[header_end..].to_vec()).unwrap();
+            // The receiver may have been dropped if the test doesn't care about requests.
+            requests_tx.send(MockHttpRequest { headers, body }).ok();
+
+            write!(
+                stream,
+                "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                expected.status,
+                expected.body.len(),
+                expected.body
+            )
+            .unwrap();
+        }
+    });
+    (format!("http://{addr}"), requests_rx, server)
+}
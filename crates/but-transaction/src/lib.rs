@@ -918,6 +918,38 @@ where
         })
     }
 
+    /// Split `commit` into `pieces`, see [`but_workspace::commit::split_commit()`].
+    ///
+    /// Returns the commits of the pieces in order, starting with the rewritten `commit`,
+    /// followed by the commit with the changes that weren't part of any piece, if there were any.
+    pub fn split_commit(
+        &mut self,
+        commit: ObjectId,
+        pieces: Vec<but_workspace::commit::SplitPiece>,
+    ) -> anyhow::Result<Vec<CommitIdentifiers>> {
+        let context_lines = self.context_lines();
+        self.rebase(|editor, commit_mappings| {
+            let but_workspace::commit::SplitCommitOutcome {
+                rebase,
+                piece_selectors,
+                remainder_selector,
+            } = but_workspace::commit::split_commit(
+                editor,
+                commit_mappings.map(commit),
+                pieces,
+                context_lines,
+            )?;
+
+            let commits = piece_selectors
+                .into_iter()
+                .chain(remainder_selector)
+                .map(|selector| rebase.lookup_commit(selector))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            Ok((commits, MaterializeWithoutCheckout::No, rebase))
+        })
+    }
+
     /// Look up a commit that has been rewritten as part of a rebase.
     ///
     /// In most cases this shouldn't be necessary. See [`with_transaction`] for more details.
@@ -236,6 +236,7 @@ fn local_commits_gix(
             author,
             change_id: commit.change_id().to_string(),
             gerrit_review_url: None,
+            signature: None,
         });
     }
     Ok(out)
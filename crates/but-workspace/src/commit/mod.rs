@@ -122,6 +122,8 @@ pub mod discard_commit;
 pub use discard_commit::discard_commits;
 pub mod squash_commits;
 pub use squash_commits::{SquashCommitsOutcome, squash_commits};
+pub mod split_commit;
+pub use split_commit::{SplitCommitOutcome, SplitPiece, split_commit};
 
 /// A minimal stack for use by [WorkspaceCommit::new_from_stacks()].
 #[derive(Clone)]
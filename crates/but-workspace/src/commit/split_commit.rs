@@ -0,0 +1,279 @@
+//! An action to split a commit into several commits
+
+use anyhow::{Result, bail};
+use bstr::{BString, ByteSlice};
+use but_core::{DiffSpec, HunkHeader, RefMetadata, UnifiedPatch};
+use but_rebase::{
+    commit::DateMode,
+    graph_rebase::{
+        Editor, Selector, Step, SuccessfulRebase, ToCommitSelector, mutate::InsertSide,
+    },
+};
+
+use crate::commit::{
+    MoveChangesOutcome, insert_blank_commit, move_changes_between_commits, reword,
+};
+
+/// One of the commits to split a commit into.
+#[derive(Debug, Clone)]
+pub struct SplitPiece {
+    /// The changes of the original commit that go into this piece, relative to the
+    /// original commit's first parent.
+    pub changes: Vec<DiffSpec>,
+    /// The message of the commit.
+    pub message: BString,
+}
+
+/// The result of a [`split_commit()`] operation.
+#[derive(Debug)]
+pub struct SplitCommitOutcome<'ws, 'meta, M: RefMetadata> {
+    /// The successful rebase result
+    pub rebase: SuccessfulRebase<'ws, 'meta, M>,
+    /// Selectors pointing to the commits of the pieces, in the order of the pieces.
+    /// The first one is the commit that was split.
+    pub piece_selectors: Vec<Selector>,
+    /// Selector pointing to the commit with all changes that aren't part of any piece,
+    /// or `None` if there were no such changes.
+    pub remainder_selector: Option<Selector>,
+}
+
+/// Split `commit` into one commit per piece, stacked in the order of `pieces`.
+///
+/// The first piece stays in place of `commit` and keeps its change-id, and each of the
+/// following pieces becomes a new commit on top of the one before it. Changes that aren't
+/// part of any piece go into one more commit on top, which keeps the message of `commit`.
+/// Descendants and references of `commit` end up on top of the last of these commits.
+///
+/// All `changes` are relative to `commit` and its first parent, so hunks of the same file
+/// may go into different pieces.
+///
+/// It's an error if `commit` is conflicted, if any change can't be found in it or is part
+/// of more than one piece, if a piece has no changes, or if there is nothing to split as
+/// the only piece has all changes of `commit`.
+pub fn split_commit<'ws, 'meta, M: RefMetadata>(
+    editor: Editor<'ws, 'meta, M>,
+    commit: impl ToCommitSelector,
+    pieces: Vec<SplitPiece>,
+    context_lines: u32,
+) -> Result<SplitCommitOutcome<'ws, 'meta, M>> {
+    let (original_selector, original) = editor.find_selectable_commit(commit)?;
+    if original.clone().attach(editor.repo()).is_conflicted() {
+        bail!("Cannot split a conflicted commit");
+    }
+    let mut pieces = pieces.into_iter();
+    let Some(first_piece) = pieces.next() else {
+        bail!("Need at least one piece to split the commit into");
+    };
+    let pieces: Vec<_> = pieces.collect();
+
+    let mut files = files_with_hunks(editor.repo(), &original, context_lines)?;
+    for (idx, piece) in std::iter::once(&first_piece).chain(&pieces).enumerate() {
+        if piece.changes.is_empty() {
+            bail!("Piece {} doesn't contain any changes", idx + 1);
+        }
+        for change in &piece.changes {
+            let Some(file) = files.iter_mut().find(|file| file.spec.path == change.path) else {
+                bail!("Failed to extract described changes from the commit to split");
+            };
+            file.claim(&change.hunk_headers)?;
+        }
+    }
+    let remainder: Vec<_> = files.iter().filter_map(FileHunks::unclaimed).collect();
+    if pieces.is_empty() && remainder.is_empty() {
+        bail!("Nothing to split, as the only piece has all changes of the commit");
+    }
+
+    let author = original.author.clone();
+    let original_message = original.message.clone();
+    let mut editor = editor;
+    let mut previous_selector = original_selector;
+    let mut piece_selectors = vec![original_selector];
+    let mut remainder_selector = None;
+    let new_commits = pieces
+        .into_iter()
+        .map(|piece| (piece.changes, piece.message, false))
+        .chain((!remainder.is_empty()).then(|| (remainder, original_message, true)));
+    for (changes, message, is_remainder) in new_commits {
+        let changes = whole_files_where_possible(&files, changes);
+        let (rebase, blank_selector) =
+            insert_blank_commit(editor, InsertSide::Above, previous_selector)?;
+        let mut inner_editor = rebase.into_editor();
+        // Hunks are selected in the diff of the original commit, but the commit to take them
+        // from has lost the hunks of the pieces before, so they have to be found again.
+        let changes = hunks_in_commit(&inner_editor, original_selector, changes, context_lines)?;
+        let MoveChangesOutcome { rebase, .. } = move_changes_between_commits(
+            inner_editor,
+            original_selector,
+            blank_selector,
+            changes,
+            context_lines,
+        )?;
+        inner_editor = rebase.into_editor();
+
+        // A blank commit is authored by whoever splits, but the changes are by the original author.
+        let (selector, mut commit) = inner_editor.find_selectable_commit(blank_selector)?;
+        commit.message = message;
+        commit.author = author.clone();
+        let new_id = inner_editor.new_commit(commit, DateMode::CommitterUpdateAuthorKeep)?;
+        inner_editor.replace(selector, Step::new_pick(new_id))?;
+        editor = inner_editor;
+
+        previous_selector = blank_selector;
+        if is_remainder {
+            remainder_selector = Some(blank_selector);
+        } else {
+            piece_selectors.push(blank_selector);
+        }
+    }
+
+    let (rebase, _) = reword(editor, original_selector, first_piece.message.as_bstr())?;
+
+    Ok(SplitCommitOutcome {
+        rebase,
+        piece_selectors,
+        remainder_selector,
+    })
+}
+
+/// A file changed by the commit to split, along with the hunks that can be selected in it.
+struct FileHunks {
+    spec: DiffSpec,
+    /// All hunks of the file's diff, or nothing if it can only be selected as a whole.
+    hunks: Vec<HunkHeader>,
+    /// The hunks that are part of a piece already.
+    claimed: Vec<HunkHeader>,
+    claimed_whole: bool,
+}
+
+impl FileHunks {
+    fn claim(&mut self, hunks: &[HunkHeader]) -> Result<()> {
+        let path = self.spec.path.as_bstr();
+        if self.claimed_whole || (hunks.is_empty() && !self.claimed.is_empty()) {
+            bail!("Changes of '{path}' are part of more than one piece");
+        }
+        if hunks.is_empty() {
+            self.claimed_whole = true;
+            return Ok(());
+        }
+        for hunk in hunks {
+            if !self.hunks.contains(hunk) {
+                bail!("Failed to extract described changes from the commit to split");
+            }
+            if self.claimed.contains(hunk) {
+                bail!("Changes of '{path}' are part of more than one piece");
+            }
+            self.claimed.push(*hunk);
+        }
+        Ok(())
+    }
+
+    /// The changes of this file that aren't part of any piece, if there are any.
+    fn unclaimed(&self) -> Option<DiffSpec> {
+        if self.claimed_whole {
+            return None;
+        }
+        if self.claimed.is_empty() {
+            return Some(self.spec.clone());
+        }
+        let hunk_headers: Vec<_> = self
+            .hunks
+            .iter()
+            .filter(|hunk| !self.claimed.contains(hunk))
+            .copied()
+            .collect();
+        (!hunk_headers.is_empty()).then(|| DiffSpec {
+            hunk_headers,
+            ..self.spec.clone()
+        })
+    }
+}
+
+fn files_with_hunks(
+    repo: &gix::Repository,
+    commit: &but_core::CommitOwned,
+    context_lines: u32,
+) -> Result<Vec<FileHunks>> {
+    let changes = but_core::diff::tree_changes(repo, commit.parents.first().copied(), commit.id)?;
+    let mut out = Vec::with_capacity(changes.len());
+    for change in changes {
+        let hunks = match change.unified_patch(repo, context_lines)? {
+            Some(UnifiedPatch::Patch {
+                hunks,
+                is_result_of_binary_to_text_conversion: false,
+                ..
+            }) => hunks.iter().map(HunkHeader::from).collect(),
+            _ => Vec::new(),
+        };
+        out.push(FileHunks {
+            spec: DiffSpec::from(&change),
+            hunks,
+            claimed: Vec::new(),
+            claimed_whole: false,
+        });
+    }
+    Ok(out)
+}
+
+/// Select files of which all hunks are selected as a whole instead, which also works for
+/// additions and deletions.
+fn whole_files_where_possible(files: &[FileHunks], changes: Vec<DiffSpec>) -> Vec<DiffSpec> {
+    let mut changes = crate::flatten_diff_specs(changes);
+    for change in &mut changes {
+        if let Some(file) = files.iter().find(|file| file.spec.path == change.path)
+            && file
+                .hunks
+                .iter()
+                .all(|hunk| change.hunk_headers.contains(hunk))
+        {
+            change.hunk_headers.clear();
+        }
+    }
+    changes
+}
+
+/// Find the hunks of `changes`, taken from the diff of the original commit, in the diff of the
+/// commit at `selector`, which lacks some of the original hunks. All hunks keep their place in
+/// the unchanged parent though, so they can be found by it.
+fn hunks_in_commit<M: RefMetadata>(
+    editor: &Editor<'_, '_, M>,
+    selector: Selector,
+    changes: Vec<DiffSpec>,
+    context_lines: u32,
+) -> Result<Vec<DiffSpec>> {
+    if changes.iter().all(|change| change.hunk_headers.is_empty()) {
+        return Ok(changes);
+    }
+    let (_, commit) = editor.find_selectable_commit(selector)?;
+    let repo = editor.repo();
+    let tree_changes =
+        but_core::diff::tree_changes(repo, commit.parents.first().copied(), commit.id)?;
+    changes
+        .into_iter()
+        .map(|mut change| {
+            if change.hunk_headers.is_empty() {
+                return Ok(change);
+            }
+            let hunks: Vec<HunkHeader> = match tree_changes
+                .iter()
+                .find(|tree_change| tree_change.path == change.path)
+                .map(|tree_change| tree_change.unified_patch(repo, context_lines))
+                .transpose()?
+                .flatten()
+            {
+                Some(UnifiedPatch::Patch { hunks, .. }) => {
+                    hunks.iter().map(HunkHeader::from).collect()
+                }
+                _ => Vec::new(),
+            };
+            for header in &mut change.hunk_headers {
+                let Some(hunk) = hunks.iter().find(|hunk| {
+                    hunk.old_start == header.old_start && hunk.old_lines == header.old_lines
+                }) else {
+                    bail!("Failed to extract described changes from the commit to split");
+                };
+                *header = *hunk;
+            }
+            Ok(change)
+        })
+        .collect()
+}
@@ -50,6 +50,9 @@ pub struct Commit {
     pub change_id: Option<but_core::ChangeId>,
     /// Optional URL to the Gerrit review for this commit, if applicable.
     pub gerrit_review_url: Option<String>,
+    /// The verified signature of the commit, or `None` if signatures weren't verified.
+    /// See [`Options::verify_signatures`].
+    pub signature: Option<but_core::commit::signature::Signature>,
 }
 
 impl std::fmt::Debug for Commit {
@@ -90,6 +93,7 @@ impl From<but_core::Commit<'_>> for Commit {
             refs: Vec::new(),
             flags: StackCommitFlags::empty(),
             gerrit_review_url: None,
+            signature: None,
         }
     }
 }
@@ -272,6 +276,10 @@ pub struct Options<'db> {
     pub expensive_commit_info: bool,
     /// Configure whether Gerrit metadata should augment the standard graph-derived result.
     pub gerrit_mode: GerritMode<'db>,
+    /// Verify the signature of each local commit and store it in [`Commit::signature`].
+    ///
+    /// This runs the program configured for verification once per signed commit.
+    pub verify_signatures: bool,
 }
 
 /// A segment of a commit graph, representing a set of commits exclusively.
@@ -609,6 +617,9 @@ pub fn graph_to_ref_info(
     if let GerritMode::Enabled(metadata) = opts.gerrit_mode {
         info.apply_gerrit_metadata(metadata)?;
     }
+    if opts.verify_signatures {
+        info.verify_signatures(repo)?;
+    }
     Ok(info)
 }
 
@@ -737,6 +748,19 @@ impl RefInfo {
         }
         Ok(())
     }
+
+    /// Verify the signature of every local commit, like `git log --show-signature` would.
+    fn verify_signatures(&mut self, repo: &gix::Repository) -> anyhow::Result<()> {
+        for commit in self
+            .stacks
+            .iter_mut()
+            .flat_map(|stack| stack.segments.iter_mut())
+            .flat_map(|segment| segment.commits.iter_mut())
+        {
+            commit.inner.signature = Some(but_core::commit::signature::verify(repo, commit.id)?);
+        }
+        Ok(())
+    }
 }
 
 /// Derive push status for a Gerrit-enriched segment.
@@ -74,6 +74,8 @@ pub struct Commit {
     /// Optional URL to the Gerrit review for this commit, if applicable.
     /// Only populated if Gerrit mode is enabled and the commit has an associated review.
     pub gerrit_review_url: Option<String>,
+    /// The verified signature of the commit, or `None` if signatures weren't verified.
+    pub signature: Option<but_core::commit::signature::Signature>,
 }
 #[cfg(feature = "export-schema")]
 but_schemars::register_sdk_type!(Commit);
@@ -115,6 +117,7 @@ impl Commit {
             author: author.to_ref(&mut TimeBuf::default()).trim().into(),
             change_id,
             gerrit_review_url: None,
+            signature: None,
         }
     }
 }
@@ -319,6 +322,7 @@ impl From<&crate::ref_info::Commit> for ui::UpstreamCommit {
             has_conflicts: _,
             change_id,
             gerrit_review_url: _,
+            signature: _,
         }: &crate::ref_info::Commit,
     ) -> Self {
         ui::UpstreamCommit {
@@ -352,6 +356,7 @@ impl From<&LocalCommit> for ui::Commit {
                     has_conflicts,
                     change_id,
                     gerrit_review_url,
+                    signature,
                 },
             relation,
         }: &LocalCommit,
@@ -374,6 +379,7 @@ impl From<&LocalCommit> for ui::Commit {
                     but_core::commit::Headers::synthetic_change_id_from_commit_id(*id).to_string()
                 }),
             gerrit_review_url: gerrit_review_url.clone(),
+            signature: signature.clone(),
         }
     }
 }
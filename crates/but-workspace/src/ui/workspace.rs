@@ -321,5 +321,6 @@ fn commit_for_ui(commit: ref_info::Commit, state: super::CommitState) -> super::
         author: commit.author.to_ref(&mut TimeBuf::default()).into(),
         change_id,
         gerrit_review_url: commit.gerrit_review_url,
+        signature: commit.signature,
     }
 }
@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+
+set -eu -o pipefail
+
+git init
+
+git checkout -b main
+echo "base" > base.txt
+seq 1 10 > lines.txt
+git add .
+git commit -m "base"
+
+git checkout -b feature
+echo "a" > a.txt
+echo "b" > b.txt
+echo "c" > c.txt
+{ echo one; seq 2 9; echo ten; } > lines.txt
+git add .
+git commit -m "add three files"
+
+echo "d" > d.txt
+echo "e" > e.txt
+git add .
+git commit -m "add d and e"
@@ -6,6 +6,7 @@ mod insert_blank_commit;
 mod move_changes;
 mod move_commit;
 mod reword;
+mod split_commit;
 mod squash_commits;
 mod uncommit_changes;
 
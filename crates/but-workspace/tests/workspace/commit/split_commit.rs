@@ -0,0 +1,340 @@
+use anyhow::Result;
+use bstr::ByteSlice;
+use but_core::{DiffSpec, HunkHeader};
+use but_rebase::graph_rebase::Editor;
+use but_workspace::commit::{SplitPiece, split_commit};
+use gix::prelude::ObjectIdExt;
+
+use crate::ref_info::with_workspace_commit::utils::named_writable_scenario_with_description_and_graph as writable_scenario;
+
+fn diff_spec_for_file(path: &str) -> DiffSpec {
+    DiffSpec {
+        previous_path: None,
+        path: path.into(),
+        hunk_headers: vec![],
+    }
+}
+
+fn piece(paths: &[&str], message: &str) -> SplitPiece {
+    SplitPiece {
+        changes: paths.iter().copied().map(diff_spec_for_file).collect(),
+        message: message.into(),
+    }
+}
+
+/// A single changed line at `line` of `lines.txt`, as seen with zero context lines.
+fn line_of_lines_txt(line: u32) -> DiffSpec {
+    DiffSpec {
+        hunk_headers: vec![HunkHeader {
+            old_start: line,
+            old_lines: 1,
+            new_start: line,
+            new_lines: 1,
+        }],
+        ..diff_spec_for_file("lines.txt")
+    }
+}
+
+/// List the first-parent history of `rev` as `<message>: <files in tree>`, without hashes.
+fn history(repo: &gix::Repository, rev: &str) -> Result<String> {
+    let mut out = String::new();
+    let mut next = Some(repo.rev_parse_single(rev)?.detach());
+    while let Some(id) = next {
+        let commit = repo.find_commit(id)?;
+        let files = commit
+            .tree()?
+            .iter()
+            .map(|entry| entry.map(|entry| entry.filename().to_str_lossy().into_owned()))
+            .collect::<Result<Vec<_>, _>>()?;
+        out.push_str(&format!(
+            "{}: {}\n",
+            commit.message()?.summary(),
+            files.join(", ")
+        ));
+        next = commit.parent_ids().next().map(|id| id.detach());
+    }
+    Ok(out)
+}
+
+fn lines_txt(repo: &gix::Repository, rev: &str) -> Result<String> {
+    let blob = repo.rev_parse_single(format!("{rev}:lines.txt").as_str())?;
+    Ok(blob.object()?.data.to_str_lossy().into_owned())
+}
+
+#[test]
+fn split_into_pieces_with_the_remainder_on_top() -> Result<()> {
+    let (_tmp, graph, repo, mut meta, _description, mut db) =
+        writable_scenario("split-commit", |_| {})?;
+    snapbox::assert_data_eq!(
+        history(&repo, "feature")?,
+        snapbox::str![[r#"
+add d and e: a.txt, b.txt, base.txt, c.txt, d.txt, e.txt, lines.txt
+add three files: a.txt, b.txt, base.txt, c.txt, lines.txt
+base: base.txt, lines.txt
+
+"#]]
+    );
+
+    let head_tree = repo.head_tree_id()?;
+    let original_id = repo.rev_parse_single("feature~1")?.detach();
+    let original_change_id = but_core::Commit::from_id(original_id.attach(&repo))?.change_id();
+
+    let mut ws = graph.into_workspace()?;
+    let editor = Editor::create(&mut ws, &mut meta, &repo, &mut db)?;
+    let outcome = split_commit(
+        editor,
+        original_id,
+        vec![piece(&["b.txt"], "add b"), piece(&["c.txt"], "add c")],
+        0,
+    )?;
+    let pieces = outcome
+        .piece_selectors
+        .iter()
+        .map(|selector| outcome.rebase.lookup_commit(*selector))
+        .collect::<Result<Vec<_>>>()?;
+    let remainder = outcome.rebase.lookup_commit(
+        outcome
+            .remainder_selector
+            .expect("a.txt and lines.txt weren't selected"),
+    )?;
+    outcome.rebase.materialize(Default::default())?;
+
+    snapbox::assert_data_eq!(
+        history(&repo, "feature")?,
+        snapbox::str![[r#"
+add d and e: a.txt, b.txt, base.txt, c.txt, d.txt, e.txt, lines.txt
+add three files: a.txt, b.txt, base.txt, c.txt, lines.txt
+add c: b.txt, base.txt, c.txt, lines.txt
+add b: b.txt, base.txt, lines.txt
+base: base.txt, lines.txt
+
+"#]]
+    );
+    assert_eq!(
+        head_tree,
+        repo.head_tree_id()?,
+        "the overall result is unchanged"
+    );
+
+    assert_eq!(pieces.len(), 2);
+    assert_eq!(repo.rev_parse_single("feature~3")?.detach(), pieces[0].id);
+    assert_eq!(repo.rev_parse_single("feature~2")?.detach(), pieces[1].id);
+    assert_eq!(repo.rev_parse_single("feature~1")?.detach(), remainder.id);
+    assert_eq!(
+        pieces[0].change_id, original_change_id,
+        "the first piece keeps the change-id of the commit that was split"
+    );
+    for commit in [&pieces[1], &remainder] {
+        assert_ne!(
+            commit.change_id, original_change_id,
+            "all other commits are new and have their own change-id"
+        );
+    }
+    assert_ne!(pieces[1].change_id, remainder.change_id);
+
+    Ok(())
+}
+
+#[test]
+fn split_head_commit_moves_the_branch_to_the_remainder() -> Result<()> {
+    let (_tmp, graph, repo, mut meta, _description, mut db) =
+        writable_scenario("split-commit", |_| {})?;
+
+    let head_id = repo.rev_parse_single("feature")?.detach();
+    let mut ws = graph.into_workspace()?;
+    let editor = Editor::create(&mut ws, &mut meta, &repo, &mut db)?;
+    split_commit(editor, head_id, vec![piece(&["e.txt"], "add e")], 0)?
+        .rebase
+        .materialize(Default::default())?;
+
+    snapbox::assert_data_eq!(
+        history(&repo, "feature")?,
+        snapbox::str![[r#"
+add d and e: a.txt, b.txt, base.txt, c.txt, d.txt, e.txt, lines.txt
+add e: a.txt, b.txt, base.txt, c.txt, e.txt, lines.txt
+add three files: a.txt, b.txt, base.txt, c.txt, lines.txt
+base: base.txt, lines.txt
+
+"#]]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn split_without_remainder_if_pieces_take_all_changes() -> Result<()> {
+    let (_tmp, graph, repo, mut meta, _description, mut db) =
+        writable_scenario("split-commit", |_| {})?;
+
+    let original_id = repo.rev_parse_single("feature~1")?.detach();
+    let mut ws = graph.into_workspace()?;
+    let editor = Editor::create(&mut ws, &mut meta, &repo, &mut db)?;
+    let outcome = split_commit(
+        editor,
+        original_id,
+        vec![
+            piece(&["a.txt", "b.txt"], "add a and b"),
+            piece(&["c.txt", "lines.txt"], "add c and spell out lines"),
+        ],
+        0,
+    )?;
+    assert!(outcome.remainder_selector.is_none());
+    outcome.rebase.materialize(Default::default())?;
+
+    snapbox::assert_data_eq!(
+        history(&repo, "feature")?,
+        snapbox::str![[r#"
+add d and e: a.txt, b.txt, base.txt, c.txt, d.txt, e.txt, lines.txt
+add c and spell out lines: a.txt, b.txt, base.txt, c.txt, lines.txt
+add a and b: a.txt, b.txt, base.txt, lines.txt
+base: base.txt, lines.txt
+
+"#]]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn split_hunks_of_a_file_into_different_commits() -> Result<()> {
+    let (_tmp, graph, repo, mut meta, _description, mut db) =
+        writable_scenario("split-commit", |_| {})?;
+
+    let head_tree = repo.head_tree_id()?;
+    let original_id = repo.rev_parse_single("feature~1")?.detach();
+    let mut ws = graph.into_workspace()?;
+    let editor = Editor::create(&mut ws, &mut meta, &repo, &mut db)?;
+    split_commit(
+        editor,
+        original_id,
+        vec![
+            SplitPiece {
+                changes: vec![line_of_lines_txt(10)],
+                message: "spell out the last line".into(),
+            },
+            SplitPiece {
+                changes: vec![line_of_lines_txt(1)],
+                message: "spell out the first line".into(),
+            },
+        ],
+        0,
+    )?
+    .rebase
+    .materialize(Default::default())?;
+
+    snapbox::assert_data_eq!(
+        history(&repo, "feature")?,
+        snapbox::str![[r#"
+add d and e: a.txt, b.txt, base.txt, c.txt, d.txt, e.txt, lines.txt
+add three files: a.txt, b.txt, base.txt, c.txt, lines.txt
+spell out the first line: base.txt, lines.txt
+spell out the last line: base.txt, lines.txt
+base: base.txt, lines.txt
+
+"#]]
+    );
+    assert_eq!(
+        lines_txt(&repo, "feature~3")?,
+        "1\n2\n3\n4\n5\n6\n7\n8\n9\nten\n"
+    );
+    assert_eq!(
+        lines_txt(&repo, "feature~2")?,
+        "one\n2\n3\n4\n5\n6\n7\n8\n9\nten\n",
+        "the second hunk is found even though the first one is gone from the commit"
+    );
+    assert_eq!(
+        head_tree,
+        repo.head_tree_id()?,
+        "the overall result is unchanged"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn split_with_all_changes_in_one_piece_is_an_error() -> Result<()> {
+    let (_tmp, graph, repo, mut meta, _description, mut db) =
+        writable_scenario("split-commit", |_| {})?;
+
+    let original_id = repo.rev_parse_single("feature~1")?.detach();
+    let mut ws = graph.into_workspace()?;
+    let editor = Editor::create(&mut ws, &mut meta, &repo, &mut db)?;
+    let err = split_commit(
+        editor,
+        original_id,
+        vec![SplitPiece {
+            changes: vec![
+                diff_spec_for_file("a.txt"),
+                diff_spec_for_file("b.txt"),
+                diff_spec_for_file("c.txt"),
+                line_of_lines_txt(1),
+                line_of_lines_txt(10),
+            ],
+            message: "everything".into(),
+        }],
+        0,
+    )
+    .err()
+    .expect("selecting all hunks of a file is the same as selecting the file");
+    assert_eq!(
+        err.to_string(),
+        "Nothing to split, as the only piece has all changes of the commit"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn split_rejects_pieces_without_changes_and_changes_in_multiple_pieces() -> Result<()> {
+    let (_tmp, graph, repo, mut meta, _description, mut db) =
+        writable_scenario("split-commit", |_| {})?;
+
+    let original_id = repo.rev_parse_single("feature~1")?.detach();
+    let mut ws = graph.into_workspace()?;
+    let editor = Editor::create(&mut ws, &mut meta, &repo, &mut db)?;
+    let err = split_commit(
+        editor,
+        original_id,
+        vec![piece(&["b.txt"], "add b"), piece(&[], "nothing")],
+        0,
+    )
+    .err()
+    .expect("the second piece is empty");
+    assert_eq!(err.to_string(), "Piece 2 doesn't contain any changes");
+
+    let editor = Editor::create(&mut ws, &mut meta, &repo, &mut db)?;
+    let err = split_commit(
+        editor,
+        original_id,
+        vec![piece(&["b.txt"], "add b"), piece(&["b.txt"], "add b again")],
+        0,
+    )
+    .err()
+    .expect("b.txt can only be in one piece");
+    assert_eq!(
+        err.to_string(),
+        "Changes of 'b.txt' are part of more than one piece"
+    );
+
+    let editor = Editor::create(&mut ws, &mut meta, &repo, &mut db)?;
+    let err = split_commit(
+        editor,
+        original_id,
+        vec![
+            piece(&["lines.txt"], "spell out lines"),
+            SplitPiece {
+                changes: vec![line_of_lines_txt(1)],
+                message: "spell out the first line".into(),
+            },
+        ],
+        0,
+    )
+    .err()
+    .expect("a hunk can't be taken from a file that was selected as a whole");
+    assert_eq!(
+        err.to_string(),
+        "Changes of 'lines.txt' are part of more than one piece"
+    );
+
+    Ok(())
+}
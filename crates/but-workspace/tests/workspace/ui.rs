@@ -290,7 +290,8 @@ TreeChanges {
                 "gravatarUrl": "https://www.gravatar.com/avatar/5c1e6d6e64e12aca17657581a48005d1?s=100&r=g&d=retro"
               },
               "changeId": "mvvzyyvvvuosslxwrvqpxopvomovrrmz",
-              "gerritReviewUrl": null
+              "gerritReviewUrl": null,
+              "signature": null
             }
           ],
           "commitsOnRemote": [
@@ -479,7 +480,8 @@ TreeChanges {
                 "gravatarUrl": "https://www.gravatar.com/avatar/5c1e6d6e64e12aca17657581a48005d1?s=100&r=g&d=retro"
               },
               "changeId": "mvvzyyvvvuosslxwrvqpxopvomovrrmz",
-              "gerritReviewUrl": null
+              "gerritReviewUrl": null,
+              "signature": null
             }
           ],
           "commitsOnRemote": [
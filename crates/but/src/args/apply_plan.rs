@@ -0,0 +1,52 @@
+//! Arguments for `apply-plan`.
+
+#![deny(missing_docs)]
+
+use std::path::PathBuf;
+
+/// Apply a plan of operations as a single undoable operation.
+///
+/// The plan is a JSON or YAML list of operations, each selected by its `op` field. Commits,
+/// branches and uncommitted changes are referred to by CLI IDs or names as shown by `but status`,
+/// and keep referring to the same objects after earlier operations rewrote them. Branches created
+/// by the plan can be referred to by name in later operations.
+///
+/// - `commit`: `branch`, `message`, optionally `changes` (defaults to all uncommitted changes)
+/// - `branch`: `name`, optionally `above` to stack it on an existing branch
+/// - `stack`: `branch`, `onto`
+/// - `move`: `commits`, and `branch` to move them to its top or a commit to place them `above` or
+///   `below`
+/// - `squash`: `commits`, `into`, optionally `message`
+/// - `reword`: `commit`, `message`
+///
+/// The whole plan is validated by running it in memory first. Only if all operations succeed is it
+/// applied, as one entry in the operation log that `but undo` reverts as a whole.
+///
+/// ## Examples
+///
+/// ```text
+/// - op: branch
+///   name: feature-tests
+///   above: feature
+/// - op: move
+///   commits: [tp, lm]
+///   branch: feature-tests
+/// - op: reword
+///   commit: ab
+///   message: Add the feature
+/// ```
+///
+/// For more details about CLI IDs, see `but help cli-ids`.
+#[derive(Debug, clap::Parser)]
+#[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+pub struct Platform {
+    /// The plan file, or `-` to read the plan from standard input.
+    ///
+    /// Files ending in `.json` are read as JSON, everything else as YAML.
+    #[clap(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Validate the plan and show what it would do without changing anything.
+    #[clap(long)]
+    pub dry_run: bool,
+}
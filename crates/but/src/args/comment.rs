@@ -1,9 +1,10 @@
 use crate::args::atoms::CliIdArg;
 
-/// Work with ephemeral comments anchored to lines in diffs.
+/// Work with comments anchored to lines in diffs.
 ///
 /// Comments are typically created in the GUI on a line of a diff — of an uncommitted file, or of
 /// a commit — and picked up here by agents, which act on them and archive them when done.
+/// Every comment starts a thread that can be replied to, and resolved once the conversation is over.
 /// Anchors follow the diff as it changes; comments whose anchored line disappears from the diff
 /// (for example because the change was committed or discarded) are archived automatically.
 #[derive(Debug, clap::Parser)]
@@ -33,8 +34,36 @@ pub enum Subcommands {
         /// How many seconds `--wait` blocks before giving up for this invocation.
         #[clap(long, value_name = "SECONDS", default_value_t = 60, requires = "wait")]
         timeout: u64,
+        /// Only list threads that aren't resolved.
+        #[clap(long)]
+        unresolved: bool,
+        /// Only list threads on commits of this branch.
+        #[clap(long, value_name = "BRANCH")]
+        branch: Option<String>,
+    },
+    /// Reply to a comment, adding the reply to its thread.
+    Reply {
+        /// The id of the comment or reply to answer. A unique prefix is enough.
+        id: String,
+        /// The reply text.
+        #[clap(short, long)]
+        message: String,
+    },
+    /// Resolve the thread a comment belongs to, once its conversation is over.
+    ///
+    /// Resolved threads are still listed unless `--unresolved` is used.
+    Resolve {
+        /// The id of a comment or reply in the thread. A unique prefix is enough.
+        id: String,
+    },
+    /// Mark a resolved thread as unresolved again.
+    Reopen {
+        /// The id of a comment or reply in the thread. A unique prefix is enough.
+        id: String,
     },
     /// Archive a comment, hiding it from all future listings.
+    ///
+    /// Archiving the comment that starts a thread archives its replies as well.
     Archive {
         /// The id of the comment to archive. A unique prefix is enough.
         id: String,
@@ -56,4 +85,23 @@ pub enum Subcommands {
         #[clap(long)]
         old: bool,
     },
+    /// Exchange comments on commits with teammates through the `refs/notes/gitbutler/comments` ref.
+    ///
+    /// Comments from the notes are merged into the local comments, and all local comments on
+    /// commits are written back to the notes. Share the ref with
+    /// `git push <remote> refs/notes/gitbutler/comments` and fetch it with
+    /// `git fetch <remote> +refs/notes/gitbutler/comments:refs/notes/gitbutler/comments`.
+    /// Set `gitbutler.commentNotes` to `true` to sync automatically whenever comments change.
+    Sync,
+    /// Import the inline comments of a forge review as comments on the commits of its branch.
+    ///
+    /// Each comment is anchored to the first commit of the branch whose diff has the commented
+    /// line, and follows it through amends and rebases from then on. Importing again adds new
+    /// comments and updates edited ones, so reviewer feedback can be read and answered with the
+    /// other `but _comment` commands.
+    #[cfg(feature = "legacy")]
+    Import {
+        /// The number of the review, like `42` for PR #42.
+        review: usize,
+    },
 }
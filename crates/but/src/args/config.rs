@@ -45,7 +45,7 @@ pub enum Subcommands {
 
     /// View and manage forge configuration.
     ///
-    /// Shows configured forge accounts (GitHub, GitLab, Bitbucket) and authentication status.
+    /// Shows configured forge accounts (GitHub, GitLab, Bitbucket, Azure DevOps, Gitea) and authentication status.
     /// Use subcommands to manage accounts or native GitHub stacked pull requests.
     ///
     /// ## Examples
@@ -528,7 +528,7 @@ impl UserConfigKey {
 /// Subcommands for `but config forge`
 #[derive(Debug, Clone, clap::Subcommand)]
 pub enum ForgeSubcommand {
-    /// Authenticate with your forge provider (GitHub, GitLab or Bitbucket).
+    /// Authenticate with your forge provider (GitHub, GitLab, Bitbucket, Azure DevOps or Gitea).
     ///
     /// This will guide you through the authentication process using either:
     /// GitHub
@@ -544,6 +544,14 @@ pub enum ForgeSubcommand {
     ///  - Atlassian API token with scopes (read:user:bitbucket,
     ///    read:repository:bitbucket, read:pullrequest:bitbucket,
     ///    write:pullrequest:bitbucket)
+    ///
+    /// Azure DevOps
+    ///  - Personal Access Token (PAT) for one organization, with the
+    ///    Code (Read & write) scope
+    ///
+    /// Gitea / Forgejo
+    ///  - Access token for any instance (e.g. Codeberg or self-hosted), with
+    ///    user (Read), repository and issue (Read and Write) scopes
     Auth,
 
     /// List authenticated forge accounts known to GitButler.
@@ -0,0 +1,50 @@
+//! Arguments for `exec`.
+
+#![deny(missing_docs)]
+
+use crate::args::atoms::CliIdArg;
+
+/// Run a command against every commit of a branch.
+///
+/// Each commit of the stack, from the bottom up to the tip of the given branch, is checked
+/// out in turn in a temporary worktree and the command is run there. The workspace and its
+/// uncommitted changes are left untouched. Once all commits were visited, the pass or fail
+/// of each commit is reported. Conflicted commits are skipped until they are resolved with
+/// `but resolve`.
+///
+/// The command is run with the temporary worktree as working directory, and may be a shell
+/// expression if it's given as a single argument.
+///
+/// With `--edit`, the run stops at the first failing commit and enters edit mode on it, so
+/// it can be fixed right away, just like `but resolve` does for conflicted commits.
+///
+/// ## Examples
+///
+/// Run the tests on every commit of a branch:
+///
+/// ```text
+/// but exec my-feature -- cargo test
+/// ```
+///
+/// Stop at the first commit that doesn't build and start editing it:
+///
+/// ```text
+/// but exec my-feature --edit -- "cargo check && cargo clippy"
+/// ```
+#[derive(Debug, clap::Parser)]
+#[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+pub struct Platform {
+    /// The branch (name or CLI ID) whose commits, and those of the branches below it, to run the command on.
+    pub branch: CliIdArg,
+    /// Stop at the first commit for which the command fails.
+    #[clap(long)]
+    pub fail_fast: bool,
+    /// Stop at the first commit for which the command fails and enter edit mode on it.
+    ///
+    /// Implies `--fail-fast`.
+    #[clap(long)]
+    pub edit: bool,
+    /// The command to run, along with its arguments.
+    #[clap(last = true, required = true, num_args = 1.., value_name = "COMMAND")]
+    pub command: Vec<String>,
+}
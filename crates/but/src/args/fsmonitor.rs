@@ -0,0 +1,45 @@
+//! Arguments for `fsmonitor`.
+
+#![deny(missing_docs)]
+
+/// Let Git ask GitButler's file watcher which files changed.
+///
+/// Once enabled, Git asks `but` for the files that changed since it last looked whenever it needs
+/// the status of the worktree, and only checks those instead of scanning the whole worktree. This
+/// makes commands like `git status` faster in large repositories, as long as the GitButler app or
+/// `but watch` runs for the repository; otherwise Git scans the worktree as usual.
+///
+/// Git also needs `core.untrackedCache` to skip directories without changes.
+///
+/// ## Examples
+///
+/// Let Git ask the file watcher:
+///
+/// ```text
+/// but fsmonitor enable
+/// ```
+#[derive(Debug, clap::Parser)]
+#[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+pub struct Platform {
+    /// What to do.
+    #[clap(subcommand)]
+    pub cmd: Subcommands,
+}
+
+/// The `fsmonitor` subcommands.
+#[derive(Debug, clap::Subcommand)]
+pub enum Subcommands {
+    /// Configure the repository so Git asks `but` which files changed.
+    Enable,
+    /// Undo `but fsmonitor enable`, so Git scans the worktree again.
+    Disable,
+    /// Answer a query of Git, which is what Git runs once enabled.
+    #[clap(hide = true)]
+    Hook {
+        /// The version of the hook protocol, of which only version 2 is supported.
+        version: u32,
+        /// The token returned by the previous query, naming the point in time to list changes since.
+        #[clap(default_value = "")]
+        token: String,
+    },
+}
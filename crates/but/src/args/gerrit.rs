@@ -0,0 +1,63 @@
+//! Arguments for `gerrit`.
+
+#![deny(missing_docs)]
+
+/// Arguments for the `but gerrit` command family.
+#[derive(Debug, clap::Parser)]
+pub struct Platform {
+    /// The subcommand to run, which defaults to `chain`.
+    #[clap(subcommand)]
+    pub cmd: Option<Subcommands>,
+}
+
+/// Subcommands of `but gerrit`.
+#[derive(Debug, clap::Subcommand)]
+pub enum Subcommands {
+    /// Show the relation chain of each stack.
+    ///
+    /// Every commit is shown with the Gerrit change it was pushed as, its current patchset
+    /// and whether the local commit is up-to-date with it, needs to be pushed, or whether
+    /// somebody else uploaded a newer patchset that can be pulled with `but gerrit pull`.
+    ///
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Chain,
+
+    /// Pull patchsets that others uploaded into the local stacks.
+    ///
+    /// The current patchset of each change replaces its local commit, and all commits
+    /// above it are rebased on top. The commit keeps its change-id, so it remains associated
+    /// with its Gerrit change. If the pulled patchset had to be rebased, the change needs to
+    /// be pushed again afterwards.
+    ///
+    /// Without arguments, each patchset uploaded by others is offered for pulling.
+    ///
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Pull {
+        /// The changes to pull, either by their Gerrit change number or by the id of the local commit.
+        #[clap(value_name = "CHANGE")]
+        changes: Vec<String>,
+        /// Pull all patchsets uploaded by others without asking.
+        #[clap(long, short = 'y')]
+        yes: bool,
+        /// Show what would be pulled without changing the workspace.
+        #[clap(long = "dry-run")]
+        dry_run: bool,
+    },
+
+    /// Submit the relation chain of a stack.
+    ///
+    /// This submits the top-most open change of the stack, which makes Gerrit submit all
+    /// of its ancestors as well. It only works if every open change in the chain is
+    /// up-to-date with its local commit and submittable.
+    ///
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Submit {
+        /// The name of the top-most branch of the stack to submit.
+        /// It can be omitted if there is only one stack.
+        #[clap(value_name = "BRANCH")]
+        branch: Option<String>,
+        /// Submit without asking for confirmation.
+        #[clap(long, short = 'y')]
+        yes: bool,
+    },
+}
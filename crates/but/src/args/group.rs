@@ -0,0 +1,60 @@
+//! Arguments for `group`.
+
+#![deny(missing_docs)]
+
+use std::path::PathBuf;
+
+/// Use AI to split uncommitted changes into branches and commits.
+///
+/// All uncommitted hunks are sent to the configured AI provider, which proposes a set of branches,
+/// each with commits and their messages. Existing branches may be reused, and new branches are
+/// created as needed. Hunks that depend on commits of a branch are only committed to that branch
+/// or to a new branch stacked on top of it; hunks that can't be placed are left uncommitted.
+///
+/// The proposal is shown for review before it is applied, and can be edited as a plan in the
+/// format of `but apply-plan`. It is applied as a single operation that `but undo` reverts as a
+/// whole.
+///
+/// Configure the AI provider with `but config ai`.
+///
+/// ## Examples
+///
+/// Review and apply a proposal:
+///
+/// ```text
+/// but group
+/// ```
+///
+/// Guide the proposal, and apply it without asking:
+///
+/// ```text
+/// but group --prompt "Keep the refactoring separate from the bug fix" --yes
+/// ```
+///
+/// Save the proposal to edit and apply it later:
+///
+/// ```text
+/// but group --output plan.yaml
+/// but apply-plan plan.yaml
+/// ```
+#[derive(Debug, clap::Parser)]
+#[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+pub struct Platform {
+    /// Additional instructions for the AI, for example how to split the changes.
+    #[clap(short, long, value_name = "TEXT")]
+    pub prompt: Option<String>,
+
+    /// Apply the proposal without asking for confirmation.
+    #[clap(short, long)]
+    pub yes: bool,
+
+    /// Only show the proposal, without applying it.
+    #[clap(long, conflicts_with_all = ["yes", "output"])]
+    pub dry_run: bool,
+
+    /// Write the proposal as plan to FILE instead of applying it.
+    ///
+    /// Apply the plan, possibly after editing it, with `but apply-plan FILE`.
+    #[clap(short, long, value_name = "FILE", conflicts_with = "yes")]
+    pub output: Option<PathBuf>,
+}
@@ -11,18 +11,26 @@ pub enum CommandName {
     Stf,
     Uncommit,
     Amend,
+    ApplyPlan,
+    Group,
     Squash,
+    Split,
     Move,
     Diff,
     Diff2,
     Edit,
     Show,
+    Watch,
+    Exec,
     Commit,
     CommitEmpty,
     Push,
     Reword,
     OplogList,
     OplogSnapshot,
+    OplogGc,
+    OplogExport,
+    OplogImport,
     Restore,
     Undo,
     Redo,
@@ -50,6 +58,9 @@ pub enum CommandName {
     EnableAutoMerge,
     SetReviewReady,
     SetReviewDraft,
+    GerritChain,
+    GerritPull,
+    GerritSubmit,
     Completions,
     AliasCheck,
     AliasAdd,
@@ -208,7 +208,7 @@ pub enum Subcommands {
         /// Show verbose output with commit author and timestamp.
         #[clap(short = 'v', long = "verbose", default_value_t = false)]
         verbose: bool,
-        /// Forces a sync of pull requests from the forge before showing status.
+        /// Forces a sync of pull requests from the forge, or of change status from Gerrit, before showing status.
         #[clap(short = 'r', long = "refresh-prs", default_value_t = false)]
         refresh_prs: bool,
         /// Show detailed list of upstream commits that haven't been integrated yet.
@@ -292,6 +292,14 @@ pub enum Subcommands {
         verbose: bool,
     },
 
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Watch(watch::Platform),
+
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Exec(exec::Platform),
+
     #[cfg(feature = "legacy")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Commit(commit::Platform),
@@ -300,6 +308,10 @@ pub enum Subcommands {
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Squash(squash::Platform),
 
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Split(split::Platform),
+
     #[cfg(feature = "legacy")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Move(r#move::Platform),
@@ -421,6 +433,10 @@ pub enum Subcommands {
     /// Alternatively, resolve with AI in one step: `but resolve <commit-id> --ai`,
     /// or `but resolve --ai` to resolve all conflicted commits, oldest first.
     ///
+    /// Resolutions are recorded and applied automatically whenever a rebase runs into
+    /// the same conflict again. List them with `but resolve recorded` and forget them
+    /// with `but resolve forget`.
+    ///
     /// When in resolution mode, `but status` will also show that you're resolving conflicts.
     ///
     #[cfg(feature = "legacy")]
@@ -504,6 +520,18 @@ pub enum Subcommands {
     #[clap(visible_alias = "mr")]
     Pr(forge::pr::Platform),
 
+    /// Commands for working with the relation chains of stacks pushed to Gerrit.
+    ///
+    /// When a stack is pushed to Gerrit, each of its commits becomes a change, and together
+    /// they form a relation chain. These commands show how the local commits map to these
+    /// changes, pull patchsets that others uploaded into the local stack, and submit the chain.
+    ///
+    /// Running `but gerrit` without a subcommand defaults to `but gerrit chain`.
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Gerrit(gerrit::Platform),
+
     /// Amends changes into the appropriate commits where they belong.
     ///
     /// The semantic for finding "the appropriate commit" is as follows:
@@ -582,6 +610,14 @@ pub enum Subcommands {
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Amend(amend::Platform),
 
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    ApplyPlan(apply_plan::Platform),
+
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Group(group::Platform),
+
     /// Commands for viewing and managing operation history.
     ///
     /// Displays a list of past operations performed in the repository,
@@ -1023,6 +1059,11 @@ pub enum Subcommands {
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Fetch,
 
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    #[clap(hide = true)]
+    Fsmonitor(fsmonitor::Platform),
+
     /// AI: capture agent logs into GitMeta.
     #[clap(name = "agentlog", hide = true)]
     AgentLog {
@@ -1066,6 +1107,8 @@ pub mod alias;
 pub mod amend;
 #[cfg(feature = "legacy")]
 pub mod apply;
+#[cfg(feature = "legacy")]
+pub mod apply_plan;
 pub mod comment;
 #[cfg(feature = "legacy")]
 pub mod commit;
@@ -1087,6 +1130,8 @@ pub mod redo;
 pub mod reword2;
 pub mod skill;
 #[cfg(feature = "legacy")]
+pub mod split;
+#[cfg(feature = "legacy")]
 pub mod squash;
 #[cfg(feature = "legacy")]
 pub mod tui;
@@ -1124,13 +1169,23 @@ pub mod actions {
     }
 }
 
+#[cfg(feature = "legacy")]
+pub mod exec;
 pub mod forge;
+#[cfg(feature = "legacy")]
+pub mod fsmonitor;
+#[cfg(feature = "legacy")]
+pub mod gerrit;
+#[cfg(feature = "legacy")]
+pub mod group;
 pub mod metrics;
 #[cfg(feature = "legacy")]
 pub mod oplog;
 pub mod push;
 #[cfg(feature = "legacy")]
 pub mod resolve;
+#[cfg(feature = "legacy")]
+pub mod watch;
 
 pub mod branch;
 
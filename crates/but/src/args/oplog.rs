@@ -49,10 +49,83 @@ pub enum Subcommands {
     /// You need to provide the SHA of the oplog entry you want to restore to,
     /// which you can find by running `but oplog` or `but oplog list`.
     ///
+    /// To only restore some files, list their paths after `--`, like in
+    /// `but oplog restore <sha> -- src/lib.rs docs/`. Only these files are
+    /// changed, while branches, commits and all other files stay as they are.
+    ///
     #[cfg(feature = "legacy")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Restore {
         /// Oplog SHA to restore to
         oplog_sha: String,
+        /// Only show which files would change when restoring paths
+        #[clap(long, requires = "paths")]
+        dry_run: bool,
+        /// Restore only these paths, relative to the root of the repository
+        #[clap(last = true)]
+        paths: Vec<String>,
+    },
+
+    /// Remove old snapshots from the operation history.
+    ///
+    /// Snapshots are kept according to the retention policy configured with
+    /// `gitbutler.oplogMaxAgeDays`, `gitbutler.oplogMaxCount`,
+    /// `gitbutler.oplogKeepEveryNth` and `gitbutler.oplogThinAfter`,
+    /// each of which can be overridden with the respective option.
+    /// The most recent snapshot is always kept.
+    ///
+    /// Removed snapshots can no longer be restored, and the objects only they
+    /// referred to are reclaimed by the next `git gc`.
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Gc {
+        /// Remove snapshots older than this many days
+        #[clap(long, value_name = "DAYS")]
+        max_age_days: Option<u64>,
+        /// Keep at most this many snapshots
+        #[clap(long, value_name = "COUNT")]
+        max_count: Option<usize>,
+        /// Keep only every n-th snapshot beyond those kept by --thin-after
+        #[clap(long, value_name = "N")]
+        keep_every_nth: Option<usize>,
+        /// The amount of most recent snapshots that are never thinned out
+        #[clap(long, value_name = "COUNT")]
+        thin_after: Option<usize>,
+        /// Only report what would be removed
+        #[clap(long)]
+        dry_run: bool,
+    },
+
+    /// Export snapshots to a bundle file.
+    ///
+    /// This writes a range of snapshots, including everything needed to restore
+    /// them, into a Git bundle. Use `but oplog import` in another clone of the
+    /// repository to continue with the exported undo history there.
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Export {
+        /// The bundle file to write
+        path: std::path::PathBuf,
+        /// The oldest snapshot to export, defaults to the oldest one in the history
+        #[clap(long)]
+        from: Option<String>,
+        /// The newest snapshot to export, defaults to the most recent one
+        #[clap(long)]
+        to: Option<String>,
+    },
+
+    /// Import snapshots from a bundle file.
+    ///
+    /// The snapshots of a bundle written by `but oplog export` are added on top
+    /// of the operation history, and can be restored with `but oplog restore`.
+    /// Their target commit must have been fetched for that to work.
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Import {
+        /// The bundle file to read
+        path: std::path::PathBuf,
     },
 }
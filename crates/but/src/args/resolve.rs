@@ -50,6 +50,30 @@ pub enum Subcommands {
     /// resolved commit, and returns to the normal workspace.
     Finish,
 
+    /// List the recorded conflict resolutions, or show one of them.
+    ///
+    /// Whenever a conflicted commit is resolved, the resolution of each of its
+    /// conflicted files is recorded. When a rebase runs into the same conflict
+    /// again, for example after updating the target branch, the recorded
+    /// resolution is applied automatically. Set `gitbutler.rerere` to `false`
+    /// to turn this off.
+    Recorded {
+        /// The id, or a unique prefix of it, of the resolution to show along
+        /// with the conflict it resolves.
+        id: Option<String>,
+    },
+
+    /// Forget recorded conflict resolutions so they aren't applied anymore.
+    Forget {
+        /// The id, or a unique prefix of it, of the resolution to forget,
+        /// as listed by `but resolve recorded`.
+        #[clap(required_unless_present = "all")]
+        id: Option<String>,
+        /// Forget all recorded resolutions.
+        #[clap(long, conflicts_with = "id")]
+        all: bool,
+    },
+
     /// Cancel conflict resolution and return to workspace mode.
     ///
     /// This discards all changes made during resolution and restores
@@ -0,0 +1,70 @@
+//! Arguments for `split`.
+
+#![deny(missing_docs)]
+
+use crate::args::atoms::{AllowMergedArg, CliIdArg};
+
+/// Split a commit into several commits.
+///
+/// Each group of changes given with `--changes` becomes a commit with the message given by
+/// the `--message` at the same position, stacked in the order they were given. The first
+/// group stays in place of the original commit and keeps its change-id.
+///
+/// Changes that weren't selected go into one more commit on top, which keeps the message
+/// of the original commit. Commits and branches that were on top of the original commit
+/// end up on top of the last of the new commits.
+///
+/// A change is the CLI ID of a file in the commit, as shown by `but status -f`, optionally
+/// followed by `@<n>` to only select the `n`-th hunk of its diff, counting from 1.
+///
+/// Without `--changes`, the hunks of the commit are offered for selection one group at a
+/// time, each followed by a prompt for the message of its commit. Selecting nothing ends
+/// the split. Selecting everything for the first commit leaves the commit unchanged.
+///
+/// All new commits are created in a single operation, so `but undo` restores the original
+/// commit.
+///
+/// ## Examples
+///
+/// Keep two files in the original commit, moving all other changes into a commit on top:
+///
+/// ```text
+/// but split tpm --changes tpm:t,tpm:u --message "Extract the parser"
+/// ```
+///
+/// Split a commit into three, with the changes that weren't selected on top:
+///
+/// ```text
+/// but split tpm -c tpm:t -m "Add tests" -c tpm:u -m "Update docs"
+/// ```
+///
+/// Split the hunks of a file between two commits:
+///
+/// ```text
+/// but split tpm -c tpm:t@1 -m "Fix the parser" -c tpm:t@2 -m "Fix the printer"
+/// ```
+///
+/// Interactively select the hunks of each commit:
+///
+/// ```text
+/// but split tpm
+/// ```
+#[derive(Debug, clap::Parser)]
+#[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+pub struct Platform {
+    /// The commit (CLI ID or hash) to split.
+    pub commit: CliIdArg,
+    /// A comma-separated group of file CLI IDs in the commit that make up one commit.
+    /// Append `@<n>` to a file CLI ID to only select its `n`-th hunk.
+    ///
+    /// Can be supplied any number of times, once per commit.
+    #[clap(short, long = "changes", value_name = "IDS", requires = "message")]
+    pub changes: Vec<String>,
+    /// The message of a commit, paired with the `--changes` at the same position.
+    #[clap(short, long, requires = "changes")]
+    pub message: Vec<String>,
+
+    #[clap(flatten)]
+    #[allow(missing_docs)]
+    pub allow_merged: AllowMergedArg,
+}
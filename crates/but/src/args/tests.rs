@@ -688,6 +688,37 @@ mod agentlog {
     }
 }
 
+#[cfg(feature = "legacy")]
+mod fsmonitor {
+    use clap::Parser;
+
+    use crate::args::{Args, Subcommands, fsmonitor};
+
+    #[test]
+    fn hook_parses_what_git_passes() {
+        let args = Args::try_parse_from(["but", "fsmonitor", "hook", "2", "gitbutler:abc:42"])
+            .expect("parse args");
+        let Some(Subcommands::Fsmonitor(fsmonitor::Platform {
+            cmd: fsmonitor::Subcommands::Hook { version, token },
+        })) = args.cmd
+        else {
+            panic!("unexpected command shape")
+        };
+        assert_eq!(version, 2);
+        assert_eq!(token, "gitbutler:abc:42");
+
+        let args =
+            Args::try_parse_from(["but", "fsmonitor", "hook", "2"]).expect("the token is optional");
+        let Some(Subcommands::Fsmonitor(fsmonitor::Platform {
+            cmd: fsmonitor::Subcommands::Hook { token, .. },
+        })) = args.cmd
+        else {
+            panic!("unexpected command shape")
+        };
+        assert_eq!(token, "");
+    }
+}
+
 #[cfg(feature = "legacy")]
 mod push {
     use clap::Parser;
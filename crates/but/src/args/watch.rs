@@ -0,0 +1,49 @@
+//! Arguments for `watch`.
+
+#![deny(missing_docs)]
+
+/// Stream changes to the workspace as they happen.
+///
+/// Runs the same file watcher as the GitButler app and prints an event whenever the
+/// worktree, `HEAD`, branches or fetched state change, until interrupted. Worktree events
+/// carry the uncommitted changes along with their hunk assignments and dependencies.
+///
+/// With `--json`, each event is printed as one JSON object per line, with its kind in the
+/// `kind` field, so editor plugins and scripts can react to changes without polling
+/// `but status`.
+///
+/// ## Examples
+///
+/// Follow all changes:
+///
+/// ```text
+/// but watch
+/// ```
+///
+/// Stream worktree changes and new commits as JSON:
+///
+/// ```text
+/// but watch --json --kind worktree-changes --kind git-activity
+/// ```
+#[derive(Debug, clap::Parser)]
+#[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+pub struct Platform {
+    /// Only print events of this kind. Can be given multiple times.
+    #[clap(short, long = "kind", value_name = "KIND")]
+    pub kinds: Vec<EventKind>,
+}
+
+/// The kinds of events `but watch` prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EventKind {
+    /// A fetch updated the remote-tracking state.
+    GitFetch,
+    /// `HEAD` changed its target, or was attached or detached.
+    GitHead,
+    /// The commit at `HEAD` changed.
+    GitActivity,
+    /// Branches or remote-tracking state changed which may affect the workspace.
+    WorkspaceActivity,
+    /// Files in the worktree or the index changed.
+    WorktreeChanges,
+}
@@ -1,7 +1,10 @@
 //! Implementation of the `but _comment` command.
 
 use but_api::comments::{self, store};
-use but_comments::{DiffComment, DiffSide, NewComment, StoredComment};
+use but_comments::{
+    CommentReply, DiffComment, DiffSide, ImportOutcome, NewComment, NewReply, StoredComment,
+    ThreadFilter, notes::NotesSync,
+};
 use but_core::sync::RepoShared;
 use but_ctx::Context;
 use gix::prelude::ObjectIdExt as _;
@@ -20,7 +23,20 @@ use crate::{
 
 #[derive(Debug)]
 pub enum CommentOperation {
-    List,
+    List(ThreadFilter),
+    Reply {
+        /// The full id of the comment to reply to.
+        id: String,
+        payload: String,
+    },
+    Resolve {
+        /// The full id of a comment in the thread to resolve.
+        id: String,
+    },
+    Reopen {
+        /// The full id of a comment in the thread to reopen.
+        id: String,
+    },
     Archive {
         /// The full id of the comment to archive.
         id: String,
@@ -39,6 +55,7 @@ pub enum CommentOperation {
         line_number: u32,
         payload: String,
     },
+    Sync,
 }
 
 #[must_use]
@@ -48,6 +65,11 @@ pub enum CommentOutcome {
     Archived { id: String },
     AlreadyArchived { id: String },
     Added(DiffComment),
+    Replied(CommentReply),
+    Resolved { id: String, changed: bool },
+    Reopened { id: String, changed: bool },
+    Synced(NotesSync),
+    Imported(ImportOutcome),
 }
 
 impl CliOutputHuman for CommentOutcome {
@@ -85,6 +107,50 @@ impl CliOutputHuman for CommentOutcome {
                 writeln!(out, "Added comment")?;
                 write_comment(out, &comment, false)?;
             }
+            CommentOutcome::Replied(reply) => {
+                writeln!(out, "Added reply")?;
+                write_reply(out, &reply)?;
+            }
+            CommentOutcome::Resolved { id, changed: true } => {
+                writeln!(out, "Resolved the thread of comment {id}")?;
+            }
+            CommentOutcome::Resolved { id, changed: false } => {
+                writeln!(
+                    out,
+                    "The thread of comment {id} was already resolved; nothing to do"
+                )?;
+            }
+            CommentOutcome::Reopened { id, changed: true } => {
+                writeln!(out, "Reopened the thread of comment {id}")?;
+            }
+            CommentOutcome::Reopened { id, changed: false } => {
+                writeln!(
+                    out,
+                    "The thread of comment {id} wasn't resolved; nothing to do"
+                )?;
+            }
+            CommentOutcome::Synced(NotesSync { imported, exported }) => {
+                writeln!(
+                    out,
+                    "Imported {imported} comment(s) from notes, exported {exported} comment(s) to notes"
+                )?;
+            }
+            CommentOutcome::Imported(ImportOutcome {
+                imported,
+                updated,
+                unanchored,
+            }) => {
+                writeln!(
+                    out,
+                    "Imported {imported} review comment(s), updated {updated} comment(s)"
+                )?;
+                if unanchored > 0 {
+                    writeln!(
+                        out,
+                        "Skipped {unanchored} comment(s) whose line isn't in the diff of any commit of the branch"
+                    )?;
+                }
+            }
         }
         Ok(())
     }
@@ -103,11 +169,20 @@ fn write_comment(
         DiffSide::Old => ", old side",
         DiffSide::New => "",
     };
-    writeln!(
+    let resolved = if comment.is_resolved() {
+        ", resolved"
+    } else {
+        ""
+    };
+    write!(
         out,
-        "[{}] {}:{} ({scope}{side})",
+        "[{}] {}:{} ({scope}{side}{resolved})",
         comment.id, comment.path, comment.line_number
     )?;
+    match &comment.author {
+        Some(author) => writeln!(out, " by {author}")?,
+        None => writeln!(out)?,
+    }
     for line in comment.payload.lines() {
         writeln!(out, "  {line}")?;
     }
@@ -116,6 +191,22 @@ fn write_comment(
             writeln!(out, "  | {line}")?;
         }
     }
+    for reply in &comment.replies {
+        write!(out, "  ")?;
+        write_reply(out, reply)?;
+    }
+    Ok(())
+}
+
+fn write_reply(out: &mut dyn WriteWithUtils, reply: &CommentReply) -> anyhow::Result<()> {
+    write!(out, "> [{}]", reply.id)?;
+    match &reply.author {
+        Some(author) => writeln!(out, " by {author}")?,
+        None => writeln!(out)?,
+    }
+    for line in reply.payload.lines() {
+        writeln!(out, "    {line}")?;
+    }
     Ok(())
 }
 
@@ -131,6 +222,11 @@ impl CliOutput for CommentOutcome {
             Listed { comments: Vec<DiffComment> },
             Archived { archived: String },
             Added { comment: DiffComment },
+            Replied { reply: CommentReply },
+            Resolved { resolved: String },
+            Reopened { reopened: String },
+            Synced { synced: NotesSync },
+            Imported { imported: ImportOutcome },
         }
 
         match self {
@@ -143,11 +239,17 @@ impl CliOutput for CommentOutcome {
                 Output::Archived { archived: id }
             }
             CommentOutcome::Added(comment) => Output::Added { comment },
+            CommentOutcome::Replied(reply) => Output::Replied { reply },
+            // Like archiving, reaching the goal state is all that matters to consumers.
+            CommentOutcome::Resolved { id, .. } => Output::Resolved { resolved: id },
+            CommentOutcome::Reopened { id, .. } => Output::Reopened { reopened: id },
+            CommentOutcome::Synced(synced) => Output::Synced { synced },
+            CommentOutcome::Imported(imported) => Output::Imported { imported },
         }
     }
 }
 
-pub fn comment(
+pub async fn comment(
     ctx: &mut Context,
     _out: IntermediateChannel<'_>,
     args: Platform,
@@ -158,9 +260,18 @@ pub fn comment(
     if let Subcommands::List {
         wait: true,
         timeout,
+        unresolved,
+        branch,
     } = args.cmd
     {
-        return Ok(run_wait(ctx, timeout)?);
+        return Ok(run_wait(ctx, timeout, ThreadFilter { unresolved, branch })?);
+    }
+    // Talking to the forge must not hold the shared worktree guard either, so importing
+    // acquires it only once the comments are fetched.
+    #[cfg(feature = "legacy")]
+    if let Subcommands::Import { review } = args.cmd {
+        let outcome = but_api::legacy::forge::import_review_comments(ctx.to_sync(), review).await?;
+        return Ok(CommentOutcome::Imported(outcome));
     }
 
     let guard = ctx.shared_worktree_access();
@@ -169,13 +280,18 @@ pub fn comment(
     Ok(run(ctx, operation, perm)?)
 }
 
-/// Poll for unarchived comments until one survives re-anchoring or `timeout_secs` elapses.
+/// Poll for unarchived comments until one that passes `filter` survives re-anchoring or
+/// `timeout_secs` elapses.
 ///
 /// The cheap existence check reads only the comments file; the full (diff-computing,
 /// auto-archiving) listing runs only when rows the CLI could actually surface exist. A row can
 /// exist yet produce an empty listing when its anchor is gone — the listing archives it and the
 /// wait continues.
-fn run_wait(ctx: &Context, timeout_secs: u64) -> anyhow::Result<CommentOutcome> {
+fn run_wait(
+    ctx: &Context,
+    timeout_secs: u64,
+    filter: ThreadFilter,
+) -> anyhow::Result<CommentOutcome> {
     const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
     /// When rows exist but none are listable (e.g. their commit's branch is unapplied), every
     /// poll pays for a full listing — back off so a watching agent doesn't burn a status scan
@@ -185,10 +301,12 @@ fn run_wait(ctx: &Context, timeout_secs: u64) -> anyhow::Result<CommentOutcome>
     loop {
         let mut interval = POLL_INTERVAL;
         // Blank rows are invisible to the CLI, so they must not trigger the expensive listing.
-        let rows_exist = store(ctx)
-            .read()
-            .iter()
-            .any(|comment| comment.archived_at_ms.is_none() && !comment.payload.trim().is_empty());
+        let rows_exist = store(ctx).read().iter().any(|comment| {
+            comment.archived_at_ms.is_none()
+                && comment.parent_id.is_none()
+                && !(filter.unresolved && comment.resolved_at_ms.is_some())
+                && !comment.payload.trim().is_empty()
+        });
         if rows_exist {
             // Long waits outlive the cached workspace projection: a commit created mid-wait
             // must be resolvable or its comments would be treated as scope-less.
@@ -197,7 +315,7 @@ fn run_wait(ctx: &Context, timeout_secs: u64) -> anyhow::Result<CommentOutcome>
                 let guard = ctx.shared_worktree_access();
                 comments::comments_list_with_perm(ctx, guard.read_permission())?
             };
-            let comments = without_blank_payloads(comments);
+            let comments = filtered(comments, &filter);
             if !comments.is_empty() {
                 return Ok(CommentOutcome::Listed(comments));
             }
@@ -211,19 +329,46 @@ fn run_wait(ctx: &Context, timeout_secs: u64) -> anyhow::Result<CommentOutcome>
     }
 }
 
-/// Hide comments without any text yet: the GUI creates the backend comment the moment the
-/// gutter button is clicked, before the user has typed anything, and agents must not wake up on
-/// (or act on) those.
-fn without_blank_payloads(comments: Vec<DiffComment>) -> Vec<DiffComment> {
+/// Keep the threads that pass `filter`, and hide comments without any text yet: the GUI creates
+/// the backend comment the moment the gutter button is clicked, before the user has typed
+/// anything, and agents must not wake up on (or act on) those.
+fn filtered(comments: Vec<DiffComment>, filter: &ThreadFilter) -> Vec<DiffComment> {
     comments
         .into_iter()
-        .filter(|comment| !comment.payload.trim().is_empty())
+        .filter(|comment| !comment.payload.trim().is_empty() && filter.matches(comment))
         .collect()
 }
 
+/// Find the full id of the single unarchived comment or reply whose id starts with `id`.
+fn unarchived_comment_id(ctx: &Context, id: String) -> CliResult<String> {
+    let matches = store(ctx)
+        .read()
+        .into_iter()
+        .filter(|comment| comment.archived_at_ms.is_none() && comment.id.starts_with(&id))
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [comment] => Ok(comment.id.clone()),
+        [] => Err(bad_input("No unarchived comment with this id")
+            .arg_name("<ID>")
+            .arg_value(id)
+            .hint("Use `but _comment list` to see the ids of all comments")
+            .into()),
+        _ => Err(bad_input("The id prefix matches more than one comment")
+            .arg_name("<ID>")
+            .arg_value(id)
+            .hint("Use more characters of the id shown by `but _comment list`")
+            .into()),
+    }
+}
+
 fn resolve(ctx: &Context, args: Platform, perm: &RepoShared) -> CliResult<CommentOperation> {
     match args.cmd {
-        Subcommands::List { wait: false, .. } => Ok(CommentOperation::List),
+        Subcommands::List {
+            wait: false,
+            unresolved,
+            branch,
+            ..
+        } => Ok(CommentOperation::List(ThreadFilter { unresolved, branch })),
         Subcommands::List { wait: true, .. } => {
             unreachable!("waiting listings are dispatched before resolve")
         }
@@ -301,6 +446,21 @@ fn resolve(ctx: &Context, args: Platform, perm: &RepoShared) -> CliResult<Commen
                 payload: message,
             })
         }
+        Subcommands::Reply { id, message } => Ok(CommentOperation::Reply {
+            id: unarchived_comment_id(ctx, id)?,
+            payload: message,
+        }),
+        Subcommands::Resolve { id } => Ok(CommentOperation::Resolve {
+            id: unarchived_comment_id(ctx, id)?,
+        }),
+        Subcommands::Reopen { id } => Ok(CommentOperation::Reopen {
+            id: unarchived_comment_id(ctx, id)?,
+        }),
+        Subcommands::Sync => Ok(CommentOperation::Sync),
+        #[cfg(feature = "legacy")]
+        Subcommands::Import { .. } => {
+            unreachable!("imports are dispatched before resolve")
+        }
     }
 }
 
@@ -310,9 +470,29 @@ pub fn run(
     perm: &RepoShared,
 ) -> anyhow::Result<CommentOutcome> {
     match operation {
-        CommentOperation::List => Ok(CommentOutcome::Listed(without_blank_payloads(
+        CommentOperation::List(filter) => Ok(CommentOutcome::Listed(filtered(
             comments::comments_list_with_perm(ctx, perm)?,
+            &filter,
         ))),
+        CommentOperation::Reply { id, payload } => {
+            Ok(CommentOutcome::Replied(comments::comment_reply(
+                ctx,
+                NewReply {
+                    id: None,
+                    comment_id: id,
+                    payload,
+                    author: None,
+                },
+            )?))
+        }
+        CommentOperation::Resolve { id } => Ok(CommentOutcome::Resolved {
+            changed: comments::comment_resolve(ctx, id.clone())?,
+            id,
+        }),
+        CommentOperation::Reopen { id } => Ok(CommentOutcome::Reopened {
+            changed: comments::comment_reopen(ctx, id.clone())?,
+            id,
+        }),
         CommentOperation::Archive { id } => {
             // `false` means another process archived it between resolving and now — the goal
             // state is reached either way.
@@ -349,10 +529,12 @@ pub fn run(
                     side,
                     line_number,
                     payload,
+                    author: None,
                 },
                 perm,
             )?;
             Ok(CommentOutcome::Added(comment))
         }
+        CommentOperation::Sync => Ok(CommentOutcome::Synced(comments::comments_sync_notes(ctx)?)),
     }
 }
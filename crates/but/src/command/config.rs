@@ -732,10 +732,14 @@ async fn forge_show_overview(out: &mut OutputChannel) -> Result<()> {
     let known_gh_accounts = but_api::github::list_known_github_accounts()?;
     let known_gl_accounts = but_api::gitlab::list_known_gitlab_accounts()?;
     let known_bb_accounts = but_api::bitbucket::list_known_bitbucket_accounts()?;
+    let known_az_accounts = but_api::azure::list_known_azure_accounts()?;
+    let known_gitea_accounts = but_api::gitea::list_known_gitea_accounts()?;
 
     let no_accounts = known_gh_accounts.is_empty()
         && known_gl_accounts.is_empty()
-        && known_bb_accounts.is_empty();
+        && known_bb_accounts.is_empty()
+        && known_az_accounts.is_empty()
+        && known_gitea_accounts.is_empty();
 
     if let Some(out) = out.for_human() {
         if no_accounts {
@@ -743,7 +747,7 @@ async fn forge_show_overview(out: &mut OutputChannel) -> Result<()> {
             writeln!(out)?;
             writeln!(
                 out,
-                "Run {} to authenticate with GitHub, GitLab or Bitbucket.",
+                "Run {} to authenticate with GitHub, GitLab, Bitbucket, Azure DevOps or Gitea.",
                 t.command_suggestion.paint("but config forge auth")
             )?;
         } else {
@@ -753,6 +757,10 @@ async fn forge_show_overview(out: &mut OutputChannel) -> Result<()> {
                 display_authenticated_gitlab_accounts(&known_gl_accounts, out).await?;
             some_accounts_invalid |=
                 display_authenticated_bitbucket_accounts(&known_bb_accounts, out).await?;
+            some_accounts_invalid |=
+                display_authenticated_azure_accounts(&known_az_accounts, out).await?;
+            some_accounts_invalid |=
+                display_authenticated_gitea_accounts(&known_gitea_accounts, out).await?;
 
             if some_accounts_invalid {
                 writeln!(
@@ -783,8 +791,13 @@ async fn forge_show_overview(out: &mut OutputChannel) -> Result<()> {
             )?;
         }
     } else if let Some(out) = out.for_json() {
-        let accounts =
-            extract_account_details(known_gh_accounts, known_gl_accounts, known_bb_accounts);
+        let accounts = extract_account_details(
+            known_gh_accounts,
+            known_gl_accounts,
+            known_bb_accounts,
+            known_az_accounts,
+            known_gitea_accounts,
+        );
 
         out.write_value(serde_json::json!({ "accounts": accounts }))?;
     }
@@ -799,11 +812,13 @@ struct ForgeAccount {
     account_type: String,
 }
 
-/// Extract account details for JSON output, combining GitHub, GitLab and Bitbucket accounts into a unified format
+/// Extract account details for JSON output, combining GitHub, GitLab, Bitbucket, Azure DevOps and Gitea accounts into a unified format
 fn extract_account_details(
     known_gh_accounts: Vec<but_github::GithubAccountIdentifier>,
     known_gl_accounts: Vec<but_gitlab::GitlabAccountIdentifier>,
     known_bb_accounts: Vec<but_bitbucket::BitbucketAccountIdentifier>,
+    known_az_accounts: Vec<but_azure::AzureAccountIdentifier>,
+    known_gitea_accounts: Vec<but_gitea::GiteaAccountIdentifier>,
 ) -> Vec<ForgeAccount> {
     let mut accounts: Vec<ForgeAccount> = Vec::new();
 
@@ -859,6 +874,39 @@ fn extract_account_details(
             account_type,
         });
     }
+
+    // Add Azure DevOps accounts
+    for account in &known_az_accounts {
+        let (username, account_type) = match account {
+            but_azure::AzureAccountIdentifier::Pat {
+                organization,
+                username,
+            } => (
+                format!("{username}@{organization}"),
+                "Personal Access Token".to_string(),
+            ),
+        };
+        accounts.push(ForgeAccount {
+            provider: "Azure DevOps".to_string(),
+            username,
+            account_type,
+        });
+    }
+
+    // Add Gitea accounts
+    for account in &known_gitea_accounts {
+        let (username, account_type) = match account {
+            but_gitea::GiteaAccountIdentifier::Pat { username, host } => (
+                format!("{username}@{host}"),
+                "Personal Access Token".to_string(),
+            ),
+        };
+        accounts.push(ForgeAccount {
+            provider: "Gitea".to_string(),
+            username,
+            account_type,
+        });
+    }
     accounts
 }
 
@@ -869,6 +917,8 @@ async fn forge_auth(out: &mut OutputChannel) -> Result<()> {
         GitHub,
         GitLab,
         Bitbucket,
+        Azure,
+        Gitea,
     }
 
     impl From<ForgeProvider> for String {
@@ -877,6 +927,8 @@ async fn forge_auth(out: &mut OutputChannel) -> Result<()> {
                 ForgeProvider::GitHub => "GitHub".to_string(),
                 ForgeProvider::GitLab => "GitLab".to_string(),
                 ForgeProvider::Bitbucket => "Bitbucket".to_string(),
+                ForgeProvider::Azure => "Azure DevOps".to_string(),
+                ForgeProvider::Gitea => "Gitea / Forgejo".to_string(),
             }
         }
     }
@@ -884,7 +936,9 @@ async fn forge_auth(out: &mut OutputChannel) -> Result<()> {
     let auth_options = nonempty::nonempty![
         ("GitHub", ForgeProvider::GitHub),
         ("GitLab", ForgeProvider::GitLab),
-        ("Bitbucket", ForgeProvider::Bitbucket)
+        ("Bitbucket", ForgeProvider::Bitbucket),
+        ("Azure DevOps", ForgeProvider::Azure),
+        ("Gitea / Forgejo", ForgeProvider::Gitea)
     ];
     let selected_option = {
         let mut input = out
@@ -905,9 +959,97 @@ async fn forge_auth(out: &mut OutputChannel) -> Result<()> {
         ForgeProvider::GitHub => github_auth(out).await,
         ForgeProvider::GitLab => gitlab_auth(out).await,
         ForgeProvider::Bitbucket => bitbucket_auth(out).await,
+        ForgeProvider::Azure => azure_auth(out).await,
+        ForgeProvider::Gitea => gitea_auth(out).await,
     }
 }
 
+/// Authenticate with a Gitea or Forgejo instance using an access token.
+async fn gitea_auth(out: &mut OutputChannel) -> Result<()> {
+    use but_gitea::AuthStatusResponse;
+
+    let t = theme::get();
+    let mut inout = out
+        .prepare_for_terminal_input()
+        .context("Human input required - run this in a terminal")?;
+
+    let host = inout
+        .prompt("Please enter the URL of your Gitea or Forgejo instance (e.g. https://codeberg.org) and hit enter:")?
+        .context("No instance URL provided. Aborting authentication.")?;
+    let host = host.trim().trim_end_matches('/').to_owned();
+
+    writeln!(
+        inout,
+        "Create an access token at {}, granting:",
+        t.command_suggestion
+            .paint(format!("{host}/user/settings/applications"))
+    )?;
+    writeln!(inout, "  • user (Read)          identify your account")?;
+    writeln!(
+        inout,
+        "  • repository (Read and Write)  pull requests, reviews & commit statuses"
+    )?;
+    writeln!(inout, "  • issue (Read and Write)       labels & comments")?;
+    writeln!(inout)?;
+
+    let token = inout
+        .prompt_secret("Now, please enter your access token and hit enter:")?
+        .context("No access token provided. Aborting authentication.")?;
+
+    let AuthStatusResponse { username, .. } = but_api::gitea::store_gitea_pat(host, token)
+        .await
+        .map_err(|err| {
+            err.context(
+                "Authentication failed. Check the instance URL and that the token hasn't expired.",
+            )
+        })?;
+
+    writeln!(inout, "Authentication successful! Welcome, {username}.")?;
+    Ok(())
+}
+
+/// Authenticate with Azure DevOps using a personal access token.
+async fn azure_auth(out: &mut OutputChannel) -> Result<()> {
+    use but_azure::AuthStatusResponse;
+
+    let t = theme::get();
+    let mut inout = out
+        .prepare_for_terminal_input()
+        .context("Human input required - run this in a terminal")?;
+
+    writeln!(
+        inout,
+        "Create a personal access token at {} for the organization hosting your repository, granting:",
+        t.command_suggestion
+            .paint("https://dev.azure.com/{organization}/_usersSettings/tokens")
+    )?;
+    writeln!(
+        inout,
+        "  • Code (Read & write)  repositories, pull requests & comments"
+    )?;
+    writeln!(inout)?;
+
+    let organization = inout
+        .prompt("Please enter your Azure DevOps organization name and hit enter:")?
+        .context("No organization provided. Aborting authentication.")?;
+
+    let token = inout
+        .prompt_secret("Now, please enter your personal access token and hit enter:")?
+        .context("No personal access token provided. Aborting authentication.")?;
+
+    let AuthStatusResponse { username, .. } =
+        but_api::azure::store_azure_pat(organization.trim().to_owned(), token)
+            .await
+            .map_err(|err| {
+                err.context(
+                    "Authentication failed. Check that the token belongs to this organization and hasn't expired.",
+                )
+            })?;
+
+    writeln!(inout, "Authentication successful! Welcome, {username}.")?;
+    Ok(())
+}
+
 /// Authenticate with Bitbucket Cloud using an Atlassian API token.
 async fn bitbucket_auth(out: &mut OutputChannel) -> Result<()> {
     use but_bitbucket::AuthStatusResponse;
@@ -1261,11 +1403,97 @@ async fn display_authenticated_bitbucket_accounts(
     Ok(some_accounts_invalid)
 }
 
+async fn display_authenticated_azure_accounts(
+    known_az_accounts: &Vec<but_azure::AzureAccountIdentifier>,
+    out: &mut dyn Write,
+) -> Result<bool, anyhow::Error> {
+    let t = theme::get();
+    if known_az_accounts.is_empty() {
+        return Ok(false);
+    }
+
+    writeln!(
+        out,
+        "\n{}:",
+        t.important.paint("Authenticated Azure DevOps accounts")
+    )?;
+    writeln!(out)?;
+
+    let mut some_accounts_invalid = false;
+
+    for account in known_az_accounts {
+        let account_status = but_api::azure::check_azure_credentials(account.clone())
+            .await
+            .ok();
+
+        let message = match account_status {
+            Some(but_azure::CredentialCheckResult::Valid) => t.success.paint("(valid credentials)"),
+            Some(but_azure::CredentialCheckResult::Invalid) => {
+                some_accounts_invalid = true;
+                t.attention.paint("(invalid credentials)")
+            }
+            Some(but_azure::CredentialCheckResult::NoCredentials) => {
+                some_accounts_invalid = true;
+                t.attention.paint("(no credentials)")
+            }
+            None => t.error.paint("(unknown status)"),
+        };
+
+        writeln!(out, "  • {account} {message}")?;
+    }
+    writeln!(out)?;
+    Ok(some_accounts_invalid)
+}
+
+async fn display_authenticated_gitea_accounts(
+    known_gitea_accounts: &Vec<but_gitea::GiteaAccountIdentifier>,
+    out: &mut dyn Write,
+) -> Result<bool, anyhow::Error> {
+    let t = theme::get();
+    if known_gitea_accounts.is_empty() {
+        return Ok(false);
+    }
+
+    writeln!(
+        out,
+        "\n{}:",
+        t.important.paint("Authenticated Gitea accounts")
+    )?;
+    writeln!(out)?;
+
+    let mut some_accounts_invalid = false;
+
+    for account in known_gitea_accounts {
+        let account_status = but_api::gitea::check_gitea_credentials(account.clone())
+            .await
+            .ok();
+
+        let message = match account_status {
+            Some(but_gitea::CredentialCheckResult::Valid) => t.success.paint("(valid credentials)"),
+            Some(but_gitea::CredentialCheckResult::Invalid) => {
+                some_accounts_invalid = true;
+                t.attention.paint("(invalid credentials)")
+            }
+            Some(but_gitea::CredentialCheckResult::NoCredentials) => {
+                some_accounts_invalid = true;
+                t.attention.paint("(no credentials)")
+            }
+            None => t.error.paint("(unknown status)"),
+        };
+
+        writeln!(out, "  • {account} {message}")?;
+    }
+    writeln!(out)?;
+    Ok(some_accounts_invalid)
+}
+
 #[derive(Debug, Clone)]
 enum AccountToForget {
     GitHub(but_github::GithubAccountIdentifier),
     GitLab(but_gitlab::GitlabAccountIdentifier),
     Bitbucket(but_bitbucket::BitbucketAccountIdentifier),
+    Azure(but_azure::AzureAccountIdentifier),
+    Gitea(but_gitea::GiteaAccountIdentifier),
 }
 
 impl Display for AccountToForget {
@@ -1274,6 +1502,8 @@ impl Display for AccountToForget {
             AccountToForget::GitHub(account) => write!(f, "GitHub account '{account}'"),
             AccountToForget::GitLab(account) => write!(f, "GitLab account '{account}'"),
             AccountToForget::Bitbucket(account) => write!(f, "Bitbucket account '{account}'"),
+            AccountToForget::Azure(account) => write!(f, "Azure DevOps account '{account}'"),
+            AccountToForget::Gitea(account) => write!(f, "Gitea account '{account}'"),
         }
     }
 }
@@ -1289,6 +1519,12 @@ fn forget_account(account: &AccountToForget) -> Result<()> {
         AccountToForget::Bitbucket(bb_account) => {
             but_api::bitbucket::forget_bitbucket_account(bb_account.clone())
         }
+        AccountToForget::Azure(az_account) => {
+            but_api::azure::forget_azure_account(az_account.clone())
+        }
+        AccountToForget::Gitea(gitea_account) => {
+            but_api::gitea::forget_gitea_account(gitea_account.clone())
+        }
     }
 }
 
@@ -1297,6 +1533,8 @@ async fn forge_forget(username: Option<String>, out: &mut OutputChannel) -> Resu
     let known_gh_accounts = but_api::github::list_known_github_accounts()?;
     let known_gl_accounts = but_api::gitlab::list_known_gitlab_accounts()?;
     let known_bb_accounts = but_api::bitbucket::list_known_bitbucket_accounts()?;
+    let known_az_accounts = but_api::azure::list_known_azure_accounts()?;
+    let known_gitea_accounts = but_api::gitea::list_known_gitea_accounts()?;
 
     // Gather all potential accounts to delete based on the provided username (or all if no username provided)
     let mut accounts_to_delete: Vec<AccountToForget> = Vec::new();
@@ -1319,6 +1557,18 @@ async fn forge_forget(username: Option<String>, out: &mut OutputChannel) -> Resu
         }
     }
 
+    for account in known_az_accounts {
+        if username.as_ref().is_none_or(|u| account.username() == u) {
+            accounts_to_delete.push(AccountToForget::Azure(account.clone()));
+        }
+    }
+
+    for account in known_gitea_accounts {
+        if username.as_ref().is_none_or(|u| account.username() == u) {
+            accounts_to_delete.push(AccountToForget::Gitea(account.clone()));
+        }
+    }
+
     // Handle case where no matching account was found
     if accounts_to_delete.is_empty() {
         if let Some((username, out)) = username.zip(out.for_human()) {
@@ -1651,6 +1901,12 @@ fn ai_config_interactive(
             );
             apply_openrouter_config(repo, scope, model, secret)?;
         }
+        LLMProviderKind::Record | LLMProviderKind::Replay => {
+            anyhow::bail!(
+                "The {} provider can only be configured with git config",
+                provider.display_name()
+            )
+        }
     }
 
     writeln!(
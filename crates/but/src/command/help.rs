@@ -121,6 +121,10 @@ fn print_grouped_with_truncation(
                 SubcommandDiscriminant::_Diff2 => Group::Inspection,
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Show => Group::Inspection,
+                #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Watch => Group::Inspection,
+                #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Exec => Group::Inspection,
                 SubcommandDiscriminant::_Comment => Group::Inspection,
 
                 #[cfg(feature = "legacy")]
@@ -150,6 +154,8 @@ fn print_grouped_with_truncation(
                 SubcommandDiscriminant::Land => Group::ServerInteractions,
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Pr => Group::ServerInteractions,
+                #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Gerrit => Group::ServerInteractions,
 
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Absorb => Group::EditingCommits,
@@ -162,8 +168,14 @@ fn print_grouped_with_truncation(
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Amend => Group::EditingCommits,
                 #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::ApplyPlan => Group::EditingCommits,
+                #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Group => Group::BranchingAndCommitting,
+                #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Squash => Group::EditingCommits,
                 #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Split => Group::EditingCommits,
+                #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Move => Group::EditingCommits,
 
                 #[cfg(feature = "legacy")]
@@ -204,6 +216,8 @@ fn print_grouped_with_truncation(
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Fetch => continue,
                 SubcommandDiscriminant::AgentLog => continue,
+                #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Fsmonitor => continue,
             };
             groups.entry(group).or_default().push(*clap_subcommand);
         } else {
@@ -373,6 +387,8 @@ Inspection:
   status       Overview of the project workspace state
   diff         Displays the diff of changes in the repo
   show         Shows detailed information about a commit or branch
+  watch        Stream changes to the workspace as they happen
+  exec         Run a command against every commit of a branch
   open         Open the project in GitButler
 
 Branching and Committing:
@@ -382,16 +398,19 @@ Branching and Committing:
   resolve      Resolve conflicts in a commit or in uncommitted files
   unapply      Unapply a branch
   apply        Apply a branch
+  group        Use AI to split uncommitted changes into branches and commits
   clean        Remove empty branches from the workspace
   pick         Cherry-pick commits into an applied branch
 
 Editing Commits:
   squash       Squash commits, branches, or changes
+  split        Split a commit into several commits
   move         Move commits and changes around
   absorb       Amends changes into the appropriate commits where they belong
   reword       Edit the commit message of the specified commit
   uncommit     Uncommit commits, branches, or committed files
   amend        Amend uncommitted changes into a commit or branch
+  apply-plan   Apply a plan of operations as a single undoable operation
 
 Operation History:
   oplog        Commands for viewing and managing operation history
@@ -0,0 +1,589 @@
+use std::{io::Read as _, path::Path};
+
+use anyhow::Context as _;
+use but_api::{
+    WorkspaceState,
+    json::{ChangeIdString, HexHash},
+};
+use but_core::{
+    DiffSpec, DryRun, RefMetadata,
+    ref_metadata::StackId,
+    sync::{RepoExclusive, RepoShared},
+};
+use but_ctx::Context;
+use but_graph::Workspace;
+use but_rebase::graph_rebase::mutate::{InsertSide, RelativeTo};
+use but_transaction::{IntermediateCommitCreateResult, Transaction};
+use but_workspace::{
+    branch::create_reference::{Anchor, Position},
+    commit::{ChangeSource, squash_commits::MessageCombinationStrategy},
+};
+use gitbutler_oplog::entry::{OperationKind, SnapshotDetails};
+use gix::{ObjectId, refs::FullName};
+use itertools::Itertools as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CliResult, IdMap,
+    args::{
+        apply_plan::Platform,
+        atoms::{BranchArg, CliIdArg},
+    },
+    bad_input,
+    command::legacy::commit_message_prep::normalize_commit_message,
+    id::CommitId,
+    theme::{self, Theme},
+    utils::{
+        CliOutput, CliOutputHuman, IntermediateChannel, WriteWithUtils,
+        diff_specs::DiffSpecBuilder, rejection,
+    },
+};
+
+/// One operation of a plan file, as written by the user.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "op", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub(crate) enum PlanOperation {
+    Commit {
+        branch: String,
+        message: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        changes: Vec<String>,
+    },
+    Branch {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        above: Option<String>,
+    },
+    Stack {
+        branch: String,
+        onto: String,
+    },
+    Move {
+        commits: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        branch: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        above: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        below: Option<String>,
+    },
+    Squash {
+        commits: Vec<String>,
+        into: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+    Reword {
+        commit: String,
+        message: String,
+    },
+}
+
+/// A plan operation with all of its IDs resolved against the workspace before the plan ran.
+struct Step {
+    op: &'static str,
+    description: String,
+    action: Action,
+}
+
+enum Action {
+    Commit {
+        branch: FullName,
+        message: String,
+        changes: Vec<DiffSpec>,
+    },
+    CreateBranch {
+        name: FullName,
+        above: Option<FullName>,
+    },
+    Stack {
+        branch: FullName,
+        onto: FullName,
+    },
+    Move {
+        commits: Vec<ObjectId>,
+        relative_to: RelativeTo,
+        side: InsertSide,
+    },
+    Squash {
+        commits: Vec<ObjectId>,
+        into: ObjectId,
+        message: Option<String>,
+    },
+    Reword {
+        commit: ObjectId,
+        message: String,
+    },
+}
+
+pub struct ApplyPlanOutcome {
+    dry_run: bool,
+    steps: Vec<StepOutcome>,
+}
+
+struct StepOutcome {
+    op: &'static str,
+    description: String,
+    new_commit: Option<CommitId>,
+}
+
+impl CliOutputHuman for ApplyPlanOutcome {
+    fn on_human(
+        self,
+        out: &mut dyn WriteWithUtils,
+        _agent: bool,
+        _theme: &Theme,
+    ) -> anyhow::Result<()> {
+        let count = self.steps.len();
+        let operations = if count == 1 {
+            "operation"
+        } else {
+            "operations"
+        };
+        if self.dry_run {
+            writeln!(out, "Would apply {count} {operations}:")?;
+        } else {
+            writeln!(out, "Applied {count} {operations}:")?;
+        }
+        for (index, step) in self.steps.into_iter().enumerate() {
+            match step.new_commit {
+                Some(new_commit) => writeln!(
+                    out,
+                    "  {}. {} -> {}",
+                    index + 1,
+                    step.description,
+                    theme::Commit(new_commit)
+                )?,
+                None => writeln!(out, "  {}. {}", index + 1, step.description)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CliOutput for ApplyPlanOutcome {
+    fn on_json(self) -> impl Serialize {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Operation {
+            op: &'static str,
+            description: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            new_commit_id: Option<HexHash>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            new_change_id: Option<ChangeIdString>,
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Output {
+            dry_run: bool,
+            operations: Vec<Operation>,
+        }
+
+        Output {
+            dry_run: self.dry_run,
+            operations: self
+                .steps
+                .into_iter()
+                .map(|step| Operation {
+                    op: step.op,
+                    description: step.description,
+                    new_commit_id: step
+                        .new_commit
+                        .as_ref()
+                        .map(|commit| commit.commit_id.into()),
+                    new_change_id: step
+                        .new_commit
+                        .and_then(|commit| commit.change_id)
+                        .map(Into::into),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn apply_plan(
+    ctx: &mut Context,
+    _out: IntermediateChannel<'_>,
+    args: Platform,
+) -> CliResult<(ApplyPlanOutcome, Option<WorkspaceState>)> {
+    let operations = read_plan(&args.file)?;
+    apply_operations(ctx, operations, args.dry_run)
+}
+
+/// Validate `operations` by running them in memory, and unless `dry_run` is set, apply them as one
+/// undoable operation.
+pub(crate) fn apply_operations(
+    ctx: &mut Context,
+    operations: Vec<PlanOperation>,
+    dry_run: bool,
+) -> CliResult<(ApplyPlanOutcome, Option<WorkspaceState>)> {
+    let mut guard = ctx.exclusive_worktree_access();
+    let mut meta = ctx.meta()?;
+    let id_map = IdMap::new_from_context(ctx, guard.read_permission())?;
+    let steps = resolve(ctx, guard.read_permission(), &id_map, operations)?;
+
+    // Run the whole plan in memory first so nothing is changed unless every step succeeds.
+    let (preview, _) = run(
+        ctx,
+        &mut meta,
+        guard.write_permission(),
+        &steps,
+        DryRun::Yes,
+    )?;
+    if dry_run {
+        return Ok((preview, None));
+    }
+    let (outcome, ws) = run(ctx, &mut meta, guard.write_permission(), &steps, DryRun::No)?;
+    Ok((outcome, Some(ws)))
+}
+
+fn read_plan(path: &Path) -> CliResult<Vec<PlanOperation>> {
+    let content = if path == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .context("Could not read the plan from standard input")?;
+        content
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read the plan from {}", path.display()))?
+    };
+
+    parse_plan(&content, path.extension().is_some_and(|ext| ext == "json"))
+}
+
+/// Parse the plan in `content`, which is JSON if `is_json` is set and YAML otherwise.
+pub(crate) fn parse_plan(content: &str, is_json: bool) -> CliResult<Vec<PlanOperation>> {
+    let operations: Vec<PlanOperation> = if is_json {
+        serde_json::from_str(content).map_err(|err| bad_input(format!("Invalid plan: {err}")))?
+    } else {
+        serde_yaml_ng::from_str(content).map_err(|err| bad_input(format!("Invalid plan: {err}")))?
+    };
+    if operations.is_empty() {
+        return Err(bad_input("The plan contains no operations").into());
+    }
+    Ok(operations)
+}
+
+fn resolve(
+    ctx: &Context,
+    perm: &RepoShared,
+    id_map: &IdMap,
+    operations: Vec<PlanOperation>,
+) -> CliResult<Vec<Step>> {
+    let (repo, ws, _db) = ctx.workspace_and_db_with_perm(perm)?;
+    let mut resolver = Resolver {
+        repo: &repo,
+        ws: &ws,
+        id_map,
+        context_lines: ctx.settings.context_lines,
+        created_branches: Vec::new(),
+    };
+    operations
+        .into_iter()
+        .enumerate()
+        .map(|(index, operation)| {
+            resolver.resolve(operation).map_err(|err| {
+                err.arg_name_if_unset(format!("step {}", index + 1))
+                    .context(format!("Could not resolve step {} of the plan", index + 1))
+            })
+        })
+        .collect()
+}
+
+struct Resolver<'a> {
+    repo: &'a gix::Repository,
+    ws: &'a Workspace,
+    id_map: &'a IdMap,
+    context_lines: u32,
+    /// Branches created by earlier steps, which later steps may refer to by name.
+    created_branches: Vec<FullName>,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self, operation: PlanOperation) -> CliResult<Step> {
+        Ok(match operation {
+            PlanOperation::Commit {
+                branch,
+                message,
+                changes,
+            } => Step {
+                op: "commit",
+                description: format!("commit to {branch}"),
+                action: Action::Commit {
+                    branch: self.branch(&branch)?,
+                    message: normalize_commit_message(&message).to_owned(),
+                    changes: self.changes(&changes)?,
+                },
+            },
+            PlanOperation::Branch { name, above } => {
+                let description = match &above {
+                    Some(above) => format!("create branch {name} above {above}"),
+                    None => format!("create branch {name}"),
+                };
+                let above = above.map(|above| self.branch(&above)).transpose()?;
+                Step {
+                    op: "branch",
+                    description,
+                    action: Action::CreateBranch {
+                        name: self.new_branch(&name)?,
+                        above,
+                    },
+                }
+            }
+            PlanOperation::Stack { branch, onto } => Step {
+                op: "stack",
+                description: format!("stack {branch} onto {onto}"),
+                action: Action::Stack {
+                    branch: self.branch(&branch)?,
+                    onto: self.branch(&onto)?,
+                },
+            },
+            PlanOperation::Move {
+                commits,
+                branch,
+                above,
+                below,
+            } => {
+                let sources = commits.iter().join(", ");
+                let (description, relative_to, side) = match (branch, above, below) {
+                    (Some(branch), None, None) => (
+                        format!("move {sources} to {branch}"),
+                        RelativeTo::Reference(self.branch(&branch)?),
+                        InsertSide::Below,
+                    ),
+                    (None, Some(above), None) => (
+                        format!("move {sources} above {above}"),
+                        RelativeTo::Commit(self.commit(&above)?),
+                        InsertSide::Above,
+                    ),
+                    (None, None, Some(below)) => (
+                        format!("move {sources} below {below}"),
+                        RelativeTo::Commit(self.commit(&below)?),
+                        InsertSide::Below,
+                    ),
+                    _ => {
+                        return Err(bad_input(
+                            "A move needs exactly one of `branch`, `above` or `below`",
+                        )
+                        .into());
+                    }
+                };
+                Step {
+                    op: "move",
+                    description,
+                    action: Action::Move {
+                        commits: self.commits(&commits)?,
+                        relative_to,
+                        side,
+                    },
+                }
+            }
+            PlanOperation::Squash {
+                commits,
+                into,
+                message,
+            } => Step {
+                op: "squash",
+                description: format!("squash {} into {into}", commits.iter().join(", ")),
+                action: Action::Squash {
+                    commits: self.commits(&commits)?,
+                    into: self.commit(&into)?,
+                    message: message.map(|message| normalize_commit_message(&message).to_owned()),
+                },
+            },
+            PlanOperation::Reword { commit, message } => Step {
+                op: "reword",
+                description: format!("reword {commit}"),
+                action: Action::Reword {
+                    commit: self.commit(&commit)?,
+                    message: normalize_commit_message(&message).to_owned(),
+                },
+            },
+        })
+    }
+
+    fn commit(&self, id: &str) -> CliResult<ObjectId> {
+        CliIdArg(id.to_owned()).resolve_commit_in_workspace(self.repo, self.id_map)
+    }
+
+    fn commits(&self, ids: &[String]) -> CliResult<Vec<ObjectId>> {
+        if ids.is_empty() {
+            return Err(bad_input("No commits were given").into());
+        }
+        ids.iter().map(|id| self.commit(id)).collect()
+    }
+
+    fn branch(&self, name: &str) -> CliResult<FullName> {
+        if let Some(created) = self
+            .created_branches
+            .iter()
+            .find(|created| created.shorten() == name)
+        {
+            return Ok(created.clone());
+        }
+        CliIdArg(name.to_owned()).resolve_existing_local_branch(self.repo, self.id_map)
+    }
+
+    fn new_branch(&mut self, name: &str) -> CliResult<FullName> {
+        if self
+            .created_branches
+            .iter()
+            .any(|created| created.shorten() == name)
+        {
+            return Err(bad_input(format!(
+                "A branch named '{name}' is already created by the plan"
+            ))
+            .into());
+        }
+        let name = BranchArg(name.to_owned()).resolve_for_creation(self.repo, self.ws)?;
+        self.created_branches.push(name.clone());
+        Ok(name)
+    }
+
+    /// The uncommitted changes named by `ids`, or all of them if there are none.
+    fn changes(&self, ids: &[String]) -> CliResult<Vec<DiffSpec>> {
+        let mut builder = DiffSpecBuilder::new(self.repo, self.context_lines);
+        if ids.is_empty() {
+            builder.push_changes_from_uncommitted_area()?;
+        } else {
+            for id in ids {
+                for change in CliIdArg(id.clone()).resolve_uncommitted(self.repo, self.id_map)? {
+                    builder.push_changes_from_uncommitted(&change)?;
+                }
+            }
+            builder.reconcile_worktree_diff_specs()?;
+        }
+        let changes = builder.into_diff_specs();
+        if changes.is_empty() {
+            return Err(bad_input("There are no uncommitted changes to commit").into());
+        }
+        Ok(changes)
+    }
+}
+
+fn run(
+    ctx: &mut Context,
+    meta: &mut impl RefMetadata,
+    perm: &mut RepoExclusive,
+    steps: &[Step],
+    dry_run: DryRun,
+) -> CliResult<(ApplyPlanOutcome, WorkspaceState)> {
+    let snapshot_details = SnapshotDetails {
+        title: "ApplyPlan".into(),
+        ..SnapshotDetails::new(OperationKind::GenericBranchUpdate)
+    }
+    .with_count(steps.len());
+    let (new_commits, ws) = but_transaction::with_transaction_with_perm(
+        ctx,
+        meta,
+        perm,
+        snapshot_details,
+        dry_run,
+        |mut tx| {
+            let mut new_commits = Vec::with_capacity(steps.len());
+            for (index, step) in steps.iter().enumerate() {
+                let new_commit = step.action.execute(&mut tx).with_context(|| {
+                    format!(
+                        "Step {} of the plan failed: {}",
+                        index + 1,
+                        step.description
+                    )
+                })?;
+                new_commits.push(new_commit);
+            }
+            Ok(but_transaction::Commit(new_commits))
+        },
+    )?;
+
+    let steps = steps
+        .iter()
+        .zip(new_commits)
+        .map(|(step, new_commit)| StepOutcome {
+            op: step.op,
+            description: step.description.clone(),
+            new_commit: new_commit.map(Into::into),
+        })
+        .collect();
+    Ok((
+        ApplyPlanOutcome {
+            dry_run: dry_run.into(),
+            steps,
+        },
+        ws,
+    ))
+}
+
+impl Action {
+    /// Perform the action in `tx`, returning the commit it created or rewrote, if any.
+    fn execute<M: RefMetadata>(
+        &self,
+        tx: &mut Transaction<'_, '_, M>,
+    ) -> anyhow::Result<Option<but_core::commit::CommitIdentifiers>> {
+        Ok(match self {
+            Action::Commit {
+                branch,
+                message,
+                changes,
+            } => {
+                let IntermediateCommitCreateResult {
+                    new_commit,
+                    rejected_specs,
+                } = tx.create_commit(
+                    RelativeTo::Reference(branch.clone()),
+                    InsertSide::Below,
+                    changes.clone(),
+                    message.clone(),
+                    ChangeSource::Head,
+                )?;
+                if !rejected_specs.is_empty() {
+                    return Err(rejection::RejectedChanges(rejected_specs).into());
+                }
+                Some(new_commit.context("BUG: rejected_specs is empty yet nothing was committed")?)
+            }
+            Action::CreateBranch { name, above } => {
+                let anchor = above
+                    .as_ref()
+                    .map(|above| Anchor::at_segment(above.as_ref(), Position::Above));
+                tx.create_reference(name.as_ref(), anchor, |_| StackId::generate(), Some(0))?;
+                None
+            }
+            Action::Stack { branch, onto } => {
+                tx.stack_branch_on(branch.as_ref(), onto.as_ref())?;
+                None
+            }
+            Action::Move {
+                commits,
+                relative_to,
+                side,
+            } => {
+                tx.move_commits(commits.iter().copied(), relative_to.clone(), *side)?;
+                None
+            }
+            Action::Squash {
+                commits,
+                into,
+                message,
+            } => {
+                let how_to_combine_messages = if message.is_some() {
+                    MessageCombinationStrategy::KeepTarget
+                } else {
+                    MessageCombinationStrategy::KeepBoth
+                };
+                let new_commit =
+                    tx.squash_commits(commits.iter().copied(), *into, how_to_combine_messages)?;
+                match message {
+                    Some(message) => {
+                        Some(tx.reword_commit(new_commit.id, message.as_str().into())?)
+                    }
+                    None => Some(new_commit),
+                }
+            }
+            Action::Reword { commit, message } => {
+                Some(tx.reword_commit(*commit, message.as_str().into())?)
+            }
+        })
+    }
+}
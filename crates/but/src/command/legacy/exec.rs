@@ -0,0 +1,367 @@
+use std::{
+    ffi::OsStr,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::Context as _;
+use but_core::ref_metadata::StackId;
+use but_ctx::Context;
+use gix::prelude::ObjectIdExt as _;
+use serde::Serialize;
+
+use crate::{
+    CliResult, IdMap,
+    args::exec::Platform,
+    bad_input,
+    theme::{self, Paint as _},
+    utils::{OutputChannel, shorten_object_id},
+};
+
+/// The outcome of running the command on a single commit.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommitOutcome {
+    #[serde(serialize_with = "serialize_object_id")]
+    commit_id: gix::ObjectId,
+    subject: String,
+    passed: bool,
+    /// Whether the commit is conflicted, in which case the command isn't run on it.
+    conflicted: bool,
+    /// The exit code of the command, or `None` if it was terminated by a signal or not run.
+    exit_code: Option<i32>,
+}
+
+impl CommitOutcome {
+    fn failed(&self) -> bool {
+        !self.passed && !self.conflicted
+    }
+}
+
+/// The outcome of `but exec`, for JSON output.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecOutcome {
+    /// One entry per commit the command was run on, from the bottom of the stack upwards.
+    commits: Vec<CommitOutcome>,
+    /// The commits that were not visited as the run stopped at the first failure.
+    skipped: usize,
+    /// The commit on which edit mode was entered, if any.
+    editing: Option<String>,
+}
+
+fn serialize_object_id<S: serde::Serializer>(id: &gix::ObjectId, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_str(id)
+}
+
+/// Run the command of `args` on each commit of the stack up to the given branch, oldest first,
+/// in a temporary worktree, and report which commits it passed on.
+pub fn handle(ctx: &mut Context, out: &mut OutputChannel, args: Platform) -> CliResult<()> {
+    let Platform {
+        branch,
+        fail_fast,
+        edit,
+        command,
+    } = args;
+    let fail_fast = fail_fast || edit;
+
+    let (stack_id, commits) = {
+        let guard = ctx.shared_worktree_access();
+        let id_map = IdMap::new_from_context(ctx, guard.read_permission())?;
+        let (repo, ws, _) = ctx.workspace_and_db_with_perm(guard.read_permission())?;
+        let ref_name = branch
+            .resolve_branch_in_workspace(&repo, &id_map)?
+            .resolve_local_branch_name()?;
+        let Some((stack, segment)) = ws.find_segment_and_stack_by_refname(ref_name.as_ref()) else {
+            return Err(bad_input(format!("Branch '{branch}' not found in any stack")).into());
+        };
+        let segment_idx = stack
+            .segments
+            .iter()
+            .position(|s| std::ptr::eq(s, segment))
+            .context("BUG: segment must be part of its own stack")?;
+        let commits = stack.segments[segment_idx..]
+            .iter()
+            .rev()
+            .flat_map(|segment| segment.commits.iter().rev())
+            .map(|commit| {
+                let subject = repo
+                    .find_commit(commit.id)?
+                    .message()?
+                    .summary()
+                    .to_string();
+                let conflicted =
+                    but_core::Commit::from_id(commit.id.attach(&repo))?.is_conflicted();
+                anyhow::Ok((commit.id, subject, conflicted))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        (stack.id, commits)
+    };
+    if commits.is_empty() {
+        return Err(bad_input(format!("Branch '{branch}' has no commits to run on")).into());
+    }
+
+    let t = theme::get();
+    let mut progress = out.progress_channel();
+    let worktree = TempWorktree::new(&ctx.workdir_or_fail()?, commits[0].0)?;
+    let mut outcomes = Vec::with_capacity(commits.len());
+    for (commit_id, subject, conflicted) in &commits {
+        let short_id = shorten_object_id(&*ctx.repo.get()?, *commit_id);
+        // A conflicted commit stores both sides of the conflict in its tree, so there is nothing
+        // meaningful to run the command on until it's resolved.
+        if *conflicted {
+            writeln!(
+                progress,
+                "{} {} {}",
+                t.hint.paint("Skipping conflicted"),
+                t.commit_id.paint(&short_id),
+                subject
+            )?;
+            outcomes.push(CommitOutcome {
+                commit_id: *commit_id,
+                subject: subject.clone(),
+                passed: false,
+                conflicted: true,
+                exit_code: None,
+            });
+            continue;
+        }
+        writeln!(
+            progress,
+            "{} {} {}",
+            t.hint.paint("Running on"),
+            t.commit_id.paint(&short_id),
+            subject
+        )?;
+        worktree.checkout(*commit_id)?;
+        let status = run(&command, &worktree.path(), out.for_json().is_some())?;
+        outcomes.push(CommitOutcome {
+            commit_id: *commit_id,
+            subject: subject.clone(),
+            passed: status.success(),
+            conflicted: false,
+            exit_code: status.code(),
+        });
+        if fail_fast && !status.success() {
+            break;
+        }
+    }
+    drop(worktree);
+
+    let first_failure = outcomes
+        .iter()
+        .find(|outcome| outcome.failed())
+        .map(|outcome| outcome.commit_id);
+    let editing = match first_failure {
+        Some(commit_id) if edit => {
+            let stack_id: StackId = stack_id.context("The stack has no ID to enter edit mode")?;
+            but_api::legacy::modes::enter_edit_mode(ctx, commit_id, stack_id)
+                .context("Failed to enter edit mode")?;
+            Some(commit_id)
+        }
+        _ => None,
+    };
+
+    let failed = outcomes.iter().filter(|outcome| outcome.failed()).count();
+    let conflicted = outcomes.iter().filter(|outcome| outcome.conflicted).count();
+    let skipped = commits.len() - outcomes.len();
+    if let Some(out) = out.for_human_or_shell() {
+        let repo = ctx.repo.get()?;
+        writeln!(out)?;
+        for outcome in &outcomes {
+            let short_id = shorten_object_id(&repo, outcome.commit_id);
+            if outcome.passed {
+                writeln!(
+                    out,
+                    "{} {} {}",
+                    t.sym().success,
+                    t.commit_id.paint(&short_id),
+                    outcome.subject
+                )?;
+            } else if outcome.conflicted {
+                writeln!(
+                    out,
+                    "{} {} {} {}",
+                    t.sym().warning,
+                    t.commit_id.paint(&short_id),
+                    outcome.subject,
+                    t.attention.paint("(conflicted, skipped)")
+                )?;
+            } else {
+                let reason = match outcome.exit_code {
+                    Some(code) => format!("exit code {code}"),
+                    None => "terminated by signal".into(),
+                };
+                writeln!(
+                    out,
+                    "{} {} {} {}",
+                    t.sym().error,
+                    t.commit_id.paint(&short_id),
+                    outcome.subject,
+                    t.error.paint(format!("({reason})"))
+                )?;
+            }
+        }
+        if conflicted > 0 {
+            writeln!(
+                out,
+                "{}",
+                t.hint.paint(format!(
+                    "Skipped {conflicted} conflicted commit{}, resolve with `but resolve` to run on {}",
+                    if conflicted == 1 { "" } else { "s" },
+                    if conflicted == 1 { "it" } else { "them" }
+                ))
+            )?;
+        }
+        if skipped > 0 {
+            writeln!(
+                out,
+                "{}",
+                t.hint.paint(format!(
+                    "Skipped {skipped} commit{} after the first failure",
+                    if skipped == 1 { "" } else { "s" }
+                ))
+            )?;
+        }
+        if let Some(commit_id) = editing {
+            let short_id = shorten_object_id(&repo, commit_id);
+            writeln!(
+                out,
+                "{} {}, finish with {} or cancel with {}",
+                t.important.paint("Editing commit"),
+                t.commit_id.paint(short_id),
+                t.success.paint("but resolve finish"),
+                t.error.paint("but resolve cancel")
+            )?;
+        }
+    } else if let Some(out) = out.for_json() {
+        out.write_value(&ExecOutcome {
+            commits: outcomes,
+            skipped,
+            editing: editing.map(|id| id.to_string()),
+        })?;
+    }
+
+    if failed > 0 {
+        let ran = commits.len() - skipped - conflicted;
+        return Err(anyhow::anyhow!(
+            "command failed on {failed} of {ran} commit{}",
+            if ran == 1 { "" } else { "s" }
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Run `command` in `cwd` and wait for it to finish. In JSON mode, its standard output is
+/// redirected to standard error to keep standard output parseable.
+fn run(command: &[String], cwd: &Path, json: bool) -> anyhow::Result<std::process::ExitStatus> {
+    let (program, args) = command
+        .split_first()
+        .context("BUG: clap requires at least one command argument")?;
+    let mut cmd = Command::from(
+        gix::command::prepare(program)
+            .args(args)
+            .command_may_be_shell_script(),
+    );
+    cmd.current_dir(cwd).stdin(Stdio::null());
+    if json {
+        cmd.stdout(std::io::stderr());
+    }
+    cmd.status()
+        .with_context(|| format!("Failed to run '{}'", command.join(" ")))
+}
+
+/// A detached linked worktree in a temporary directory, removed again on drop.
+struct TempWorktree {
+    /// The worktree of the repository that the temporary worktree belongs to.
+    workdir: PathBuf,
+    /// Keeps the temporary directory alive, with the worktree inside of it.
+    tmp: tempfile::TempDir,
+}
+
+impl TempWorktree {
+    fn new(workdir: &Path, commit_id: gix::ObjectId) -> anyhow::Result<Self> {
+        let tmp = tempfile::Builder::new().prefix("but-exec-").tempdir()?;
+        let worktree = Self {
+            workdir: workdir.to_owned(),
+            tmp,
+        };
+        git(
+            workdir,
+            [
+                OsStr::new("worktree"),
+                "add".as_ref(),
+                "--detach".as_ref(),
+                "--quiet".as_ref(),
+                worktree.path().as_os_str(),
+                commit_id.to_string().as_ref(),
+            ],
+        )
+        .context("Failed to create a temporary worktree")?;
+        Ok(worktree)
+    }
+
+    fn path(&self) -> PathBuf {
+        self.tmp.path().join("worktree")
+    }
+
+    /// Check out `commit_id`, discarding everything the previous run may have left behind.
+    fn checkout(&self, commit_id: gix::ObjectId) -> anyhow::Result<()> {
+        let path = self.path();
+        git(
+            &path,
+            [
+                "checkout",
+                "--detach",
+                "--force",
+                "--quiet",
+                commit_id.to_string().as_str(),
+            ],
+        )
+        .with_context(|| format!("Failed to check out {commit_id} in the temporary worktree"))?;
+        git(&path, ["clean", "-d", "--force", "--quiet"])
+    }
+}
+
+impl Drop for TempWorktree {
+    fn drop(&mut self) {
+        let path = self.path();
+        if git(
+            &self.workdir,
+            [
+                OsStr::new("worktree"),
+                "remove".as_ref(),
+                "--force".as_ref(),
+                path.as_os_str(),
+            ],
+        )
+        .is_err()
+        {
+            // The directory goes away with `tmp`, and with it all Git needs to forget about it.
+            _ = git(&self.workdir, ["worktree", "prune"]);
+        }
+    }
+}
+
+fn git<I, S>(dir: &Path, args: I) -> anyhow::Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new(gix::path::env::exe_invocation())
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to execute git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
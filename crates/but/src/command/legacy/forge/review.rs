@@ -399,12 +399,11 @@ async fn ensure_forge_authentication(ctx: &mut Context) -> Result<(), anyhow::Er
             .await?;
 
     let forge_display_name = match forge_repo_info.forge {
-        but_forge::ForgeName::Azure => {
-            anyhow::bail!("Azure is unsupported at the minute. Sorry 😞.");
-        }
         but_forge::ForgeName::GitHub => "GitHub",
         but_forge::ForgeName::GitLab => "GitLab",
         but_forge::ForgeName::Bitbucket => "Bitbucket",
+        but_forge::ForgeName::Azure => "Azure DevOps",
+        but_forge::ForgeName::Gitea => "Gitea",
     };
 
     match account_validity {
@@ -0,0 +1,78 @@
+//! Let Git use the fsmonitor journal of the GitButler file watcher.
+
+use std::path::Path;
+
+use anyhow::{Context as _, bail};
+use but_core::git_config::{edit_repo_config, remove_config_value, set_config_value};
+use gitbutler_watcher::fsmonitor;
+use serde_json::json;
+
+use crate::{
+    args::fsmonitor::Subcommands,
+    theme::{self, Paint as _},
+    utils::OutputChannel,
+};
+
+/// What Git runs to query the file watcher, with the protocol version and token appended.
+const HOOK_COMMAND: &str = "but fsmonitor hook";
+const FSMONITOR: &str = "core.fsmonitor";
+const FSMONITOR_HOOK_VERSION: &str = "core.fsmonitorHookVersion";
+
+/// Let Git use the file watcher of the repository at `current_dir`, stop it from doing so, or answer
+/// a query of Git about changed files.
+pub fn handle(current_dir: &Path, cmd: Subcommands, out: &mut OutputChannel) -> anyhow::Result<()> {
+    let repo = gix::discover(current_dir).context("fsmonitor needs a Git repository")?;
+    match cmd {
+        Subcommands::Hook { version, token } => {
+            // Git reads the response as is, so it must not go through the output channel.
+            let mut stdout = std::io::stdout().lock();
+            fsmonitor::write_hook_response(repo.git_dir(), version, &token, &mut stdout)
+        }
+        Subcommands::Enable => {
+            let current = repo.config_snapshot().string(FSMONITOR);
+            if let Some(current) = current.filter(|current| **current != *HOOK_COMMAND) {
+                bail!(
+                    "{FSMONITOR} is already set to '{current}', unset it first to use the GitButler file watcher"
+                );
+            }
+            edit_repo_config(&repo, gix::config::Source::Local, |config| {
+                set_config_value(config, FSMONITOR, HOOK_COMMAND)?;
+                set_config_value(config, FSMONITOR_HOOK_VERSION, "2")
+            })?;
+            report(out, true)
+        }
+        Subcommands::Disable => {
+            let is_ours = repo
+                .config_snapshot()
+                .string(FSMONITOR)
+                .is_some_and(|current| *current == *HOOK_COMMAND);
+            if is_ours {
+                edit_repo_config(&repo, gix::config::Source::Local, |config| {
+                    remove_config_value(config, FSMONITOR)?;
+                    remove_config_value(config, FSMONITOR_HOOK_VERSION)
+                })?;
+            }
+            report(out, false)
+        }
+    }
+}
+
+fn report(out: &mut OutputChannel, enabled: bool) -> anyhow::Result<()> {
+    if let Some(out) = out.for_human() {
+        let t = theme::get();
+        if enabled {
+            writeln!(
+                out,
+                "Git now asks the GitButler file watcher which files changed.\n{}",
+                t.hint.paint(
+                    "This helps while the GitButler app or `but watch` runs for this repository."
+                )
+            )?;
+        } else {
+            writeln!(out, "Git no longer asks the GitButler file watcher.")?;
+        }
+    } else if let Some(out) = out.for_json() {
+        out.write_value(json!({ "enabled": enabled }))?;
+    }
+    Ok(())
+}
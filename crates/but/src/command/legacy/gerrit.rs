@@ -0,0 +1,344 @@
+//! Implementation of the `but gerrit` command family, which works with the relation chains
+//! that stacks become when they are pushed to Gerrit.
+
+use std::fmt::Write;
+
+use anyhow::{Result, bail};
+use but_api::{json::HexHash, legacy::gerrit::GerritRelationChain};
+use but_core::{DryRun, RepositoryExt};
+use but_ctx::Context;
+use but_gerrit::{ChainLink, ChainLinkState, GerritChangeState};
+use serde::Serialize;
+
+use crate::{
+    bad_input,
+    theme::{self, Paint},
+    utils::{Confirm, ConfirmDefault, OutputChannel},
+};
+
+/// Show the relation chain of every stack that has commits.
+pub fn show_chains(ctx: &Context, out: &mut OutputChannel) -> Result<()> {
+    let chains = relation_chains(ctx)?;
+    if let Some(out) = out.for_json() {
+        out.write_value(&chains)?;
+        return Ok(());
+    }
+    let Some(out) = out.for_human() else {
+        return Ok(());
+    };
+    let t = theme::get();
+    if chains.is_empty() {
+        writeln!(out, "There are no commits in the workspace.")?;
+        return Ok(());
+    }
+    for chain in &chains {
+        writeln!(
+            out,
+            "{}",
+            t.local_branch
+                .paint(chain.name.as_deref().unwrap_or("<anonymous stack>"))
+        )?;
+        for link in chain.links.iter().rev() {
+            let number = link
+                .status
+                .as_ref()
+                .map(|status| format!(" #{}", status.number))
+                .unwrap_or_default();
+            let patchset = link
+                .patchset
+                .as_ref()
+                .map(|patchset| format!(" ps{}", patchset.number))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "  {}{}{} {} {}",
+                theme::Commit(link.commit_id),
+                t.pr_number.paint(number),
+                t.hint.paint(patchset),
+                link.title,
+                state_label(link),
+            )?;
+        }
+    }
+    if chains
+        .iter()
+        .flat_map(|chain| &chain.links)
+        .any(|link| link.state == ChainLinkState::UploadedByOthers)
+    {
+        writeln!(
+            out,
+            "\nRun {} to pull the patchsets uploaded by others into your stacks.",
+            t.command_suggestion.paint("but gerrit pull")
+        )?;
+    }
+    Ok(())
+}
+
+/// Pull the patchsets that others uploaded for `changes`, or offer each one that exists if no
+/// change is given, unless `yes` is set to pull all of them without asking.
+pub fn pull(
+    ctx: &mut Context,
+    changes: Vec<String>,
+    yes: bool,
+    dry_run: bool,
+    out: &mut OutputChannel,
+) -> Result<()> {
+    let pullable: Vec<_> = relation_chains(ctx)?
+        .into_iter()
+        .flat_map(|chain| chain.links)
+        .filter(|link| link.state == ChainLinkState::UploadedByOthers)
+        .collect();
+    let selected: Vec<ChainLink> = if !changes.is_empty() {
+        changes
+            .iter()
+            .map(|change| {
+                pullable
+                    .iter()
+                    .find(|link| link_matches(link, change))
+                    .cloned()
+                    .ok_or_else(|| {
+                        bad_input(format!(
+                            "'{change}' is not a change with a patchset uploaded by others"
+                        ))
+                        .into()
+                    })
+            })
+            .collect::<Result<_>>()?
+    } else if pullable.is_empty() {
+        if let Some(out) = out.for_human() {
+            writeln!(out, "There are no patchsets uploaded by others to pull.")?;
+        }
+        return Ok(());
+    } else if yes {
+        pullable
+    } else if let Some(mut inout) = out.prepare_for_terminal_input() {
+        let mut selected = Vec::new();
+        for link in pullable {
+            let prompt = format!("Pull {} into '{}'?", describe_patchset(&link), link.title);
+            if inout.confirm(prompt, ConfirmDefault::Yes)? == Confirm::Yes {
+                selected.push(link);
+            }
+        }
+        selected
+    } else {
+        return Err(bad_input(
+            "Non-interactive environment detected. Provide the changes to pull, or pass --yes to pull all of them.",
+        )
+        .into());
+    };
+    if selected.is_empty() {
+        return Ok(());
+    }
+
+    let dry_run = if dry_run { DryRun::Yes } else { DryRun::No };
+    let workspace = but_api::legacy::gerrit::gerrit_pull_patchsets(
+        ctx,
+        selected.iter().map(|link| link.change_id.clone()).collect(),
+        dry_run,
+    )?;
+
+    let pulled: Vec<_> = selected
+        .iter()
+        .filter_map(|link| {
+            let patchset = link.patchset.as_ref()?;
+            let new_commit_id = workspace
+                .replaced_commits
+                .get(&link.commit_id)
+                .copied()
+                .unwrap_or(link.commit_id);
+            Some(PulledPatchset {
+                change_id: link.change_id.clone(),
+                number: link.status.as_ref().map(|status| status.number),
+                patchset: patchset.number,
+                commit_id: new_commit_id.into(),
+                needs_push: new_commit_id != patchset.commit_id,
+            })
+        })
+        .collect();
+
+    if let Some(out) = out.for_json() {
+        out.write_value(&pulled)?;
+    } else if let Some(out) = out.for_human() {
+        let t = theme::get();
+        for (link, pulled) in selected.iter().zip(&pulled) {
+            writeln!(
+                out,
+                "{} {} into '{}'",
+                if dry_run == DryRun::Yes {
+                    "Would pull"
+                } else {
+                    "Pulled"
+                },
+                describe_patchset(link),
+                link.title
+            )?;
+            if pulled.needs_push {
+                writeln!(
+                    out,
+                    "  {}",
+                    t.hint
+                        .paint("It was rebased onto your stack and needs to be pushed again.")
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The outcome of pulling a single patchset.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PulledPatchset {
+    change_id: String,
+    number: Option<i64>,
+    patchset: u32,
+    /// The id of the local commit that now holds the patchset.
+    commit_id: HexHash,
+    /// Whether the patchset had to be rebased, so the local commit differs from the one on the server.
+    needs_push: bool,
+}
+
+/// Submit the relation chain of the stack whose top-most branch is `branch`, or of the only stack
+/// if there is just one, after asking for confirmation unless `yes` is set.
+pub fn submit(
+    ctx: &Context,
+    branch: Option<&str>,
+    yes: bool,
+    out: &mut OutputChannel,
+) -> Result<()> {
+    let chains = relation_chains(ctx)?;
+    let chain = match branch {
+        Some(branch) => chains
+            .iter()
+            .find(|chain| chain.name.as_deref() == Some(branch))
+            .ok_or_else(|| {
+                bad_input(format!(
+                    "There is no stack with the branch '{branch}' on top"
+                ))
+            })?,
+        None => match chains.as_slice() {
+            [chain] => chain,
+            [] => bail!("There are no commits in the workspace to submit"),
+            _ => {
+                return Err(bad_input(
+                    "There are multiple stacks, provide the name of the top-most branch of the one to submit.",
+                )
+                .into());
+            }
+        },
+    };
+    let Some(stack_id) = chain.stack_id else {
+        bail!("Only stacks in the workspace can be submitted");
+    };
+    let open = chain
+        .links
+        .iter()
+        .filter(|link| link.state != ChainLinkState::Merged)
+        .count();
+
+    if !yes {
+        let name = chain.name.as_deref().unwrap_or("<anonymous stack>");
+        let Some(mut inout) = out.prepare_for_terminal_input() else {
+            return Err(bad_input(
+                "Non-interactive environment detected. Pass --yes to submit without confirmation.",
+            )
+            .into());
+        };
+        let prompt = format!("Submit {open} open change(s) of '{name}'?");
+        if inout.confirm(prompt, ConfirmDefault::No)? == Confirm::No {
+            return Ok(());
+        }
+    }
+
+    let state = but_api::legacy::gerrit::gerrit_submit_chain(ctx, stack_id)?;
+    if let Some(out) = out.for_json() {
+        out.write_value(serde_json::json!({ "state": state }))?;
+    } else if let Some(out) = out.for_human() {
+        let t = theme::get();
+        match state {
+            GerritChangeState::Merged => {
+                writeln!(out, "{}", t.success.paint(format!("Submitted {open} change(s).")))?
+            }
+            state => writeln!(
+                out,
+                "{}",
+                t.attention.paint(format!(
+                    "The chain was submitted, but its top-most change is {state:?} instead of merged."
+                ))
+            )?,
+        }
+    }
+    Ok(())
+}
+
+/// Obtain the relation chains of all stacks with commits, or fail if Gerrit mode is disabled.
+fn relation_chains(ctx: &Context) -> Result<Vec<GerritRelationChain>> {
+    if !ctx
+        .repo
+        .get()?
+        .git_settings()?
+        .gitbutler_gerrit_mode
+        .unwrap_or(false)
+    {
+        return Err(bad_input("Gerrit mode is not enabled for this repository.")
+            .hint("Enable it with `git config gitbutler.gerritMode true`.")
+            .into());
+    }
+    Ok(but_api::legacy::gerrit::gerrit_relation_chains(ctx)?
+        .into_iter()
+        .filter(|chain| !chain.links.is_empty())
+        .collect())
+}
+
+/// Return `true` if `change` is the Gerrit change number of `link`, or a prefix of its commit id.
+fn link_matches(link: &ChainLink, change: &str) -> bool {
+    link.status
+        .as_ref()
+        .is_some_and(|status| status.number.to_string() == change)
+        || (change.len() >= 4 && link.commit_id.to_hex().to_string().starts_with(change))
+}
+
+/// Describe the patchset of `link` for display, like `patchset 3 of #4711 by Jane`.
+fn describe_patchset(link: &ChainLink) -> String {
+    let mut out = String::from("patchset");
+    if let Some(patchset) = &link.patchset {
+        out.push_str(&format!(" {}", patchset.number));
+    }
+    if let Some(status) = &link.status {
+        out.push_str(&format!(" of #{}", status.number));
+    }
+    if let Some(uploader) = link
+        .patchset
+        .as_ref()
+        .and_then(|patchset| patchset.uploader.as_deref())
+    {
+        out.push_str(&format!(" by {uploader}"));
+    }
+    out
+}
+
+fn state_label(link: &ChainLink) -> String {
+    let t = theme::get();
+    match link.state {
+        ChainLinkState::NotPushed => t.hint.paint("not pushed").to_string(),
+        ChainLinkState::Missing => t.error.paint("not found on server").to_string(),
+        ChainLinkState::UpToDate => {
+            let submittable = link
+                .status
+                .as_ref()
+                .is_some_and(|status| status.submittable);
+            if submittable {
+                t.success.paint("✓ submittable").to_string()
+            } else {
+                t.success.paint("up-to-date").to_string()
+            }
+        }
+        ChainLinkState::NeedsPush => t.attention.paint("needs push").to_string(),
+        ChainLinkState::UploadedByOthers => t
+            .attention
+            .paint(format!("newer {}", describe_patchset(link)))
+            .to_string(),
+        ChainLinkState::Merged => t.success.paint("merged").to_string(),
+        ChainLinkState::Abandoned => t.hint.paint("abandoned").to_string(),
+    }
+}
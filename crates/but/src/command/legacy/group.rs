@@ -0,0 +1,570 @@
+//! Split uncommitted changes into branches and commits as proposed by an AI model.
+
+use std::collections::HashSet;
+
+use anyhow::Context as _;
+use bstr::ByteSlice as _;
+use but_action::group::{GroupingProposal, HunkToGroup};
+use but_api::WorkspaceState;
+use but_ctx::Context;
+use but_hunk_dependency::ui::hunk_dependencies_for_workspace_changes_by_worktree_dir;
+use but_llm::LLMProvider;
+use nonempty::nonempty;
+use serde::Serialize;
+
+use crate::{
+    ChangeSourceId, CliResult, IdMap,
+    args::group::Platform,
+    command::legacy::apply_plan::{self, ApplyPlanOutcome, PlanOperation},
+    theme::{self, Paint as _},
+    utils::{CliOutput, CliOutputHuman, OutputChannel, WriteWithUtils, rejection},
+};
+
+/// A hunk the proposal could not place, and why.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LeftUncommitted {
+    id: String,
+    reason: String,
+}
+
+/// The proposal of the model, validated and turned into a plan for `but apply-plan`.
+#[derive(Debug, Default, PartialEq)]
+struct Plan {
+    operations: Vec<PlanOperation>,
+    left_uncommitted: Vec<LeftUncommitted>,
+}
+
+pub struct GroupOutcome {
+    plan: Plan,
+    applied: Option<ApplyPlanOutcome>,
+}
+
+impl CliOutputHuman for GroupOutcome {
+    fn on_human(
+        self,
+        out: &mut dyn WriteWithUtils,
+        agent: bool,
+        theme: &'static theme::Theme,
+    ) -> anyhow::Result<()> {
+        match self.applied {
+            Some(applied) => applied.on_human(out, agent, theme),
+            None => {
+                writeln!(out, "{}", theme.hint.paint("Nothing was applied."))?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl CliOutput for GroupOutcome {
+    fn on_json(self) -> impl Serialize {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Output<Applied> {
+            plan: Vec<PlanOperation>,
+            left_uncommitted: Vec<LeftUncommitted>,
+            applied: Option<Applied>,
+        }
+
+        Output {
+            plan: self.plan.operations,
+            left_uncommitted: self.plan.left_uncommitted,
+            applied: self.applied.map(CliOutput::on_json),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Choice {
+    Apply,
+    Edit,
+    Cancel,
+}
+
+pub fn handle(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    args: Platform,
+) -> CliResult<Option<WorkspaceState>> {
+    let (hunks, branches) = hunks_to_group(ctx)?;
+    if hunks.is_empty() {
+        if let Some(out) = out.for_human() {
+            writeln!(out, "There are no uncommitted changes to group.")?;
+        } else if let Some(out) = out.for_json() {
+            out.write_value(
+                GroupOutcome {
+                    plan: Plan::default(),
+                    applied: None,
+                }
+                .on_json(),
+            )?;
+        }
+        return Ok(None);
+    }
+
+    let llm = {
+        let repo = ctx.repo.get()?;
+        let config = repo.config_snapshot();
+        LLMProvider::from_git_config(config.plumbing())
+            .context("AI is not configured. Configure an AI provider with `but config ai` first.")?
+    };
+    {
+        let mut progress = out.progress_channel();
+        writeln!(
+            progress,
+            "Asking the AI to group {} {}…",
+            hunks.len(),
+            if hunks.len() == 1 { "hunk" } else { "hunks" }
+        )?;
+    }
+    let proposal = but_action::group::hunks(&llm, &hunks, &branches, args.prompt.as_deref())?;
+    let plan = plan_from_proposal(&hunks, &branches, proposal);
+
+    if let Some(out) = out.for_human() {
+        write_plan(out, &plan)?;
+    }
+    if plan.operations.is_empty() {
+        out.print_cli_output(GroupOutcome {
+            plan,
+            applied: None,
+        })?;
+        return Ok(None);
+    }
+
+    if let Some(path) = &args.output {
+        let yaml = serde_yaml_ng::to_string(&plan.operations)?;
+        std::fs::write(path, yaml)
+            .with_context(|| format!("Could not write the plan to {}", path.display()))?;
+        if let Some(out) = out.for_human() {
+            writeln!(
+                out,
+                "Wrote the plan to {}. Apply it with `but apply-plan {}`.",
+                path.display(),
+                path.display()
+            )?;
+        } else if let Some(out) = out.for_json() {
+            out.write_value(
+                GroupOutcome {
+                    plan,
+                    applied: None,
+                }
+                .on_json(),
+            )?;
+        }
+        return Ok(None);
+    }
+
+    let operations = if args.yes || args.dry_run {
+        plan.operations.clone()
+    } else {
+        let choice = match out.prepare_for_terminal_input() {
+            Some(mut inout) => {
+                let options = nonempty![
+                    ("Apply", Choice::Apply),
+                    ("Edit the plan, then apply", Choice::Edit),
+                    ("Cancel", Choice::Cancel),
+                ];
+                inout
+                    .prompt_select("Apply this plan?", &options)?
+                    .copied()
+                    .unwrap_or(Choice::Cancel)
+            }
+            None => {
+                if let Some(out) = out.for_human() {
+                    writeln!(
+                        out,
+                        "{}",
+                        theme::get().hint.paint(
+                            "Run with --yes to apply the plan, or with --output to save it for editing."
+                        )
+                    )?;
+                }
+                Choice::Cancel
+            }
+        };
+        match choice {
+            Choice::Apply => plan.operations.clone(),
+            Choice::Edit => match edit_plan(&plan.operations)? {
+                Some(operations) => operations,
+                None => Vec::new(),
+            },
+            Choice::Cancel => Vec::new(),
+        }
+    };
+    if operations.is_empty() {
+        out.print_cli_output(GroupOutcome {
+            plan,
+            applied: None,
+        })?;
+        return Ok(None);
+    }
+
+    let (applied, ws) = apply_plan::apply_operations(ctx, operations, args.dry_run)?;
+    out.print_cli_output(GroupOutcome {
+        plan,
+        applied: Some(applied),
+    })?;
+    Ok(ws)
+}
+
+/// Collect the uncommitted hunks of the main worktree along with the branches they are locked to,
+/// and the names of all branches in the workspace.
+fn hunks_to_group(ctx: &Context) -> anyhow::Result<(Vec<HunkToGroup>, Vec<String>)> {
+    let guard = ctx.shared_worktree_access();
+    let id_map = IdMap::new_from_context(ctx, guard.read_permission())?;
+    let (repo, ws, _db) = ctx.workspace_and_db_with_perm(guard.read_permission())?;
+    let dependencies = hunk_dependencies_for_workspace_changes_by_worktree_dir(&repo, &ws, None)
+        .context("Could not compute the dependencies of uncommitted changes")?;
+
+    let mut hunks = Vec::new();
+    for file in id_map
+        .uncommitted_files
+        .values()
+        .filter(|file| file.source == ChangeSourceId::Head)
+    {
+        let file_hunks = file.hunks();
+        let is_single_hunk = file_hunks.len() == 1;
+        for (hunk_id, hunk) in file_hunks {
+            hunks.push(HunkToGroup {
+                // A file with a single hunk is referred to as a whole, keeping the IDs short.
+                id: if is_single_hunk {
+                    file.short_id.to_string()
+                } else {
+                    format!("{}:{hunk_id}", file.short_id)
+                },
+                path: file.path().to_str_lossy().into_owned(),
+                diff: hunk.diff.as_ref().map_or_else(
+                    || "(binary change)".to_owned(),
+                    |diff| diff.to_str_lossy().into_owned(),
+                ),
+                locked_to: rejection::locking_branches(
+                    &ws,
+                    &dependencies,
+                    file.path(),
+                    hunk.hunk_header.as_ref(),
+                ),
+            });
+        }
+    }
+
+    let branches = ws
+        .stacks
+        .iter()
+        .flat_map(|stack| &stack.segments)
+        .filter_map(|segment| segment.ref_name())
+        .map(|ref_name| ref_name.shorten().to_string())
+        .collect();
+    Ok((hunks, branches))
+}
+
+/// Turn `proposal` into plan operations, leaving out every hunk that is unknown, assigned more than
+/// once, or that can't be committed to its proposed branch because it depends on another branch.
+///
+/// New branches are stacked on top of the branch their first locked hunk depends on, so that all of
+/// their hunks that depend on that branch can be committed.
+fn plan_from_proposal(
+    hunks: &[HunkToGroup],
+    branches: &[String],
+    proposal: GroupingProposal,
+) -> Plan {
+    let mut plan = Plan::default();
+    let mut seen = HashSet::new();
+    let mut created: Vec<String> = Vec::new();
+
+    for branch in proposal.branches {
+        let name = branch.name.trim().to_owned();
+        if name.is_empty() {
+            continue;
+        }
+        let exists = branches.contains(&name) || created.contains(&name);
+        // The branch that locked hunks must depend on to be committed to `name`.
+        let mut anchor = exists.then(|| name.clone());
+        let mut commits = Vec::new();
+        for commit in branch.commits {
+            let mut changes = Vec::new();
+            for id in commit.hunk_ids {
+                let Some(hunk) = hunks.iter().find(|hunk| hunk.id == id) else {
+                    tracing::warn!(%id, "The AI proposed an unknown hunk");
+                    continue;
+                };
+                if !seen.insert(hunk.id.as_str()) {
+                    continue;
+                }
+                if let [lock] = hunk.locked_to.as_slice() {
+                    if anchor.get_or_insert_with(|| lock.clone()) != lock {
+                        plan.left_uncommitted.push(LeftUncommitted {
+                            id: hunk.id.clone(),
+                            reason: format!("depends on {lock}, but was proposed for {name}"),
+                        });
+                        continue;
+                    }
+                } else if !hunk.locked_to.is_empty() {
+                    plan.left_uncommitted.push(LeftUncommitted {
+                        id: hunk.id.clone(),
+                        reason: format!("depends on {}", hunk.locked_to.join(", ")),
+                    });
+                    continue;
+                }
+                changes.push(hunk.id.clone());
+            }
+            if !changes.is_empty() {
+                commits.push(PlanOperation::Commit {
+                    branch: name.clone(),
+                    message: commit.message.trim().to_owned(),
+                    changes,
+                });
+            }
+        }
+
+        if commits.is_empty() {
+            continue;
+        }
+        if !exists {
+            plan.operations.push(PlanOperation::Branch {
+                name: name.clone(),
+                above: anchor,
+            });
+            created.push(name);
+        }
+        plan.operations.extend(commits);
+    }
+
+    plan.left_uncommitted.extend(
+        hunks
+            .iter()
+            .filter(|hunk| !seen.contains(hunk.id.as_str()))
+            .map(|hunk| LeftUncommitted {
+                id: hunk.id.clone(),
+                reason: "was not assigned to a commit".into(),
+            }),
+    );
+    plan
+}
+
+fn write_plan(out: &mut dyn WriteWithUtils, plan: &Plan) -> anyhow::Result<()> {
+    let t = theme::get();
+    if plan.operations.is_empty() {
+        writeln!(out, "The AI proposed no commits.")?;
+    } else {
+        writeln!(out, "{}", t.important.paint("Proposed plan:"))?;
+    }
+    for (index, operation) in plan.operations.iter().enumerate() {
+        match operation {
+            PlanOperation::Branch {
+                name,
+                above: Some(above),
+            } => writeln!(
+                out,
+                "  {}. create branch {} above {}",
+                index + 1,
+                t.local_branch.paint(name),
+                t.local_branch.paint(above)
+            )?,
+            PlanOperation::Branch { name, above: None } => writeln!(
+                out,
+                "  {}. create branch {}",
+                index + 1,
+                t.local_branch.paint(name)
+            )?,
+            PlanOperation::Commit {
+                branch,
+                message,
+                changes,
+            } => {
+                writeln!(
+                    out,
+                    "  {}. commit {} to {}",
+                    index + 1,
+                    changes
+                        .iter()
+                        .map(|id| t.cli_id.paint(id).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    t.local_branch.paint(branch)
+                )?;
+                for line in message.lines() {
+                    writeln!(out, "       {line}")?;
+                }
+            }
+            _ => unreachable!("proposals only create branches and commits"),
+        }
+    }
+    if !plan.left_uncommitted.is_empty() {
+        writeln!(out, "{}", t.important.paint("Left uncommitted:"))?;
+        for hunk in &plan.left_uncommitted {
+            writeln!(out, "  {} {}", t.cli_id.paint(&hunk.id), hunk.reason)?;
+        }
+    }
+    Ok(())
+}
+
+/// Let the user edit `operations` as YAML plan, returning `None` if everything was removed.
+fn edit_plan(operations: &[PlanOperation]) -> CliResult<Option<Vec<PlanOperation>>> {
+    let initial = format!(
+        "# Edit the plan in the format of `but apply-plan`, and save it to apply it.\n\
+         # Remove all operations to cancel.\n{}",
+        serde_yaml_ng::to_string(operations)?
+    );
+    let edited = crate::tui::get_text::from_editor("group_plan", &initial, None, ".yaml")?;
+    let edited = edited.to_str_lossy();
+    if edited
+        .lines()
+        .all(|line| line.trim().is_empty() || line.trim_start().starts_with('#'))
+    {
+        return Ok(None);
+    }
+    apply_plan::parse_plan(&edited, false).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use but_action::group::{GroupingProposal, HunkToGroup, ProposedBranch, ProposedCommit};
+
+    use super::{LeftUncommitted, Plan, plan_from_proposal};
+    use crate::command::legacy::apply_plan::PlanOperation;
+
+    fn hunk(id: &str, locked_to: &[&str]) -> HunkToGroup {
+        HunkToGroup {
+            id: id.into(),
+            path: "file".into(),
+            diff: "@@ -1 +1 @@\n-a\n+b\n".into(),
+            locked_to: locked_to.iter().map(|branch| branch.to_string()).collect(),
+        }
+    }
+
+    fn branch(name: &str, commits: &[(&str, &[&str])]) -> ProposedBranch {
+        ProposedBranch {
+            name: name.into(),
+            commits: commits
+                .iter()
+                .map(|(message, hunk_ids)| ProposedCommit {
+                    message: message.to_string(),
+                    hunk_ids: hunk_ids.iter().map(|id| id.to_string()).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn left(id: &str, reason: &str) -> LeftUncommitted {
+        LeftUncommitted {
+            id: id.into(),
+            reason: reason.into(),
+        }
+    }
+
+    #[test]
+    fn new_branches_are_created_before_their_commits() {
+        let hunks = [hunk("ab", &[]), hunk("cd:01", &[]), hunk("cd:02", &[])];
+        let proposal = GroupingProposal {
+            branches: vec![
+                branch("A", &[("Fix a", &["ab"])]),
+                branch("feature", &[("First", &["cd:01"]), ("Second", &["cd:02"])]),
+            ],
+        };
+
+        let plan = plan_from_proposal(&hunks, &["A".into()], proposal);
+        assert_eq!(
+            plan,
+            Plan {
+                operations: vec![
+                    PlanOperation::Commit {
+                        branch: "A".into(),
+                        message: "Fix a".into(),
+                        changes: vec!["ab".into()],
+                    },
+                    PlanOperation::Branch {
+                        name: "feature".into(),
+                        above: None,
+                    },
+                    PlanOperation::Commit {
+                        branch: "feature".into(),
+                        message: "First".into(),
+                        changes: vec!["cd:01".into()],
+                    },
+                    PlanOperation::Commit {
+                        branch: "feature".into(),
+                        message: "Second".into(),
+                        changes: vec!["cd:02".into()],
+                    },
+                ],
+                left_uncommitted: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn locked_hunks_stack_new_branches_or_stay_uncommitted() {
+        let hunks = [
+            hunk("ab", &["A"]),
+            hunk("cd", &["B"]),
+            hunk("ef", &["B"]),
+            hunk("gh", &["A", "B"]),
+            hunk("ij", &[]),
+        ];
+        let proposal = GroupingProposal {
+            branches: vec![
+                branch("A", &[("Into A", &["ab", "cd"])]),
+                branch("new", &[("Into new", &["ef", "gh", "zz"])]),
+            ],
+        };
+
+        let plan = plan_from_proposal(&hunks, &["A".into(), "B".into()], proposal);
+        assert_eq!(
+            plan,
+            Plan {
+                operations: vec![
+                    PlanOperation::Commit {
+                        branch: "A".into(),
+                        message: "Into A".into(),
+                        changes: vec!["ab".into()],
+                    },
+                    PlanOperation::Branch {
+                        name: "new".into(),
+                        above: Some("B".into()),
+                    },
+                    PlanOperation::Commit {
+                        branch: "new".into(),
+                        message: "Into new".into(),
+                        changes: vec!["ef".into()],
+                    },
+                ],
+                left_uncommitted: vec![
+                    left("cd", "depends on B, but was proposed for A"),
+                    left("gh", "depends on A, B"),
+                    left("ij", "was not assigned to a commit"),
+                ],
+            },
+            "unknown hunks are ignored"
+        );
+    }
+
+    #[test]
+    fn hunks_are_committed_only_once_and_empty_branches_are_skipped() {
+        let hunks = [hunk("ab", &[])];
+        let proposal = GroupingProposal {
+            branches: vec![
+                branch("first", &[("One", &["ab"])]),
+                branch("second", &[("Again", &["ab"])]),
+                branch("  ", &[("Unnamed", &["ab"])]),
+            ],
+        };
+
+        let plan = plan_from_proposal(&hunks, &[], proposal);
+        assert_eq!(
+            plan.operations,
+            vec![
+                PlanOperation::Branch {
+                    name: "first".into(),
+                    above: None,
+                },
+                PlanOperation::Commit {
+                    branch: "first".into(),
+                    message: "One".into(),
+                    changes: vec!["ab".into()],
+                },
+            ]
+        );
+        assert_eq!(plan.left_uncommitted, vec![]);
+    }
+}
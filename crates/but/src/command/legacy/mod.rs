@@ -8,6 +8,7 @@ pub mod absorb;
 pub mod actions;
 pub mod amend;
 pub mod apply;
+pub mod apply_plan;
 pub mod branch;
 pub mod clean;
 pub mod commit;
@@ -17,7 +18,11 @@ pub mod diff;
 #[cfg(feature = "legacy")]
 pub mod diff2;
 pub mod discard;
+pub mod exec;
 pub mod forge;
+pub mod fsmonitor;
+pub mod gerrit;
+pub mod group;
 pub mod land;
 pub mod r#move;
 pub mod open;
@@ -31,6 +36,7 @@ pub mod reword;
 pub mod reword2;
 pub mod setup;
 pub mod show;
+pub mod split;
 pub mod squash;
 pub mod status;
 pub mod teardown;
@@ -38,6 +44,7 @@ pub mod unapply;
 pub mod uncommit;
 pub mod undo_redo;
 pub(crate) mod upstream;
+pub mod watch;
 pub(crate) mod workspace_target;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
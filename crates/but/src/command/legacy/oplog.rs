@@ -1,7 +1,16 @@
+use std::{path::Path, time::Duration};
+
 use but_api::legacy::oplog::RestoreKind;
-use but_core::RepositoryExt;
-use gitbutler_oplog::entry::{OperationKind, Snapshot, Trailer};
-use gix::{date::time::CustomFormat, prelude::ObjectIdExt};
+use but_core::{DryRun, RepositoryExt};
+use gitbutler_oplog::{
+    RetentionPolicy, SnapshotRange,
+    entry::{OperationKind, Snapshot, Trailer},
+};
+use gix::{
+    bstr::{BString, ByteSlice},
+    date::time::CustomFormat,
+    prelude::ObjectIdExt,
+};
 
 use crate::{
     theme::{self, Paint},
@@ -90,7 +99,7 @@ pub(crate) fn show_oplog(
                         .filter(|b| !b.is_empty())
                         .cloned()
                         .unwrap_or_else(|| details.operation.title().to_owned()),
-                    OperationKind::Discard => {
+                    OperationKind::Discard | OperationKind::RestorePathsFromSnapshot => {
                         let file_names = details
                             .trailers
                             .iter()
@@ -187,6 +196,7 @@ pub(crate) fn show_oplog(
                     | OperationKind::AutoHandleChangesBefore
                     | OperationKind::AutoHandleChangesAfter
                     | OperationKind::SplitBranch
+                    | OperationKind::SplitCommit
                     | OperationKind::CleanWorkspace
                     | OperationKind::Unknown => details.operation.title().to_owned(),
                 };
@@ -206,7 +216,8 @@ pub(crate) fn show_oplog(
                 OperationKind::UndoCommit
                 | OperationKind::RestoreFromSnapshot
                 | OperationKind::RestoreFromSnapshotViaUndo
-                | OperationKind::RestoreFromSnapshotViaRedo => {
+                | OperationKind::RestoreFromSnapshotViaRedo
+                | OperationKind::RestorePathsFromSnapshot => {
                     t.error.paint(operation_type.kind_str())
                 }
                 OperationKind::DiscardChanges | OperationKind::Discard => {
@@ -215,6 +226,7 @@ pub(crate) fn show_oplog(
                 OperationKind::CreateBranch => t.local_branch.paint(operation_type.kind_str()),
                 OperationKind::MoveCommit
                 | OperationKind::ReorderCommit
+                | OperationKind::SplitCommit
                 | OperationKind::MoveHunk => t.info.paint(operation_type.kind_str()),
                 OperationKind::OnDemandSnapshot => t.hint.paint(operation_type.kind_str()),
                 OperationKind::StashIntoBranch
@@ -336,6 +348,76 @@ pub(crate) fn restore_to_oplog(
     Ok(())
 }
 
+pub(crate) fn restore_paths(
+    ctx: &mut but_ctx::Context,
+    out: &mut OutputChannel,
+    oplog_sha: &str,
+    paths: &[String],
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let commit_id = ctx.repo.get()?.rev_parse_single(oplog_sha)?.detach();
+    // Make sure it's a snapshot before looking into its tree.
+    but_api::legacy::oplog::get_snapshot(ctx, commit_id)?;
+    let paths: Vec<BString> = paths.iter().map(|path| path.as_str().into()).collect();
+
+    let changes = but_api::legacy::oplog::restore_snapshot_paths_diff(ctx, commit_id, &paths)?;
+    if changes.is_empty() {
+        return Err(
+            crate::bad_input("The given paths are already in the state of the snapshot.")
+                .hint("Paths are relative to the root of the repository.")
+                .into(),
+        );
+    }
+    let snapshot_id = if dry_run {
+        None
+    } else {
+        Some(but_api::legacy::oplog::restore_snapshot_paths(
+            ctx, commit_id, &paths,
+        )?)
+    };
+
+    if let Some(out) = out.for_json() {
+        out.write_value(serde_json::json!({
+            "snapshot_id": snapshot_id.map(|id| id.to_string()),
+            "restored_from": commit_id.to_string(),
+            "dry_run": dry_run,
+            "changes": changes
+                .iter()
+                .map(|change| serde_json::json!({
+                    "path": change.path.to_str_lossy(),
+                    "status": super::change_status(change),
+                }))
+                .collect::<Vec<_>>(),
+        }))?;
+    } else if let Some(out) = out.for_human() {
+        let t = theme::get();
+        let commit_short = {
+            let repo = ctx.repo.get()?;
+            shorten_object_id(&repo, commit_id)
+        };
+        let heading = if dry_run {
+            "Restoring would change these files:"
+        } else {
+            "Restored these files:"
+        };
+        writeln!(out, "{}", t.important.paint(heading))?;
+        for change in &changes {
+            writeln!(
+                out,
+                "  {:<8} {}",
+                super::change_status(change),
+                change.path.to_str_lossy()
+            )?;
+        }
+        writeln!(out, "  Snapshot: {}", t.commit_id.paint(&commit_short))?;
+        if !dry_run {
+            writeln!(out, "\n{} Restore completed successfully!", t.sym().success)?;
+        }
+    }
+
+    Ok(())
+}
+
 pub(crate) fn create_snapshot(
     ctx: &mut but_ctx::Context,
     out: &mut OutputChannel,
@@ -370,3 +452,184 @@ pub(crate) fn create_snapshot(
 
     Ok(())
 }
+
+/// Retention limits given on the command-line, which take precedence over the configured ones.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RetentionOverrides {
+    /// Overrides [`RetentionPolicy::max_age`], in days.
+    pub max_age_days: Option<u64>,
+    /// Overrides [`RetentionPolicy::max_count`].
+    pub max_count: Option<usize>,
+    /// Overrides [`RetentionPolicy::keep_every_nth`].
+    pub keep_every_nth: Option<usize>,
+    /// Overrides [`RetentionPolicy::thin_after`].
+    pub thin_after: Option<usize>,
+}
+
+pub(crate) fn gc(
+    ctx: &mut but_ctx::Context,
+    out: &mut OutputChannel,
+    overrides: RetentionOverrides,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let configured = RetentionPolicy::from_config(&ctx.repo.get()?.config_snapshot());
+    let policy = RetentionPolicy {
+        max_age: overrides
+            .max_age_days
+            .map(|days| Duration::from_secs(days * 24 * 60 * 60))
+            .or(configured.max_age),
+        max_count: overrides.max_count.or(configured.max_count),
+        keep_every_nth: overrides
+            .keep_every_nth
+            .filter(|nth| *nth > 0)
+            .or(configured.keep_every_nth),
+        thin_after: overrides.thin_after.or(configured.thin_after),
+    };
+    if policy.is_unlimited() {
+        return Err(crate::bad_input("No oplog retention policy is configured.")
+            .hint(
+                "Set `gitbutler.oplogMaxAgeDays`, `gitbutler.oplogMaxCount` or `gitbutler.oplogKeepEveryNth`, or pass --max-age-days, --max-count or --keep-every-nth.",
+            )
+            .into());
+    }
+
+    let dry_run = if dry_run { DryRun::Yes } else { DryRun::No };
+    let outcome = but_api::legacy::oplog::gc_snapshots(ctx, policy, dry_run)?;
+
+    if let Some(out) = out.for_json() {
+        out.write_value(&outcome)?;
+    } else if let Some(out) = out.for_human() {
+        let t = theme::get();
+        if outcome.removed_snapshots == 0 {
+            writeln!(
+                out,
+                "Nothing to remove, all {} snapshots are retained.",
+                outcome.kept_snapshots
+            )?;
+            return Ok(());
+        }
+        let (removed, reclaimable) = if dry_run == DryRun::Yes {
+            ("Would remove", "would become reclaimable")
+        } else {
+            ("Removed", "can be reclaimed by `git gc`")
+        };
+        writeln!(
+            out,
+            "{} {} of {} snapshots.",
+            t.success.paint(removed),
+            outcome.removed_snapshots,
+            outcome.removed_snapshots + outcome.kept_snapshots
+        )?;
+        writeln!(
+            out,
+            "{} objects ({}) {reclaimable}.",
+            outcome.reclaimable_objects,
+            human_bytes(outcome.reclaimable_bytes)
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn export(
+    ctx: &but_ctx::Context,
+    out: &mut OutputChannel,
+    path: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> anyhow::Result<()> {
+    let range = {
+        let repo = ctx.repo.get()?;
+        let resolve = |rev: Option<&str>| {
+            rev.map(|rev| {
+                repo.rev_parse_single(rev)
+                    .map(|id| id.detach())
+                    .map_err(|_| anyhow::anyhow!("No oplog entry found matching SHA: {rev}"))
+            })
+            .transpose()
+        };
+        SnapshotRange {
+            from: resolve(from)?,
+            to: resolve(to)?,
+        }
+    };
+    let outcome = but_api::legacy::oplog::export_snapshots(ctx, range, path)?;
+
+    if let Some(out) = out.for_json() {
+        out.write_value(&outcome)?;
+    } else if let Some(out) = out.for_human() {
+        let t = theme::get();
+        writeln!(
+            out,
+            "{}",
+            t.success.paint(format!(
+                "Exported {} snapshots to {}",
+                outcome.snapshots,
+                path.display()
+            ))
+        )?;
+        writeln!(
+            out,
+            "\n{} Use 'but oplog import {}' in another clone to import them.",
+            t.info.paint("💡"),
+            path.display()
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn import(
+    ctx: &mut but_ctx::Context,
+    out: &mut OutputChannel,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let outcome = but_api::legacy::oplog::import_snapshots(ctx, path)?;
+
+    if let Some(out) = out.for_json() {
+        out.write_value(&outcome)?;
+    } else if let Some(out) = out.for_human() {
+        let t = theme::get();
+        let repo = ctx.repo.get()?;
+        writeln!(
+            out,
+            "{}",
+            t.success.paint(format!(
+                "Imported {} snapshots from {}",
+                outcome.snapshots,
+                path.display()
+            ))
+        )?;
+        writeln!(
+            out,
+            "  Most recent snapshot: {}",
+            t.cli_id.paint(shorten_object_id(&repo, outcome.new_head))
+        )?;
+        for target in &outcome.missing_target_commits {
+            writeln!(
+                out,
+                "{}",
+                t.attention.paint(format!(
+                    "⚠ Target commit {} is missing, fetch it before restoring snapshots that use it.",
+                    shorten_object_id(&repo, *target)
+                ))
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
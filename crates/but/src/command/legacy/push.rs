@@ -97,6 +97,10 @@ pub async fn handle(
             let full_name = gix::refs::FullName::try_from(format!("refs/heads/{name}"))?;
             merged.ensure_branch_not_merged(full_name.as_ref())?;
         }
+        let mut progress = out.progress_channel();
+        for name in names {
+            warn_about_unsigned_commits(ctx, name, gerrit_mode, &mut progress)?;
+        }
     }
 
     match branch_selection {
@@ -711,6 +715,7 @@ async fn push_all_branches(
     for candidate in branches_to_push {
         let branch_name = candidate.branch_name;
         let unpushed_count = candidate.unpushed_commits;
+        warn_about_unsigned_commits(ctx, &branch_name, gerrit_mode, &mut progress)?;
         write!(
             progress,
             "  {} {}... ",
@@ -1210,17 +1215,112 @@ fn gerrit_review_ref(
     perm: &RepoShared,
     repo: &gix::Repository,
 ) -> anyhow::Result<String> {
-    let target_ref_name = workspace_target::ResolvedTarget::resolve_with_perm(ctx, perm)?
+    let target_branch = target_branch_short_name(ctx, perm, repo)?
+        .ok_or_else(|| anyhow::anyhow!("Failed to determine Gerrit target branch"))?;
+    Ok(format!("refs/for/{target_branch}"))
+}
+
+/// Return the name of the target branch on its remote, like `main` for `refs/remotes/origin/main`,
+/// or `None` if there is no target.
+fn target_branch_short_name(
+    ctx: &Context,
+    perm: &RepoShared,
+    repo: &gix::Repository,
+) -> anyhow::Result<Option<String>> {
+    let Some(target_ref_name) = workspace_target::ResolvedTarget::resolve_with_perm(ctx, perm)?
         .ref_name()
         .map(ToOwned::to_owned)
-        .ok_or_else(|| anyhow::anyhow!("Failed to determine Gerrit target branch"))?;
+    else {
+        return Ok(None);
+    };
     let remote_names = repo.remote_names();
-    let target_branch =
+    Ok(Some(
         but_core::extract_remote_name_and_short_name(target_ref_name.as_ref(), &remote_names)
             .map(|(_, short_name)| short_name.to_string())
-            .unwrap_or_else(|| target_ref_name.shorten().to_string());
+            .unwrap_or_else(|| target_ref_name.shorten().to_string()),
+    ))
+}
 
-    Ok(format!("refs/for/{target_branch}"))
+/// If `gitbutler.verifySignatures` is enabled and `branch_name` is in a stack that targets the
+/// target branch, which is typically protected and may only accept signed commits, warn about
+/// commits without a good signature that the push would include.
+/// In Gerrit mode, every push is for review on the target branch.
+/// Like with conflicted commits, the branch's stack ancestors are checked too.
+fn warn_about_unsigned_commits(
+    ctx: &Context,
+    branch_name: &str,
+    gerrit_mode: bool,
+    out: &mut dyn std::fmt::Write,
+) -> anyhow::Result<()> {
+    let repo = ctx.repo.get()?.clone().for_commit_shortening();
+    if !repo
+        .git_settings()?
+        .gitbutler_verify_signatures
+        .unwrap_or(false)
+    {
+        return Ok(());
+    }
+    let target_branch = {
+        let guard = ctx.shared_worktree_access();
+        target_branch_short_name(ctx, guard.read_permission(), &repo)?
+    };
+    // All applied stacks integrate into the workspace target, so without one there is
+    // nothing protected to push towards, unless Gerrit reviews the push.
+    if target_branch.is_none() && !gerrit_mode {
+        return Ok(());
+    }
+    let stacks = crate::legacy::workspace::applied_stacks(ctx)?;
+    let Some((stack, position)) = stacks.iter().find_map(|stack| {
+        stack.id.as_ref()?;
+        let position = stack.branches.iter().position(|b| b.name == branch_name)?;
+        Some((stack, position))
+    }) else {
+        return Ok(());
+    };
+
+    let mut badly_signed = Vec::new();
+    for commit in stack.branches[position..]
+        .iter()
+        .flat_map(|branch| &branch.commits)
+    {
+        let needs_push = match commit.state {
+            but_workspace::ui::CommitState::LocalOnly => true,
+            but_workspace::ui::CommitState::LocalAndRemote(remote_id) => remote_id != commit.id,
+            but_workspace::ui::CommitState::Integrated => false,
+        };
+        if !needs_push {
+            continue;
+        }
+        let signature = but_core::commit::signature::verify(&repo, commit.id)?;
+        if !signature.status.is_good() {
+            badly_signed.push((commit.id, signature.status));
+        }
+    }
+    if badly_signed.is_empty() {
+        return Ok(());
+    }
+
+    let t = theme::get();
+    writeln!(
+        out,
+        "{} '{branch_name}' would push {} commit{} without a good signature for {}:",
+        t.sym().warning,
+        badly_signed.len(),
+        if badly_signed.len() == 1 { "" } else { "s" },
+        target_branch.map_or_else(
+            || "the target branch".to_owned(),
+            |name| format!("the target branch '{name}'")
+        ),
+    )?;
+    for (id, status) in badly_signed {
+        writeln!(
+            out,
+            "  {} {}",
+            t.commit_id.paint(shorten_object_id(&repo, id)),
+            t.attention.paint(status.to_string())
+        )?;
+    }
+    Ok(())
 }
 
 /// Check if a push of this branch would include any conflicted commits.
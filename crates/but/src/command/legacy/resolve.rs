@@ -10,6 +10,7 @@ use but_api::legacy::modes::{
     save_edit_and_return_to_workspace_with_output,
 };
 use but_ctx::Context;
+use but_rebase::rerere::{Resolution, Store};
 use gitbutler_commit::commit_ext::{CommitExt, CommitMessageBstr};
 use gitbutler_edit_mode::commands::changes_from_initial;
 use gitbutler_operating_modes::OperatingMode;
@@ -19,7 +20,9 @@ use crate::{
     args::resolve::Subcommands,
     id::{CliId, CommitId, CommitIdRef},
     theme::{self, Paint},
-    utils::{Confirm, ConfirmDefault, OutputChannel, shorten_object_id},
+    utils::{
+        Confirm, ConfirmDefault, OutputChannel, shorten_object_id, time::format_relative_time,
+    },
 };
 
 pub(crate) fn handle(
@@ -89,6 +92,10 @@ pub(crate) fn handle(
         }
         Some(Subcommands::Status) => show_status(ctx, out),
         Some(Subcommands::Finish) => finish_resolution(ctx, out),
+        Some(Subcommands::Recorded { id }) => show_recorded_resolutions(ctx, out, id.as_deref()),
+        Some(Subcommands::Forget { id, all }) => {
+            forget_recorded_resolutions(ctx, out, id.as_deref(), all)
+        }
         Some(Subcommands::Cancel { force }) => cancel_resolution(ctx, out, force),
         None => {
             // Default action: enter resolution mode for the specified commit
@@ -640,6 +647,155 @@ fn cancel_resolution(ctx: &mut Context, out: &mut OutputChannel, force: bool) ->
     Ok(())
 }
 
+/// List the recorded conflict resolutions, or show the one identified by `id`
+/// with the conflict it resolves.
+fn show_recorded_resolutions(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    id: Option<&str>,
+) -> Result<()> {
+    let t = theme::get();
+    let store = Store::from_repo(&*ctx.repo.get()?)?;
+    let now = std::time::SystemTime::now();
+    let Some(id) = id else {
+        let resolutions = store.list()?;
+        if let Some(human_out) = out.for_human() {
+            if resolutions.is_empty() {
+                writeln!(
+                    human_out,
+                    "{}",
+                    t.hint.paint("No conflict resolutions were recorded yet.")
+                )?;
+            }
+            for resolution in &resolutions {
+                writeln!(
+                    human_out,
+                    "{} {} {}",
+                    t.commit_id.paint(short_resolution_id(resolution)),
+                    resolution.path,
+                    t.hint
+                        .paint(format_relative_time(now, resolution.recorded_at))
+                )?;
+            }
+        }
+        if let Some(json_out) = out.for_json() {
+            json_out.write_value(
+                resolutions
+                    .iter()
+                    .map(|resolution| {
+                        serde_json::json!({
+                            "id": resolution.id,
+                            "path": resolution.path.to_str_lossy(),
+                            "recorded_at": resolution.recorded_at,
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            )?;
+        }
+        return Ok(());
+    };
+
+    let resolution = find_recorded_resolution(&store, id)?;
+    if let Some(human_out) = out.for_human() {
+        writeln!(
+            human_out,
+            "{} {} {} {}",
+            t.important.paint("Resolution"),
+            t.commit_id.paint(short_resolution_id(&resolution)),
+            t.important.paint("of"),
+            t.attention.paint(resolution.path.to_str_lossy()),
+        )?;
+        writeln!(
+            human_out,
+            "{}",
+            t.hint.paint(format!(
+                "Recorded {}",
+                format_relative_time(now, resolution.recorded_at)
+            ))
+        )?;
+        writeln!(human_out)?;
+        writeln!(human_out, "{}", t.important.paint("── conflict"))?;
+        write!(human_out, "{}", resolution.preimage)?;
+        writeln!(human_out, "{}", t.important.paint("── resolved to"))?;
+        write!(human_out, "{}", resolution.postimage)?;
+    }
+    if let Some(json_out) = out.for_json() {
+        json_out.write_value(serde_json::json!({
+            "id": resolution.id,
+            "path": resolution.path.to_str_lossy(),
+            "recorded_at": resolution.recorded_at,
+            "preimage": resolution.preimage.to_str_lossy(),
+            "postimage": resolution.postimage.to_str_lossy(),
+        }))?;
+    }
+    Ok(())
+}
+
+/// Forget the recorded conflict resolution identified by `id`, or all of them.
+fn forget_recorded_resolutions(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    id: Option<&str>,
+    all: bool,
+) -> Result<()> {
+    let t = theme::get();
+    let store = Store::from_repo(&*ctx.repo.get()?)?;
+    let forgotten = match id {
+        Some(id) if !all => vec![find_recorded_resolution(&store, id)?],
+        _ => store.list()?,
+    };
+    for resolution in &forgotten {
+        store.forget(&resolution.id)?;
+    }
+
+    if let Some(human_out) = out.for_human() {
+        if forgotten.is_empty() {
+            writeln!(
+                human_out,
+                "{}",
+                t.hint.paint("No conflict resolutions were recorded.")
+            )?;
+        }
+        for resolution in &forgotten {
+            writeln!(
+                human_out,
+                "{} {} {} {}",
+                t.success.paint("Forgot resolution"),
+                t.commit_id.paint(short_resolution_id(resolution)),
+                t.success.paint("of"),
+                resolution.path,
+            )?;
+        }
+    }
+    if let Some(json_out) = out.for_json() {
+        json_out.write_value(serde_json::json!({
+            "forgotten": forgotten.iter().map(|resolution| &resolution.id).collect::<Vec<_>>(),
+        }))?;
+    }
+    Ok(())
+}
+
+/// Find the recorded resolution whose id is `id` or starts with it.
+fn find_recorded_resolution(store: &Store, id: &str) -> Result<Resolution> {
+    let mut matches = store
+        .list()?
+        .into_iter()
+        .filter(|resolution| resolution.id.starts_with(id));
+    match (matches.next(), matches.next()) {
+        (Some(resolution), None) => Ok(resolution),
+        (None, _) => bail!(
+            "There is no recorded resolution with id \"{id}\". Run `but resolve recorded` to list them."
+        ),
+        (Some(_), Some(_)) => {
+            bail!("\"{id}\" is ambiguous. Please provide more characters to uniquely identify it.")
+        }
+    }
+}
+
+fn short_resolution_id(resolution: &Resolution) -> &str {
+    &resolution.id[..resolution.id.len().min(7)]
+}
+
 /// Where a conflicted-commit target resolved to, with enough context to tell
 /// the user what scope they are working in.
 struct ConflictTarget {
@@ -1,6 +1,7 @@
 use super::FileChange;
 use anyhow::{Result, bail};
 use bstr::ByteSlice;
+use but_core::commit::signature::Status;
 use but_ctx::Context;
 use but_ctx::access::RepoShared;
 
@@ -103,6 +104,7 @@ pub(crate) fn show_commit(
         .find("change-id")
         .or_else(|| decoded.extra_headers().find("gitbutler-change-id"))
         .map(|v| v.to_str_lossy().to_string());
+    let signature = but_core::commit::signature::verify(&repo, commit_id)?;
 
     // Display commit information
     if let Some(out) = out.for_human() {
@@ -153,6 +155,23 @@ pub(crate) fn show_commit(
             )?;
         }
 
+        // Signature (only if signed)
+        if signature.status != Status::Unsigned {
+            let style = match signature.status {
+                Status::Good => t.success,
+                Status::Untrusted | Status::Unverifiable => t.attention,
+                _ => t.error,
+            };
+            let mut line = style.paint(signature.status.to_string()).to_string();
+            if let Some(signer) = &signature.signer {
+                line.push_str(&format!(" by {}", t.user.paint(signer)));
+            }
+            if let Some(key) = &signature.key {
+                line.push_str(&format!(" {}", t.hint.paint(format!("({key})"))));
+            }
+            writeln!(out, "{} {line}", t.important.paint("Signature:"))?;
+        }
+
         writeln!(out)?;
 
         // Commit message - first line bold, rest normal, no indentation
@@ -229,6 +248,7 @@ pub(crate) fn show_commit(
         if let Some(ref change_id) = change_id {
             json_output["changeId"] = serde_json::json!(change_id);
         }
+        json_output["signature"] = serde_json::to_value(&signature)?;
 
         out.write_value(json_output)?;
     }
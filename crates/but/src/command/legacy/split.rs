@@ -0,0 +1,390 @@
+use anyhow::Context as _;
+use bstr::{BStr, ByteSlice as _};
+use but_api::{
+    WorkspaceState,
+    json::{ChangeIdString, HexHash},
+};
+use but_core::{DiffSpec, DryRun, UnifiedPatch};
+use but_ctx::Context;
+use but_workspace::commit::SplitPiece;
+use gitbutler_oplog::entry::{OperationKind, SnapshotDetails};
+use itertools::Itertools as _;
+use nonempty::NonEmpty;
+use serde::Serialize;
+
+use crate::{
+    CliResult, IdMap,
+    args::{
+        atoms::{CliIdArg, Priority, Purpose, ResolvedCliIdArg},
+        split::Platform,
+    },
+    bad_input,
+    id::CommitId,
+    theme::{self, Theme},
+    utils::{
+        CliOutput, CliOutputHuman, IntermediateChannel, WriteWithUtils,
+        diff_specs::DiffSpecBuilder, merged_upstream::MergedUpstream,
+    },
+};
+
+pub enum SplitOutcome {
+    Split {
+        source: CommitId,
+        /// The commits the source was split into, from the bottom up, starting with the
+        /// rewritten source commit.
+        commits: Vec<CommitId>,
+    },
+    /// All changes were selected for the first commit, so nothing was split.
+    Unchanged { source: CommitId },
+}
+
+impl CliOutputHuman for SplitOutcome {
+    fn on_human(
+        self,
+        out: &mut dyn WriteWithUtils,
+        _agent: bool,
+        _theme: &Theme,
+    ) -> anyhow::Result<()> {
+        match self {
+            SplitOutcome::Split { source, commits } => {
+                let commits = commits.into_iter().map(theme::Commit).join(", ");
+                writeln!(out, "Split {} into {commits}", theme::Commit(source))?;
+            }
+            SplitOutcome::Unchanged { source } => {
+                writeln!(
+                    out,
+                    "Nothing to split, {} keeps all of its changes",
+                    theme::Commit(source)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CliOutput for SplitOutcome {
+    fn on_json(self) -> impl Serialize {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Commit {
+            commit_id: HexHash,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            change_id: Option<ChangeIdString>,
+        }
+
+        impl From<CommitId> for Commit {
+            fn from(
+                CommitId {
+                    commit_id,
+                    change_id,
+                }: CommitId,
+            ) -> Self {
+                Commit {
+                    commit_id: commit_id.into(),
+                    change_id: change_id.map(Into::into),
+                }
+            }
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Output {
+            commits: Vec<Commit>,
+        }
+
+        let commits = match self {
+            SplitOutcome::Split { commits, .. } => commits,
+            SplitOutcome::Unchanged { source } => vec![source],
+        };
+        Output {
+            commits: commits.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+pub fn split(
+    ctx: &mut Context,
+    mut out: IntermediateChannel<'_>,
+    args: Platform,
+) -> CliResult<(SplitOutcome, Option<WorkspaceState>)> {
+    let mut guard = ctx.exclusive_worktree_access();
+    let mut meta = ctx.meta()?;
+    let id_map = IdMap::new_from_context(ctx, guard.read_permission())?;
+    let merged = MergedUpstream::from_ctx(ctx, args.allow_merged)?;
+    let context_lines = ctx.settings.context_lines;
+
+    let (source, pieces) = {
+        let (repo, _ws, _) = ctx.workspace_and_db_with_perm(guard.read_permission())?;
+        let Platform {
+            commit,
+            changes,
+            message,
+            allow_merged: _,
+        } = args;
+
+        let source = match commit.resolve_in_workspace(
+            &repo,
+            &id_map,
+            Purpose::Commit,
+            Some(Priority::Commit),
+        )? {
+            ResolvedCliIdArg::Commit(commit) => commit,
+            other => {
+                return Err(bad_input(format!(
+                    "Expected a commit to split, but '{commit}' is {}",
+                    other.kind_for_humans()
+                ))
+                .into());
+            }
+        };
+        merged.ensure_commit_not_merged(source.commit_id)?;
+
+        let pieces = if changes.is_empty() {
+            let Some(pieces) =
+                pieces_from_terminal(&mut out, &repo, source.commit_id, context_lines)?
+            else {
+                return Ok((SplitOutcome::Unchanged { source }, None));
+            };
+            pieces
+        } else {
+            pieces_from_args(
+                &repo,
+                &id_map,
+                source.commit_id,
+                changes,
+                message,
+                context_lines,
+            )?
+        };
+        (source, pieces)
+    };
+
+    let (commits, ws) = but_transaction::with_transaction_with_perm(
+        ctx,
+        &mut meta,
+        guard.write_permission(),
+        SnapshotDetails::new(OperationKind::SplitCommit),
+        DryRun::No,
+        |mut tx| {
+            let split = tx.split_commit(source.commit_id, pieces)?;
+            Ok(but_transaction::Commit(split))
+        },
+    )?;
+
+    Ok((
+        SplitOutcome::Split {
+            source,
+            commits: commits.into_iter().map(Into::into).collect(),
+        },
+        Some(ws),
+    ))
+}
+
+/// Build one piece per group of comma-separated committed-file IDs in `changes`, paired
+/// with the message at the same position in `messages`. A file ID may be followed by
+/// `@<n>` to only select its `n`-th hunk.
+fn pieces_from_args(
+    repo: &gix::Repository,
+    id_map: &IdMap,
+    commit_id: gix::ObjectId,
+    changes: Vec<String>,
+    messages: Vec<String>,
+    context_lines: u32,
+) -> CliResult<Vec<SplitPiece>> {
+    if changes.len() != messages.len() {
+        return Err(bad_input(format!(
+            "Each --changes needs a --message, but got {} --changes and {} --message",
+            changes.len(),
+            messages.len()
+        ))
+        .into());
+    }
+
+    let mut pieces = Vec::with_capacity(changes.len());
+    for (group, message) in changes.into_iter().zip(messages) {
+        let mut builder = DiffSpecBuilder::new(repo, context_lines);
+        let mut hunks = Vec::new();
+        for id in group.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+            let (id, hunk) = match id.rsplit_once('@') {
+                Some((id, hunk)) => {
+                    let hunk = hunk
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|hunk| *hunk > 0)
+                        .ok_or_else(|| {
+                            bad_input(format!("'{hunk}' in '{id}@{hunk}' isn't a hunk number"))
+                        })?;
+                    (id, Some(hunk))
+                }
+                None => (id, None),
+            };
+            let id = CliIdArg(id.to_owned());
+            match id.resolve_in_workspace(repo, id_map, Purpose::Source, None)? {
+                ResolvedCliIdArg::CommittedFile(file) if file.commit_id == commit_id => {
+                    match hunk {
+                        Some(hunk) => hunks.push(committed_hunk(
+                            repo,
+                            commit_id,
+                            file.path.as_ref(),
+                            hunk,
+                            context_lines,
+                        )?),
+                        None => builder
+                            .push_changes_from_committed_file(commit_id, file.path.as_ref())?,
+                    }
+                }
+                ResolvedCliIdArg::CommittedFile(_) => {
+                    return Err(bad_input(format!(
+                        "'{id}' is a file of another commit than the one to split"
+                    ))
+                    .into());
+                }
+                other => {
+                    return Err(bad_input(format!(
+                        "Expected a file of the commit to split, but '{id}' is {}",
+                        other.kind_for_humans()
+                    ))
+                    .into());
+                }
+            }
+        }
+        let changes =
+            but_workspace::flatten_diff_specs(builder.into_diff_specs().into_iter().chain(hunks));
+        if changes.is_empty() {
+            return Err(bad_input("--changes must name at least one file").into());
+        }
+        pieces.push(SplitPiece {
+            changes,
+            message: message.into(),
+        });
+    }
+    Ok(pieces)
+}
+
+/// Let the user pick the hunks of each piece from the changes of `commit_id`, along with
+/// its message, until nothing is selected or left anymore.
+///
+/// Returns `None` if all changes were selected for the first piece, leaving nothing to split.
+fn pieces_from_terminal(
+    out: &mut IntermediateChannel<'_>,
+    repo: &gix::Repository,
+    commit_id: gix::ObjectId,
+    context_lines: u32,
+) -> CliResult<Option<Vec<SplitPiece>>> {
+    let Some(mut inout) = out.prepare_for_terminal_input() else {
+        return Err(bad_input(
+            "Specify the changes of each new commit with --changes and --message, or run in a terminal to select them",
+        )
+        .into());
+    };
+
+    let mut remaining = selectable_changes(repo, commit_id, context_lines)?;
+    let mut pieces = Vec::new();
+    while let Some(items) = NonEmpty::from_vec(remaining.clone()) {
+        let prompt = format!("Select the changes for commit {}", pieces.len() + 1);
+        let Some(selected) = inout.prompt_multi_select(prompt, &items)? else {
+            break;
+        };
+        if selected.is_empty() {
+            break;
+        }
+        let changes = selected.into_iter().cloned().collect::<Vec<_>>();
+        let Some(message) = inout.prompt(format!("Message for commit {}:", pieces.len() + 1))?
+        else {
+            return Err(bad_input("A message is required for each commit").into());
+        };
+
+        remaining.retain(|(_, change)| !changes.contains(change));
+        pieces.push(SplitPiece {
+            changes: but_workspace::flatten_diff_specs(changes),
+            message: message.into(),
+        });
+    }
+
+    if pieces.is_empty() {
+        return Err(bad_input("No changes were selected, nothing to split").into());
+    }
+    if pieces.len() == 1 && remaining.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(pieces))
+}
+
+/// Return the `hunk`-th hunk, counting from 1, of the file at `path` in `commit_id`.
+fn committed_hunk(
+    repo: &gix::Repository,
+    commit_id: gix::ObjectId,
+    path: &BStr,
+    hunk: usize,
+    context_lines: u32,
+) -> CliResult<DiffSpec> {
+    let commit = repo.find_commit(commit_id)?;
+    let parent_id = commit.parent_ids().next().map(|id| id.detach());
+    let change = but_core::diff::tree_changes(repo, parent_id, commit_id)?
+        .into_iter()
+        .find(|change| change.path == path)
+        .with_context(|| format!("BUG: '{path}' must be changed in the commit it was found in"))?;
+    let hunks = match change.unified_patch(repo, context_lines)? {
+        Some(UnifiedPatch::Patch {
+            hunks,
+            is_result_of_binary_to_text_conversion: false,
+            ..
+        }) => hunks,
+        _ => Vec::new(),
+    };
+    let Some(header) = hunks.get(hunk - 1).map(but_core::HunkHeader::from) else {
+        return Err(bad_input(format!(
+            "Cannot select hunk {hunk} of '{path}', which has {} hunk{}",
+            hunks.len(),
+            if hunks.len() == 1 { "" } else { "s" }
+        ))
+        .into());
+    };
+    Ok(DiffSpec {
+        hunk_headers: vec![header],
+        ..DiffSpec::from(&change)
+    })
+}
+
+/// Return the changes of `commit_id` along with a label to select them by. Files with
+/// multiple hunks can be selected hunk by hunk, all others only as a whole.
+fn selectable_changes(
+    repo: &gix::Repository,
+    commit_id: gix::ObjectId,
+    context_lines: u32,
+) -> anyhow::Result<Vec<(String, DiffSpec)>> {
+    let commit = repo.find_commit(commit_id)?;
+    let parent_id = commit.parent_ids().next().map(|id| id.detach());
+    let mut out = Vec::new();
+    for change in but_core::diff::tree_changes(repo, parent_id, commit_id)? {
+        let label = match change.previous_path() {
+            Some(previous_path) => format!("{previous_path} → {}", change.path),
+            None => change.path.to_str_lossy().into_owned(),
+        };
+        let hunks = match change.unified_patch(repo, context_lines)? {
+            Some(UnifiedPatch::Patch {
+                hunks,
+                is_result_of_binary_to_text_conversion: false,
+                ..
+            }) if hunks.len() > 1 && change.previous_path().is_none() => hunks,
+            _ => {
+                out.push((label, DiffSpec::from(&change)));
+                continue;
+            }
+        };
+        for hunk in hunks {
+            let header = but_core::HunkHeader::from(&hunk);
+            out.push((
+                format!(
+                    "{label} @@ -{},{} +{},{} @@",
+                    header.old_start, header.old_lines, header.new_start, header.new_lines
+                ),
+                DiffSpec {
+                    hunk_headers: vec![header],
+                    ..DiffSpec::from(&change)
+                },
+            ));
+        }
+    }
+    Ok(out)
+}
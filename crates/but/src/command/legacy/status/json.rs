@@ -252,6 +252,16 @@ pub(crate) struct Commit {
     conflicted: Option<bool>,
     /// If but status was invoked with --review and if the commit has an associated review ID (eg. Gerrit review number), it will be present here
     review_id: Option<String>,
+    /// The URL of the Gerrit review of this commit, if it was pushed to Gerrit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    review_url: Option<String>,
+    /// The status of the Gerrit change of this commit, if it was pushed to Gerrit and its status is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gerrit_change: Option<but_gerrit::GerritChangeStatus>,
+    /// The verified signature of this commit, if signatures are verified as enabled with the
+    /// `gitbutler.verifySignatures` setting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<but_core::commit::signature::Signature>,
     /// If but status was invoked with --files, the list of file changes in this commit will be present here
     changes: Option<Vec<FileChange>>,
 }
@@ -349,6 +359,7 @@ impl Branch {
         push_statuses_by_segment_id: &HashMap<SegmentIndex, but_workspace::ui::PushStatus>,
         local_commits_by_id: &HashMap<gix::ObjectId, LocalCommit>,
         remote_commits_by_id: &HashMap<gix::ObjectId, but_workspace::ref_info::Commit>,
+        gerrit_changes: &HashMap<gix::ObjectId, but_gerrit::GerritChangeStatus>,
         review_id: Option<String>,
         show_files: FilesStatusFlag,
         ci: Option<Vec<but_forge::CiCheck>>,
@@ -363,7 +374,14 @@ impl Branch {
                     .as_ref()
                     .map(|change_id| change_id.padded_short_id())
                     .unwrap_or_else(|| c.short_id.clone());
-                Commit::from_local_commit(repo, cli_id, c.clone(), local_commits_by_id, show_files)
+                Commit::from_local_commit(
+                    repo,
+                    cli_id,
+                    c.clone(),
+                    local_commits_by_id,
+                    gerrit_changes,
+                    show_files,
+                )
             })
             .collect::<anyhow::Result<Vec<_>>>()?;
 
@@ -417,6 +435,7 @@ impl Commit {
         cli_id: String,
         commit: WorkspaceCommitWithId,
         local_commits_by_id: &HashMap<gix::ObjectId, LocalCommit>,
+        gerrit_changes: &HashMap<gix::ObjectId, but_gerrit::GerritChangeStatus>,
         show_files: FilesStatusFlag,
     ) -> anyhow::Result<Self> {
         let changes = if show_files.show_files_for(commit.inner.id) {
@@ -441,6 +460,7 @@ impl Commit {
             .get(&commit.commit_id())
             .context("BUG: head_info does not have local commit that graph has")?
             .inner;
+        let gerrit_change = gerrit_changes.get(&commit.id).cloned();
         Ok(Commit {
             cli_id,
             change_id,
@@ -450,9 +470,12 @@ impl Commit {
             author_name: commit.author.name.to_string(),
             author_email: commit.author.email.to_string(),
             conflicted: Some(commit.has_conflicts),
-            // TODO: populate but_workspace::ref_info::LocalCommit with the
-            // Gerrit URL
-            review_id: None,
+            review_id: gerrit_change
+                .as_ref()
+                .map(|change| format!("(#{})", change.number)),
+            review_url: commit.gerrit_review_url.clone(),
+            gerrit_change,
+            signature: commit.signature.clone(),
             changes,
         })
     }
@@ -478,6 +501,9 @@ impl Commit {
             author_email: commit.author.email.to_string(),
             conflicted: None,
             review_id: None,
+            review_url: None,
+            gerrit_change: None,
+            signature: None,
             changes,
         }))
     }
@@ -497,6 +523,9 @@ impl Commit {
             author_email: commit.author.email,
             conflicted: None,
             review_id: None,
+            review_url: None,
+            gerrit_change: None,
+            signature: None,
             changes,
         }
     }
@@ -606,6 +635,7 @@ fn convert_branch_to_json(
         &status_ctx.push_statuses_by_segment_id,
         &status_ctx.local_commits_by_id,
         &status_ctx.remote_commits_by_id,
+        &status_ctx.gerrit_changes,
         review_id,
         status_ctx.flags.show_files,
         ci,
@@ -648,6 +678,7 @@ fn build_worktrees_json(
                     cli_id,
                     commit.clone(),
                     &status_ctx.local_commits_by_id,
+                    &status_ctx.gerrit_changes,
                     status_ctx.flags.show_files,
                 )
             })
@@ -251,6 +251,8 @@ struct StatusContext<'a> {
     upstream_state: Option<UpstreamState>,
     last_fetched_ms: Option<u128>,
     review_map: std::collections::HashMap<String, Vec<but_forge::ForgeReview>>,
+    /// The Gerrit review status of pushed workspace commits, only populated in Gerrit mode.
+    gerrit_changes: HashMap<gix::ObjectId, but_gerrit::GerritChangeStatus>,
     ci_map: BTreeMap<String, Vec<but_forge::CiCheck>>,
     branch_merge_statuses: BTreeMap<String, UpstreamBranchStatus>,
     has_branches: bool,
@@ -462,13 +464,24 @@ fn build_status_context<'a>(
         commit_id_to_change_id,
         worktrees,
     ) = {
-        let (repo, ws, _db) = ctx.workspace_and_db_with_perm(perm.read_permission())?;
+        let (repo, ws, db) = ctx.workspace_and_db_with_perm(perm.read_permission())?;
+        let gerrit_mode = if is_gerrit_mode(&repo)? {
+            but_workspace::ref_info::GerritMode::Enabled(db.gerrit_metadata())
+        } else {
+            but_workspace::ref_info::GerritMode::Disabled
+        };
         let head_info = but_workspace::graph_to_ref_info(
             &ws,
             &repo,
             but_workspace::ref_info::Options {
                 project_meta: ws.graph.project_meta.clone(),
                 expensive_commit_info: true,
+                gerrit_mode,
+                // Verification spawns `gpg` or `gpgsm` for each signed commit, so it's opt-in.
+                verify_signatures: repo
+                    .git_settings()?
+                    .gitbutler_verify_signatures
+                    .unwrap_or(false),
                 ..Default::default()
             },
         )?
@@ -543,6 +556,7 @@ fn build_status_context<'a>(
         but_forge::CacheConfig::CacheOnly
     };
     let review_map = review::get_review_map(ctx, Some(cache_config.clone()))?;
+    let gerrit_changes = gerrit_changes_by_commit_id(ctx, &cache_config, &commit_id_to_change_id)?;
 
     let worktree_changes = but_api::diff::changes_in_worktree_with_perm(
         ctx,
@@ -724,6 +738,7 @@ fn build_status_context<'a>(
         upstream_state,
         last_fetched_ms,
         review_map,
+        gerrit_changes,
         ci_map,
         branch_merge_statuses,
         flags,
@@ -1251,6 +1266,48 @@ fn print_worktree_status(
     Ok(has_merged_upstream_branch)
 }
 
+fn is_gerrit_mode(repo: &gix::Repository) -> anyhow::Result<bool> {
+    Ok(repo.git_settings()?.gitbutler_gerrit_mode.unwrap_or(false))
+}
+
+/// Return the Gerrit review status of each workspace commit that was pushed as a Gerrit change,
+/// querying the server instead of the cache if `cache_config` demands it.
+///
+/// Like forge reviews, this is best-effort: the server may be unreachable, which shouldn't fail `status`.
+fn gerrit_changes_by_commit_id(
+    ctx: &Context,
+    cache_config: &but_forge::CacheConfig,
+    commit_id_to_change_id: &gix::hashtable::HashMap<gix::ObjectId, ChangeId>,
+) -> anyhow::Result<HashMap<gix::ObjectId, but_gerrit::GerritChangeStatus>> {
+    let repo = ctx.repo.get()?;
+    if !is_gerrit_mode(&repo)? {
+        return Ok(HashMap::new());
+    }
+    let change_ids = commit_id_to_change_id.values().cloned();
+    let mut db = ctx.db.get_cache_mut()?;
+    let statuses = match cache_config {
+        but_forge::CacheConfig::NoCache => {
+            but_gerrit::refresh_change_statuses(&repo, &mut db, change_ids)
+        }
+        _ => but_gerrit::change_statuses_from_cache(&db, change_ids),
+    }
+    .inspect_err(|err| tracing::warn!("Could not obtain Gerrit change status: {err:#}"))
+    .unwrap_or_default();
+
+    let mut by_change_id: HashMap<_, _> = statuses
+        .into_iter()
+        .map(|status| (status.change_id.clone(), status))
+        .collect();
+    Ok(commit_id_to_change_id
+        .iter()
+        .filter_map(|(commit_id, change_id)| {
+            by_change_id
+                .remove(&change_id.to_string())
+                .map(|status| (*commit_id, status))
+        })
+        .collect())
+}
+
 fn ci_map(
     ctx: &Context,
     cache_config: &but_forge::CacheConfig,
@@ -1739,10 +1796,7 @@ fn print_group(
                     &inner.inner,
                     CommitChanges::Workspace(&commit.tree_changes_using_repo(&repo)?),
                     classification,
-                    // TODO: populate the Gerrit review URL. It
-                    // seems to be populated in handle_gerrit in
-                    // crates/but-api/src/legacy/workspace.rs
-                    None,
+                    inner.inner.gerrit_review_url.clone(),
                     0,
                     output,
                 )?;
@@ -1890,6 +1944,24 @@ fn status_from_changes(changes: &[ui::TreeChange], path: BString) -> Option<ui::
     })
 }
 
+/// Show the outcome of verifying a commit signature, or nothing if the commit isn't signed.
+fn signature_display_cli(signature: &but_core::commit::signature::Signature) -> Vec<Span<'static>> {
+    use but_core::commit::signature::Status;
+
+    let t = crate::theme::get();
+    let (symbol, style) = match signature.status {
+        Status::Unsigned => return Vec::new(),
+        Status::Good => ("✓", t.success),
+        Status::Untrusted | Status::Unverifiable => ("?", t.attention),
+        Status::Bad | Status::Expired | Status::ExpiredKey | Status::RevokedKey => ("✗", t.error),
+    };
+    let label = match (&signature.status, &signature.signer) {
+        (Status::Good, Some(signer)) => format!("{symbol} signed by {signer}"),
+        (status, _) => format!("{symbol} {status} signature"),
+    };
+    Vec::from([Span::raw(" "), Span::styled(label, style)])
+}
+
 enum CommitChanges<'a> {
     Workspace(&'a [TreeChangeWithId]),
     Remote(&'a [but_core::TreeChange]),
@@ -1919,6 +1991,25 @@ fn print_commit(
     };
 
     let upstream_commit = matches!(commit_changes, CommitChanges::Remote(_));
+    let gerrit_spans: Vec<Span<'static>> = status_ctx
+        .gerrit_changes
+        .get(&commit.id)
+        .map(|status| {
+            status
+                .display_cli(
+                    status_ctx.flags.verbose,
+                    status_ctx.should_truncate_for_terminal,
+                )
+                .into_iter()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let signature_spans = commit
+        .signature
+        .as_ref()
+        .map(signature_display_cli)
+        .unwrap_or_default();
 
     // One-shot output pads file ID prefixes to match the change ID shown on
     // the commit line; the TUI keeps the minimal IDs.
@@ -1972,6 +2063,8 @@ fn print_commit(
                             Span::raw("◗"),
                         ]
                     }))
+                    .chain(gerrit_spans.iter().cloned())
+                    .chain(signature_spans.iter().cloned())
                     .collect(),
             },
             commit_cli_id.clone(),
@@ -2015,6 +2108,8 @@ fn print_commit(
                             Span::raw("◗"),
                         ]
                     }))
+                    .chain(gerrit_spans.iter().cloned())
+                    .chain(signature_spans.iter().cloned())
                     .collect(),
             },
             commit_cli_id.clone(),
@@ -2413,6 +2508,47 @@ impl CliDisplay for CiChecks {
     }
 }
 
+impl CliDisplay for but_gerrit::GerritChangeStatus {
+    fn display_cli(
+        &self,
+        _verbose: bool,
+        _should_truncate_for_terminal: bool,
+    ) -> impl IntoIterator<Item = Span<'static>> {
+        let t = crate::theme::get();
+        let vote = |label: &str, value: Option<i32>| {
+            value.map(|value| {
+                let style = if value < 0 { t.error } else { t.success };
+                Span::styled(format!(" {label}{value:+}"), style)
+            })
+        };
+        let state = match self.state {
+            but_gerrit::GerritChangeState::Merged => Some(Span::styled(" merged", t.remote_branch)),
+            but_gerrit::GerritChangeState::Abandoned => Some(Span::styled(" abandoned", t.hint)),
+            but_gerrit::GerritChangeState::New if self.work_in_progress => {
+                Some(Span::styled(" wip", t.hint))
+            }
+            but_gerrit::GerritChangeState::New => None,
+        };
+        let is_open = self.state == but_gerrit::GerritChangeState::New;
+        [
+            Span::raw(" #"),
+            Span::styled(self.number.to_string(), t.important),
+        ]
+        .into_iter()
+        .chain(state)
+        .chain(vote("CR", self.code_review))
+        .chain(vote("V", self.verified))
+        .chain((is_open && self.unresolved_comment_count > 0).then(|| {
+            Span::styled(
+                format!(" {} unresolved", self.unresolved_comment_count),
+                t.attention,
+            )
+        }))
+        .chain((is_open && self.submittable).then(|| Span::styled(" ✓ submittable", t.success)))
+        .collect::<Vec<_>>()
+    }
+}
+
 fn display_available_update(
     update: &but_update::AvailableUpdate,
     verbose: bool,
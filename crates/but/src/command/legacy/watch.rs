@@ -0,0 +1,176 @@
+use std::sync::mpsc;
+
+use anyhow::Context as _;
+use but_ctx::Context;
+use gitbutler_watcher::Change;
+use serde_json::json;
+
+use crate::{
+    args::watch::{EventKind, Platform},
+    command::config::load_app_settings_sync,
+    theme::{self, Paint as _},
+    utils::OutputChannel,
+};
+
+/// Run the watcher on the project of `ctx` and print its events until interrupted, or until
+/// the output is closed.
+pub fn handle(ctx: &mut Context, out: &mut OutputChannel, args: Platform) -> anyhow::Result<()> {
+    let app_settings = load_app_settings_sync()?;
+    let watch_mode = gitbutler_watcher::WatchMode::from_env_or_settings(
+        &app_settings.get()?.feature_flags.watch_mode,
+        |key| std::env::var(key).ok(),
+    );
+
+    let (tx, rx) = mpsc::channel();
+    let handler = gitbutler_watcher::Handler::new(move |change| {
+        _ = tx.send(change);
+        Ok(())
+    });
+    let _watcher = gitbutler_watcher::watch_in_background(
+        handler,
+        ctx.workdir_or_fail()?,
+        ctx.legacy_project.id.clone(),
+        app_settings,
+        watch_mode,
+    )
+    .context("failed to start filesystem watcher")?;
+
+    if let Some(out) = out.for_human_ui() {
+        writeln!(
+            out,
+            "{}",
+            theme::get()
+                .hint
+                .paint("Watching for changes, press Ctrl-C to stop.")
+        )?;
+    }
+
+    for change in rx {
+        if !args.kinds.is_empty() && !args.kinds.contains(&kind(&change)) {
+            continue;
+        }
+        if let Some(out) = out.for_json() {
+            match out.write_value_line(to_json(change)) {
+                // Whoever consumed the events is gone, which is the end of it.
+                Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => break,
+                res => res?,
+            }
+        } else if let Some(out) = out.for_human_or_shell() {
+            writeln!(out, "{}", describe(&change))?;
+        }
+    }
+    Ok(())
+}
+
+fn kind(change: &Change) -> EventKind {
+    match change {
+        Change::GitFetch(_) => EventKind::GitFetch,
+        Change::GitHead { .. } => EventKind::GitHead,
+        Change::GitActivity { .. } => EventKind::GitActivity,
+        Change::WorkspaceActivity { .. } => EventKind::WorkspaceActivity,
+        Change::WorktreeChanges { .. } => EventKind::WorktreeChanges,
+    }
+}
+
+/// Turn `change` into the JSON object of a single event line, with the same payloads the
+/// frontend receives.
+fn to_json(change: Change) -> serde_json::Value {
+    match change {
+        Change::GitFetch(_) => json!({ "kind": "gitFetch" }),
+        Change::GitHead {
+            head,
+            operating_mode,
+            ..
+        } => json!({
+            "kind": "gitHead",
+            "head": head,
+            "operatingMode": operating_mode,
+        }),
+        Change::GitActivity { head_sha, .. } => json!({
+            "kind": "gitActivity",
+            "headSha": head_sha,
+        }),
+        Change::WorkspaceActivity { .. } => json!({ "kind": "workspaceActivity" }),
+        Change::WorktreeChanges {
+            changes,
+            changed_paths,
+            ..
+        } => json!({
+            "kind": "worktreeChanges",
+            "changes": changes,
+            "changedPaths": changed_paths
+                .iter()
+                .map(|path| path.to_string_lossy())
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
+
+fn describe(change: &Change) -> String {
+    let t = theme::get();
+    match change {
+        Change::GitFetch(_) => format!("{} remote branches were fetched", t.info.paint("fetch")),
+        Change::GitHead {
+            head: Some(head), ..
+        } => {
+            format!(
+                "{} HEAD points to {}",
+                t.info.paint("head"),
+                t.local_branch.paint(head)
+            )
+        }
+        Change::GitHead { head: None, .. } => format!("{} HEAD is detached", t.info.paint("head")),
+        Change::GitActivity { head_sha, .. } => format!(
+            "{} HEAD is at {}",
+            t.info.paint("commit"),
+            t.commit_id
+                .paint(head_sha.get(..7).unwrap_or(head_sha.as_str()))
+        ),
+        Change::WorkspaceActivity { .. } => {
+            format!(
+                "{} branches in the workspace may have changed",
+                t.info.paint("workspace")
+            )
+        }
+        Change::WorktreeChanges { changes, .. } => {
+            let files = changes.worktree_changes.changes.len();
+            format!(
+                "{} {files} uncommitted {}",
+                t.info.paint("worktree"),
+                if files == 1 { "file" } else { "files" }
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use but_ctx::{ProjectHandle, ProjectHandleOrLegacyProjectId};
+
+    use super::*;
+
+    fn project_id() -> ProjectHandleOrLegacyProjectId {
+        ProjectHandleOrLegacyProjectId::ProjectHandle(
+            ProjectHandle::from_path(std::env::temp_dir()).unwrap(),
+        )
+    }
+
+    #[test]
+    fn events_carry_their_kind() {
+        let change = Change::GitActivity {
+            project_id: project_id(),
+            head_sha: "0123456789abcdef".into(),
+        };
+        assert_eq!(kind(&change), EventKind::GitActivity);
+        assert_eq!(
+            to_json(change),
+            json!({ "kind": "gitActivity", "headSha": "0123456789abcdef" })
+        );
+        assert_eq!(
+            to_json(Change::WorkspaceActivity {
+                project_id: project_id()
+            }),
+            json!({ "kind": "workspaceActivity" })
+        );
+    }
+}
@@ -18,6 +18,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use url::Url;
 
+mod mutate;
+
 const WORKSPACE_RESOURCE_URI: &str = "ui://gitbutler/workspace/v6.html";
 const REVIEW_RESOURCE_URI: &str = "ui://gitbutler/review/v2.html";
 const MCP_APP_MIME_TYPE: &str = "text/html;profile=mcp-app";
@@ -90,6 +92,42 @@ impl Mcp {
         })
     }
 
+    #[tool(
+        name = "gitbutler_comment_threads",
+        title = "List GitButler comment threads",
+        description = "Lists the comment threads on local changes with their replies, anchored to lines in the uncommitted diff or in commits of the workspace. Pass a branch to only list threads on its commits, and unresolved to leave out resolved threads. Pass the active repository path when it is known.",
+        annotations(
+            title = "List GitButler comment threads",
+            read_only_hint = true,
+            destructive_hint = false,
+            idempotent_hint = true,
+            open_world_hint = false
+        )
+    )]
+    async fn gitbutler_comment_threads(
+        &self,
+        Parameters(request): Parameters<CommentThreadsRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = comment_threads_for_request(request, context)
+            .await
+            .and_then(|view| {
+                let message = format!(
+                    "{} comment thread{} in {}.",
+                    view.threads.len(),
+                    if view.threads.len() == 1 { "" } else { "s" },
+                    view.repository.name,
+                );
+                structured_tool_result(message, view)
+            });
+        Ok(match result {
+            Ok(result) => result,
+            Err(err) => CallToolResult::error(vec![Content::text(format!(
+                "Could not list the comment threads: {err:#}"
+            ))]),
+        })
+    }
+
     #[tool(
         name = "gitbutler_commit_details",
         title = "Load GitButler commit details",
@@ -231,6 +269,156 @@ impl Mcp {
             ))]),
         })
     }
+
+    #[tool(
+        name = "gitbutler_commit",
+        title = "Commit changes",
+        description = "Creates a commit on top of a branch in the GitButler workspace. Pass changes to commit only selected files or hunks of the uncommitted diff, or omit them to commit all uncommitted changes. Pass dryRun to preview the result without changing anything.",
+        annotations(
+            title = "Commit changes",
+            read_only_hint = false,
+            destructive_hint = false,
+            idempotent_hint = false,
+            open_world_hint = false
+        )
+    )]
+    async fn gitbutler_commit(
+        &self,
+        Parameters(request): Parameters<mutate::CommitRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = mutate::commit(request).and_then(mutation_tool_result);
+        Ok(match result {
+            Ok(result) => result,
+            Err(err) => CallToolResult::error(vec![Content::text(format!(
+                "Could not create the commit: {err:#}"
+            ))]),
+        })
+    }
+
+    #[tool(
+        name = "gitbutler_create_branch",
+        title = "Create branch",
+        description = "Creates a branch in the GitButler workspace, either as a new stack or stacked on top of an existing branch. Pass dryRun to preview the result without changing anything.",
+        annotations(
+            title = "Create branch",
+            read_only_hint = false,
+            destructive_hint = false,
+            idempotent_hint = false,
+            open_world_hint = false
+        )
+    )]
+    async fn gitbutler_create_branch(
+        &self,
+        Parameters(request): Parameters<mutate::CreateBranchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = mutate::create_branch(request).and_then(mutation_tool_result);
+        Ok(match result {
+            Ok(result) => result,
+            Err(err) => CallToolResult::error(vec![Content::text(format!(
+                "Could not create the branch: {err:#}"
+            ))]),
+        })
+    }
+
+    #[tool(
+        name = "gitbutler_move_commits",
+        title = "Move commits",
+        description = "Moves commits to the top of a branch or on top of another commit in the GitButler workspace, rebasing the commits above them. Pass dryRun to preview the result without changing anything.",
+        annotations(
+            title = "Move commits",
+            read_only_hint = false,
+            destructive_hint = false,
+            idempotent_hint = false,
+            open_world_hint = false
+        )
+    )]
+    async fn gitbutler_move_commits(
+        &self,
+        Parameters(request): Parameters<mutate::MoveCommitsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = mutate::move_commits(request).and_then(mutation_tool_result);
+        Ok(match result {
+            Ok(result) => result,
+            Err(err) => CallToolResult::error(vec![Content::text(format!(
+                "Could not move the commits: {err:#}"
+            ))]),
+        })
+    }
+
+    #[tool(
+        name = "gitbutler_squash_commits",
+        title = "Squash commits",
+        description = "Squashes commits into a target commit in the GitButler workspace, combining their changes and, unless a message is given, their messages. Pass dryRun to preview the result without changing anything.",
+        annotations(
+            title = "Squash commits",
+            read_only_hint = false,
+            destructive_hint = true,
+            idempotent_hint = false,
+            open_world_hint = false
+        )
+    )]
+    async fn gitbutler_squash_commits(
+        &self,
+        Parameters(request): Parameters<mutate::SquashCommitsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = mutate::squash_commits(request).and_then(mutation_tool_result);
+        Ok(match result {
+            Ok(result) => result,
+            Err(err) => CallToolResult::error(vec![Content::text(format!(
+                "Could not squash the commits: {err:#}"
+            ))]),
+        })
+    }
+
+    #[tool(
+        name = "gitbutler_reword_commit",
+        title = "Reword commit",
+        description = "Changes the message of a commit in the GitButler workspace, rebasing the commits above it. Pass dryRun to preview the result without changing anything.",
+        annotations(
+            title = "Reword commit",
+            read_only_hint = false,
+            destructive_hint = false,
+            idempotent_hint = false,
+            open_world_hint = false
+        )
+    )]
+    async fn gitbutler_reword_commit(
+        &self,
+        Parameters(request): Parameters<mutate::RewordCommitRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = mutate::reword_commit(request).and_then(mutation_tool_result);
+        Ok(match result {
+            Ok(result) => result,
+            Err(err) => CallToolResult::error(vec![Content::text(format!(
+                "Could not reword the commit: {err:#}"
+            ))]),
+        })
+    }
+
+    #[tool(
+        name = "gitbutler_absorb",
+        title = "Absorb changes",
+        description = "Amends each uncommitted hunk into the workspace commit it depends on or that last changed its lines. Pass dryRun to preview the result without changing anything.",
+        annotations(
+            title = "Absorb changes",
+            read_only_hint = false,
+            destructive_hint = false,
+            idempotent_hint = false,
+            open_world_hint = false
+        )
+    )]
+    async fn gitbutler_absorb(
+        &self,
+        Parameters(request): Parameters<mutate::AbsorbRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = mutate::absorb(request).and_then(mutation_tool_result);
+        Ok(match result {
+            Ok(result) => result,
+            Err(err) => CallToolResult::error(vec![Content::text(format!(
+                "Could not absorb the changes: {err:#}"
+            ))]),
+        })
+    }
 }
 
 #[tool_handler]
@@ -247,7 +435,7 @@ impl ServerHandler for Mcp {
 
         ServerInfo {
             instructions: Some(
-                "Use gitbutler_workspace to inspect a repository's current GitButler workspace. Pass the active repository path when it is available; omit it only when the client is known to expose that repository as a filesystem root. After `but pr new`, call gitbutler_review_card with the returned review numbers so the user can see the created reviews."
+                "Use gitbutler_workspace to inspect a repository's current GitButler workspace. Pass the active repository path when it is available; omit it only when the client is known to expose that repository as a filesystem root. After `but pr new`, call gitbutler_review_card with the returned review numbers so the user can see the created reviews. To change the workspace, use gitbutler_commit, gitbutler_create_branch, gitbutler_move_commits, gitbutler_squash_commits, gitbutler_reword_commit and gitbutler_absorb; each call is a single operation that `but undo` reverts, and passing dryRun previews it without changing anything."
                     .into(),
             ),
             capabilities: ServerCapabilities::builder()
@@ -305,6 +493,19 @@ struct WorkspaceRequest {
     repository: Option<PathBuf>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct CommentThreadsRequest {
+    /// Active repository to inspect. Omit only when the MCP client exposes the
+    /// desired repository as a filesystem root.
+    repository: Option<PathBuf>,
+    /// Short or full name of the branch whose commits the threads are on.
+    branch: Option<String>,
+    /// Leave out resolved threads.
+    #[serde(default)]
+    unresolved: bool,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 struct CommitDetailsRequest {
@@ -351,6 +552,13 @@ struct MarkReviewReadyRequest {
     review_number: usize,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentThreadsView {
+    repository: RepositoryView,
+    threads: Vec<but_comments::DiffComment>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct WorkspaceView {
@@ -485,6 +693,27 @@ async fn workspace_view_for_request(
     workspace_view_from_context(&resolved.ctx, &resolved.repository.path)
 }
 
+async fn comment_threads_for_request(
+    request: CommentThreadsRequest,
+    context: RequestContext<RoleServer>,
+) -> Result<CommentThreadsView> {
+    let resolved = resolve_repository(request.repository, context).await?;
+    let filter = but_comments::ThreadFilter {
+        unresolved: request.unresolved,
+        branch: request.branch,
+    };
+    let guard = resolved.ctx.shared_worktree_access();
+    let threads =
+        but_api::comments::comments_list_with_perm(&resolved.ctx, guard.read_permission())?
+            .into_iter()
+            .filter(|thread| !thread.payload.trim().is_empty() && filter.matches(thread))
+            .collect();
+    Ok(CommentThreadsView {
+        repository: resolved.repository,
+        threads,
+    })
+}
+
 async fn resolve_repository(
     repository: Option<PathBuf>,
     context: RequestContext<RoleServer>,
@@ -1065,6 +1294,11 @@ fn structured_tool_result(message: String, value: impl Serialize) -> Result<Call
     })
 }
 
+fn mutation_tool_result(mut view: mutate::MutationView) -> Result<CallToolResult> {
+    let message = std::mem::take(&mut view.message);
+    structured_tool_result(message, view)
+}
+
 fn workspace_tool_meta() -> Meta {
     let mut meta = Meta::new();
     meta.0.insert(
@@ -1311,6 +1545,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn comment_threads_tool_is_read_only_and_model_visible() {
+        let server = Mcp::new();
+        let tool = server
+            .tool_router
+            .get("gitbutler_comment_threads")
+            .expect("comment threads tool is registered");
+        let serialized = serde_json::to_value(tool).expect("tool serializes");
+
+        assert!(
+            serialized["_meta"]["ui"].is_null(),
+            "the model lists threads without an app"
+        );
+        assert_eq!(serialized["annotations"]["readOnlyHint"], true);
+        assert_eq!(
+            serialized["inputSchema"]["properties"]["unresolved"]["type"],
+            "boolean"
+        );
+        assert_eq!(
+            serialized["inputSchema"]["properties"]["branch"]["type"],
+            "string"
+        );
+    }
+
+    #[test]
+    fn mutation_tools_are_model_visible_and_support_dry_runs() {
+        let server = Mcp::new();
+        for name in [
+            "gitbutler_commit",
+            "gitbutler_create_branch",
+            "gitbutler_move_commits",
+            "gitbutler_squash_commits",
+            "gitbutler_reword_commit",
+            "gitbutler_absorb",
+        ] {
+            let tool = server
+                .tool_router
+                .get(name)
+                .unwrap_or_else(|| panic!("{name} is registered"));
+            let serialized = serde_json::to_value(tool).expect("tool serializes");
+
+            assert!(serialized["_meta"]["ui"].is_null(), "{name} has no app");
+            assert_eq!(serialized["annotations"]["readOnlyHint"], false, "{name}");
+            assert_eq!(serialized["annotations"]["openWorldHint"], false, "{name}");
+            assert_eq!(
+                serialized["inputSchema"]["properties"]["dryRun"]["type"], "boolean",
+                "{name}"
+            );
+        }
+    }
+
     #[test]
     fn review_tools_have_separate_model_and_app_visibility() {
         let server = Mcp::new();
@@ -1509,6 +1794,51 @@ mod tests {
         })
     }
 
+    #[test]
+    fn reword_dry_run_previews_without_changing_the_workspace() -> anyhow::Result<()> {
+        but_testsupport::isolated_app_data_dir(|| {
+            let env =
+                but_testsupport::Sandbox::open_or_init_scenario_with_target_and_default_settings(
+                    "one-stack",
+                );
+            let ctx = env.context();
+            let head = ctx.repo.get()?.head_id()?.detach();
+            let workspace = workspace_view_from_context(&ctx, env.projects_root())?;
+            let commit_id = workspace
+                .workspace
+                .stacks
+                .iter()
+                .flat_map(|stack| &stack.rows)
+                .find_map(|row| match &row.data {
+                    DetailedGraphRowData::Commit(commit) => Some(commit.id.to_string()),
+                    DetailedGraphRowData::Reference(_) => None,
+                })
+                .context("fixture has a commit")?;
+            drop(ctx);
+
+            let request = serde_json::from_value(json!({
+                "repository": env.projects_root(),
+                "commitId": commit_id,
+                "message": "reworded",
+                "dryRun": true,
+            }))?;
+            let view = serde_json::to_value(mutate::reword_commit(request)?)?;
+
+            assert_eq!(view["dryRun"], true);
+            assert_eq!(
+                view["replacedCommits"][commit_id.as_str()],
+                view["commitId"],
+                "the preview reports the commit that would replace the reworded one"
+            );
+            assert_eq!(
+                env.context().repo.get()?.head_id()?.detach(),
+                head,
+                "a dry run leaves the workspace untouched"
+            );
+            Ok(())
+        })
+    }
+
     #[test]
     fn branch_details_include_commits_and_upstream_state() -> anyhow::Result<()> {
         but_testsupport::isolated_app_data_dir(|| {
@@ -0,0 +1,493 @@
+//! Tools that change the workspace.
+//!
+//! Each call runs in a single workspace transaction, so it becomes one oplog entry that `but undo`
+//! reverts as a whole, and with `dryRun` it is previewed in memory without changing anything.
+
+use std::{borrow::Cow, collections::BTreeMap, path::PathBuf};
+
+use anyhow::{Context as _, Result, bail};
+use bstr::BString;
+use but_core::{DiffSpec, HunkHeader, ref_metadata::StackId};
+use but_oplog::legacy::{OperationKind, SnapshotDetails};
+use but_rebase::graph_rebase::mutate::{InsertSide, RelativeTo};
+use but_transaction::IntermediateCommitCreateResult;
+use but_workspace::{
+    branch::create_reference::{Anchor, Position},
+    commit::{ChangeSource, squash_commits::MessageCombinationStrategy},
+};
+use gix::refs::FullName;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{RepositoryView, open_repository};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct CommitRequest {
+    /// Canonical repository path from the workspace result.
+    repository: PathBuf,
+    /// Short or full name of the branch to commit to. The commit becomes its new tip.
+    branch: String,
+    /// The message of the new commit.
+    message: String,
+    /// The uncommitted changes to commit. Omit to commit all uncommitted changes.
+    changes: Option<Vec<ChangeSelection>>,
+    /// Only report what would change, without changing anything.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ChangeSelection {
+    /// Worktree-relative path of the changed file.
+    path: String,
+    /// The previous path of the file if it was renamed.
+    previous_path: Option<String>,
+    /// The hunks of the file to commit, as listed in its diff. Omit to commit the whole file.
+    #[serde(default)]
+    hunks: Vec<HunkSelection>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct HunkSelection {
+    /// The 1-based line at which the hunk starts in the previous version of the file.
+    old_start: u32,
+    /// The amount of lines of the hunk in the previous version of the file.
+    old_lines: u32,
+    /// The 1-based line at which the hunk starts in the new version of the file.
+    new_start: u32,
+    /// The amount of lines of the hunk in the new version of the file.
+    new_lines: u32,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct CreateBranchRequest {
+    /// Canonical repository path from the workspace result.
+    repository: PathBuf,
+    /// Short or full name of the new branch.
+    name: String,
+    /// Short or full name of the branch to stack the new branch on. Omit to create a new stack.
+    stack_on: Option<String>,
+    /// Only report what would change, without changing anything.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct MoveCommitsRequest {
+    /// Canonical repository path from the workspace result.
+    repository: PathBuf,
+    /// Object IDs of the commits to move.
+    commit_ids: Vec<String>,
+    /// Short or full name of the branch to move the commits to the top of.
+    branch: Option<String>,
+    /// Object ID of the commit to move the commits on top of, instead of a branch.
+    onto_commit_id: Option<String>,
+    /// Only report what would change, without changing anything.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct SquashCommitsRequest {
+    /// Canonical repository path from the workspace result.
+    repository: PathBuf,
+    /// Object IDs of the commits to squash into the target commit.
+    commit_ids: Vec<String>,
+    /// Object ID of the commit that receives the changes of the squashed commits.
+    target_commit_id: String,
+    /// The message of the resulting commit. Omit to keep the messages of all squashed commits.
+    message: Option<String>,
+    /// Only report what would change, without changing anything.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct RewordCommitRequest {
+    /// Canonical repository path from the workspace result.
+    repository: PathBuf,
+    /// Object ID of the commit to reword.
+    commit_id: String,
+    /// The new commit message.
+    message: String,
+    /// Only report what would change, without changing anything.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct AbsorbRequest {
+    /// Canonical repository path from the workspace result.
+    repository: PathBuf,
+    /// Only report what would change, without changing anything.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// The result of a tool that changed the workspace, or would have in a dry run.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct MutationView {
+    repository: RepositoryView,
+    dry_run: bool,
+    /// The commit that was created or rewritten, if the operation produces one.
+    commit_id: Option<String>,
+    /// The full name of the branch that was created, if any.
+    branch: Option<String>,
+    /// Commits that were rewritten by the operation, as `oldId -> newId`.
+    replaced_commits: BTreeMap<String, String>,
+    /// Paths of changes that couldn't be absorbed into any commit.
+    rejected_paths: Vec<String>,
+    /// A sentence describing what happened, for the model.
+    #[serde(skip)]
+    pub(super) message: String,
+}
+
+impl MutationView {
+    fn new(
+        repository: RepositoryView,
+        dry_run: bool,
+        workspace: but_api::WorkspaceState,
+        message: String,
+    ) -> Self {
+        MutationView {
+            repository,
+            dry_run,
+            commit_id: None,
+            branch: None,
+            replaced_commits: workspace
+                .replaced_commits
+                .into_iter()
+                .map(|(old, new)| (old.to_string(), new.to_string()))
+                .collect(),
+            rejected_paths: Vec::new(),
+            message: if dry_run {
+                format!("Dry run: {message}")
+            } else {
+                message
+            },
+        }
+    }
+}
+
+pub(super) fn commit(request: CommitRequest) -> Result<MutationView> {
+    let mut resolved = open_repository(&request.repository)?;
+    let branch = branch_ref_name(&request.branch)?;
+    let changes = match request.changes {
+        Some(changes) => changes.into_iter().map(DiffSpec::from).collect(),
+        None => {
+            let repo = resolved.ctx.repo.get()?;
+            but_core::diff::worktree_changes(&repo)?
+                .changes
+                .into_iter()
+                .map(DiffSpec::from)
+                .collect::<Vec<_>>()
+        }
+    };
+    if changes.is_empty() {
+        bail!("There are no uncommitted changes to commit");
+    }
+
+    let mut meta = resolved.ctx.meta()?;
+    let (new_commit, workspace) = but_transaction::with_transaction(
+        &mut resolved.ctx,
+        &mut meta,
+        SnapshotDetails::new(OperationKind::CreateCommit),
+        request.dry_run.into(),
+        |mut tx| {
+            let IntermediateCommitCreateResult {
+                new_commit,
+                rejected_specs,
+            } = tx.create_commit(
+                RelativeTo::Reference(branch.clone()),
+                InsertSide::Below,
+                changes,
+                request.message,
+                ChangeSource::Head,
+            )?;
+            if !rejected_specs.is_empty() {
+                let paths = rejected_specs
+                    .iter()
+                    .map(|(reason, spec)| format!("{} ({reason:?})", spec.path))
+                    .collect::<Vec<_>>();
+                bail!("Could not commit {}", paths.join(", "));
+            }
+            let new_commit = new_commit.context("Nothing was committed")?;
+            Ok(but_transaction::Commit(new_commit.id))
+        },
+    )?;
+
+    let message = format!(
+        "Committed {} to {}.",
+        new_commit.to_hex_with_len(7),
+        branch.shorten()
+    );
+    Ok(MutationView {
+        commit_id: Some(new_commit.to_string()),
+        ..MutationView::new(resolved.repository, request.dry_run, workspace, message)
+    })
+}
+
+pub(super) fn create_branch(request: CreateBranchRequest) -> Result<MutationView> {
+    let mut resolved = open_repository(&request.repository)?;
+    let new_ref = branch_ref_name(&request.name)?;
+    let stack_on = request
+        .stack_on
+        .as_deref()
+        .map(branch_ref_name)
+        .transpose()?;
+
+    let mut meta = resolved.ctx.meta()?;
+    let workspace = but_transaction::with_transaction(
+        &mut resolved.ctx,
+        &mut meta,
+        SnapshotDetails::new(OperationKind::CreateBranch),
+        request.dry_run.into(),
+        |mut tx| {
+            let anchor = stack_on
+                .as_ref()
+                .map(|stack_on| Anchor::at_segment(stack_on.as_ref(), Position::Above));
+            tx.create_reference(new_ref.as_ref(), anchor, |_| StackId::generate(), Some(0))?;
+            Ok(())
+        },
+    )?;
+
+    let message = match &stack_on {
+        Some(stack_on) => format!(
+            "Created branch {} on top of {}.",
+            new_ref.shorten(),
+            stack_on.shorten()
+        ),
+        None => format!("Created branch {} as a new stack.", new_ref.shorten()),
+    };
+    Ok(MutationView {
+        branch: Some(new_ref.to_string()),
+        ..MutationView::new(resolved.repository, request.dry_run, workspace, message)
+    })
+}
+
+pub(super) fn move_commits(request: MoveCommitsRequest) -> Result<MutationView> {
+    let mut resolved = open_repository(&request.repository)?;
+    let (commit_ids, relative_to, side, destination) = {
+        let repo = resolved.ctx.repo.get()?;
+        let commit_ids = commit_ids(&repo, &request.commit_ids)?;
+        let (relative_to, side, destination) = match (&request.branch, &request.onto_commit_id) {
+            (Some(branch), None) => {
+                let branch = branch_ref_name(branch)?;
+                let destination = branch.shorten().to_string();
+                (
+                    RelativeTo::Reference(branch),
+                    InsertSide::Below,
+                    destination,
+                )
+            }
+            (None, Some(onto)) => {
+                let onto = commit_id(&repo, onto)?;
+                (
+                    RelativeTo::Commit(onto),
+                    InsertSide::Above,
+                    onto.to_hex_with_len(7).to_string(),
+                )
+            }
+            _ => bail!("Pass either a branch or a commit to move the commits onto"),
+        };
+        (commit_ids, relative_to, side, destination)
+    };
+
+    let mut meta = resolved.ctx.meta()?;
+    let workspace = but_transaction::with_transaction(
+        &mut resolved.ctx,
+        &mut meta,
+        SnapshotDetails::new(OperationKind::MoveCommit),
+        request.dry_run.into(),
+        |mut tx| tx.move_commits(commit_ids.iter().copied(), relative_to, side),
+    )?;
+
+    let message = format!(
+        "Moved {} commit{} onto {destination}.",
+        commit_ids.len(),
+        if commit_ids.len() == 1 { "" } else { "s" }
+    );
+    Ok(MutationView::new(
+        resolved.repository,
+        request.dry_run,
+        workspace,
+        message,
+    ))
+}
+
+pub(super) fn squash_commits(request: SquashCommitsRequest) -> Result<MutationView> {
+    let mut resolved = open_repository(&request.repository)?;
+    let (subjects, target) = {
+        let repo = resolved.ctx.repo.get()?;
+        (
+            commit_ids(&repo, &request.commit_ids)?,
+            commit_id(&repo, &request.target_commit_id)?,
+        )
+    };
+
+    let mut meta = resolved.ctx.meta()?;
+    let (new_commit, workspace) = but_transaction::with_transaction(
+        &mut resolved.ctx,
+        &mut meta,
+        SnapshotDetails::new(OperationKind::SquashCommit),
+        request.dry_run.into(),
+        |mut tx| {
+            let mut new_commit = tx.squash_commits(
+                subjects.iter().copied(),
+                target,
+                MessageCombinationStrategy::KeepBoth,
+            )?;
+            if let Some(message) = request.message {
+                new_commit = tx.reword_commit(new_commit.id, BString::from(message).as_ref())?;
+            }
+            Ok(but_transaction::Commit(new_commit.id))
+        },
+    )?;
+
+    let message = format!(
+        "Squashed {} commit{} into {}.",
+        subjects.len(),
+        if subjects.len() == 1 { "" } else { "s" },
+        new_commit.to_hex_with_len(7)
+    );
+    Ok(MutationView {
+        commit_id: Some(new_commit.to_string()),
+        ..MutationView::new(resolved.repository, request.dry_run, workspace, message)
+    })
+}
+
+pub(super) fn reword_commit(request: RewordCommitRequest) -> Result<MutationView> {
+    let mut resolved = open_repository(&request.repository)?;
+    let target = commit_id(&*resolved.ctx.repo.get()?, &request.commit_id)?;
+
+    let mut meta = resolved.ctx.meta()?;
+    let (new_commit, workspace) = but_transaction::with_transaction(
+        &mut resolved.ctx,
+        &mut meta,
+        SnapshotDetails::new(OperationKind::UpdateCommitMessage),
+        request.dry_run.into(),
+        |mut tx| {
+            let new_commit = tx.reword_commit(target, BString::from(request.message).as_ref())?;
+            Ok(but_transaction::Commit(new_commit.id))
+        },
+    )?;
+
+    let message = format!(
+        "Reworded {} as {}.",
+        target.to_hex_with_len(7),
+        new_commit.to_hex_with_len(7)
+    );
+    Ok(MutationView {
+        commit_id: Some(new_commit.to_string()),
+        ..MutationView::new(resolved.repository, request.dry_run, workspace, message)
+    })
+}
+
+/// Amend each uncommitted hunk into the commit it depends on, or the commit that last changed
+/// its lines, like `but absorb`.
+#[cfg(feature = "legacy")]
+pub(super) fn absorb(request: AbsorbRequest) -> Result<MutationView> {
+    let mut resolved = open_repository(&request.repository)?;
+    let ctx = &mut resolved.ctx;
+    let mut meta = ctx.meta()?;
+    let mut guard = ctx.exclusive_worktree_access();
+    let plan = but_api::legacy::absorb::absorption_plan_with_perm(
+        ctx,
+        but_hunk_assignment::AbsorptionTarget::All,
+        guard.write_permission(),
+    )?;
+    if plan.is_empty() {
+        bail!("There are no uncommitted changes to absorb");
+    }
+
+    let (rejected_paths, workspace) = but_transaction::with_transaction_with_perm(
+        ctx,
+        &mut meta,
+        guard.write_permission(),
+        SnapshotDetails::new(OperationKind::Absorb),
+        request.dry_run.into(),
+        |mut tx| {
+            let mut rejected_paths = Vec::new();
+            for absorption in &plan {
+                let changes = but_hunk_assignment::convert_hunks_to_diff_specs(&absorption.hunks)?;
+                let outcome = tx.amend_commit(absorption.commit_id, changes, ChangeSource::Head)?;
+                rejected_paths.extend(
+                    outcome
+                        .rejected_specs
+                        .into_iter()
+                        .map(|(_, spec)| spec.path.to_string()),
+                );
+            }
+            Ok(but_transaction::Commit(rejected_paths))
+        },
+    )?;
+
+    let message = format!(
+        "Absorbed changes into {} commit{}.",
+        plan.len(),
+        if plan.len() == 1 { "" } else { "s" }
+    );
+    Ok(MutationView {
+        rejected_paths,
+        ..MutationView::new(resolved.repository, request.dry_run, workspace, message)
+    })
+}
+
+#[cfg(not(feature = "legacy"))]
+pub(super) fn absorb(_request: AbsorbRequest) -> Result<MutationView> {
+    bail!("Absorbing changes is not available in this build")
+}
+
+impl From<ChangeSelection> for DiffSpec {
+    fn from(change: ChangeSelection) -> Self {
+        DiffSpec {
+            previous_path: change.previous_path.map(Into::into),
+            path: change.path.into(),
+            hunk_headers: change
+                .hunks
+                .into_iter()
+                .map(|hunk| HunkHeader {
+                    old_start: hunk.old_start,
+                    old_lines: hunk.old_lines,
+                    new_start: hunk.new_start,
+                    new_lines: hunk.new_lines,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The full reference name of the local branch `name`, which may already be a full name.
+fn branch_ref_name(name: &str) -> Result<FullName> {
+    let name = if name.starts_with("refs/") {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(format!("refs/heads/{name}"))
+    };
+    FullName::try_from(name.as_ref()).with_context(|| format!("Invalid branch name: {name}"))
+}
+
+fn commit_id(repo: &gix::Repository, rev: &str) -> Result<gix::ObjectId> {
+    Ok(repo
+        .rev_parse_single(rev)
+        .with_context(|| format!("Could not find commit {rev}"))?
+        .object()?
+        .peel_to_commit()?
+        .id)
+}
+
+fn commit_ids(repo: &gix::Repository, revs: &[String]) -> Result<Vec<gix::ObjectId>> {
+    if revs.is_empty() {
+        bail!("At least one commit ID is required");
+    }
+    revs.iter().map(|rev| commit_id(repo, rev)).collect()
+}
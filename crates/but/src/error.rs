@@ -158,6 +158,16 @@ impl CliError {
         }
     }
 
+    /// Name the argument a [`Self::BadInput`] is about, unless it already names one.
+    pub fn arg_name_if_unset<S: AsRef<str>>(self, name: S) -> Self {
+        match self {
+            Self::BadInput(value) if value.argument_name().is_none() => {
+                Self::BadInput(value.arg_name(name))
+            }
+            other => other,
+        }
+    }
+
     /// Add a lazily computed hint if the result is a [`CliError::BadInput`].
     fn with_hint<S: AsRef<str>>(self, make_hint: impl FnOnce() -> S) -> Self {
         match self {
@@ -96,6 +96,7 @@ fn head_info(
         traversal: but_graph::init::Options::limited(),
         expensive_commit_info,
         gerrit_mode,
+        verify_signatures: false,
     };
     let mut info = ref_info::graph_to_ref_info(&ws, &repo, options)?.pruned_to_entrypoint();
 
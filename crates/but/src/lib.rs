@@ -330,6 +330,15 @@ pub async fn handle_args(args: impl Iterator<Item = OsString>) -> Result<()> {
         };
         return run_agentlog_command(&args.current_dir, cmd, &mut out);
     }
+    // Git runs this for most of its commands, so it has to be quick and must not emit metrics.
+    #[cfg(feature = "legacy")]
+    if let Some(Subcommands::Fsmonitor(..)) = &args.cmd {
+        let Some(Subcommands::Fsmonitor(args::fsmonitor::Platform { cmd })) = args.cmd.take()
+        else {
+            unreachable!("fsmonitor command was checked above")
+        };
+        return command::legacy::fsmonitor::handle(&args.current_dir, cmd, &mut out);
+    }
     let app_settings = app_settings()?.clone();
 
     let result = match args.cmd.take() {
@@ -823,6 +832,8 @@ async fn match_subcommand(
         | Subcommands::Undo(..)
         | Subcommands::Redo(..)
         | Subcommands::RefreshRemoteData { .. }
+        | Subcommands::Watch(..)
+        | Subcommands::Exec(..)
         | Subcommands::Land { .. } => setup::init_ctx(&args, InitCtxOptions::default(), out)?,
         #[cfg(feature = "legacy")]
         Subcommands::Clean { .. }
@@ -831,6 +842,7 @@ async fn match_subcommand(
         | Subcommands::Show { .. }
         | Subcommands::Commit(..)
         | Subcommands::Squash(..)
+        | Subcommands::Split(..)
         | Subcommands::Move(..)
         | Subcommands::_Diff2(..)
         | Subcommands::_Reword2(..)
@@ -838,9 +850,12 @@ async fn match_subcommand(
         | Subcommands::Absorb { .. }
         | Subcommands::Discard(..)
         | Subcommands::Pr(..)
+        | Subcommands::Gerrit(..)
         | Subcommands::Resolve { .. }
         | Subcommands::Uncommit(..)
         | Subcommands::Amend(..)
+        | Subcommands::ApplyPlan(..)
+        | Subcommands::Group(..)
         | Subcommands::Pick(..)
         | Subcommands::Unapply(..)
         | Subcommands::Open(..)
@@ -874,6 +889,10 @@ async fn match_subcommand(
         Subcommands::AgentLog { .. } => {
             unreachable!("agentlog command is handled before metrics setup")
         }
+        #[cfg(feature = "legacy")]
+        Subcommands::Fsmonitor(..) => {
+            unreachable!("fsmonitor command is handled before metrics setup")
+        }
         Subcommands::External(_) => {
             unreachable!("external commands are delegated before reaching match_subcommand")
         }
@@ -904,7 +923,7 @@ async fn match_subcommand(
             unreachable!("handled above")
         }
         #[cfg(feature = "legacy")]
-        Subcommands::Setup { .. } => {
+        Subcommands::Setup { .. } | Subcommands::Fsmonitor(..) => {
             unreachable!("handled above")
         }
         Subcommands::_Open {
@@ -1215,6 +1234,16 @@ async fn match_subcommand(
                 .show_root_cause_error_then_exit_without_destructors(output)
         }
         #[cfg(feature = "legacy")]
+        Subcommands::Watch(watch_args) => {
+            command::legacy::watch::handle(&mut ctx, out, watch_args).emit_metrics(metrics_ctx)?;
+            None
+        }
+        #[cfg(feature = "legacy")]
+        Subcommands::Exec(exec_args) => {
+            command::legacy::exec::handle(&mut ctx, out, exec_args).emit_metrics(metrics_ctx)?;
+            None
+        }
+        #[cfg(feature = "legacy")]
         Subcommands::Commit(commit_args) => {
             use crate::utils::IntermediateChannel;
 
@@ -1251,6 +1280,21 @@ async fn match_subcommand(
             ws
         }
         #[cfg(feature = "legacy")]
+        Subcommands::Split(split_args) => {
+            use crate::utils::IntermediateChannel;
+
+            let status_after = args.status_after;
+            out.begin_status_after(status_after);
+            status_after_data = Some(status_after);
+
+            newly_conflicted_data = Some(command::legacy::conflict_notice::snapshot(&ctx));
+            let (outcome, ws) =
+                command::legacy::split::split(&mut ctx, IntermediateChannel::new(out), split_args)
+                    .emit_metrics(metrics_ctx)?;
+            out.print_cli_output(outcome)?;
+            ws
+        }
+        #[cfg(feature = "legacy")]
         Subcommands::Move(move_args) => {
             use crate::utils::IntermediateChannel;
 
@@ -1343,8 +1387,55 @@ async fn match_subcommand(
                         .emit_metrics(metrics_ctx)?;
                     None
                 }
-                Some(args::oplog::Subcommands::Restore { oplog_sha }) => {
-                    command::legacy::oplog::restore_to_oplog(&mut ctx, out, &oplog_sha)
+                Some(args::oplog::Subcommands::Restore {
+                    oplog_sha,
+                    dry_run,
+                    paths,
+                }) => {
+                    if paths.is_empty() {
+                        command::legacy::oplog::restore_to_oplog(&mut ctx, out, &oplog_sha)
+                    } else {
+                        command::legacy::oplog::restore_paths(
+                            &mut ctx, out, &oplog_sha, &paths, dry_run,
+                        )
+                    }
+                    .emit_metrics(metrics_ctx)?;
+                    None
+                }
+                Some(args::oplog::Subcommands::Gc {
+                    max_age_days,
+                    max_count,
+                    keep_every_nth,
+                    thin_after,
+                    dry_run,
+                }) => {
+                    command::legacy::oplog::gc(
+                        &mut ctx,
+                        out,
+                        command::legacy::oplog::RetentionOverrides {
+                            max_age_days,
+                            max_count,
+                            keep_every_nth,
+                            thin_after,
+                        },
+                        dry_run,
+                    )
+                    .emit_metrics(metrics_ctx)?;
+                    None
+                }
+                Some(args::oplog::Subcommands::Export { path, from, to }) => {
+                    command::legacy::oplog::export(
+                        &ctx,
+                        out,
+                        &path,
+                        from.as_deref(),
+                        to.as_deref(),
+                    )
+                    .emit_metrics(metrics_ctx)?;
+                    None
+                }
+                Some(args::oplog::Subcommands::Import { path }) => {
+                    command::legacy::oplog::import(&mut ctx, out, &path)
                         .emit_metrics(metrics_ctx)?;
                     None
                 }
@@ -1433,6 +1524,7 @@ async fn match_subcommand(
 
             let outcome =
                 command::comment::comment(&mut ctx, IntermediateChannel::new(out), comment_args)
+                    .await
                     .emit_metrics(metrics_ctx)?;
             out.print_cli_output(outcome)?;
             None
@@ -1560,6 +1652,31 @@ async fn match_subcommand(
             }
         }
         #[cfg(feature = "legacy")]
+        Subcommands::Gerrit(args::gerrit::Platform { cmd }) => {
+            match cmd {
+                None | Some(args::gerrit::Subcommands::Chain) => {
+                    command::legacy::gerrit::show_chains(&ctx, out)
+                        .context("Failed to show the Gerrit relation chains.")
+                        .emit_metrics(metrics_ctx)?;
+                }
+                Some(args::gerrit::Subcommands::Pull {
+                    changes,
+                    yes,
+                    dry_run,
+                }) => {
+                    command::legacy::gerrit::pull(&mut ctx, changes, yes, dry_run, out)
+                        .context("Failed to pull Gerrit patchsets.")
+                        .emit_metrics(metrics_ctx)?;
+                }
+                Some(args::gerrit::Subcommands::Submit { branch, yes }) => {
+                    command::legacy::gerrit::submit(&ctx, branch.as_deref(), yes, out)
+                        .context("Failed to submit the Gerrit relation chain.")
+                        .emit_metrics(metrics_ctx)?;
+                }
+            }
+            None
+        }
+        #[cfg(feature = "legacy")]
         Subcommands::RefreshRemoteData {
             fetch,
             pr: prs,
@@ -1618,6 +1735,33 @@ async fn match_subcommand(
             ws
         }
         #[cfg(feature = "legacy")]
+        Subcommands::ApplyPlan(plan_args) => {
+            use crate::utils::IntermediateChannel;
+
+            let status_after = args.status_after;
+            out.begin_status_after(status_after);
+            status_after_data = Some(status_after);
+
+            newly_conflicted_data = Some(command::legacy::conflict_notice::snapshot(&ctx));
+            let (outcome, ws) = command::legacy::apply_plan::apply_plan(
+                &mut ctx,
+                IntermediateChannel::new(out),
+                plan_args,
+            )
+            .emit_metrics(metrics_ctx)?;
+            out.print_cli_output(outcome)?;
+            ws
+        }
+        #[cfg(feature = "legacy")]
+        Subcommands::Group(group_args) => {
+            let status_after = args.status_after;
+            out.begin_status_after(status_after);
+            status_after_data = Some(status_after);
+
+            newly_conflicted_data = Some(command::legacy::conflict_notice::snapshot(&ctx));
+            command::legacy::group::handle(&mut ctx, out, group_args).emit_metrics(metrics_ctx)?
+        }
+        #[cfg(feature = "legacy")]
         Subcommands::Land {
             branch,
             yes,
@@ -1729,6 +1873,10 @@ fn run_agentlog_command(
                 Some(DetectedAgent::ClaudeCode | DetectedAgent::ClaudeCodeCowork) => {
                     Some(but_agentlog::Agent::Claude)
                 }
+                Some(DetectedAgent::GeminiCli) => Some(but_agentlog::Agent::Gemini),
+                Some(DetectedAgent::Cursor | DetectedAgent::CursorCli) => {
+                    Some(but_agentlog::Agent::Cursor)
+                }
                 _ => None,
             };
         }
@@ -61,7 +61,7 @@ pub fn from_editor(
     rest_text: Option<&str>,
     file_suffix: &str,
 ) -> Result<BString> {
-    const ALLOWED_SUFFIXES: &[&str] = &[".txt", ".md", ".patch"]; // feel free to add more allowed suffixes
+    const ALLOWED_SUFFIXES: &[&str] = &[".txt", ".md", ".patch", ".yaml"]; // feel free to add more allowed suffixes
     if !ALLOWED_SUFFIXES.contains(&file_suffix) {
         bail!(
             "File suffix '{}' is not allowed. Must be one of: {}",
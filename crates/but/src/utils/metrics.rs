@@ -117,6 +117,10 @@ impl Subcommands {
             #[cfg(feature = "legacy")]
             Subcommands::Show { .. } => Show,
             #[cfg(feature = "legacy")]
+            Subcommands::Watch(..) => Watch,
+            #[cfg(feature = "legacy")]
+            Subcommands::Exec(..) => Exec,
+            #[cfg(feature = "legacy")]
             Subcommands::Pull { .. } => Pull,
             #[cfg(feature = "legacy")]
             Subcommands::Fetch => Pull,
@@ -154,6 +158,9 @@ impl Subcommands {
                 Some(crate::args::oplog::Subcommands::List { .. }) => OplogList,
                 Some(crate::args::oplog::Subcommands::Snapshot { .. }) => OplogSnapshot,
                 Some(crate::args::oplog::Subcommands::Restore { .. }) => Restore,
+                Some(crate::args::oplog::Subcommands::Gc { .. }) => OplogGc,
+                Some(crate::args::oplog::Subcommands::Export { .. }) => OplogExport,
+                Some(crate::args::oplog::Subcommands::Import { .. }) => OplogImport,
             },
             #[cfg(feature = "legacy")]
             Subcommands::Undo(..) => Undo,
@@ -177,6 +184,12 @@ impl Subcommands {
                 Some(forge::pr::Subcommands::SetDraft { .. }) => SetReviewDraft,
                 Some(forge::pr::Subcommands::SetReady { .. }) => SetReviewReady,
             },
+            #[cfg(feature = "legacy")]
+            Subcommands::Gerrit(crate::args::gerrit::Platform { cmd }) => match cmd {
+                None | Some(crate::args::gerrit::Subcommands::Chain) => GerritChain,
+                Some(crate::args::gerrit::Subcommands::Pull { .. }) => GerritPull,
+                Some(crate::args::gerrit::Subcommands::Submit { .. }) => GerritSubmit,
+            },
             Subcommands::Mcp(_) => Unknown,
             #[cfg(feature = "legacy")]
             Subcommands::Actions(_) | Subcommands::Setup { .. } | Subcommands::Teardown { .. } => {
@@ -218,8 +231,14 @@ impl Subcommands {
             #[cfg(feature = "legacy")]
             Subcommands::Amend(..) => Amend,
             #[cfg(feature = "legacy")]
+            Subcommands::ApplyPlan(..) => ApplyPlan,
+            #[cfg(feature = "legacy")]
+            Subcommands::Group(..) => Group,
+            #[cfg(feature = "legacy")]
             Subcommands::Squash(..) => Squash,
             #[cfg(feature = "legacy")]
+            Subcommands::Split(..) => Split,
+            #[cfg(feature = "legacy")]
             Subcommands::Move(..) => Move,
             #[cfg(feature = "legacy")]
             Subcommands::Land { .. } => Land,
@@ -238,6 +257,8 @@ impl Subcommands {
             Subcommands::Clean { .. } => Clean,
             Subcommands::Onboarding => Unknown,
             Subcommands::AgentLog { .. } => Unknown,
+            #[cfg(feature = "legacy")]
+            Subcommands::Fsmonitor(..) => Unknown,
             Subcommands::External(_) => External,
         }
     }
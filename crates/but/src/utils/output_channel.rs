@@ -822,6 +822,16 @@ impl OutputChannel {
         }
     }
 
+    /// Write `value` as compact JSON on a line of its own, and flush it right away.
+    ///
+    /// This is for commands that stream values as they happen, to be consumed line by line.
+    pub fn write_value_line(&mut self, value: impl serde::Serialize) -> std::io::Result<()> {
+        let mut stdout = self.stdout.lock();
+        serde_json::to_writer(&mut stdout, &value).map_err(std::io::Error::other)?;
+        stdout.write_all(b"\n")?;
+        stdout.flush()
+    }
+
     /// Start buffering JSON output instead of writing to stdout.
     pub fn start_json_buffering(&mut self) {
         self.json_buffer = Some(serde_json::Value::Null);
@@ -422,6 +422,36 @@ fn dependencies_for_spec(
     Ok(result)
 }
 
+/// The short names of the branches whose commits the uncommitted `hunk` in
+/// `path` depends on, as computed by `dependencies`. A `hunk` of `None` stands
+/// for the whole file, which depends on everything any of its hunks depends on.
+pub(crate) fn locking_branches(
+    ws: &Workspace,
+    dependencies: &HunkDependencies,
+    path: &bstr::BStr,
+    hunk: Option<&HunkHeader>,
+) -> Vec<String> {
+    let mut branches = Vec::new();
+    for (dep_path, dep_hunk, locks) in &dependencies.diffs {
+        if dep_path.as_bytes().as_bstr() != path {
+            continue;
+        }
+        if let Some(hunk) = hunk
+            && !hunks_overlap(hunk, &HunkHeader::from(dep_hunk))
+        {
+            continue;
+        }
+        for lock in locks {
+            if let Some(branch) = branch_of_commit(ws, lock.commit_id, stack_of(lock.target))
+                && !branches.contains(&branch)
+            {
+                branches.push(branch);
+            }
+        }
+    }
+    branches
+}
+
 /// The stack a lock points at, if it is identifiable.
 fn stack_of(target: HunkLockTarget) -> Option<StackId> {
     match target {
@@ -0,0 +1,130 @@
+use snapbox::str;
+
+use crate::utils::{CommandExt as _, Sandbox};
+
+const PLAN: &str = "\
+- op: branch
+  name: B
+  above: A
+- op: reword
+  commit: tpm
+  message: Planned message
+";
+
+#[test]
+fn dry_run_previews_plan_without_changing_anything() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file(".git/plan.yaml", PLAN);
+    let subject = env.invoke_git("show -s --format=%s refs/heads/A");
+
+    env.but("apply-plan .git/plan.yaml --dry-run")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Would apply 2 operations:
+  1. create branch B above A
+  2. reword tpm -> [..]
+
+"#]]);
+
+    assert_eq!(
+        env.invoke_git("show -s --format=%s refs/heads/A"),
+        subject,
+        "a dry run doesn't reword"
+    );
+    env.invoke_git_fails(
+        "rev-parse --verify refs/heads/B",
+        "a dry run doesn't create branches",
+    );
+}
+
+#[test]
+fn plan_is_applied_as_one_undoable_operation() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file(".git/plan.yaml", PLAN);
+    let subject = env.invoke_git("show -s --format=%s refs/heads/A");
+
+    env.but("apply-plan .git/plan.yaml")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Applied 2 operations:
+  1. create branch B above A
+  2. reword tpm -> [..]
+
+"#]]);
+
+    assert_eq!(
+        env.invoke_git("show -s --format=%s refs/heads/A"),
+        "Planned message"
+    );
+    env.invoke_git("rev-parse --verify refs/heads/B");
+
+    env.but("undo").assert().success();
+    assert_eq!(
+        env.invoke_git("show -s --format=%s refs/heads/A"),
+        subject,
+        "a single undo reverts the whole plan"
+    );
+}
+
+#[test]
+fn json_plans_report_rewritten_commits() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file(
+        ".git/plan.json",
+        r#"[{ "op": "reword", "commit": "tpm", "message": "Planned message" }]"#,
+    );
+
+    env.but("--json apply-plan .git/plan.json --dry-run")
+        .allow_json()
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+{
+  "dryRun": true,
+  "operations": [
+    {
+      "op": "reword",
+      "description": "reword tpm",
+      "newCommitId": "[..]",
+      "newChangeId": "[..]"
+    }
+  ]
+}
+
+"#]]);
+}
+
+#[test]
+fn invalid_step_fails_without_changing_anything() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file(
+        ".git/plan.yaml",
+        "\
+- op: reword
+  commit: tpm
+  message: Planned message
+- op: squash
+  commits: [nope]
+  into: tpm
+",
+    );
+    let subject = env.invoke_git("show -s --format=%s refs/heads/A");
+
+    env.but("apply-plan .git/plan.yaml")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Bad input for 'step 2'
+
+Could not find commit: 'nope'
+...
+"#]]);
+
+    assert_eq!(env.invoke_git("show -s --format=%s refs/heads/A"), subject);
+}
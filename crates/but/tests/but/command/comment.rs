@@ -14,7 +14,7 @@ fn add_list_drift_archive_on_uncommitted_changes() {
         .success()
         .stdout_eq(snapbox::str![[r#"
 Added comment
-[[..]] src/note.ts:2 (uncommitted)
+[[..]] src/note.ts:2 (uncommitted) by [..]
   rename this variable
 
 "#]]);
@@ -25,7 +25,7 @@ Added comment
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-[[..]] src/note.ts:3 (uncommitted)
+[[..]] src/note.ts:3 (uncommitted) by [..]
   rename this variable
   | +line zero
   | +line one
@@ -96,7 +96,7 @@ fn add_and_list_on_a_commit() {
         .success()
         .stdout_eq(snapbox::str![[r#"
 Added comment
-[[..]] A:1 (commit tpm[..])
+[[..]] A:1 (commit tpm[..]) by [..]
   commit-anchored
 
 "#]]);
@@ -105,7 +105,7 @@ Added comment
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-[[..]] A:1 (commit tpm[..])
+[[..]] A:1 (commit tpm[..]) by [..]
   commit-anchored
   | +A
 
@@ -136,7 +136,7 @@ No comments appeared within 0s. Run `but _comment list --wait` again to keep wai
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-[[..]] src/note.ts:1 (uncommitted)
+[[..]] src/note.ts:1 (uncommitted) by [..]
   already here
   | +line one
 
@@ -161,7 +161,7 @@ fn wait_unblocks_when_a_comment_appears_mid_wait() {
             add.assert().success();
         });
         wait.assert().success().stdout_eq(snapbox::str![[r#"
-[[..]] src/note.ts:1 (uncommitted)
+[[..]] src/note.ts:1 (uncommitted) by [..]
   mid-wait arrival
   | +line one
 
@@ -222,13 +222,100 @@ No comments
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-[[..]] A:1 (commit tpm[..])
+[[..]] A:1 (commit tpm[..]) by [..]
   still wanted
   | +A
 
 "#]]);
 }
 
+/// Replies join the thread of the comment they answer, and resolved threads can be filtered out
+/// and reopened.
+#[test]
+fn reply_resolve_and_reopen_threads() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("_comment add A:1 --commit tpm -m 'why this line?'")
+        .assert()
+        .success();
+    let id = single_comment_id(&env);
+
+    env.but(format!("_comment reply {} -m 'it is needed'", &id[..8]))
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+Added reply
+> [[..]] by [..]
+    it is needed
+
+"#]]);
+    env.but("_comment list --branch A")
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+[[..]] A:1 (commit tpm[..]) by [..]
+  why this line?
+  | +A
+  > [[..]] by [..]
+    it is needed
+
+"#]]);
+    env.but("_comment list --branch does-not-exist")
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+No comments
+
+"#]]);
+
+    env.but(format!("_comment resolve {}", &id[..8]))
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+Resolved the thread of comment [..]
+
+"#]]);
+    env.but(format!("_comment resolve {}", &id[..8]))
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+The thread of comment [..] was already resolved; nothing to do
+
+"#]]);
+    env.but("_comment list")
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+[[..]] A:1 (commit tpm[..], resolved) by [..]
+  why this line?
+  | +A
+  > [[..]] by [..]
+    it is needed
+
+"#]]);
+    env.but("_comment list --unresolved")
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+No comments
+
+"#]]);
+
+    env.but(format!("_comment reopen {}", &id[..8]))
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+Reopened the thread of comment [..]
+
+"#]]);
+    assert_eq!(
+        single_comment_id(&env),
+        id,
+        "replies aren't listed as threads"
+    );
+}
+
 /// Bad anchors and unknown ids are rejected as bad input.
 #[test]
 fn rejects_bad_input() {
@@ -0,0 +1,122 @@
+use snapbox::str;
+
+use crate::utils::{CommandExt as _, Sandbox};
+
+#[test]
+fn runs_command_on_each_commit_of_the_stack_from_the_bottom() {
+    let env =
+        Sandbox::init_scenario_with_target_and_default_settings("one-stack-two-dependent-branches");
+    env.setup_single_stack_metadata_at_target(&["B", "A"], "origin/main");
+
+    env.but("exec B -- test -f A")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+
+✓ [..] add A
+✓ [..] add B
+
+"#]]);
+
+    // Only the commits up to the given branch are visited.
+    env.but("exec A -- test -f A")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+
+✓ [..] add A
+
+"#]]);
+
+    assert!(
+        !env.invoke_git("worktree list").contains("but-exec-"),
+        "the temporary worktree is removed again"
+    );
+}
+
+#[test]
+fn reports_failing_commits_and_can_stop_at_the_first() {
+    let env =
+        Sandbox::init_scenario_with_target_and_default_settings("one-stack-two-dependent-branches");
+    env.setup_single_stack_metadata_at_target(&["B", "A"], "origin/main");
+
+    env.but("exec B -- test -f B")
+        .assert()
+        .failure()
+        .stdout_eq(str![[r#"
+
+✗ [..] add A (exit code 1)
+✓ [..] add B
+
+"#]])
+        .stderr_eq(str![[r#"
+...
+Error: command failed on 1 of 2 commits
+
+"#]]);
+
+    env.but("exec B --fail-fast -- test -f B")
+        .assert()
+        .failure()
+        .stdout_eq(str![[r#"
+
+✗ [..] add A (exit code 1)
+Skipped 1 commit after the first failure
+
+"#]]);
+}
+
+#[test]
+fn conflicted_commits_are_skipped() {
+    let env = super::util::sandbox_with_conflicted_commit();
+    env.but("branch new top --anchor A").assert().success();
+    env.file("top.txt", "top");
+    env.but("commit -b top -m 'clean top commit'")
+        .assert()
+        .success();
+
+    env.but("exec top -- test -f top.txt")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+
+⚠ [..] A-change (conflicted, skipped)
+✓ [..] clean top commit
+Skipped 1 conflicted commit, resolve with `but resolve` to run on it
+
+"#]]);
+
+    // A conflicted commit doesn't count as failure, so there is nothing to stop at or edit.
+    env.but("exec top --edit -- test -f top.txt")
+        .assert()
+        .success();
+    assert_ne!(
+        env.invoke_git("rev-parse --abbrev-ref HEAD"),
+        "gitbutler/edit"
+    );
+}
+
+#[test]
+fn edit_enters_edit_mode_on_the_first_failing_commit() {
+    let env =
+        Sandbox::init_scenario_with_target_and_default_settings("one-stack-two-dependent-branches");
+    env.setup_single_stack_metadata_at_target(&["B", "A"], "origin/main");
+
+    env.but("exec B --edit -- test -f B")
+        .assert()
+        .failure()
+        .stdout_eq(str![[r#"
+
+✗ [..] add A (exit code 1)
+Skipped 1 commit after the first failure
+Editing commit [..], finish with but resolve finish or cancel with but resolve cancel
+
+"#]]);
+
+    assert_eq!(
+        env.invoke_git("rev-parse --abbrev-ref HEAD"),
+        "gitbutler/edit",
+        "the failing commit is checked out for editing"
+    );
+    assert_eq!(env.invoke_git("ls-files"), "A\nM");
+}
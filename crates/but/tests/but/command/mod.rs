@@ -10,6 +10,8 @@ mod alias;
 #[cfg(feature = "legacy")]
 mod amend;
 #[cfg(feature = "legacy")]
+mod apply_plan;
+#[cfg(feature = "legacy")]
 mod branch;
 #[cfg(feature = "legacy")]
 mod clean;
@@ -25,6 +27,8 @@ mod diff2;
 #[cfg(feature = "legacy")]
 mod discard;
 #[cfg(feature = "legacy")]
+mod exec;
+#[cfg(feature = "legacy")]
 mod expand;
 #[cfg(unix)]
 mod external;
@@ -54,6 +58,8 @@ mod reword2;
 mod setup;
 mod skill;
 #[cfg(feature = "legacy")]
+mod split;
+#[cfg(feature = "legacy")]
 mod squash;
 #[cfg(feature = "legacy")]
 mod status;
@@ -311,6 +311,47 @@ fn agent_resolve_finish_json_status_tracks_rebased_conflict_queue() {
     );
 }
 
+#[test]
+fn resolve_finish_records_the_resolution_until_it_is_forgotten() {
+    let env = enter_edit_mode_with_conflicted_commit();
+    env.but("resolve recorded")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+No conflict resolutions were recorded yet.
+
+"#]]);
+
+    env.file("file.txt", "resolved content\n");
+    env.invoke_git("add file.txt");
+    env.but("resolve finish").assert().success();
+
+    env.but("resolve recorded")
+        .assert()
+        .success()
+        .stderr_eq(str![""])
+        .stdout_eq(str![[r#"
+[..] file.txt [..]
+
+"#]]);
+
+    env.but("resolve forget --all")
+        .assert()
+        .success()
+        .stderr_eq(str![""])
+        .stdout_eq(str![[r#"
+Forgot resolution [..] of file.txt
+
+"#]]);
+    env.but("resolve recorded")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+No conflict resolutions were recorded yet.
+
+"#]]);
+}
+
 #[test]
 fn resolve_cancel_works_in_edit_mode() {
     let env = enter_edit_mode_with_conflicted_commit();
@@ -0,0 +1,124 @@
+use snapbox::str;
+
+use crate::utils::{CommandExt as _, Sandbox};
+
+fn one_commit_with_two_files() -> Sandbox {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("zero-stacks");
+    env.setup_metadata(&[]);
+
+    env.file("one", "content of one");
+    env.file("two", "content of two");
+    env.file("three", "content of three");
+
+    env.but("commit -m 'add one' one").assert().success();
+    env.but("commit -m 'add two and three' two three")
+        .assert()
+        .success();
+
+    env
+}
+
+#[test]
+fn split_file_into_new_commit_and_undo() {
+    let env = one_commit_with_two_files();
+    let before = env.invoke_git("log --format=%s -3 a-branch-1");
+
+    env.but("split 1#0 -c 1#0:o -m 'add three'")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Split [..] into [..], [..]
+
+"#]]);
+
+    assert_eq!(
+        env.invoke_git("log --format=%s -3 a-branch-1"),
+        "add two and three\nadd three\nadd one"
+    );
+    assert_eq!(
+        env.invoke_git("diff-tree --no-commit-id --name-only -r a-branch-1~1"),
+        "three",
+        "the first piece takes the place of the original commit"
+    );
+    assert_eq!(
+        env.invoke_git("diff-tree --no-commit-id --name-only -r a-branch-1"),
+        "two",
+        "what wasn't selected ends up on top with the original message"
+    );
+
+    env.but("undo").assert().success();
+    assert_eq!(
+        env.invoke_git("log --format=%s -3 a-branch-1"),
+        before,
+        "a single undo restores the original commit"
+    );
+}
+
+#[test]
+fn split_needs_a_message_per_group() {
+    let env = one_commit_with_two_files();
+
+    env.but("split 1#0 -c 1#0:o -c 1#0:t -m 'add three'")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Each --changes needs a --message, but got 2 --changes and 1 --message
+...
+"#]]);
+}
+
+#[test]
+fn split_hunks_of_a_file() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("zero-stacks");
+    env.setup_metadata(&[]);
+    env.file("lines", "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n");
+    env.but("commit -m 'add lines' lines").assert().success();
+    env.file("lines", "one\n2\n3\n4\n5\n6\n7\n8\n9\nten\n");
+    env.but("commit -m 'spell out first and last line' lines")
+        .assert()
+        .success();
+
+    env.but("split 1#0 -c 1#0:lines@2 -m 'spell out last line'")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Split [..] into [..], [..]
+
+"#]]);
+
+    assert_eq!(
+        env.invoke_git("log --format=%s -3 a-branch-1"),
+        "spell out first and last line\nspell out last line\nadd lines"
+    );
+    assert_eq!(
+        env.invoke_git("show a-branch-1~1:lines"),
+        "1\n2\n3\n4\n5\n6\n7\n8\n9\nten",
+        "only the selected hunk is part of the first commit"
+    );
+    assert_eq!(
+        env.invoke_git("show a-branch-1:lines"),
+        "one\n2\n3\n4\n5\n6\n7\n8\n9\nten"
+    );
+
+    env.but("split 1#0 -c 1#0:lines@3 -m 'nothing'")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Cannot select hunk 3 of 'lines', which has 1 hunk
+...
+"#]]);
+}
+
+#[test]
+fn split_rejects_taking_all_changes() {
+    let env = one_commit_with_two_files();
+
+    env.but("split 1#0 -c 1#0:o,1#0:t -m 'add both'")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+...
+Error: Nothing to split, as the only piece has all changes of the commit
+...
+"#]]);
+}
@@ -97,6 +97,7 @@ pub fn list_branches(
             traversal,
             expensive_commit_info: false,
             gerrit_mode,
+            verify_signatures: false,
         },
     )?;
     // Resolve each segment's PR association from the forge review cache (keyed by
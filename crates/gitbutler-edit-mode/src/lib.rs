@@ -343,6 +343,13 @@ pub(crate) fn save_and_return_to_workspace(ctx: &Context, perm: &mut RepoExclusi
         )?
     };
 
+    // Remember how the conflicts of the edited commit were resolved before
+    // rebasing the commits above it, so they already run into them resolved.
+    if let Err(err) = but_rebase::rerere::record(repo, edit_mode_metadata.commit_oid, tree_id, None)
+    {
+        tracing::warn!("Could not record conflict resolutions: {err:#}");
+    }
+
     let workspace_commit = repo
         .find_reference(WORKSPACE_BRANCH_REF)?
         .peel_to_commit()?;
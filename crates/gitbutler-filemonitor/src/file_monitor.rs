@@ -14,6 +14,7 @@ use tracing::Level;
 
 use crate::{
     events::InternalEvent,
+    fsmonitor,
     watch_plan::{
         build_index_icase_accelerator_if_needed, compute_watch_plan_for_repo, is_tracked_in_index,
         is_watchable_directory, to_repo_relative_path,
@@ -284,6 +285,17 @@ pub fn spawn(
         worktree_path.display()
     ))?;
     let git_dir = repo.path().to_owned();
+    // Start the journal first so its cookie files are created in a watched directory.
+    let mut journal = fsmonitor::Journal::start(&git_dir)
+        .inspect_err(|err| {
+            tracing::warn!(
+                %project_id,
+                ?err,
+                "failed to start the fsmonitor journal; worktree changes will be found by scanning"
+            )
+        })
+        .ok()
+        .flatten();
 
     let mut effective_watch_mode = watch_mode;
 
@@ -383,6 +395,8 @@ pub fn spawn(
             match result {
                 Err(err) => {
                     tracing::error!(?err, "ignored file watcher error");
+                    // Events may have been lost, so readers can't rely on the journal anymore.
+                    update_journal(&mut journal, fsmonitor::Journal::record_everything);
                 }
                 Ok(events) => {
                     let num_events = events.len();
@@ -454,10 +468,21 @@ pub fn spawn(
                         };
                     let (mut stripped_git_paths, mut worktree_relative_paths) =
                         (HashSet::new(), HashSet::new());
+                    let (mut journal_paths, mut cookies) = (BTreeSet::new(), Vec::new());
                     for (file_path, kind) in classified_file_paths {
                         match kind {
-                            FileKind::ProjectIgnored => ignored += 1,
+                            FileKind::ProjectIgnored => {
+                                ignored += 1;
+                                if let Ok(relative_file_path) =
+                                    file_path.strip_prefix(&worktree_path)
+                                {
+                                    journal_paths.insert(
+                                        to_repo_relative_path(relative_file_path).into_owned(),
+                                    );
+                                }
+                            }
                             FileKind::GitUninteresting => git_noop += 1,
+                            FileKind::FsmonitorCookie => cookies.push(file_path),
                             FileKind::Project | FileKind::Git => match file_path
                                 .strip_prefix(&worktree_path)
                             {
@@ -468,6 +493,9 @@ pub fn spawn(
                                     if let Ok(stripped) = relative_file_path.strip_prefix(".git") {
                                         stripped_git_paths.insert(stripped.to_owned());
                                     } else {
+                                        journal_paths.insert(
+                                            to_repo_relative_path(relative_file_path).into_owned(),
+                                        );
                                         worktree_relative_paths
                                             .insert(relative_file_path.to_owned());
                                     };
@@ -518,6 +546,17 @@ pub fn spawn(
                         }
                     }
 
+                    update_journal(&mut journal, |journal| {
+                        journal.record(journal_paths.iter().map(AsRef::as_ref))
+                    });
+                    // Whoever waits for a cookie can now read all changes made before it was created.
+                    // Cookies are left to the monitor keeping the journal, if that's not us.
+                    if journal.is_some() {
+                        for cookie in cookies {
+                            std::fs::remove_file(cookie).ok();
+                        }
+                    }
+
                     if !stripped_git_paths.is_empty() {
                         let paths_dedup: Vec<_> = stripped_git_paths.into_iter().collect();
                         stats.record("git_dedup", paths_dedup.len());
@@ -544,6 +583,22 @@ pub fn spawn(
     Ok(FileMonitorHandle { cmd_tx })
 }
 
+/// Apply `record` to `journal`, and stop keeping it if that fails as it would be incomplete.
+fn update_journal(
+    journal: &mut Option<fsmonitor::Journal>,
+    record: impl FnOnce(&mut fsmonitor::Journal) -> Result<()>,
+) {
+    if let Some(inner) = journal.as_mut()
+        && let Err(err) = record(inner)
+    {
+        tracing::warn!(
+            ?err,
+            "failed to write the fsmonitor journal; stopped keeping it"
+        );
+        *journal = None;
+    }
+}
+
 #[cfg(target_family = "unix")]
 fn is_interesting_kind(kind: notify::EventKind) -> bool {
     matches!(
@@ -608,6 +663,8 @@ enum FileKind {
     Project,
     /// A file that was ignored in the project, and thus shouldn't trigger a computation.
     ProjectIgnored,
+    /// A cookie file created by [`fsmonitor::sync()`] to learn when we caught up.
+    FsmonitorCookie,
 }
 
 fn classify_file(git_dir: &Path, file_path: &Path) -> FileKind {
@@ -622,6 +679,8 @@ fn classify_file(git_dir: &Path, file_path: &Path) -> FileKind {
             || check_file_path.starts_with(REMOTE_REFS_DIR)
         {
             FileKind::Git
+        } else if fsmonitor::is_cookie(check_file_path) {
+            FileKind::FsmonitorCookie
         } else {
             FileKind::GitUninteresting
         }
@@ -698,6 +757,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn classify_fsmonitor_cookie() {
+        assert_eq!(
+            classify_file(
+                git_dir(),
+                Path::new("/repo/.git/gitbutler/fsmonitor-cookie-42-abc")
+            ),
+            FileKind::FsmonitorCookie
+        );
+        assert_eq!(
+            classify_file(
+                git_dir(),
+                Path::new("/repo/.git/gitbutler/fsmonitor/journal")
+            ),
+            FileKind::GitUninteresting
+        );
+    }
+
     #[test]
     fn classify_metadata_store_as_uninteresting() {
         // The metadata store is uninteresting — the sentinel is what signals refreshes.
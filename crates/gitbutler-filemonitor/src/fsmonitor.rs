@@ -0,0 +1,360 @@
+//! A token-based journal of worktree changes, which lets Git and GitButler learn what changed since
+//! they last looked instead of scanning the whole worktree.
+//!
+//! While it runs, the [file monitor](crate::spawn()) appends the worktree-relative paths it sees to
+//! a journal in `.git/gitbutler/fsmonitor/`. A token names a position in that journal, so [`query()`]
+//! can answer with all paths recorded after it, which is what Git's fsmonitor hook protocol (version 2)
+//! expects as well, see [`write_hook_response()`].
+//!
+//! Tokens are only valid for as long as the monitor that wrote the journal is running, as nobody knows
+//! what changed while no monitor was running. Unknown tokens, or the absence of a monitor, yield
+//! [`Changes::Everything`], which makes the caller fall back to a full scan.
+
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    io::{ErrorKind, Write as _},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Result, bail};
+use gix::bstr::{BStr, BString, ByteSlice};
+
+/// The git-dir relative directory of the journal.
+const JOURNAL_DIR: &str = "gitbutler/fsmonitor";
+/// The git-dir relative prefix of the cookie files [`sync()`] creates, and which the monitor removes
+/// once it sees them.
+///
+/// They live in the storage directory itself as it's watched in all watch modes.
+const COOKIE_PREFIX: &str = "gitbutler/fsmonitor-cookie-";
+
+const JOURNAL_FILE: &str = "journal";
+/// Held exclusively by the monitor writing the journal, which tells readers that it's running.
+const LOCK_FILE: &str = "lock";
+/// Start over once the journal gets larger than this, invalidating all tokens handed out so far.
+const MAX_JOURNAL_LEN: u64 = 16 * 1024 * 1024;
+const TOKEN_PREFIX: &str = "gitbutler:";
+/// The token handed out if there is no journal to refer to, which is never valid.
+const UNMONITORED_TOKEN: &str = "gitbutler:unmonitored";
+/// The path that stands for everything, as in Git's hook protocol.
+const EVERYTHING: &[u8] = b"/";
+/// How long the hook waits for the monitor to catch up before giving up. It has to wait for the
+/// debouncer, which holds back events until the filesystem was quiet for a moment.
+const HOOK_SYNC_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The writing end of the journal, kept by the file monitor while it runs.
+pub struct Journal {
+    dir: PathBuf,
+    file: File,
+    /// Locked exclusively for as long as we live.
+    _lock: File,
+    len: u64,
+}
+
+impl Journal {
+    /// Start a new journal for the repository at `git_dir`, or return `None` if another monitor
+    /// already keeps one.
+    pub fn start(git_dir: &Path) -> Result<Option<Self>> {
+        let dir = git_dir.join(JOURNAL_DIR);
+        std::fs::create_dir_all(&dir)?;
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(dir.join(LOCK_FILE))?;
+        match lock.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Error(err)) => return Err(err.into()),
+        }
+        let (file, len) = new_session(&dir)?;
+        Ok(Some(Journal {
+            dir,
+            file,
+            _lock: lock,
+            len,
+        }))
+    }
+
+    /// Append the worktree-relative `paths` to the journal, using slashes as separators just like Git.
+    ///
+    /// A directory stands for everything below it.
+    pub fn record<'a>(&mut self, paths: impl IntoIterator<Item = &'a BStr>) -> Result<()> {
+        let mut buf = Vec::new();
+        for path in paths {
+            buf.extend_from_slice(path);
+            buf.push(0);
+        }
+        if buf.is_empty() {
+            return Ok(());
+        }
+        if self.len + buf.len() as u64 > MAX_JOURNAL_LEN {
+            (self.file, self.len) = new_session(&self.dir)?;
+        }
+        self.file.write_all(&buf)?;
+        self.len += buf.len() as u64;
+        Ok(())
+    }
+
+    /// Record that anything may have changed, for instance because filesystem events were lost.
+    pub fn record_everything(&mut self) -> Result<()> {
+        self.record([EVERYTHING.as_bstr()])
+    }
+}
+
+/// Return `true` if the git-dir relative `path` is a cookie file created by [`sync()`].
+pub(crate) fn is_cookie(path: &Path) -> bool {
+    let (dir, prefix) = COOKIE_PREFIX
+        .rsplit_once('/')
+        .expect("the prefix is in a directory");
+    path.parent() == Some(Path::new(dir))
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(prefix))
+}
+
+/// Replace the journal in `dir` with an empty one of a new session, and return it along with its length.
+///
+/// Readers still see the complete previous journal, as it's replaced atomically.
+fn new_session(dir: &Path) -> Result<(File, u64)> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let header = format!("{:x}-{nanos:x}\n", std::process::id());
+    let tmp_path = dir.join(format!("{JOURNAL_FILE}.tmp"));
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(header.as_bytes())?;
+    std::fs::rename(&tmp_path, dir.join(JOURNAL_FILE))?;
+    Ok((file, header.len() as u64))
+}
+
+/// What changed since a token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Changes {
+    /// Anything may have changed, so everything has to be checked.
+    Everything,
+    /// Only these worktree-relative paths changed, sorted and without duplicates.
+    /// Directories stand for everything below them.
+    Paths(Vec<BString>),
+}
+
+/// The answer of [`query()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    /// The token to pass to the next query to learn about changes after this one.
+    pub token: String,
+    /// What changed since the token passed to [`query()`].
+    pub changes: Changes,
+}
+
+/// Return what changed in the worktree of the repository at `git_dir` since `token`, which was returned
+/// by an earlier query. Pass an empty `token` to just obtain one.
+///
+/// Note that only changes that the monitor already processed are known, use [`sync()`] to wait for it
+/// to catch up with recent changes.
+pub fn query(git_dir: &Path, token: &str) -> Result<Query> {
+    let dir = git_dir.join(JOURNAL_DIR);
+    if !is_monitored(&dir)? {
+        return Ok(Query {
+            token: UNMONITORED_TOKEN.into(),
+            changes: Changes::Everything,
+        });
+    }
+    let journal = match std::fs::read(dir.join(JOURNAL_FILE)) {
+        Ok(journal) => journal,
+        Err(err) if err.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err.into()),
+    };
+    Ok(query_journal(&journal, token))
+}
+
+fn query_journal(journal: &[u8], since: &str) -> Query {
+    let Some((session, records)) = journal
+        .split_once_str("\n")
+        .and_then(|(session, records)| Some((session.to_str().ok()?, records)))
+    else {
+        return Query {
+            token: UNMONITORED_TOKEN.into(),
+            changes: Changes::Everything,
+        };
+    };
+    let header_len = session.len() + 1;
+    // Only complete records count, the last one may still be written.
+    let end = records.rfind_byte(0).map_or(0, |pos| pos + 1);
+    let start = since
+        .strip_prefix(TOKEN_PREFIX)
+        .and_then(|since| since.rsplit_once(':'))
+        .filter(|(since_session, _)| *since_session == session)
+        .and_then(|(_, offset)| offset.parse::<usize>().ok())
+        .and_then(|offset| offset.checked_sub(header_len))
+        .filter(|start| *start <= end && (*start == 0 || records[*start - 1] == 0));
+
+    let changes = match start {
+        None => Changes::Everything,
+        Some(start) => {
+            let mut paths: Vec<BString> = records[start..end]
+                .split(|byte| *byte == 0)
+                .filter(|path| !path.is_empty())
+                .map(Into::into)
+                .collect();
+            if paths.iter().any(|path| path == EVERYTHING) {
+                Changes::Everything
+            } else {
+                paths.sort();
+                paths.dedup();
+                Changes::Paths(paths)
+            }
+        }
+    };
+    Query {
+        token: format!("{TOKEN_PREFIX}{session}:{}", header_len + end),
+        changes,
+    }
+}
+
+/// Return `true` if a monitor keeps the journal in `dir`.
+fn is_monitored(dir: &Path) -> Result<bool> {
+    let lock = match File::open(dir.join(LOCK_FILE)) {
+        Ok(lock) => lock,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err.into()),
+    };
+    match lock.try_lock_shared() {
+        // Our lock is released when `lock` is dropped.
+        Ok(()) => Ok(false),
+        Err(TryLockError::WouldBlock) => Ok(true),
+        Err(TryLockError::Error(err)) => Err(err.into()),
+    }
+}
+
+/// Wait for up to `timeout` until the monitor of the repository at `git_dir` recorded all changes made
+/// before this call, and return `true` if it did.
+///
+/// This works by creating a cookie file that the monitor removes once it sees it, which happens only
+/// after it recorded the changes it saw before. Return `false` right away if no monitor is running.
+pub fn sync(git_dir: &Path, timeout: Duration) -> Result<bool> {
+    if !is_monitored(&git_dir.join(JOURNAL_DIR))? {
+        return Ok(false);
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let cookie = git_dir.join(format!("{COOKIE_PREFIX}{}-{nanos:x}", std::process::id()));
+    File::create(&cookie)?;
+    let started = Instant::now();
+    while cookie.symlink_metadata().is_ok() {
+        if started.elapsed() > timeout {
+            std::fs::remove_file(&cookie).ok();
+            return Ok(false);
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    Ok(true)
+}
+
+/// Answer a query of Git's fsmonitor hook protocol `version` about changes since `token` in the
+/// worktree of the repository at `git_dir`, and write the response to `out`.
+///
+/// Only version 2 is supported, whose response is the new token followed by the changed paths, each
+/// terminated by a NUL byte, with `/` standing for everything.
+pub fn write_hook_response(
+    git_dir: &Path,
+    version: u32,
+    token: &str,
+    out: &mut dyn std::io::Write,
+) -> Result<()> {
+    if version != 2 {
+        bail!("fsmonitor hook protocol version {version} isn't supported, only version 2 is");
+    }
+    let query = if sync(git_dir, HOOK_SYNC_TIMEOUT)? {
+        query(git_dir, token)?
+    } else {
+        // Recent changes may be missing, so only hand out the latest token.
+        query(git_dir, "")?
+    };
+    out.write_all(query.token.as_bytes())?;
+    out.write_all(b"\0")?;
+    match query.changes {
+        Changes::Everything => {
+            out.write_all(EVERYTHING)?;
+            out.write_all(b"\0")?;
+        }
+        Changes::Paths(paths) => {
+            for path in paths {
+                out.write_all(&path)?;
+                out.write_all(b"\0")?;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(paths: &[&str]) -> Changes {
+        Changes::Paths(paths.iter().map(|path| (*path).into()).collect())
+    }
+
+    #[test]
+    fn changes_since_token() {
+        let journal = b"session\na\0dir/b\0a\0";
+        let initial = query_journal(&journal[..8], "");
+        assert_eq!(initial.token, "gitbutler:session:8");
+        assert_eq!(
+            initial.changes,
+            Changes::Everything,
+            "the empty token is unknown"
+        );
+
+        let all = query_journal(journal, &initial.token);
+        assert_eq!(all.token, "gitbutler:session:18");
+        assert_eq!(all.changes, paths(&["a", "dir/b"]));
+
+        let latest = query_journal(journal, "gitbutler:session:10");
+        assert_eq!(latest.changes, paths(&["a", "dir/b"]));
+        let latest = query_journal(journal, "gitbutler:session:16");
+        assert_eq!(latest.changes, paths(&["a"]));
+        assert_eq!(
+            query_journal(journal, &all.token).changes,
+            paths(&[]),
+            "nothing changed since the latest token"
+        );
+    }
+
+    #[test]
+    fn incomplete_records_are_not_handed_out() {
+        let query = query_journal(b"session\na\0dir/", "gitbutler:session:8");
+        assert_eq!(query.token, "gitbutler:session:10");
+        assert_eq!(query.changes, paths(&["a"]));
+    }
+
+    #[test]
+    fn everything_changed_for_unknown_tokens() {
+        let journal = b"session\na\0b\0";
+        for token in [
+            "",
+            "gitbutler:other:8",
+            "gitbutler:session:9",
+            "gitbutler:session:13",
+            "gitbutler:session:7",
+            "gitbutler:session:x",
+            UNMONITORED_TOKEN,
+        ] {
+            let query = query_journal(journal, token);
+            assert_eq!(query.changes, Changes::Everything, "{token}");
+            assert_eq!(query.token, "gitbutler:session:12");
+        }
+    }
+
+    #[test]
+    fn everything_changed_if_recorded() {
+        let query = query_journal(b"session\na\0/\0b\0", "gitbutler:session:8");
+        assert_eq!(query.changes, Changes::Everything);
+    }
+}
@@ -4,6 +4,8 @@
 
 mod watch_plan;
 
+pub mod fsmonitor;
+
 mod events;
 pub use events::InternalEvent;
 
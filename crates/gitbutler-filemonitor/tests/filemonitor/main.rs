@@ -6,7 +6,7 @@ mod spawn {
     };
 
     use but_project_handle::{ProjectHandle, ProjectHandleOrLegacyProjectId};
-    use gitbutler_filemonitor::{InternalEvent, WatchMode};
+    use gitbutler_filemonitor::{InternalEvent, WatchMode, fsmonitor};
     use tokio::sync::mpsc;
 
     async fn expect_matching_event(
@@ -115,6 +115,77 @@ mod spawn {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn in_process_fetch_is_seen_as_fetch_head_change() -> anyhow::Result<()> {
+        let generous_timeout_for_ci = Duration::from_secs(10);
+        let tmp = but_testsupport::gix_testtools::tempfile::TempDir::new()?;
+        but_testsupport::invoke_bash_at_dir(
+            r#"
+git init -q --bare -b main remote.git
+git clone -q remote.git local 2>/dev/null
+(cd local && git commit -q --allow-empty -m first && git push -q origin main)
+"#,
+            tmp.path(),
+        );
+        let workdir = gix::path::realpath(tmp.path().join("local"))?;
+        let project_id =
+            ProjectHandleOrLegacyProjectId::ProjectHandle(ProjectHandle::from_path(&workdir)?);
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let monitor =
+            gitbutler_filemonitor::spawn(project_id.clone(), &workdir, tx, WatchMode::Modern)?;
+
+        let fetch_dir = workdir.clone();
+        tokio::task::spawn_blocking(move || {
+            gitbutler_git::in_process::fetch(&fetch_dir, "origin", None, &mut |_| {})
+        })
+        .await??;
+        monitor.flush()?;
+        // The watcher turns this into a `GitFetch` event.
+        expect_matching_event(&mut rx, generous_timeout_for_ci, |event| match event {
+            InternalEvent::GitFilesChange(id, paths) => {
+                *id == project_id && contains_path(paths, Path::new("FETCH_HEAD"))
+            }
+            _ => false,
+        })
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn journal_records_changes_for_fsmonitor_queries() -> anyhow::Result<()> {
+        let generous_timeout_for_ci = Duration::from_secs(10);
+        let (repo, _tmp) = but_testsupport::writable_scenario("watch-plan-rename-dir");
+        let workdir = repo.workdir().expect("non-bare").to_owned();
+        let git_dir = repo.path().to_owned();
+        let project_id =
+            ProjectHandleOrLegacyProjectId::ProjectHandle(ProjectHandle::from_path(&workdir)?);
+
+        let before = fsmonitor::query(&git_dir, "")?;
+        assert_eq!(
+            before.changes,
+            fsmonitor::Changes::Everything,
+            "without a monitor, anything may have changed"
+        );
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let _monitor = gitbutler_filemonitor::spawn(project_id, &workdir, tx, WatchMode::Modern)?;
+        let start = fsmonitor::query(&git_dir, "")?;
+
+        std::fs::write(workdir.join("new-file"), "hi")?;
+        let (caught_up, changed) = tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
+            let caught_up = fsmonitor::sync(&git_dir, generous_timeout_for_ci)?;
+            Ok((caught_up, fsmonitor::query(&git_dir, &start.token)?))
+        })
+        .await??;
+        assert!(caught_up, "the monitor removes the cookie once it saw it");
+        assert_eq!(
+            changed.changes,
+            fsmonitor::Changes::Paths(vec!["new-file".into()])
+        );
+        Ok(())
+    }
 }
 
 mod watch_mode {
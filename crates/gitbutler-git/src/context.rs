@@ -146,6 +146,30 @@ pub fn fetch_with_askpass(
     repo_path: impl AsRef<Path>,
     remote_name: &str,
     action: Option<String>,
+) -> Result<()> {
+    fetch_refspecs_with_askpass(repo_path, remote_name, None, action)
+}
+
+/// Like [`fetch_with_askpass()`], but fetch `refspecs` instead of the configured fetch refspecs
+/// of `remote_name` if they are provided.
+pub fn fetch_refspecs_with_askpass(
+    repo_path: impl AsRef<Path>,
+    remote_name: &str,
+    refspecs: Option<Vec<String>>,
+    action: Option<String>,
+) -> Result<()> {
+    fetch_refspecs_with_progress(repo_path, remote_name, refspecs, action, trace_progress)
+}
+
+/// Like [`fetch_refspecs_with_askpass()`], but report each step of a fetch done
+/// [in-process](crate::in_process) to `progress`, which can forward it to a channel, for
+/// instance. Fetches with the Git CLI don't report progress.
+pub fn fetch_refspecs_with_progress(
+    repo_path: impl AsRef<Path>,
+    remote_name: &str,
+    refspecs: Option<Vec<String>>,
+    action: Option<String>,
+    mut progress: impl FnMut(crate::in_process::Progress) + Send + 'static,
 ) -> Result<()> {
     let on_prompt = if askpass::get_broker().is_some() {
         Some(move |prompt: String| handle_git_prompt_fetch(prompt, action.clone()))
@@ -155,17 +179,29 @@ pub fn fetch_with_askpass(
 
     let repo_path = repo_path.as_ref().to_owned();
     let remote = remote_name.to_owned();
-    let result = std::thread::spawn(move || -> Result<_> {
+    std::thread::spawn(move || -> Result<()> {
+        if crate::in_process::is_enabled() {
+            match crate::in_process::fetch(&repo_path, &remote, refspecs.clone(), &mut progress) {
+                Ok(_) => return Ok(()),
+                Err(err) if use_cli_instead(&err, on_prompt.is_some()) => {
+                    tracing::debug!(%err, "fetching with the Git CLI instead");
+                }
+                Err(err) => return Err(map_in_process_error(err)),
+            }
+        }
         let runtime = tokio::runtime::Runtime::new().context(
             but_error::Context::new("failed to initialize async runtime for git fetch")
                 .with_code(Code::Unknown),
         )?;
-        Ok(runtime.block_on(crate::fetch(
-            repo_path,
-            crate::tokio::TokioExecutor,
-            &remote,
-            on_prompt,
-        )))
+        runtime
+            .block_on(crate::fetch_refspecs(
+                repo_path,
+                crate::tokio::TokioExecutor,
+                &remote,
+                refspecs,
+                on_prompt,
+            ))
+            .map_err(map_needs_authorization)
     })
     .join()
     .map_err(|panic| {
@@ -180,13 +216,44 @@ pub fn fetch_with_askpass(
         anyhow!("git fetch worker thread panicked: {reason}").context(
             but_error::Context::new("git fetch failed unexpectedly").with_code(Code::Unknown),
         )
-    })??;
-    result.map_err(map_needs_authorization)
+    })?
 }
 
 /// The concrete error type produced by fetch/push through the tokio executor.
 type GitError = crate::Error<crate::repository::Error<crate::tokio::TokioExecutor>>;
 
+/// The error type produced by fetch/push through [`crate::in_process`].
+type InProcessError = crate::Error<crate::in_process::Error>;
+
+/// Return `true` if the Git CLI should be tried after the in-process transport failed with
+/// `err`, which is also the case for missing credentials if the CLI can prompt for them.
+fn use_cli_instead(err: &InProcessError, can_prompt: bool) -> bool {
+    match err {
+        crate::Error::Backend(crate::in_process::Error::Unsupported(_)) => true,
+        crate::Error::AuthorizationFailed(_) => can_prompt,
+        _ => false,
+    }
+}
+
+fn trace_progress(progress: crate::in_process::Progress) {
+    tracing::debug!(?progress, "in-process git transfer");
+}
+
+/// Like [`map_needs_authorization()`], but for errors of the in-process transport.
+fn map_in_process_error(err: InProcessError) -> anyhow::Error {
+    let context = match &err {
+        crate::Error::AuthorizationFailed(crate::in_process::Error::Credentials {
+            url, ..
+        }) => but_error::Context::new(format!(
+            "{}\n\nOriginal error: {err}",
+            missing_credentials_advice(url)
+        ))
+        .with_code(Code::ProjectGitAuth),
+        _ => return err.into(),
+    };
+    anyhow::Error::from(err).context(context)
+}
+
 /// Convert the error into an anyhow error, giving `NeedsAuthorization` failures a message that
 /// tells the user what to do about it (see [`needs_authorization_message`]); every other error
 /// converts as-is.
@@ -213,17 +280,22 @@ fn needs_authorization_message(prompt: &str, original_error: impl std::fmt::Disp
         .or_else(|| prompt.strip_prefix("Password for "))
         .map(|url| url.trim_end_matches(':').trim().trim_matches('\''));
     let advice = match credential_url {
-        Some(url) => format!(
-            "Git couldn't obtain credentials for {url}. Configure a git credential helper (for example Git Credential Manager), or switch the remote to SSH."
-        ),
+        Some(url) => missing_credentials_advice(url),
         None => format!("Git asked for input and none was provided: {prompt}"),
     };
     format!("{advice}\n\nOriginal error: {original_error}")
 }
 
+fn missing_credentials_advice(url: &str) -> String {
+    format!(
+        "Git couldn't obtain credentials for {url}. Configure a git credential helper (for example Git Credential Manager), or switch the remote to SSH."
+    )
+}
+
 /// Push the given commit to the provided remote branch.
 ///
-/// Returns the stderr output of the Git executable if used.
+/// Returns the stderr output of the Git executable if used, or the messages of the remote
+/// if the push was done [in-process](crate::in_process).
 #[allow(clippy::too_many_arguments)]
 pub fn push_with_askpass<B>(
     repo: &gix::Repository,
@@ -235,6 +307,38 @@ pub fn push_with_askpass<B>(
     askpass_broker: Option<Option<but_core::Id<'S'>>>,
     push_opts: Vec<String>,
 ) -> Result<String>
+where
+    B: TryInto<gix::refs::FullName>,
+    B::Error: Into<anyhow::Error>,
+{
+    push_with_progress(
+        repo,
+        head,
+        branch,
+        with_force,
+        force_push_protection,
+        refspec,
+        askpass_broker,
+        push_opts,
+        trace_progress,
+    )
+}
+
+/// Like [`push_with_askpass()`], but report each step of a push done
+/// [in-process](crate::in_process) to `progress`, which can forward it to a channel, for
+/// instance. Pushes with the Git CLI don't report progress.
+#[allow(clippy::too_many_arguments)]
+pub fn push_with_progress<B>(
+    repo: &gix::Repository,
+    head: gix::ObjectId,
+    branch: B,
+    with_force: bool,
+    force_push_protection: bool,
+    refspec: Option<String>,
+    askpass_broker: Option<Option<but_core::Id<'S'>>>,
+    push_opts: Vec<String>,
+    mut progress: impl FnMut(crate::in_process::Progress) + Send + 'static,
+) -> Result<String>
 where
     B: TryInto<gix::refs::FullName>,
     B::Error: Into<anyhow::Error>,
@@ -250,25 +354,47 @@ where
     };
 
     let repo_path = repo.git_dir().to_owned();
-    let result = std::thread::spawn(move || -> Result<_> {
-        let runtime = tokio::runtime::Runtime::new().context(
-            but_error::Context::new("failed to initialize async runtime for git push")
-                .with_code(Code::Unknown),
-        )?;
+    std::thread::spawn(move || -> Result<String> {
         let refspec = crate::RefSpec::parse(&refspec).context(
             but_error::Context::new(format!("failed to parse git push refspec `{refspec}`"))
                 .with_code(Code::Validation),
         )?;
-        Ok(runtime.block_on(crate::push(
-            repo_path,
-            crate::tokio::TokioExecutor,
-            &remote,
-            refspec,
-            with_force,
-            force_push_protection,
-            on_prompt,
-            push_opts,
-        )))
+        if crate::in_process::is_enabled() {
+            match crate::in_process::push(
+                &repo_path,
+                &remote,
+                std::slice::from_ref(&refspec),
+                with_force,
+                force_push_protection,
+                &push_opts,
+                &mut progress,
+            ) {
+                Ok(outcome) => {
+                    tracing::debug!(updates = ?outcome.updates, "pushed in-process");
+                    return Ok(outcome.remote_messages);
+                }
+                Err(err) if use_cli_instead(&err, on_prompt.is_some()) => {
+                    tracing::debug!(%err, "pushing with the Git CLI instead");
+                }
+                Err(err) => return map_push_error(err, map_in_process_error),
+            }
+        }
+        let runtime = tokio::runtime::Runtime::new().context(
+            but_error::Context::new("failed to initialize async runtime for git push")
+                .with_code(Code::Unknown),
+        )?;
+        runtime
+            .block_on(crate::push(
+                repo_path,
+                crate::tokio::TokioExecutor,
+                &remote,
+                refspec,
+                with_force,
+                force_push_protection,
+                on_prompt,
+                push_opts,
+            ))
+            .or_else(|err| map_push_error(err, map_needs_authorization))
     })
     .join()
     .map_err(|panic| {
@@ -283,22 +409,30 @@ where
         anyhow!("git push worker thread panicked: {reason}").context(
             but_error::Context::new("git push failed unexpectedly").with_code(Code::Unknown),
         )
-    })??;
-    match result {
-            Ok(stderr) => Ok(stderr),
-            Err(err) => match err {
-                crate::Error::ForcePushProtection(e) => Err(anyhow!(
-                    "The force push was blocked because the remote branch contains commits that would be overwritten.\n\n{e}"
-                )
-                .context(Code::GitForcePushProtection)),
-                crate::Error::GerritNoNewChanges(_) => {
-                    // Treat "no new changes" as success for Gerrit.
-                    Ok(String::new())
-                }
-                crate::Error::NonFastForward(_) => Err(err).context(Code::GitNonFastForward),
-                _ => Err(map_needs_authorization(err)),
-            },
+    })?
+}
+
+/// Give push failures that callers handle specifically their error code, and convert all
+/// others with `map_other`.
+fn map_push_error<BE>(
+    err: crate::Error<BE>,
+    map_other: impl FnOnce(crate::Error<BE>) -> anyhow::Error,
+) -> Result<String>
+where
+    BE: std::error::Error + Send + Sync + 'static,
+{
+    match err {
+        crate::Error::ForcePushProtection(e) => Err(anyhow!(
+            "The force push was blocked because the remote branch contains commits that would be overwritten.\n\n{e}"
+        )
+        .context(Code::GitForcePushProtection)),
+        crate::Error::GerritNoNewChanges(_) => {
+            // Treat "no new changes" as success for Gerrit.
+            Ok(String::new())
         }
+        crate::Error::NonFastForward(_) => Err(err).context(Code::GitNonFastForward),
+        _ => Err(map_other(err)),
+    }
 }
 
 fn serialize_branch_to_remote<S>(
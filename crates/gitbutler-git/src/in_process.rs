@@ -0,0 +1,1099 @@
+//! Fetch and push without spawning the Git CLI, using `gix` in this process.
+//!
+//! Fetches and pushes work for `file://`, `git://` and HTTP(S) remotes, and use the credential
+//! helpers configured in Git. Pushes talk to `receive-pack` on the remote like `git push` does,
+//! so the remote runs its hooks as usual. Everything else yields [`Error::Unsupported`], in which
+//! case callers are expected to fall back to the Git CLI in [`crate::fetch()`] and
+//! [`crate::push()`].
+use std::{
+    collections::HashSet,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use gix::{
+    bstr::{BString, ByteSlice},
+    protocol::transport::{
+        Protocol, Service,
+        client::{ExtendedBufRead, MessageKind, ReadlineBufRead, TransportWithoutIO, WriteMode},
+        packetline::PacketLineRef,
+    },
+    refs::transaction::PreviousValue,
+    remote::{
+        Direction,
+        fetch::{Mapping, SpecIndex},
+    },
+};
+
+use crate::RefSpec;
+
+/// The environment variable to select the Git CLI for all fetches and pushes, if set to `cli`.
+const BACKEND_ENV: &str = "GITBUTLER_GIT_BACKEND";
+
+/// Errors specific to the in-process transport.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The operation needs the Git CLI, for the reason given.
+    #[error("not supported in-process: {0}")]
+    Unsupported(String),
+    /// The repository could not be opened.
+    #[error("failed to open repository at `{}`: {source}", path.display())]
+    RepositoryOpen {
+        /// The repository path that could not be opened.
+        path: PathBuf,
+        /// The repository open error.
+        #[source]
+        source: Box<gix::open::Error>,
+    },
+    /// The remote configuration could not be read.
+    #[error("failed to read configuration for remote `{remote}`: {source}")]
+    RemoteConfiguration {
+        /// The remote whose configuration could not be read.
+        remote: String,
+        /// The configuration lookup error.
+        #[source]
+        source: gix::remote::find::existing::Error,
+    },
+    /// No credential helper could provide credentials for the remote.
+    #[error("no credentials for {url}: {source}")]
+    Credentials {
+        /// The URL credentials were requested for.
+        url: String,
+        /// The error of the operation that required them.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// Talking to the remote failed.
+    #[error("failed to fetch from `{remote}`: {source}")]
+    Fetch {
+        /// The remote that was fetched from.
+        remote: String,
+        /// The underlying error.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// Sending objects or reference updates to the remote failed.
+    #[error("failed to push to `{remote}`: {source}")]
+    Push {
+        /// The remote that was pushed to.
+        remote: String,
+        /// The underlying error.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// A reference update was rejected.
+    #[error("[rejected] {refname} ({reason})")]
+    Rejected {
+        /// The reference that wasn't updated.
+        refname: String,
+        /// Why the update was rejected.
+        reason: String,
+    },
+}
+
+/// A step of a fetch or push, as reported to the `progress` callback of [`fetch()`] and [`push()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    /// A connection to the remote of the given name is established.
+    Connecting {
+        /// The name of the remote.
+        remote: String,
+    },
+    /// The references of the remote were listed.
+    RefsListed {
+        /// The amount of references the remote advertised.
+        remote_refs: usize,
+    },
+    /// A pack was received from the remote.
+    PackReceived {
+        /// The amount of objects in the pack.
+        objects: usize,
+    },
+    /// A pack with the objects the remote doesn't have yet was sent to it.
+    PackSent {
+        /// The amount of objects in the pack.
+        objects: usize,
+    },
+    /// References were updated.
+    RefsUpdated {
+        /// The amount of references that were changed.
+        refs: usize,
+    },
+}
+
+/// The result of [`fetch()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchOutcome {
+    /// The local references that were created or changed.
+    pub updated_refs: Vec<gix::refs::FullName>,
+    /// The amount of objects that were received.
+    pub objects: usize,
+}
+
+/// How a remote reference was changed by [`push()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefUpdateStatus {
+    /// The reference already pointed to the pushed commit.
+    UpToDate,
+    /// The reference didn't exist before.
+    Created,
+    /// The reference moved to a descendant of its previous target.
+    FastForward,
+    /// The reference was force-updated.
+    Forced,
+    /// The reference was deleted.
+    Deleted,
+}
+
+/// The update of a single remote reference by [`push()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefUpdate {
+    /// The name of the reference in the remote.
+    pub remote_ref: gix::refs::FullName,
+    /// The local remote-tracking reference that was updated along with it, if there is one.
+    pub tracking_ref: Option<gix::refs::FullName>,
+    /// The previous target of the remote reference, if it existed.
+    pub before: Option<gix::ObjectId>,
+    /// The new target of the remote reference, or `None` if it was deleted.
+    pub after: Option<gix::ObjectId>,
+    /// How the reference was changed.
+    pub status: RefUpdateStatus,
+}
+
+/// The result of [`push()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushOutcome {
+    /// How each pushed reference was changed, in the order of the refspecs.
+    pub updates: Vec<RefUpdate>,
+    /// The messages the remote sent along, with each line prefixed with `remote: ` like
+    /// `git push` prints them.
+    pub remote_messages: String,
+}
+
+/// Return `true` unless the Git CLI was selected for fetches and pushes by setting
+/// `GITBUTLER_GIT_BACKEND=cli`.
+pub fn is_enabled() -> bool {
+    std::env::var_os(BACKEND_ENV).is_none_or(|backend| backend != "cli")
+}
+
+/// Fetch from `remote_name` in the repository at `repo_path`, using `refspecs` if given or the
+/// configured fetch refspecs otherwise, and report each step to `progress`.
+///
+/// SSH remotes are [unsupported](Error::Unsupported) as the Git CLI configures SSH and answers
+/// its prompts.
+pub fn fetch(
+    repo_path: impl AsRef<Path>,
+    remote_name: &str,
+    refspecs: Option<Vec<String>>,
+    progress: &mut dyn FnMut(Progress),
+) -> Result<FetchOutcome, crate::Error<Error>> {
+    let repo = open(repo_path.as_ref())?;
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|source| remote_error(remote_name, source))?;
+    let url = remote
+        .url(Direction::Fetch)
+        .ok_or_else(|| Error::Unsupported(format!("remote `{remote_name}` has no URL")))?
+        .to_owned();
+    if url.scheme == gix::url::Scheme::Ssh {
+        return Err(Error::Unsupported("SSH remotes".into()).into());
+    }
+
+    let explicit_refspecs = refspecs.is_some();
+    let refspecs = match refspecs {
+        Some(refspecs) => refspecs,
+        None => crate::repository::configured_fetch_refspecs(&repo, remote_name)
+            .map_err(|source| remote_error(remote_name, source))?,
+    };
+    let fetch_error = |source: Box<dyn std::error::Error + Send + Sync>| Error::Fetch {
+        remote: remote_name.to_owned(),
+        source,
+    };
+    remote
+        .replace_refspecs(refspecs.iter().map(String::as_str), Direction::Fetch)
+        .map_err(|err| fetch_error(err.into()))?;
+
+    progress(Progress::Connecting {
+        remote: remote_name.to_owned(),
+    });
+    let missing_credentials = Arc::new(AtomicBool::new(false));
+    let authenticate = credentials(&repo, &url, missing_credentials.clone())
+        .map_err(|err| fetch_error(err.into()))?;
+    let connection = remote
+        .connect(Direction::Fetch)
+        .map_err(|err| fetch_error(err.into()))?
+        .with_credentials(authenticate);
+    let transport_error = |source: Box<dyn std::error::Error + Send + Sync>| {
+        connection_error(&missing_credentials, &url, source, fetch_error)
+    };
+
+    let prepare = connection
+        .prepare_fetch(
+            gix::progress::Discard,
+            gix::remote::ref_map::Options::default(),
+        )
+        .map_err(|err| transport_error(err.into()))?;
+    let remote_refs = &prepare.ref_map().remote_refs;
+    progress(Progress::RefsListed {
+        remote_refs: remote_refs.len(),
+    });
+    if explicit_refspecs
+        && let Some(source) = refspecs
+            .iter()
+            .find_map(|spec| missing_source(spec, remote_refs))
+    {
+        return Err(crate::Error::RefNotFound(source.to_owned()));
+    }
+
+    let outcome = prepare
+        .receive(gix::progress::Discard, &AtomicBool::new(false))
+        .map_err(|err| transport_error(err.into()))?;
+    let (objects, update_refs) = match outcome.status {
+        gix::remote::fetch::Status::NoPackReceived { update_refs, .. } => (0, update_refs),
+        gix::remote::fetch::Status::Change {
+            write_pack_bundle,
+            update_refs,
+            ..
+        } => {
+            let objects = write_pack_bundle.index.num_objects as usize;
+            progress(Progress::PackReceived { objects });
+            (objects, update_refs)
+        }
+    };
+
+    // Updates are listed in the order of the mappings they were made for.
+    if let Some(mapping) = update_refs
+        .updates
+        .iter()
+        .zip(&outcome.ref_map.mappings)
+        .find_map(|(update, mapping)| {
+            matches!(
+                update.mode,
+                gix::remote::fetch::refs::update::Mode::RejectedNonFastForward
+            )
+            .then_some(mapping)
+        })
+    {
+        let refname = mapping
+            .local
+            .as_ref()
+            .map_or_else(|| remote_name.to_owned(), ToString::to_string);
+        return Err(Error::Rejected {
+            refname,
+            reason: "non-fast-forward".into(),
+        }
+        .into());
+    }
+
+    write_fetch_head(
+        &repo,
+        remote_name,
+        &url,
+        &outcome.ref_map.mappings,
+        explicit_refspecs.then_some(refspecs.as_slice()),
+    )
+    .map_err(|err| fetch_error(err.into()))?;
+
+    let updated_refs: Vec<_> = update_refs
+        .edits
+        .into_iter()
+        .map(|edit| edit.name)
+        .collect();
+    progress(Progress::RefsUpdated {
+        refs: updated_refs.len(),
+    });
+    Ok(FetchOutcome {
+        updated_refs,
+        objects,
+    })
+}
+
+/// Push each of `refspecs` to `remote_name` from the repository at `repo_path`, report each
+/// step to `progress`, and return how each remote reference was changed.
+///
+/// Non-fast-forward updates are rejected unless `force` is set or the refspec starts with `+`.
+/// With `force_push_protection`, forced updates are rejected if the remote reference isn't where
+/// its remote-tracking reference says it is, like `--force-with-lease` does.
+/// SSH remotes, SHA-256 repositories, remotes without support for the `push_opts`, and
+/// force-pushes that need the reflog for `--force-if-includes` are
+/// [unsupported](Error::Unsupported).
+///
+/// The remote updates each reference atomically, and the matching remote-tracking references are
+/// updated afterwards. Like with `git push`, the updates of multiple refspecs are not atomic as a
+/// whole.
+pub fn push(
+    repo_path: impl AsRef<Path>,
+    remote_name: &str,
+    refspecs: &[RefSpec],
+    force: bool,
+    force_push_protection: bool,
+    push_opts: &[String],
+    progress: &mut dyn FnMut(Progress),
+) -> Result<PushOutcome, crate::Error<Error>> {
+    let repo = open(repo_path.as_ref())?;
+    let remote = repo
+        .find_remote(remote_name)
+        .map_err(|source| remote_error(remote_name, source))?;
+    let url = remote
+        .url(Direction::Push)
+        .ok_or_else(|| Error::Unsupported(format!("remote `{remote_name}` has no URL")))?
+        .to_owned();
+    if url.scheme == gix::url::Scheme::Ssh {
+        return Err(Error::Unsupported("SSH remotes".into()).into());
+    }
+    // The remote assumes SHA-1 unless told otherwise.
+    if repo.object_hash() != gix::hash::Kind::Sha1 {
+        return Err(Error::Unsupported(format!("pushing {:?} objects", repo.object_hash())).into());
+    }
+    let push_error = |source: Box<dyn std::error::Error + Send + Sync>| Error::Push {
+        remote: remote_name.to_owned(),
+        source,
+    };
+
+    // Resolve the local side first so nothing is sent if a refspec can't be pushed.
+    let mut pushes = Vec::with_capacity(refspecs.len());
+    for refspec in refspecs {
+        let Some(destination) = refspec.destination.as_deref().or(refspec.source.as_deref()) else {
+            return Err(
+                push_error(format!("refspec `{refspec}` has no destination").into()).into(),
+            );
+        };
+        let remote_ref = remote_refname(destination).map_err(|err| push_error(err.into()))?;
+        let after = match refspec.source.as_deref() {
+            Some(source) => {
+                let id = repo
+                    .rev_parse_single(source)
+                    .map_err(|_| crate::Error::RefNotFound(source.to_owned()))?;
+                let kind = id.object().map_err(|err| push_error(err.into()))?.kind;
+                if kind != gix::object::Kind::Commit {
+                    return Err(Error::Unsupported(format!("pushing {kind} objects")).into());
+                }
+                Some(id.detach())
+            }
+            None => None,
+        };
+        pushes.push((refspec, remote_ref, after));
+    }
+
+    progress(Progress::Connecting {
+        remote: remote_name.to_owned(),
+    });
+    let missing_credentials = Arc::new(AtomicBool::new(false));
+    let authenticate = credentials(&repo, &url, missing_credentials.clone())
+        .map_err(|err| push_error(err.into()))?;
+    let mut connection = remote
+        .connect(Direction::Push)
+        .map_err(|err| push_error(err.into()))?;
+    let transport = connection.transport_mut();
+    let handshake = gix::protocol::handshake(
+        &mut *transport,
+        Service::ReceivePack,
+        authenticate,
+        Vec::new(),
+        &mut gix::progress::Discard,
+    )
+    .map_err(|err| connection_error(&missing_credentials, &url, err.into(), push_error))?;
+    let remote_refs = handshake.refs.unwrap_or_default();
+    progress(Progress::RefsListed {
+        remote_refs: remote_refs.len(),
+    });
+    let capabilities = &handshake.capabilities;
+    if !capabilities.contains("report-status") {
+        return Err(Error::Unsupported("remotes that don't report the push status".into()).into());
+    }
+    if !push_opts.is_empty() && !capabilities.contains("push-options") {
+        return Err(Error::Unsupported("push options for this remote".into()).into());
+    }
+
+    let mut updates = Vec::with_capacity(pushes.len());
+    for (refspec, remote_ref, after) in pushes {
+        let before = remote_refs.iter().find_map(|remote_ref_info| {
+            let (name, target, _) = remote_ref_info.unpack();
+            (name == remote_ref.as_bstr())
+                .then(|| target.map(ToOwned::to_owned))
+                .flatten()
+        });
+        let status = match (before, after) {
+            (None, None) => return Err(crate::Error::RefNotFound(remote_ref.to_string())),
+            (before, after) if before == after => RefUpdateStatus::UpToDate,
+            (Some(_), None) => RefUpdateStatus::Deleted,
+            (None, Some(_)) => RefUpdateStatus::Created,
+            (Some(before), Some(after)) => {
+                if is_ancestor(&repo, before, after) {
+                    RefUpdateStatus::FastForward
+                } else if force || refspec.update_non_fastforward {
+                    RefUpdateStatus::Forced
+                } else {
+                    let reason = if repo.has_object(before) {
+                        "non-fast-forward"
+                    } else {
+                        "fetch first"
+                    };
+                    return Err(crate::Error::NonFastForward(Error::Rejected {
+                        refname: remote_ref.to_string(),
+                        reason: reason.into(),
+                    }));
+                }
+            }
+        };
+        if force && force_push_protection && status != RefUpdateStatus::UpToDate {
+            check_lease(&repo, remote_name, remote_ref.as_ref(), before)?;
+        }
+        updates.push(RefUpdate {
+            tracking_ref: tracking_refname(remote_name, remote_ref.as_ref()),
+            remote_ref,
+            before,
+            after,
+            status,
+        });
+    }
+
+    let commands: Vec<_> = updates
+        .iter()
+        .filter(|update| update.status != RefUpdateStatus::UpToDate)
+        .collect();
+    let mut request = transport
+        .request(WriteMode::Binary, MessageKind::Flush, false)
+        .map_err(|err| push_error(err.into()))?;
+    if commands.is_empty() {
+        // Tell the remote that there is nothing to update, and we are done.
+        request
+            .write_message(MessageKind::Flush)
+            .map_err(|err| push_error(err.into()))?;
+        return Ok(PushOutcome {
+            updates,
+            remote_messages: String::new(),
+        });
+    }
+
+    let side_band = capabilities.contains("side-band-64k");
+    let mut requested = vec!["report-status"];
+    if side_band {
+        requested.push("side-band-64k");
+    }
+    // Like `git push --quiet`, only messages of the remote's hooks should be sent back.
+    if capabilities.contains("quiet") {
+        requested.push("quiet");
+    }
+    if !push_opts.is_empty() {
+        requested.push("push-options");
+    }
+    let remote_tips: Vec<_> = remote_refs
+        .iter()
+        .filter_map(|remote_ref| {
+            let (_, target, peeled) = remote_ref.unpack();
+            peeled.or(target).map(ToOwned::to_owned)
+        })
+        .collect();
+    // The request is written verbatim, as only the commands are sent as packet lines.
+    let (mut writer, mut reader) = request.into_parts();
+    let objects = write_push_request(
+        &repo,
+        &mut writer,
+        &commands,
+        &requested.join(" "),
+        push_opts,
+        &remote_tips,
+    )
+    .map_err(|err| push_error(err.into()))?;
+    // The remote may only answer once the request is complete.
+    drop(writer);
+    if objects > 0 {
+        progress(Progress::PackSent { objects });
+    }
+
+    reader.reset(Protocol::V1);
+    let mut lines = Vec::new();
+    while let Some(line) = reader.readline() {
+        let line = line
+            .map_err(|err| push_error(err.into()))?
+            .map_err(|err| push_error(err.into()))?;
+        if let PacketLineRef::Data(data) = line {
+            lines.push(data.to_owned());
+        }
+    }
+    let (report, remote_messages) =
+        demultiplex(lines, side_band).map_err(|err| push_error(err.into()))?;
+    let mut report = report.iter().map(String::as_str);
+    match report.next() {
+        Some("unpack ok") => {}
+        Some(unpack) => {
+            let reason = unpack.strip_prefix("unpack ").unwrap_or(unpack);
+            return Err(push_error(
+                format!("the remote failed to unpack the objects: {reason}").into(),
+            )
+            .into());
+        }
+        None => return Err(push_error("the remote didn't report the push status".into()).into()),
+    }
+    let mut rejection = None;
+    let mut updated = HashSet::new();
+    for line in report {
+        if let Some(refname) = line.strip_prefix("ok ") {
+            updated.insert(refname);
+        } else if let Some(rejected) = line.strip_prefix("ng ")
+            && rejection.is_none()
+        {
+            let (refname, reason) = rejected.split_once(' ').unwrap_or((rejected, ""));
+            rejection = Some(Error::Rejected {
+                refname: refname.to_owned(),
+                reason: reason.to_owned(),
+            });
+        }
+    }
+
+    // Like `git push`, remember the updates that succeeded even if others were rejected.
+    let mut tracking_refs = 0;
+    for update in &commands {
+        if let Some(tracking_ref) = &update.tracking_ref
+            && updated.contains(update.remote_ref.to_string().as_str())
+        {
+            update_tracking_ref(&repo, tracking_ref.as_ref(), update.after)
+                .map_err(|err| push_error(err.into()))?;
+            tracking_refs += 1;
+        }
+    }
+    progress(Progress::RefsUpdated {
+        refs: tracking_refs,
+    });
+
+    if let Some(rejection) = rejection {
+        return Err(rejected_push_error(rejection));
+    }
+    if let Some(update) = commands
+        .iter()
+        .find(|update| !updated.contains(update.remote_ref.to_string().as_str()))
+    {
+        return Err(push_error(
+            format!(
+                "the remote didn't report the status of `{}`",
+                update.remote_ref
+            )
+            .into(),
+        )
+        .into());
+    }
+    Ok(PushOutcome {
+        updates,
+        remote_messages,
+    })
+}
+
+fn open(path: &Path) -> Result<gix::Repository, Error> {
+    gix::open(path).map_err(|source| Error::RepositoryOpen {
+        path: path.to_owned(),
+        source: Box::new(source),
+    })
+}
+
+fn remote_error(remote: &str, source: gix::remote::find::existing::Error) -> crate::Error<Error> {
+    let remote_not_found = matches!(source, gix::remote::find::existing::Error::NotFound { .. });
+    let err = Error::RemoteConfiguration {
+        remote: remote.to_owned(),
+        source,
+    };
+    if remote_not_found {
+        crate::Error::NoSuchRemote(remote.to_owned(), err)
+    } else {
+        err.into()
+    }
+}
+
+/// Return a function that asks the credential helpers configured for `url`, and sets
+/// `missing_credentials` if they couldn't provide any.
+fn credentials(
+    repo: &gix::Repository,
+    url: &gix::Url,
+    missing_credentials: Arc<AtomicBool>,
+) -> Result<
+    impl FnMut(gix::credentials::helper::Action) -> gix::credentials::protocol::Result + use<>,
+    Box<dyn std::error::Error + Send + Sync>,
+> {
+    let (mut helpers, _action, prompt) = repo.config_snapshot().credential_helpers(url.clone())?;
+    Ok(move |action| {
+        let is_get = matches!(action, gix::credentials::helper::Action::Get(_));
+        let outcome = helpers.invoke(action, prompt.clone());
+        if is_get && !matches!(outcome, Ok(Some(_))) {
+            missing_credentials.store(true, Ordering::SeqCst);
+        }
+        outcome
+    })
+}
+
+/// Turn `source`, an error of the connection to `url`, into an authorization failure if
+/// credentials were missing, as that most likely caused it, or into an error made by `error`.
+fn connection_error(
+    missing_credentials: &AtomicBool,
+    url: &gix::Url,
+    source: Box<dyn std::error::Error + Send + Sync>,
+    error: impl FnOnce(Box<dyn std::error::Error + Send + Sync>) -> Error,
+) -> crate::Error<Error> {
+    if missing_credentials.load(Ordering::SeqCst) {
+        crate::Error::AuthorizationFailed(Error::Credentials {
+            url: url.to_bstring().to_string(),
+            source,
+        })
+    } else {
+        error(source).into()
+    }
+}
+
+/// Return the source of `refspec` if it names a single reference that isn't among `remote_refs`.
+fn missing_source<'a>(
+    refspec: &'a str,
+    remote_refs: &[gix::protocol::handshake::Ref],
+) -> Option<&'a str> {
+    let source = refspec.trim_start_matches('+').split(':').next()?;
+    if source.is_empty()
+        || source.contains('*')
+        || gix::ObjectId::from_hex(source.as_bytes()).is_ok()
+    {
+        return None;
+    }
+    // The same lookup order `git rev-parse` uses to expand short names.
+    let candidates = [
+        source.to_owned(),
+        format!("refs/{source}"),
+        format!("refs/tags/{source}"),
+        format!("refs/heads/{source}"),
+        format!("refs/remotes/{source}"),
+        format!("refs/remotes/{source}/HEAD"),
+    ];
+    let found = remote_refs.iter().any(|remote_ref| {
+        let (name, _, _) = remote_ref.unpack();
+        candidates
+            .iter()
+            .any(|candidate| name == candidate.as_str())
+    });
+    (!found).then_some(source)
+}
+
+/// Turn the destination of a push refspec into a full reference name, assuming a branch
+/// for short names like `git push` does.
+fn remote_refname(destination: &str) -> Result<gix::refs::FullName, gix::refs::name::Error> {
+    if destination.starts_with("refs/") {
+        destination.try_into()
+    } else {
+        format!("refs/heads/{destination}").try_into()
+    }
+}
+
+/// Return the name of the remote-tracking branch `git push` updates along with `remote_ref`,
+/// if it is a branch.
+fn tracking_refname(
+    remote_name: &str,
+    remote_ref: &gix::refs::FullNameRef,
+) -> Option<gix::refs::FullName> {
+    let branch = remote_ref.as_bstr().strip_prefix(b"refs/heads/")?;
+    format!("refs/remotes/{remote_name}/{}", branch.as_bstr())
+        .try_into()
+        .ok()
+}
+
+/// Point `tracking_ref` at `after`, or delete it if `after` is `None`.
+fn update_tracking_ref(
+    repo: &gix::Repository,
+    tracking_ref: &gix::refs::FullNameRef,
+    after: Option<gix::ObjectId>,
+) -> anyhow::Result<()> {
+    match after {
+        Some(after) => {
+            repo.reference(tracking_ref, after, PreviousValue::Any, "update by push")?;
+        }
+        None => {
+            if let Some(reference) = repo.try_find_reference(tracking_ref)? {
+                reference.delete()?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_ancestor(repo: &gix::Repository, ancestor: gix::ObjectId, commit: gix::ObjectId) -> bool {
+    repo.has_object(ancestor)
+        && repo
+            .merge_base(ancestor, commit)
+            .is_ok_and(|base| base == ancestor)
+}
+
+/// Reject a forced update of `remote_ref` from `before` unless its remote-tracking branch
+/// also points to `before`, and the local branch of the same name contains it, like
+/// `git push --force-with-lease --force-if-includes` does.
+fn check_lease(
+    repo: &gix::Repository,
+    remote_name: &str,
+    remote_ref: &gix::refs::FullNameRef,
+    before: Option<gix::ObjectId>,
+) -> Result<(), crate::Error<Error>> {
+    let Some(tracking_ref) = tracking_refname(remote_name, remote_ref) else {
+        return Err(Error::Unsupported(format!("force-push protection for `{remote_ref}`")).into());
+    };
+    let reference_id = |name: &gix::refs::FullNameRef| {
+        repo.try_find_reference(name)
+            .map(|reference| {
+                reference.and_then(|reference| reference.try_id().map(|id| id.detach()))
+            })
+            .map_err(|err| Error::Push {
+                remote: remote_name.to_owned(),
+                source: err.into(),
+            })
+    };
+    if reference_id(tracking_ref.as_ref())? != before {
+        return Err(crate::Error::ForcePushProtection(Error::Rejected {
+            refname: remote_ref.to_string(),
+            reason: "stale info".into(),
+        }));
+    }
+    if let (Some(before), Some(local)) = (before, reference_id(remote_ref)?)
+        && !is_ancestor(repo, before, local)
+    {
+        // The remote commits may also have been integrated and rewritten since, which only
+        // the reflog the Git CLI checks can tell.
+        return Err(
+            Error::Unsupported("checking if the remote commits were integrated".into()).into(),
+        );
+    }
+    Ok(())
+}
+
+/// Classify a rejection reported by the remote like [`crate::push()`] does for the Git CLI.
+fn rejected_push_error(rejection: Error) -> crate::Error<Error> {
+    let reason = match &rejection {
+        Error::Rejected { reason, .. } => reason.as_str(),
+        _ => "",
+    };
+    if reason.contains("no new changes") {
+        crate::Error::GerritNoNewChanges(rejection)
+    } else if reason == "stale info" {
+        crate::Error::ForcePushProtection(rejection)
+    } else if reason.contains("non-fast-forward") || reason.contains("fetch first") {
+        crate::Error::NonFastForward(rejection)
+    } else {
+        rejection.into()
+    }
+}
+
+/// Write the `commands` with the `capabilities` to request, and the `push_opts` to `out`,
+/// followed by a pack with the objects the remote doesn't have according to `remote_tips`.
+/// Return the amount of objects in the pack.
+fn write_push_request(
+    repo: &gix::Repository,
+    out: &mut dyn Write,
+    commands: &[&RefUpdate],
+    capabilities: &str,
+    push_opts: &[String],
+    remote_tips: &[gix::ObjectId],
+) -> anyhow::Result<usize> {
+    let null_id = repo.object_hash().null();
+    for (index, update) in commands.iter().enumerate() {
+        let mut command = format!(
+            "{} {} {}",
+            update.before.unwrap_or(null_id),
+            update.after.unwrap_or(null_id),
+            update.remote_ref
+        );
+        if index == 0 {
+            command.push('\0');
+            command.push_str(capabilities);
+        }
+        write_packet_line(out, command.as_bytes())?;
+    }
+    out.write_all(b"0000")?;
+    if !push_opts.is_empty() {
+        for opt in push_opts {
+            write_packet_line(out, opt.as_bytes())?;
+        }
+        out.write_all(b"0000")?;
+    }
+
+    let tips: Vec<_> = commands.iter().filter_map(|update| update.after).collect();
+    // Only deletions don't need a pack.
+    if tips.is_empty() {
+        out.flush()?;
+        return Ok(0);
+    }
+    let objects = objects_to_send(repo, &tips, remote_tips)?;
+    write_pack(repo, &objects, out)?;
+    out.flush()?;
+    Ok(objects.len())
+}
+
+fn write_packet_line(out: &mut dyn Write, data: &[u8]) -> std::io::Result<()> {
+    write!(out, "{:04x}", data.len() + 4)?;
+    out.write_all(data)
+}
+
+/// Split the packet lines the remote answered a push with into the lines of the status report
+/// and the messages it sent along, which are only sent on a side-band if `side_band` is set.
+fn demultiplex(lines: Vec<Vec<u8>>, side_band: bool) -> anyhow::Result<(Vec<String>, String)> {
+    if !side_band {
+        let report = lines
+            .iter()
+            .map(|line| {
+                line.trim_end_with(|c| c == '\n')
+                    .to_str_lossy()
+                    .into_owned()
+            })
+            .collect();
+        return Ok((report, String::new()));
+    }
+
+    let mut status = Vec::new();
+    let mut messages = Vec::new();
+    for line in lines {
+        match line.split_first() {
+            Some((1, data)) => status.extend_from_slice(data),
+            Some((2, data)) => messages.extend_from_slice(data),
+            Some((3, data)) => anyhow::bail!("{}", data.trim_end().to_str_lossy()),
+            _ => {}
+        }
+    }
+
+    // The status report is sent as packet lines itself.
+    let mut report = Vec::new();
+    let mut status = status.as_slice();
+    while status.len() >= 4 {
+        let len = std::str::from_utf8(&status[..4])
+            .ok()
+            .and_then(|len| usize::from_str_radix(len, 16).ok())
+            .ok_or_else(|| anyhow::anyhow!("invalid packet line in the push status"))?;
+        if len == 0 {
+            break;
+        }
+        let line = status
+            .get(4..len)
+            .ok_or_else(|| anyhow::anyhow!("truncated packet line in the push status"))?;
+        report.push(
+            line.trim_end_with(|c| c == '\n')
+                .to_str_lossy()
+                .into_owned(),
+        );
+        status = &status[len..];
+    }
+
+    let mut remote_messages = String::new();
+    let messages = messages.to_str_lossy();
+    let mut messages: Vec<_> = messages.split(['\n', '\r']).collect();
+    if messages.last() == Some(&"") {
+        messages.pop();
+    }
+    for message in messages {
+        remote_messages.push_str(&format!("remote: {message}\n"));
+    }
+    Ok((report, remote_messages))
+}
+
+/// Return the commits reachable from `tips` that aren't reachable from `remote_tips`, along
+/// with all trees and blobs they refer to that the remote doesn't have, assuming it has
+/// everything reachable from the commits they are based on.
+fn objects_to_send(
+    repo: &gix::Repository,
+    tips: &[gix::ObjectId],
+    remote_tips: &[gix::ObjectId],
+) -> anyhow::Result<Vec<gix::ObjectId>> {
+    let hidden: Vec<_> = remote_tips
+        .iter()
+        .copied()
+        .filter(|id| repo.find_commit(*id).is_ok())
+        .collect();
+    let mut commits = Vec::new();
+    for info in repo
+        .rev_walk(tips.iter().copied())
+        .with_hidden(hidden)
+        .all()?
+    {
+        commits.push(info?.id);
+    }
+
+    let new_commits: HashSet<_> = commits.iter().copied().collect();
+    let mut trees = Vec::with_capacity(commits.len());
+    let mut known = HashSet::new();
+    for id in &commits {
+        let commit = repo.find_object(*id)?;
+        let commit = commit.try_to_commit_ref()?;
+        trees.push(commit.tree());
+        for parent in commit.parents() {
+            if new_commits.contains(&parent) || !repo.has_object(parent) {
+                continue;
+            }
+            let parent = repo.find_object(parent)?;
+            add_tree(
+                repo,
+                parent.try_to_commit_ref()?.tree(),
+                &mut known,
+                &mut Vec::new(),
+            )?;
+        }
+    }
+
+    let mut objects = commits;
+    for tree in trees {
+        add_tree(repo, tree, &mut known, &mut objects)?;
+    }
+    Ok(objects)
+}
+
+/// Add `tree` and all trees and blobs in it to `objects`, skipping everything in `known`,
+/// which receives all added objects.
+fn add_tree(
+    repo: &gix::Repository,
+    tree: gix::ObjectId,
+    known: &mut HashSet<gix::ObjectId>,
+    objects: &mut Vec<gix::ObjectId>,
+) -> anyhow::Result<()> {
+    let mut queue = vec![tree];
+    while let Some(id) = queue.pop() {
+        if !known.insert(id) {
+            continue;
+        }
+        objects.push(id);
+        let tree = repo.find_object(id)?;
+        for entry in tree.try_to_tree_ref()?.entries {
+            let id = entry.oid.to_owned();
+            if entry.mode.is_tree() {
+                queue.push(id);
+            } else if !entry.mode.is_commit() && known.insert(id) {
+                // Submodule commits live in another repository.
+                objects.push(id);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write a pack with `objects` to `out`, without deltas as the remote doesn't need them.
+fn write_pack(
+    repo: &gix::Repository,
+    objects: &[gix::ObjectId],
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let mut out = HashingWriter {
+        inner: out,
+        hasher: gix::hash::hasher(repo.object_hash()),
+    };
+    out.write_all(b"PACK")?;
+    out.write_all(&2u32.to_be_bytes())?;
+    out.write_all(&u32::try_from(objects.len())?.to_be_bytes())?;
+    for id in objects {
+        let object = repo.find_object(*id)?;
+        let kind: u8 = match object.kind {
+            gix::object::Kind::Commit => 1,
+            gix::object::Kind::Tree => 2,
+            gix::object::Kind::Blob => 3,
+            gix::object::Kind::Tag => 4,
+        };
+        // The type and the size of the object, the latter in little-endian groups of 7 bits.
+        let mut size = object.data.len();
+        let mut header = vec![(kind << 4) | (size & 0x0f) as u8];
+        size >>= 4;
+        while size != 0 {
+            *header.last_mut().expect("not empty") |= 0x80;
+            header.push((size & 0x7f) as u8);
+            size >>= 7;
+        }
+        out.write_all(&header)?;
+        let mut encoder = flate2::write::ZlibEncoder::new(&mut out, flate2::Compression::default());
+        encoder.write_all(&object.data)?;
+        encoder.finish()?;
+    }
+    let checksum = out.hasher.try_finalize()?;
+    out.inner.write_all(checksum.as_bytes())?;
+    Ok(())
+}
+
+/// A writer that hashes everything it writes, for the checksum at the end of a pack.
+struct HashingWriter<'a> {
+    inner: &'a mut dyn Write,
+    hasher: gix::hash::Hasher,
+}
+
+impl Write for HashingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Write `FETCH_HEAD` like `git fetch` does, which is also how the file monitor learns about fetches.
+///
+/// With `explicit_refspecs`, everything they fetched without a wildcard is marked for merging,
+/// and otherwise only the upstream of the current branch if it's on `remote_name`.
+fn write_fetch_head(
+    repo: &gix::Repository,
+    remote_name: &str,
+    url: &gix::Url,
+    mappings: &[Mapping],
+    explicit_refspecs: Option<&[String]>,
+) -> std::io::Result<()> {
+    let mut url = url.clone();
+    url.set_user(None);
+    url.set_password(None);
+    let url = url.to_bstring();
+    let url = url.trim_end_with(|c| c == '/');
+    let url = url.strip_suffix(b".git").unwrap_or(url).as_bstr();
+    let upstream = upstream_ref(repo, remote_name);
+
+    let mut fetch_head = Vec::new();
+    for mapping in mappings {
+        let name = mapping.remote.as_name();
+        let for_merge = match explicit_refspecs {
+            Some(refspecs) => match mapping.spec_index {
+                SpecIndex::ExplicitInRemote(index) => refspecs
+                    .get(index)
+                    .is_some_and(|refspec| !refspec.contains('*')),
+                SpecIndex::Implicit(_) => false,
+            },
+            None => upstream
+                .as_ref()
+                .zip(name)
+                .is_some_and(|(upstream, name)| upstream.as_bstr() == name),
+        };
+        let description = match name {
+            Some(name) if name == "HEAD" => url.to_string(),
+            Some(name) => {
+                if let Some(branch) = name.strip_prefix(b"refs/heads/") {
+                    format!("branch '{}' of {url}", branch.as_bstr())
+                } else if let Some(tag) = name.strip_prefix(b"refs/tags/") {
+                    format!("tag '{}' of {url}", tag.as_bstr())
+                } else if let Some(branch) = name.strip_prefix(b"refs/remotes/") {
+                    format!("remote-tracking branch '{}' of {url}", branch.as_bstr())
+                } else {
+                    format!("'{name}' of {url}")
+                }
+            }
+            None => format!("'{}' of {url}", mapping.remote.as_id()),
+        };
+        fetch_head.extend_from_slice(
+            format!(
+                "{}\t{}\t{description}\n",
+                mapping.remote.as_id(),
+                if for_merge { "" } else { "not-for-merge" }
+            )
+            .as_bytes(),
+        );
+    }
+    std::fs::write(repo.git_dir().join("FETCH_HEAD"), fetch_head)
+}
+
+/// Return the full name of the branch in `remote_name` the current branch merges from, if any.
+fn upstream_ref(repo: &gix::Repository, remote_name: &str) -> Option<BString> {
+    let head = repo.head_name().ok().flatten()?;
+    let branch = head.as_bstr().strip_prefix(b"refs/heads/")?.as_bstr();
+    let config = repo.config_snapshot();
+    let remote = config.string(format!("branch.{branch}.remote").as_str())?;
+    if remote.as_bstr() != remote_name {
+        return None;
+    }
+    config
+        .string(format!("branch.{branch}.merge").as_str())
+        .map(|merge| merge.into_owned())
+}
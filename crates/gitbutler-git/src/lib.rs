@@ -1,6 +1,9 @@
 //! GitButler utility library for pushing/fetching Git repositories
 //! using the Git CLI.
 //!
+//! The `*_with_askpass()` and `*_with_progress()` helpers fetch and push [`in_process`] where
+//! possible, and use the Git CLI for everything else.
+//!
 //! **Important Note:** This is an interim library. Please do not rely on it;
 //! it's only used as a temporary measure in the GitButler app until we implement
 //! a longer-term solution for managing Git operations.
@@ -13,15 +16,19 @@ mod context;
 mod error;
 /// utilities to execute a command
 pub mod executor;
+pub mod in_process;
 mod refspec;
 mod repository;
 
 #[cfg(feature = "tokio")]
-pub use self::context::{GitContextExt, PushResult, fetch_with_askpass, push_with_askpass};
+pub use self::context::{
+    GitContextExt, PushResult, fetch_refspecs_with_askpass, fetch_refspecs_with_progress,
+    fetch_with_askpass, push_with_askpass, push_with_progress,
+};
 #[cfg(feature = "tokio")]
 pub use self::executor::tokio;
 pub use self::{
     error::Error,
     refspec::{Error as RefSpecError, RefSpec},
-    repository::{clone, fetch, push},
+    repository::{clone, fetch, fetch_refspecs, push},
 };
@@ -423,6 +423,25 @@ pub async fn fetch<P, F, Fut, E>(
     remote: &str,
     on_prompt: Option<F>,
 ) -> Result<(), crate::Error<Error<E>>>
+where
+    P: AsRef<Path>,
+    E: GitExecutor,
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Option<String>>,
+{
+    fetch_refspecs(repo_path, executor, remote, None, on_prompt).await
+}
+
+/// Like [`fetch()`], but fetches `refspecs` instead of the configured fetch refspecs of `remote`
+/// if they are provided. This is useful to obtain refs that are not covered by the configured
+/// refspecs, like the patchsets of Gerrit changes.
+pub async fn fetch_refspecs<P, F, Fut, E>(
+    repo_path: P,
+    executor: E,
+    remote: &str,
+    refspecs: Option<Vec<String>>,
+    on_prompt: Option<F>,
+) -> Result<(), crate::Error<Error<E>>>
 where
     P: AsRef<Path>,
     E: GitExecutor,
@@ -434,7 +453,11 @@ where
         path: repo_path.to_owned(),
         source,
     })?;
-    let refspecs = fetch_refspecs(&repo, remote).map_err(|source| {
+    let refspecs = match refspecs {
+        Some(refspecs) => repo.find_remote(remote).map(|_| refspecs),
+        None => configured_fetch_refspecs(&repo, remote),
+    }
+    .map_err(|source| {
         let remote_not_found =
             matches!(source, gix::remote::find::existing::Error::NotFound { .. });
         let source = Error::<E>::RemoteConfiguration {
@@ -483,7 +506,7 @@ where
     }
 }
 
-fn fetch_refspecs(
+pub(crate) fn configured_fetch_refspecs(
     repo: &gix::Repository,
     remote: &str,
 ) -> Result<Vec<String>, gix::remote::find::existing::Error> {
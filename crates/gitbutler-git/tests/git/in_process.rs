@@ -0,0 +1,429 @@
+use std::{
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    path::Path,
+    process::{Child, Command},
+    time::Duration,
+};
+
+use but_testsupport::{gix_testtools::tempfile::TempDir, invoke_bash_at_dir};
+use gitbutler_git::{
+    RefSpec,
+    in_process::{self, Progress, RefUpdateStatus},
+};
+
+/// A bare `remote.git` with one commit on `main`, `source` which pushed it, and `local`
+/// which cloned it.
+fn remote_with_clone() -> anyhow::Result<TempDir> {
+    let tmp = TempDir::new()?;
+    invoke_bash_at_dir(
+        r#"
+git init -q --bare -b main remote.git
+git init -q -b main source
+(cd source
+  git commit -q --allow-empty -m first
+  git remote add origin ../remote.git
+  git push -q origin main
+)
+git clone -q remote.git local
+"#,
+        tmp.path(),
+    );
+    Ok(tmp)
+}
+
+fn rev_parse(dir: &Path, spec: &str) -> anyhow::Result<Option<gix::ObjectId>> {
+    let repo = gix::open(dir)?;
+    Ok(repo.rev_parse_single(spec).ok().map(|id| id.detach()))
+}
+
+fn push(
+    dir: &Path,
+    refspec: &str,
+    force: bool,
+) -> Result<in_process::PushOutcome, gitbutler_git::Error<in_process::Error>> {
+    in_process::push(
+        dir,
+        "origin",
+        &[RefSpec::parse(refspec).expect("valid refspec")],
+        force,
+        false,
+        &[],
+        &mut |_| {},
+    )
+}
+
+#[test]
+fn fetch_from_local_bare_repository() -> anyhow::Result<()> {
+    let tmp = remote_with_clone()?;
+    invoke_bash_at_dir(
+        "git commit -q --allow-empty -m second && git push -q origin main",
+        &tmp.path().join("source"),
+    );
+
+    let local = tmp.path().join("local");
+    let mut progress = Vec::new();
+    let outcome = in_process::fetch(&local, "origin", None, &mut |step| progress.push(step))?;
+
+    assert_eq!(
+        outcome.updated_refs,
+        vec![gix::refs::FullName::try_from("refs/remotes/origin/main")?]
+    );
+    assert_eq!(
+        rev_parse(&local, "refs/remotes/origin/main")?,
+        rev_parse(&tmp.path().join("source"), "main")?,
+        "the remote-tracking branch points to the new commit"
+    );
+    let fetch_head = std::fs::read_to_string(local.join(".git/FETCH_HEAD"))?;
+    assert!(
+        fetch_head.starts_with(&format!(
+            "{}\t\tbranch 'main' of ",
+            rev_parse(&tmp.path().join("source"), "main")?.expect("pushed")
+        )),
+        "`FETCH_HEAD` is written like `git fetch` does, with the upstream marked for merging: {fetch_head}"
+    );
+    assert_eq!(fetch_head.lines().count(), 1);
+    assert!(
+        fetch_head.trim_end().ends_with("/remote"),
+        "the URL is written without the `.git` suffix: {fetch_head}"
+    );
+    assert_eq!(
+        progress.first(),
+        Some(&Progress::Connecting {
+            remote: "origin".into()
+        })
+    );
+    assert!(
+        progress
+            .iter()
+            .any(|step| matches!(step, Progress::PackReceived { objects } if *objects > 0)),
+        "{progress:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn fetch_of_missing_refspec_source_fails() -> anyhow::Result<()> {
+    let tmp = remote_with_clone()?;
+
+    let err = in_process::fetch(
+        tmp.path().join("local"),
+        "origin",
+        Some(vec![
+            "refs/heads/missing:refs/remotes/origin/missing".into(),
+        ]),
+        &mut |_| {},
+    )
+    .unwrap_err();
+
+    assert!(
+        matches!(&err, gitbutler_git::Error::RefNotFound(name) if name == "refs/heads/missing"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn fetch_from_unknown_remote_fails() -> anyhow::Result<()> {
+    let tmp = remote_with_clone()?;
+
+    let err =
+        in_process::fetch(tmp.path().join("local"), "unknown", None, &mut |_| {}).unwrap_err();
+
+    assert!(
+        matches!(&err, gitbutler_git::Error::NoSuchRemote(name, _) if name == "unknown"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn push_to_local_bare_repository() -> anyhow::Result<()> {
+    let tmp = remote_with_clone()?;
+    let local = tmp.path().join("local");
+    let remote = tmp.path().join("remote.git");
+    invoke_bash_at_dir(
+        "echo content >file && git add file && git commit -q -m feature",
+        &local,
+    );
+    let head = rev_parse(&local, "HEAD")?;
+
+    let mut progress = Vec::new();
+    let outcome = in_process::push(
+        &local,
+        "origin",
+        &[RefSpec::parse("HEAD:refs/heads/feature")?],
+        false,
+        false,
+        &[],
+        &mut |step| progress.push(step),
+    )?;
+    let updates = outcome.updates;
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].status, RefUpdateStatus::Created);
+    assert_eq!(updates[0].before, None);
+    assert_eq!(updates[0].after, head);
+    assert_eq!(
+        updates[0].tracking_ref.as_ref().map(ToString::to_string),
+        Some("refs/remotes/origin/feature".into())
+    );
+    assert_eq!(rev_parse(&remote, "refs/heads/feature")?, head);
+    assert_eq!(rev_parse(&local, "refs/remotes/origin/feature")?, head);
+    assert!(
+        progress
+            .iter()
+            .any(|step| matches!(step, Progress::PackSent { objects: 3 })),
+        "the commit, its tree and the new blob are sent: {progress:?}"
+    );
+    invoke_bash_at_dir("git fsck --strict --no-dangling", &remote);
+
+    let updates = push(&local, "HEAD:feature", false)?.updates;
+    assert_eq!(updates[0].status, RefUpdateStatus::UpToDate);
+
+    invoke_bash_at_dir("git commit -q --allow-empty -m more", &local);
+    let updates = push(&local, "HEAD:feature", false)?.updates;
+    assert_eq!(updates[0].status, RefUpdateStatus::FastForward);
+
+    invoke_bash_at_dir("git commit -q --amend --allow-empty -m rewritten", &local);
+    let err = push(&local, "HEAD:feature", false).unwrap_err();
+    assert!(
+        matches!(err, gitbutler_git::Error::NonFastForward(_)),
+        "{err:?}"
+    );
+    let updates = push(&local, "HEAD:feature", true)?.updates;
+    assert_eq!(updates[0].status, RefUpdateStatus::Forced);
+    assert_eq!(
+        rev_parse(&remote, "refs/heads/feature")?,
+        rev_parse(&local, "HEAD")?
+    );
+    invoke_bash_at_dir("git fsck --strict --no-dangling", &remote);
+
+    let updates = push(&local, ":refs/heads/feature", false)?.updates;
+    assert_eq!(updates[0].status, RefUpdateStatus::Deleted);
+    assert_eq!(updates[0].after, None);
+    assert_eq!(rev_parse(&remote, "refs/heads/feature")?, None);
+    assert_eq!(rev_parse(&local, "refs/remotes/origin/feature")?, None);
+    Ok(())
+}
+
+#[test]
+fn push_runs_the_hooks_of_the_remote() -> anyhow::Result<()> {
+    let tmp = remote_with_clone()?;
+    let local = tmp.path().join("local");
+    let remote = tmp.path().join("remote.git");
+    invoke_bash_at_dir(
+        r#"printf '#!/bin/sh\necho "checking $(cat)"\n' > hooks/pre-receive && chmod +x hooks/pre-receive"#,
+        &remote,
+    );
+    invoke_bash_at_dir("git commit -q --allow-empty -m accepted", &local);
+
+    let outcome = push(&local, "HEAD:main", false)?;
+    assert_eq!(outcome.updates[0].status, RefUpdateStatus::FastForward);
+    assert_eq!(
+        outcome.remote_messages,
+        format!(
+            "remote: checking {} {} refs/heads/main\n",
+            outcome.updates[0].before.expect("existed"),
+            outcome.updates[0].after.expect("pushed")
+        ),
+        "the messages of hooks are passed on like `git push` prints them"
+    );
+
+    invoke_bash_at_dir(
+        "printf '#!/bin/sh\necho denied; exit 1\n' > hooks/pre-receive",
+        &remote,
+    );
+    invoke_bash_at_dir("git commit -q --allow-empty -m rejected", &local);
+    let err = push(&local, "HEAD:main", false).unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            gitbutler_git::Error::Backend(in_process::Error::Rejected { refname, reason })
+                if refname == "refs/heads/main" && reason == "pre-receive hook declined"
+        ),
+        "{err:?}"
+    );
+    assert_ne!(
+        rev_parse(&remote, "refs/heads/main")?,
+        rev_parse(&local, "HEAD")?,
+        "the hook kept the remote from updating"
+    );
+    Ok(())
+}
+
+#[test]
+fn force_push_protection_rejects_unknown_remote_commits() -> anyhow::Result<()> {
+    let tmp = remote_with_clone()?;
+    let local = tmp.path().join("local");
+    invoke_bash_at_dir(
+        "git commit -q --allow-empty -m second && git push -q origin main",
+        &tmp.path().join("source"),
+    );
+    invoke_bash_at_dir("git commit -q --allow-empty -m local", &local);
+
+    let err = in_process::push(
+        &local,
+        "origin",
+        &[RefSpec::parse("HEAD:refs/heads/main")?],
+        true,
+        true,
+        &[],
+        &mut |_| {},
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, gitbutler_git::Error::ForcePushProtection(_)),
+        "the remote-tracking branch doesn't know the remote commit yet: {err:?}"
+    );
+
+    in_process::fetch(&local, "origin", None, &mut |_| {})?;
+    invoke_bash_at_dir("git reset -q --hard origin/main", &local);
+    invoke_bash_at_dir("git commit -q --amend --allow-empty -m rewritten", &local);
+    let outcome = in_process::push(
+        &local,
+        "origin",
+        &[RefSpec::parse("HEAD:refs/heads/main")?],
+        true,
+        true,
+        &[],
+        &mut |_| {},
+    );
+    assert!(
+        matches!(
+            outcome,
+            Err(gitbutler_git::Error::Backend(
+                in_process::Error::Unsupported(_)
+            ))
+        ),
+        "only the Git CLI can check the reflog to see if the remote commit was integrated"
+    );
+    Ok(())
+}
+
+#[test]
+fn push_leaves_unsupported_cases_to_the_git_cli() -> anyhow::Result<()> {
+    let tmp = remote_with_clone()?;
+    let local = tmp.path().join("local");
+
+    let err = in_process::push(
+        &local,
+        "origin",
+        &[RefSpec::parse("HEAD:refs/heads/main")?],
+        false,
+        false,
+        &["topic=x".into()],
+        &mut |_| {},
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            gitbutler_git::Error::Backend(in_process::Error::Unsupported(_))
+        ),
+        "the remote doesn't accept push options: {err:?}"
+    );
+    Ok(())
+}
+
+/// A `git daemon` serving the directory it was started in, killed on drop.
+struct Daemon {
+    child: Child,
+    port: u16,
+}
+
+impl Daemon {
+    fn serve(dir: &Path) -> anyhow::Result<Self> {
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+            .local_addr()?
+            .port();
+        let mut cmd = Command::new(gix::path::env::exe_invocation());
+        but_testsupport::isolate_env_std_cmd(&mut cmd);
+        let child = cmd
+            .args([
+                "daemon",
+                "--reuseaddr",
+                "--export-all",
+                "--enable=receive-pack",
+                "--listen=127.0.0.1",
+            ])
+            .arg(format!("--port={port}"))
+            .arg(format!("--base-path={}", dir.display()))
+            .arg(dir)
+            .spawn()?;
+        let daemon = Daemon { child, port };
+        for _ in 0..100 {
+            if TcpStream::connect((Ipv4Addr::LOCALHOST, port)).is_ok() {
+                return Ok(daemon);
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        anyhow::bail!("git daemon didn't start listening on port {port}")
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        _ = self.child.kill();
+        _ = self.child.wait();
+    }
+}
+
+#[test]
+fn fetch_over_git_daemon() -> anyhow::Result<()> {
+    let tmp = remote_with_clone()?;
+    let daemon = Daemon::serve(tmp.path())?;
+    let local = tmp.path().join("local");
+    invoke_bash_at_dir(
+        &format!(
+            "git remote set-url origin git://127.0.0.1:{}/remote.git",
+            daemon.port
+        ),
+        &local,
+    );
+    invoke_bash_at_dir(
+        "git commit -q --allow-empty -m second && git push -q origin main",
+        &tmp.path().join("source"),
+    );
+
+    in_process::fetch(&local, "origin", None, &mut |_| {})?;
+
+    assert_eq!(
+        rev_parse(&local, "refs/remotes/origin/main")?,
+        rev_parse(&tmp.path().join("source"), "main")?,
+    );
+    Ok(())
+}
+
+#[test]
+fn push_over_git_daemon() -> anyhow::Result<()> {
+    let tmp = remote_with_clone()?;
+    let daemon = Daemon::serve(tmp.path())?;
+    let local = tmp.path().join("local");
+    invoke_bash_at_dir(
+        &format!(
+            "git remote set-url origin git://127.0.0.1:{}/remote.git",
+            daemon.port
+        ),
+        &local,
+    );
+    invoke_bash_at_dir(
+        "echo content >file && git add file && git commit -q -m second",
+        &local,
+    );
+
+    let updates = push(&local, "HEAD:refs/heads/main", false)?.updates;
+
+    assert_eq!(updates[0].status, RefUpdateStatus::FastForward);
+    assert_eq!(
+        rev_parse(&tmp.path().join("remote.git"), "refs/heads/main")?,
+        rev_parse(&local, "HEAD")?,
+    );
+    assert_eq!(
+        rev_parse(&local, "refs/remotes/origin/main")?,
+        rev_parse(&local, "HEAD")?,
+    );
+    invoke_bash_at_dir(
+        "git fsck --strict --no-dangling",
+        &tmp.path().join("remote.git"),
+    );
+    Ok(())
+}
@@ -172,6 +172,8 @@ pub enum OperationKind {
     /// Or old oplog entries that existed before `RestoreFromSnapshotViaUndo` and
     /// `RestoreFromSnapshotViaRedo` were introduced.
     RestoreFromSnapshot,
+    /// Restore of selected paths via `but oplog restore <snapshot> -- <paths>`
+    RestorePathsFromSnapshot,
     ReorderCommit,
     InsertBlankCommit,
     MoveCommitFile,
@@ -188,6 +190,7 @@ pub enum OperationKind {
     AutoHandleChangesBefore,
     AutoHandleChangesAfter,
     SplitBranch,
+    SplitCommit,
     CleanWorkspace,
     OnDemandSnapshot,
     Unknown,
@@ -212,6 +215,7 @@ impl OperationKind {
             OperationKind::RestoreFromSnapshotViaUndo => "UNDO",
             OperationKind::RestoreFromSnapshotViaRedo => "REDO",
             OperationKind::RestoreFromSnapshot => "RESTORE",
+            OperationKind::RestorePathsFromSnapshot => "RESTORE_PATHS",
             OperationKind::ReorderCommit => "REORDER",
             OperationKind::InsertBlankCommit => "INSERT_COMMIT",
             OperationKind::MoveHunk => "MOVE_HUNK",
@@ -239,6 +243,7 @@ impl OperationKind {
             | OperationKind::UpdateDependentBranchDescription
             | OperationKind::UpdateDependentBranchPrNumber => "UPDATE_BRANCH",
             OperationKind::SplitBranch => "SPLIT_BRANCH",
+            OperationKind::SplitCommit => "SPLIT_COMMIT",
             OperationKind::ResolveConflicts => "RESOLVE",
             OperationKind::ResolveConflictsAi => "AI_RESOLVE",
             OperationKind::StashIntoBranch
@@ -290,6 +295,7 @@ impl OperationKind {
             OperationKind::RestoreFromSnapshotViaUndo
             | OperationKind::RestoreFromSnapshotViaRedo
             | OperationKind::RestoreFromSnapshot => "Restored from snapshot",
+            OperationKind::RestorePathsFromSnapshot => "Restored paths from snapshot",
             OperationKind::ReorderCommit => "Reordered commit",
             OperationKind::InsertBlankCommit => "Inserted blank commit",
             OperationKind::MoveCommitFile => "Moved file",
@@ -306,6 +312,7 @@ impl OperationKind {
             OperationKind::AutoHandleChangesBefore => "Handled changes before action",
             OperationKind::AutoHandleChangesAfter => "Handled changes after action",
             OperationKind::SplitBranch => "Split branch",
+            OperationKind::SplitCommit => "Split commit",
             OperationKind::CleanWorkspace => "Cleaned workspace",
             OperationKind::OnDemandSnapshot => "Created snapshot",
             OperationKind::Unknown => "Unknown operation",
@@ -348,6 +355,7 @@ impl OperationKind {
             OperationKind::RestoreFromSnapshotViaUndo => "RestoreFromSnapshotViaUndo",
             OperationKind::RestoreFromSnapshotViaRedo => "RestoreFromSnapshotViaRedo",
             OperationKind::RestoreFromSnapshot => "RestoreFromSnapshot",
+            OperationKind::RestorePathsFromSnapshot => "RestorePathsFromSnapshot",
             OperationKind::ReorderCommit => "ReorderCommit",
             OperationKind::InsertBlankCommit => "InsertBlankCommit",
             OperationKind::MoveCommitFile => "MoveCommitFile",
@@ -364,6 +372,7 @@ impl OperationKind {
             OperationKind::AutoHandleChangesBefore => "AutoHandleChangesBefore",
             OperationKind::AutoHandleChangesAfter => "AutoHandleChangesAfter",
             OperationKind::SplitBranch => "SplitBranch",
+            OperationKind::SplitCommit => "SplitCommit",
             OperationKind::CleanWorkspace => "CleanWorkspace",
             OperationKind::OnDemandSnapshot => "OnDemandSnapshot",
             OperationKind::Unknown => "Unknown",
@@ -406,6 +415,7 @@ impl OperationKind {
             "RestoreFromSnapshotViaUndo" => Self::RestoreFromSnapshotViaUndo,
             "RestoreFromSnapshotViaRedo" => Self::RestoreFromSnapshotViaRedo,
             "RestoreFromSnapshot" => Self::RestoreFromSnapshot,
+            "RestorePathsFromSnapshot" => Self::RestorePathsFromSnapshot,
             "ReorderCommit" => Self::ReorderCommit,
             "InsertBlankCommit" => Self::InsertBlankCommit,
             "MoveCommitFile" => Self::MoveCommitFile,
@@ -422,6 +432,7 @@ impl OperationKind {
             "AutoHandleChangesBefore" => Self::AutoHandleChangesBefore,
             "AutoHandleChangesAfter" => Self::AutoHandleChangesAfter,
             "SplitBranch" => Self::SplitBranch,
+            "SplitCommit" => Self::SplitCommit,
             "CleanWorkspace" => Self::CleanWorkspace,
             "OnDemandSnapshot" => Self::OnDemandSnapshot,
             "Unknown" => Self::Unknown,
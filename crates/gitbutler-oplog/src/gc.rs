@@ -0,0 +1,343 @@
+//! Pruning of old snapshots from the oplog according to a [`RetentionPolicy`].
+//!
+//! Snapshots form a chain of commits, so removing one of them means rewriting all snapshots above it.
+//! Their trees, messages and signatures stay the same, only the parents change.
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::Result;
+use but_core::DryRun;
+use but_ctx::{Context, access::RepoExclusive};
+use gix::{bstr::ByteSlice, objs::tree::EntryKind};
+use serde::Serialize;
+
+use crate::{
+    entry::Trailer,
+    reflog::{ReflogCommits, set_reference_to_oplog},
+    state::OplogHandle,
+};
+
+const MAX_AGE_DAYS: &str = "gitbutler.oplogMaxAgeDays";
+const MAX_COUNT: &str = "gitbutler.oplogMaxCount";
+const KEEP_EVERY_NTH: &str = "gitbutler.oplogKeepEveryNth";
+const THIN_AFTER: &str = "gitbutler.oplogThinAfter";
+
+/// Controls which snapshots are kept when the oplog is garbage-collected.
+///
+/// A snapshot is kept only if it satisfies every configured limit, and the most recent snapshot is always kept.
+/// A policy without any limit keeps everything.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Remove snapshots that are older than this.
+    pub max_age: Option<Duration>,
+    /// Keep at most this many snapshots.
+    pub max_count: Option<usize>,
+    /// Of the snapshots beyond [`thin_after`](Self::thin_after), keep only every n-th one.
+    pub keep_every_nth: Option<usize>,
+    /// The amount of most recent snapshots that are never thinned out by [`keep_every_nth`](Self::keep_every_nth).
+    pub thin_after: Option<usize>,
+}
+
+impl RetentionPolicy {
+    /// Read the policy from `gitbutler.oplogMaxAgeDays`, `gitbutler.oplogMaxCount`, `gitbutler.oplogKeepEveryNth`
+    /// and `gitbutler.oplogThinAfter` in `config`. Values that aren't positive numbers are ignored.
+    pub fn from_config(config: &gix::config::Snapshot<'_>) -> Self {
+        let positive = |key: &str| {
+            let value = config.integer(key)?;
+            match usize::try_from(value) {
+                Ok(value) if value > 0 => Some(value),
+                _ => {
+                    tracing::warn!(key, value, "Ignoring non-positive oplog retention value");
+                    None
+                }
+            }
+        };
+        RetentionPolicy {
+            max_age: positive(MAX_AGE_DAYS)
+                .map(|days| Duration::from_secs(days as u64 * 24 * 60 * 60)),
+            max_count: positive(MAX_COUNT),
+            keep_every_nth: positive(KEEP_EVERY_NTH),
+            thin_after: config
+                .integer(THIN_AFTER)
+                .and_then(|value| usize::try_from(value).ok()),
+        }
+    }
+
+    /// Return `true` if no limit is configured, so nothing would be removed.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_age.is_none() && self.max_count.is_none() && self.keep_every_nth.is_none()
+    }
+
+    /// Return `true` if the snapshot at `index`, with `0` being the most recent one, should be kept
+    /// if it has been created `age` ago.
+    pub fn keeps(&self, index: usize, age: Duration) -> bool {
+        if index == 0 {
+            return true;
+        }
+        if self.max_count.is_some_and(|max| index >= max) {
+            return false;
+        }
+        if self.max_age.is_some_and(|max| age > max) {
+            return false;
+        }
+        match self.keep_every_nth {
+            Some(nth) => {
+                let thin_after = self.thin_after.unwrap_or_default();
+                index < thin_after || (index - thin_after) % nth == 0
+            }
+            None => true,
+        }
+    }
+}
+
+/// The result of [`gc_snapshots()`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcOutcome {
+    /// The amount of snapshots that remain in the oplog.
+    pub kept_snapshots: usize,
+    /// The amount of snapshots that were removed from the oplog.
+    pub removed_snapshots: usize,
+    /// The amount of objects that were only reachable through removed snapshots, including their commits.
+    ///
+    /// These are removed by the next `git gc` unless something outside the oplog still refers to them.
+    pub reclaimable_objects: usize,
+    /// The uncompressed size of all [reclaimable objects](Self::reclaimable_objects) in bytes.
+    pub reclaimable_bytes: u64,
+    /// The new head of the oplog, if it changed.
+    #[serde(with = "but_serde::object_id_opt")]
+    pub new_head: Option<gix::ObjectId>,
+}
+
+/// Remove all snapshots from the oplog of `ctx` that `policy` doesn't keep, and rewrite the remaining
+/// snapshots to form a new chain.
+///
+/// The reflog that protects the oplog from garbage collection is updated to the new head, so the removed
+/// snapshots become unreachable. Restore snapshots that refer to a removed snapshot lose that reference.
+/// With `dry_run`, nothing is written and the outcome only reports what would be removed.
+pub fn gc_snapshots(
+    ctx: &Context,
+    policy: &RetentionPolicy,
+    dry_run: DryRun,
+    _exclusive_access: &mut RepoExclusive,
+) -> Result<GcOutcome> {
+    let oplog_state = OplogHandle::new(&ctx.project_data_dir());
+    let Some(head_id) = oplog_state.oplog_head()? else {
+        return Ok(GcOutcome::default());
+    };
+    let repo = ctx.repo.get()?;
+
+    // Snapshots newest first, just like `SnapshotIter` sees them, along with the commit below them
+    // that we leave untouched.
+    let now = gix::date::Time::now_utc().seconds;
+    let mut snapshots = Vec::new();
+    let mut base = None;
+    let mut next = Some(head_id);
+    while let Some(id) = next {
+        let commit = repo.find_commit(id)?;
+        let mut parents = commit.parent_ids();
+        let (first_parent, second_parent) = (parents.next(), parents.next());
+        if second_parent.is_some() {
+            base = Some(id);
+            break;
+        }
+        let age = Duration::from_secs(now.saturating_sub(commit.time()?.seconds).max(0) as u64);
+        let keep = policy.keeps(snapshots.len(), age);
+        snapshots.push((id, keep));
+        next = first_parent.map(|id| id.detach());
+    }
+
+    let (kept, removed): (Vec<_>, Vec<_>) = snapshots.iter().partition(|(_, keep)| *keep);
+    let mut outcome = GcOutcome {
+        kept_snapshots: kept.len(),
+        removed_snapshots: removed.len(),
+        ..Default::default()
+    };
+    if removed.is_empty() {
+        return Ok(outcome);
+    }
+
+    let mut reachable = HashSet::new();
+    for (id, _) in &kept {
+        collect_tree_objects(
+            &repo,
+            repo.find_commit(*id)?.tree_id()?.detach(),
+            &mut reachable,
+        )?;
+    }
+    let mut reclaimable: HashSet<_> = removed.iter().map(|(id, _)| *id).collect();
+    let mut removed_objects = HashSet::new();
+    for (id, _) in &removed {
+        collect_tree_objects(
+            &repo,
+            repo.find_commit(*id)?.tree_id()?.detach(),
+            &mut removed_objects,
+        )?;
+    }
+    reclaimable.extend(removed_objects.difference(&reachable));
+    outcome.reclaimable_objects = reclaimable.len();
+    for id in &reclaimable {
+        outcome.reclaimable_bytes += repo.find_header(*id)?.size();
+    }
+
+    if dry_run == DryRun::Yes {
+        return Ok(outcome);
+    }
+
+    // The rewritten snapshots must be protected from `git gc`, which needs the target.
+    let target = ctx.project_meta()?.target_commit_id_or_err()?;
+    let removed: HashSet<_> = removed.into_iter().map(|(id, _)| *id).collect();
+    let kept: Vec<_> = kept.into_iter().rev().map(|(id, _)| *id).collect();
+    let new_head = rewrite_chain(&repo, &kept, base, |id| removed.contains(id))?
+        .expect("the most recent snapshot is always kept");
+    oplog_state.set_oplog_head(new_head)?;
+    set_reference_to_oplog(repo.git_dir(), ReflogCommits::new(ctx, target)?)?;
+    outcome.new_head = (new_head != head_id).then_some(new_head);
+    Ok(outcome)
+}
+
+/// Rewrite the snapshots in `oldest_first` to form a chain on top of `base`, keeping their trees, messages
+/// and signatures. `restored_from` trailers are adjusted to the rewritten snapshots, and removed if they refer
+/// to a snapshot for which `is_dropped` returns `true`.
+///
+/// Return the id of the newest rewritten snapshot, or `None` if there was none.
+pub(crate) fn rewrite_chain(
+    repo: &gix::Repository,
+    oldest_first: &[gix::ObjectId],
+    base: Option<gix::ObjectId>,
+    is_dropped: impl Fn(&gix::ObjectId) -> bool,
+) -> Result<Option<gix::ObjectId>> {
+    let mut rewritten = HashMap::new();
+    let mut parent = base;
+    for id in oldest_first {
+        let mut commit = repo.find_commit(*id)?.decode()?.to_owned()?;
+        commit.parents = parent.into_iter().collect();
+        commit.message = remap_restored_from(commit.message.as_bstr(), &rewritten, &is_dropped);
+        let new_id = repo.write_object(&commit)?.detach();
+        rewritten.insert(*id, new_id);
+        parent = Some(new_id);
+    }
+    Ok(parent.filter(|_| !oldest_first.is_empty()))
+}
+
+/// Point the `restored_from` trailers in `message` to the `rewritten` snapshots, and drop those for which
+/// `is_dropped` returns `true`.
+fn remap_restored_from(
+    message: &gix::bstr::BStr,
+    rewritten: &HashMap<gix::ObjectId, gix::ObjectId>,
+    is_dropped: &impl Fn(&gix::ObjectId) -> bool,
+) -> gix::bstr::BString {
+    let mut out = gix::bstr::BString::default();
+    for line in message.lines_with_terminator() {
+        let trailer = line
+            .trim_end()
+            .to_str()
+            .ok()
+            .and_then(|line| Trailer::from_str(line).ok());
+        match trailer {
+            Some(Trailer::RestoredFrom(id)) => match rewritten.get(&id) {
+                Some(new_id) => {
+                    out.extend_from_slice(Trailer::RestoredFrom(*new_id).to_string().as_bytes());
+                    out.extend_from_slice(&line[line.trim_end().len()..]);
+                }
+                None if is_dropped(&id) => continue,
+                None => out.extend_from_slice(line),
+            },
+            _ => out.extend_from_slice(line),
+        }
+    }
+    out
+}
+
+/// Insert the ids of all trees and blobs reachable from `tree_id` into `out`.
+fn collect_tree_objects(
+    repo: &gix::Repository,
+    tree_id: gix::ObjectId,
+    out: &mut HashSet<gix::ObjectId>,
+) -> Result<()> {
+    if !out.insert(tree_id) {
+        return Ok(());
+    }
+    let tree = repo.find_tree(tree_id)?;
+    for entry in tree.iter() {
+        let entry = entry?;
+        match entry.mode().kind() {
+            EntryKind::Tree => collect_tree_objects(repo, entry.object_id(), out)?,
+            EntryKind::Commit => {}
+            EntryKind::Blob | EntryKind::BlobExecutable | EntryKind::Link => {
+                out.insert(entry.object_id());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn unlimited_policy_keeps_everything() {
+        let policy = RetentionPolicy::default();
+        assert!(policy.is_unlimited());
+        assert!((0..100).all(|index| policy.keeps(index, DAY * 1000)));
+    }
+
+    #[test]
+    fn most_recent_snapshot_is_always_kept() {
+        let policy = RetentionPolicy {
+            max_age: Some(DAY),
+            max_count: Some(1),
+            keep_every_nth: Some(10),
+            thin_after: None,
+        };
+        assert!(policy.keeps(0, DAY * 1000));
+        assert!(!policy.keeps(1, Duration::ZERO));
+    }
+
+    #[test]
+    fn limits_are_combined() {
+        let policy = RetentionPolicy {
+            max_age: Some(DAY * 30),
+            max_count: Some(10),
+            keep_every_nth: Some(3),
+            thin_after: Some(4),
+        };
+        let kept: Vec<_> = (0..12).filter(|index| policy.keeps(*index, DAY)).collect();
+        assert_eq!(
+            kept,
+            [0, 1, 2, 3, 4, 7],
+            "the first four are kept, then every third up to the maximum count"
+        );
+        assert!(
+            !policy.keeps(1, DAY * 31),
+            "recent positions are still subject to the maximum age"
+        );
+    }
+
+    #[test]
+    fn restored_from_trailers_follow_rewritten_snapshots() {
+        let id =
+            |hex: char| gix::ObjectId::from_hex(hex.to_string().repeat(40).as_bytes()).unwrap();
+        let (kept, new, removed) = (id('1'), id('2'), id('3'));
+        let message = format!(
+            "Restored from snapshot\n\nVersion: 3\nrestored_from: {kept}\nrestored_from: {removed}\nrestored_date: 42\n"
+        );
+        let actual = remap_restored_from(
+            message.as_str().into(),
+            &HashMap::from([(kept, new)]),
+            &|id: &gix::ObjectId| *id == removed,
+        );
+        assert_eq!(
+            actual,
+            format!(
+                "Restored from snapshot\n\nVersion: 3\nrestored_from: {new}\nrestored_date: 42\n"
+            )
+        );
+    }
+}
@@ -1,4 +1,6 @@
 pub mod entry;
+mod gc;
+pub use gc::{GcOutcome, RetentionPolicy, gc_snapshots};
 mod oplog;
 pub use oplog::OplogExt;
 pub use oplog::RestoreKind;
@@ -7,6 +9,10 @@ mod reflog;
 mod snapshot;
 pub use snapshot::SnapshotExt;
 mod state;
+mod transfer;
+pub use transfer::{
+    ExportOutcome, ImportOutcome, SnapshotRange, export_snapshots, import_snapshots,
+};
 
 /// The name of the file holding our state, useful for watching for changes.
 const OPLOG_FILE_NAME: &str = "operations-log.toml";
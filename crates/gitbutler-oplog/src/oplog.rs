@@ -21,7 +21,7 @@ use gitbutler_repo::{
 use gix::objs::Write as _;
 use gix::{
     ObjectId,
-    bstr::ByteSlice,
+    bstr::{BStr, BString, ByteSlice},
     index::entry::{Flags, Stage},
     object::tree::EntryKind,
 };
@@ -207,6 +207,23 @@ pub trait OplogExt {
         guard: &mut RepoExclusive,
     ) -> Result<gix::ObjectId>;
 
+    /// Returns the changes that [`restore_paths`](Self::restore_paths) would make to the working directory,
+    /// i.e. the diff from the current working directory to the one of the snapshot at `sha`, limited to `paths`.
+    fn restore_paths_diff(&self, sha: gix::ObjectId, paths: &[BString]) -> Result<Vec<TreeChange>>;
+
+    /// Restores only `paths` in the working directory to their state in the snapshot at `snapshot_commit_id`.
+    /// Each of `paths` is relative to the root of the worktree, and directories include everything below them.
+    ///
+    /// Unlike [`restore_snapshot`](Self::restore_snapshot), the index, references, commits and virtual branches
+    /// are left untouched, and so are all files outside of `paths`.
+    /// Upon success, a new snapshot is created representing the state right before this call.
+    fn restore_paths(
+        &self,
+        snapshot_commit_id: gix::ObjectId,
+        paths: &[BString],
+        guard: &mut RepoExclusive,
+    ) -> Result<gix::ObjectId>;
+
     /// Returns the diff showing what this snapshot's operation changed.
     ///
     /// When `child_id` is provided, it is used as the "after" state directly,
@@ -308,6 +325,24 @@ impl OplogExt for Context {
         restore_snapshot(self, snapshot_commit_id, restore_kind, guard)
     }
 
+    fn restore_paths_diff(&self, sha: gix::ObjectId, paths: &[BString]) -> Result<Vec<TreeChange>> {
+        // Only objects of the current worktree are written, which don't have to outlive the diff.
+        let repo = self.clone_repo_for_merging_non_persisting()?;
+        #[expect(deprecated)]
+        let worktree_tree_id = repo.create_wd_tree(AUTO_TRACK_LIMIT_BYTES)?;
+        let snapshot_workdir_tree_id = get_workdir_tree(None, sha, &repo)?;
+        changes_in_paths(&repo, worktree_tree_id, snapshot_workdir_tree_id, paths)
+    }
+
+    fn restore_paths(
+        &self,
+        snapshot_commit_id: gix::ObjectId,
+        paths: &[BString],
+        guard: &mut RepoExclusive,
+    ) -> Result<gix::ObjectId> {
+        restore_paths(self, snapshot_commit_id, paths, guard)
+    }
+
     fn snapshot_diff(
         &self,
         sha: gix::ObjectId,
@@ -553,7 +588,7 @@ fn snapshot_checkout(
     }))
 }
 
-fn snapshot_metadata(
+pub(crate) fn snapshot_metadata(
     snapshot_tree: &gix::Tree<'_>,
     repo: &gix::Repository,
 ) -> Result<(ProjectMeta, VirtualBranches)> {
@@ -1166,6 +1201,142 @@ fn restore_snapshot(
     )
 }
 
+fn restore_paths(
+    ctx: &Context,
+    snapshot_commit_id: gix::ObjectId,
+    paths: &[BString],
+    exclusive_access: &mut RepoExclusive,
+) -> Result<gix::ObjectId> {
+    if paths.is_empty() {
+        bail!("at least one path to restore is required");
+    }
+    let repo = ctx.repo.get()?;
+    let snapshot_commit = repo.find_commit(snapshot_commit_id)?;
+    let target = ctx.project_meta()?.target_commit_id_or_err()?;
+
+    let before_restore_snapshot_tree_id =
+        prepare_snapshot(ctx, exclusive_access.read_permission())?;
+    let before_restore_snapshot_tree = repo.find_tree(before_restore_snapshot_tree_id)?;
+    let before_restore_index_tree_id = before_restore_snapshot_tree
+        .lookup_entry_by_path("index")?
+        .context("Could not get index tree of snapshot created before the restore")?
+        .id()
+        .detach();
+    let before_restore_index_conflicts_tree_id = before_restore_snapshot_tree
+        .lookup_entry_by_path("index-conflicts")?
+        .map(|entry| entry.id().detach());
+    let before_restore_snapshot_workdir_tree_id =
+        get_v3_workdir_tree(before_restore_snapshot_tree)?
+            .context("Could not get workdir tree of snapshot created before the restore")?;
+
+    let gix_repo = ctx.clone_repo_for_merging()?;
+    let snapshot_workdir_tree_id = get_workdir_tree(None, snapshot_commit_id, &gix_repo)?;
+    let changes = changes_in_paths(
+        &gix_repo,
+        before_restore_snapshot_workdir_tree_id,
+        snapshot_workdir_tree_id,
+        paths,
+    )?;
+    if changes.is_empty() {
+        bail!("the given paths are already in the state of the snapshot");
+    }
+
+    // The tree to check out is the current worktree, with the selected paths as they are in the snapshot.
+    let snapshot_workdir_tree = gix_repo.find_tree(snapshot_workdir_tree_id)?;
+    let mut workdir_tree = gix_repo
+        .find_tree(before_restore_snapshot_workdir_tree_id)?
+        .edit()?;
+    for path in changes
+        .iter()
+        .flat_map(|change| std::iter::once(change.path.as_bstr()).chain(change.previous_path()))
+        .filter(|path| is_in_paths(path, paths))
+    {
+        match snapshot_workdir_tree.lookup_entry_by_path(gix::path::from_bstr(path))? {
+            Some(entry) => {
+                workdir_tree.upsert(path.split_str("/"), entry.mode().kind(), entry.object_id())?;
+            }
+            None => {
+                workdir_tree.remove(path.split_str("/"))?;
+            }
+        }
+    }
+    let workdir_tree_id = workdir_tree.write()?.detach();
+
+    // As in a full restore, the current worktree is the merge base, so only the selected paths change.
+    but_core::worktree::safe_checkout_from_head(
+        workdir_tree_id,
+        &gix_repo,
+        but_core::worktree::checkout::Options {
+            merge_base_override: Some(before_restore_snapshot_workdir_tree_id),
+            ..Default::default()
+        },
+    )?;
+    // The checkout also updates the index, which is meant to stay as it was.
+    reset_index_to_tree(
+        ctx,
+        before_restore_index_tree_id,
+        before_restore_index_conflicts_tree_id,
+    )?;
+
+    let restored_operation = snapshot_commit
+        .message_raw()?
+        .to_str()
+        .ok()
+        .and_then(|msg| SnapshotDetails::from_str(msg).ok())
+        .map(|d| d.operation)
+        .unwrap_or(OperationKind::Unknown);
+    let restored_date_ms = snapshot_commit.time()?.seconds * 1000;
+    let operation = OperationKind::RestorePathsFromSnapshot;
+    let details = SnapshotDetails {
+        version: Default::default(),
+        operation,
+        title: operation.as_persisted_str().to_owned(),
+        body: None,
+        trailers: [
+            Trailer::RestoredFrom(snapshot_commit_id),
+            Trailer::RestoredOperation(restored_operation),
+            Trailer::RestoredDate(restored_date_ms),
+        ]
+        .into_iter()
+        .chain(paths.iter().map(|path| Trailer::File(path.to_string())))
+        .collect(),
+    };
+    commit_snapshot(
+        ctx,
+        &repo,
+        before_restore_snapshot_tree_id,
+        details,
+        exclusive_access,
+        target,
+    )
+}
+
+/// Returns the changes between the worktree trees `from` and `to` that touch any of `paths`.
+fn changes_in_paths(
+    repo: &gix::Repository,
+    from: gix::ObjectId,
+    to: gix::ObjectId,
+    paths: &[BString],
+) -> Result<Vec<TreeChange>> {
+    Ok(tree_changes(repo, Some(from), to)?
+        .into_iter()
+        .filter(|change| {
+            std::iter::once(change.path.as_bstr())
+                .chain(change.previous_path())
+                .any(|path| is_in_paths(path, paths))
+        })
+        .collect())
+}
+
+/// Returns `true` if `path` is one of `paths`, or inside of a directory in `paths`.
+fn is_in_paths(path: &BStr, paths: &[BString]) -> bool {
+    paths.iter().any(|prefix| {
+        let prefix = prefix.trim_end_with(|c| c == '/');
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(b"/"))
+    })
+}
+
 /// Restore the state of .git/base_merge_parent and .git/conflicts from the snapshot
 /// Will remove those files if they are not present in the snapshot
 fn restore_conflicts_tree(snapshot_tree: &gix::Tree, repo: &gix::Repository) -> Result<()> {
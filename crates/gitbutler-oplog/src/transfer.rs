@@ -0,0 +1,205 @@
+//! Moving snapshots between clones of a repository with Git bundles.
+//!
+//! An export writes a range of snapshots into a bundle, as a chain that doesn't depend on anything else in the
+//! oplog. Since snapshot trees contain the worktree, the index, the metadata TOML files and the commits of all
+//! stacks, that is all a restore needs except for the target commit, which is expected to be fetched from the
+//! remote. An import unbundles the chain and attaches it on top of the local oplog.
+use std::{collections::HashSet, path::Path, process::Command};
+
+use anyhow::{Context as _, Result, bail};
+use but_ctx::{
+    Context,
+    access::{RepoExclusive, RepoShared},
+};
+use gix::bstr::ByteSlice;
+use serde::Serialize;
+
+use crate::{
+    gc::rewrite_chain,
+    oplog::snapshot_metadata,
+    reflog::{ReflogCommits, set_reference_to_oplog},
+    state::OplogHandle,
+};
+
+/// The reference that temporarily points to the exported chain while the bundle is created.
+const EXPORT_REF: &str = "refs/gitbutler/oplog-export";
+
+/// The range of snapshots to export, with both ends included.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotRange {
+    /// The oldest snapshot to export, or `None` to export all snapshots below [`to`](Self::to).
+    pub from: Option<gix::ObjectId>,
+    /// The newest snapshot to export, or `None` to start at the head of the oplog.
+    pub to: Option<gix::ObjectId>,
+}
+
+/// The result of [`export_snapshots()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOutcome {
+    /// The amount of snapshots written to the bundle.
+    pub snapshots: usize,
+    /// The newest snapshot in the bundle, which differs from the exported one as the chain was rewritten.
+    #[serde(with = "but_serde::object_id")]
+    pub head: gix::ObjectId,
+}
+
+/// The result of [`import_snapshots()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOutcome {
+    /// The amount of snapshots that were added to the oplog.
+    pub snapshots: usize,
+    /// The new head of the oplog, which is the newest imported snapshot.
+    #[serde(with = "but_serde::object_id")]
+    pub new_head: gix::ObjectId,
+    /// The target commits of imported snapshots that don't exist in this repository.
+    ///
+    /// Snapshots with a missing target can only be restored after it was fetched.
+    #[serde(with = "but_serde::object_id_vec")]
+    pub missing_target_commits: Vec<gix::ObjectId>,
+}
+
+/// Write the snapshots in `range` of the oplog of `ctx` into a Git bundle at `bundle_path`.
+///
+/// The snapshots are rewritten into a chain of their own, so the bundle has no prerequisites. `restored_from`
+/// trailers that refer to snapshots outside of `range` are removed. The oplog itself isn't changed.
+pub fn export_snapshots(
+    ctx: &Context,
+    range: SnapshotRange,
+    bundle_path: &Path,
+    _shared_access: &RepoShared,
+) -> Result<ExportOutcome> {
+    let repo = ctx.repo.get()?;
+    let to = match range.to {
+        Some(id) => id,
+        None => OplogHandle::new(&ctx.project_data_dir())
+            .oplog_head()?
+            .context("There are no snapshots to export")?,
+    };
+
+    let mut newest_first = Vec::new();
+    let mut next = Some(to);
+    while let Some(id) = next {
+        let commit = repo.find_commit(id)?;
+        let mut parents = commit.parent_ids();
+        let (first_parent, second_parent) = (parents.next(), parents.next());
+        if second_parent.is_some() {
+            break;
+        }
+        newest_first.push(id);
+        if range.from == Some(id) {
+            break;
+        }
+        next = first_parent.map(|id| id.detach());
+    }
+    if let Some(from) = range.from
+        && newest_first.last() != Some(&from)
+    {
+        bail!("Snapshot {from} is not an ancestor of {to} in the oplog");
+    }
+
+    newest_first.reverse();
+    let exported: HashSet<_> = newest_first.iter().copied().collect();
+    let head = rewrite_chain(&repo, &newest_first, None, |id| !exported.contains(id))?
+        .context("There are no snapshots to export")?;
+
+    repo.reference(
+        EXPORT_REF,
+        head,
+        gix::refs::transaction::PreviousValue::Any,
+        "GitButler: export oplog",
+    )?;
+    let res = git(
+        &repo,
+        &[
+            "bundle".as_ref(),
+            "create".as_ref(),
+            bundle_path.as_os_str(),
+            EXPORT_REF.as_ref(),
+        ],
+    );
+    repo.find_reference(EXPORT_REF)?.delete()?;
+    res.context("Failed to create the oplog bundle")?;
+
+    Ok(ExportOutcome {
+        snapshots: newest_first.len(),
+        head,
+    })
+}
+
+/// Read the snapshots from the Git bundle at `bundle_path`, as written by [`export_snapshots()`], and add them
+/// on top of the oplog of `ctx`, so the newest imported snapshot becomes its head.
+///
+/// Imported snapshots can be restored like local ones, as long as their target commit exists in the repository.
+pub fn import_snapshots(
+    ctx: &Context,
+    bundle_path: &Path,
+    _exclusive_access: &mut RepoExclusive,
+) -> Result<ImportOutcome> {
+    let repo = ctx.repo.get()?;
+    let output = git(
+        &repo,
+        &[
+            "bundle".as_ref(),
+            "unbundle".as_ref(),
+            bundle_path.as_os_str(),
+        ],
+    )
+    .context("Failed to read the oplog bundle")?;
+    let bundle_head = output
+        .lines()
+        .find_map(|line| {
+            let (id, name) = line.split_once_str(" ")?;
+            (name == EXPORT_REF.as_bytes()).then(|| gix::ObjectId::from_hex(id))
+        })
+        .transpose()?
+        .context("The bundle doesn't contain exported snapshots")?;
+
+    let mut oldest_first = Vec::new();
+    let mut missing_target_commits = Vec::new();
+    let mut next = Some(bundle_head);
+    while let Some(id) = next {
+        let commit = repo.find_commit(id)?;
+        let (project_meta, _) = snapshot_metadata(&commit.tree()?, &repo)
+            .with_context(|| format!("Commit {id} in the bundle is not a snapshot"))?;
+        let target = project_meta.target_commit_id_or_err()?;
+        if !repo.has_object(target) && !missing_target_commits.contains(&target) {
+            missing_target_commits.push(target);
+        }
+        oldest_first.push(id);
+        next = commit.parent_ids().next().map(|id| id.detach());
+    }
+    oldest_first.reverse();
+
+    // The imported snapshots must be protected from `git gc`, which needs the target.
+    let target = ctx.project_meta()?.target_commit_id_or_err()?;
+    let oplog_state = OplogHandle::new(&ctx.project_data_dir());
+    let base = oplog_state
+        .oplog_head()?
+        .filter(|head_id| repo.has_object(head_id));
+    let new_head = rewrite_chain(&repo, &oldest_first, base, |_| false)?
+        .expect("the bundle contains at least one snapshot");
+    oplog_state.set_oplog_head(new_head)?;
+    set_reference_to_oplog(repo.git_dir(), ReflogCommits::new(ctx, target)?)?;
+
+    Ok(ImportOutcome {
+        snapshots: oldest_first.len(),
+        new_head,
+        missing_target_commits,
+    })
+}
+
+/// Run `git` with `args` in `repo` and return its standard output, or fail with its standard error.
+fn git(repo: &gix::Repository, args: &[&std::ffi::OsStr]) -> Result<Vec<u8>> {
+    let output = Command::new(gix::path::env::exe_invocation())
+        .arg("--git-dir")
+        .arg(repo.git_dir())
+        .args(args)
+        .output()
+        .context("Failed to execute git")?;
+    if !output.status.success() {
+        bail!("{}", output.stderr.to_str_lossy().trim());
+    }
+    Ok(output.stdout)
+}
@@ -4,11 +4,14 @@ use std::{fs, path::Path};
 use std::os::unix::ffi::OsStrExt as _;
 
 use anyhow::Context as _;
-use but_core::{GitConfigSettings, RepositoryExt as _};
+use but_core::{DryRun, GitConfigSettings, RepositoryExt as _, ref_metadata::ProjectMeta};
 use but_ctx::Context;
 use but_testsupport::Sandbox;
 use gitbutler_oplog::entry::{OperationKind, SnapshotDetails};
-use gitbutler_oplog::{OplogExt, RestoreKind};
+use gitbutler_oplog::{
+    GcOutcome, OplogExt, RestoreKind, RetentionPolicy, SnapshotRange, export_snapshots,
+    gc_snapshots, import_snapshots,
+};
 use gix::bstr::ByteSlice as _;
 
 #[test]
@@ -592,6 +595,65 @@ fn restore_repoints_workspace_and_worktree() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn restore_paths_only_changes_the_given_paths() -> anyhow::Result<()> {
+    let Test { repo, ctx } = &mut Test::from_scenario("one-stack-two-commits", &["A"]);
+    let gix_repo = repo.open_repo();
+    let workspace = gix_repo.rev_parse_single("test-workspace-two")?.detach();
+    let mut guard = ctx.exclusive_worktree_access();
+    let snapshot = ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::OnDemandSnapshot),
+        guard.write_permission(),
+    )?;
+
+    fs::write(repo.projects_root().join("first"), "changed\n")?;
+    fs::write(repo.projects_root().join("second"), "changed\n")?;
+    let paths = [gix::bstr::BString::from("first")];
+
+    let preview = ctx.restore_paths_diff(snapshot, &paths)?;
+    assert_eq!(
+        preview
+            .iter()
+            .map(|change| change.path.to_string())
+            .collect::<Vec<_>>(),
+        ["first"],
+        "the preview only lists changes within the given paths"
+    );
+
+    ctx.restore_paths(snapshot, &paths, guard.write_permission())?;
+    assert_eq!(
+        fs::read_to_string(repo.projects_root().join("first"))?,
+        "first\n",
+        "the given path is restored from the snapshot"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.projects_root().join("second"))?,
+        "changed\n",
+        "paths that weren't given keep their changes"
+    );
+    assert_eq!(
+        gix_repo
+            .find_reference(but_core::WORKSPACE_REF_NAME)?
+            .peel_to_id()?
+            .detach(),
+        workspace,
+        "the workspace ref is left alone"
+    );
+
+    let latest = ctx
+        .snapshots_iter(None, Vec::new(), None)?
+        .next()
+        .context("the restore is recorded")??;
+    let details = latest.details.context("the restore has details")?;
+    assert_eq!(details.operation, OperationKind::RestorePathsFromSnapshot);
+    assert!(
+        ctx.restore_paths(snapshot, &paths, guard.write_permission())
+            .is_err(),
+        "restoring paths that already match the snapshot is an error"
+    );
+    Ok(())
+}
+
 #[test]
 fn restore_round_trips_workspace_and_ad_hoc_checkouts() -> anyhow::Result<()> {
     let Test { repo, ctx } = &mut Test::default();
@@ -715,6 +777,196 @@ fn snapshot_history_orders_and_paginates() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn gc_removes_old_snapshots_and_rewrites_the_rest() -> anyhow::Result<()> {
+    let Test { repo, ctx } = &mut Test::default();
+    let mut guard = ctx.exclusive_worktree_access();
+    ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::CreateBranch),
+        guard.write_permission(),
+    )?;
+    fs::write(repo.projects_root().join("one"), "one")?;
+    let second = ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::CreateCommit),
+        guard.write_permission(),
+    )?;
+    fs::write(repo.projects_root().join("two"), "two")?;
+    let third = ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::GenericBranchUpdate),
+        guard.write_permission(),
+    )?;
+    let policy = RetentionPolicy {
+        max_count: Some(2),
+        ..Default::default()
+    };
+
+    let outcome = gc_snapshots(ctx, &policy, DryRun::Yes, guard.write_permission())?;
+    assert_eq!(outcome.kept_snapshots, 2);
+    assert_eq!(outcome.removed_snapshots, 1);
+    assert!(
+        outcome.reclaimable_objects > 0,
+        "at least the commit of the removed snapshot can be reclaimed"
+    );
+    assert_eq!(outcome.new_head, None, "nothing is written in dry-run mode");
+    assert_eq!(ctx.oplog_head()?, Some(third));
+
+    let dry_run_outcome = outcome;
+    let outcome = gc_snapshots(ctx, &policy, DryRun::No, guard.write_permission())?;
+    assert_eq!(
+        GcOutcome {
+            new_head: None,
+            ..outcome.clone()
+        },
+        dry_run_outcome,
+        "the dry-run predicts the actual outcome"
+    );
+    let new_head = outcome.new_head.expect("the chain was rewritten");
+    assert_eq!(ctx.oplog_head()?, Some(new_head));
+
+    let snapshots = ctx
+        .snapshots_iter(None, Vec::new(), None)?
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    assert_eq!(
+        snapshots
+            .iter()
+            .map(|snapshot| snapshot.details.as_ref().map(|details| details.operation))
+            .collect::<Vec<_>>(),
+        [
+            Some(OperationKind::GenericBranchUpdate),
+            Some(OperationKind::CreateCommit)
+        ],
+        "the oldest snapshot is gone"
+    );
+    let git_repo = ctx.repo.get()?;
+    for (rewritten, original) in snapshots.iter().zip([third, second]) {
+        assert_eq!(
+            git_repo.find_commit(rewritten.commit_id)?.tree_id()?,
+            git_repo.find_commit(original)?.tree_id()?,
+            "snapshots retain their content"
+        );
+    }
+
+    let outcome = gc_snapshots(ctx, &policy, DryRun::No, guard.write_permission())?;
+    assert_eq!(outcome.removed_snapshots, 0, "gc is idempotent");
+    assert_eq!(outcome.new_head, None);
+    Ok(())
+}
+
+#[test]
+fn gc_without_a_target_fails_before_rewriting_the_oplog() -> anyhow::Result<()> {
+    let Test { ctx, .. } = &mut Test::default();
+    let mut guard = ctx.exclusive_worktree_access();
+    for operation in [OperationKind::CreateBranch, OperationKind::CreateCommit] {
+        ctx.create_snapshot(SnapshotDetails::new(operation), guard.write_permission())?;
+    }
+    let head = ctx.oplog_head()?;
+    ctx.set_project_meta(ProjectMeta::default())?;
+
+    let policy = RetentionPolicy {
+        max_count: Some(1),
+        ..Default::default()
+    };
+    gc_snapshots(ctx, &policy, DryRun::No, guard.write_permission())
+        .expect_err("the rewritten snapshots couldn't be protected from `git gc` without a target");
+    assert_eq!(
+        ctx.oplog_head()?,
+        head,
+        "the oplog still points to the snapshots that are protected"
+    );
+    Ok(())
+}
+
+#[test]
+fn exported_snapshots_can_be_imported_and_restored_elsewhere() -> anyhow::Result<()> {
+    let Test { repo, ctx } = &mut Test::default();
+    let mut guard = ctx.exclusive_worktree_access();
+    let unrelated = ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::CreateBranch),
+        guard.write_permission(),
+    )?;
+    fs::write(repo.projects_root().join("file"), "exported")?;
+    let first = ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::CreateCommit),
+        guard.write_permission(),
+    )?;
+    fs::write(repo.projects_root().join("file"), "changed later")?;
+    let second = ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::OnDemandSnapshot),
+        guard.write_permission(),
+    )?;
+    drop(guard);
+
+    let bundle = tempfile::tempdir()?;
+    let bundle_path = bundle.path().join("oplog.bundle");
+    let outcome = export_snapshots(
+        ctx,
+        SnapshotRange {
+            from: Some(first),
+            to: None,
+        },
+        &bundle_path,
+        ctx.shared_worktree_access().read_permission(),
+    )?;
+    assert_eq!(outcome.snapshots, 2, "the range includes both ends");
+    assert_eq!(
+        ctx.oplog_head()?,
+        Some(second),
+        "exporting doesn't change the oplog"
+    );
+
+    let Test {
+        repo: other_repo,
+        ctx: other_ctx,
+    } = &mut Test::default();
+    let mut guard = other_ctx.exclusive_worktree_access();
+    let local = other_ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::GenericBranchUpdate),
+        guard.write_permission(),
+    )?;
+    let outcome = import_snapshots(other_ctx, &bundle_path, guard.write_permission())?;
+    assert_eq!(outcome.snapshots, 2);
+    assert!(
+        outcome.missing_target_commits.is_empty(),
+        "both clones share the target"
+    );
+    assert_eq!(other_ctx.oplog_head()?, Some(outcome.new_head));
+
+    let snapshots = other_ctx
+        .snapshots_iter(None, Vec::new(), None)?
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    assert_eq!(
+        snapshots
+            .iter()
+            .map(|snapshot| snapshot.details.as_ref().map(|details| details.operation))
+            .collect::<Vec<_>>(),
+        [
+            Some(OperationKind::OnDemandSnapshot),
+            Some(OperationKind::CreateCommit),
+            Some(OperationKind::GenericBranchUpdate),
+        ],
+        "imported snapshots are added on top of the local ones"
+    );
+    assert_eq!(snapshots[2].commit_id, local);
+    assert!(
+        snapshots
+            .iter()
+            .all(|snapshot| snapshot.commit_id != unrelated),
+        "snapshots outside of the range aren't exported"
+    );
+
+    other_ctx.restore_snapshot(
+        snapshots[1].commit_id,
+        RestoreKind::ExplicitRestoreFromSnapshot,
+        guard.write_permission(),
+    )?;
+    assert_eq!(
+        fs::read_to_string(other_repo.projects_root().join("file"))?,
+        "exported",
+        "the imported snapshot restores the worktree of the exporting clone"
+    );
+    Ok(())
+}
+
 #[test]
 fn first_snapshot_diff_works() -> anyhow::Result<()> {
     let Test { ctx, .. } = &mut Test::from_scenario("one-stack-two-commits", &["A"]);
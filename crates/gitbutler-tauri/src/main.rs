@@ -13,8 +13,8 @@
 
 use anyhow::{Context, bail};
 use but_api::{
-    bitbucket, branch, commit, diff, github, gitlab, land, legacy, open, platform, resolve,
-    workspace,
+    azure, bitbucket, branch, commit, diff, gitea, github, gitlab, land, legacy, open, platform,
+    resolve, workspace,
 };
 use but_settings::AppSettingsWithDiskSync;
 use gitbutler_tauri::{
@@ -232,6 +232,18 @@ fn main() -> anyhow::Result<()> {
                 bitbucket::tauri_list_known_bitbucket_accounts::list_known_bitbucket_accounts,
                 bitbucket::tauri_clear_all_bitbucket_tokens::clear_all_bitbucket_tokens,
                 bitbucket::tauri_check_bitbucket_credentials::check_bitbucket_credentials,
+                azure::tauri_store_azure_pat::store_azure_pat,
+                azure::tauri_get_az_user::get_az_user,
+                azure::tauri_forget_azure_account::forget_azure_account,
+                azure::tauri_list_known_azure_accounts::list_known_azure_accounts,
+                azure::tauri_clear_all_azure_tokens::clear_all_azure_tokens,
+                azure::tauri_check_azure_credentials::check_azure_credentials,
+                gitea::tauri_store_gitea_pat::store_gitea_pat,
+                gitea::tauri_get_gitea_user::get_gitea_user,
+                gitea::tauri_forget_gitea_account::forget_gitea_account,
+                gitea::tauri_list_known_gitea_accounts::list_known_gitea_accounts,
+                gitea::tauri_clear_all_gitea_tokens::clear_all_gitea_tokens,
+                gitea::tauri_check_gitea_credentials::check_gitea_credentials,
                 diff::tauri_commit_details::commit_details,
                 diff::tauri_commit_details_with_line_stats::commit_details_with_line_stats,
                 workspace::tauri_get_workspace::get_workspace,
@@ -327,6 +339,10 @@ fn main() -> anyhow::Result<()> {
                 legacy::forge::tauri_get_repo_info::get_repo_info,
                 legacy::forge::tauri_update_review::update_review,
                 legacy::forge::tauri_list_ci_checks::list_ci_checks,
+                legacy::gerrit::tauri_list_gerrit_changes::list_gerrit_changes,
+                legacy::gerrit::tauri_gerrit_relation_chains::gerrit_relation_chains,
+                legacy::gerrit::tauri_gerrit_pull_patchsets::gerrit_pull_patchsets,
+                legacy::gerrit::tauri_gerrit_submit_chain::gerrit_submit_chain,
                 legacy::forge::tauri_publish_review::publish_review,
                 legacy::forge::tauri_merge_review::merge_review,
                 legacy::forge::tauri_set_review_auto_merge::set_review_auto_merge,
@@ -1,4 +1,8 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+};
 
 use anyhow::{Context as _, Result};
 use but_core::{TreeChange, sync::RepoExclusive};
@@ -10,9 +14,10 @@ use but_project_handle::{REFRESH_SENTINEL_PATH, process_sentinel_token};
 use but_settings::{AppSettings, AppSettingsWithDiskSync};
 use gitbutler_filemonitor::{
     FETCH_HEAD, HEAD, HEAD_ACTIVITY, INDEX, InternalEvent, LOCAL_REFS_DIR, REMOTE_REFS_DIR,
+    fsmonitor,
 };
 use gitbutler_operating_modes::operating_mode;
-use gix::bstr::ByteSlice as _;
+use gix::bstr::{BStr, ByteSlice as _};
 use tracing::instrument;
 
 use crate::Change;
@@ -29,6 +34,18 @@ pub struct Handler {
     // need extra protection.
     /// A function to send events - decoupled from app-handle for testing purposes.
     send_event: Arc<dyn Fn(Change) -> Result<()> + Send + Sync + 'static>,
+    /// The worktree changes last computed for each git-dir, so the next computation only has to
+    /// look at the paths the fsmonitor journal recorded since.
+    baselines: Arc<Mutex<HashMap<PathBuf, Baseline>>>,
+}
+
+/// Worktree changes along with what they were computed from.
+struct Baseline {
+    /// The fsmonitor token obtained right before `changes` were computed.
+    token: String,
+    head_tree: gix::ObjectId,
+    index_checksum: Option<gix::ObjectId>,
+    changes: but_core::WorktreeChanges,
 }
 
 impl Handler {
@@ -36,6 +53,7 @@ impl Handler {
     pub fn new(send_event: impl Fn(Change) -> Result<()> + Send + Sync + 'static) -> Self {
         Handler {
             send_event: Arc::new(send_event),
+            baselines: Default::default(),
         }
     }
 
@@ -100,7 +118,7 @@ impl Handler {
         let context_lines = ctx.settings.context_lines;
         let (repo, ws, mut db) = ctx.workspace_and_db_mut_with_perm(perm.read_permission())?;
 
-        let wt_changes = but_core::diff::worktree_changes(&repo)?;
+        let wt_changes = self.worktree_changes(&repo)?;
 
         let dependencies = hunk_dependencies_for_workspace_changes_by_worktree_dir(
             &repo,
@@ -134,6 +152,51 @@ impl Handler {
         Ok(())
     }
 
+    /// Compute the worktree changes of `repo`, only looking at the paths that the fsmonitor journal
+    /// recorded since the last computation if `HEAD^{tree}` and the index are still the same.
+    fn worktree_changes(&self, repo: &gix::Repository) -> Result<but_core::WorktreeChanges> {
+        let git_dir = repo.path().to_owned();
+        let head_tree = repo.head_tree_id_or_empty()?.detach();
+        let index_checksum = repo.index_or_empty()?.checksum();
+        let previous = self
+            .baselines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&git_dir);
+
+        let query = fsmonitor::query(
+            &git_dir,
+            previous
+                .as_ref()
+                .map_or("", |previous| previous.token.as_str()),
+        )?;
+        let changes = match (previous, &query.changes) {
+            (Some(previous), fsmonitor::Changes::Paths(paths))
+                if previous.head_tree == head_tree
+                    && previous.index_checksum.is_some()
+                    && previous.index_checksum == index_checksum
+                    && !paths.iter().any(|path| affects_other_paths(path.as_bstr())) =>
+            {
+                but_core::diff::refresh_worktree_changes(repo, previous.changes, paths)?
+            }
+            _ => but_core::diff::worktree_changes(repo)?,
+        };
+
+        self.baselines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                git_dir,
+                Baseline {
+                    token: query.token,
+                    head_tree,
+                    index_checksum,
+                    changes: changes.clone(),
+                },
+            );
+        Ok(changes)
+    }
+
     pub fn git_files_change(
         &self,
         project_id: ProjectHandleOrLegacyProjectId,
@@ -210,6 +273,14 @@ impl Handler {
     }
 }
 
+/// Return `true` if a change to `path` can change the status of other paths as well.
+fn affects_other_paths(path: &BStr) -> bool {
+    let file_name = path
+        .rfind_byte(b'/')
+        .map_or(path, |pos| path[pos + 1..].as_bstr());
+    file_name == ".gitignore" || file_name == ".gitattributes"
+}
+
 fn head_info(ctx: &mut Context) -> Result<(String, String)> {
     let repo = &*ctx.repo.get()?;
     let mut head_ref = repo.head().context("failed to get head")?;
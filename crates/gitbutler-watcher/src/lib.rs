@@ -19,7 +19,7 @@ use gitbutler_filemonitor::{FileMonitorHandle, InternalEvent};
 mod handler;
 
 /// Re-export for convenience
-pub use gitbutler_filemonitor::WatchMode;
+pub use gitbutler_filemonitor::{WatchMode, fsmonitor};
 
 /// An abstraction over a link to the spawned watcher, which runs in the background.
 pub struct WatcherHandle {
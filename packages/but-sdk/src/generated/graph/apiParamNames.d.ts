@@ -26,8 +26,12 @@ export declare const apiParamNames: {
 	readonly checkSigningSettings: readonly ["projectId"];
 	readonly commentArchive: readonly ["projectId", "id"];
 	readonly commentCreate: readonly ["projectId", "comment"];
+	readonly commentReopen: readonly ["projectId", "id"];
+	readonly commentReply: readonly ["projectId", "reply"];
+	readonly commentResolve: readonly ["projectId", "id"];
 	readonly commentUpdate: readonly ["projectId", "id", "payload"];
 	readonly commentsList: readonly ["projectId"];
+	readonly commentsSyncNotes: readonly ["projectId"];
 	readonly commitAmend: readonly ["projectId", "commitId", "changes", "changesSource", "dryRun"];
 	readonly commitCherryPick: readonly ["projectId", "sourceCommitIds", "relativeTo", "side", "dryRun"];
 	readonly commitConflicts: readonly ["projectId", "commitId"];
@@ -53,14 +57,21 @@ export declare const apiParamNames: {
 	readonly forgeCompareBranchUrl: readonly ["projectId", "base", "branch", "fork"];
 	readonly forgeInfo: readonly ["projectId"];
 	readonly forgeProvider: readonly ["projectId"];
+	readonly forgetAzureAccount: readonly ["account"];
 	readonly forgetBitbucketAccount: readonly ["account"];
+	readonly forgetGiteaAccount: readonly ["account"];
 	readonly forgetGithubAccount: readonly ["account"];
 	readonly forgetGitlabAccount: readonly ["account"];
+	readonly gerritPullPatchsets: readonly ["projectId", "changeIds", "dryRun"];
+	readonly gerritRelationChains: readonly ["projectId"];
+	readonly gerritSubmitChain: readonly ["projectId", "stackId"];
 	readonly getAiConfiguration: readonly [];
+	readonly getAzUser: readonly ["account"];
 	readonly getBbUser: readonly ["account"];
 	readonly getBlobFile: readonly ["projectId", "relativePath", "blobId"];
 	readonly getGbConfig: readonly ["projectId"];
 	readonly getGhUser: readonly ["account"];
+	readonly getGiteaUser: readonly ["account"];
 	readonly getGlUser: readonly ["account"];
 	readonly getInitialBranchIntegration: readonly ["projectId", "branch", "strategy"];
 	readonly getLoginToken: readonly [];
@@ -77,6 +88,7 @@ export declare const apiParamNames: {
 	readonly gitTestFetch: readonly ["projectId", "remoteName", "action"];
 	readonly gitTestPush: readonly ["projectId", "remoteName", "branchName"];
 	readonly headInfo: readonly ["projectId"];
+	readonly importReviewComments: readonly ["projectId", "reviewId"];
 	readonly initApplicationNamespace: readonly ["identifier"];
 	readonly initGithubDeviceOauth: readonly [];
 	readonly listAvailableReviewTemplates: readonly ["projectId"];
@@ -84,7 +96,10 @@ export declare const apiParamNames: {
 	readonly listCiChecks: readonly ["projectId", "reference", "cacheConfig"];
 	readonly listCommentReactions: readonly ["projectId", "commentId"];
 	readonly listEditors: readonly [];
+	readonly listGerritChanges: readonly ["projectId", "cacheConfig"];
+	readonly listKnownAzureAccounts: readonly [];
 	readonly listKnownBitbucketAccounts: readonly [];
+	readonly listKnownGiteaAccounts: readonly [];
 	readonly listKnownGithubAccounts: readonly [];
 	readonly listKnownGitlabAccounts: readonly [];
 	readonly listPrograms: readonly [];
@@ -121,7 +136,9 @@ export declare const apiParamNames: {
 	readonly setReviewDraftiness: readonly ["projectId", "reviewId", "draft"];
 	readonly setReviewTemplate: readonly ["projectId", "templatePath"];
 	readonly setTargetRefAndInitProject: readonly ["projectId", "targetRef", "pushRemote"];
+	readonly storeAzurePat: readonly ["organization", "accessToken"];
 	readonly storeBitbucketApiToken: readonly ["email", "accessToken"];
+	readonly storeGiteaPat: readonly ["host", "accessToken"];
 	readonly storeGithubPat: readonly ["accessToken"];
 	readonly storeGitlabPat: readonly ["accessToken"];
 	readonly tearOffBranch: readonly ["projectId", "subjectBranch", "dryRun"];
@@ -26,8 +26,12 @@ export const apiParamNames = {
 	checkSigningSettings: ["projectId"],
 	commentArchive: ["projectId", "id"],
 	commentCreate: ["projectId", "comment"],
+	commentReopen: ["projectId", "id"],
+	commentReply: ["projectId", "reply"],
+	commentResolve: ["projectId", "id"],
 	commentUpdate: ["projectId", "id", "payload"],
 	commentsList: ["projectId"],
+	commentsSyncNotes: ["projectId"],
 	commitAmend: ["projectId", "commitId", "changes", "changesSource", "dryRun"],
 	commitCherryPick: ["projectId", "sourceCommitIds", "relativeTo", "side", "dryRun"],
 	commitConflicts: ["projectId", "commitId"],
@@ -53,14 +57,21 @@ export const apiParamNames = {
 	forgeCompareBranchUrl: ["projectId", "base", "branch", "fork"],
 	forgeInfo: ["projectId"],
 	forgeProvider: ["projectId"],
+	forgetAzureAccount: ["account"],
 	forgetBitbucketAccount: ["account"],
+	forgetGiteaAccount: ["account"],
 	forgetGithubAccount: ["account"],
 	forgetGitlabAccount: ["account"],
+	gerritPullPatchsets: ["projectId", "changeIds", "dryRun"],
+	gerritRelationChains: ["projectId"],
+	gerritSubmitChain: ["projectId", "stackId"],
 	getAiConfiguration: [],
+	getAzUser: ["account"],
 	getBbUser: ["account"],
 	getBlobFile: ["projectId", "relativePath", "blobId"],
 	getGbConfig: ["projectId"],
 	getGhUser: ["account"],
+	getGiteaUser: ["account"],
 	getGlUser: ["account"],
 	getInitialBranchIntegration: ["projectId", "branch", "strategy"],
 	getLoginToken: [],
@@ -77,6 +88,7 @@ export const apiParamNames = {
 	gitTestFetch: ["projectId", "remoteName", "action"],
 	gitTestPush: ["projectId", "remoteName", "branchName"],
 	headInfo: ["projectId"],
+	importReviewComments: ["projectId", "reviewId"],
 	initApplicationNamespace: ["identifier"],
 	initGithubDeviceOauth: [],
 	listAvailableReviewTemplates: ["projectId"],
@@ -84,7 +96,10 @@ export const apiParamNames = {
 	listCiChecks: ["projectId", "reference", "cacheConfig"],
 	listCommentReactions: ["projectId", "commentId"],
 	listEditors: [],
+	listGerritChanges: ["projectId", "cacheConfig"],
+	listKnownAzureAccounts: [],
 	listKnownBitbucketAccounts: [],
+	listKnownGiteaAccounts: [],
 	listKnownGithubAccounts: [],
 	listKnownGitlabAccounts: [],
 	listPrograms: [],
@@ -121,7 +136,9 @@ export const apiParamNames = {
 	setReviewDraftiness: ["projectId", "reviewId", "draft"],
 	setReviewTemplate: ["projectId", "templatePath"],
 	setTargetRefAndInitProject: ["projectId", "targetRef", "pushRemote"],
+	storeAzurePat: ["organization", "accessToken"],
 	storeBitbucketApiToken: ["email", "accessToken"],
+	storeGiteaPat: ["host", "accessToken"],
 	storeGithubPat: ["accessToken"],
 	storeGitlabPat: ["accessToken"],
 	tearOffBranch: ["projectId", "subjectBranch", "dryRun"],
@@ -15,6 +15,7 @@ export declare const apiProvides: {
 	readonly commitDetailsWithLineStats: readonly ["Commits"];
 	readonly currentForgeLogin: readonly ["ForgeLogin"];
 	readonly forgeInfo: readonly ["ForgeInfo"];
+	readonly gerritRelationChains: readonly ["Reviews"];
 	readonly getAiConfiguration: readonly ["AiConfiguration"];
 	readonly getBlobFile: readonly [];
 	readonly getGbConfig: readonly ["GbConfig"];
@@ -24,6 +25,7 @@ export declare const apiProvides: {
 	readonly headInfo: readonly ["Workspace"];
 	readonly listCiChecks: readonly ["Checks"];
 	readonly listCommentReactions: readonly ["CommentReactions"];
+	readonly listGerritChanges: readonly ["Reviews"];
 	readonly listRepoLabels: readonly ["RepoLabels"];
 	readonly listReviewComments: readonly ["ReviewComments"];
 	readonly listReviewReactions: readonly ["ReviewReactions"];
@@ -46,9 +48,12 @@ export declare const apiInvalidates: {
 	readonly deleteAllData: readonly ["Projects"];
 	readonly deleteProject: readonly ["Projects"];
 	readonly deleteReviewComment: readonly ["ReviewComments"];
+	readonly forgetAzureAccount: readonly ["ForgeAccounts", "ForgeLogin"];
 	readonly forgetBitbucketAccount: readonly ["ForgeAccounts", "ForgeLogin"];
+	readonly forgetGiteaAccount: readonly ["ForgeAccounts", "ForgeLogin"];
 	readonly forgetGithubAccount: readonly ["ForgeAccounts", "ForgeLogin"];
 	readonly forgetGitlabAccount: readonly ["ForgeAccounts", "ForgeLogin"];
+	readonly gerritSubmitChain: readonly ["Reviews"];
 	readonly mergeReview: readonly ["Reviews", "MergeStatus", "Checks"];
 	readonly publishReview: readonly ["Reviews"];
 	readonly removeCommentReaction: readonly ["CommentReactions", "ReviewComments"];
@@ -59,7 +64,9 @@ export declare const apiInvalidates: {
 	readonly setGbConfig: readonly ["GbConfig", "SigningSettings"];
 	readonly setReviewAutoMerge: readonly ["Reviews"];
 	readonly setReviewDraftiness: readonly ["Reviews", "MergeStatus"];
+	readonly storeAzurePat: readonly ["ForgeAccounts", "ForgeLogin"];
 	readonly storeBitbucketApiToken: readonly ["ForgeAccounts", "ForgeLogin"];
+	readonly storeGiteaPat: readonly ["ForgeAccounts", "ForgeLogin"];
 	readonly storeGithubPat: readonly ["ForgeAccounts", "ForgeLogin"];
 	readonly storeGitlabPat: readonly ["ForgeAccounts", "ForgeLogin"];
 	readonly updateAiConfiguration: readonly ["AiConfiguration"];
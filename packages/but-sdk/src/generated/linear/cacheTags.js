@@ -13,6 +13,7 @@ export const apiProvides = {
 	commitDetailsWithLineStats: ["Commits"],
 	currentForgeLogin: ["ForgeLogin"],
 	forgeInfo: ["ForgeInfo"],
+	gerritRelationChains: ["Reviews"],
 	getAiConfiguration: ["AiConfiguration"],
 	getBlobFile: [],
 	getGbConfig: ["GbConfig"],
@@ -22,6 +23,7 @@ export const apiProvides = {
 	headInfo: ["Workspace"],
 	listCiChecks: ["Checks"],
 	listCommentReactions: ["CommentReactions"],
+	listGerritChanges: ["Reviews"],
 	listRepoLabels: ["RepoLabels"],
 	listReviewComments: ["ReviewComments"],
 	listReviewReactions: ["ReviewReactions"],
@@ -44,9 +46,12 @@ export const apiInvalidates = {
 	deleteAllData: ["Projects"],
 	deleteProject: ["Projects"],
 	deleteReviewComment: ["ReviewComments"],
+	forgetAzureAccount: ["ForgeAccounts", "ForgeLogin"],
 	forgetBitbucketAccount: ["ForgeAccounts", "ForgeLogin"],
+	forgetGiteaAccount: ["ForgeAccounts", "ForgeLogin"],
 	forgetGithubAccount: ["ForgeAccounts", "ForgeLogin"],
 	forgetGitlabAccount: ["ForgeAccounts", "ForgeLogin"],
+	gerritSubmitChain: ["Reviews"],
 	mergeReview: ["Reviews", "MergeStatus", "Checks"],
 	publishReview: ["Reviews"],
 	removeCommentReaction: ["CommentReactions", "ReviewComments"],
@@ -57,7 +62,9 @@ export const apiInvalidates = {
 	setGbConfig: ["GbConfig", "SigningSettings"],
 	setReviewAutoMerge: ["Reviews"],
 	setReviewDraftiness: ["Reviews", "MergeStatus"],
+	storeAzurePat: ["ForgeAccounts", "ForgeLogin"],
 	storeBitbucketApiToken: ["ForgeAccounts", "ForgeLogin"],
+	storeGiteaPat: ["ForgeAccounts", "ForgeLogin"],
 	storeGithubPat: ["ForgeAccounts", "ForgeLogin"],
 	storeGitlabPat: ["ForgeAccounts", "ForgeLogin"],
 	updateAiConfiguration: ["AiConfiguration"],
@@ -301,6 +301,24 @@ export declare function commentArchive(projectId: string, id: string): Promise<b
  */
 export declare function commentCreate(projectId: string, comment: NewComment): Promise<DiffComment>
 
+/**
+ * Mark the thread the comment with the given `id` belongs to as unresolved again.
+ * Returns `false` if the thread wasn't resolved.
+ */
+export declare function commentReopen(projectId: string, id: string): Promise<boolean>
+
+/**
+ * Reply to a comment, adding the reply to the thread the comment belongs to.
+ * The reply is attributed to the configured Git author unless it names its author.
+ */
+export declare function commentReply(projectId: string, reply: NewReply): Promise<CommentReply>
+
+/**
+ * Resolve the thread the comment with the given `id` belongs to, on behalf of the configured
+ * Git author. Returns `false` if the thread was already resolved.
+ */
+export declare function commentResolve(projectId: string, id: string): Promise<boolean>
+
 /**
  * List all unarchived comments, re-anchored against the current diffs.
  *
@@ -308,6 +326,14 @@ export declare function commentCreate(projectId: string, comment: NewComment): P
  */
 export declare function commentsList(projectId: string): Promise<Array<DiffComment>>
 
+/**
+ * Merge the comments shared in the Git notes ref into the store and write all comments on
+ * commits back to it, whether or not syncing is enabled in the configuration.
+ *
+ * See [`but_comments::notes`] for how to share the ref with teammates.
+ */
+export declare function commentsSyncNotes(projectId: string): Promise<NotesSync>
+
 /** Replace the payload of the unarchived comment with the given `id`. */
 export declare function commentUpdate(projectId: string, id: string, payload: string): Promise<void>
 
@@ -531,6 +557,19 @@ export declare function forgeInfo(projectId: string): Promise<ForgeInfo | null>
  */
 export declare function forgeProvider(projectId: string): Promise<ForgeName | null>
 
+/**
+ * Removes stored credentials for a specific Azure DevOps account.
+ *
+ * # Arguments
+ *
+ * * `account` - Identifier for the Azure DevOps account
+ *
+ * # Returns
+ *
+ * * `Ok(())` - Always succeeds, even if no token was found
+ */
+export declare function forgetAzureAccount(account: AzureAccountIdentifier): Promise<void>
+
 /**
  * Removes stored credentials for a specific Bitbucket account.
  *
@@ -544,6 +583,19 @@ export declare function forgeProvider(projectId: string): Promise<ForgeName | nu
  */
 export declare function forgetBitbucketAccount(account: BitbucketAccountIdentifier): Promise<void>
 
+/**
+ * Removes stored credentials for a specific Gitea account.
+ *
+ * # Arguments
+ *
+ * * `account` - Identifier for the Gitea account
+ *
+ * # Returns
+ *
+ * * `Ok(())` - Always succeeds, even if no token was found
+ */
+export declare function forgetGiteaAccount(account: GiteaAccountIdentifier): Promise<void>
+
 /**
  * Removes stored credentials for a specific GitHub account.
  *
@@ -576,9 +628,50 @@ export declare function forgetGithubAccount(account: GithubAccountIdentifier): P
  */
 export declare function forgetGitlabAccount(account: GitlabAccountIdentifier): Promise<void>
 
+/**
+ * Replace the local commits of `change_ids` with the current patchsets that others uploaded for them,
+ * rebasing everything on top. If `change_ids` is empty, all such patchsets in the workspace are pulled.
+ *
+ * This acquires exclusive worktree access from `ctx` and records an oplog entry.
+ * See [`gerrit_pull_patchsets_with_perm()`] for details.
+ */
+export declare function gerritPullPatchsets(projectId: string, changeIds: Array<string>, dryRun: boolean): Promise<WorkspaceState>
+
+/**
+ * List the relation chain of each stack in the workspace, mapping every commit to the Gerrit change
+ * it was pushed as and comparing it with the current patchset on the server.
+ *
+ * This is how patchsets that others uploaded are detected, which can then be pulled into the local
+ * stack with [`gerrit_pull_patchsets()`]. It's empty unless Gerrit mode is enabled for the repository.
+ */
+export declare function gerritRelationChains(projectId: string): Promise<Array<GerritRelationChain>>
+
+/**
+ * Submit the relation chain of the stack with `stack_id`, which submits all of its open changes at once.
+ *
+ * This fails unless every open change in the chain is up-to-date with the local commit and submittable,
+ * and returns the state of the top-most change after the submission.
+ */
+export declare function gerritSubmitChain(projectId: string, stackId: string): Promise<GerritChangeState>
+
 /** Read application-global AI configuration without exposing stored secrets. */
 export declare function getAiConfiguration(): Promise<AiConfiguration>
 
+/**
+ * Retrieves the authenticated user information for an Azure DevOps account.
+ *
+ * # Arguments
+ *
+ * * `account` - Identifier for the Azure DevOps account to query
+ *
+ * # Returns
+ *
+ * * `Ok(Some(AuthenticatedUser))` - User information
+ * * `Ok(None)` - No credentials stored for this account
+ * * `Err(_)` - If the API request fails or credentials are invalid
+ */
+export declare function getAzUser(account: AzureAccountIdentifier): Promise<AzureAuthenticatedUserSensitive | null>
+
 /**
  * Retrieves the authenticated user information for a Bitbucket account.
  *
@@ -626,6 +719,21 @@ export declare function getGbConfig(projectId: string): Promise<GitConfigSetting
  */
 export declare function getGhUser(account: GithubAccountIdentifier): Promise<GithubAuthenticatedUserSensitive | null>
 
+/**
+ * Retrieves the authenticated user information for a Gitea account.
+ *
+ * # Arguments
+ *
+ * * `account` - Identifier for the Gitea account to query
+ *
+ * # Returns
+ *
+ * * `Ok(Some(AuthenticatedUser))` - User information
+ * * `Ok(None)` - No credentials stored for this account
+ * * `Err(_)` - If the API request fails or credentials are invalid
+ */
+export declare function getGiteaUser(account: GiteaAccountIdentifier): Promise<GiteaAuthenticatedUserSensitive | null>
+
 /**
  * Retrieves the authenticated user information for a GitLab account.
  *
@@ -703,6 +811,14 @@ export declare function gitTestPush(projectId: string, remoteName: string, branc
 
 export declare function headInfo(projectId: string): Promise<RefInfo>
 
+/**
+ * Import the inline comments on the lines of a review as local comments on the commits of the
+ * workspace branch the review belongs to, so they can be seen and addressed without the forge.
+ *
+ * See [`but_comments::import_review_comments`] for how they are anchored.
+ */
+export declare function importReviewComments(projectId: string, reviewId: number): Promise<ImportOutcome>
+
 /**
  * Initialize the secret namespace used by build-kind scoped credentials.
  *
@@ -742,6 +858,28 @@ export declare function listCommentReactions(projectId: string, commentId: numbe
 /** List all editors that can be opened from a GUI client. */
 export declare function listEditors(): Promise<Array<Editor>>
 
+/**
+ * List the review status of all workspace commits that were pushed to Gerrit.
+ *
+ * This is the Gerrit counterpart to listing forge reviews, with one entry per pushed change
+ * carrying its state, the `Code-Review` and `Verified` votes, the unresolved comment count
+ * and whether it is submittable. It's empty unless Gerrit mode is enabled for the repository.
+ *
+ * With `cache_config` set to `NoCache` (the default), the Gerrit server is queried and
+ * the cache is updated, otherwise the last known status is returned.
+ */
+export declare function listGerritChanges(projectId: string, cacheConfig: CacheConfig | null): Promise<Array<GerritChangeStatus>>
+
+/**
+ * Lists all Azure DevOps accounts with stored credentials.
+ *
+ * # Returns
+ *
+ * * `Ok(Vec<AzureAccountIdentifier>)` - List of all known accounts
+ * * `Err(_)` - If storage access fails
+ */
+export declare function listKnownAzureAccounts(): Promise<Array<AzureAccountIdentifier>>
+
 /**
  * Lists all Bitbucket accounts with stored credentials.
  *
@@ -752,6 +890,16 @@ export declare function listEditors(): Promise<Array<Editor>>
  */
 export declare function listKnownBitbucketAccounts(): Promise<Array<BitbucketAccountIdentifier>>
 
+/**
+ * Lists all Gitea accounts with stored credentials.
+ *
+ * # Returns
+ *
+ * * `Ok(Vec<GiteaAccountIdentifier>)` - List of all known accounts
+ * * `Err(_)` - If storage access fails
+ */
+export declare function listKnownGiteaAccounts(): Promise<Array<GiteaAccountIdentifier>>
+
 /**
  * Lists all GitHub accounts with stored credentials.
  *
@@ -1026,6 +1174,25 @@ export declare function setReviewTemplate(projectId: string, templatePath: strin
  */
 export declare function setTargetRefAndInitProject(projectId: string, targetRef: string, pushRemote: string | null): Promise<void>
 
+/**
+ * Stores a personal access token for an Azure DevOps organization.
+ *
+ * Azure DevOps PATs are issued per organization and authenticate over HTTP Basic
+ * with an empty username. Validates and stores the provided token, then returns
+ * the authenticated user.
+ *
+ * # Arguments
+ *
+ * * `organization` - The organization name, as in `https://dev.azure.com/{organization}`
+ * * `access_token` - The personal access token to store (wrapped in Sensitive)
+ *
+ * # Returns
+ *
+ * * `Ok(_)` - The token is valid and stored
+ * * `Err(_)` - If the token is invalid or storage fails
+ */
+export declare function storeAzurePat(organization: string, accessToken: string): Promise<AzureAuthStatusResponse>
+
 /**
  * Stores an Atlassian API token for Bitbucket Cloud.
  *
@@ -1045,6 +1212,25 @@ export declare function setTargetRefAndInitProject(projectId: string, targetRef:
  */
 export declare function storeBitbucketApiToken(email: string, accessToken: string): Promise<BitbucketAuthStatusResponse>
 
+/**
+ * Stores an access token for a Gitea or Forgejo instance.
+ *
+ * There is no canonical public instance, so the token is always stored together with
+ * the instance it was issued by. Validates and stores the provided token, then returns
+ * the authenticated user.
+ *
+ * # Arguments
+ *
+ * * `host` - The URL of the instance, e.g. `https://codeberg.org`
+ * * `access_token` - The access token to store (wrapped in Sensitive)
+ *
+ * # Returns
+ *
+ * * `Ok(_)` - The token is valid and stored
+ * * `Err(_)` - If the token is invalid or storage fails
+ */
+export declare function storeGiteaPat(host: string, accessToken: string): Promise<GiteaAuthStatusResponse>
+
 /**
  * Stores a GitHub Personal Access Token (PAT) for github.com.
  *
@@ -1518,6 +1704,42 @@ export type Author = {
   gravatarUrl: string;
 };
 
+export type AzureAccountIdentifier = {
+  type: "pat";
+  info: {
+    organization: string;
+    username: string;
+  };
+};
+
+/**
+ * Serializable version of [`AuthStatusResponse`], without the access token.
+ *
+ * The credential is stored by the backend as part of the call, so the caller is told
+ * who authenticated and nothing more. Field names are camelCase for JSON.
+ */
+export type AzureAuthStatusResponse = {
+  username: string;
+  name: string | null;
+  organization: string;
+};
+
+/** Serializable version of [`AuthenticatedUser`] with exposed access token. */
+export type AzureAuthenticatedUserSensitive = {
+  /** The Azure DevOps personal access token as a plain string (sensitive data). */
+  accessToken: string;
+  /** The unique name of the user, usually their email address. */
+  username: string;
+  /** The organization the token was issued for. */
+  organization: string;
+  /** The URL to the user's avatar image, if available. */
+  avatarUrl: string | null;
+  /** The user's display name, if available. */
+  name: string | null;
+  /** The user's email, if available. */
+  email: string | null;
+};
+
 export type BaseBranch = {
   branchName: string;
   remoteName: string;
@@ -1834,6 +2056,29 @@ export type CalculationError = {
   path: string;
 };
 
+/** A commit of a stack along with the Gerrit change it belongs to. */
+export type ChainLink = {
+  /** The id of the commit in the local stack. */
+  commitId: string;
+  /** The GitButler change-id of the commit. */
+  changeId: string;
+  /** The `Change-Id` the commit has on the Gerrit server. */
+  gerritChangeId: string;
+  /** The first line of the commit message. */
+  title: string;
+  /** How the local commit relates to the change on the server. */
+  state: ChainLinkState;
+  /** The URL to review the change at, if it was pushed. */
+  reviewUrl: string | null;
+  /** The review status of the change, if the server knows it. */
+  status: GerritChangeStatus | null;
+  /** The current patchset of the change, if the server knows it. */
+  patchset: Patchset | null;
+};
+
+/** How a local commit relates to the change it was pushed as. */
+export type ChainLinkState = "notPushed" | "missing" | "upToDate" | "needsPush" | "uploadedByOthers" | "merged" | "abandoned";
+
 export type ChangeState = {
   id: string;
   kind: EntryKind;
@@ -1907,6 +2152,20 @@ export type Claude = {
  */
 export type Code = "Validation" | "RepoOwnership" | "ProjectGitAuth" | "DefaultTargetNotFound" | "CommitSigningFailed" | "CommitMergeConflictFailure" | "ProjectMissing" | "AuthorMissing" | "BranchNotFound" | "SecretKeychainNotFound" | "MissingLoginKeychain" | "GitForcePushProtection" | "NetworkError" | "ProjectDatabaseIncompatible" | "DefaultTerminalNotFound" | "Unknown" | "GitNonFastForward" | "CliInstallCancelled" | "GitHubTokenExpired" | "PreconditionFailed" | "EditorExitedWithNonZeroStatus";
 
+/** A reply in the thread started by a [`DiffComment`]. */
+export type CommentReply = {
+  /** The unique identifier of the reply. */
+  id: string;
+  /** The name of whoever wrote the reply, if known. */
+  author: string | null;
+  /** The reply text. */
+  payload: string;
+  /** When the reply was created, in milliseconds since the Unix epoch (UTC). */
+  createdAtMs: number;
+  /** When the reply payload was last updated, in milliseconds since the Unix epoch (UTC). */
+  updatedAtMs: number;
+};
+
 /** Commit that is part of a legacy stack branch and contains state derived in relation to it. */
 export type Commit = {
   /** The OID of the commit. */
@@ -1945,6 +2204,8 @@ export type Commit = {
    * Only populated if Gerrit mode is enabled and the commit has an associated review.
    */
   gerritReviewUrl: string | null;
+  /** The verified signature of the commit, or `None` if signatures weren't verified. */
+  signature: Signature | null;
 };
 
 /** Information about absorptions grouped by commit */
@@ -2230,10 +2491,29 @@ export type DiffComment = {
   lineContent: string;
   /** The comment text itself. */
   payload: string;
+  /** The name of whoever wrote the comment, if known. */
+  author: string | null;
   /** When the comment was created, in milliseconds since the Unix epoch (UTC). */
   createdAtMs: number;
-  /** When the comment payload was last updated, in milliseconds since the Unix epoch (UTC). */
+  /**
+   * When the comment payload or the resolution of its thread was last updated, in milliseconds
+   * since the Unix epoch (UTC).
+   */
   updatedAtMs: number;
+  /**
+   * When the thread was resolved, in milliseconds since the Unix epoch (UTC), or `None` if it
+   * is unresolved.
+   */
+  resolvedAtMs: number | null;
+  /** The name of whoever resolved the thread, if known. */
+  resolvedBy: string | null;
+  /**
+   * The short name of the branch containing the anchored commit, or `None` for comments on
+   * the uncommitted changes and on commits of anonymous segments.
+   */
+  branch: string | null;
+  /** The replies to this comment, oldest first. */
+  replies: Array<CommentReply>;
   /**
    * A unified-diff-formatted excerpt of the current diff around the anchored line, so consumers
    * can understand what the comment is about without recomputing the diff.
@@ -2376,6 +2656,9 @@ export type ForgeCapabilities = {
   reviewManagement: boolean;
 };
 
+/** The side of a review's diff that an inline comment is on. */
+export type ForgeDiffSide = "old" | "new";
+
 /**
  * Per-forge display + URL config delivered to the frontend so it
  * doesn't need to branch on forge name. Computed from the project's
@@ -2401,8 +2684,41 @@ export type ForgeInfo = {
   capabilities: ForgeCapabilities;
 };
 
+/**
+ * A comment on a line of the diff of a review, as opposed to a [`ForgeReviewComment`] in its
+ * conversation.
+ */
+export type ForgeInlineReviewComment = {
+  /** Forge-assigned identifier of the comment. */
+  id: number;
+  /** The comment this comment replies to, if any. */
+  inReplyTo: number | null;
+  /** The repository-relative path of the commented file. */
+  path: string;
+  /** The 1-based number of the commented line on `side`, or `None` if the comment is outdated. */
+  line: number | null;
+  /** The side of the diff the commented line is on. */
+  side: ForgeDiffSide;
+  /** The full id of the commit the comment was made on, if the forge reports it. */
+  commitId: string | null;
+  /** The content of the commented line, if the forge reports it. */
+  lineContent: string | null;
+  /** The content of the line before the commented one on the same side, if the forge reports it. */
+  lineBefore: string | null;
+  /** The comment text, as forge-flavored markdown. */
+  body: string;
+  /** The comment's author. */
+  author: ForgeReviewUser | null;
+  /** ISO 8601 timestamp of when the comment was created. */
+  createdAt: string | null;
+  /** ISO 8601 timestamp of the comment's last edit. */
+  modifiedAt: string | null;
+  /** The URL to view this comment in a web browser. */
+  htmlUrl: string;
+};
+
 /** Supported git forge types */
-export type ForgeName = "github" | "gitlab" | "bitbucket" | "azure";
+export type ForgeName = "github" | "gitlab" | "bitbucket" | "azure" | "gitea";
 
 /**
  * Represents a review (pull request/merge request) from a forge platform (GitHub, GitLab, etc.).
@@ -2618,6 +2934,12 @@ export type ForgeUser = {
 } | {
   provider: "bitbucket";
   details: BitbucketAccountIdentifier;
+} | {
+  provider: "azure";
+  details: AzureAccountIdentifier;
+} | {
+  provider: "gitea";
+  details: GiteaAccountIdentifier;
 };
 
 /**
@@ -2637,10 +2959,48 @@ export type FullRefName = {
   full: string;
 };
 
+/** The state of a change on the Gerrit server. */
+export type GerritChangeState = "new" | "merged" | "abandoned";
+
+/** The review status of a single pushed change, similar to what a forge review provides for pull requests. */
+export type GerritChangeStatus = {
+  /** The GitButler change-id of the commit that was pushed as this change. */
+  changeId: string;
+  /** The numeric ID of the change on the Gerrit server. */
+  number: number;
+  /** Whether the change is open, merged or abandoned. */
+  state: GerritChangeState;
+  /** The summarized `Code-Review` vote, where negative votes take precedence. */
+  codeReview: number | null;
+  /** The summarized `Verified` vote, where negative votes take precedence. */
+  verified: number | null;
+  /** The amount of unresolved comment threads. */
+  unresolvedCommentCount: number;
+  /** Whether the change could be submitted right now. */
+  submittable: boolean;
+  /** Whether the change is marked as work in progress. */
+  workInProgress: boolean;
+  /** The commit id of the current patchset on the server. */
+  currentRevision: string | null;
+  /** The time at which this information was obtained from the server. */
+  lastSyncAt: string;
+};
+
+/** The commits of a stack in the workspace along with the Gerrit changes they were pushed as. */
+export type GerritRelationChain = {
+  /** The id of the stack, if it has one. */
+  stackId: string | null;
+  /** The short name of the top-most branch of the stack, if it has one. */
+  name: string | null;
+  /** The commits of the stack from bottom to top, which is the order of the relation chain on Gerrit. */
+  links: Array<ChainLink>;
+};
+
 /** See [`GitConfigSettings`](crate::GitConfigSettings) for the docs. */
 export type GitConfigSettings = {
   signCommits: boolean | null;
   gitbutlerGerritMode: boolean | null;
+  gitbutlerVerifySignatures: boolean | null;
   gitbutlerReviewStackingDescription: ReviewStackingDescription | null;
   gitbutlerGithubStackingMode: GitHubStackingMode | null;
   gitbutlerForgeReviewTemplatePath: string | null;
@@ -2660,6 +3020,43 @@ export type GitHubOAuthAppSettings = {
 /** Controls whether GitButler registers reviewed stacks with GitHub's native stacks API. */
 export type GitHubStackingMode = "auto" | "disabled" | "native";
 
+export type GiteaAccountIdentifier = {
+  type: "pat";
+  info: {
+    username: string;
+    host: string;
+  };
+};
+
+/**
+ * Serializable version of [`AuthStatusResponse`], without the access token.
+ *
+ * The credential is stored by the backend as part of the call, so the caller is told
+ * who authenticated and nothing more. Field names are camelCase for JSON.
+ */
+export type GiteaAuthStatusResponse = {
+  username: string;
+  name: string | null;
+  email: string | null;
+  host: string;
+};
+
+/** Serializable version of [`AuthenticatedUser`] with exposed access token. */
+export type GiteaAuthenticatedUserSensitive = {
+  /** The Gitea access token as a plain string (sensitive data). */
+  accessToken: string;
+  /** The login of the user. */
+  username: string;
+  /** The instance the account belongs to. */
+  host: string;
+  /** The URL to the user's avatar image, if available. */
+  avatarUrl: string | null;
+  /** The user's display name, if available. */
+  name: string | null;
+  /** The user's email, if available. */
+  email: string | null;
+};
+
 export type GithubAccountIdentifier = {
   type: "oAuthUsername";
   info: {
@@ -2936,6 +3333,19 @@ export type IgnoredWorktreeChange = {
 /** The status we can't handle, which always originated in the worktree. */
 export type IgnoredWorktreeTreeChangeStatus = "Conflict" | "TreeIndex" | "TreeIndexWorktreeChangeIneffective";
 
+/** The result of [`import_review_comments`]. */
+export type ImportOutcome = {
+  /** The amount of comments and replies that were added. */
+  imported: number;
+  /** The amount of previously imported comments and replies whose text changed on the forge. */
+  updated: number;
+  /**
+   * The amount of comments and replies that couldn't be anchored to a commit of the branch,
+   * for instance because their line isn't part of any commit's diff anymore.
+   */
+  unanchored: number;
+};
+
 /** JSON transport type for the initial branch integration proposal. */
 export type InitialBranchIntegration = {
   /** The editable execution plan for integrating the branch upstream. */
@@ -3223,11 +3633,33 @@ export type NewComment = {
   lineNumber: number;
   /** The comment text. */
   payload: string;
+  /** The name of whoever writes the comment, if known. */
+  author: string | null;
+};
+
+/** Everything needed to reply to a comment. See [`CommentReply`] for the field semantics. */
+export type NewReply = {
+  /** An optional client-supplied ID. An ID will be generated if this is absent. */
+  id: string | null;
+  /** The id of the comment to reply to. Replies to replies are added to the same thread. */
+  commentId: string;
+  /** The reply text. */
+  payload: string;
+  /** The name of whoever writes the reply, if known. */
+  author: string | null;
 };
 
 /** A column in a detailed graph node row. */
 export type NodeLine = "blank" | "ancestor" | "parent" | "node";
 
+/** The result of [`sync_with_notes`]. */
+export type NotesSync = {
+  /** The amount of comments that were added or updated from the notes. */
+  imported: number;
+  /** The amount of comments written to the notes, or `0` if the notes didn't change. */
+  exported: number;
+};
+
 export type OperatingMode = {
   type: "OpenWorkspace";
 } | {
@@ -3238,7 +3670,7 @@ export type OperatingMode = {
   subject: EditModeMetadata;
 };
 
-export type OperationKind = "CreateCommit" | "CreateBranch" | "StashIntoBranch" | "SetBaseBranch" | "MergeUpstream" | "UpdateWorkspaceBase" | "MoveHunk" | "UpdateBranchName" | "UpdateBranchNotes" | "ReorderBranches" | "UpdateBranchRemoteName" | "GenericBranchUpdate" | "DeleteBranch" | "ApplyBranch" | "DiscardLines" | "DiscardHunk" | "DiscardFile" | "DiscardChanges" | "Discard" | "AmendCommit" | "Absorb" | "AutoCommit" | "UndoCommit" | "DiscardCommit" | "UnapplyBranch" | "CherryPick" | "SquashCommit" | "UpdateCommitMessage" | "MoveCommit" | "MoveBranch" | "TearOffBranch" | "ReorderCommit" | "InsertBlankCommit" | "MoveCommitFile" | "FileChanges" | "EnterEditMode" | "ResolveConflicts" | "ResolveConflictsAi" | "SyncWorkspace" | "CreateDependentBranch" | "RemoveDependentBranch" | "UpdateDependentBranchName" | "UpdateDependentBranchDescription" | "UpdateDependentBranchPrNumber" | "AutoHandleChangesBefore" | "AutoHandleChangesAfter" | "SplitBranch" | "CleanWorkspace" | "OnDemandSnapshot" | "Unknown" | "RestoreFromSnapshotViaUndo" | "RestoreFromSnapshotViaRedo" | "RestoreFromSnapshot";
+export type OperationKind = "CreateCommit" | "CreateBranch" | "StashIntoBranch" | "SetBaseBranch" | "MergeUpstream" | "UpdateWorkspaceBase" | "MoveHunk" | "UpdateBranchName" | "UpdateBranchNotes" | "ReorderBranches" | "UpdateBranchRemoteName" | "GenericBranchUpdate" | "DeleteBranch" | "ApplyBranch" | "DiscardLines" | "DiscardHunk" | "DiscardFile" | "DiscardChanges" | "Discard" | "AmendCommit" | "Absorb" | "AutoCommit" | "UndoCommit" | "DiscardCommit" | "UnapplyBranch" | "CherryPick" | "SquashCommit" | "UpdateCommitMessage" | "MoveCommit" | "MoveBranch" | "TearOffBranch" | "ReorderCommit" | "InsertBlankCommit" | "MoveCommitFile" | "FileChanges" | "EnterEditMode" | "ResolveConflicts" | "ResolveConflictsAi" | "SyncWorkspace" | "CreateDependentBranch" | "RemoveDependentBranch" | "UpdateDependentBranchName" | "UpdateDependentBranchDescription" | "UpdateDependentBranchPrNumber" | "AutoHandleChangesBefore" | "AutoHandleChangesAfter" | "SplitBranch" | "SplitCommit" | "CleanWorkspace" | "OnDemandSnapshot" | "Unknown" | "RestoreFromSnapshotViaUndo" | "RestoreFromSnapshotViaRedo" | "RestoreFromSnapshot" | "RestorePathsFromSnapshot";
 
 /** What kind of apply operation completed. */
 export type OutcomeStatus = "alreadyApplied" | "applied" | "conflictAborted";
@@ -3253,6 +3685,18 @@ export type OutsideWorkspaceMetadata = {
 /** A column in a detailed graph padding row. */
 export type PadLine = "blank" | "ancestor" | "parent";
 
+/** The current patchset of a change on the server. */
+export type Patchset = {
+  /** The patchset number, starting at 1. */
+  number: number;
+  /** The commit of the patchset. */
+  commitId: string;
+  /** The ref to fetch the patchset from, like `refs/changes/11/4711/3`. */
+  refName: string;
+  /** The name of whoever uploaded the patchset, if known. */
+  uploader: string | null;
+};
+
 /**
  * API-specific project type that can be enriched with computed/derived data
  * while preserving the original project structure for persistence.
@@ -3684,6 +4128,29 @@ export type SerdeError = {
   source: any | null;
 };
 
+/** The signature of a commit along with the outcome of its verification. */
+export type Signature = {
+  /** The outcome of the verification. */
+  status: SignatureStatus;
+  /** The kind of signature, or `None` if the commit is unsigned or the format isn't known. */
+  format: SignatureFormat | null;
+  /**
+   * Who made the signature, as far as the verifying program could tell.
+   *
+   * This is the user ID for OpenPGP and X.509 signatures, and the principal from
+   * the allowed signers file for SSH signatures.
+   */
+  signer: string | null;
+  /** The fingerprint or ID of the key that made the signature. */
+  key: string | null;
+};
+
+/** The kind of a commit signature. */
+export type SignatureFormat = "openPgp" | "ssh" | "x509";
+
+/** The outcome of verifying a commit signature, like `%G?` in `git log --format`. */
+export type SignatureStatus = "unsigned" | "good" | "untrusted" | "bad" | "expired" | "expiredKey" | "revokedKey" | "unverifiable";
+
 /**
  * A single hunk of an uncommitted change, identified by its `path` and `hunk_header`.
  *
@@ -3880,6 +4347,14 @@ export type TerminalOption = {
   platform: string;
 };
 
+/** Which threads to keep after [listing](list_comments) them. */
+export type ThreadFilter = {
+  /** Only keep threads that aren't resolved. */
+  unresolved?: boolean;
+  /** Only keep threads on commits of the branch with this short or full name. */
+  branch: string | null;
+};
+
 export type Trailer = {
   key: string;
   value: string;
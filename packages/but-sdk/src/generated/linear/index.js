@@ -579,7 +579,7 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { absorb, absorptionPlan, addCommentReaction, addProject, addReviewLabels, addReviewReaction, apply, applyBranchIntegration, assignHunk, branchCannedName, branchCheckout, branchCheckoutNew, branchCreate, branchDetails, branchDiff, branchLand, branchList, branchRemove, branchRename, changesInWorktree, changesInWorktreeWithPerm, checkGithubAuthStatus, checkSigningSettings, commentArchive, commentCreate, commentsList, commentUpdate, commitAmend, commitCherryPick, commitConflicts, commitCreate, commitDetailsWithLineStats, commitDiscard, commitDiscardChanges, commitInsertBlank, commitMove, commitMoveChangesBetween, commitReword, commitSquash, commitUncommit, commitUncommitChanges, commitUncommitChangesFromCommits, createReviewComment, currentForgeLogin, deleteAllData, deleteProject, deleteReviewComment, deleteUser, discardWorktreeChanges, forgeCompareBranchUrl, forgeInfo, forgeProvider, forgetBitbucketAccount, forgetGithubAccount, forgetGitlabAccount, getAiConfiguration, getBbUser, getBlobFile, getGbConfig, getGhUser, getGlUser, getInitialBranchIntegration, getLoginToken, getRedoTargetSnapshot, getRepoInfo, getReview, getReviewBaseRepoUrl, getReviewMergeStatus, getTerminalOptionsForPlatform, getUndoTargetSnapshot, getUserProfileLocal, getWorkspace, getWorkspaceFile, gitTestFetch, gitTestPush, headInfo, initApplicationNamespace, initGithubDeviceOauth, listAvailableReviewTemplates, listBranches, listCiChecks, listCommentReactions, listEditors, listKnownBitbucketAccounts, listKnownGithubAccounts, listKnownGitlabAccounts, listPrograms, listProjectsStateless, listRepoLabels, listReviewComments, listReviewerCandidates, listReviewReactions, listReviews, listReviewsForBranch, listReviewSubmissions, listReviewTimelineEvents, loginAndPersist, mergeReview, moveBranch, openInProgram, openInTerminal, peelRestoreSnapshot, ProgramCategory, publishReview, removeBranch, removeCommentReaction, removeReviewLabel, removeReviewReaction, requestReview, resetAiConfiguration, resolveCommitConflictHunks, resolveWorktreeConflicts, restoreSnapshotWithKind, reviewApply, reviewTemplate, setGbConfig, setPushRemote, setReviewAutoMerge, setReviewDraftiness, setReviewTemplate, setTargetRefAndInitProject, storeBitbucketApiToken, storeGithubPat, storeGitlabPat, tearOffBranch, treeChangeDiffs, unapplyStack, updateAiConfiguration, updateProfileAndPersist, updateProjectSettings, updateReview, updateReviewComment, updateReviewFooters, warmCiChecksCache, withdrawReviewRequest, workspaceBranchAndAncestorsPush, workspaceCheckout, workspaceFetchFromRemotes, workspaceFetchStatus, workspaceIntegrateUpstream, workspaceTargetCommits, WatcherHandle, ANY_FORK, ANY_FORK_OR_MERGE, ANY_MERGE, askpassInit, askpassSubmitPromptResponse, CHILD, getAppSettings, HORIZ_ANCESTOR, HORIZ_PARENT, HORIZONTAL, initTracing, interactiveLoginShellEnvironment, LEFT_FORK, LEFT_FORK_ANCESTOR, LEFT_FORK_PARENT, LEFT_MERGE, LEFT_MERGE_ANCESTOR, LEFT_MERGE_PARENT, RIGHT_FORK, RIGHT_FORK_ANCESTOR, RIGHT_FORK_PARENT, RIGHT_MERGE, RIGHT_MERGE_ANCESTOR, RIGHT_MERGE_PARENT, shutdownTracing, streamAiResponse, updateFeatureFlags, updateFetch, updateOnboardingComplete, updateReviews, updateTelemetry, updateTelemetryDistinctId, updateUi, VERT_ANCESTOR, VERT_PARENT, VERTICAL, watcherStart } = nativeBinding
+const { absorb, absorptionPlan, addCommentReaction, addProject, addReviewLabels, addReviewReaction, apply, applyBranchIntegration, assignHunk, branchCannedName, branchCheckout, branchCheckoutNew, branchCreate, branchDetails, branchDiff, branchLand, branchList, branchRemove, branchRename, changesInWorktree, changesInWorktreeWithPerm, checkGithubAuthStatus, checkSigningSettings, commentArchive, commentCreate, commentReopen, commentReply, commentResolve, commentsList, commentsSyncNotes, commentUpdate, commitAmend, commitCherryPick, commitConflicts, commitCreate, commitDetailsWithLineStats, commitDiscard, commitDiscardChanges, commitInsertBlank, commitMove, commitMoveChangesBetween, commitReword, commitSquash, commitUncommit, commitUncommitChanges, commitUncommitChangesFromCommits, createReviewComment, currentForgeLogin, deleteAllData, deleteProject, deleteReviewComment, deleteUser, discardWorktreeChanges, forgeCompareBranchUrl, forgeInfo, forgeProvider, forgetAzureAccount, forgetBitbucketAccount, forgetGiteaAccount, forgetGithubAccount, forgetGitlabAccount, gerritPullPatchsets, gerritRelationChains, gerritSubmitChain, getAiConfiguration, getAzUser, getBbUser, getBlobFile, getGbConfig, getGhUser, getGiteaUser, getGlUser, getInitialBranchIntegration, getLoginToken, getRedoTargetSnapshot, getRepoInfo, getReview, getReviewBaseRepoUrl, getReviewMergeStatus, getTerminalOptionsForPlatform, getUndoTargetSnapshot, getUserProfileLocal, getWorkspace, getWorkspaceFile, gitTestFetch, gitTestPush, headInfo, importReviewComments, initApplicationNamespace, initGithubDeviceOauth, listAvailableReviewTemplates, listBranches, listCiChecks, listCommentReactions, listEditors, listGerritChanges, listKnownAzureAccounts, listKnownBitbucketAccounts, listKnownGiteaAccounts, listKnownGithubAccounts, listKnownGitlabAccounts, listPrograms, listProjectsStateless, listRepoLabels, listReviewComments, listReviewerCandidates, listReviewReactions, listReviews, listReviewsForBranch, listReviewSubmissions, listReviewTimelineEvents, loginAndPersist, mergeReview, moveBranch, openInProgram, openInTerminal, peelRestoreSnapshot, ProgramCategory, publishReview, removeBranch, removeCommentReaction, removeReviewLabel, removeReviewReaction, requestReview, resetAiConfiguration, resolveCommitConflictHunks, resolveWorktreeConflicts, restoreSnapshotWithKind, reviewApply, reviewTemplate, setGbConfig, setPushRemote, setReviewAutoMerge, setReviewDraftiness, setReviewTemplate, setTargetRefAndInitProject, storeAzurePat, storeBitbucketApiToken, storeGiteaPat, storeGithubPat, storeGitlabPat, tearOffBranch, treeChangeDiffs, unapplyStack, updateAiConfiguration, updateProfileAndPersist, updateProjectSettings, updateReview, updateReviewComment, updateReviewFooters, warmCiChecksCache, withdrawReviewRequest, workspaceBranchAndAncestorsPush, workspaceCheckout, workspaceFetchFromRemotes, workspaceFetchStatus, workspaceIntegrateUpstream, workspaceTargetCommits, WatcherHandle, ANY_FORK, ANY_FORK_OR_MERGE, ANY_MERGE, askpassInit, askpassSubmitPromptResponse, CHILD, getAppSettings, HORIZ_ANCESTOR, HORIZ_PARENT, HORIZONTAL, initTracing, interactiveLoginShellEnvironment, LEFT_FORK, LEFT_FORK_ANCESTOR, LEFT_FORK_PARENT, LEFT_MERGE, LEFT_MERGE_ANCESTOR, LEFT_MERGE_PARENT, RIGHT_FORK, RIGHT_FORK_ANCESTOR, RIGHT_FORK_PARENT, RIGHT_MERGE, RIGHT_MERGE_ANCESTOR, RIGHT_MERGE_PARENT, shutdownTracing, streamAiResponse, updateFeatureFlags, updateFetch, updateOnboardingComplete, updateReviews, updateTelemetry, updateTelemetryDistinctId, updateUi, VERT_ANCESTOR, VERT_PARENT, VERTICAL, watcherStart } = nativeBinding
 export { absorb }
 export { absorptionPlan }
 export { addCommentReaction }
@@ -605,7 +605,11 @@ export { checkGithubAuthStatus }
 export { checkSigningSettings }
 export { commentArchive }
 export { commentCreate }
+export { commentReopen }
+export { commentReply }
+export { commentResolve }
 export { commentsList }
+export { commentsSyncNotes }
 export { commentUpdate }
 export { commitAmend }
 export { commitCherryPick }
@@ -632,14 +636,21 @@ export { discardWorktreeChanges }
 export { forgeCompareBranchUrl }
 export { forgeInfo }
 export { forgeProvider }
+export { forgetAzureAccount }
 export { forgetBitbucketAccount }
+export { forgetGiteaAccount }
 export { forgetGithubAccount }
 export { forgetGitlabAccount }
+export { gerritPullPatchsets }
+export { gerritRelationChains }
+export { gerritSubmitChain }
 export { getAiConfiguration }
+export { getAzUser }
 export { getBbUser }
 export { getBlobFile }
 export { getGbConfig }
 export { getGhUser }
+export { getGiteaUser }
 export { getGlUser }
 export { getInitialBranchIntegration }
 export { getLoginToken }
@@ -656,6 +667,7 @@ export { getWorkspaceFile }
 export { gitTestFetch }
 export { gitTestPush }
 export { headInfo }
+export { importReviewComments }
 export { initApplicationNamespace }
 export { initGithubDeviceOauth }
 export { listAvailableReviewTemplates }
@@ -663,7 +675,10 @@ export { listBranches }
 export { listCiChecks }
 export { listCommentReactions }
 export { listEditors }
+export { listGerritChanges }
+export { listKnownAzureAccounts }
 export { listKnownBitbucketAccounts }
+export { listKnownGiteaAccounts }
 export { listKnownGithubAccounts }
 export { listKnownGitlabAccounts }
 export { listPrograms }
@@ -701,7 +716,9 @@ export { setReviewAutoMerge }
 export { setReviewDraftiness }
 export { setReviewTemplate }
 export { setTargetRefAndInitProject }
+export { storeAzurePat }
 export { storeBitbucketApiToken }
+export { storeGiteaPat }
 export { storeGithubPat }
 export { storeGitlabPat }
 export { tearOffBranch }